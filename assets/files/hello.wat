@@ -0,0 +1,50 @@
+;; Source of hello.wasm, a test plugin following Typst's plugin protocol.
+(module
+  (import "typst_env" "wasm_minimal_protocol_write_args_to_buffer"
+    (func $write_args (param i32)))
+  (import "typst_env" "wasm_minimal_protocol_send_result_to_host"
+    (func $send_result (param i32 i32)))
+
+  (memory (export "memory") 1)
+  (data (i32.const 0) "Hello from wasm!!!")
+  (data (i32.const 32) "This is an error")
+
+  ;; Returns a greeting.
+  (func (export "hello") (result i32)
+    (call $send_result (i32.const 0) (i32.const 18))
+    (i32.const 0))
+
+  ;; Returns its argument.
+  (func (export "echo") (param i32) (result i32)
+    (call $write_args (i32.const 1024))
+    (call $send_result (i32.const 1024) (local.get 0))
+    (i32.const 0))
+
+  ;; Returns its two arguments joined together.
+  (func (export "concat") (param i32 i32) (result i32)
+    (call $write_args (i32.const 1024))
+    (call $send_result (i32.const 1024) (i32.add (local.get 0) (local.get 1)))
+    (i32.const 0))
+
+  ;; Fails with an error message.
+  (func (export "returns_err") (result i32)
+    (call $send_result (i32.const 32) (i32.const 16))
+    (i32.const 1))
+
+  ;; Traps.
+  (func (export "will_panic") (result i32)
+    unreachable)
+
+  ;; Never terminates.
+  (func (export "forever") (result i32)
+    (loop (br 0))
+    unreachable)
+
+  ;; Returns an invalid status code.
+  (func (export "bad_code") (result i32)
+    (i32.const 2))
+
+  ;; Sends a result that lies outside of its memory.
+  (func (export "oob_read") (result i32)
+    (call $send_result (i32.const 0) (i32.const 0x20000))
+    (i32.const 0)))
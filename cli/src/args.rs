@@ -31,6 +31,7 @@ pub struct CliArguments {
 pub enum DiagnosticFormat {
     Human,
     Short,
+    Json,
 }
 
 impl Display for DiagnosticFormat {
@@ -42,6 +43,26 @@ impl Display for DiagnosticFormat {
     }
 }
 
+/// Which PDF standard the output should conform to.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, ValueEnum)]
+pub enum PdfStandard {
+    /// PDF 1.7
+    #[value(name = "1.7")]
+    Pdf17,
+    /// PDF/A-2b for archival
+    #[value(name = "a-2b")]
+    A2b,
+}
+
+impl Display for PdfStandard {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.to_possible_value()
+            .expect("no values are skipped")
+            .get_name()
+            .fmt(f)
+    }
+}
+
 /// What to do.
 #[derive(Debug, Clone, Subcommand)]
 #[command()]
@@ -80,7 +101,7 @@ pub struct CompileCommand {
     /// Path to input Typst file
     pub input: PathBuf,
 
-    /// Path to output PDF file or PNG file(s)
+    /// Path to output PDF, SVG, or PNG file(s)
     pub output: Option<PathBuf>,
 
     /// Opens the output file after compilation using the default PDF viewer
@@ -91,6 +112,32 @@ pub struct CompileCommand {
     #[arg(long = "ppi")]
     pub ppi: Option<f32>,
 
+    /// Which PDF standard to conform to if exported as PDF
+    #[clap(
+        long,
+        default_value_t = PdfStandard::Pdf17,
+        value_parser = clap::value_parser!(PdfStandard)
+    )]
+    pub pdf_standard: PdfStandard,
+
+    /// The UNIX timestamp to use as the current date and time, for
+    /// reproducible builds
+    #[clap(
+        long = "creation-timestamp",
+        env = "SOURCE_DATE_EPOCH",
+        value_name = "UNIX_TIMESTAMP"
+    )]
+    pub creation_timestamp: Option<i64>,
+
+    /// Add a string key-value pair visible to documents through `sys.inputs`
+    #[clap(
+        long = "input",
+        value_name = "key=value",
+        action = ArgAction::Append,
+        value_parser = parse_input_pair,
+    )]
+    pub inputs: Vec<(String, String)>,
+
     /// In which format to emit diagnostics
     #[clap(
         long,
@@ -104,6 +151,18 @@ pub struct CompileCommand {
     pub flamegraph: Option<Option<PathBuf>>,
 }
 
+/// Parses a key-value pair split at the first equals sign.
+fn parse_input_pair(raw: &str) -> Result<(String, String), String> {
+    let (key, value) = raw
+        .split_once('=')
+        .ok_or("input must be a key and a value separated by an equals sign")?;
+    let key = key.trim();
+    if key.is_empty() {
+        return Err("input key must not be empty".into());
+    }
+    Ok((key.into(), value.into()))
+}
+
 /// List all discovered fonts in system and custom font paths
 #[derive(Debug, Clone, Parser)]
 pub struct FontsCommand {
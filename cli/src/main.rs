@@ -10,7 +10,7 @@ use std::io::{self, IsTerminal, Write};
 use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 
-use chrono::Datelike;
+use chrono::{Datelike, TimeZone, Timelike};
 use clap::Parser;
 use codespan_reporting::diagnostic::{Diagnostic, Label};
 use codespan_reporting::term::{self, termcolor};
@@ -22,19 +22,22 @@ use siphasher::sip128::{Hasher128, SipHasher13};
 use std::cell::OnceCell;
 use termcolor::{ColorChoice, StandardStream, WriteColor};
 use typst::diag::{
-    bail, FileError, FileResult, PackageError, PackageResult, SourceError, StrResult,
+    bail, EcoString, FileError, FileResult, PackageError, PackageResult, Severity,
+    SourceError, SourceResult, StrResult,
 };
 use typst::doc::Document;
-use typst::eval::{eco_format, Datetime, Library};
-use typst::file::{FileId, PackageSpec};
+use typst::eval::{eco_format, Datetime, Library, Tracer};
+use typst::export::PdfOptions;
+use typst::file::FileId;
 use typst::font::{Font, FontBook, FontInfo, FontVariant};
-use typst::geom::Color;
+use typst::geom::{Abs, Color};
+use typst::package::{PackageSpec, Version};
 use typst::syntax::Source;
 use typst::util::{Bytes, PathExt};
 use typst::World;
 use walkdir::WalkDir;
 
-use crate::args::{CliArguments, Command, CompileCommand, DiagnosticFormat};
+use crate::args::{CliArguments, Command, CompileCommand, DiagnosticFormat, PdfStandard};
 
 type CodespanResult<T> = Result<T, CodespanError>;
 type CodespanError = codespan_reporting::files::Error;
@@ -105,6 +108,12 @@ struct CompileSettings {
     open: Option<Option<String>>,
     /// The PPI to use for PNG export.
     ppi: Option<f32>,
+    /// The PDF standard to conform to for PDF export.
+    pdf_standard: typst::export::PdfStandard,
+    /// The UNIX timestamp to use as the current date and time.
+    creation_timestamp: Option<i64>,
+    /// The key-value pairs visible through `sys.inputs`.
+    inputs: Vec<(String, String)>,
     /// In which format to emit diagnostics.
     diagnostic_format: DiagnosticFormat,
 }
@@ -119,6 +128,9 @@ impl CompileSettings {
         font_paths: Vec<PathBuf>,
         open: Option<Option<String>>,
         ppi: Option<f32>,
+        pdf_standard: typst::export::PdfStandard,
+        creation_timestamp: Option<i64>,
+        inputs: Vec<(String, String)>,
         diagnostic_format: DiagnosticFormat,
     ) -> Self {
         let output = match output {
@@ -133,6 +145,9 @@ impl CompileSettings {
             open,
             diagnostic_format,
             ppi,
+            pdf_standard,
+            creation_timestamp,
+            inputs,
         }
     }
 
@@ -142,14 +157,39 @@ impl CompileSettings {
     /// Panics if the command is not a compile or watch command.
     fn with_arguments(args: CliArguments) -> Self {
         let watch = matches!(args.command, Command::Watch(_));
-        let CompileCommand { input, output, open, ppi, diagnostic_format, .. } =
-            match args.command {
-                Command::Compile(command) => command,
-                Command::Watch(command) => command,
-                _ => unreachable!(),
-            };
+        let CompileCommand {
+            input,
+            output,
+            open,
+            ppi,
+            pdf_standard,
+            creation_timestamp,
+            inputs,
+            diagnostic_format,
+            ..
+        } = match args.command {
+            Command::Compile(command) => command,
+            Command::Watch(command) => command,
+            _ => unreachable!(),
+        };
 
-        Self::new(input, output, watch, args.font_paths, open, ppi, diagnostic_format)
+        let pdf_standard = match pdf_standard {
+            PdfStandard::Pdf17 => typst::export::PdfStandard::Pdf17,
+            PdfStandard::A2b => typst::export::PdfStandard::A2b,
+        };
+
+        Self::new(
+            input,
+            output,
+            watch,
+            args.font_paths,
+            open,
+            ppi,
+            pdf_standard,
+            creation_timestamp,
+            inputs,
+            diagnostic_format,
+        )
     }
 }
 
@@ -181,7 +221,12 @@ impl FontsSettings {
 /// Execute a compilation command.
 fn compile(mut command: CompileSettings) -> StrResult<()> {
     // Create the world that serves sources, files, and fonts.
-    let mut world = SystemWorld::new(&command.input, &command.font_paths);
+    let mut world = SystemWorld::new(
+        &command.input,
+        &command.font_paths,
+        command.creation_timestamp,
+        &command.inputs,
+    );
 
     // Perform initial compilation.
     let ok = compile_once(&mut world, &command)?;
@@ -233,7 +278,7 @@ fn compile(mut command: CompileSettings) -> StrResult<()> {
 
             // Recompile.
             let ok = compile_once(&mut world, &command)?;
-            comemo::evict(10);
+            typst::evict_cache(10);
 
             // Adjust the watching.
             world.watch(&mut watcher, dependencies)?;
@@ -263,14 +308,22 @@ fn compile_once(world: &mut SystemWorld, command: &CompileSettings) -> StrResult
     world.reset();
     world.source(world.main).map_err(|err| err.to_string())?;
 
-    let result = typst::compile(world);
+    let mut tracer = Tracer::default();
+    let result = typst::compile(world, &mut tracer);
     let duration = start.elapsed();
+    let warnings = tracer.warnings().to_vec();
+
+    // Export the PDF / PNG.
+    let result = match result {
+        Ok(document) => export(&document, command)?,
+        Err(errors) => Err(errors),
+    };
 
     match result {
-        // Export the PDF / PNG.
-        Ok(document) => {
-            export(&document, command)?;
+        Ok(()) => {
             status(command, Status::Success(duration)).unwrap();
+            print_diagnostics(world, warnings, command.diagnostic_format)
+                .map_err(|_| "failed to print diagnostics")?;
             tracing::info!("Compilation succeeded in {duration:?}");
             Ok(true)
         }
@@ -279,7 +332,8 @@ fn compile_once(world: &mut SystemWorld, command: &CompileSettings) -> StrResult
         Err(errors) => {
             set_failed();
             status(command, Status::Error).unwrap();
-            print_diagnostics(world, *errors, command.diagnostic_format)
+            let diagnostics = warnings.into_iter().chain(*errors).collect();
+            print_diagnostics(world, diagnostics, command.diagnostic_format)
                 .map_err(|_| "failed to print diagnostics")?;
             tracing::info!("Compilation failed after {duration:?}");
             Ok(false)
@@ -288,7 +342,10 @@ fn compile_once(world: &mut SystemWorld, command: &CompileSettings) -> StrResult
 }
 
 /// Export into the target format.
-fn export(document: &Document, command: &CompileSettings) -> StrResult<()> {
+///
+/// Returns the errors of the document if it cannot be represented in the
+/// target format.
+fn export(document: &Document, command: &CompileSettings) -> StrResult<SourceResult<()>> {
     match command.output.extension() {
         Some(ext) if ext.eq_ignore_ascii_case("png") => {
             // Determine whether we have a `{n}` numbering.
@@ -316,12 +373,24 @@ fn export(document: &Document, command: &CompileSettings) -> StrResult<()> {
                 pixmap.save_png(path).map_err(|_| "failed to write PNG file")?;
             }
         }
+        Some(ext) if ext.eq_ignore_ascii_case("svg") => {
+            let svg = typst::export::svg_merged(document, Abs::pt(5.0));
+            fs::write(&command.output, svg).map_err(|_| "failed to write SVG file")?;
+        }
+        Some(ext) if ext.eq_ignore_ascii_case("txt") => {
+            let text = typst::export::text(document);
+            fs::write(&command.output, text).map_err(|_| "failed to write text file")?;
+        }
         _ => {
-            let buffer = typst::export::pdf(document);
+            let options = PdfOptions { standard: command.pdf_standard };
+            let buffer = match typst::export::pdf(document, &options) {
+                Ok(buffer) => buffer,
+                Err(errors) => return Ok(Err(errors)),
+            };
             fs::write(&command.output, buffer).map_err(|_| "failed to write PDF file")?;
         }
     }
-    Ok(())
+    Ok(Ok(()))
 }
 
 /// Clear the terminal and render the status message.
@@ -399,12 +468,25 @@ impl Status {
 /// Print diagnostic messages to the terminal.
 fn print_diagnostics(
     world: &SystemWorld,
-    errors: Vec<SourceError>,
+    diagnostics: Vec<SourceError>,
     diagnostic_format: DiagnosticFormat,
 ) -> Result<(), codespan_reporting::files::Error> {
+    // Machine-readable diagnostics, one JSON object per line.
+    if diagnostic_format == DiagnosticFormat::Json {
+        let mut w = io::stderr().lock();
+        for error in diagnostics {
+            serde_json::to_writer(&mut w, &error.resolve(world))
+                .map_err(io::Error::from)?;
+            writeln!(w)?;
+        }
+        return Ok(());
+    }
+
     let mut w = match diagnostic_format {
         DiagnosticFormat::Human => color_stream(),
-        DiagnosticFormat::Short => StandardStream::stderr(ColorChoice::Never),
+        DiagnosticFormat::Short | DiagnosticFormat::Json => {
+            StandardStream::stderr(ColorChoice::Never)
+        }
     };
 
     let mut config = term::Config { tab_width: 2, ..Default::default() };
@@ -412,9 +494,14 @@ fn print_diagnostics(
         config.display_style = term::DisplayStyle::Short;
     }
 
-    for error in errors {
+    for error in diagnostics {
         // The main diagnostic.
-        let diag = Diagnostic::error()
+        let diag = match error.severity {
+            Severity::Error => Diagnostic::error(),
+            Severity::Warning => Diagnostic::warning(),
+        };
+
+        let diag = diag
             .with_message(error.message)
             .with_notes(
                 error
@@ -477,9 +564,16 @@ struct SystemWorld {
     hashes: RefCell<HashMap<FileId, FileResult<PathHash>>>,
     /// Maps canonical path hashes to source files and buffers.
     paths: RefCell<HashMap<PathHash, PathSlot>>,
-    /// The current date if requested. This is stored here to ensure it is
-    /// always the same within one compilation. Reset between compilations.
-    today: OnceCell<Option<Datetime>>,
+    /// The current date and time if requested. This is stored here to ensure
+    /// it is always the same within one compilation. Reset between
+    /// compilations.
+    now: OnceCell<Option<chrono::DateTime<chrono::Utc>>>,
+    /// A fixed UNIX timestamp to use instead of the system clock.
+    creation_timestamp: Option<i64>,
+    /// The packages that are available, listed once on first use. Not
+    /// reset between compilations, so that the index isn't fetched over the
+    /// network again.
+    packages: OnceCell<Vec<(PackageSpec, Option<EcoString>)>>,
 }
 
 /// Holds details about the location of a font and lazily the font itself.
@@ -506,7 +600,12 @@ struct PathSlot {
 }
 
 impl SystemWorld {
-    fn new(input: &Path, font_paths: &[PathBuf]) -> Self {
+    fn new(
+        input: &Path,
+        font_paths: &[PathBuf],
+        creation_timestamp: Option<i64>,
+        inputs: &[(String, String)],
+    ) -> Self {
         let mut searcher = FontSearcher::new();
         searcher.search(font_paths);
 
@@ -524,12 +623,18 @@ impl SystemWorld {
         Self {
             root,
             main,
-            library: Prehashed::new(typst_library::build()),
+            library: Prehashed::new(typst_library::build_with_inputs(
+                inputs
+                    .iter()
+                    .map(|(key, value)| (key.as_str().into(), value.as_str().into())),
+            )),
             book: Prehashed::new(searcher.book),
             fonts: searcher.fonts,
             hashes: RefCell::default(),
             paths: RefCell::default(),
-            today: OnceCell::new(),
+            now: OnceCell::new(),
+            creation_timestamp,
+            packages: OnceCell::new(),
         }
     }
 }
@@ -576,18 +681,36 @@ impl World for SystemWorld {
     }
 
     fn today(&self, offset: Option<i64>) -> Option<Datetime> {
-        *self.today.get_or_init(|| {
-            let naive = match offset {
-                None => chrono::Local::now().naive_local(),
-                Some(o) => (chrono::Utc::now() + chrono::Duration::hours(o)).naive_utc(),
-            };
+        let naive = self.naive_now(offset)?;
+        Datetime::from_ymd(
+            naive.year(),
+            naive.month().try_into().ok()?,
+            naive.day().try_into().ok()?,
+        )
+    }
 
-            Datetime::from_ymd(
-                naive.year(),
-                naive.month().try_into().ok()?,
-                naive.day().try_into().ok()?,
-            )
-        })
+    fn now(&self, offset: Option<i64>) -> Option<Datetime> {
+        let naive = self.naive_now(offset)?;
+        Datetime::from_ymd_hms(
+            naive.year(),
+            naive.month().try_into().ok()?,
+            naive.day().try_into().ok()?,
+            naive.hour().try_into().ok()?,
+            naive.minute().try_into().ok()?,
+            naive.second().try_into().ok()?,
+        )
+    }
+
+    fn package_versions(&self, namespace: EcoString, name: EcoString) -> Vec<Version> {
+        self.packages()
+            .iter()
+            .filter(|(spec, _)| spec.namespace == namespace && spec.name == name)
+            .map(|(spec, _)| spec.version)
+            .collect()
+    }
+
+    fn packages(&self) -> &[(PackageSpec, Option<EcoString>)] {
+        self.packages.get_or_init(list_packages)
     }
 }
 
@@ -672,7 +795,27 @@ impl SystemWorld {
     fn reset(&mut self) {
         self.hashes.borrow_mut().clear();
         self.paths.borrow_mut().clear();
-        self.today.take();
+        self.now.take();
+    }
+
+    /// The current date and time with the given offset in hours.
+    ///
+    /// If a creation timestamp was given, it is used instead of the system
+    /// clock and the local offset is taken to be UTC, so that the result does
+    /// not depend on the machine's time zone.
+    fn naive_now(&self, offset: Option<i64>) -> Option<chrono::NaiveDateTime> {
+        let now = (*self.now.get_or_init(|| match self.creation_timestamp {
+            Some(timestamp) => chrono::Utc.timestamp_opt(timestamp, 0).single(),
+            None => Some(chrono::Utc::now()),
+        }))?;
+
+        Some(match offset {
+            None if self.creation_timestamp.is_none() => {
+                now.with_timezone(&chrono::Local).naive_local()
+            }
+            None => now.naive_utc(),
+            Some(o) => (now + chrono::Duration::hours(o)).naive_utc(),
+        })
     }
 
     /// Lookup a source file by id.
@@ -746,6 +889,70 @@ fn prepare_package(spec: &PackageSpec) -> PackageResult<PathBuf> {
     Err(PackageError::NotFound(spec.clone()))
 }
 
+/// How long to wait for the package index before giving up on it.
+const INDEX_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// List the packages that are installed locally or, for the `@preview`
+/// namespace, available for download.
+///
+/// Failing to fetch the index, for example when offline, is not an error: Only
+/// the locally available packages are listed then.
+fn list_packages() -> Vec<(PackageSpec, Option<EcoString>)> {
+    let mut packages = preview_index();
+
+    for dir in [dirs::data_dir(), dirs::cache_dir()].into_iter().flatten() {
+        let Ok(namespaces) = fs::read_dir(dir.join("typst/packages")) else { continue };
+        for namespace in namespaces.flatten() {
+            let Ok(entries) = fs::read_dir(namespace.path()) else { continue };
+            let namespace: EcoString = namespace.file_name().to_string_lossy().into();
+            for entry in entries.flatten() {
+                let file_name = entry.file_name();
+                let Some((name, version)) =
+                    file_name.to_str().and_then(|name| name.rsplit_once('-'))
+                else {
+                    continue;
+                };
+
+                let Ok(version) = version.parse() else { continue };
+                let spec = PackageSpec {
+                    namespace: namespace.clone(),
+                    name: name.into(),
+                    version,
+                };
+                if !packages.iter().any(|(known, _)| *known == spec) {
+                    packages.push((spec, None));
+                }
+            }
+        }
+    }
+
+    packages
+}
+
+/// Fetch the index of the packages in the `@preview` namespace.
+fn preview_index() -> Vec<(PackageSpec, Option<EcoString>)> {
+    let agent = ureq::AgentBuilder::new().timeout(INDEX_TIMEOUT).build();
+    let index: Vec<serde_json::Value> = agent
+        .get("https://packages.typst.org/preview/index.json")
+        .call()
+        .ok()
+        .and_then(|response| serde_json::from_reader(response.into_reader()).ok())
+        .unwrap_or_default();
+
+    index
+        .iter()
+        .filter_map(|entry| {
+            let spec = PackageSpec {
+                namespace: "preview".into(),
+                name: entry.get("name")?.as_str()?.into(),
+                version: entry.get("version")?.as_str()?.parse().ok()?,
+            };
+            let description = entry.get("description").and_then(|d| d.as_str());
+            Some((spec, description.map(Into::into)))
+        })
+        .collect()
+}
+
 /// Download a package over the network.
 fn download_package(spec: &PackageSpec, package_dir: &Path) -> PackageResult<()> {
     // The `@preview` namespace is the only namespace that supports on-demand
@@ -978,7 +1185,7 @@ impl FontSearcher {
         let path = path.as_ref();
         if let Ok(file) = File::open(path) {
             if let Ok(mmap) = unsafe { Mmap::map(&file) } {
-                for (i, info) in FontInfo::iter(&mmap).enumerate() {
+                for (i, info) in FontInfo::iter_fast(&mmap).enumerate() {
                     self.book.push(info);
                     self.fonts.push(FontSlot {
                         path: path.into(),
@@ -5,7 +5,7 @@ use md::escape::escape_html;
 use pulldown_cmark as md;
 use typed_arena::Arena;
 use typst::diag::FileResult;
-use typst::eval::Datetime;
+use typst::eval::{Datetime, Tracer};
 use typst::file::FileId;
 use typst::font::{Font, FontBook};
 use typst::geom::{Point, Size};
@@ -418,7 +418,8 @@ fn code_block(resolver: &dyn Resolver, lang: &str, text: &str) -> Html {
     let id = FileId::new(None, Path::new("main.typ"));
     let source = Source::new(id, compile);
     let world = DocWorld(source);
-    let mut frames = match typst::compile(&world) {
+    let mut tracer = Tracer::default();
+    let mut frames = match typst::compile(&world, &mut tracer) {
         Ok(doc) => doc.pages,
         Err(err) => {
             let msg = &err[0].message;
@@ -499,4 +500,8 @@ impl World for DocWorld {
     fn today(&self, _: Option<i64>) -> Option<Datetime> {
         Some(Datetime::from_ymd(1970, 1, 1).unwrap())
     }
+
+    fn now(&self, _: Option<i64>) -> Option<Datetime> {
+        Some(Datetime::from_ymd_hms(1970, 1, 1, 0, 0, 0).unwrap())
+    }
 }
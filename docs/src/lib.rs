@@ -152,6 +152,7 @@ fn reference_pages(resolver: &dyn Resolver) -> PageModel {
         category_page(resolver, "symbols"),
         category_page(resolver, "foundations").with_part("Compute"),
         category_page(resolver, "calculate"),
+        category_page(resolver, "random"),
         category_page(resolver, "construct"),
         category_page(resolver, "data-loading"),
     ];
@@ -229,12 +230,14 @@ fn category_page(resolver: &dyn Resolver, category: &str) -> PageModel {
     let focus = match category {
         "math" => &LIBRARY.math,
         "calculate" => module(&LIBRARY.global, "calc").unwrap(),
+        "random" => module(&LIBRARY.global, "random").unwrap(),
         _ => &LIBRARY.global,
     };
 
     let parents: &[&str] = match category {
         "math" => &[],
         "calculate" => &["calc"],
+        "random" => &["random"],
         _ => &[],
     };
 
@@ -270,6 +273,23 @@ fn category_page(resolver: &dyn Resolver, category: &str) -> PageModel {
         children.push(subpage);
     }
 
+    // Add the functions of the color module.
+    if category == "construct" {
+        let color = module(&LIBRARY.global, "color").unwrap();
+        for (_, value) in color.scope().iter() {
+            let Value::Func(func) = value else { continue };
+            let Some(info) = func.info() else { continue };
+            let subpage = function_page(resolver, &route, func, info, &["color"]);
+            items.push(CategoryItem {
+                name: format!("color.{}", info.name),
+                route: subpage.route.clone(),
+                oneliner: oneliner(info.docs).into(),
+                code: true,
+            });
+            children.push(subpage);
+        }
+    }
+
     // Add grouped functions.
     for group in grouped {
         let mut functions = vec![];
@@ -955,7 +975,9 @@ const TYPE_ORDER: &[&str] = &[
     "fraction",
     "color",
     "datetime",
+    "duration",
     "string",
+    "bytes",
     "regex",
     "label",
     "content",
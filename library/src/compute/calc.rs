@@ -752,13 +752,17 @@ pub fn fract(
 
 /// Rounds a number to the nearest integer.
 ///
-/// Optionally, a number of decimal places can be specified.
+/// Optionally, a number of decimal places can be specified. A negative number
+/// of places rounds to the left of the decimal point. Halfway cases are
+/// rounded away from zero, also for negative numbers.
 ///
 /// ## Example { #example }
 /// ```example
 /// #assert(calc.round(3.14) == 3)
 /// #assert(calc.round(3.5) == 4)
-/// #calc.round(3.1415, digits: 2)
+/// #assert(calc.round(-3.5) == -4)
+/// #calc.round(3.1415, digits: 2) \
+/// #calc.round(1234, digits: -2)
 /// ```
 ///
 /// Display: Round
@@ -774,14 +778,33 @@ pub fn round(
 ) -> Num {
     match value {
         Num::Int(n) if digits == 0 => Num::Int(n),
-        _ => {
-            let n = value.float();
-            let factor = 10.0_f64.powi(digits as i32);
-            Num::Float((n * factor).round() / factor)
-        }
+        _ => Num::Float(round_to_digits(value.float(), digits)),
     }
 }
 
+/// Round a float to the given number of decimal places, which may be negative.
+pub fn round_to_digits(n: f64, digits: i64) -> f64 {
+    let digits = digits.clamp(-400, 400) as i32;
+    let rounded = if digits >= 0 {
+        let factor = 10.0_f64.powi(digits);
+        let scaled = n * factor;
+        if !scaled.is_finite() {
+            // Floats are not precise enough to have decimal places that fine.
+            return n;
+        }
+        scaled.round() / factor
+    } else {
+        let factor = 10.0_f64.powi(-digits);
+        if !factor.is_finite() {
+            return 0.0;
+        }
+        (n / factor).round() * factor
+    };
+
+    // Avoid negative zero, which would be displayed as `-0`.
+    rounded + 0.0
+}
+
 /// Clamps a number between a minimum and maximum value.
 ///
 /// ## Example { #example }
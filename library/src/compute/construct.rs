@@ -3,8 +3,11 @@ use std::str::FromStr;
 
 use time::{Month, PrimitiveDateTime};
 
-use typst::eval::{Datetime, Regex};
+use typst::eval::{Datetime, Duration, Module, Regex, TypeValue, Version};
+use typst::util::Bytes;
 
+use super::calc::{round_to_digits, Num};
+use super::Encoding;
 use crate::prelude::*;
 
 /// Converts a value to an integer.
@@ -169,6 +172,7 @@ pub struct Component(u8);
 
 cast! {
     Component,
+    self => Value::Int(self.0 as i64),
     v: i64 => match v {
         0 ..= 255 => Self(v as u8),
         _ => bail!("number must be between 0 and 255"),
@@ -184,7 +188,8 @@ cast! {
 ///
 /// You can specify the [datetime]($type/datetime) using a year, month, day,
 /// hour, minute, and second. You can also get the current date with
-/// [`datetime.today`]($func/datetime.today).
+/// [`datetime.today`]($func/datetime.today) or the current date and time with
+/// [`datetime.now`]($func/datetime.now).
 ///
 /// ## Example
 /// ```example
@@ -216,6 +221,7 @@ cast! {
 #[func]
 #[scope(
     scope.define("today", datetime_today_func());
+    scope.define("now", datetime_now_func());
     scope
 )]
 pub fn datetime(
@@ -338,6 +344,114 @@ pub fn datetime_today(
         .ok_or("unable to get the current date")?)
 }
 
+/// Returns the current date and time.
+///
+/// When compiling for reproducible builds, the host may fix this to a
+/// predetermined point in time instead of the actual clock.
+///
+/// ## Example
+/// ```example
+/// It is now
+/// #datetime.now().display().
+/// ```
+///
+/// Display: Now
+/// Category: construct
+#[func]
+pub fn datetime_now(
+    /// An offset to apply to the current UTC date and time. If set to
+    /// `{auto}`, the offset will be the local offset.
+    #[named]
+    #[default]
+    offset: Smart<i64>,
+    /// The virtual machine.
+    vt: &mut Vt,
+) -> StrResult<Datetime> {
+    Ok(vt
+        .world
+        .now(offset.as_custom())
+        .ok_or("unable to get the current date and time")?)
+}
+
+/// Creates a duration.
+///
+/// A duration can be added to or subtracted from a
+/// [datetime]($type/datetime), and subtracting two datetimes of the same kind
+/// yields a duration. The given components are summed up, so
+/// `{duration(hours: 1, minutes: 30)}` is the same as
+/// `{duration(minutes: 90)}`.
+///
+/// ## Example { #example }
+/// ```example
+/// #let start = datetime(year: 2023, month: 6, day: 1)
+/// #let deadline = start + duration(days: 14)
+/// Submit by #deadline.display().
+/// ```
+///
+/// Display: Duration
+/// Category: construct
+#[func]
+pub fn duration(
+    /// The number of seconds.
+    #[named]
+    #[default]
+    seconds: i64,
+    /// The number of minutes.
+    #[named]
+    #[default]
+    minutes: i64,
+    /// The number of hours.
+    #[named]
+    #[default]
+    hours: i64,
+    /// The number of days.
+    #[named]
+    #[default]
+    days: i64,
+    /// The number of weeks.
+    #[named]
+    #[default]
+    weeks: i64,
+) -> StrResult<Duration> {
+    let parts =
+        [(seconds, 1), (minutes, 60), (hours, 3_600), (days, 86_400), (weeks, 604_800)];
+
+    let total = parts
+        .into_iter()
+        .try_fold(0i64, |acc, (count, unit)| count.checked_mul(unit)?.checked_add(acc))
+        .ok_or("duration is too large")?;
+
+    Ok(Duration::from_seconds(total))
+}
+
+/// Creates a version from its components.
+///
+/// Versions can be compared with each other, where missing trailing
+/// components count as zero, so `{version(1, 2) == version(1, 2, 0)}`. The
+/// version of the compiler is available as `sys.version`.
+///
+/// ## Example { #example }
+/// ```example
+/// #version(0, 8, 1) \
+/// #(version(1, 10) > version(1, 9, 3)) \
+/// #version(2, 4).minor
+/// ```
+///
+/// Display: Version
+/// Category: construct
+#[func]
+pub fn version(
+    /// The components of the version, from the most to the least
+    /// significant.
+    #[variadic]
+    components: Vec<u32>,
+) -> StrResult<Version> {
+    if components.is_empty() {
+        bail!("version must have at least one component");
+    }
+    Ok(Version::new(components))
+}
+
 /// Creates a CMYK color.
 ///
 /// This is useful if you want to target a specific printer. The conversion
@@ -379,6 +493,504 @@ cast! {
     },
 }
 
+/// Creates a color in the Oklab color space.
+///
+/// Oklab is perceptually uniform: Colors with the same lightness appear
+/// equally bright and changing a component by the same amount changes the
+/// perceived color by about the same amount. This makes it well suited for
+/// mixing colors and building gradients. For display and export, the color
+/// is converted to sRGB, clipping it to the sRGB gamut if necessary.
+///
+/// ## Example { #example }
+/// ```example
+/// #square(fill: oklab(62.8%, 0.225, 0.126))
+/// #square(fill: oklab(70%, -0.1, -0.1, 50%))
+/// ```
+///
+/// Display: Oklab
+/// Category: construct
+#[func]
+pub fn oklab(
+    /// The perceived lightness.
+    lightness: UnitRatio,
+    /// The position on the green-red axis, typically between `{-0.4}` and
+    /// `{0.4}`.
+    a: f64,
+    /// The position on the blue-yellow axis, typically between `{-0.4}` and
+    /// `{0.4}`.
+    b: f64,
+    /// The alpha component.
+    #[default(Component(255))]
+    alpha: Component,
+) -> Color {
+    OklabColor::new(lightness.0, a, b, alpha.0).into()
+}
+
+/// Creates a color in the polar form of the Oklab color space.
+///
+/// Instead of coordinates on two axes, the color is given by its chroma (the
+/// distance from gray) and its hue angle. The resulting color is an
+/// [Oklab]($func/oklab) color.
+///
+/// ## Example { #example }
+/// ```example
+/// #for hue in range(0, 360, step: 60) {
+///   box(square(size: 20pt, fill: oklch(70%, 0.15, hue * 1deg)))
+/// }
+/// ```
+///
+/// Display: Oklch
+/// Category: construct
+#[func]
+pub fn oklch(
+    /// The perceived lightness.
+    lightness: UnitRatio,
+    /// The chroma, typically between `{0}` and `{0.4}`.
+    chroma: f64,
+    /// The hue angle.
+    hue: Angle,
+    /// The alpha component.
+    #[default(Component(255))]
+    alpha: Component,
+) -> Color {
+    OklabColor::from_lch(lightness.0, chroma, hue, alpha.0).into()
+}
+
+/// A ratio between 0% and 100%.
+pub struct UnitRatio(f64);
+
+cast! {
+    UnitRatio,
+    v: Ratio => if (0.0 ..= 1.0).contains(&v.get()) {
+        Self(v.get())
+    } else {
+        bail!("ratio must be between 0% and 100%");
+    },
+}
+
+/// A module with functions for constructing and mixing colors.
+pub fn color_module() -> Module {
+    let mut scope = Scope::new();
+    scope.define("hsl", hsl_func());
+    scope.define("hsv", hsv_func());
+    scope.define("mix", mix_func());
+    Module::new("color").with_scope(scope)
+}
+
+/// Creates an RGB color from a hue, saturation, and lightness.
+///
+/// This function is part of the `color` module.
+///
+/// ## Example { #example }
+/// ```example
+/// #square(fill: color.hsl(200deg, 80%, 40%))
+/// ```
+///
+/// Display: HSL
+/// Category: construct
+#[func]
+pub fn hsl(
+    /// The hue angle.
+    hue: Angle,
+    /// The saturation.
+    saturation: UnitRatio,
+    /// The lightness.
+    lightness: UnitRatio,
+    /// The alpha component.
+    #[default(Component(255))]
+    alpha: Component,
+) -> Color {
+    let (s, l) = (Ratio::new(saturation.0), Ratio::new(lightness.0));
+    RgbaColor::from_hsl(hue, s, l, alpha.0).into()
+}
+
+/// Creates an RGB color from a hue, saturation, and value.
+///
+/// This function is part of the `color` module.
+///
+/// ## Example { #example }
+/// ```example
+/// #square(fill: color.hsv(200deg, 80%, 60%))
+/// ```
+///
+/// Display: HSV
+/// Category: construct
+#[func]
+pub fn hsv(
+    /// The hue angle.
+    hue: Angle,
+    /// The saturation.
+    saturation: UnitRatio,
+    /// The value.
+    value: UnitRatio,
+    /// The alpha component.
+    #[default(Component(255))]
+    alpha: Component,
+) -> Color {
+    let (s, v) = (Ratio::new(saturation.0), Ratio::new(value.0));
+    RgbaColor::from_hsv(hue, s, v, alpha.0).into()
+}
+
+/// Mixes colors.
+///
+/// Each color can be given with a weight, as in `{(blue, 30%)}`. Colors
+/// without a weight have a weight of `{100%}`. The alpha components are
+/// mixed alongside the color. This function is part of the `color` module.
+///
+/// ## Example { #example }
+/// ```example
+/// #set square(size: 20pt)
+/// #square(fill: color.mix(red, blue))
+/// #square(fill: color.mix(red, (blue, 30%)))
+/// #square(fill: color.mix(red, blue, space: "srgb"))
+/// ```
+///
+/// Display: Mix
+/// Category: construct
+#[func]
+pub fn mix(
+    /// The colors to mix, optionally with weights.
+    #[variadic]
+    colors: Vec<WeightedColor>,
+    /// The color space in which to mix the colors.
+    ///
+    /// Mixing in `{"oklab"}` produces an Oklab color, in `{"cmyk"}` a CMYK
+    /// color and in the other spaces an RGB color. Since CMYK colors have no
+    /// alpha component, colors mixed in CMYK are opaque.
+    #[named]
+    #[default(ColorSpace::Oklab)]
+    space: ColorSpace,
+) -> StrResult<Color> {
+    if colors.is_empty() {
+        bail!("expected at least one color to mix");
+    }
+    Color::mix(colors.into_iter().map(|c| (c.0, c.1)), space)
+}
+
+/// A color with a weight for mixing.
+pub struct WeightedColor(Color, f64);
+
+cast! {
+    WeightedColor,
+    v: Color => Self(v, 1.0),
+    v: Array => {
+        let mut iter = v.into_iter();
+        match (iter.next(), iter.next(), iter.next()) {
+            (Some(c), Some(w), None) => Self(
+                c.cast()?,
+                match w {
+                    Value::Ratio(r) => r.get(),
+                    v => v.cast::<f64>()?,
+                },
+            ),
+            _ => bail!("expected a color or an array of a color and a weight"),
+        }
+    },
+}
+
+/// Creates a linear gradient.
+///
+/// This is a shorthand for [`gradient.linear`]($func/gradient.linear). Radial
+/// gradients are created with [`gradient.radial`]($func/gradient.radial).
+///
+/// Gradients can be used wherever a color can be used as a paint: as the
+/// fill of shapes and text and as the paint of strokes.
+///
+/// ## Example { #example }
+/// ```example
+/// #rect(width: 100%, fill: gradient(blue, green))
+/// #text(24pt, fill: gradient.linear(
+///   red, orange, yellow, relative: "self",
+/// ))[*Sunset*]
+/// ```
+///
+/// Display: Gradient
+/// Category: construct
+#[func]
+#[scope(
+    scope.define("linear", gradient_linear_func());
+    scope.define("radial", gradient_radial_func());
+    scope
+)]
+pub fn gradient(
+    /// The color stops of the gradient.
+    #[variadic]
+    stops: Vec<GradientStop>,
+    /// The direction of the gradient.
+    #[named]
+    #[default(Angle::zero())]
+    angle: Angle,
+    /// The color space in which to interpolate.
+    #[named]
+    #[default(ColorSpace::Srgb)]
+    space: ColorSpace,
+    /// The bounding box the gradient is spread over.
+    #[named]
+    #[default]
+    relative: Smart<RelativeTo>,
+) -> StrResult<Gradient> {
+    gradient_linear(stops, angle, space, relative)
+}
+
+/// Creates a linear gradient.
+///
+/// The colors change along a straight line through the center of the
+/// gradient's bounding box. The line is just long enough for the first and
+/// last color to reach the box's corners.
+///
+/// ## Example { #example }
+/// ```example
+/// #rect(width: 100%, fill: gradient.linear(
+///   (red, 0%), (yellow, 30%), (blue, 100%),
+///   angle: 45deg,
+/// ))
+/// #rect(width: 100%, fill: gradient.linear(
+///   blue, yellow, space: "oklab",
+/// ))
+/// ```
+///
+/// Display: Linear Gradient
+/// Category: construct
+#[func]
+pub fn gradient_linear(
+    /// The color stops of the gradient.
+    ///
+    /// A stop is either a color or an array of a color and its offset along
+    /// the gradient. Stops without an offset are spaced evenly between their
+    /// neighbours. There must be at least two stops.
+    #[variadic]
+    stops: Vec<GradientStop>,
+    /// The direction of the gradient, measured clockwise from left to right.
+    #[named]
+    #[default(Angle::zero())]
+    angle: Angle,
+    /// The color space in which to interpolate between the stops.
+    ///
+    /// Stops may be given in any color space. Interpolating in `{"oklab"}`
+    /// produces perceptually even transitions, while `{"srgb"}` matches most
+    /// other tools.
+    #[named]
+    #[default(ColorSpace::Srgb)]
+    space: ColorSpace,
+    /// The bounding box the gradient is spread over.
+    ///
+    /// If this is `{auto}`, gradients on text span its container and all
+    /// other gradients span the shape they fill or stroke. With
+    /// `{"parent"}`, multiple shapes can share a gradient that spans the
+    /// whole page.
+    #[named]
+    #[default]
+    relative: Smart<RelativeTo>,
+) -> StrResult<Gradient> {
+    let kind = GradientKind::Linear { angle };
+    Gradient::new(kind, stops.into_iter().map(|s| (s.0, s.1)).collect(), space, relative)
+}
+
+/// Creates a radial gradient.
+///
+/// The colors change with the distance from a center point. The circles of
+/// equal color stay circles even if the bounding box isn't a square.
+///
+/// ## Example { #example }
+/// ```example
+/// #circle(radius: 20pt, fill: gradient.radial(
+///   white, blue, center: (30%, 30%),
+/// ))
+/// ```
+///
+/// Display: Radial Gradient
+/// Category: construct
+#[func]
+pub fn gradient_radial(
+    /// The color stops of the gradient.
+    ///
+    /// A stop is either a color or an array of a color and its offset from
+    /// the center to the gradient's radius. There must be at least two stops.
+    #[variadic]
+    stops: Vec<GradientStop>,
+    /// The center of the gradient, relative to its bounding box.
+    #[named]
+    #[default(Axes::splat(Ratio::new(0.5)))]
+    center: Axes<Ratio>,
+    /// The radius of the gradient, relative to half the diagonal of its
+    /// bounding box. At the default of `{100%}`, the last color reaches the
+    /// corners of the box for a centered gradient.
+    #[named]
+    #[default(Ratio::one())]
+    radius: Ratio,
+    /// The color space in which to interpolate between the stops.
+    #[named]
+    #[default(ColorSpace::Srgb)]
+    space: ColorSpace,
+    /// The bounding box the gradient is spread over.
+    #[named]
+    #[default]
+    relative: Smart<RelativeTo>,
+) -> StrResult<Gradient> {
+    if radius.get() <= 0.0 {
+        bail!("radius must be positive");
+    }
+
+    let kind = GradientKind::Radial { center, radius };
+    Gradient::new(kind, stops.into_iter().map(|s| (s.0, s.1)).collect(), space, relative)
+}
+
+/// A color stop of a gradient with an optional offset.
+pub struct GradientStop(Color, Option<Ratio>);
+
+cast! {
+    GradientStop,
+    color: Color => Self(color, None),
+    array: Array => {
+        let mut iter = array.into_iter();
+        match (iter.next(), iter.next(), iter.next()) {
+            (Some(color), Some(offset), None) => Self(color.cast()?, Some(offset.cast()?)),
+            _ => bail!("a color stop must be a color or an array of a color and an offset"),
+        }
+    },
+}
+
+/// Creates a tiling pattern.
+///
+/// The body is laid out once into a tile, which is then repeated to fill
+/// shapes, text, and backgrounds. Like gradients, patterns can be used
+/// wherever a color can be used as a paint.
+///
+/// ## Example { #example }
+/// ```example
+/// #let hatch = pattern(
+///   size: (6pt, 6pt),
+///   line(start: (0%, 100%), end: (100%, 0%), stroke: 0.5pt),
+/// )
+/// #rect(width: 100%, height: 30pt, fill: hatch)
+/// #text(24pt, weight: "bold", fill: pattern(
+///   spacing: (1pt, 1pt),
+///   square(size: 2pt, fill: blue),
+/// ))[Dots]
+/// ```
+///
+/// The body is laid out with the default styles, so set rules around the
+/// pattern don't affect its tiles. Set rules within the body work as usual.
+/// Anything that extends beyond the tile is clipped.
+///
+/// Display: Pattern
+/// Category: construct
+#[func]
+pub fn pattern(
+    /// The content of a single tile.
+    body: Content,
+    /// The size of a single tile. If this is `{auto}`, the tile is just large
+    /// enough to fit the body.
+    #[named]
+    #[default]
+    size: Smart<Axes<Length>>,
+    /// The gap between two neighbouring tiles.
+    #[named]
+    #[default(Axes::splat(Length::zero()))]
+    spacing: Axes<Length>,
+    /// The bounding box the tiles are aligned to.
+    ///
+    /// If this is `{auto}`, patterns on text are aligned to its container and
+    /// all other patterns to the shape they fill or stroke. With
+    /// `{"parent"}`, the tiles of neighbouring shapes line up seamlessly.
+    #[named]
+    #[default]
+    relative: Smart<RelativeTo>,
+    /// The callsite span.
+    span: Span,
+    /// The virtual machine.
+    vm: &mut Vm,
+) -> SourceResult<Pattern> {
+    let world = vm.world();
+    let styles = StyleChain::new(&world.library().styles);
+    let size = size.resolve(styles);
+    let spacing = spacing.resolve(styles);
+    if spacing.x < Abs::zero() || spacing.y < Abs::zero() {
+        bail!(span, "pattern spacing must not be negative");
+    }
+
+    let pod = match size {
+        Smart::Auto => Regions::one(Size::splat(Abs::inf()), Axes::splat(false)),
+        Smart::Custom(size) => Regions::one(size, Axes::splat(true)),
+    };
+
+    let frame = body.measure(&mut vm.vt, styles, pod)?.into_frame();
+    let Size { x, y } = frame.size();
+    if !x.is_finite() || !y.is_finite() {
+        bail!(span, "pattern tile size must be finite");
+    } else if x <= Abs::zero() || y <= Abs::zero() {
+        bail!(span, "pattern tile size must be positive");
+    }
+
+    Ok(Pattern::new(frame, spacing, relative))
+}
+
+/// Creates a stroke.
+///
+/// This is equivalent to passing a dictionary with the same keys wherever a
+/// stroke is expected. Fields that are left out are taken from the context,
+/// for example from a set rule or the default stroke.
+///
+/// ## Example { #example }
+/// ```example
+/// #line(length: 100%, stroke: stroke(
+///   paint: blue,
+///   thickness: 2pt,
+///   dash: "dashed",
+/// ))
+/// #line(length: 100%, stroke: stroke(
+///   thickness: 2pt,
+///   cap: "round",
+///   dash: (array: (6pt, 3pt, "dot", 3pt), phase: 2pt),
+/// ))
+/// ```
+///
+/// Display: Stroke
+/// Category: construct
+#[func]
+pub fn stroke(
+    /// The color, gradient, or pattern of the stroke.
+    #[named]
+    #[default]
+    paint: Smart<Paint>,
+    /// The thickness of the stroke.
+    #[named]
+    #[default]
+    thickness: Smart<Length>,
+    /// How the ends of the stroke are shaped: `{"butt"}`, `{"round"}`, or
+    /// `{"square"}`.
+    #[named]
+    #[default]
+    cap: Smart<LineCap>,
+    /// How corners of the stroke are shaped: `{"miter"}`, `{"round"}`, or
+    /// `{"bevel"}`.
+    #[named]
+    #[default]
+    join: Smart<LineJoin>,
+    /// The dash pattern of the stroke. This is either one of the predefined
+    /// patterns like `{"dashed"}` or `{"dotted"}`, an array of dash and gap
+    /// lengths, or a dictionary with such an `array` and a `phase` that
+    /// shifts the pattern's start. In an array, `{"dot"}` stands for a length
+    /// equal to the stroke's thickness.
+    #[named]
+    #[default]
+    dash: Smart<Option<DashPattern>>,
+    /// How far a miter join may extend, relative to the stroke's thickness,
+    /// before it is cut off with a bevel.
+    #[named]
+    #[default]
+    miter_limit: Smart<f64>,
+) -> PartialStroke {
+    PartialStroke {
+        paint,
+        thickness,
+        line_cap: cap,
+        line_join: join,
+        dash_pattern: dash,
+        miter_limit: miter_limit.map(Scalar),
+    }
+}
+
 /// Creates a custom symbol with modifiers.
 ///
 /// ## Example { #example }
@@ -446,8 +1058,12 @@ cast! {
 ///
 /// - Integers are formatted in base 10. This can be overridden with the
 ///   optional `base` parameter.
-/// - Floats are formatted in base 10 and never in exponential notation.
+/// - Floats are formatted in base 10 and never in exponential notation. The
+///   number of decimal places can be fixed with the optional `precision`
+///   parameter. For more control, see [`format-number`]($func/format-number).
 /// - From labels the name is extracted.
+/// - Bytes are decoded as UTF-8. This can be made explicit with the optional
+///   `encoding` parameter.
 ///
 /// If you wish to convert from and to Unicode code points, see
 /// [`str.to-unicode`]($func/str.to-unicode) and
@@ -458,8 +1074,10 @@ cast! {
 /// #str(10) \
 /// #str(4000, base: 16) \
 /// #str(2.7) \
+/// #str(2.7, precision: 3) \
 /// #str(1e8) \
-/// #str(<intro>)
+/// #str(<intro>) \
+/// #str(bytes((72, 105)))
 /// ```
 ///
 /// Display: String
@@ -472,12 +1090,37 @@ cast! {
 )]
 pub fn str(
     /// The value that should be converted to a string.
-    value: ToStr,
+    value: Spanned<ToStr>,
     /// The base (radix) to display integers in, between 2 and 36.
     #[named]
     #[default(Spanned::new(10, Span::detached()))]
     base: Spanned<i64>,
+    /// The number of decimal places to display numbers with, at most 17.
+    /// Numbers are rounded if necessary.
+    #[named]
+    precision: Option<Spanned<usize>>,
+    /// The encoding to decode bytes with.
+    #[named]
+    encoding: Option<Spanned<Encoding>>,
 ) -> SourceResult<Str> {
+    let Spanned { v: value, span } = value;
+    if let Some(Spanned { span, .. }) = encoding {
+        if !matches!(value, ToStr::Bytes(_)) {
+            bail!(span, "encoding is only supported for bytes");
+        }
+    }
+
+    if let Some(Spanned { span, .. }) = precision {
+        if !matches!(value, ToStr::Int(_) | ToStr::Float(_)) {
+            bail!(span, "precision is only supported for numbers");
+        }
+        if base.v != 10 {
+            bail!(span, "precision is only supported in base 10");
+        }
+    }
+
+    let precision = check_decimals(precision)?;
+
     Ok(match value {
         ToStr::Str(s) => {
             if base.v != 10 {
@@ -489,7 +1132,24 @@ pub fn str(
             if base.v < 2 || base.v > 36 {
                 bail!(base.span, "base must be between 2 and 36");
             }
-            int_to_base(n, base.v).into()
+            if precision.is_some() {
+                format_decimal(Num::Int(n), precision).into()
+            } else {
+                int_to_base(n, base.v).into()
+            }
+        }
+        ToStr::Float(n) => {
+            if base.v != 10 {
+                bail!(base.span, "base is only supported for integers");
+            }
+            format_decimal(Num::Float(n), precision).into()
+        }
+        ToStr::Bytes(bytes) => {
+            if base.v != 10 {
+                bail!(base.span, "base is only supported for integers");
+            }
+            let encoding = encoding.map_or(Encoding::Utf8, |encoding| encoding.v);
+            encoding.decode(&bytes).at(span)?
         }
     })
 }
@@ -500,14 +1160,114 @@ pub enum ToStr {
     Str(Str),
     /// An integer about to be formatted in a given base.
     Int(i64),
+    /// A float about to be formatted with a given precision.
+    Float(f64),
+    /// Bytes about to be decoded.
+    Bytes(Bytes),
 }
 
 cast! {
     ToStr,
     v: i64 => Self::Int(v),
-    v: f64 => Self::Str(format_str!("{}", v)),
+    v: f64 => Self::Float(v),
     v: Label => Self::Str(v.0.into()),
     v: Str => Self::Str(v),
+    v: Bytes => Self::Bytes(v),
+    v: Version => Self::Str(eco_format!("{v}").into()),
+    v: TypeValue => Self::Str(v.name().into()),
+}
+
+/// Formats a number with a fixed number of decimal places and separators.
+///
+/// The number is always written in base 10 and never in exponential notation.
+/// If necessary, it is rounded to the given number of decimal places, with
+/// halfway cases being rounded away from zero.
+///
+/// ## Example { #example }
+/// ```example
+/// #format-number(1234567.891, decimals: 2, thousands: ",") \
+/// #format-number(0.5, decimals: 3, decimal: ",") \
+/// #format-number(-42, decimals: 1) \
+/// #format-number(1e21, thousands: " ")
+/// ```
+///
+/// Display: Format Number
+/// Category: construct
+#[func]
+pub fn format_number(
+    /// The number to format.
+    value: Num,
+    /// The number of decimal places, at most 17. If omitted, as many decimal
+    /// places as necessary are displayed.
+    #[named]
+    decimals: Option<Spanned<usize>>,
+    /// The separator between groups of three digits in front of the decimal
+    /// separator. If omitted, digits aren't grouped.
+    #[named]
+    thousands: Option<EcoString>,
+    /// The separator between the integral and the fractional part.
+    #[named]
+    #[default(".".into())]
+    decimal: EcoString,
+) -> SourceResult<Str> {
+    let text = format_decimal(value, check_decimals(decimals)?);
+    let (sign, digits) = match text.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", text.as_str()),
+    };
+
+    let (integral, fractional) = match digits.split_once('.') {
+        Some((integral, fractional)) => (integral, Some(fractional)),
+        None => (digits, None),
+    };
+
+    // Infinity and NaN are displayed as they are.
+    if !integral.bytes().all(|b| b.is_ascii_digit()) {
+        return Ok(text.into());
+    }
+
+    let mut out = EcoString::from(sign);
+    for (i, c) in integral.chars().enumerate() {
+        if i > 0 && (integral.len() - i) % 3 == 0 {
+            out.push_str(thousands.as_deref().unwrap_or_default());
+        }
+        out.push(c);
+    }
+
+    if let Some(fractional) = fractional {
+        out.push_str(&decimal);
+        out.push_str(fractional);
+    }
+
+    Ok(out.into())
+}
+
+/// The maximum number of decimal places that numbers can be formatted with.
+/// Floats don't have more significant decimal digits than this.
+const MAX_DECIMALS: usize = 17;
+
+/// Ensure that a number of decimal places is not too large.
+fn check_decimals(decimals: Option<Spanned<usize>>) -> SourceResult<Option<usize>> {
+    match decimals {
+        Some(Spanned { v, span }) if v > MAX_DECIMALS => {
+            bail!(span, "number of decimal places must be at most {MAX_DECIMALS}")
+        }
+        decimals => Ok(decimals.map(|decimals| decimals.v)),
+    }
+}
+
+/// Format a number in base 10 without exponential notation, optionally with a
+/// fixed number of decimal places.
+fn format_decimal(value: Num, decimals: Option<usize>) -> EcoString {
+    match (value, decimals) {
+        (Num::Int(n), None | Some(0)) => eco_format!("{n}"),
+        (Num::Int(n), Some(decimals)) => eco_format!("{n}.{}", "0".repeat(decimals)),
+        (Num::Float(n), None) => eco_format!("{n}"),
+        (Num::Float(n), Some(decimals)) => {
+            let rounded = round_to_digits(n, decimals.try_into().unwrap_or(i64::MAX));
+            eco_format!("{rounded:.decimals$}")
+        }
+    }
 }
 
 /// Format an integer in a base.
@@ -595,6 +1355,75 @@ cast! {
     },
 }
 
+/// Converts a value to bytes.
+///
+/// - Strings are encoded in UTF-8.
+/// - Arrays of integers between `{0}` and `{255}` are converted directly.
+///
+/// ## Example { #example }
+/// ```example
+/// #bytes("Hello 😃") \
+/// #bytes((123, 160, 22, 0))
+/// ```
+///
+/// Display: Bytes
+/// Category: construct
+#[func]
+pub fn bytes(
+    /// The value that should be converted to bytes.
+    value: ToBytes,
+) -> Bytes {
+    value.0
+}
+
+/// A value that can be cast to bytes.
+pub struct ToBytes(Bytes);
+
+cast! {
+    ToBytes,
+    v: Str => Self(v.as_bytes().into()),
+    v: Bytes => Self(v),
+    v: Array => Self(
+        v.into_iter()
+            .map(|item| match item.cast::<i64>()? {
+                byte @ 0..=255 => Ok(byte as u8),
+                _ => bail!("number must be between 0 and 255"),
+            })
+            .collect::<StrResult<Vec<u8>>>()?
+            .into(),
+    ),
+}
+
+/// Converts a value to an array.
+///
+/// Bytes are converted into an array of integers between `{0}` and `{255}`.
+/// Arrays are returned as they are.
+///
+/// ## Example { #example }
+/// ```example
+/// #let hi = "Hello 😃"
+/// #array(bytes(hi))
+/// ```
+///
+/// Display: Array
+/// Category: construct
+#[func]
+pub fn array(
+    /// The value that should be converted to an array.
+    value: ToArray,
+) -> Array {
+    value.0
+}
+
+/// A value that can be cast to an array.
+pub struct ToArray(Array);
+
+cast! {
+    ToArray,
+    v: Bytes => Self(v.iter().map(|&b| Value::Int(b as i64)).collect()),
+    v: Array => Self(v),
+}
+
 /// Creates a label from a string.
 ///
 /// Inserting a label into content attaches it to the closest previous element
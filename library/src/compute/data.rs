@@ -1,11 +1,16 @@
+use std::ops::Range;
+
 use typst::diag::{format_xml_like_error, FileError};
-use typst::eval::Datetime;
+use typst::eval::{Datetime, Duration};
+use typst::util::Bytes;
 
 use crate::prelude::*;
 
-/// Reads plain text from a file.
+/// Reads plain text or data from a file.
 ///
-/// The file will be read and returned as a string.
+/// By default, the file will be read as UTF-8 and returned as a string. If
+/// `{none}` is passed as the `encoding`, the raw [bytes]($type/bytes) of the
+/// file are returned instead.
 ///
 /// ## Example { #example }
 /// ```example
@@ -13,6 +18,9 @@ use crate::prelude::*;
 ///
 /// An example for a HTML file:\
 /// #raw(text, lang: "html")
+///
+/// Raw bytes:
+/// #repr(read("tiger.jpg", encoding: none))
 /// ```
 ///
 /// Display: Read
@@ -21,16 +29,47 @@ use crate::prelude::*;
 pub fn read(
     /// Path to a file.
     path: Spanned<EcoString>,
+    /// The encoding to read the file with.
+    ///
+    /// If set to `{none}`, this function returns raw bytes.
+    #[named]
+    #[default(Some(Encoding::Utf8))]
+    encoding: Option<Encoding>,
     /// The virtual machine.
     vm: &mut Vm,
-) -> SourceResult<Str> {
+) -> SourceResult<Value> {
     let Spanned { v: path, span } = path;
     let id = vm.location().join(&path).at(span)?;
     let data = vm.world().file(id).at(span)?;
-    let text = std::str::from_utf8(&data)
-        .map_err(|_| "file is not valid utf-8")
-        .at(span)?;
-    Ok(text.into())
+    Ok(match encoding {
+        None => data.into_value(),
+        Some(Encoding::Utf8) => std::str::from_utf8(&data)
+            .map_err(|_| "file is not valid utf-8")
+            .at(span)?
+            .into_value(),
+    })
+}
+
+/// An encoding of text.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum Encoding {
+    /// The Unicode UTF-8 encoding.
+    #[string("utf-8")]
+    Utf8,
+}
+
+impl Encoding {
+    /// Decode bytes into a string.
+    pub fn decode(self, bytes: &[u8]) -> StrResult<Str> {
+        match self {
+            Self::Utf8 => std::str::from_utf8(bytes).map(Into::into).map_err(|err| {
+                eco_format!(
+                    "bytes are not valid utf-8 (invalid sequence at byte {})",
+                    err.valid_up_to()
+                )
+            }),
+        }
+    }
 }
 
 /// Reads structured data from a CSV file.
@@ -311,7 +350,10 @@ fn format_toml_error(error: toml::de::Error) -> EcoString {
 /// Typst arrays. Strings and booleans will be converted into the Typst
 /// equivalents, null-values (`null`, `~` or empty ``) will be converted into
 /// `{none}`, and numbers will be converted to floats or integers depending on
-/// whether they are whole numbers.
+/// whether they are whole numbers. Strings in the format of YAML timestamps,
+/// like `2023-06-12` or `2023-06-12T14:30:15Z`, will be converted into
+/// [datetimes]($type/datetime). Fractional seconds are dropped and timestamps
+/// with a time zone are converted to UTC.
 ///
 /// Note that mapping keys that are not a string cause the entry to be
 /// discarded.
@@ -345,6 +387,10 @@ fn format_toml_error(error: toml::de::Error) -> EcoString {
 /// Display: YAML
 /// Category: data-loading
 #[func]
+#[scope(
+    scope.define("encode", yaml_encode_func());
+    scope
+)]
 pub fn yaml(
     /// Path to a YAML file.
     path: Spanned<EcoString>,
@@ -368,7 +414,10 @@ fn convert_yaml(value: serde_yaml::Value) -> Value {
             Some(int) => int.into_value(),
             None => v.as_f64().unwrap_or(f64::NAN).into_value(),
         },
-        serde_yaml::Value::String(v) => v.into_value(),
+        serde_yaml::Value::String(v) => match parse_yaml_timestamp(&v) {
+            Some(datetime) => datetime.into_value(),
+            None => v.into_value(),
+        },
         serde_yaml::Value::Sequence(v) => {
             v.into_iter().map(convert_yaml).collect::<Array>().into_value()
         }
@@ -391,11 +440,270 @@ fn convert_yaml_key(key: serde_yaml::Value) -> Option<Str> {
     }
 }
 
+/// Parse a YAML timestamp like `2001-12-14` or `2001-12-14t21:59:43.10-05:00`.
+fn parse_yaml_timestamp(text: &str) -> Option<Datetime> {
+    /// Parse a number with the given range of digits.
+    fn number<T: std::str::FromStr>(text: &str, digits: Range<usize>) -> Option<T> {
+        if !digits.contains(&text.len()) || !text.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        text.parse().ok()
+    }
+
+    let (date, time) = match text.find(['T', 't', ' ', '\t']) {
+        Some(i) => (&text[..i], Some(text[i + 1..].trim_start())),
+        None => (text, None),
+    };
+
+    let mut parts = date.split('-');
+    let year = number(parts.next()?, 4..5)?;
+    let month = number(parts.next()?, 1..3)?;
+    let day = number(parts.next()?, 1..3)?;
+    if parts.next().is_some() {
+        return None;
+    }
+
+    let Some(time) = time else {
+        return Datetime::from_ymd(year, month, day);
+    };
+
+    // Split off the time zone and determine its offset from UTC in seconds.
+    let (clock, offset) = if let Some(clock) = time.strip_suffix(['Z', 'z']) {
+        (clock.trim_end(), 0)
+    } else if let Some(i) = time.rfind(['+', '-']) {
+        let (hours, minutes) = match time[i + 1..].split_once(':') {
+            Some((hours, minutes)) => (hours, number(minutes, 2..3)?),
+            None => (&time[i + 1..], 0),
+        };
+        let offset = 3600 * number::<i64>(hours, 1..3)? + 60 * minutes;
+        let sign = if time[i..].starts_with('-') { -1 } else { 1 };
+        (time[..i].trim_end(), sign * offset)
+    } else {
+        (time, 0)
+    };
+
+    let mut parts = clock.split(':');
+    let hour = number(parts.next()?, 1..3)?;
+    let minute = number(parts.next()?, 2..3)?;
+    let seconds = parts.next()?;
+    let (second, fraction) = seconds.split_once('.').unwrap_or((seconds, "0"));
+    let second = number(second, 2..3)?;
+    if fraction.is_empty() || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    if parts.next().is_some() {
+        return None;
+    }
+
+    Datetime::from_ymd_hms(year, month, day, hour, minute, second)?
+        .sub_duration(Duration::from_seconds(offset))
+        .ok()
+}
+
 /// Format the user-facing YAML error message.
 fn format_yaml_error(error: serde_yaml::Error) -> EcoString {
     eco_format!("failed to parse yaml file: {}", error.to_string().trim())
 }
 
+/// Encodes structured data into a YAML string.
+///
+/// Dictionaries, arrays, strings, numbers, booleans, `{none}`, and
+/// datetimes can be encoded. This is the inverse of [`yaml`]($func/yaml) and
+/// mostly useful to inspect data while debugging a template.
+///
+/// ## Example { #example }
+/// ```example
+/// #raw(
+///   yaml.encode((name: "Typst", tags: ("fast", "fun"))),
+///   lang: "yaml",
+/// )
+/// ```
+///
+/// Display: Encode YAML
+/// Category: data-loading
+#[func]
+pub fn yaml_encode(
+    /// The value to encode.
+    value: Spanned<Value>,
+) -> SourceResult<Str> {
+    let Spanned { v: value, span } = value;
+    let yaml = encode_yaml(value).at(span)?;
+    let text = serde_yaml::to_string(&yaml)
+        .map_err(|err| eco_format!("failed to encode value as yaml: {err}"))
+        .at(span)?;
+    Ok(text.trim_start_matches("---\n").into())
+}
+
+/// Convert a Typst value to a YAML value.
+fn encode_yaml(value: Value) -> StrResult<serde_yaml::Value> {
+    Ok(match value {
+        Value::None => serde_yaml::Value::Null,
+        Value::Bool(v) => serde_yaml::Value::Bool(v),
+        Value::Int(v) => serde_yaml::Value::Number(v.into()),
+        Value::Float(v) => serde_yaml::Value::Number(v.into()),
+        Value::Str(v) => serde_yaml::Value::String(v.into()),
+        Value::Array(v) => serde_yaml::Value::Sequence(
+            v.into_iter().map(encode_yaml).collect::<StrResult<_>>()?,
+        ),
+        Value::Dict(v) => serde_yaml::Value::Mapping(
+            v.into_iter()
+                .map(|(key, value)| {
+                    Ok((serde_yaml::Value::String(key.into()), encode_yaml(value)?))
+                })
+                .collect::<StrResult<_>>()?,
+        ),
+        Value::Dyn(v) if v.is::<Datetime>() => {
+            let datetime = v.downcast::<Datetime>().unwrap();
+            serde_yaml::Value::String(datetime.display(None)?.to_string())
+        }
+        v => bail!("cannot encode {} as yaml", v.type_name()),
+    })
+}
+
+/// Reads structured data from a CBOR file.
+///
+/// CBOR is a compact binary format for the same kind of data as JSON. Maps
+/// will be converted into Typst dictionaries, and arrays will be converted
+/// into Typst arrays. Strings, booleans, and numbers will be converted into
+/// the Typst equivalents, byte strings into [bytes]($type/bytes), and `null`
+/// and `undefined` into `{none}`. Tags are ignored, though the tagged value
+/// will still be present.
+///
+/// Note that map keys that are not a string cause the entry to be discarded.
+///
+/// ## Example { #example }
+/// ```example
+/// #let data = cbor("data.cbor")
+/// #data.name has #data.stars stars.
+/// ```
+///
+/// Display: CBOR
+/// Category: data-loading
+#[func]
+#[scope(
+    scope.define("encode", cbor_encode_func());
+    scope
+)]
+pub fn cbor(
+    /// Path to a CBOR file.
+    path: Spanned<EcoString>,
+    /// The virtual machine.
+    vm: &mut Vm,
+) -> SourceResult<Value> {
+    let Spanned { v: path, span } = path;
+    let id = vm.location().join(&path).at(span)?;
+    let data = vm.world().file(id).at(span)?;
+    let value: ciborium::value::Value = ciborium::de::from_reader(data.as_slice())
+        .map_err(format_cbor_error)
+        .at(span)?;
+    Ok(convert_cbor(value))
+}
+
+/// Convert a CBOR value to a Typst value.
+fn convert_cbor(value: ciborium::value::Value) -> Value {
+    match value {
+        ciborium::value::Value::Null => Value::None,
+        ciborium::value::Value::Bool(v) => v.into_value(),
+        ciborium::value::Value::Integer(v) => match i64::try_from(v) {
+            Ok(int) => int.into_value(),
+            Err(_) => (i128::from(v) as f64).into_value(),
+        },
+        ciborium::value::Value::Float(v) => v.into_value(),
+        ciborium::value::Value::Text(v) => v.into_value(),
+        ciborium::value::Value::Bytes(v) => Bytes::from(v).into_value(),
+        ciborium::value::Value::Array(v) => {
+            v.into_iter().map(convert_cbor).collect::<Array>().into_value()
+        }
+        ciborium::value::Value::Map(v) => v
+            .into_iter()
+            .filter_map(|(key, value)| match key {
+                ciborium::value::Value::Text(key) => {
+                    Some((key.into(), convert_cbor(value)))
+                }
+                _ => None,
+            })
+            .collect::<Dict>()
+            .into_value(),
+        ciborium::value::Value::Tag(_, v) => convert_cbor(*v),
+        _ => Value::None,
+    }
+}
+
+/// Format the user-facing CBOR error message.
+fn format_cbor_error(error: ciborium::de::Error<std::io::Error>) -> EcoString {
+    match error {
+        ciborium::de::Error::Syntax(offset) => {
+            eco_format!("failed to parse cbor file: syntax error at byte {offset}")
+        }
+        ciborium::de::Error::Semantic(Some(offset), message) => {
+            eco_format!("failed to parse cbor file: {message} at byte {offset}")
+        }
+        ciborium::de::Error::Semantic(None, message) => {
+            eco_format!("failed to parse cbor file: {message}")
+        }
+        ciborium::de::Error::Io(_) => {
+            "failed to parse cbor file: unexpected end of file".into()
+        }
+        ciborium::de::Error::RecursionLimitExceeded => {
+            "failed to parse cbor file: data is nested too deeply".into()
+        }
+    }
+}
+
+/// Encodes structured data into CBOR bytes.
+///
+/// Dictionaries, arrays, strings, bytes, numbers, booleans, `{none}`, and
+/// datetimes can be encoded. Datetimes are encoded as strings. This is the
+/// inverse of [`cbor`]($func/cbor).
+///
+/// ## Example { #example }
+/// ```example
+/// #repr(cbor.encode((name: "Typst", stars: 25000)))
+/// ```
+///
+/// Display: Encode CBOR
+/// Category: data-loading
+#[func]
+pub fn cbor_encode(
+    /// The value to encode.
+    value: Spanned<Value>,
+) -> SourceResult<Bytes> {
+    let Spanned { v: value, span } = value;
+    let cbor = encode_cbor(value).at(span)?;
+    let mut bytes = Vec::new();
+    ciborium::ser::into_writer(&cbor, &mut bytes)
+        .map_err(|err| eco_format!("failed to encode value as cbor: {err}"))
+        .at(span)?;
+    Ok(bytes.into())
+}
+
+/// Convert a Typst value to a CBOR value.
+fn encode_cbor(value: Value) -> StrResult<ciborium::value::Value> {
+    Ok(match value {
+        Value::None => ciborium::value::Value::Null,
+        Value::Bool(v) => ciborium::value::Value::Bool(v),
+        Value::Int(v) => ciborium::value::Value::Integer(v.into()),
+        Value::Float(v) => ciborium::value::Value::Float(v),
+        Value::Str(v) => ciborium::value::Value::Text(v.into()),
+        Value::Bytes(v) => ciborium::value::Value::Bytes(v.to_vec()),
+        Value::Array(v) => ciborium::value::Value::Array(
+            v.into_iter().map(encode_cbor).collect::<StrResult<_>>()?,
+        ),
+        Value::Dict(v) => ciborium::value::Value::Map(
+            v.into_iter()
+                .map(|(key, value)| {
+                    Ok((ciborium::value::Value::Text(key.into()), encode_cbor(value)?))
+                })
+                .collect::<StrResult<_>>()?,
+        ),
+        Value::Dyn(v) if v.is::<Datetime>() => {
+            let datetime = v.downcast::<Datetime>().unwrap();
+            ciborium::value::Value::Text(datetime.display(None)?.to_string())
+        }
+        v => bail!("cannot encode {} as cbor", v.type_name()),
+    })
+}
+
 /// Reads structured data from an XML file.
 ///
 /// The XML file is parsed into an array of dictionaries and strings. XML nodes
@@ -1,8 +1,32 @@
+use typst::eval::{Datetime, Duration, Module, Plugin, Regex, TypeValue, Version};
+
+use super::{
+    array_func, bytes_func, datetime_func, duration_func, float_func, gradient_func,
+    int_func, label_func, pattern_func, regex_func, str_func, stroke_func, symbol_func,
+    version_func,
+};
 use crate::prelude::*;
 
+/// A module with information about the system Typst runs in.
+///
+/// Its `version` is the version of the compiler and its `inputs` are the
+/// key-value pairs of strings the host application passed into the
+/// compilation.
+pub fn sys_module(inputs: Dict) -> Module {
+    let mut scope = Scope::new();
+    scope.define("version", Version::compiler());
+    scope.define("inputs", inputs);
+    Module::new("sys").with_scope(scope)
+}
+
 /// Determines the type of a value.
 ///
-/// Returns the name of the value's type.
+/// Returns the value's type. Types compare equal to the functions that
+/// construct them, so you can check for an integer with `{type(x) == int}`.
+/// For compatibility, types also compare equal to their name.
+///
+/// A type with a constructor can be called like the constructor itself: The
+/// call `{type(1)("12")}` converts the string into an integer.
 ///
 /// ## Example { #example }
 /// ```example
@@ -11,7 +35,9 @@ use crate::prelude::*;
 /// #type("hello") \
 /// #type(none) \
 /// #type([Hi]) \
-/// #type(x => x + 1)
+/// #type(x => x + 1) \
+/// #(type(12) == int) \
+/// #(type("hello") == "string")
 /// ```
 ///
 /// Display: Type
@@ -20,8 +46,27 @@ use crate::prelude::*;
 pub fn type_(
     /// The value whose type's to determine.
     value: Value,
-) -> Str {
-    value.type_name().into()
+) -> Value {
+    let ty = TypeValue::of(&value);
+    let constructor = match &value {
+        Value::Int(_) => int_func(),
+        Value::Float(_) => float_func(),
+        Value::Str(_) => str_func(),
+        Value::Symbol(_) => symbol_func(),
+        Value::Bytes(_) => bytes_func(),
+        Value::Label(_) => label_func(),
+        Value::Array(_) => array_func(),
+        Value::Dyn(v) if v.is::<Regex>() => regex_func(),
+        Value::Dyn(v) if v.is::<Datetime>() => datetime_func(),
+        Value::Dyn(v) if v.is::<Duration>() => duration_func(),
+        Value::Dyn(v) if v.is::<Version>() => version_func(),
+        Value::Dyn(v) if v.is::<Gradient>() => gradient_func(),
+        Value::Dyn(v) if v.is::<Pattern>() => pattern_func(),
+        Value::Dyn(v) if v.is::<PartialStroke>() => stroke_func(),
+        Value::Dyn(v) if v.is::<TypeValue>() => type_func(),
+        _ => return Value::dynamic(ty),
+    };
+    Value::dynamic(ty.with_constructor(constructor.into()))
 }
 
 /// Returns the string representation of a value.
@@ -56,7 +101,7 @@ pub fn repr(
 /// ## Example { #example }
 /// The code below produces the error `panicked with: "this is wrong"`.
 /// ```typ
-/// #panic("this is wrong")
+/// #panic("this is wrong", hint: "try doing it right")
 /// ```
 ///
 /// Display: Panic
@@ -66,7 +111,10 @@ pub fn panic(
     /// The values to panic with.
     #[variadic]
     values: Vec<Value>,
-) -> StrResult<Never> {
+    /// A hint that is shown together with the error.
+    #[named]
+    hint: Option<EcoString>,
+) -> HintedStrResult<Never> {
     let mut msg = EcoString::from("panicked");
     if !values.is_empty() {
         msg.push_str(" with: ");
@@ -77,7 +125,41 @@ pub fn panic(
             msg.push_str(&value.repr());
         }
     }
-    Err(msg)
+    fail(msg, hint)
+}
+
+/// Emits a warning without stopping compilation.
+///
+/// The warning is reported at the arguments of the call. If the same warning
+/// is emitted multiple times at the same place, for example in a loop, it is
+/// only reported once.
+///
+/// ## Example { #example }
+/// ```typ
+/// #let price(amount) = {
+///   if amount < 0 {
+///     warn("negative price", hint: "this is probably a typo")
+///   }
+///   [#amount €]
+/// }
+/// ```
+///
+/// Display: Warn
+/// Category: foundations
+#[func]
+pub fn warn(
+    /// The warning message.
+    message: EcoString,
+    /// A hint that is shown together with the warning.
+    #[named]
+    hint: Option<EcoString>,
+    /// The virtual machine.
+    vm: &mut Vm,
+    /// The callsite span.
+    span: Span,
+) -> NoneValue {
+    vm.vt.tracer.warn(warning!(span, "{}", message).with_hints(hint));
+    NoneValue
 }
 
 /// Ensures that a condition is fulfilled.
@@ -90,7 +172,7 @@ pub fn panic(
 ///
 /// ## Example { #example }
 /// ```typ
-/// #assert(1 < 2, message: "math broke")
+/// #assert(1 < 2, message: "math broke", hint: "check your universe")
 /// ```
 ///
 /// Display: Assert
@@ -107,12 +189,15 @@ pub fn assert(
     /// The error message when the assertion fails.
     #[named]
     message: Option<EcoString>,
-) -> StrResult<NoneValue> {
+    /// A hint that is shown together with the error when the assertion fails.
+    #[named]
+    hint: Option<EcoString>,
+) -> HintedStrResult<NoneValue> {
     if !condition {
         if let Some(message) = message {
-            bail!("assertion failed: {message}");
+            return fail(eco_format!("assertion failed: {message}"), hint);
         } else {
-            bail!("assertion failed");
+            return fail("assertion failed".into(), hint);
         }
     }
     Ok(NoneValue)
@@ -142,12 +227,21 @@ pub fn assert_eq(
     /// of the compared values.
     #[named]
     message: Option<EcoString>,
-) -> StrResult<NoneValue> {
+
+    /// A hint that is shown together with the error when the assertion fails.
+    #[named]
+    hint: Option<EcoString>,
+) -> HintedStrResult<NoneValue> {
     if left != right {
         if let Some(message) = message {
-            bail!("equality assertion failed: {message}");
+            return fail(eco_format!("equality assertion failed: {message}"), hint);
         } else {
-            bail!("equality assertion failed: value {left:?} was not equal to {right:?}");
+            return fail(
+                eco_format!(
+                    "equality assertion failed: value {left:?} was not equal to {right:?}"
+                ),
+                hint,
+            );
         }
     }
     Ok(NoneValue)
@@ -177,17 +271,31 @@ pub fn assert_ne(
     /// of the compared values.
     #[named]
     message: Option<EcoString>,
-) -> StrResult<NoneValue> {
+
+    /// A hint that is shown together with the error when the assertion fails.
+    #[named]
+    hint: Option<EcoString>,
+) -> HintedStrResult<NoneValue> {
     if left == right {
         if let Some(message) = message {
-            bail!("inequality assertion failed: {message}");
+            return fail(eco_format!("inequality assertion failed: {message}"), hint);
         } else {
-            bail!("inequality assertion failed: value {left:?} was equal to {right:?}");
+            return fail(
+                eco_format!(
+                    "inequality assertion failed: value {left:?} was equal to {right:?}"
+                ),
+                hint,
+            );
         }
     }
     Ok(NoneValue)
 }
 
+/// Fail with an error message and an optional user-provided hint.
+fn fail<T>(message: EcoString, hint: Option<EcoString>) -> HintedStrResult<T> {
+    Err(HintedString { message, hints: hint.into_iter().collect() })
+}
+
 /// Evaluates a string as Typst code.
 ///
 /// This function should only be used as a last resort.
@@ -211,5 +319,55 @@ pub fn eval(
     vm: &mut Vm,
 ) -> SourceResult<Value> {
     let Spanned { v: text, span } = source;
-    typst::eval::eval_string(vm.world(), &text, span)
+    typst::eval::eval_string(vm.world(), vm.vt.limits, &text, span)
+}
+
+/// Loads a WebAssembly plugin.
+///
+/// Plugins extend Typst with functionality written in any language that
+/// compiles to WebAssembly. Call the plugin's exported functions as methods on
+/// the loaded plugin. They take and return [bytes]($type/bytes), so
+/// arguments and results can be converted with [`str`]($func/str) or the
+/// [data loading]($category/data-loading) functions.
+///
+/// Plugins run in a sandbox: They cannot access files, the network, or the
+/// system clock, and each call is limited in both memory and execution time.
+/// Calling a function twice with the same arguments thus always yields the
+/// same result, which is cached.
+///
+/// ## Example { #example }
+/// ```example
+/// #let hello = plugin("hello.wasm")
+/// #str(hello.hello()) \
+/// #str(hello.concat(bytes("a, "), bytes("b")))
+/// ```
+///
+/// ## Protocol { #protocol }
+/// An exported function receives the lengths of its byte arguments as 32-bit
+/// integer parameters and returns a 32-bit integer status code. To
+/// communicate, the plugin imports two functions from the `typst_env`
+/// module:
+///
+/// - `wasm_minimal_protocol_write_args_to_buffer(ptr)` writes all arguments,
+///   one after another, to the plugin's memory starting at `ptr`.
+/// - `wasm_minimal_protocol_send_result_to_host(ptr, len)` sends `len` bytes
+///   starting at `ptr` back to Typst.
+///
+/// A status code of `0` means success and sends the output back as bytes. A
+/// status code of `1` means failure and turns the output into an error
+/// message. The plugin must export its memory as `memory`.
+///
+/// Display: Plugin
+/// Category: foundations
+#[func]
+pub fn plugin(
+    /// Path to a WebAssembly file.
+    path: Spanned<EcoString>,
+    /// The virtual machine.
+    vm: &mut Vm,
+) -> SourceResult<Plugin> {
+    let Spanned { v: path, span } = path;
+    let id = vm.location().join(&path).at(span)?;
+    let data = vm.world().file(id).at(span)?;
+    Plugin::new(data).at(span)
 }
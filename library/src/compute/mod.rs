@@ -4,6 +4,7 @@ pub mod calc;
 mod construct;
 mod data;
 mod foundations;
+pub mod random;
 
 pub use self::construct::*;
 pub use self::data::*;
@@ -16,16 +17,28 @@ pub(super) fn define(global: &mut Scope) {
     global.define("type", type_func());
     global.define("repr", repr_func());
     global.define("panic", panic_func());
+    global.define("warn", warn_func());
     global.define("assert", assert_func());
     global.define("eval", eval_func());
+    global.define("plugin", plugin_func());
     global.define("int", int_func());
     global.define("float", float_func());
     global.define("luma", luma_func());
     global.define("rgb", rgb_func());
     global.define("cmyk", cmyk_func());
+    global.define("oklab", oklab_func());
+    global.define("oklch", oklch_func());
+    global.define("gradient", gradient_func());
+    global.define("pattern", pattern_func());
+    global.define("stroke", stroke_func());
     global.define("datetime", datetime_func());
+    global.define("duration", duration_func());
+    global.define("version", version_func());
     global.define("symbol", symbol_func());
     global.define("str", str_func());
+    global.define("format-number", format_number_func());
+    global.define("bytes", bytes_func());
+    global.define("array", array_func());
     global.define("label", label_func());
     global.define("regex", regex_func());
     global.define("range", range_func());
@@ -34,6 +47,9 @@ pub(super) fn define(global: &mut Scope) {
     global.define("json", json_func());
     global.define("toml", toml_func());
     global.define("yaml", yaml_func());
+    global.define("cbor", cbor_func());
     global.define("xml", xml_func());
     global.define("calc", calc::module());
+    global.define("random", random::module());
+    global.define("color", color_module());
 }
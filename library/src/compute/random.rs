@@ -0,0 +1,174 @@
+//! Deterministic pseudo-random numbers.
+
+use typst::eval::{Module, Scope};
+
+use crate::prelude::*;
+
+/// A module with deterministic random functions.
+pub fn module() -> Module {
+    let mut scope = Scope::new();
+    scope.define("integer", integer_func());
+    scope.define("shuffle", shuffle_func());
+    scope.define("sample", sample_func());
+    Module::new("random").with_scope(scope)
+}
+
+/// Generates a random integer between `min` and `max`, both inclusive.
+///
+/// The result only depends on the seed and on the bounds, so compiling the
+/// same document twice always yields the same number.
+///
+/// ## Example { #example }
+/// ```example
+/// #for seed in range(5) [
+///   #random.integer(seed: seed, min: 1, max: 6)
+/// ]
+/// ```
+///
+/// Display: Random Integer
+/// Category: random
+#[func]
+pub fn integer(
+    /// The seed for the generator. Different seeds produce independent
+    /// results.
+    #[named]
+    seed: Option<i64>,
+    /// The smallest integer that may be produced.
+    #[named]
+    #[default(0)]
+    min: i64,
+    /// The largest integer that may be produced.
+    #[named]
+    #[default(i64::MAX)]
+    max: i64,
+    /// The callsite span.
+    span: Span,
+) -> SourceResult<i64> {
+    let mut rng = Rng::new(seed, span)?;
+    if min > max {
+        bail!(span, "minimum must not be greater than maximum");
+    }
+
+    let range = (max as i128 - min as i128 + 1) as u128;
+    Ok(match u64::try_from(range) {
+        Ok(range) => min.wrapping_add(rng.below(range) as i64),
+        Err(_) => rng.next() as i64,
+    })
+}
+
+/// Returns the items of an array in a random order.
+///
+/// The order only depends on the seed and on the number of items.
+///
+/// ## Example { #example }
+/// ```example
+/// #random.shuffle(seed: 3, range(10))
+/// ```
+///
+/// Display: Random Shuffle
+/// Category: random
+#[func]
+pub fn shuffle(
+    /// The array to shuffle.
+    array: Array,
+    /// The seed for the generator. Different seeds produce independent
+    /// results.
+    #[named]
+    seed: Option<i64>,
+    /// The callsite span.
+    span: Span,
+) -> SourceResult<Array> {
+    let mut rng = Rng::new(seed, span)?;
+    let len = array.len();
+    Ok(rng.pick(array.as_slice().to_vec(), len))
+}
+
+/// Picks a number of distinct items from an array at random.
+///
+/// Each item is picked at most once. With the same seed, sampling all items
+/// of an array results in the same order as shuffling it.
+///
+/// ## Example { #example }
+/// ```example
+/// #random.sample(seed: 7, ("Ana", "Ben", "Cleo", "Dan"), 2)
+/// ```
+///
+/// Display: Random Sample
+/// Category: random
+#[func]
+pub fn sample(
+    /// The array to pick items from.
+    array: Array,
+    /// The number of items to pick.
+    count: usize,
+    /// The seed for the generator. Different seeds produce independent
+    /// results.
+    #[named]
+    seed: Option<i64>,
+    /// The callsite span.
+    span: Span,
+) -> SourceResult<Array> {
+    let mut rng = Rng::new(seed, span)?;
+    if count > array.len() {
+        bail!(
+            span,
+            "cannot sample {count} items from an array with {} items",
+            array.len()
+        );
+    }
+
+    Ok(rng.pick(array.as_slice().to_vec(), count))
+}
+
+/// A SplitMix64 generator.
+///
+/// The algorithm is fixed so that documents produce the same output on every
+/// platform and with every version.
+struct Rng(u64);
+
+impl Rng {
+    /// Create a generator from the seed, which must be given.
+    fn new(seed: Option<i64>, span: Span) -> SourceResult<Self> {
+        let Some(seed) = seed else {
+            bail!(error!(span, "missing argument: seed").with_hints([
+                "random functions are deterministic and need a seed".into(),
+                "try passing one like `seed: 42`".into(),
+            ]));
+        };
+        Ok(Self(seed as u64))
+    }
+
+    /// Produce the next 64 random bits.
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Produce a uniformly distributed number in `0..bound`.
+    ///
+    /// Draws that would favour small numbers are rejected.
+    fn below(&mut self, bound: u64) -> u64 {
+        let threshold = bound.wrapping_neg() % bound;
+        loop {
+            let x = self.next();
+            if x >= threshold {
+                return x % bound;
+            }
+        }
+    }
+
+    /// Move `count` randomly picked items to the front with a partial
+    /// Fisher-Yates shuffle and return them.
+    fn pick(&mut self, mut items: Vec<Value>, count: usize) -> Array {
+        let len = items.len();
+        for i in 0..count.min(len.saturating_sub(1)) {
+            let j = i + self.below((len - i) as u64) as usize;
+            items.swap(i, j);
+        }
+        items.truncate(count);
+        items.into_iter().collect()
+    }
+}
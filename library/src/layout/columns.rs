@@ -4,10 +4,10 @@ use crate::text::TextElem;
 /// Separates a region into multiple equally sized columns.
 ///
 /// The `column` function allows to separate the interior of any container into
-/// multiple columns. It will not equalize the height of the columns, instead,
-/// the columns will take up the height of their container or the remaining
-/// height on the page. The columns function can break across pages if
-/// necessary.
+/// multiple columns. By default, it will not equalize the height of the
+/// columns, instead, the columns will take up the height of their container or
+/// the remaining height on the page. The columns function can break across
+/// pages if necessary.
 ///
 /// ## Example { #example }
 /// ```example
@@ -44,6 +44,24 @@ pub struct ColumnsElem {
     #[default(Ratio::new(0.04).into())]
     pub gutter: Rel<Length>,
 
+    /// Whether to balance the columns in the last region.
+    ///
+    /// If `{true}`, the content in the last region (typically, the last page)
+    /// is distributed so that all columns end at about the same height instead
+    /// of filling them one after another. The content is still broken by the
+    /// usual rules, so blocks that cannot break and paragraphs that would
+    /// leave orphans or widows can prevent a perfect balance.
+    ///
+    /// ```example
+    /// #rect(width: 180pt, inset: 8pt, columns(2, balance: true)[
+    ///   A special plight has befallen our document.
+    ///   Columns in text boxes reigned down unto the soil
+    ///   to waste a year's crop of rich layouts.
+    /// ])
+    /// ```
+    #[default(false)]
+    pub balance: bool,
+
     /// The content that should be layouted into the columns.
     #[required]
     pub body: Content,
@@ -70,28 +88,23 @@ impl Layout for ColumnsElem {
         let gutter = self.gutter(styles).relative_to(regions.base().x);
         let width = (regions.size.x - gutter * (columns - 1) as f64) / columns as f64;
 
-        let backlog: Vec<_> = std::iter::once(&regions.size.y)
-            .chain(regions.backlog)
-            .flat_map(|&height| std::iter::repeat(height).take(columns))
-            .skip(1)
-            .collect();
-
-        // Create the pod regions.
-        let pod = Regions {
-            size: Size::new(width, regions.size.y),
-            full: regions.full,
-            backlog: &backlog,
-            last: regions.last,
-            expand: Axes::new(true, regions.expand.y),
-            root: regions.root,
-        };
-
         // Layout the children.
-        let mut frames = body.layout(vt, styles, pod)?.into_iter();
+        let mut frames =
+            layout_columns(vt, &body, styles, regions, columns, width, None)?;
+        let total_regions = (frames.len() as f32 / columns as f32).ceil() as usize;
+
+        if self.balance(styles) && columns > 1 && total_regions > 0 {
+            if let Some(balanced) =
+                balance(vt, &body, styles, regions, columns, width, total_regions)?
+            {
+                frames = balanced;
+            }
+        }
+
+        let mut frames = frames.into_iter();
         let mut finished = vec![];
 
         let dir = TextElem::dir_in(styles);
-        let total_regions = (frames.len() as f32 / columns as f32).ceil() as usize;
 
         // Stitch together the columns for each region.
         for region in regions.iter().take(total_regions) {
@@ -127,6 +140,113 @@ impl Layout for ColumnsElem {
     }
 }
 
+/// The maximum number of layout passes used to balance columns.
+const MAX_BALANCE_ITERATIONS: usize = 8;
+
+/// Layout the body into columns, returning one frame per column.
+///
+/// If a limit is given, the columns in the region with the given index are at
+/// most as high as the given height.
+fn layout_columns(
+    vt: &mut Vt,
+    body: &Content,
+    styles: StyleChain,
+    regions: Regions,
+    columns: usize,
+    width: Abs,
+    limit: Option<(usize, Abs)>,
+) -> SourceResult<Vec<Frame>> {
+    let mut heights: Vec<_> = std::iter::once(regions.size.y)
+        .chain(regions.backlog.iter().copied())
+        .collect();
+
+    if let Some((index, height)) = limit {
+        while heights.len() <= index {
+            heights.push(regions.last.unwrap_or(regions.size.y));
+        }
+        heights[index].set_min(height);
+    }
+
+    let backlog: Vec<_> = heights
+        .iter()
+        .flat_map(|&height| std::iter::repeat(height).take(columns))
+        .skip(1)
+        .collect();
+
+    // Create the pod regions.
+    let pod = Regions {
+        size: Size::new(width, heights[0]),
+        full: regions.full,
+        backlog: &backlog,
+        last: regions.last,
+        expand: Axes::new(true, regions.expand.y),
+        root: regions.root,
+    };
+
+    Ok(body.layout(vt, styles, pod)?.into_frames())
+}
+
+/// Find the smallest height for the columns in the last region at which the
+/// content still fits into that region.
+///
+/// Returns the balanced column frames or `None` if balancing is not possible.
+fn balance(
+    vt: &mut Vt,
+    body: &Content,
+    styles: StyleChain,
+    regions: Regions,
+    columns: usize,
+    width: Abs,
+    total_regions: usize,
+) -> SourceResult<Option<Vec<Frame>>> {
+    let index = total_regions - 1;
+    let Some(available) = regions.iter().nth(index).map(|size| size.y) else {
+        return Ok(None);
+    };
+
+    // Measure the natural height of the content in the last region.
+    let mut natural = regions;
+    natural.expand.y = false;
+    let frames = layout_columns(vt, body, styles, natural, columns, width, None)?;
+    let total: Abs = frames.iter().skip(index * columns).map(Frame::height).sum();
+    if total <= Abs::zero() {
+        return Ok(None);
+    }
+
+    // Perfectly balanced columns can't be lower than this and the content is
+    // known to fit into the upper bound. As breaking the content into columns
+    // works at the granularity of lines and blocks, search for the smallest
+    // height that works, but stop after a few passes.
+    let mut lo = total / columns as f64;
+    let mut hi = available.min(total);
+    let mut best = None;
+    for _ in 0..MAX_BALANCE_ITERATIONS {
+        if hi - lo < Abs::pt(1.0) {
+            break;
+        }
+
+        let mid = (lo + hi) / 2.0;
+        let limit = Some((index, mid));
+        let frames = layout_columns(vt, body, styles, regions, columns, width, limit)?;
+        if frames.len() <= total_regions * columns {
+            hi = mid;
+            best = Some(frames);
+        } else {
+            lo = mid;
+        }
+    }
+
+    if best.is_none() && hi < available {
+        let limit = Some((index, hi));
+        let frames = layout_columns(vt, body, styles, regions, columns, width, limit)?;
+        if frames.len() <= total_regions * columns {
+            best = Some(frames);
+        }
+    }
+
+    Ok(best)
+}
+
 /// Forces a column break.
 ///
 /// The function will behave like a [page break]($func/pagebreak) when used in a
@@ -157,6 +277,12 @@ impl Layout for ColumnsElem {
 pub struct ColbreakElem {
     /// If `{true}`, the column break is skipped if the current column is
     /// already empty.
+    ///
+    /// ```example
+    /// #set page(height: 60pt, columns: 2)
+    /// #colbreak(weak: true)
+    /// Still in the first column.
+    /// ```
     #[default(false)]
     pub weak: bool,
 }
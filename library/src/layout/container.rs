@@ -92,6 +92,13 @@ pub struct BoxElem {
     pub outset: Sides<Option<Rel<Length>>>,
 
     /// Whether to clip the content inside the box.
+    ///
+    /// The content is clipped to the box's rectangle, with its corners rounded
+    /// according to the `radius`.
+    ///
+    /// ```example
+    /// #box(clip: true, radius: 8pt, image("tiger.jpg", width: 80pt))
+    /// ```
     #[default(false)]
     pub clip: bool,
 
@@ -146,7 +153,8 @@ impl Layout for BoxElem {
 
         // Clip the contents
         if self.clip(styles) {
-            frame.clip();
+            let path = clip_path(frame.size(), self.radius(styles));
+            frame.clip(path);
         }
 
         // Prepare fill and stroke.
@@ -316,7 +324,9 @@ pub struct BlockElem {
     #[default(VElem::block_spacing(Em::new(1.2).into()))]
     pub below: VElem,
 
-    /// Whether to clip the content inside the block.
+    /// Whether to clip the content inside the block. Like for the
+    /// [box]($func/box.clip), the corners of the clipped area are rounded
+    /// according to the `radius`.
     #[default(false)]
     pub clip: bool,
 
@@ -411,8 +421,10 @@ impl Layout for BlockElem {
 
         // Clip the contents
         if self.clip(styles) {
+            let radius = self.radius(styles);
             for frame in frames.iter_mut() {
-                frame.clip();
+                let path = clip_path(frame.size(), radius);
+                frame.clip(path);
             }
         }
 
@@ -449,6 +461,13 @@ impl Layout for BlockElem {
     }
 }
 
+/// The path a box or block with the given size and corner radii clips its
+/// contents to.
+fn clip_path(size: Size, radius: Corners<Rel<Abs>>) -> Path {
+    let radius = radius.map(|side| side.relative_to(size.x.min(size.y) / 2.0));
+    rounded_path(size, radius)
+}
+
 /// Defines how to size a grid cell along an axis.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum Sizing {
@@ -5,7 +5,7 @@ use crate::meta::{Numbering, NumberingPattern};
 use crate::prelude::*;
 use crate::text::TextElem;
 
-use super::GridLayouter;
+use super::{Cell, GridLayouter};
 
 /// A numbered list.
 ///
@@ -113,12 +113,23 @@ pub struct EnumElem {
 
     /// Which number to start the enumeration with.
     ///
+    /// This can also continue the numbering of an enumeration that was
+    /// interrupted by other content.
+    ///
     /// ```example
     /// #enum(
     ///   start: 3,
     ///   [Skipping],
     ///   [Ahead],
     /// )
+    ///
+    /// + First
+    /// + Second
+    ///
+    /// An interruption.
+    ///
+    /// #set enum(start: 3)
+    /// + Third
     /// ```
     #[default(1)]
     pub start: usize,
@@ -245,8 +256,9 @@ impl Layout for EnumElem {
 
             // Disable overhang as a workaround to end-aligned dots glitching
             // and decreasing spacing between numbers and items.
-            let resolved =
-                resolved.aligned(number_align).styled(TextElem::set_overhang(false));
+            let resolved = resolved
+                .aligned(number_align)
+                .styled(TextElem::set_overhang(false.into()));
 
             cells.push(Content::empty());
             cells.push(resolved);
@@ -255,6 +267,7 @@ impl Layout for EnumElem {
             number = number.saturating_add(1);
         }
 
+        let cells = Cell::row_major(cells, 4);
         let layouter = GridLayouter::new(
             Axes::with_x(&[
                 Sizing::Rel(indent.into()),
@@ -1,7 +1,8 @@
 use std::mem;
 
 use super::{
-    AlignElem, BlockElem, ColbreakElem, ColumnsElem, ParElem, PlaceElem, Spacing, VElem,
+    AlignElem, BlockElem, ColbreakElem, ColumnsElem, FlushElem, ParElem, PlaceElem,
+    Spacing, VElem,
 };
 use crate::meta::{FootnoteElem, FootnoteEntry};
 use crate::prelude::*;
@@ -68,11 +69,21 @@ impl Layout for FlowElem {
                 });
             } else if child.can::<dyn Layout>() {
                 layouter.layout_multiple(vt, child, styles)?;
-            } else if child.is::<ColbreakElem>() {
+            } else if let Some(colbreak) = child.to::<ColbreakElem>() {
+                // A weak column break is skipped if the column is still empty.
+                let empty = layouter.items.iter().all(|item| {
+                    matches!(item, FlowItem::Absolute(..) | FlowItem::Fractional(_))
+                });
+                if colbreak.weak(styles) && empty {
+                    continue;
+                }
+
                 if !layouter.regions.backlog.is_empty() || layouter.regions.last.is_some()
                 {
                     layouter.finish_region()?;
                 }
+            } else if child.is::<FlushElem>() {
+                layouter.flush()?;
             } else {
                 bail!(child.span(), "unexpected flow child");
             }
@@ -103,6 +114,9 @@ struct FlowLayouter<'a> {
     has_footnotes: bool,
     /// Footnote configuration.
     footnote_config: FootnoteConfig,
+    /// Floating frames that didn't fit into their region yet, with their
+    /// alignment and clearance.
+    pending_floats: Vec<(Frame, Smart<Align>, Abs)>,
     /// Finished frames for previous regions.
     finished: Vec<Frame>,
 }
@@ -127,6 +141,9 @@ enum FlowItem {
     Frame { frame: Frame, aligns: Axes<Align>, sticky: bool, movable: bool },
     /// An absolutely placed frame.
     Placed(Frame),
+    /// A floating frame, placed at the top or bottom of the region and kept
+    /// apart from the other content by the given clearance.
+    Float { frame: Frame, align: Align, clearance: Abs },
     /// A footnote frame (can also be the separator).
     Footnote(Frame),
 }
@@ -138,6 +155,7 @@ impl FlowItem {
             Self::Absolute(v, _) => *v,
             Self::Fractional(_) | Self::Placed(_) => Abs::zero(),
             Self::Frame { frame, .. } | Self::Footnote(frame) => frame.height(),
+            Self::Float { frame, clearance, .. } => frame.height() + *clearance,
         }
     }
 }
@@ -165,6 +183,7 @@ impl<'a> FlowLayouter<'a> {
                 clearance: FootnoteEntry::clearance_in(styles),
                 gap: FootnoteEntry::gap_in(styles),
             },
+            pending_floats: vec![],
             finished: vec![],
         }
     }
@@ -198,24 +217,19 @@ impl<'a> FlowLayouter<'a> {
         styles: StyleChain,
     ) -> SourceResult<()> {
         let aligns = AlignElem::alignment_in(styles).resolve(styles);
-        let leading = ParElem::leading_in(styles);
         let consecutive = self.last_was_par;
-        let lines = par
-            .layout(vt, styles, consecutive, self.regions.base(), self.regions.expand.x)?
-            .into_frames();
-
-        let mut sticky = self.items.len();
-        for (i, item) in self.items.iter().enumerate().rev() {
-            match *item {
-                FlowItem::Absolute(_, _) => {}
-                FlowItem::Frame { sticky: true, .. } => sticky = i,
-                _ => break,
-            }
-        }
+        let (fragment, spacing) = par.layout(
+            vt,
+            styles,
+            consecutive,
+            self.regions.base(),
+            self.regions.expand.x,
+        )?;
+        let lines = fragment.into_frames();
 
         if let Some(first) = lines.first() {
             if !self.regions.size.y.fits(first.height()) && !self.regions.in_last() {
-                let carry: Vec<_> = self.items.drain(sticky..).collect();
+                let carry = self.take_sticky();
                 self.finish_region()?;
                 for item in carry {
                     self.layout_item(vt, item)?;
@@ -225,7 +239,7 @@ impl<'a> FlowLayouter<'a> {
 
         for (i, frame) in lines.into_iter().enumerate() {
             if i > 0 {
-                self.layout_item(vt, FlowItem::Absolute(leading, true))?;
+                self.layout_item(vt, FlowItem::Absolute(spacing[i - 1], true))?;
             }
 
             self.layout_item(
@@ -238,6 +252,29 @@ impl<'a> FlowLayouter<'a> {
         Ok(())
     }
 
+    /// Remove the sticky items at the end of the current region, so that they
+    /// can move to the next region together with the item after them.
+    fn take_sticky(&mut self) -> Vec<FlowItem> {
+        let mut sticky = self.items.len();
+        for (i, item) in self.items.iter().enumerate().rev() {
+            match *item {
+                FlowItem::Absolute(_, _) => {}
+                FlowItem::Frame { sticky: true, .. } => sticky = i,
+                _ => break,
+            }
+        }
+
+        // Moving everything would only leave an empty region behind.
+        if !self.items[..sticky]
+            .iter()
+            .any(|item| matches!(item, FlowItem::Frame { .. }))
+        {
+            return vec![];
+        }
+
+        self.items.drain(sticky..).collect()
+    }
+
     /// Layout into a single region.
     #[tracing::instrument(name = "FlowLayouter::layout_single", skip_all)]
     fn layout_single(
@@ -265,7 +302,15 @@ impl<'a> FlowLayouter<'a> {
         // Placed elements that are out of flow produce placed items which
         // aren't aligned later.
         if let Some(placed) = block.to::<PlaceElem>() {
-            if placed.out_of_flow(styles) {
+            if placed.float(styles) {
+                let frame = block.layout(vt, styles, self.regions)?.into_frame();
+                let align = match placed.alignment(styles).y {
+                    Some(GenAlign::Specific(align)) => Smart::Custom(align),
+                    _ => Smart::Auto,
+                };
+                self.layout_float(frame, align, placed.clearance(styles));
+                return Ok(());
+            } else if placed.out_of_flow(styles) {
                 let frame = block.layout(vt, styles, self.regions)?.into_frame();
                 self.layout_item(vt, FlowItem::Placed(frame))?;
                 return Ok(());
@@ -292,8 +337,28 @@ impl<'a> FlowLayouter<'a> {
             self.regions.root = true;
         }
 
-        // Layout the block itself.
         let sticky = BlockElem::sticky_in(styles);
+
+        // If none of the block's content fits into this region, for example
+        // because it starts with an unbreakable block, move the whole block to
+        // the next region instead of leaving an empty piece of it behind. The
+        // sticky items before it, like headings, go along. This is only
+        // measured, so that the block is laid out just once for real.
+        if !self.regions.in_last()
+            && self.items.iter().any(|item| matches!(item, FlowItem::Frame { .. }))
+        {
+            let measured = block.measure(vt, styles, self.regions)?;
+            if measured.len() > 1 && measured.iter().next().map_or(false, Frame::is_empty)
+            {
+                let carry = self.take_sticky();
+                self.finish_region()?;
+                for item in carry {
+                    self.layout_item(vt, item)?;
+                }
+            }
+        }
+
+        // Layout the block itself.
         let fragment = block.layout(vt, styles, self.regions)?;
         let mut notes = Vec::new();
 
@@ -341,10 +406,16 @@ impl<'a> FlowLayouter<'a> {
                 self.regions.size.y -= v
             }
             FlowItem::Fractional(_) => {}
-            FlowItem::Frame { ref frame, movable, .. } => {
+            FlowItem::Frame { ref frame, movable, sticky } => {
                 let size = frame.size();
                 if !self.regions.size.y.fits(size.y) && !self.regions.in_last() {
+                    // Keep sticky items, like headings, with the unbreakable
+                    // block after them.
+                    let carry = if sticky { vec![] } else { self.take_sticky() };
                     self.finish_region()?;
+                    for item in carry {
+                        self.layout_item(vt, item)?;
+                    }
                 }
 
                 self.regions.size.y -= size.y;
@@ -363,6 +434,7 @@ impl<'a> FlowLayouter<'a> {
                 }
             }
             FlowItem::Placed(_) => {}
+            FlowItem::Float { .. } => {}
             FlowItem::Footnote(_) => {}
         }
 
@@ -370,6 +442,42 @@ impl<'a> FlowLayouter<'a> {
         Ok(())
     }
 
+    /// Layout a floating frame at the top or bottom of the region, or defer it
+    /// to the next region if it doesn't fit.
+    fn layout_float(&mut self, frame: Frame, align: Smart<Align>, clearance: Abs) {
+        // Floats keep their order, so once one is pending, all later ones are
+        // pending, too.
+        let height = frame.height() + clearance;
+        if !self.pending_floats.is_empty()
+            || (!self.regions.size.y.fits(height) && !self.regions.in_last())
+        {
+            self.pending_floats.push((frame, align, clearance));
+            return;
+        }
+
+        // An automatically placed float goes to the top if it was written in
+        // the upper half of the region and to the bottom otherwise.
+        let align = align.unwrap_or_else(|| {
+            let ratio = (self.regions.size.y - height / 2.0) / self.regions.full;
+            if ratio >= 0.5 {
+                Align::Top
+            } else {
+                Align::Bottom
+            }
+        });
+
+        self.regions.size.y -= height;
+        self.items.push(FlowItem::Float { frame, align, clearance });
+    }
+
+    /// Finish regions until all pending floats are placed.
+    fn flush(&mut self) -> SourceResult<()> {
+        while !self.pending_floats.is_empty() {
+            self.finish_region()?;
+        }
+        Ok(())
+    }
+
     /// Finish the frame for one region.
     fn finish_region(&mut self) -> SourceResult<()> {
         // Trim weak spacing.
@@ -385,6 +493,8 @@ impl<'a> FlowLayouter<'a> {
         let mut fr = Fr::zero();
         let mut used = Size::zero();
         let mut footnote_height = Abs::zero();
+        let mut float_top_height = Abs::zero();
+        let mut float_bottom_height = Abs::zero();
         let mut first_footnote = true;
         for item in &self.items {
             match item {
@@ -396,6 +506,15 @@ impl<'a> FlowLayouter<'a> {
                     used.x.set_max(size.x);
                 }
                 FlowItem::Placed(_) => {}
+                FlowItem::Float { frame, align, clearance } => {
+                    let height = frame.height() + *clearance;
+                    match align {
+                        Align::Bottom => float_bottom_height += height,
+                        _ => float_top_height += height,
+                    }
+                    used.y += height;
+                    used.x.set_max(frame.width());
+                }
                 FlowItem::Footnote(frame) => {
                     let size = frame.size();
                     footnote_height += size.y;
@@ -418,9 +537,11 @@ impl<'a> FlowLayouter<'a> {
         }
 
         let mut output = Frame::new(size);
-        let mut offset = Abs::zero();
+        let mut offset = float_top_height;
         let mut ruler = Align::Top;
         let mut footnote_offset = size.y - footnote_height;
+        let mut float_top_offset = Abs::zero();
+        let mut float_bottom_offset = footnote_offset - float_bottom_height;
 
         // Place all frames.
         for item in self.items.drain(..) {
@@ -448,6 +569,22 @@ impl<'a> FlowLayouter<'a> {
                 FlowItem::Placed(frame) => {
                     output.push_frame(Point::zero(), frame);
                 }
+                FlowItem::Float { frame, align, clearance } => {
+                    let height = frame.height();
+                    let y = match align {
+                        Align::Bottom => {
+                            let y = float_bottom_offset + clearance;
+                            float_bottom_offset += height + clearance;
+                            y
+                        }
+                        _ => {
+                            let y = float_top_offset;
+                            float_top_offset += height + clearance;
+                            y
+                        }
+                    };
+                    output.push_frame(Point::with_y(y), frame);
+                }
             }
         }
 
@@ -456,6 +593,12 @@ impl<'a> FlowLayouter<'a> {
         self.regions.next();
         self.initial = self.regions.size;
         self.has_footnotes = false;
+
+        // Try to place floats that didn't fit into the previous region.
+        for (frame, align, clearance) in mem::take(&mut self.pending_floats) {
+            self.layout_float(frame, align, clearance);
+        }
+
         Ok(())
     }
 
@@ -468,6 +611,12 @@ impl<'a> FlowLayouter<'a> {
         }
 
         self.finish_region()?;
+
+        // Finish the regions with floats that didn't fit before.
+        while !self.items.is_empty() {
+            self.finish_region()?;
+        }
+
         Ok(Fragment::frames(self.finished))
     }
 }
@@ -1,3 +1,6 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
 use crate::prelude::*;
 use crate::text::TextElem;
 
@@ -59,9 +62,16 @@ use super::Sizing;
 /// )
 /// ```
 ///
+/// Cells can span multiple columns or rows. To create such a cell, use the
+/// [`grid.cell`]($func/grid.cell) function.
+///
 /// Display: Grid
 /// Category: layout
 #[element(Layout)]
+#[scope(
+    scope.define("cell", GridCell::func());
+    scope
+)]
 pub struct GridElem {
     /// The column sizes.
     ///
@@ -96,8 +106,11 @@ pub struct GridElem {
 
     /// The contents of the grid cells.
     ///
-    /// The cells are populated in row-major order.
+    /// The cells are populated in row-major order. A cell that spans multiple
+    /// columns or rows is placed at the first position where all the slots it
+    /// covers are still free.
     #[variadic]
+    #[parse(spanned_children(args)?)]
     pub children: Vec<Content>,
 }
 
@@ -109,10 +122,26 @@ impl Layout for GridElem {
         styles: StyleChain,
         regions: Regions,
     ) -> SourceResult<Fragment> {
+        let columns = self.columns(styles).0;
+        let rows = self.rows(styles).0;
+        let children = self
+            .children()
+            .into_iter()
+            .map(|child| {
+                let span = child.span();
+                match child.to::<GridCell>() {
+                    Some(cell) => {
+                        (cell.body(), cell.colspan(styles), cell.rowspan(styles), span)
+                    }
+                    None => (child, NonZeroUsize::ONE, NonZeroUsize::ONE, span),
+                }
+            })
+            .collect();
+
         // Prepare grid layout by unifying content and gutter tracks.
-        let cells = self.children();
+        let cells = place_cells(children, columns.len().max(1))?;
         let layouter = GridLayouter::new(
-            Axes::new(&self.columns(styles).0, &self.rows(styles).0),
+            Axes::new(&columns, &rows),
             Axes::new(&self.column_gutter(styles).0, &self.row_gutter(styles).0),
             &cells,
             regions,
@@ -124,6 +153,160 @@ impl Layout for GridElem {
     }
 }
 
+/// A cell in a grid that spans multiple columns or rows.
+///
+/// The cell takes up the space of all the tracks it covers, including the
+/// gutters between them. Automatically sized columns and rows grow to fit the
+/// cell if the tracks it spans are too small for it. Rows that are spanned by
+/// a cell are kept together in the same region.
+///
+/// ```example
+/// #grid(
+///   columns: 3,
+///   gutter: 3pt,
+///   grid.cell(colspan: 2, rect(width: 100%)[Wide]),
+///   grid.cell(rowspan: 2, rect[Tall]),
+///   rect[A],
+///   rect[B],
+/// )
+/// ```
+///
+/// Display: Grid Cell
+/// Category: layout
+#[element(Show)]
+pub struct GridCell {
+    /// The cell's body.
+    #[required]
+    pub body: Content,
+
+    /// The number of columns the cell spans.
+    #[default(NonZeroUsize::ONE)]
+    pub colspan: NonZeroUsize,
+
+    /// The number of rows the cell spans.
+    #[default(NonZeroUsize::ONE)]
+    pub rowspan: NonZeroUsize,
+}
+
+impl Show for GridCell {
+    fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        Ok(self.body())
+    }
+}
+
+/// Parse the children of a grid, attaching the span of their argument to them
+/// so that errors about a cell can point to it.
+pub(super) fn spanned_children(args: &mut Args) -> SourceResult<Vec<Content>> {
+    Ok(args
+        .all::<Spanned<Content>>()?
+        .into_iter()
+        .map(|child| child.v.spanned(child.span))
+        .collect())
+}
+
+/// A grid cell whose position is resolved.
+#[derive(Debug, Clone)]
+pub struct Cell {
+    /// The cell's body.
+    pub body: Content,
+    /// The column in which the cell starts, not counting gutter tracks.
+    pub x: usize,
+    /// The row in which the cell starts, not counting gutter tracks.
+    pub y: usize,
+    /// The number of columns the cell spans.
+    pub colspan: usize,
+    /// The number of rows the cell spans.
+    pub rowspan: usize,
+}
+
+impl Cell {
+    /// Place children that each take up a single slot into a grid with the
+    /// given number of columns, in row-major order.
+    pub fn row_major(children: Vec<Content>, columns: usize) -> Vec<Self> {
+        children
+            .into_iter()
+            .enumerate()
+            .map(|(i, body)| Self {
+                body,
+                x: i % columns,
+                y: i / columns,
+                colspan: 1,
+                rowspan: 1,
+            })
+            .collect()
+    }
+}
+
+/// The maximum number of rows a single cell may span.
+const MAX_ROWSPAN: usize = 1 << 12;
+
+/// Resolve the positions of cells, given their bodies, spans, and source
+/// spans.
+///
+/// The cells are placed in row-major order into a grid with the given number
+/// of columns. Each cell takes the first position after the previous one at
+/// which all the slots it covers are free.
+///
+/// Cells may span rows past the grid's explicitly sized ones, which are then
+/// created as needed. Their rowspan is limited, so that a single cell can't
+/// make the grid arbitrarily large.
+pub fn place_cells(
+    children: Vec<(Content, NonZeroUsize, NonZeroUsize, Span)>,
+    columns: usize,
+) -> SourceResult<Vec<Cell>> {
+    let mut cells = vec![];
+    let mut taken: Vec<bool> = vec![];
+    let mut cursor = 0;
+
+    for (body, colspan, rowspan, span) in children {
+        let (colspan, rowspan) = (colspan.get(), rowspan.get());
+        if colspan > columns {
+            bail!(
+                span,
+                "cell's colspan ({colspan}) exceeds the number of columns ({columns})"
+            );
+        }
+
+        if rowspan > MAX_ROWSPAN {
+            bail!(
+                span,
+                "cell's rowspan ({rowspan}) exceeds the maximum of {MAX_ROWSPAN} rows"
+            );
+        }
+
+        let free =
+            |x: usize, y: usize| !taken.get(y * columns + x).copied().unwrap_or(false);
+
+        // Find the first position at which the cell fits.
+        loop {
+            let (x, y) = (cursor % columns, cursor / columns);
+            if x + colspan <= columns
+                && (y..y + rowspan).all(|y| (x..x + colspan).all(|x| free(x, y)))
+            {
+                break;
+            }
+            cursor += 1;
+        }
+
+        let (x, y) = (cursor % columns, cursor / columns);
+        let needed = (y + rowspan) * columns;
+        if taken.len() < needed {
+            taken.resize(needed, false);
+        }
+
+        for ty in y..y + rowspan {
+            for tx in x..x + colspan {
+                taken[ty * columns + tx] = true;
+            }
+        }
+
+        cells.push(Cell { body, x, y, colspan, rowspan });
+        cursor += colspan;
+    }
+
+    Ok(cells)
+}
+
 /// Track sizing definitions.
 #[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
 pub struct TrackSizings(pub Vec<Sizing>);
@@ -139,7 +322,12 @@ cast! {
 /// Performs grid layout.
 pub struct GridLayouter<'a> {
     /// The grid cells.
-    cells: &'a [Content],
+    cells: &'a [Cell],
+    /// The column and row tracks covered by each cell, in display order.
+    spans: Vec<Axes<Range<usize>>>,
+    /// For each pair of column and row track, the index of the cell covering
+    /// it, in row-major order.
+    slots: Vec<Option<usize>>,
     /// Whether this is an RTL grid.
     is_rtl: bool,
     /// Whether this grid has gutters.
@@ -164,6 +352,14 @@ pub struct GridLayouter<'a> {
     initial: Size,
     /// Frames for finished regions.
     finished: Vec<Frame>,
+    /// The rows that are neither header nor footer rows.
+    body: Range<usize>,
+    /// The header rows, repeated at the top of each region.
+    header: Vec<(Frame, usize)>,
+    /// The footer rows, repeated at the bottom of each region.
+    footer: Vec<(Frame, usize)>,
+    /// The span of the grid, for errors about repeated rows.
+    span: Span,
 }
 
 /// The resulting sizes of columns and rows in a grid.
@@ -175,6 +371,148 @@ pub struct GridLayout {
     pub cols: Vec<Abs>,
     /// The heights of the resulting rows segments, by region.
     pub rows: Vec<Vec<RowPiece>>,
+    /// The rows that are neither header nor footer rows.
+    pub body: Range<usize>,
+    /// The column and row tracks covered by each cell.
+    pub spans: Vec<Axes<Range<usize>>>,
+    /// For each pair of column and row track, the index of the cell covering
+    /// it, in row-major order.
+    pub slots: Vec<Option<usize>>,
+}
+
+impl GridLayout {
+    /// Draw the lines between and around the cells.
+    ///
+    /// The `stroke` function is called with the column and row of each cell's
+    /// first slot and returns the strokes for the cell's sides. Empty slots
+    /// are treated like cells of their own. Where two cells share an edge,
+    /// only one line is drawn, with the stroke of the cell below or to the
+    /// right taking precedence. Neighbouring edges with the same stroke are
+    /// drawn as a single line, so that dash patterns continue across cells.
+    pub fn stroke_lines(
+        &mut self,
+        span: Span,
+        mut stroke: impl FnMut(usize, usize) -> SourceResult<Sides<Option<Stroke>>>,
+    ) -> SourceResult<()> {
+        let cols = self.cols.len();
+        let mut cache = HashMap::new();
+        for (frame, rows) in self.fragment.iter_mut().zip(&self.rows) {
+            if cols == 0 || rows.is_empty() {
+                continue;
+            }
+
+            // The strokes of the cell covering each slot, indexed by column and
+            // then by the row's index in this region.
+            let covering = |x: usize, y: usize| self.slots[y * cols + x];
+            let mut sides = vec![];
+            for x in 0..cols {
+                let mut column = vec![];
+                for row in rows {
+                    let origin = match covering(x, row.y) {
+                        Some(i) => (self.spans[i].x.start, self.spans[i].y.start),
+                        None => (x, row.y),
+                    };
+                    let cell = match cache.get(&origin) {
+                        Some(cell) => cell.clone(),
+                        None => {
+                            let cell = stroke(origin.0, origin.1)?;
+                            cache.insert(origin, cell.clone());
+                            cell
+                        }
+                    };
+                    column.push(cell);
+                }
+                sides.push(column);
+            }
+
+            // Whether a line between two slots would cross a cell spanning
+            // both of them.
+            let merged = |a: (usize, usize), b: (usize, usize)| {
+                let cell = covering(a.0, a.1);
+                cell.is_some() && cell == covering(b.0, b.1)
+            };
+
+            // Render horizontal lines.
+            let xs: Vec<_> = points(self.cols.iter().copied()).collect();
+            let ys: Vec<_> = points(rows.iter().map(|piece| piece.height)).collect();
+            for (k, &offset) in ys.iter().enumerate() {
+                let inner = k > 0 && k < rows.len();
+                let segments = (0..cols).map(|x| {
+                    if inner && merged((x, rows[k - 1].y), (x, rows[k].y)) {
+                        return None;
+                    }
+                    let before =
+                        k.checked_sub(1).and_then(|i| sides[x][i].bottom.as_ref());
+                    let after = sides[x].get(k).and_then(|cell| cell.top.as_ref());
+                    after.or(before)
+                });
+
+                for (start, end, stroke) in runs(segments) {
+                    let half = stroke.thickness / 2.0;
+                    let target = Point::with_x(xs[end] - xs[start] + stroke.thickness);
+                    let hline = Geometry::Line(target).stroked(stroke.clone());
+                    frame.prepend(
+                        Point::new(xs[start] - half, offset),
+                        FrameItem::Shape(hline, span),
+                    );
+                }
+            }
+
+            // Render vertical lines.
+            for (k, &offset) in xs.iter().enumerate() {
+                let inner = k > 0 && k < cols;
+                let segments = (0..rows.len()).map(|i| {
+                    if inner && merged((k - 1, rows[i].y), (k, rows[i].y)) {
+                        return None;
+                    }
+                    let before =
+                        k.checked_sub(1).and_then(|x| sides[x][i].right.as_ref());
+                    let after = sides.get(k).and_then(|column| column[i].left.as_ref());
+                    after.or(before)
+                });
+
+                for (start, end, stroke) in runs(segments) {
+                    let half = stroke.thickness / 2.0;
+                    let target = Point::with_y(ys[end] - ys[start] + stroke.thickness);
+                    let vline = Geometry::Line(target).stroked(stroke.clone());
+                    frame.prepend(
+                        Point::new(offset, ys[start] - half),
+                        FrameItem::Shape(vline, span),
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Turn an iterator of extents into an iterator of offsets before, in between,
+/// and after the extents, e.g. [10mm, 5mm] -> [0mm, 10mm, 15mm].
+fn points(extents: impl IntoIterator<Item = Abs>) -> impl Iterator<Item = Abs> {
+    let mut offset = Abs::zero();
+    std::iter::once(Abs::zero())
+        .chain(extents.into_iter())
+        .map(move |extent| {
+            offset += extent;
+            offset
+        })
+}
+
+/// Group the maximal runs of consecutive segments with the same stroke,
+/// returned as their start, end, and stroke.
+fn runs<'a>(
+    segments: impl IntoIterator<Item = Option<&'a Stroke>>,
+) -> Vec<(usize, usize, &'a Stroke)> {
+    let mut runs: Vec<(usize, usize, &Stroke)> = vec![];
+    for (i, segment) in segments.into_iter().enumerate() {
+        let Some(stroke) = segment else { continue };
+        match runs.last_mut() {
+            Some((_, end, prev)) if *end == i && *prev == stroke => *end += 1,
+            _ => runs.push((i, i + 1, stroke)),
+        }
+    }
+    runs
 }
 
 /// Details about a resulting row piece.
@@ -202,7 +540,7 @@ impl<'a> GridLayouter<'a> {
     pub fn new(
         tracks: Axes<&[Sizing]>,
         gutter: Axes<&[Sizing]>,
-        cells: &'a [Content],
+        cells: &'a [Cell],
         regions: Regions<'a>,
         styles: StyleChain<'a>,
     ) -> Self {
@@ -214,12 +552,10 @@ impl<'a> GridLayouter<'a> {
 
         // Number of content rows: At least as many as given, but also at least
         // as many as needed to place each item.
-        let r = {
-            let len = cells.len();
-            let given = tracks.y.len();
-            let needed = len / c + (len % c).clamp(0, 1);
-            given.max(needed)
-        };
+        let r = cells
+            .iter()
+            .map(|cell| cell.y + cell.rowspan)
+            .fold(tracks.y.len(), usize::max);
 
         let has_gutter = gutter.any(|tracks| !tracks.is_empty());
         let auto = Sizing::Auto;
@@ -256,13 +592,43 @@ impl<'a> GridLayouter<'a> {
             cols.reverse();
         }
 
+        // Determine the tracks covered by each cell. With gutter, even tracks
+        // are content and odd ones are gutter.
+        let step = 1 + has_gutter as usize;
+        let spans: Vec<_> = cells
+            .iter()
+            .map(|cell| {
+                let start = cell.x * step;
+                let end = (cell.x + cell.colspan - 1) * step + 1;
+                let x = if is_rtl {
+                    cols.len() - end..cols.len() - start
+                } else {
+                    start..end
+                };
+                let y = cell.y * step..(cell.y + cell.rowspan - 1) * step + 1;
+                Axes::new(x, y)
+            })
+            .collect();
+
+        let mut slots = vec![None; cols.len() * rows.len()];
+        for (i, span) in spans.iter().enumerate() {
+            for y in span.y.clone() {
+                for x in span.x.clone() {
+                    slots[y * cols.len() + x] = Some(i);
+                }
+            }
+        }
+
         // We use these regions for auto row measurement. Since at that moment,
         // columns are already sized, we can enable horizontal expansion.
         let mut regions = regions;
         regions.expand = Axes::new(true, false);
 
+        let body = 0..rows.len();
         Self {
             cells,
+            spans,
+            slots,
             is_rtl,
             has_gutter,
             rows,
@@ -275,33 +641,99 @@ impl<'a> GridLayouter<'a> {
             lrows: vec![],
             initial: regions.size,
             finished: vec![],
+            body,
+            header: vec![],
+            footer: vec![],
+            span: Span::detached(),
         }
     }
 
+    /// Repeat the given number of rows at the top and bottom of the grid in
+    /// each region it spans.
+    ///
+    /// The rows are laid out once and then placed at the top and bottom of
+    /// each region, so they should not contain content that refers to its own
+    /// location. The span is used for errors about repeated rows that don't
+    /// fit.
+    pub fn repeat(mut self, header: usize, footer: usize, span: Span) -> Self {
+        let step = 1 + self.has_gutter as usize;
+        let len = self.rows.len();
+        let start = (header * step).min(len);
+        let end = if footer > 0 {
+            (len + self.has_gutter as usize)
+                .saturating_sub(footer * step)
+                .max(start)
+        } else {
+            len
+        };
+        self.body = start..end;
+        self.span = span;
+        self
+    }
+
     /// Determines the columns sizes and then layouts the grid row-by-row.
     pub fn layout(mut self, vt: &mut Vt) -> SourceResult<GridLayout> {
         self.measure_columns(vt)?;
 
-        for y in 0..self.rows.len() {
+        // Layout the repeated rows once.
+        let header = 0..self.body.start;
+        let footer = self.body.end..self.rows.len();
+        for (rows, target) in [(header, 0), (footer, 1)] {
+            if rows.is_empty() {
+                continue;
+            }
+
+            let heights = self.measure_rows(vt, rows.clone())?;
+            let frames = self.layout_rows(vt, rows.clone(), &heights)?;
+            let placed = frames.into_iter().zip(rows).collect();
+            if target == 0 {
+                self.header = placed;
+            } else {
+                self.footer = placed;
+            }
+        }
+
+        // Skip to the first region in which the repeated rows fit.
+        let repeated = self.repeated_height();
+        while !self.regions.size.y.fits(repeated) && !self.regions.in_last() {
+            self.push_region(vt)?;
+        }
+        self.start_region()?;
+
+        let mut y = self.body.start;
+        while y < self.body.end {
             // Skip to next region if current one is full, but only for content
             // rows, not for gutter rows.
             if self.regions.is_full() && (!self.has_gutter || y % 2 == 0) {
                 self.finish_region(vt)?;
             }
 
+            // Rows that are spanned by a cell are laid out together.
+            let end = self.group_end(y);
+            if end > y + 1 {
+                self.layout_row_group(vt, y..end)?;
+                y = end;
+                continue;
+            }
+
             match self.rows[y] {
                 Sizing::Auto => self.layout_auto_row(vt, y)?,
                 Sizing::Rel(v) => self.layout_relative_row(vt, v, y)?,
                 Sizing::Fr(v) => self.lrows.push(Row::Fr(v, y)),
             }
+
+            y += 1;
         }
 
-        self.finish_region(vt)?;
+        self.push_region(vt)?;
 
         Ok(GridLayout {
             fragment: Fragment::frames(self.finished),
             cols: self.rcols,
             rows: self.rrows,
+            body: self.body,
+            spans: self.spans,
+            slots: self.slots,
         })
     }
 
@@ -357,9 +789,6 @@ impl<'a> GridLayouter<'a> {
         vt: &mut Vt,
         available: Abs,
     ) -> SourceResult<(Abs, usize)> {
-        let mut auto = Abs::zero();
-        let mut count = 0;
-
         // Determine size of auto columns by laying out all cells in those
         // columns, measuring them and finding the largest one.
         for (x, &col) in self.cols.iter().enumerate() {
@@ -369,31 +798,74 @@ impl<'a> GridLayouter<'a> {
 
             let mut resolved = Abs::zero();
             for y in 0..self.rows.len() {
-                if let Some(cell) = self.cell(x, y) {
-                    // For relative rows, we can already resolve the correct
-                    // base and for auto and fr we could only guess anyway.
-                    let height = match self.rows[y] {
-                        Sizing::Rel(v) => {
-                            v.resolve(self.styles).relative_to(self.regions.base().y)
-                        }
-                        _ => self.regions.base().y,
-                    };
-
-                    let size = Size::new(available, height);
-                    let pod = Regions::one(size, Axes::splat(false));
-                    let frame = cell.measure(vt, self.styles, pod)?.into_frame();
-                    resolved.set_max(frame.width());
+                // Cells spanning multiple columns are considered below.
+                let Some(i) = self.cell(x, y) else { continue };
+                if self.spans[i].x.len() > 1 {
+                    continue;
                 }
+
+                let width = self.measure_cell_width(vt, i, available)?;
+                resolved.set_max(width);
             }
 
             self.rcols[x] = resolved;
-            auto += resolved;
-            count += 1;
+        }
+
+        // If a cell spanning multiple columns is wider than the columns it
+        // spans, distribute the excess evenly across its auto columns.
+        for i in 0..self.cells.len() {
+            let span = self.spans[i].x.clone();
+            let autos: Vec<_> =
+                span.clone().filter(|&x| self.cols[x] == Sizing::Auto).collect();
+            if span.len() == 1 || autos.is_empty() {
+                continue;
+            }
+
+            let width = self.measure_cell_width(vt, i, available)?;
+            let current: Abs = self.rcols[span].iter().sum();
+            if width > current {
+                let share = (width - current) / autos.len() as f64;
+                for x in autos {
+                    self.rcols[x] += share;
+                }
+            }
+        }
+
+        let mut auto = Abs::zero();
+        let mut count = 0;
+        for (&col, &rcol) in self.cols.iter().zip(&self.rcols) {
+            if col == Sizing::Auto {
+                auto += rcol;
+                count += 1;
+            }
         }
 
         Ok((auto, count))
     }
 
+    /// Measure the natural width of a cell.
+    fn measure_cell_width(
+        &self,
+        vt: &mut Vt,
+        i: usize,
+        available: Abs,
+    ) -> SourceResult<Abs> {
+        // For relative rows, we can already resolve the correct base and for
+        // auto and fr we could only guess anyway.
+        let span = &self.spans[i];
+        let height = match self.rows[span.y.start] {
+            Sizing::Rel(v) if span.y.len() == 1 => {
+                v.resolve(self.styles).relative_to(self.regions.base().y)
+            }
+            _ => self.regions.base().y,
+        };
+
+        let size = Size::new(available, height);
+        let pod = Regions::one(size, Axes::splat(false));
+        let frame = self.cells[i].body.measure(vt, self.styles, pod)?.into_frame();
+        Ok(frame.width())
+    }
+
     /// Distribute remaining space to fractional columns.
     fn grow_fractional_columns(&mut self, remaining: Abs, fr: Fr) {
         if fr.is_zero() {
@@ -468,8 +940,9 @@ impl<'a> GridLayouter<'a> {
         // Expand all but the last region.
         // Skip the first region if the space is eaten up by an fr row.
         let len = resolved.len();
+        let mut backlog = vec![];
         for (region, target) in self
-            .regions
+            .body_regions(&mut backlog)
             .iter()
             .zip(&mut resolved[..len - 1])
             .skip(self.lrows.iter().any(|row| matches!(row, Row::Fr(..))) as usize)
@@ -499,12 +972,15 @@ impl<'a> GridLayouter<'a> {
         can_skip: bool,
     ) -> SourceResult<Option<Vec<Abs>>> {
         let mut resolved: Vec<Abs> = vec![];
+        let mut backlog = vec![];
+        let regions = self.body_regions(&mut backlog);
 
-        for (x, &rcol) in self.rcols.iter().enumerate() {
-            if let Some(cell) = self.cell(x, y) {
-                let mut pod = self.regions;
-                pod.size.x = rcol;
+        for x in 0..self.cols.len() {
+            if let Some(i) = self.cell(x, y) {
+                let mut pod = regions;
+                pod.size.x = self.cell_width(i);
 
+                let cell = &self.cells[i].body;
                 let frames = cell.measure(vt, self.styles, pod)?.into_frames();
 
                 // Skip the first region if one cell in it is empty. Then,
@@ -545,7 +1021,7 @@ impl<'a> GridLayouter<'a> {
 
         // Skip to fitting region.
         let height = frame.height();
-        while !self.regions.size.y.fits(height) && !self.regions.in_last() {
+        while !self.regions.size.y.fits(height) && !self.in_last() {
             self.finish_region(vt)?;
 
             // Don't skip multiple regions for gutter and don't push a row.
@@ -570,12 +1046,13 @@ impl<'a> GridLayouter<'a> {
         let mut pos = Point::zero();
 
         for (x, &rcol) in self.rcols.iter().enumerate() {
-            if let Some(cell) = self.cell(x, y) {
-                let size = Size::new(rcol, height);
+            if let Some(i) = self.cell(x, y) {
+                let size = Size::new(self.cell_width(i), height);
                 let mut pod = Regions::one(size, Axes::splat(true));
                 if self.rows[y] == Sizing::Auto {
                     pod.full = self.regions.full;
                 }
+                let cell = &self.cells[i].body;
                 let frame = cell.layout(vt, self.styles, pod)?.into_frame();
                 output.push_frame(pos, frame);
             }
@@ -608,11 +1085,11 @@ impl<'a> GridLayouter<'a> {
         // Layout the row.
         let mut pos = Point::zero();
         for (x, &rcol) in self.rcols.iter().enumerate() {
-            if let Some(cell) = self.cell(x, y) {
-                pod.size.x = rcol;
+            if let Some(i) = self.cell(x, y) {
+                pod.size.x = self.cell_width(i);
 
                 // Push the layouted frames into the individual output frames.
-                let fragment = cell.layout(vt, self.styles, pod)?;
+                let fragment = self.cells[i].body.layout(vt, self.styles, pod)?;
                 for (output, frame) in outputs.iter_mut().zip(fragment) {
                     output.push_frame(pos, frame);
                 }
@@ -624,14 +1101,214 @@ impl<'a> GridLayouter<'a> {
         Ok(Fragment::frames(outputs))
     }
 
+    /// Determine the end of the group of rows starting at `y` that are bound
+    /// together by cells spanning multiple rows.
+    fn group_end(&self, y: usize) -> usize {
+        let mut end = y + 1;
+        let mut row = y;
+        while row < end {
+            for x in 0..self.cols.len() {
+                if let Some(i) = self.slots[row * self.cols.len() + x] {
+                    end = end.max(self.spans[i].y.end);
+                }
+            }
+            row += 1;
+        }
+        end
+    }
+
+    /// Layout a group of rows that are bound together by cells spanning
+    /// multiple rows. Such a group cannot break across multiple regions, but
+    /// it may force a region break.
+    fn layout_row_group(&mut self, vt: &mut Vt, group: Range<usize>) -> SourceResult<()> {
+        let heights = self.measure_rows(vt, group.clone())?;
+
+        // Skip to fitting region.
+        let total: Abs = heights.iter().sum();
+        while !self.regions.size.y.fits(total) && !self.in_last() {
+            self.finish_region(vt)?;
+        }
+
+        let frames = self.layout_rows(vt, group.clone(), &heights)?;
+        for (frame, y) in frames.into_iter().zip(group) {
+            self.push_row(frame, y);
+        }
+
+        Ok(())
+    }
+
+    /// Determine the heights of rows that are laid out together, without
+    /// breaking across regions.
+    fn measure_rows(&self, vt: &mut Vt, group: Range<usize>) -> SourceResult<Vec<Abs>> {
+        let base = self.regions.base().y;
+
+        // Determine the height of each row from its cells which don't span
+        // multiple rows. Fractional rows are sized like auto rows here.
+        let mut heights = vec![];
+        for y in group.clone() {
+            let height = match self.rows[y] {
+                Sizing::Rel(v) => v.resolve(self.styles).relative_to(base),
+                _ => {
+                    let mut height = Abs::zero();
+                    for x in 0..self.cols.len() {
+                        let Some(i) = self.cell(x, y) else { continue };
+                        if self.spans[i].y.len() == 1 {
+                            height.set_max(self.measure_cell_height(vt, i)?);
+                        }
+                    }
+                    height
+                }
+            };
+            heights.push(height);
+        }
+
+        // If a cell spanning multiple rows is taller than the rows it spans,
+        // distribute the excess evenly across its auto rows.
+        for y in group.clone() {
+            for x in 0..self.cols.len() {
+                let Some(i) = self.cell(x, y) else { continue };
+                let span = self.spans[i].y.clone();
+                let autos: Vec<_> = span
+                    .clone()
+                    .filter(|&y| !matches!(self.rows[y], Sizing::Rel(_)))
+                    .collect();
+                if span.len() == 1 || autos.is_empty() {
+                    continue;
+                }
+
+                let height = self.measure_cell_height(vt, i)?;
+                let current: Abs = span.map(|y| heights[y - group.start]).sum();
+                if height > current {
+                    let share = (height - current) / autos.len() as f64;
+                    for y in autos {
+                        heights[y - group.start] += share;
+                    }
+                }
+            }
+        }
+
+        Ok(heights)
+    }
+
+    /// Layout rows with the given heights into one frame per row. The frames
+    /// of cells that span multiple rows are placed into the first row they
+    /// span and extend over the others.
+    fn layout_rows(
+        &self,
+        vt: &mut Vt,
+        group: Range<usize>,
+        heights: &[Abs],
+    ) -> SourceResult<Vec<Frame>> {
+        let mut frames = vec![];
+        for y in group.clone() {
+            let mut output = Frame::new(Size::new(self.width, heights[y - group.start]));
+            let mut pos = Point::zero();
+            for (x, &rcol) in self.rcols.iter().enumerate() {
+                if let Some(i) = self.cell(x, y) {
+                    let height =
+                        self.spans[i].y.clone().map(|y| heights[y - group.start]).sum();
+                    let size = Size::new(self.cell_width(i), height);
+                    let pod = Regions::one(size, Axes::splat(true));
+                    let cell = &self.cells[i].body;
+                    let frame = cell.layout(vt, self.styles, pod)?.into_frame();
+                    output.push_frame(pos, frame);
+                }
+
+                pos.x += rcol;
+            }
+
+            frames.push(output);
+        }
+
+        Ok(frames)
+    }
+
+    /// Measure the natural height of a cell at its resolved width.
+    fn measure_cell_height(&self, vt: &mut Vt, i: usize) -> SourceResult<Abs> {
+        let size = Size::new(self.cell_width(i), self.regions.base().y);
+        let pod = Regions::one(size, Axes::new(true, false));
+        let frame = self.cells[i].body.measure(vt, self.styles, pod)?.into_frame();
+        Ok(frame.height())
+    }
+
     /// Push a row frame into the current region.
     fn push_row(&mut self, frame: Frame, y: usize) {
         self.regions.size.y -= frame.height();
         self.lrows.push(Row::Frame(frame, y));
     }
 
-    /// Finish rows for one region.
+    /// Finish rows for one region and start the next one.
     fn finish_region(&mut self, vt: &mut Vt) -> SourceResult<()> {
+        self.push_region(vt)?;
+        self.start_region()
+    }
+
+    /// Start a region by placing the header rows at its top and reserving
+    /// space for the footer rows at its bottom.
+    fn start_region(&mut self) -> SourceResult<()> {
+        if self.header.is_empty() && self.footer.is_empty() {
+            return Ok(());
+        }
+
+        // Repeated rows that take up the whole region would leave no space for
+        // the other rows, so that they would never be placed.
+        if self.repeated_height() >= self.regions.full {
+            bail!(error!(self.span, "header and footer do not fit into the region")
+                .with_hints(["they are repeated in every region, so they must leave \
+                    space for other rows"
+                    .into()]));
+        }
+
+        for (frame, y) in self.header.clone() {
+            self.push_row(frame, y);
+        }
+
+        self.regions.size.y -=
+            self.footer.iter().map(|(frame, _)| frame.height()).sum::<Abs>();
+
+        Ok(())
+    }
+
+    /// The combined height of the header and footer rows.
+    fn repeated_height(&self) -> Abs {
+        self.header
+            .iter()
+            .chain(&self.footer)
+            .map(|(frame, _)| frame.height())
+            .sum()
+    }
+
+    /// Whether the current region is the last usable one. A region that only
+    /// contains header rows counts as empty.
+    fn in_last(&self) -> bool {
+        let mut regions = self.regions;
+        if self.lrows.len() == self.header.len() {
+            regions.size.y = self.initial.y;
+        }
+        regions.in_last()
+    }
+
+    /// The regions available to rows, accounting for the space that the
+    /// repeated rows take up in followup regions.
+    fn body_regions<'v>(&self, backlog: &'v mut Vec<Abs>) -> Regions<'v> {
+        let repeated = self.repeated_height();
+        let mut regions =
+            self.regions.map(backlog, |size| Size::new(size.x, size.y - repeated));
+        regions.size = self.regions.size;
+        regions.full = self.regions.full;
+        regions.root = self.regions.root;
+        regions
+    }
+
+    /// Finish rows for one region and advance to the next one.
+    fn push_region(&mut self, vt: &mut Vt) -> SourceResult<()> {
+        // Regions that were skipped before the header was placed stay empty.
+        if !self.lrows.is_empty() {
+            for (frame, y) in self.footer.clone() {
+                self.lrows.push(Row::Frame(frame, y));
+            }
+        }
+
         // Determine the height of existing rows in the region.
         let mut used = Abs::zero();
         let mut fr = Fr::zero();
@@ -679,30 +1356,22 @@ impl<'a> GridLayouter<'a> {
         Ok(())
     }
 
-    /// Get the content of the cell in column `x` and row `y`.
+    /// Get the index of the cell which starts in column `x` and row `y`.
     ///
-    /// Returns `None` if it's a gutter cell.
+    /// Returns `None` if it's a gutter cell, an empty slot, or covered by a
+    /// cell that starts elsewhere.
     #[track_caller]
-    fn cell(&self, mut x: usize, y: usize) -> Option<&'a Content> {
+    fn cell(&self, x: usize, y: usize) -> Option<usize> {
         assert!(x < self.cols.len());
         assert!(y < self.rows.len());
 
-        // Columns are reorder, but the cell slice is not.
-        if self.is_rtl {
-            x = self.cols.len() - 1 - x;
-        }
+        let i = self.slots[y * self.cols.len() + x]?;
+        let span = &self.spans[i];
+        (span.x.start == x && span.y.start == y).then_some(i)
+    }
 
-        if self.has_gutter {
-            // Even columns and rows are children, odd ones are gutter.
-            if x % 2 == 0 && y % 2 == 0 {
-                let c = 1 + self.cols.len() / 2;
-                self.cells.get((y / 2) * c + x / 2)
-            } else {
-                None
-            }
-        } else {
-            let c = self.cols.len();
-            self.cells.get(y * c + x)
-        }
+    /// The width of the columns spanned by a cell.
+    fn cell_width(&self, i: usize) -> Abs {
+        self.rcols[self.spans[i].x.clone()].iter().sum()
     }
 }
@@ -2,7 +2,7 @@ use crate::layout::{BlockElem, ParElem, Sizing, Spacing};
 use crate::prelude::*;
 use crate::text::TextElem;
 
-use super::GridLayouter;
+use super::{Cell, GridLayouter};
 
 /// A bullet list.
 ///
@@ -67,7 +67,7 @@ pub struct ListElem {
     ///
     /// Instead of plain content, you can also pass an array with multiple
     /// markers that should be used for nested lists. If the list nesting depth
-    /// exceeds the number of markers, the last one is repeated. For total
+    /// exceeds the number of markers, the markers are cycled. For total
     /// control, you may pass a function that maps the list's nesting depth
     /// (starting from `{0}`) to a desired marker.
     ///
@@ -79,6 +79,7 @@ pub struct ListElem {
     /// #set list(marker: ([•], [--]))
     /// - Top-level
     ///   - Nested
+    ///     - Cycled
     ///   - Items
     /// - Items
     /// ```
@@ -150,6 +151,7 @@ impl Layout for ListElem {
             cells.push(item.body().styled(Self::set_depth(Depth)));
         }
 
+        let cells = Cell::row_major(cells, 4);
         let layouter = GridLayouter::new(
             Axes::with_x(&[
                 Sizing::Rel(indent.into()),
@@ -195,7 +197,7 @@ impl ListMarker {
     fn resolve(&self, vt: &mut Vt, depth: usize) -> SourceResult<Content> {
         Ok(match self {
             Self::Content(list) => {
-                list.get(depth).or(list.last()).cloned().unwrap_or_default()
+                list.get(depth % list.len().max(1)).cloned().unwrap_or_default()
             }
             Self::Func(func) => func.call_vt(vt, [depth])?.display(),
         })
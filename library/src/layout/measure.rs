@@ -35,7 +35,29 @@ use crate::prelude::*;
 /// ```
 ///
 /// The measure function returns a dictionary with the entries `width` and
-/// `height`, both of type [`length`]($type/length).
+/// `height`, both of type [`length`]($type/length). This makes it possible to
+/// size other content after it, for example to draw a background exactly
+/// around a formula:
+///
+/// ```example
+/// #let framed(body) = style(styles => {
+///   let (width, height) = measure(body, styles)
+///   box(
+///     fill: aqua,
+///     width: width,
+///     height: height,
+///     body,
+///   )
+/// })
+///
+/// Look at #framed($sum_(k=1)^n k$).
+/// ```
+///
+/// Measuring content does not add it to the document, so measured headings,
+/// counter updates, and the like have no effect. The content is laid out with
+/// the same [show rules]($styling/#show-rules) as everywhere else and the
+/// results are cached, so measuring the same content with the same styles
+/// multiple times is cheap.
 ///
 /// Display: Measure
 /// Category: layout
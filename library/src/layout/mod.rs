@@ -50,8 +50,9 @@ use std::mem;
 use typed_arena::Arena;
 use typst::diag::SourceResult;
 use typst::eval::Tracer;
-use typst::model::DelayedErrors;
 use typst::model::{applicable, realize, StyleVecBuilder};
+use typst::model::{DelayedErrors, Depth};
+use typst::Limits;
 
 use crate::math::{EquationElem, LayoutMath};
 use crate::meta::DocumentElem;
@@ -88,6 +89,7 @@ pub(super) fn define(global: &mut Scope) {
     global.define("move", MoveElem::func());
     global.define("scale", ScaleElem::func());
     global.define("rotate", RotateElem::func());
+    global.define("skew", SkewElem::func());
     global.define("hide", HideElem::func());
     global.define("measure", measure_func());
     global.define("ltr", Dir::LTR);
@@ -113,6 +115,7 @@ pub trait LayoutRoot {
 impl LayoutRoot for Content {
     #[tracing::instrument(name = "Content::layout_root", skip_all)]
     fn layout_root(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Document> {
+        #[allow(clippy::too_many_arguments)]
         #[comemo::memoize]
         fn cached(
             content: &Content,
@@ -121,25 +124,32 @@ impl LayoutRoot for Content {
             locator: Tracked<Locator>,
             delayed: TrackedMut<DelayedErrors>,
             tracer: TrackedMut<Tracer>,
+            limits: Limits,
             styles: StyleChain,
         ) -> SourceResult<Document> {
             let mut locator = Locator::chained(locator);
+            let depth = Depth::default();
             let mut vt = Vt {
                 world,
                 introspector,
                 locator: &mut locator,
                 delayed,
                 tracer,
+                limits,
+                depth: depth.track(),
             };
             let scratch = Scratch::default();
             let (realized, styles) = realize_root(&mut vt, &scratch, content, styles)?;
-            realized
+            let result = realized
                 .with::<dyn LayoutRoot>()
                 .unwrap()
-                .layout_root(&mut vt, styles)
+                .layout_root(&mut vt, styles);
+            typst::cache::record_miss(result.as_ref().map_or(0, |doc| doc.pages.len()));
+            result
         }
 
         tracing::info!("Starting layout");
+        typst::cache::record_call();
         cached(
             self,
             vt.world,
@@ -147,6 +157,7 @@ impl LayoutRoot for Content {
             vt.locator.track(),
             TrackedMut::reborrow_mut(&mut vt.delayed),
             TrackedMut::reborrow_mut(&mut vt.tracer),
+            vt.limits,
             styles,
         )
     }
@@ -180,6 +191,8 @@ pub trait Layout {
             locator: &mut locator,
             tracer: TrackedMut::reborrow_mut(&mut vt.tracer),
             delayed: TrackedMut::reborrow_mut(&mut vt.delayed),
+            limits: vt.limits,
+            depth: vt.depth,
         };
         self.layout(&mut vt, styles, regions)
     }
@@ -202,6 +215,8 @@ impl Layout for Content {
             locator: Tracked<Locator>,
             delayed: TrackedMut<DelayedErrors>,
             tracer: TrackedMut<Tracer>,
+            limits: Limits,
+            depth: Tracked<Depth>,
             styles: StyleChain,
             regions: Regions,
         ) -> SourceResult<Fragment> {
@@ -212,17 +227,32 @@ impl Layout for Content {
                 locator: &mut locator,
                 delayed,
                 tracer,
+                limits,
+                depth,
             };
             let scratch = Scratch::default();
             let (realized, styles) = realize_block(&mut vt, &scratch, content, styles)?;
-            realized
+            let result = realized
                 .with::<dyn Layout>()
                 .unwrap()
-                .layout(&mut vt, styles, regions)
+                .layout(&mut vt, styles, regions);
+            typst::cache::record_miss(result.as_ref().map_or(0, Fragment::len));
+            result
         }
 
         tracing::info!("Layouting `Content`");
+        vt.check_cancelled(self.span())?;
+
+        // The depth is checked here instead of in the memoized function, so
+        // that the cached results don't depend on it.
+        let depth = Depth::nested(vt.depth);
+        if !depth.within(vt.limits.max_layout_depth) {
+            bail!(error!(self.span(), "maximum layout depth exceeded").with_hints([
+                "check whether a show rule produces the element it matches".into(),
+            ]));
+        }
 
+        typst::cache::record_call();
         let fragment = cached(
             self,
             vt.world,
@@ -230,6 +260,8 @@ impl Layout for Content {
             vt.locator.track(),
             TrackedMut::reborrow_mut(&mut vt.delayed),
             TrackedMut::reborrow_mut(&mut vt.tracer),
+            vt.limits,
+            depth.track(),
             styles,
             regions,
         )?;
@@ -301,6 +333,9 @@ struct Builder<'a, 'v, 't> {
     par: ParBuilder<'a>,
     /// The current list building state.
     list: ListBuilder<'a>,
+    /// How many show rules successively apply to the content that is
+    /// currently accepted.
+    shows: usize,
 }
 
 /// Temporary storage arenas for building.
@@ -321,6 +356,7 @@ impl<'a, 'v, 't> Builder<'a, 'v, 't> {
             flow: FlowBuilder::default(),
             par: ParBuilder::default(),
             list: ListBuilder::default(),
+            shows: 0,
         }
     }
 
@@ -334,9 +370,25 @@ impl<'a, 'v, 't> Builder<'a, 'v, 't> {
                 self.scratch.content.alloc(EquationElem::new(content.clone()).pack());
         }
 
+        // Preparing an element does not count towards the show rule depth.
+        let prepared = !content.needs_preparation();
         if let Some(realized) = realize(self.vt, content, styles)? {
+            if prepared {
+                if self.shows >= self.vt.limits.max_show_depth {
+                    bail!(error!(content.span(), "maximum show rule depth exceeded")
+                        .with_hints([
+                            "check whether the show rule matches its own output".into(),
+                        ]));
+                }
+                self.shows += 1;
+            }
+
             let stored = self.scratch.content.alloc(realized);
-            return self.accept(stored, styles);
+            let result = self.accept(stored, styles);
+            if prepared {
+                self.shows -= 1;
+            }
+            return result;
         }
 
         if let Some((elem, local)) = content.to_styled() {
@@ -370,11 +422,12 @@ impl<'a, 'v, 't> Builder<'a, 'v, 't> {
             return Ok(());
         }
 
-        let keep = content
-            .to::<PagebreakElem>()
-            .map_or(false, |pagebreak| !pagebreak.weak(styles));
-
-        self.interrupt_page(keep.then_some(styles))?;
+        // A weak pagebreak doesn't end a page that has no content yet.
+        let weak = content.to::<PagebreakElem>().map(|pagebreak| pagebreak.weak(styles));
+        if weak != Some(true) || !self.flow.0.is_basically_empty() {
+            let keep = weak == Some(false);
+            self.interrupt_page(keep.then_some(styles))?;
+        }
 
         if let Some(doc) = &mut self.doc {
             if doc.accept(content, styles) {
@@ -492,8 +545,13 @@ struct DocBuilder<'a> {
 impl<'a> DocBuilder<'a> {
     fn accept(&mut self, content: &Content, styles: StyleChain<'a>) -> bool {
         if let Some(pagebreak) = content.to::<PagebreakElem>() {
-            self.keep_next = !pagebreak.weak(styles);
-            self.clear_next = pagebreak.to(styles);
+            // A weak pagebreak must not undo a strong one before it.
+            if !pagebreak.weak(styles) {
+                self.keep_next = true;
+            }
+            if let Some(parity) = pagebreak.to(styles) {
+                self.clear_next = Some(parity);
+            }
             return true;
         }
 
@@ -541,6 +599,7 @@ impl<'a> FlowBuilder<'a> {
 
         if content.is::<VElem>()
             || content.is::<ColbreakElem>()
+            || content.is::<FlushElem>()
             || content.is::<MetaElem>()
         {
             self.0.push(content.clone(), styles);
@@ -566,6 +625,11 @@ impl<'a> FlowBuilder<'a> {
 
             let (above, below) = if let Some(block) = content.to::<BlockElem>() {
                 (block.above(styles), block.below(styles))
+            } else if let (true, Smart::Custom(spacing)) =
+                (content.is::<ParElem>(), ParElem::spacing_in(styles))
+            {
+                let spacing = VElem::block_spacing(spacing.into());
+                (spacing.clone(), spacing)
             } else {
                 (BlockElem::above_in(styles), BlockElem::below_in(styles))
             };
@@ -707,3 +771,46 @@ impl Default for ListBuilder<'_> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use typst::eval::Tracer;
+    use typst::export::PdfOptions;
+    use typst::util::hash128;
+
+    use crate::sandbox::SandboxWorld;
+    use crate::testing::fonts;
+
+    #[test]
+    fn test_layout_cache() {
+        // The cache and its statistics are local to this test's thread.
+        let world = SandboxWorld::new("Evicted").with_fonts(fonts());
+        let compile = || {
+            let mut tracer = Tracer::default();
+            let document = typst::compile(&world, &mut tracer).unwrap();
+            hash128(&typst::export::pdf(&document, &PdfOptions::default()).unwrap())
+        };
+
+        // Recompiling is served from the cache.
+        typst::evict_cache(0);
+        let first = compile();
+        assert!(typst::cache_stats().misses > 0);
+        assert!(typst::cache_stats().frames > 0);
+        assert_eq!(first, compile());
+        assert_eq!(typst::cache_stats().misses, 0);
+        assert!(typst::cache_stats().hits > 0);
+
+        // Evicting everything rebuilds the layout with the same result.
+        typst::evict_cache(0);
+        assert_eq!(typst::cache_stats().frames, 0);
+        assert_eq!(first, compile());
+        assert!(typst::cache_stats().misses > 0);
+
+        // Exceeding the frame cap evicts everything regardless of age.
+        typst::cache::set_frame_cap(Some(0));
+        typst::evict_cache(10);
+        typst::cache::set_frame_cap(None);
+        assert_eq!(first, compile());
+        assert!(typst::cache_stats().misses > 0);
+    }
+}
@@ -1,6 +1,8 @@
 use std::ptr;
 use std::str::FromStr;
 
+use typst::profile::{self, ProfileKind};
+
 use super::{AlignElem, ColumnsElem};
 use crate::meta::{Counter, CounterKey, Numbering};
 use crate::prelude::*;
@@ -15,6 +17,10 @@ use crate::text::TextElem;
 /// Pages can be set to use `{auto}` as their width or height. In this case,
 /// the pages will grow to fit their content on the respective axis.
 ///
+/// A page set rule in the middle of the document ends the current page and
+/// applies its properties from the next page on. This way, the margins,
+/// marginals, or background can be changed for a subset of pages.
+///
 /// ## Example { #example }
 /// ```example
 /// >>> #set page(margin: auto)
@@ -108,7 +114,10 @@ pub struct PageElem {
     ///     dictionary explicitly sets a size.
     ///
     /// The values for `left` and `right` are mutually exclusive with
-    /// the values for `inside` and `outside`.
+    /// the values for `inside` and `outside`. Which side is inside is
+    /// determined by the physical page number within the whole document, so
+    /// the margins alternate correctly even if they are changed in the middle
+    /// of the document.
     ///
     /// ```example
     /// #set page(
@@ -219,6 +228,26 @@ pub struct PageElem {
     #[default(Ratio::new(0.3).into())]
     pub header_ascent: Rel<Length>,
 
+    /// The header of the blank pages that are inserted when a [page
+    /// break]($func/pagebreak) requests the next page to be even or odd.
+    /// When `{auto}`, these pages show the normal header. The footer,
+    /// background, and foreground appear on them as usual, and they count
+    /// towards the [page counter]($func/counter).
+    ///
+    /// ```example
+    /// #set page(
+    ///   height: 80pt,
+    ///   header: [_Chapter_],
+    ///   blank-header: none,
+    ///   numbering: "1",
+    /// )
+    ///
+    /// First.
+    /// #pagebreak(to: "odd")
+    /// Third.
+    /// ```
+    pub blank_header: Smart<Option<Content>>,
+
     /// The page's footer. Fills the bottom margin of each page.
     ///
     /// For just a page number, the `numbering` property, typically suffices. If
@@ -269,7 +298,9 @@ pub struct PageElem {
 
     /// Content in the page's foreground.
     ///
-    /// This content will overlay the page's body.
+    /// This content will overlay the page's body. Like headers and footers,
+    /// it is laid out for each page separately and can thus display the
+    /// [page counter]($func/counter).
     ///
     /// ```example
     /// #set page(foreground: text(24pt)[🥸])
@@ -278,6 +309,18 @@ pub struct PageElem {
     /// "Weak Reject" because they did
     /// not understand our approach...
     /// ```
+    ///
+    /// ```example
+    /// #set page(foreground: rotate(-24deg,
+    ///   text(14pt, fill: rgb("FF4136"))[
+    ///     *DRAFT* --- page
+    ///     #counter(page).display()
+    ///   ]
+    /// ))
+    ///
+    /// #counter(page).update(12)
+    /// The twelfth page of our report.
+    /// ```
     pub foreground: Option<Content>,
 
     /// The contents of the page(s).
@@ -309,6 +352,7 @@ impl PageElem {
         mut number: NonZeroUsize,
     ) -> SourceResult<Fragment> {
         tracing::info!("Page layout");
+        let _timer = profile::timer(ProfileKind::Page, self.span());
 
         // When one of the lengths is infinite the page fits its content along
         // that axis.
@@ -357,7 +401,8 @@ impl PageElem {
         let mut frames = child.layout(vt, styles, regions)?.into_frames();
 
         // Align the child to the pagebreak's parity.
-        if self.clear_to(styles).is_some_and(|p| !p.matches(number.get())) {
+        let blank = self.clear_to(styles).is_some_and(|p| !p.matches(number.get()));
+        if blank {
             let size = area.map(Abs::is_finite).select(area, Size::zero());
             frames.insert(0, Frame::new(size));
         }
@@ -366,6 +411,7 @@ impl PageElem {
         let foreground = self.foreground(styles);
         let background = self.background(styles);
         let header = self.header(styles);
+        let blank_header = self.blank_header(styles).unwrap_or_else(|| header.clone());
         let header_ascent = self.header_ascent(styles);
         let footer = self.footer(styles).or_else(|| {
             self.numbering(styles).map(|numbering| {
@@ -386,7 +432,7 @@ impl PageElem {
         );
 
         // Post-process pages.
-        for frame in frames.iter_mut() {
+        for (i, frame) in frames.iter_mut().enumerate() {
             tracing::info!("Layouting page #{number}");
 
             // The padded width of the page's content without margins.
@@ -409,8 +455,9 @@ impl PageElem {
             let size = frame.size();
 
             // Realize overlays.
+            let header = if blank && i == 0 { &blank_header } else { &header };
             for (name, marginal) in [
-                ("header", &header),
+                ("header", header),
                 ("footer", &footer),
                 ("background", &background),
                 ("foreground", &foreground),
@@ -420,7 +467,7 @@ impl PageElem {
                 let Some(content) = marginal else { continue };
 
                 let (pos, area, align);
-                if ptr::eq(marginal, &header) {
+                if ptr::eq(marginal, header) {
                     let ascent = header_ascent.relative_to(margin.top);
                     pos = Point::with_x(margin.left);
                     area = Size::new(pw, margin.top - ascent);
@@ -443,7 +490,7 @@ impl PageElem {
                     .layout(vt, styles, pod)?
                     .into_frame();
 
-                if ptr::eq(marginal, &header) || ptr::eq(marginal, &background) {
+                if ptr::eq(marginal, header) || ptr::eq(marginal, &background) {
                     frame.prepend_frame(pos, sub);
                 } else {
                     frame.push_frame(pos, sub);
@@ -652,7 +699,8 @@ pub struct PagebreakElem {
     pub weak: bool,
 
     /// If given, ensures that the next page will be an even/odd page, with an
-    /// empty page in between if necessary.
+    /// empty page in between if necessary. The empty page still has the
+    /// page's footer and its [blank header]($func/page.blank-header).
     ///
     /// ```example
     /// #set page(height: 30pt)
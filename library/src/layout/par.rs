@@ -5,7 +5,8 @@ use icu_provider_blob::BlobDataProvider;
 use icu_segmenter::{LineBreakIteratorUtf8, LineSegmenter};
 use once_cell::sync::Lazy;
 use typst::eval::Tracer;
-use typst::model::DelayedErrors;
+use typst::model::{DelayedErrors, Depth};
+use typst::Limits;
 use unicode_bidi::{BidiInfo, Level as BidiLevel};
 use unicode_script::{Script, UnicodeScript};
 
@@ -14,8 +15,9 @@ use crate::layout::AlignElem;
 use crate::math::EquationElem;
 use crate::prelude::*;
 use crate::text::{
-    is_gb_style, shape, LinebreakElem, Quoter, Quotes, ShapedText, SmartQuoteElem,
-    SpaceElem, TextElem,
+    is_cjk_char, is_gb_style, is_latin_char, shape, warn_missing_glyphs,
+    warn_synthesized_smallcaps, Case, LinebreakElem, Quoter, Quotes, ShapedText,
+    SmartQuoteElem, SpaceElem, TextElem,
 };
 
 /// Arranges text, spacing and inline-level elements into a paragraph.
@@ -49,10 +51,54 @@ use crate::text::{
 #[element(Construct)]
 pub struct ParElem {
     /// The spacing between lines.
+    ///
+    /// How this is measured depends on the [leading
+    /// model]($func/par.leading-model).
     #[resolve]
     #[default(Em::new(0.65).into())]
     pub leading: Length,
 
+    /// How to interpret the [leading]($func/par.leading).
+    ///
+    /// - `{"gap"}`: The leading is the gap between the bottom of one line and
+    ///   the top of the next. This is the default.
+    /// - `{"baseline"}`: The leading is the distance between the baselines of
+    ///   two consecutive lines. This keeps a steady baseline grid even if a
+    ///   line contains tall inline content. Lines whose content would overlap
+    ///   are pushed apart as far as needed.
+    ///
+    /// ```example
+    /// #set par(leading: 14pt, leading-model: "baseline")
+    /// The baselines of these lines
+    /// are evenly spaced, even if a
+    /// line contains $display(sum_(k=1)^n k)$
+    /// tall math.
+    /// ```
+    #[default(LeadingModel::Gap)]
+    pub leading_model: LeadingModel,
+
+    /// Whether to keep lines on the baseline grid when their content exceeds
+    /// the baseline distance.
+    ///
+    /// If enabled, such lines are pushed down by whole multiples of the
+    /// [leading]($func/par.leading) instead of just as far as needed. Only has
+    /// an effect with the `{"baseline"}` [leading
+    /// model]($func/par.leading-model).
+    ///
+    /// ```example
+    /// #set par(
+    ///   leading: 14pt,
+    ///   leading-model: "baseline",
+    ///   grid-snap: true,
+    /// )
+    /// The baselines of these lines
+    /// stay on the grid, even if a
+    /// line contains $display(sum_(k=1)^n k)$
+    /// tall math.
+    /// ```
+    #[default(false)]
+    pub grid_snap: bool,
+
     /// Whether to justify text in its line.
     ///
     /// Hyphenation will be enabled for justified paragraphs if the [text
@@ -93,20 +139,92 @@ pub struct ParElem {
 
     /// The indent the first line of a paragraph should have.
     ///
-    /// Only the first line of a consecutive paragraph will be indented (not
-    /// the first one in a block or on the page).
+    /// By default, only the first line of a consecutive paragraph will be
+    /// indented (not the first one in a block or on the page, or one directly
+    /// after a heading, list, or other block). Pass a dictionary with an
+    /// `amount` and `{all: true}` to indent all paragraphs instead.
     ///
     /// By typographic convention, paragraph breaks are indicated either by some
     /// space between paragraphs or by indented first lines. Consider reducing
-    /// the [paragraph spacing]($func/block.spacing) to the [`leading`] when
-    /// using this property (e.g. using
-    /// `[#show par: set block(spacing: 0.65em)]`).
-    pub first_line_indent: Length,
+    /// the [paragraph spacing]($func/par.spacing) to the [`leading`] when
+    /// using this property (e.g. using `[#set par(spacing: 0.65em)]`).
+    ///
+    /// ```example
+    /// #set par(first-line-indent: (amount: 1em, all: true))
+    /// = Chapter
+    /// With all paragraphs indented,
+    /// even the first one is.
+    ///
+    /// And the others, too.
+    /// ```
+    #[default]
+    pub first_line_indent: FirstLineIndent,
+
+    /// The spacing between two consecutive paragraphs.
+    ///
+    /// Unlike the [block spacing]($func/block.spacing), this only applies
+    /// between paragraphs, so that the space around other blocks, like
+    /// headings and lists, is kept. When set to `{auto}`, paragraphs are
+    /// spaced like blocks.
+    ///
+    /// ```example
+    /// #set par(spacing: 0.65em)
+    /// These paragraphs are set
+    /// closely together.
+    ///
+    /// But the list still keeps
+    /// its distance:
+    /// - Item
+    /// ```
+    #[default]
+    pub spacing: Smart<Length>,
 
     /// The indent all but the first line of a paragraph should have.
+    ///
+    /// ```example
+    /// #set par(hanging-indent: 1.5em)
+    ///
+    /// Knuth, D. E. (1984). The
+    /// TeXbook. Addison-Wesley.
+    /// ```
     #[resolve]
     pub hanging_indent: Length,
 
+    /// The minimum number of lines of a paragraph that must stay together at
+    /// the bottom of a page or column before a break. If fewer lines fit, they
+    /// move to the next page along with the rest of the paragraph.
+    ///
+    /// Set this to `{1}` to allow a single line to be left behind.
+    ///
+    /// ```example
+    /// #set page(height: 60pt)
+    /// #set par(orphans: 3)
+    /// #v(20pt)
+    /// The first lines of this
+    /// paragraph move to the
+    /// next page together.
+    /// ```
+    #[default(NonZeroUsize::new(2).unwrap())]
+    pub orphans: NonZeroUsize,
+
+    /// The minimum number of lines of a paragraph that must stay together at
+    /// the top of a page or column after a break. If fewer lines would
+    /// continue, earlier lines move along with them.
+    ///
+    /// Set this to `{1}` to allow a single line to continue on its own.
+    ///
+    /// ```example
+    /// #set page(height: 60pt)
+    /// #set par(widows: 3)
+    /// #v(10pt)
+    /// The last three lines
+    /// of this paragraph
+    /// stay together on
+    /// the next page.
+    /// ```
+    #[default(NonZeroUsize::new(2).unwrap())]
+    pub widows: NonZeroUsize,
+
     /// The contents of the paragraph.
     #[external]
     #[required]
@@ -119,11 +237,11 @@ pub struct ParElem {
 }
 
 impl Construct for ParElem {
-    fn construct(_: &mut Vm, args: &mut Args) -> SourceResult<Content> {
+    fn construct(vm: &mut Vm, args: &mut Args) -> SourceResult<Content> {
         // The paragraph constructor is special: It doesn't create a paragraph
         // element. Instead, it just ensures that the passed content lives in a
         // separate paragraph and styles it.
-        let styles = Self::set(args)?;
+        let styles = Self::set(vm, args)?;
         let body = args.expect::<Content>("body")?;
         Ok(Content::sequence([
             ParbreakElem::new().pack(),
@@ -134,7 +252,8 @@ impl Construct for ParElem {
 }
 
 impl ParElem {
-    /// Layout the paragraph into a collection of lines.
+    /// Layout the paragraph into a collection of lines and the spacing between
+    /// each two consecutive ones.
     #[tracing::instrument(name = "ParElement::layout", skip_all)]
     pub fn layout(
         &self,
@@ -143,7 +262,7 @@ impl ParElem {
         consecutive: bool,
         region: Size,
         expand: bool,
-    ) -> SourceResult<Fragment> {
+    ) -> SourceResult<(Fragment, Vec<Abs>)> {
         #[comemo::memoize]
         #[allow(clippy::too_many_arguments)]
         fn cached(
@@ -153,11 +272,13 @@ impl ParElem {
             locator: Tracked<Locator>,
             delayed: TrackedMut<DelayedErrors>,
             tracer: TrackedMut<Tracer>,
+            limits: Limits,
+            depth: Tracked<Depth>,
             styles: StyleChain,
             consecutive: bool,
             region: Size,
             expand: bool,
-        ) -> SourceResult<Fragment> {
+        ) -> SourceResult<(Fragment, Vec<Abs>)> {
             let mut locator = Locator::chained(locator);
             let mut vt = Vt {
                 world,
@@ -165,16 +286,21 @@ impl ParElem {
                 locator: &mut locator,
                 delayed,
                 tracer,
+                limits,
+                depth,
             };
             let children = par.children();
 
             // Collect all text into one string for BiDi analysis.
-            let (text, segments, spans) = collect(&children, &styles, consecutive)?;
+            let (text, segments, spans, parts) =
+                collect(&mut vt, &children, &styles, consecutive, region)?;
 
             // Perform BiDi analysis and then prepare paragraph layout by building a
             // representation on which we can do line breaking without layouting
             // each and every line from scratch.
-            let p = prepare(&mut vt, &children, &text, segments, spans, styles, region)?;
+            let p = prepare(
+                &mut vt, &children, &text, segments, spans, parts, styles, region,
+            )?;
 
             // Break the paragraph into lines.
             let lines = linebreak(&vt, &p, region.x - p.hang);
@@ -183,13 +309,15 @@ impl ParElem {
             finalize(&mut vt, &p, &lines, region, expand)
         }
 
-        let fragment = cached(
+        let (fragment, spacing) = cached(
             self,
             vt.world,
             vt.introspector,
             vt.locator.track(),
             TrackedMut::reborrow_mut(&mut vt.delayed),
             TrackedMut::reborrow_mut(&mut vt.tracer),
+            vt.limits,
+            vt.depth,
             styles,
             consecutive,
             region,
@@ -197,10 +325,41 @@ impl ParElem {
         )?;
 
         vt.locator.visit_frames(&fragment);
-        Ok(fragment)
+        Ok((fragment, spacing))
     }
 }
 
+/// How to interpret the leading of a paragraph.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum LeadingModel {
+    /// The leading is the gap between two consecutive lines.
+    Gap,
+    /// The leading is the distance between the baselines of two consecutive
+    /// lines.
+    Baseline,
+}
+
+/// How to indent the first line of a paragraph.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct FirstLineIndent {
+    /// The size of the indent.
+    pub amount: Length,
+    /// Whether to also indent paragraphs that don't follow another paragraph.
+    pub all: bool,
+}
+
+cast! {
+    FirstLineIndent,
+    self => dict! { "amount" => self.amount, "all" => self.all }.into_value(),
+    amount: Length => Self { amount, all: false },
+    mut dict: Dict => {
+        let amount = dict.take("amount")?.cast()?;
+        let all = dict.take("all").ok().map(Value::cast).transpose()?.unwrap_or(false);
+        dict.finish(&["amount", "all"])?;
+        Self { amount, all }
+    },
+}
+
 /// How to determine line breaks in a paragraph.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
 pub enum Linebreaks {
@@ -328,6 +487,9 @@ enum Segment<'a> {
     Spacing(Spacing),
     /// A mathematical equation.
     Equation(&'a EquationElem),
+    /// One part of a breakable inline equation, as an index into the
+    /// already laid out parts.
+    EquationPart(usize),
     /// A box with arbitrary content.
     Box(&'a BoxElem, bool),
     /// Metadata.
@@ -341,7 +503,9 @@ impl Segment<'_> {
             Self::Text(len) => len,
             Self::Spacing(_) => SPACING_REPLACE.len_utf8(),
             Self::Box(_, true) => SPACING_REPLACE.len_utf8(),
-            Self::Equation(_) | Self::Box(_, _) => OBJ_REPLACE.len_utf8(),
+            Self::Equation(_) | Self::EquationPart(_) | Self::Box(_, _) => {
+                OBJ_REPLACE.len_utf8()
+            }
             Self::Meta => 0,
         }
     }
@@ -400,8 +564,13 @@ impl<'a> Item<'a> {
 }
 
 /// Maps byte offsets back to spans.
+///
+/// Each piece of text has a span and the offset it starts at in the span's
+/// text. Offsets within a piece map to offsets in the span's text one to one
+/// unless the piece was changed, for example by a case transformation, in
+/// which case they all map to its start.
 #[derive(Default)]
-pub struct SpanMapper(Vec<(usize, Span)>);
+pub struct SpanMapper(Vec<(usize, Span, usize, bool)>);
 
 impl SpanMapper {
     /// Create a new span mapper.
@@ -411,7 +580,20 @@ impl SpanMapper {
 
     /// Push a span for a segment with the given length.
     pub fn push(&mut self, len: usize, span: Span) {
-        self.0.push((len, span));
+        self.0.push((len, span, 0, true));
+    }
+
+    /// Push a span for a piece of text with the given length that starts at
+    /// the given offset in the span's text and whether the piece is identical
+    /// to the span's text there.
+    pub fn push_piece(&mut self, len: usize, span: Span, offset: usize, exact: bool) {
+        if let Some((last_len, last_span, last_offset, true)) = self.0.last_mut() {
+            if exact && *last_span == span && *last_offset + *last_len == offset {
+                *last_len += len;
+                return;
+            }
+        }
+        self.0.push((len, span, offset, exact));
     }
 
     /// Determine the span at the given byte offset.
@@ -419,9 +601,10 @@ impl SpanMapper {
     /// May return a detached span.
     pub fn span_at(&self, offset: usize) -> (Span, u16) {
         let mut cursor = 0;
-        for &(len, span) in &self.0 {
+        for &(len, span, start, exact) in &self.0 {
             if (cursor..=cursor + len).contains(&offset) {
-                return (span, u16::try_from(offset - cursor).unwrap_or(0));
+                let within = if exact { start + offset - cursor } else { start };
+                return (span, u16::try_from(within).unwrap_or(0));
             }
             cursor += len;
         }
@@ -535,26 +718,33 @@ impl<'a> Line<'a> {
 
 /// Collect all text of the paragraph into one string. This also performs
 /// string-level preprocessing like case transformations.
+///
+/// Breakable inline equations are already laid out here because each of their
+/// parts needs its own placeholder in the text.
 #[allow(clippy::type_complexity)]
 fn collect<'a>(
+    vt: &mut Vt,
     children: &'a [Content],
     styles: &'a StyleChain<'a>,
     consecutive: bool,
-) -> SourceResult<(String, Vec<(Segment<'a>, StyleChain<'a>)>, SpanMapper)> {
+    region: Size,
+) -> SourceResult<(String, Vec<(Segment<'a>, StyleChain<'a>)>, SpanMapper, Vec<Frame>)> {
     let mut full = String::new();
+    let mut parts = vec![];
     let mut quoter = Quoter::new();
     let mut segments = vec![];
     let mut spans = SpanMapper::new();
+    let mut cased = false;
     let mut iter = children.iter().peekable();
 
     let first_line_indent = ParElem::first_line_indent_in(*styles);
-    if !first_line_indent.is_zero()
-        && consecutive
+    if !first_line_indent.amount.is_zero()
+        && (consecutive || first_line_indent.all)
         && AlignElem::alignment_in(*styles).x.resolve(*styles)
             == TextElem::dir_in(*styles).start().into()
     {
         full.push(SPACING_REPLACE);
-        segments.push((Segment::Spacing(first_line_indent.into()), *styles));
+        segments.push((Segment::Spacing(first_line_indent.amount.into()), *styles));
     }
 
     let hang = ParElem::hanging_indent_in(*styles);
@@ -576,10 +766,21 @@ fn collect<'a>(
             Segment::Text(1)
         } else if let Some(elem) = child.to::<TextElem>() {
             let prev = full.len();
+            let text = elem.text();
             if let Some(case) = TextElem::case_in(styles) {
-                full.push_str(&case.apply(&elem.text()));
+                // Transform each cluster on its own so that the glyphs can be
+                // mapped back to the original text.
+                let lang = TextElem::lang_in(styles);
+                let minor_words = TextElem::minor_words_in(styles);
+                let first = case == Case::Title && !full.contains(char::is_alphabetic);
+                case.transform(&text, lang, &minor_words, first, |range, piece| {
+                    let exact = piece == &text[range.clone()];
+                    spans.push_piece(piece.len(), child.span(), range.start, exact);
+                    full.push_str(piece);
+                });
+                cased = true;
             } else {
-                full.push_str(&elem.text());
+                full.push_str(&text);
             }
             Segment::Text(full.len() - prev)
         } else if let Some(elem) = child.to::<HElem>() {
@@ -598,7 +799,8 @@ fn collect<'a>(
             if SmartQuoteElem::enabled_in(styles) {
                 let lang = TextElem::lang_in(styles);
                 let region = TextElem::region_in(styles);
-                let quotes = Quotes::from_lang(lang, region);
+                let custom = SmartQuoteElem::quotes_in(styles);
+                let quotes = Quotes::new(&custom, lang, region);
                 let peeked = iter.peek().and_then(|child| {
                     let child = if let Some((child, _)) = child.to_styled() {
                         child
@@ -625,8 +827,22 @@ fn collect<'a>(
             }
             Segment::Text(full.len() - prev)
         } else if let Some(elem) = child.to::<EquationElem>() {
-            full.push(OBJ_REPLACE);
-            Segment::Equation(elem)
+            if EquationElem::breakable_in(styles) && !elem.block(styles) {
+                let pod = Regions::one(region, Axes::splat(false));
+                let start = parts.len();
+                parts.extend(elem.layout_parts(vt, styles, pod)?);
+                for i in start..parts.len() - 1 {
+                    full.push(OBJ_REPLACE);
+                    let segment = Segment::EquationPart(i);
+                    spans.push(segment.len(), child.span());
+                    segments.push((segment, styles));
+                }
+                full.push(OBJ_REPLACE);
+                Segment::EquationPart(parts.len() - 1)
+            } else {
+                full.push(OBJ_REPLACE);
+                Segment::Equation(elem)
+            }
         } else if let Some(elem) = child.to::<BoxElem>() {
             let frac = elem.width(styles).is_fractional();
             full.push(if frac { SPACING_REPLACE } else { OBJ_REPLACE });
@@ -641,7 +857,9 @@ fn collect<'a>(
             quoter.last(last);
         }
 
-        spans.push(segment.len(), child.span());
+        if !std::mem::take(&mut cased) {
+            spans.push(segment.len(), child.span());
+        }
 
         if let (Some((Segment::Text(last_len), last_styles)), Segment::Text(len)) =
             (segments.last_mut(), segment)
@@ -655,17 +873,19 @@ fn collect<'a>(
         segments.push((segment, styles));
     }
 
-    Ok((full, segments, spans))
+    Ok((full, segments, spans, parts))
 }
 
 /// Prepare paragraph layout by shaping the whole paragraph and layouting all
 /// contained inline-level content.
+#[allow(clippy::too_many_arguments)]
 fn prepare<'a>(
     vt: &mut Vt,
     children: &'a [Content],
     text: &'a str,
     segments: Vec<(Segment<'a>, StyleChain<'a>)>,
     spans: SpanMapper,
+    parts: Vec<Frame>,
     styles: StyleChain<'a>,
     region: Size,
 ) -> SourceResult<Preparation<'a>> {
@@ -703,6 +923,11 @@ fn prepare<'a>(
                 frame.translate(Point::with_y(TextElem::baseline_in(styles)));
                 items.push(Item::Frame(frame));
             }
+            Segment::EquationPart(i) => {
+                let mut frame = parts[i].clone();
+                frame.translate(Point::with_y(TextElem::baseline_in(styles)));
+                items.push(Item::Frame(frame));
+            }
             Segment::Box(elem, _) => {
                 if let Sizing::Fr(v) = elem.width(styles) {
                     items.push(Item::Fractional(v, Some((elem, styles))));
@@ -740,7 +965,7 @@ fn prepare<'a>(
 /// items for them.
 fn shape_range<'a>(
     items: &mut Vec<Item<'a>>,
-    vt: &Vt,
+    vt: &mut Vt,
     bidi: &BidiInfo<'a>,
     range: Range,
     spans: &SpanMapper,
@@ -752,6 +977,8 @@ fn shape_range<'a>(
         let dir = if level.is_ltr() { Dir::LTR } else { Dir::RTL };
         let shaped =
             shape(vt, range.start, &bidi.text[range], spans, styles, dir, lang, region);
+        warn_missing_glyphs(vt, &shaped);
+        warn_synthesized_smallcaps(vt, &shaped);
         items.push(Item::Text(shaped));
     };
 
@@ -1058,6 +1285,13 @@ static LINEBREAK_DATA: Lazy<CodePointMapData<LineBreak>> = Lazy::new(|| {
     icu_properties::maps::load_line_break(&deser_provider).unwrap()
 });
 
+/// Characters that may not start a line in Chinese and Japanese text.
+const NO_BREAK_BEFORE: &str = "、。，．・：；？！）」』〕】〉》｝］〙〗”’ーゝゞ々\
+    ぁぃぅぇぉっゃゅょゎゕゖァィゥェォッャュョヮヵヶ";
+
+/// Characters that may not end a line in Chinese and Japanese text.
+const NO_BREAK_AFTER: &str = "（「『〔【〈《｛［〘〖“‘";
+
 /// Determine all possible points in the text where lines can broken.
 ///
 /// Returns for each breakpoint the text index, whether the break is mandatory
@@ -1089,7 +1323,7 @@ struct Breakpoints<'a> {
     /// The inner iterator over the unicode line break opportunities.
     linebreaks: LineBreakIteratorUtf8<'a, 'a>,
     /// Iterator over syllables of the current word.
-    syllables: Option<hypher::Syllables<'a>>,
+    syllables: Option<Syllables<'a>>,
     /// The current text offset.
     offset: usize,
     /// The trimmed end of the current word.
@@ -1136,14 +1370,20 @@ impl Iterator for Breakpoints<'_> {
                 ) || self.end == self.p.bidi.text.len()
             });
 
+        // Don't break where it would violate the CJK line breaking rules, but
+        // treat both sides as a single word instead.
+        if !self.mandatory && self.prohibited(self.end) {
+            return self.next();
+        }
+
         // Hyphenate the next word.
         if self.p.hyphenate != Some(false) {
-            if let Some(lang) = self.lang(self.offset) {
-                let word = &self.p.bidi.text[self.offset..self.end];
-                let trimmed = word.trim_end_matches(|c: char| !c.is_alphabetic());
-                if !trimmed.is_empty() {
+            let word = &self.p.bidi.text[self.offset..self.end];
+            let trimmed = word.trim_end_matches(|c: char| !c.is_alphabetic());
+            if !trimmed.is_empty() {
+                if let Some(syllables) = self.syllables(self.offset, trimmed) {
                     self.suffix = self.offset + trimmed.len();
-                    self.syllables = Some(hypher::hyphenate(trimmed, lang));
+                    self.syllables = Some(syllables);
                     return self.next();
                 }
             }
@@ -1154,7 +1394,19 @@ impl Iterator for Breakpoints<'_> {
     }
 }
 
-impl Breakpoints<'_> {
+impl<'a> Breakpoints<'a> {
+    /// Whether breaking at the given offset is prohibited by the rules for
+    /// Chinese and Japanese line breaking (kinsoku shori): Lines may not start
+    /// with closing punctuation or small kana and may not end with opening
+    /// brackets.
+    fn prohibited(&self, offset: usize) -> bool {
+        let text = self.p.bidi.text;
+        let before = text[..offset].chars().next_back();
+        let after = text[offset..].chars().next();
+        after.map_or(false, |c| NO_BREAK_BEFORE.contains(c))
+            || before.map_or(false, |c| NO_BREAK_AFTER.contains(c))
+    }
+
     /// Whether hyphenation is enabled at the given offset.
     fn hyphenate(&self, offset: usize) -> bool {
         self.p
@@ -1166,15 +1418,46 @@ impl Breakpoints<'_> {
             .unwrap_or(false)
     }
 
-    /// The text language at the given offset.
-    fn lang(&self, offset: usize) -> Option<hypher::Lang> {
+    /// Split the word at the given offset into syllables, with the built-in
+    /// patterns for its language or custom patterns if there are none.
+    fn syllables(&self, offset: usize, word: &'a str) -> Option<Syllables<'a>> {
         let lang = self.p.lang.or_else(|| {
             let shaped = self.p.find(offset)?.text()?;
             Some(TextElem::lang_in(shaped.styles))
         })?;
 
-        let bytes = lang.as_str().as_bytes().try_into().ok()?;
-        hypher::Lang::from_iso(bytes)
+        let builtin = lang
+            .as_str()
+            .as_bytes()
+            .try_into()
+            .ok()
+            .and_then(hypher::Lang::from_iso);
+        if let Some(lang) = builtin {
+            return Some(Syllables::Builtin(hypher::hyphenate(word, lang)));
+        }
+
+        let shaped = self.p.find(offset)?.text()?;
+        let patterns = TextElem::hyphenation_patterns_in(shaped.styles)?;
+        Some(Syllables::Custom(patterns.hyphenate(word).into_iter()))
+    }
+}
+
+/// An iterator over the syllables of a word.
+enum Syllables<'a> {
+    /// Syllables determined by built-in patterns.
+    Builtin(hypher::Syllables<'a>),
+    /// Syllables determined by custom patterns.
+    Custom(std::vec::IntoIter<&'a str>),
+}
+
+impl<'a> Iterator for Syllables<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Builtin(syllables) => syllables.next(),
+            Self::Custom(syllables) => syllables.next(),
+        }
     }
 }
 
@@ -1307,7 +1590,7 @@ fn line<'a>(
         width += item.width();
     }
 
-    Line {
+    let mut line = Line {
         bidi: &p.bidi,
         trimmed: range,
         end,
@@ -1317,6 +1600,41 @@ fn line<'a>(
         width,
         justify,
         dash,
+    };
+
+    // Account for the spacing between CJK and Latin text in different items.
+    let items: Vec<_> = line.items().collect();
+    line.width += items
+        .windows(2)
+        .map(|pair| cjk_latin_spacing(pair[0], pair[1]))
+        .sum::<Abs>();
+    line
+}
+
+/// The spacing between two neighbouring items on a line if one ends with a CJK
+/// character and the other starts with a Latin letter or digit, or vice versa.
+fn cjk_latin_spacing(prev: &Item, next: &Item) -> Abs {
+    let (Some(a), Some(b)) = (prev.text(), next.text()) else { return Abs::zero() };
+    if !a.dir.is_positive() || !b.dir.is_positive() {
+        return Abs::zero();
+    }
+
+    let (Some(x), Some(y)) = (a.text.chars().next_back(), b.text.chars().next()) else {
+        return Abs::zero();
+    };
+
+    let cjk = if is_cjk_char(x) && is_latin_char(y) {
+        a
+    } else if is_latin_char(x) && is_cjk_char(y) {
+        b
+    } else {
+        return Abs::zero();
+    };
+
+    if TextElem::cjk_latin_spacing_in(cjk.styles).is_auto() {
+        Em::new(0.25).at(cjk.size)
+    } else {
+        Abs::zero()
     }
 }
 
@@ -1327,7 +1645,7 @@ fn finalize(
     lines: &[Line],
     region: Size,
     expand: bool,
-) -> SourceResult<Fragment> {
+) -> SourceResult<(Fragment, Vec<Abs>)> {
     // Determine the paragraph's width: Full width of the region if we
     // should expand or there's fractional spacing, fit-to-width otherwise.
     let width = if !region.x.is_finite()
@@ -1344,28 +1662,58 @@ fn finalize(
         .map(|line| commit(vt, p, line, width, region.y))
         .collect::<SourceResult<_>>()?;
 
-    // Prevent orphans.
-    let leading = ParElem::leading_in(p.styles);
-    if frames.len() >= 2 && !frames[1].is_empty() {
+    // Determine the spacing between each two consecutive lines.
+    let mut spacing: Vec<Abs> = frames
+        .windows(2)
+        .map(|pair| line_spacing(p.styles, &pair[0], &pair[1]))
+        .collect();
+
+    // Prevent orphans by keeping the first lines together.
+    for _ in 1..ParElem::orphans_in(p.styles).get() {
+        if frames.len() < 2 || frames[1].is_empty() {
+            break;
+        }
         let second = frames.remove(1);
         let first = &mut frames[0];
-        merge(first, second, leading);
+        merge(first, second, spacing.remove(0));
     }
 
-    // Prevent widows.
-    let len = frames.len();
-    if len >= 2 && !frames[len - 2].is_empty() {
+    // Prevent widows by keeping the last lines together.
+    for _ in 1..ParElem::widows_in(p.styles).get() {
+        let len = frames.len();
+        if len < 2 || frames[len - 2].is_empty() {
+            break;
+        }
         let second = frames.pop().unwrap();
         let first = frames.last_mut().unwrap();
-        merge(first, second, leading);
+        merge(first, second, spacing.pop().unwrap());
     }
 
-    Ok(Fragment::frames(frames))
+    Ok((Fragment::frames(frames), spacing))
+}
+
+/// Determine the spacing between two consecutive lines.
+fn line_spacing(styles: StyleChain, above: &Frame, below: &Frame) -> Abs {
+    let leading = ParElem::leading_in(styles);
+    if ParElem::leading_model_in(styles) == LeadingModel::Gap {
+        return leading;
+    }
+
+    // The extent of the lines' content between their baselines.
+    let extent = above.height() - above.baseline() + below.baseline();
+    let mut distance = leading;
+    if ParElem::grid_snap_in(styles) && leading > Abs::zero() {
+        while !distance.fits(extent) {
+            distance += leading;
+        }
+    }
+
+    (distance - extent).max(Abs::zero())
 }
 
 /// Merge two line frames
-fn merge(first: &mut Frame, second: Frame, leading: Abs) {
-    let offset = first.height() + leading;
+fn merge(first: &mut Frame, second: Frame, spacing: Abs) {
+    let offset = first.height() + spacing;
     let total = offset + second.height();
     first.push_frame(Point::with_y(offset), second);
     first.size_mut().y = total;
@@ -1388,14 +1736,14 @@ fn commit(
         offset += p.hang;
     }
 
-    // Handle hanging punctuation to the left.
-    if let Some(Item::Text(text)) = reordered.first() {
+    // Handle hanging punctuation to the left. In a right-to-left line, the
+    // left edge is the end of the line.
+    if let Some((_, Item::Text(text))) = reordered.first() {
         if let Some(glyph) = text.glyphs.first() {
-            if !text.dir.is_positive()
-                && TextElem::overhang_in(text.styles)
-                && (reordered.len() > 1 || text.glyphs.len() > 1)
-            {
-                let amount = overhang(glyph.c) * glyph.x_advance.at(text.size);
+            if reordered.len() > 1 || text.glyphs.len() > 1 {
+                let (start, end) = TextElem::overhang_in(text.styles).factors(glyph.c);
+                let factor = if text.dir.is_positive() { start } else { end };
+                let amount = factor * glyph.x_advance.at(text.size);
                 offset -= amount;
                 remaining += amount;
             }
@@ -1403,13 +1751,12 @@ fn commit(
     }
 
     // Handle hanging punctuation to the right.
-    if let Some(Item::Text(text)) = reordered.last() {
+    if let Some((_, Item::Text(text))) = reordered.last() {
         if let Some(glyph) = text.glyphs.last() {
-            if text.dir.is_positive()
-                && TextElem::overhang_in(text.styles)
-                && (reordered.len() > 1 || text.glyphs.len() > 1)
-            {
-                let amount = overhang(glyph.c) * glyph.x_advance.at(text.size);
+            if reordered.len() > 1 || text.glyphs.len() > 1 {
+                let (start, end) = TextElem::overhang_in(text.styles).factors(glyph.c);
+                let factor = if text.dir.is_positive() { end } else { start };
+                let amount = factor * glyph.x_advance.at(text.size);
                 remaining += amount;
             }
         }
@@ -1450,12 +1797,17 @@ fn commit(
 
     // Build the frames and determine the height and baseline.
     let mut frames = vec![];
-    for item in reordered {
+    let mut prev = None;
+    for (key, item) in reordered {
+        if let Some(prev) = prev.replace(item) {
+            offset += cjk_latin_spacing(prev, item);
+        }
+
         let mut push = |offset: &mut Abs, frame: Frame| {
             let width = frame.width();
             top.set_max(frame.baseline());
             bottom.set_max(frame.size().y - frame.baseline());
-            frames.push((*offset, frame));
+            frames.push((key, *offset, frame));
             *offset += width;
         };
 
@@ -1494,8 +1846,11 @@ fn commit(
     let mut output = Frame::new(size);
     output.set_baseline(top);
 
-    // Construct the line's frame.
-    for (offset, frame) in frames {
+    // Construct the line's frame. The frames are positioned in visual order,
+    // but added in logical order so that text extracted from the frame is
+    // in reading order.
+    frames.sort_by_key(|&(key, ..)| key);
+    for (_, offset, frame) in frames {
         let x = offset + p.align.position(remaining);
         let y = top - frame.baseline();
         output.push_frame(Point::new(x, y), frame);
@@ -1505,12 +1860,16 @@ fn commit(
 }
 
 /// Return a line's items in visual order.
-fn reorder<'a>(line: &'a Line<'a>) -> (Vec<&Item<'a>>, bool) {
+///
+/// Each item comes with a key by which the items can be sorted back into
+/// logical order.
+fn reorder<'a>(line: &'a Line<'a>) -> (Vec<((usize, usize), &Item<'a>)>, bool) {
     let mut reordered = vec![];
 
     // The bidi crate doesn't like empty lines.
     if line.trimmed.is_empty() {
-        return (line.slice(line.trimmed.clone()).collect(), false);
+        let items = line.slice(line.trimmed.clone()).enumerate();
+        return (items.map(|(i, item)| ((0, i), item)).collect(), false);
     }
 
     // Find the paragraph that contains the line.
@@ -1533,8 +1892,11 @@ fn reorder<'a>(line: &'a Line<'a>) -> (Vec<&Item<'a>>, bool) {
             continue;
         }
 
+        // Runs cover disjoint ranges of the text, so their starts together
+        // with the items' positions within them order the items logically.
         let prev = reordered.len();
-        reordered.extend(line.slice(run.clone()));
+        let items = line.slice(run.clone()).enumerate();
+        reordered.extend(items.map(|(i, item)| ((run.start, i), item)));
 
         if levels[run.start].is_rtl() {
             reordered[prev..].reverse();
@@ -1544,23 +1906,36 @@ fn reorder<'a>(line: &'a Line<'a>) -> (Vec<&Item<'a>>, bool) {
     (reordered, starts_rtl)
 }
 
-/// How much a character should hang into the end margin.
-///
-/// For more discussion, see:
-/// https://recoveringphysicist.com/21/
-fn overhang(c: char) -> f64 {
-    match c {
-        // Dashes.
-        '–' | '—' => 0.2,
-        '-' => 0.55,
-
-        // Punctuation.
-        '.' | ',' => 0.8,
-        ':' | ';' => 0.3,
-
-        // Arabic
-        '\u{60C}' | '\u{6D4}' => 0.4,
-
-        _ => 0.0,
+#[cfg(test)]
+mod tests {
+    use typst::eval::Tracer;
+    use typst::font::Font;
+    use typst::util::Bytes;
+
+    use crate::sandbox::SandboxWorld;
+    use crate::testing::fonts;
+
+    #[test]
+    fn test_text_logical_order() {
+        let hebrew = include_bytes!("../../../assets/fonts/NotoSerifHebrew-Regular.ttf");
+        let fonts = fonts().into_iter().chain(Font::iter(Bytes::from_static(hebrew)));
+        let world = SandboxWorld::new(
+            "#set page(width: 200pt)\n\
+             #set text(font: (\"Linux Libertine\", \"Noto Serif Hebrew\"))\n\
+             one שלום עולם two\n\n\
+             #set text(dir: rtl)\n\
+             שלום abc def עולם",
+        )
+        .with_fonts(fonts);
+        let mut tracer = Tracer::default();
+        let document = typst::compile(&world, &mut tracer).unwrap();
+
+        // The runs are placed in visual order, but extracted in the order in
+        // which they were written, in both left-to-right and right-to-left
+        // paragraphs.
+        assert_eq!(
+            typst::export::text(&document),
+            "one שלום עולם two\n\nשלום abc def עולם",
+        );
     }
 }
@@ -6,6 +6,9 @@ use crate::prelude::*;
 /// always relative to its parent container and will be in the foreground of all
 /// other content in the container. Page margins will be respected.
 ///
+/// Floating content is different: It is moved to the top or bottom of the
+/// region (for example, the page) and the other content flows around the space
+/// it takes up.
 ///
 /// ## Example { #example }
 /// ```example
@@ -24,12 +27,20 @@ use crate::prelude::*;
 /// Display: Place
 /// Category: layout
 #[element(Layout, Behave)]
+#[scope(
+    scope.define("flush", FlushElem::func());
+    scope
+)]
 pub struct PlaceElem {
     /// Relative to which position in the parent container to place the content.
     ///
     /// When an axis of the page is `{auto}` sized, all alignments relative to that
     /// axis will be ignored, instead, the item will be placed in the origin of the
     /// axis.
+    ///
+    /// Floating content can only be aligned to the `{top}` or `{bottom}`. If it
+    /// has no vertical alignment, it is placed at the top or the bottom,
+    /// whichever is closer to where it was written.
     #[positional]
     #[default(Axes::with_x(Some(GenAlign::Start)))]
     pub alignment: Axes<Option<GenAlign>>,
@@ -48,6 +59,30 @@ pub struct PlaceElem {
     /// The vertical displacement of the placed content.
     pub dy: Rel<Length>,
 
+    /// Whether the content floats to the top or bottom of its region.
+    ///
+    /// Floating content takes up space in the flow, but not where it was
+    /// written. If it doesn't fit into the current region, it is moved to the
+    /// next one. Floating content keeps the order in which it was written.
+    ///
+    /// ```example
+    /// #set page(height: 140pt)
+    /// #place(
+    ///   top,
+    ///   float: true,
+    ///   rect(width: 100%, height: 30pt, fill: aqua),
+    /// )
+    /// This text is below the floating rectangle, even though the rectangle
+    /// was written before it.
+    /// ```
+    #[default(false)]
+    pub float: bool,
+
+    /// The amount of clearance between floating content and the other content.
+    #[default(Em::new(1.5).into())]
+    #[resolve]
+    pub clearance: Length,
+
     /// The content to place.
     #[required]
     pub body: Content,
@@ -62,26 +97,44 @@ impl Layout for PlaceElem {
         regions: Regions,
     ) -> SourceResult<Fragment> {
         let out_of_flow = self.out_of_flow(styles);
+        let float = self.float(styles);
+        let alignment = self.alignment(styles);
+
+        if float
+            && alignment.y.map_or(false, |align| {
+                !matches!(align, GenAlign::Specific(Align::Top | Align::Bottom))
+            })
+        {
+            bail!(self.span(), "floating placement must be `auto`, `top`, or `bottom`");
+        }
 
         // The pod is the base area of the region because for absolute
-        // placement we don't really care about the already used area.
+        // placement we don't really care about the already used area. Floating
+        // content spans the full width, but only takes up the height it needs.
         let pod = {
             let finite = regions.base().map(Abs::is_finite);
-            let expand = finite & (regions.expand | out_of_flow);
+            let expand = if float {
+                Axes::new(finite.x, false)
+            } else {
+                finite & (regions.expand | out_of_flow)
+            };
             Regions::one(regions.base(), expand)
         };
 
         let child = self
             .body()
             .moved(Axes::new(self.dx(styles), self.dy(styles)))
-            .aligned(self.alignment(styles));
+            .aligned(alignment);
 
         let mut frame = child.layout(vt, styles, pod)?.into_frame();
 
         // If expansion is off, zero all sizes so that we don't take up any
-        // space in our parent. Otherwise, respect the expand settings.
-        let target = regions.expand.select(regions.size, Size::zero());
-        frame.resize(target, Align::LEFT_TOP);
+        // space in our parent. Otherwise, respect the expand settings. Floating
+        // content keeps its size, as the flow makes space for it.
+        if !float {
+            let target = regions.expand.select(regions.size, Size::zero());
+            frame.resize(target, Align::LEFT_TOP);
+        }
 
         Ok(Fragment::frame(frame))
     }
@@ -92,7 +145,7 @@ impl PlaceElem {
     /// base origin. Instead of relative to the parent's current flow/cursor
     /// position.
     pub fn out_of_flow(&self, styles: StyleChain) -> bool {
-        self.alignment(styles).y.is_some()
+        self.alignment(styles).y.is_some() && !self.float(styles)
     }
 }
 
@@ -101,3 +154,31 @@ impl Behave for PlaceElem {
         Behaviour::Ignorant
     }
 }
+
+/// Asks the layout algorithm to place pending floating content before
+/// continuing with the content that follows.
+///
+/// This is useful for preventing floating figures from ending up after the
+/// start of a new section.
+///
+/// ## Example { #example }
+/// ```example
+/// #set page(height: 165pt, width: 150pt)
+///
+/// Some introductory text: #lorem(15)
+///
+/// #figure(
+///   rect(width: 100%, height: 64pt),
+///   placement: auto,
+///   caption: [A rectangle],
+/// )
+///
+/// #place.flush()
+///
+/// This text appears after the figure.
+/// ```
+///
+/// Display: Flush
+/// Category: layout
+#[element]
+pub struct FlushElem {}
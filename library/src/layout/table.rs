@@ -1,9 +1,13 @@
+use std::ops::Range;
+
 use typst::eval::{CastInfo, Reflect};
 
-use crate::layout::{AlignElem, GridLayouter, TrackSizings};
+use crate::layout::{place_cells, AlignElem, Cell, GridLayouter, Sizing, TrackSizings};
 use crate::meta::{Figurable, LocalName};
 use crate::prelude::*;
 
+use super::grid::spanned_children;
+
 /// A table of items.
 ///
 /// Tables are used to arrange content in cells. Cells can contain arbitrary
@@ -15,6 +19,13 @@ use crate::prelude::*;
 /// To give a table a caption and make it [referenceable]($func/ref), put it
 /// into a [figure]($func/figure).
 ///
+/// Cells can span multiple columns or rows. To create such a cell, use the
+/// [`table.cell`]($func/table.cell) function.
+///
+/// When a table breaks across pages or columns, its header and footer rows are
+/// repeated in each of them. Use the [`table.header`]($func/table.header) and
+/// [`table.footer`]($func/table.footer) functions to mark them.
+///
 /// ## Example { #example }
 /// ```example
 /// #table(
@@ -38,13 +49,20 @@ use crate::prelude::*;
 /// Display: Table
 /// Category: layout
 #[element(Layout, LocalName, Figurable)]
+#[scope(
+    scope.define("cell", TableCell::func());
+    scope.define("header", TableHeader::func());
+    scope.define("footer", TableFooter::func());
+    scope
+)]
 pub struct TableElem {
     /// The column sizes. See the [grid documentation]($func/grid) for more
     /// information on track sizing.
     pub columns: TrackSizings,
 
     /// The row sizes. See the [grid documentation]($func/grid) for more
-    /// information on track sizing.
+    /// information on track sizing. The sizes only apply to the rows between
+    /// the header and the footer, which are always sized automatically.
     pub rows: TrackSizings,
 
     /// The gaps between rows & columns. See the [grid
@@ -69,7 +87,10 @@ pub struct TableElem {
     ///
     /// This can be a color or a function that returns a color. The function is
     /// passed the cells' column and row index, starting at zero. This can be
-    /// used to implement striped tables.
+    /// used to implement striped tables. A cell spanning multiple columns or
+    /// rows is filled with the color for its first column and row. Rows in the
+    /// header and footer are counted separately from the other rows, so that
+    /// stripes don't depend on whether a table has a header.
     ///
     /// ```example
     /// #table(
@@ -92,6 +113,7 @@ pub struct TableElem {
     /// This can either be a single alignment, an array of alignments
     /// (corresponding to each column) or a function that returns an alignment.
     /// The function is passed the cells' column and row index, starting at zero.
+    /// Like for the fill, rows in the header and footer are counted separately.
     /// If set to `{auto}`, the outer alignment is used.
     ///
     /// ```example
@@ -107,15 +129,40 @@ pub struct TableElem {
     /// How to stroke the cells.
     ///
     /// See the [line's documentation]($func/line.stroke) for more details.
-    /// Strokes can be disabled by setting this to `{none}`.
+    /// Strokes can be disabled by setting this to `{none}`. Cells spanning
+    /// multiple columns or rows are only stroked around their outside.
+    ///
+    /// To stroke the sides of the cells differently, pass a dictionary with
+    /// the keys `left`, `top`, `right`, `bottom`, `x`, `y`, or `rest`. Sides
+    /// that are left out are not stroked. Like for the fill, this can also be
+    /// an array with an entry for each column or a function that is passed the
+    /// cells' column and row index and returns a stroke or a dictionary.
     ///
-    /// _Note:_ Richer stroke customization for individual cells is not yet
-    /// implemented, but will be in the future. In the meantime, you can use
-    /// the third-party [tablex library](https://github.com/PgBiel/typst-tablex/).
-    #[resolve]
+    /// Where two cells share an edge, only a single line is drawn. The stroke
+    /// of the cell below or to the right takes precedence, unless that cell
+    /// has no stroke on the shared side.
+    ///
+    /// ```example
+    /// #table(
+    ///   columns: 3,
+    ///   stroke: (x: none, bottom: 0.5pt),
+    ///   [*Name*], [*Size*], [*Color*],
+    ///   [Apple], [Medium], [Red],
+    ///   [Grape], [Small], [Green],
+    /// )
+    ///
+    /// #table(
+    ///   columns: 3,
+    ///   stroke: (col, row) => if row == 0 {
+    ///     (bottom: (paint: blue, thickness: 1pt, dash: "dashed"))
+    ///   },
+    ///   [*Name*], [*Size*], [*Color*],
+    ///   [Apple], [Medium], [Red],
+    /// )
+    /// ```
     #[fold]
-    #[default(Some(PartialStroke::default()))]
-    pub stroke: Option<PartialStroke>,
+    #[default(Celled::Value(Sides::splat(Some(Some(PartialStroke::default())))))]
+    pub stroke: Celled<Sides<Option<Option<PartialStroke>>>>,
 
     /// How much to pad the cells' content.
     #[default(Abs::pt(5.0).into())]
@@ -123,6 +170,7 @@ pub struct TableElem {
 
     /// The contents of the table cells.
     #[variadic]
+    #[parse(spanned_children(args)?)]
     pub children: Vec<Content>,
 }
 
@@ -140,76 +188,123 @@ impl Layout for TableElem {
         let tracks = Axes::new(self.columns(styles).0, self.rows(styles).0);
         let gutter = Axes::new(self.column_gutter(styles).0, self.row_gutter(styles).0);
         let cols = tracks.x.len().max(1);
-        let cells: Vec<_> = self
-            .children()
-            .into_iter()
-            .enumerate()
-            .map(|(i, child)| {
-                let mut child = child.padded(Sides::splat(inset));
-
-                let x = i % cols;
-                let y = i / cols;
-                if let Smart::Custom(alignment) = align.resolve(vt, x, y)? {
-                    child = child.styled(AlignElem::set_alignment(alignment));
+
+        // Separate the header and footer from the other cells.
+        let mut header = None;
+        let mut footer = None;
+        let mut body = vec![];
+        for child in self.children() {
+            if let Some(elem) = child.to::<TableHeader>() {
+                if header.is_some() {
+                    bail!(child.span(), "table can only have one header");
+                } else if !body.is_empty() || footer.is_some() {
+                    bail!(child.span(), "header must come before all other cells");
+                }
+                header = Some(elem.children());
+            } else if let Some(elem) = child.to::<TableFooter>() {
+                if footer.is_some() {
+                    bail!(child.span(), "table can only have one footer");
                 }
+                footer = Some(elem.children());
+            } else if footer.is_some() {
+                bail!(child.span(), "footer must come after all other cells");
+            } else {
+                body.push(child);
+            }
+        }
 
-                Ok(child)
-            })
-            .collect::<SourceResult<_>>()?;
+        // Place the cells of each section, relative to the section.
+        let mut sections = vec![];
+        for children in [header.unwrap_or_default(), body, footer.unwrap_or_default()] {
+            let mut cells = vec![];
+            for child in children {
+                let span = child.span();
+                if child.is::<TableHeader>() || child.is::<TableFooter>() {
+                    bail!(span, "cannot nest headers and footers");
+                }
+
+                cells.push(match child.to::<TableCell>() {
+                    Some(cell) => {
+                        (cell.body(), cell.colspan(styles), cell.rowspan(styles), span)
+                    }
+                    None => (child, NonZeroUsize::ONE, NonZeroUsize::ONE, span),
+                });
+            }
+            sections.push(place_cells(cells, cols)?);
+        }
+
+        let count = |cells: &[Cell]| cells.iter().map(|cell| cell.y + cell.rowspan).max();
+        let hk = count(&sections[0]).unwrap_or(0);
+        let fk = count(&sections[2]).unwrap_or(0);
+        let bk = count(&sections[1]).unwrap_or(0).max(tracks.y.len());
+
+        // The row sizes only apply to body rows. Header and footer rows are
+        // sized automatically.
+        let mut rows = vec![Sizing::Auto; hk];
+        rows.extend((0..bk).map(|y| {
+            tracks.y.get(y).or(tracks.y.last()).copied().unwrap_or(Sizing::Auto)
+        }));
+        rows.extend(std::iter::repeat(Sizing::Auto).take(fk));
+
+        let mut cells = vec![];
+        for (section, offset) in sections.into_iter().zip([0, hk, hk + bk]) {
+            for mut cell in section {
+                let mut body = std::mem::take(&mut cell.body).padded(Sides::splat(inset));
+                if let Smart::Custom(alignment) = align.resolve(vt, cell.x, cell.y)? {
+                    body = body.styled(AlignElem::set_alignment(alignment));
+                }
+                cell.body = body;
+                cell.y += offset;
+                cells.push(cell);
+            }
+        }
 
         let fill = self.fill(styles);
-        let stroke = self.stroke(styles).map(PartialStroke::unwrap_or_default);
+        let stroke = self.stroke(styles);
 
         // Prepare grid layout by unifying content and gutter tracks.
         let layouter = GridLayouter::new(
-            tracks.as_deref(),
+            Axes::new(tracks.x.as_slice(), rows.as_slice()),
             gutter.as_deref(),
             &cells,
             regions,
             styles,
-        );
+        )
+        .repeat(hk, fk, self.span());
 
         // Measure the columns and layout the grid row-by-row.
         let mut layout = layouter.layout(vt)?;
 
-        // Add lines and backgrounds.
+        // Add lines. Rows are counted relative to their section, like for the
+        // fill.
+        let body = layout.body.clone();
+        layout.stroke_lines(self.span(), |x, y| {
+            let y = section_row(y, &body);
+            let sides = stroke.resolve(vt, x, y)?.resolve(styles);
+            Ok(sides.map(|side| side.flatten().map(PartialStroke::unwrap_or_default)))
+        })?;
+
+        // Add backgrounds.
         for (frame, rows) in layout.fragment.iter_mut().zip(&layout.rows) {
             if layout.cols.is_empty() || rows.is_empty() {
                 continue;
             }
 
-            // Render table lines.
-            if let Some(stroke) = &stroke {
-                let thickness = stroke.thickness;
-                let half = thickness / 2.0;
-
-                // Render horizontal lines.
-                for offset in points(rows.iter().map(|piece| piece.height)) {
-                    let target = Point::with_x(frame.width() + thickness);
-                    let hline = Geometry::Line(target).stroked(stroke.clone());
-                    frame.prepend(
-                        Point::new(-half, offset),
-                        FrameItem::Shape(hline, self.span()),
-                    );
-                }
-
-                // Render vertical lines.
-                for offset in points(layout.cols.iter().copied()) {
-                    let target = Point::with_y(frame.height() + thickness);
-                    let vline = Geometry::Line(target).stroked(stroke.clone());
-                    frame.prepend(
-                        Point::new(offset, -half),
-                        FrameItem::Shape(vline, self.span()),
-                    );
-                }
-            }
+            let covering = |x: usize, y: usize| layout.slots[y * layout.cols.len() + x];
 
-            // Render cell backgrounds.
+            // Render cell backgrounds. Slots covered by a cell spanning
+            // multiple tracks use the fill of the cell's first track. Rows are
+            // counted relative to their section.
             let mut dx = Abs::zero();
             for (x, &col) in layout.cols.iter().enumerate() {
                 let mut dy = Abs::zero();
                 for row in rows {
-                    if let Some(fill) = fill.resolve(vt, x, row.y)? {
+                    let (fx, fy) = match covering(x, row.y) {
+                        Some(i) => (layout.spans[i].x.start, layout.spans[i].y.start),
+                        None => (x, row.y),
+                    };
+
+                    if let Some(fill) = fill.resolve(vt, fx, section_row(fy, &body))? {
                         let pos = Point::new(dx, dy);
                         let size = Size::new(col, row.height);
                         let rect = Geometry::Rect(size).filled(fill);
@@ -225,16 +320,107 @@ impl Layout for TableElem {
     }
 }
 
-/// Turn an iterator of extents into an iterator of offsets before, in between,
-/// and after the extents, e.g. [10mm, 5mm] -> [0mm, 10mm, 15mm].
-fn points(extents: impl IntoIterator<Item = Abs>) -> impl Iterator<Item = Abs> {
-    let mut offset = Abs::zero();
-    std::iter::once(Abs::zero())
-        .chain(extents.into_iter())
-        .map(move |extent| {
-            offset += extent;
-            offset
-        })
+/// The index of a row relative to its section, given the range of the body
+/// rows.
+fn section_row(y: usize, body: &Range<usize>) -> usize {
+    if y < body.start {
+        y
+    } else if y < body.end {
+        y - body.start
+    } else {
+        y - body.end
+    }
+}
+
+/// A cell in a table that spans multiple columns or rows.
+///
+/// See the [grid cell's documentation]($func/grid.cell) for details on how
+/// spanning cells are laid out.
+///
+/// ```example
+/// #table(
+///   columns: 3,
+///   table.cell(colspan: 2)[*Name*], [*Age*],
+///   [Ada], [Lovelace], [36],
+///   table.cell(rowspan: 2)[Ernst], [Abbe], [64],
+///   [Mach], [78],
+/// )
+/// ```
+///
+/// Display: Table Cell
+/// Category: layout
+#[element(Show)]
+pub struct TableCell {
+    /// The cell's body.
+    #[required]
+    pub body: Content,
+
+    /// The number of columns the cell spans.
+    #[default(NonZeroUsize::ONE)]
+    pub colspan: NonZeroUsize,
+
+    /// The number of rows the cell spans.
+    #[default(NonZeroUsize::ONE)]
+    pub rowspan: NonZeroUsize,
+}
+
+impl Show for TableCell {
+    fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        Ok(self.body())
+    }
+}
+
+/// The header of a table, repeated at the top of each page or column the table
+/// spans.
+///
+/// The header must come before all other cells of the table. It is laid out
+/// only once, so its height must not depend on where it ends up.
+///
+/// ```example
+/// #set page(height: 120pt)
+/// #table(
+///   columns: 2,
+///   table.header[*Name*][*Value*],
+///   ..range(8).map(n => ([Item #n], [#(n * n)])).flatten(),
+/// )
+/// ```
+///
+/// Display: Table Header
+/// Category: layout
+#[element(Show)]
+pub struct TableHeader {
+    /// The cells of the header rows.
+    #[variadic]
+    #[parse(spanned_children(args)?)]
+    pub children: Vec<Content>,
+}
+
+impl Show for TableHeader {
+    fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        bail!(self.span(), "header can only be used as a direct child of a table")
+    }
+}
+
+/// The footer of a table, repeated at the bottom of each page or column the
+/// table spans.
+///
+/// The footer must come after all other cells of the table. Like the
+/// [header]($func/table.header), it is laid out only once.
+///
+/// Display: Table Footer
+/// Category: layout
+#[element(Show)]
+pub struct TableFooter {
+    /// The cells of the footer rows.
+    #[variadic]
+    #[parse(spanned_children(args)?)]
+    pub children: Vec<Content>,
+}
+
+impl Show for TableFooter {
+    fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        bail!(self.span(), "footer can only be used as a direct child of a table")
+    }
 }
 
 /// A value that can be configured per cell.
@@ -263,6 +449,26 @@ impl<T: Default + Clone + FromValue> Celled<T> {
     }
 }
 
+impl Fold for Celled<Sides<Option<Option<PartialStroke>>>> {
+    type Output = Self;
+
+    fn fold(self, outer: Self::Output) -> Self::Output {
+        // Only strokes given for the same side are folded. A side that isn't
+        // given stays unstroked instead of falling back to the outer stroke.
+        match (self, outer) {
+            (Self::Value(inner), Self::Value(outer)) => {
+                Self::Value(inner.zip(outer).map(|(inner, outer)| match (inner, outer) {
+                    (Some(Some(inner)), Some(Some(outer))) => {
+                        Some(Some(inner.fold(outer)))
+                    }
+                    (inner, _) => inner,
+                }))
+            }
+            (inner, _) => inner,
+        }
+    }
+}
+
 impl<T: Default> Default for Celled<T> {
     fn default() -> Self {
         Self::Value(T::default())
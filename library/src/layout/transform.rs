@@ -56,7 +56,7 @@ impl Layout for MoveElem {
 /// Rotates content without affecting layout.
 ///
 /// Rotates an element by a given angle. The layout will act as if the element
-/// was not rotated.
+/// was not rotated, unless the `reflow` argument is set.
 ///
 /// ## Example { #example }
 /// ```example
@@ -101,6 +101,18 @@ pub struct RotateElem {
     #[default(Align::CENTER_HORIZON)]
     pub origin: Axes<Option<GenAlign>>,
 
+    /// Whether the rotation impacts the layout.
+    ///
+    /// If set to `{false}`, the rotated content will retain the bounding box of
+    /// the original content. If set to `{true}`, the bounding box will take the
+    /// rotation of the content into account and adjust the layout accordingly.
+    ///
+    /// ```example
+    /// Hello #rotate(90deg, reflow: true)[World]!
+    /// ```
+    #[default(false)]
+    pub reflow: bool,
+
     /// The content to rotate.
     #[required]
     pub body: Content,
@@ -114,21 +126,23 @@ impl Layout for RotateElem {
         styles: StyleChain,
         regions: Regions,
     ) -> SourceResult<Fragment> {
-        let pod = Regions::one(regions.base(), Axes::splat(false));
-        let mut frame = self.body().layout(vt, styles, pod)?.into_frame();
-        let Axes { x, y } =
-            self.origin(styles).zip(frame.size()).map(|(o, s)| o.position(s));
-        let ts = Transform::translate(x, y)
-            .pre_concat(Transform::rotate(self.angle(styles)))
-            .pre_concat(Transform::translate(-x, -y));
-        frame.transform(ts);
-        Ok(Fragment::frame(frame))
+        layout_transformed(
+            vt,
+            &self.body(),
+            styles,
+            regions,
+            self.origin(styles),
+            Transform::rotate(self.angle(styles)),
+            self.reflow(styles),
+        )
     }
 }
 
 /// Scales content without affecting layout.
 ///
 /// Lets you mirror content by specifying a negative scale on a single axis.
+/// The layout will act as if the element was not scaled, unless the `reflow`
+/// argument is set.
 ///
 /// ## Example { #example }
 /// ```example
@@ -168,6 +182,18 @@ pub struct ScaleElem {
     #[default(Align::CENTER_HORIZON)]
     pub origin: Axes<Option<GenAlign>>,
 
+    /// Whether the scaling impacts the layout.
+    ///
+    /// If set to `{false}`, the scaled content will be allowed to overlap
+    /// other content. If set to `{true}`, it will compute the new size of the
+    /// scaled content and adjust the layout accordingly.
+    ///
+    /// ```example
+    /// Hello #scale(x: 20%, y: 40%, reflow: true)[World]!
+    /// ```
+    #[default(false)]
+    pub reflow: bool,
+
     /// The content to scale.
     #[required]
     pub body: Content,
@@ -181,14 +207,140 @@ impl Layout for ScaleElem {
         styles: StyleChain,
         regions: Regions,
     ) -> SourceResult<Fragment> {
-        let pod = Regions::one(regions.base(), Axes::splat(false));
-        let mut frame = self.body().layout(vt, styles, pod)?.into_frame();
-        let Axes { x, y } =
-            self.origin(styles).zip(frame.size()).map(|(o, s)| o.position(s));
-        let transform = Transform::translate(x, y)
-            .pre_concat(Transform::scale(self.x(styles), self.y(styles)))
-            .pre_concat(Transform::translate(-x, -y));
-        frame.transform(transform);
-        Ok(Fragment::frame(frame))
+        layout_transformed(
+            vt,
+            &self.body(),
+            styles,
+            regions,
+            self.origin(styles),
+            Transform::scale(self.x(styles), self.y(styles)),
+            self.reflow(styles),
+        )
+    }
+}
+
+/// Skews content without affecting layout.
+///
+/// Slants an element along the horizontal and vertical axes. The layout will
+/// act as if the element was not skewed, unless the `reflow` argument is set.
+///
+/// ## Example { #example }
+/// ```example
+/// #skew(x: -12deg)[
+///   This is some fake italic text.
+/// ]
+/// ```
+///
+/// Display: Skew
+/// Category: layout
+#[element(Layout)]
+pub struct SkewElem {
+    /// The angle by which the horizontal axis is slanted. Positive angles
+    /// move the bottom of the content to the right.
+    #[default(Angle::zero())]
+    pub x: Angle,
+
+    /// The angle by which the vertical axis is slanted. Positive angles move
+    /// the right side of the content down.
+    #[default(Angle::zero())]
+    pub y: Angle,
+
+    /// The origin of the skew transformation.
+    ///
+    /// ```example
+    /// X#box(skew(x: 30deg, origin: bottom + left)[X])X \
+    /// X#box(skew(x: 30deg, origin: top + left)[X])X
+    /// ```
+    #[resolve]
+    #[fold]
+    #[default(Align::CENTER_HORIZON)]
+    pub origin: Axes<Option<GenAlign>>,
+
+    /// Whether the skew transformation impacts the layout.
+    ///
+    /// If set to `{false}`, the skewed content will retain the bounding box of
+    /// the original content. If set to `{true}`, the bounding box will take
+    /// the transformation of the content into account and adjust the layout
+    /// accordingly.
+    ///
+    /// ```example
+    /// Hello #skew(y: 30deg, reflow: true)[World]!
+    /// ```
+    #[default(false)]
+    pub reflow: bool,
+
+    /// The content to skew.
+    #[required]
+    pub body: Content,
+}
+
+impl Layout for SkewElem {
+    #[tracing::instrument(name = "SkewElem::layout", skip_all)]
+    fn layout(
+        &self,
+        vt: &mut Vt,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Fragment> {
+        layout_transformed(
+            vt,
+            &self.body(),
+            styles,
+            regions,
+            self.origin(styles),
+            Transform::skew(self.x(styles), self.y(styles)),
+            self.reflow(styles),
+        )
+    }
+}
+
+/// Lay out the body and apply a transform around the origin to it.
+///
+/// Without reflow, the resulting frame has the size of the untransformed body.
+/// With reflow, it has the size of the transformed body's bounding box, into
+/// which the transformed body is moved. The origin thus doesn't change where
+/// reflowed content ends up.
+fn layout_transformed(
+    vt: &mut Vt,
+    body: &Content,
+    styles: StyleChain,
+    regions: Regions,
+    origin: Axes<Align>,
+    transform: Transform,
+    reflow: bool,
+) -> SourceResult<Fragment> {
+    let pod = Regions::one(regions.base(), Axes::splat(false));
+    let mut frame = body.layout(vt, styles, pod)?.into_frame();
+    let size = frame.size();
+    let Axes { x, y } = origin.zip(size).map(|(o, s)| o.position(s));
+    let ts = Transform::translate(x, y)
+        .pre_concat(transform)
+        .pre_concat(Transform::translate(-x, -y));
+
+    if !reflow {
+        frame.transform(ts);
+        return Ok(Fragment::frame(frame));
+    }
+
+    // Compute the bounding box of the transformed body.
+    let mut min = Point::splat(Abs::inf());
+    let mut max = Point::splat(-Abs::inf());
+    let corners =
+        [Point::zero(), Point::with_x(size.x), Point::with_y(size.y), size.to_point()];
+    for corner in corners {
+        let p = corner.transform(ts);
+        min = min.min(p);
+        max = max.max(p);
+    }
+
+    // Move the transformed body into its bounding box. The baseline of the
+    // body has no meaning after the transform, so it is not retained.
+    let mut output = Frame::new((max - min).to_size());
+    if !frame.is_empty() {
+        let mut group = GroupItem::new(frame);
+        group.transform = Transform::translate(-min.x, -min.y).pre_concat(ts);
+        output.push(Point::zero(), FrameItem::Group(group));
     }
+
+    Ok(Fragment::frame(output))
 }
@@ -9,28 +9,42 @@ pub mod layout;
 pub mod math;
 pub mod meta;
 pub mod prelude;
+pub mod sandbox;
 pub mod shared;
 pub mod symbols;
 pub mod text;
 pub mod visualize;
 
+#[cfg(test)]
+mod testing;
+
 use typst::diag::At;
-use typst::eval::{LangItems, Library, Module, Scope};
-use typst::geom::Smart;
+use typst::eval::{Dict, LangItems, Library, Module, Scope, Str, Value};
+use typst::geom::{Abs, Color, Corners, Sides, Smart};
 use typst::model::{Element, Styles};
 
 use self::layout::LayoutRoot;
 
 /// Construct the standard library.
 pub fn build() -> Library {
+    build_with_inputs([])
+}
+
+/// Construct the standard library with inputs from the host application.
+///
+/// Documents can read the inputs as a dictionary of strings through
+/// `sys.inputs`. Since the inputs are part of the library, changing them
+/// invalidates everything that was computed with the old library.
+pub fn build_with_inputs(inputs: impl IntoIterator<Item = (Str, Str)>) -> Library {
+    let inputs: Dict = inputs.into_iter().map(|(k, v)| (k, Value::Str(v))).collect();
     let math = math::module();
-    let global = global(math.clone());
+    let global = global(math.clone(), inputs);
     Library { global, math, styles: styles(), items: items() }
 }
 
 /// Construct the module with global definitions.
 #[tracing::instrument(skip_all)]
-fn global(math: Module) -> Module {
+fn global(math: Module, inputs: Dict) -> Module {
     let mut global = Scope::deduplicating();
 
     // Categories.
@@ -41,6 +55,7 @@ fn global(math: Module) -> Module {
     compute::define(&mut global);
     symbols::define(&mut global);
     global.define("math", math);
+    global.define("sys", compute::sys_module(inputs));
 
     Module::new("global").with_scope(global)
 }
@@ -127,6 +142,18 @@ fn items() -> LangItems {
         math_root: |index, radicand| {
             math::RootElem::new(radicand).with_index(index).pack()
         },
+        error_placeholder: |message, span| {
+            let text = text::TextElem::packed(message)
+                .spanned(span)
+                .styled(text::TextElem::set_fill(Color::WHITE.into()));
+            layout::BoxElem::new()
+                .with_body(Some(text))
+                .with_fill(Some(Color::RED.into()))
+                .with_inset(Sides::splat(Some(Abs::pt(2.0).into())))
+                .with_radius(Corners::splat(Some(Abs::pt(2.0).into())))
+                .pack()
+                .spanned(span)
+        },
         library_method: |vm, dynamic, method, args, span| {
             if let Some(counter) = dynamic.downcast::<meta::Counter>().cloned() {
                 counter.call_method(vm, method, args, span)
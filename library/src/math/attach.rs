@@ -14,6 +14,14 @@ use super::*;
 /// ) $
 /// ```
 ///
+/// The attachments before the base are aligned at its left edge, which makes
+/// them suitable for isotope notation:
+///
+/// ```example
+/// $ attach(upright(U), tl: 235, bl: 92) $
+/// $ attach("Fe", tl: 56, bl: 26) $
+/// ```
+///
 /// ## Syntax { #syntax }
 /// This function also has dedicated syntax for attachments after the base: Use
 /// the underscore (`_`) to indicate a subscript i.e. bottom attachment and the
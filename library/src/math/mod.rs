@@ -17,6 +17,7 @@ mod spacing;
 mod stretch;
 mod style;
 mod underover;
+mod unit;
 
 pub use self::accent::*;
 pub use self::align::*;
@@ -29,6 +30,7 @@ pub use self::op::*;
 pub use self::root::*;
 pub use self::style::*;
 pub use self::underover::*;
+pub use self::unit::*;
 
 use ttf_parser::{GlyphId, Rect};
 use typst::eval::{Module, Scope};
@@ -41,7 +43,7 @@ use self::ctx::*;
 use self::fragment::*;
 use self::row::*;
 use self::spacing::*;
-use crate::layout::{HElem, ParElem, Spacing};
+use crate::layout::{AlignElem, HElem, ParElem, Spacing};
 use crate::meta::Supplement;
 use crate::meta::{
     Count, Counter, CounterUpdate, LocalName, Numbering, Outlinable, Refable,
@@ -109,6 +111,9 @@ pub fn module() -> Module {
     math.define("op", OpElem::func());
     op::define(&mut math);
 
+    // Units.
+    math.define("unit", UnitElem::func());
+
     // Spacings.
     spacing::define(&mut math);
 
@@ -166,6 +171,19 @@ pub struct EquationElem {
     /// With @ratio, we get:
     /// $ F_n = floor(1 / sqrt(5) phi.alt^n) $
     /// ```
+    ///
+    /// If the pattern has more counting symbols than the equation counter has
+    /// levels, each line of a multi-line equation is numbered separately. A
+    /// number that doesn't fit next to a wide equation is moved below it.
+    ///
+    /// ```example
+    /// #set math.equation(numbering: "(1a)")
+    /// #show <plain>: set math.equation(numbering: none)
+    ///
+    /// $ a &= b + c \
+    ///     &= d $
+    /// $ e = f $ <plain>
+    /// ```
     pub numbering: Option<Numbering>,
 
     /// A supplement for the equation.
@@ -186,6 +204,38 @@ pub struct EquationElem {
     /// ```
     pub supplement: Smart<Option<Supplement>>,
 
+    /// Whether the equation may be broken across lines if it doesn't fit.
+    ///
+    /// Equations are only broken at top-level relations and binary
+    /// operators, so fractions, roots, and attachments are never split. A
+    /// display equation is broken before such an operator when it is wider
+    /// than the available space. An inline equation may be broken after such
+    /// an operator when the paragraph line is full.
+    ///
+    /// ```example
+    /// #set page(width: 150pt)
+    /// #set math.equation(breakable: true)
+    ///
+    /// $ f(x) = a_0 + a_1 x + a_2 x^2 + a_3 x^3 + a_4 x^4 $
+    ///
+    /// We have $a + b + c + d + e = f$ here.
+    /// ```
+    #[default(false)]
+    pub breakable: bool,
+
+    /// How to align the continuation lines of a display equation that was
+    /// broken because it was too wide. The first line is always aligned at
+    /// the start.
+    ///
+    /// ```example
+    /// #set page(width: 150pt)
+    /// #set math.equation(breakable: true, continuation-align: center)
+    ///
+    /// $ f(x) = a_0 + a_1 x + a_2 x^2 + a_3 x^3 + a_4 x^4 $
+    /// ```
+    #[default(HorizontalAlign(GenAlign::End))]
+    pub continuation_align: HorizontalAlign,
+
     /// The contents of the equation.
     #[required]
     pub body: Content,
@@ -226,6 +276,7 @@ impl Finalize for EquationElem {
             .styled(TextElem::set_font(FontList(vec![FontFamily::new(
                 "New Computer Modern Math",
             )])))
+            .styled(TextElem::set_case(None))
     }
 }
 
@@ -240,61 +291,121 @@ impl Layout for EquationElem {
         const NUMBER_GUTTER: Em = Em::new(0.5);
 
         let block = self.block(styles);
+        let font = self.find_font(vt, styles)?;
+        let mut ctx = MathContext::new(vt, styles, regions, &font, block);
+        let row = ctx.layout_row(self)?;
+
+        // Multi-line equations whose numbering pattern has a counting symbol
+        // to spare get a sub-number for each line.
+        let numbering = if block { self.numbering(styles) } else { None };
+        let mut state = None;
+        if let Some(Numbering::Pattern(pattern)) = &numbering {
+            if row.iter().any(|frag| matches!(frag, MathFragment::Linebreak)) {
+                let current =
+                    Counter::of(Self::func()).at(vt, self.0.location().unwrap())?;
+                if pattern.pieces() > current.0.len() {
+                    state = Some(current);
+                }
+            }
+        }
 
-        // Find a math font.
-        let variant = variant(styles);
-        let world = vt.world;
-        let Some(font) = families(styles)
-            .find_map(|family| {
-                let id = world.book().select(family.as_str(), variant)?;
-                let font = world.font(id)?;
-                let _ = font.ttf().tables().math?.constants?;
-                Some(font)
-            })
-        else {
-            bail!(self.span(), "current font does not support math");
+        let available = regions.size.x;
+        let breakable = self.breakable(styles) && available.is_finite();
+        let (mut frame, baselines) = if state.is_some() {
+            let align = AlignElem::alignment_in(styles).x.resolve(styles);
+            let (mut frame, baselines) = row.into_aligned_lines(&ctx, &[], align);
+            frame.meta(styles, false);
+            (frame, baselines)
+        } else if block && breakable && !row.is_multiline() && row.width() > available {
+            let continuation = self.continuation_align(styles).0.resolve(styles);
+            let mut frame = row.into_broken_frame(&ctx, available, continuation);
+            frame.meta(styles, false);
+            (frame, vec![])
+        } else {
+            (row.into_fragment(&ctx).into_frame(), vec![])
         };
 
-        let mut ctx = MathContext::new(vt, styles, regions, &font, block);
-        let mut frame = ctx.layout_frame(self)?;
+        if breakable && frame.width() > available {
+            warn_unbreakable(vt, self.span());
+        }
 
         if block {
-            if let Some(numbering) = self.numbering(styles) {
+            if let Some(numbering) = numbering {
                 let pod = Regions::one(regions.base(), Axes::splat(false));
-                let counter = Counter::of(Self::func())
-                    .display(Some(numbering), false)
-                    .layout(vt, styles, pod)?
-                    .into_frame();
+                let gutter = NUMBER_GUTTER.resolve(styles);
+                let mut numbers = vec![];
+                if let Some(state) = &state {
+                    for (i, &baseline) in baselines.iter().enumerate() {
+                        let mut sub = state.clone();
+                        sub.0.push(i + 1);
+                        let number = sub
+                            .display(vt, &numbering)?
+                            .layout(vt, styles, pod)?
+                            .into_frame();
+                        numbers.push((baseline, number));
+                    }
+                } else {
+                    let number = Counter::of(Self::func())
+                        .display(Some(numbering), false)
+                        .layout(vt, styles, pod)?
+                        .into_frame();
+                    numbers.push((Abs::zero(), number));
+                }
 
+                let widest = numbers
+                    .iter()
+                    .map(|(_, number)| number.width())
+                    .max()
+                    .unwrap_or_default();
                 let width = if regions.size.x.is_finite() {
                     regions.size.x
                 } else {
-                    frame.width()
-                        + 2.0 * (counter.width() + NUMBER_GUTTER.resolve(styles))
+                    frame.width() + 2.0 * (widest + gutter)
                 };
 
-                let height = frame.height().max(counter.height());
-                frame.resize(Size::new(width, height), Align::CENTER_HORIZON);
+                // A single number that would overlap with a wide equation is moved
+                // below it.
+                let below =
+                    state.is_none() && frame.width() + 2.0 * (widest + gutter) > width;
+
+                let equation = frame.height();
+                let height = if below {
+                    equation + numbers[0].1.height()
+                } else {
+                    numbers
+                        .iter()
+                        .fold(equation, |height, (_, number)| height.max(number.height()))
+                };
 
-                let x = if TextElem::dir_in(styles).is_positive() {
-                    frame.width() - counter.width()
+                let align = if below {
+                    Axes::new(Align::Center, Align::Top)
                 } else {
-                    Abs::zero()
+                    Align::CENTER_HORIZON
                 };
-                let y = (frame.height() - counter.height()) / 2.0;
 
-                frame.push_frame(Point::new(x, y), counter)
+                frame.resize(Size::new(width, height), align);
+                let shift = if below { Abs::zero() } else { (height - equation) / 2.0 };
+
+                for (baseline, number) in numbers {
+                    let x = if TextElem::dir_in(styles).is_positive() {
+                        frame.width() - number.width()
+                    } else {
+                        Abs::zero()
+                    };
+
+                    let y = if below {
+                        equation
+                    } else if state.is_some() {
+                        shift + baseline - number.baseline()
+                    } else {
+                        (frame.height() - number.height()) / 2.0
+                    };
+
+                    frame.push_frame(Point::new(x, y), number);
+                }
             }
         } else {
-            let slack = ParElem::leading_in(styles) * 0.7;
-            let top_edge = TextElem::top_edge_in(styles).resolve(styles, font.metrics());
-            let bottom_edge =
-                -TextElem::bottom_edge_in(styles).resolve(styles, font.metrics());
-
-            let ascent = top_edge.max(frame.ascent() - slack);
-            let descent = bottom_edge.max(frame.descent() - slack);
-            frame.translate(Point::with_y(ascent - frame.baseline()));
-            frame.size_mut().y = ascent + descent;
+            fit_inline(&mut frame, styles, &font);
         }
 
         // Apply metadata.
@@ -304,6 +415,80 @@ impl Layout for EquationElem {
     }
 }
 
+impl EquationElem {
+    /// Find a math font.
+    fn find_font(&self, vt: &Vt, styles: StyleChain) -> SourceResult<Font> {
+        let variant = variant(styles);
+        let world = vt.world;
+        let Some(font) = families(styles).find_map(|family| {
+            let id = world.book().select(family.as_str(), variant)?;
+            let font = world.font(id)?;
+            let _ = font.ttf().tables().math?.constants?;
+            Some(font)
+        }) else {
+            bail!(self.span(), "current font does not support math");
+        };
+        Ok(font)
+    }
+
+    /// Lay out an inline equation into parts between which a paragraph may
+    /// break lines.
+    ///
+    /// This yields a single part unless the equation is
+    /// [breakable]($func/math.equation.breakable).
+    pub fn layout_parts(
+        &self,
+        vt: &mut Vt,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Vec<Frame>> {
+        if !self.breakable(styles) {
+            return Ok(vec![self.layout(vt, styles, regions)?.into_frame()]);
+        }
+
+        let font = self.find_font(vt, styles)?;
+        let mut ctx = MathContext::new(vt, styles, regions, &font, false);
+        let row = ctx.layout_row(self)?;
+        let parts = row.split_at_operators();
+
+        let mut frames = vec![];
+        for part in parts {
+            let mut frame = part.into_fragment(&ctx).into_frame();
+            fit_inline(&mut frame, styles, &font);
+            frame.meta(styles, false);
+            frames.push(frame);
+        }
+
+        if frames.iter().any(|frame| frame.width() > regions.size.x) {
+            warn_unbreakable(vt, self.span());
+        }
+
+        Ok(frames)
+    }
+}
+
+/// Fit the frame of an inline equation to the surrounding text.
+fn fit_inline(frame: &mut Frame, styles: StyleChain, font: &Font) {
+    let slack = ParElem::leading_in(styles) * 0.7;
+    let top_edge = TextElem::top_edge_in(styles).resolve(styles, font.metrics());
+    let bottom_edge = -TextElem::bottom_edge_in(styles).resolve(styles, font.metrics());
+
+    let ascent = top_edge.max(frame.ascent() - slack);
+    let descent = bottom_edge.max(frame.descent() - slack);
+    frame.translate(Point::with_y(ascent - frame.baseline()));
+    frame.size_mut().y = ascent + descent;
+}
+
+/// Warn about a breakable equation that is too wide even when broken.
+fn warn_unbreakable(vt: &mut Vt, span: Span) {
+    vt.tracer.warn(
+        warning!(span, "equation is too wide and cannot be broken").with_hints([
+            "equations can only be broken at top-level relations and binary operators"
+                .into(),
+        ]),
+    );
+}
+
 impl Count for EquationElem {
     fn update(&self) -> Option<CounterUpdate> {
         (self.block(StyleChain::default())
@@ -1,4 +1,5 @@
 use std::iter::once;
+use std::mem;
 
 use crate::layout::AlignElem;
 
@@ -105,6 +106,105 @@ impl MathRow {
         self.iter().map(MathFragment::descent).max().unwrap_or_default()
     }
 
+    pub fn width(&self) -> Abs {
+        self.iter().map(MathFragment::width).sum()
+    }
+
+    /// Whether the row consists of multiple lines.
+    pub fn is_multiline(&self) -> bool {
+        self.iter().any(|frag| matches!(frag, MathFragment::Linebreak))
+    }
+
+    /// Split the row after its top-level relations and binary operators.
+    ///
+    /// These are the points at which an inline equation may be broken across
+    /// lines. Spacing after an operator stays with it. Since fractions, roots,
+    /// and attachments are single fragments, they are never split.
+    pub fn split_at_operators(self) -> Vec<Self> {
+        let mut parts = vec![];
+        let mut current = vec![];
+        let mut after_op = false;
+        for fragment in self.0 {
+            let spacing =
+                matches!(fragment, MathFragment::Spacing(_) | MathFragment::Space(_));
+            if after_op && !spacing {
+                parts.push(Self(mem::take(&mut current)));
+                after_op = false;
+            }
+
+            after_op |= is_breakable_op(&fragment);
+            current.push(fragment);
+        }
+
+        parts.push(Self(current));
+        parts
+    }
+
+    /// Break the row into lines that fit into the given width, if possible.
+    ///
+    /// Lines are broken before top-level relations and binary operators. The
+    /// first line is aligned at the start and the continuation lines with the
+    /// given alignment.
+    pub fn into_broken_frame(
+        self,
+        ctx: &MathContext,
+        width: Abs,
+        continuation: Align,
+    ) -> Frame {
+        let mut lines = vec![vec![]];
+        let mut current = Abs::zero();
+        let mut candidate = None;
+        for fragment in self.0 {
+            let line = lines.last_mut().unwrap();
+            if is_breakable_op(&fragment) && !line.is_empty() {
+                candidate = Some(line.len());
+            }
+
+            current += fragment.width();
+            line.push(fragment);
+
+            if current > width {
+                if let Some(k) = candidate.take() {
+                    let rest = line.split_off(k);
+                    while matches!(
+                        line.last(),
+                        Some(MathFragment::Spacing(_) | MathFragment::Space(_))
+                    ) {
+                        line.pop();
+                    }
+                    current = rest.iter().map(MathFragment::width).sum();
+                    lines.push(rest);
+                }
+            }
+        }
+
+        let leading = ParElem::leading_in(ctx.styles());
+        let frames: Vec<_> = lines
+            .into_iter()
+            .map(|line| Self(line).into_line_frame(&[], Align::Left))
+            .collect();
+
+        let width = frames.iter().map(Frame::width).max().unwrap_or_default();
+        let mut frame = Frame::new(Size::with_x(width));
+        for (i, sub) in frames.into_iter().enumerate() {
+            let size = frame.size_mut();
+            if i > 0 {
+                size.y += leading;
+            }
+
+            let x = if i > 0 {
+                continuation.position(width - sub.width())
+            } else {
+                Abs::zero()
+            };
+            let pos = Point::new(x, size.y);
+            size.y += sub.height();
+            frame.push_frame(pos, sub);
+        }
+
+        frame
+    }
+
     pub fn class(&self) -> MathClass {
         // Predict the class of the output of 'into_fragment'
         if self.0.len() == 1 {
@@ -139,6 +239,17 @@ impl MathRow {
         points: &[Abs],
         align: Align,
     ) -> Frame {
+        self.into_aligned_lines(ctx, points, align).0
+    }
+
+    /// Like [`into_aligned_frame`](Self::into_aligned_frame), but also returns
+    /// the baselines of the individual lines.
+    pub fn into_aligned_lines(
+        self,
+        ctx: &MathContext,
+        points: &[Abs],
+        align: Align,
+    ) -> (Frame, Vec<Abs>) {
         if self.iter().any(|frag| matches!(frag, MathFragment::Linebreak)) {
             let leading = if ctx.style.size >= MathSize::Text {
                 ParElem::leading_in(ctx.styles())
@@ -154,6 +265,7 @@ impl MathRow {
 
             let AlignmentResult { points, width } = alignments(&rows);
             let mut frame = Frame::new(Size::zero());
+            let mut baselines = vec![];
 
             for (i, row) in rows.into_iter().enumerate() {
                 let sub = row.into_line_frame(&points, align);
@@ -168,11 +280,14 @@ impl MathRow {
                 }
                 size.y += sub.height();
                 size.x.set_max(sub.width());
+                baselines.push(pos.y + sub.baseline());
                 frame.push_frame(pos, sub);
             }
-            frame
+            (frame, baselines)
         } else {
-            self.into_line_frame(points, align)
+            let frame = self.into_line_frame(points, align);
+            let baseline = frame.baseline();
+            (frame, vec![baseline])
         }
     }
 
@@ -238,6 +353,11 @@ impl<T: Into<MathFragment>> From<T> for MathRow {
     }
 }
 
+/// Whether a row may be broken at this fragment.
+fn is_breakable_op(fragment: &MathFragment) -> bool {
+    matches!(fragment.class(), Some(MathClass::Relation | MathClass::Binary))
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 enum LeftRightAlternator {
     Left,
@@ -0,0 +1,169 @@
+use super::*;
+
+/// A physical unit in an equation.
+///
+/// Unit symbols are set upright and separated by thin spaces. When the unit
+/// follows a number, a thin space is also inserted between the two, regardless
+/// of whether there is a space in the markup.
+///
+/// ## Example { #example }
+/// ```example
+/// $ v = 3 unit("m/s") $
+/// $ g = 9.81 unit("m s^-2") $
+/// $ E = 1 unit("kg*m^2/s^2") $
+/// ```
+///
+/// Display: Unit
+/// Category: math
+#[element(LayoutMath)]
+pub struct UnitElem {
+    /// The unit to display.
+    ///
+    /// Unit symbols are multiplied when they are separated by spaces or `*`
+    /// and divided with `/`. Each symbol may be followed by an integer
+    /// exponent, like `^2` or `^-1`.
+    #[required]
+    pub unit: Unit,
+}
+
+impl LayoutMath for UnitElem {
+    #[tracing::instrument(skip(ctx))]
+    fn layout_math(&self, ctx: &mut MathContext) -> SourceResult<()> {
+        let thin = THIN.scaled(ctx);
+        let previous = ctx
+            .fragments
+            .iter()
+            .rev()
+            .find(|fragment| !matches!(fragment, MathFragment::Space(_)));
+        if matches!(
+            previous.and_then(MathFragment::class),
+            Some(MathClass::Normal | MathClass::Alphabetic | MathClass::Closing)
+        ) {
+            ctx.push(MathFragment::Spacing(thin));
+        }
+
+        let span = self.span();
+        let mut fragments = vec![];
+        for part in self.unit().parts {
+            match part {
+                UnitPart::Symbol(symbol, exponent) => {
+                    let mut body = MathStyleElem::new(TextElem::packed(symbol))
+                        .with_italic(Some(false))
+                        .pack()
+                        .spanned(span);
+                    if let Some(exponent) = exponent {
+                        let exponent = exponent.to_string().replace('-', "−");
+                        body = AttachElem::new(body)
+                            .with_tr(Some(TextElem::packed(exponent)))
+                            .pack()
+                            .spanned(span);
+                    }
+                    let frame = ctx.layout_frame(&body)?;
+                    fragments.push(FrameFragment::new(ctx, frame).into());
+                }
+                UnitPart::Product => fragments.push(MathFragment::Spacing(thin)),
+                UnitPart::Quotient => {
+                    let mut glyph = GlyphFragment::new(ctx, '/', span);
+                    glyph.class = Some(MathClass::Normal);
+                    fragments.push(glyph.into());
+                }
+            }
+        }
+
+        let frame = MathRow::new(fragments).into_fragment(ctx).into_frame();
+        ctx.push(FrameFragment::new(ctx, frame));
+        Ok(())
+    }
+}
+
+/// A parsed physical unit.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct Unit {
+    /// The text the unit was parsed from.
+    source: EcoString,
+    /// The symbols and operators that make up the unit.
+    parts: Vec<UnitPart>,
+}
+
+/// A part of a physical unit.
+#[derive(Debug, Clone, PartialEq, Hash)]
+enum UnitPart {
+    /// A unit symbol with an optional exponent.
+    Symbol(EcoString, Option<i64>),
+    /// A multiplication of the surrounding symbols.
+    Product,
+    /// A division of the surrounding symbols.
+    Quotient,
+}
+
+impl Unit {
+    /// Parse a unit from its textual representation.
+    fn parse(source: &str) -> StrResult<Self> {
+        let mut parts = vec![];
+        let mut expect_symbol = true;
+        let mut chars = source.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '/' | '*' | '·' if !expect_symbol => {
+                    parts.push(if c == '/' {
+                        UnitPart::Quotient
+                    } else {
+                        UnitPart::Product
+                    });
+                    expect_symbol = true;
+                }
+                '^' if !expect_symbol => {
+                    let mut exponent = String::new();
+                    if let Some(sign) = chars.next_if(|&c| c == '-' || c == '−') {
+                        exponent.push(if sign == '−' { '-' } else { sign });
+                    }
+                    while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+                        exponent.push(digit);
+                    }
+                    let Ok(exponent) = exponent.parse() else {
+                        bail!("expected integer exponent in unit");
+                    };
+                    let Some(UnitPart::Symbol(_, slot @ None)) = parts.last_mut() else {
+                        bail!("unit symbol can only have one exponent");
+                    };
+                    *slot = Some(exponent);
+                }
+                c if c.is_whitespace() => {
+                    if !expect_symbol && chars.peek().map_or(false, |&c| is_symbol(c)) {
+                        parts.push(UnitPart::Product);
+                        expect_symbol = true;
+                    }
+                }
+                c if is_symbol(c) => {
+                    let mut symbol = EcoString::from(c);
+                    while let Some(c) = chars.next_if(|&c| is_symbol(c)) {
+                        symbol.push(c);
+                    }
+                    if !expect_symbol {
+                        parts.push(UnitPart::Product);
+                    }
+                    parts.push(UnitPart::Symbol(symbol, None));
+                    expect_symbol = false;
+                }
+                _ => bail!("expected unit symbol, found `{c}`"),
+            }
+        }
+
+        if expect_symbol {
+            bail!("expected unit symbol");
+        }
+
+        Ok(Self { source: source.into(), parts })
+    }
+}
+
+/// Whether the character can be part of a unit symbol.
+fn is_symbol(c: char) -> bool {
+    !c.is_whitespace() && !matches!(c, '/' | '*' | '·' | '^')
+}
+
+cast! {
+    Unit,
+    self => self.source.into_value(),
+    v: EcoString => Self::parse(&v)?,
+}
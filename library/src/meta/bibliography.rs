@@ -10,7 +10,7 @@ use hayagriva::Entry;
 use typst::diag::FileError;
 use typst::util::{option_eq, Bytes};
 
-use super::{LinkElem, LocalName, RefElem};
+use super::{CslCitation, CslStyle, LinkElem, LocalName, RefElem};
 use crate::layout::{BlockElem, GridElem, ParElem, Sizing, TrackSizings, VElem};
 use crate::meta::{FootnoteElem, HeadingElem};
 use crate::prelude::*;
@@ -25,7 +25,12 @@ use crate::text::TextElem;
 ///   designed for use with Typst. Visit its
 ///   [documentation](https://github.com/typst/hayagriva/blob/main/docs/file-format.md)
 ///   for more details.
-/// - A BibLaTeX `.bib` file.
+/// - A BibLaTeX `.bib` file. Entries inherit the fields of their `crossref`
+///   and `xdata` parents, and arXiv identifiers become links. Fields that are
+///   not supported are ignored with a warning.
+///
+/// With the built-in styles, the dates on which URLs were accessed are
+/// formatted for the [text language]($func/text.lang) of the bibliography.
 ///
 /// As soon as you add a bibliography somewhere in your document, you can start
 /// citing things with reference syntax (`[@key]`) or explicit calls to the
@@ -65,6 +70,36 @@ pub struct BibliographyElem {
         // Check that parsing works.
         let _ = load(&paths, &data).at(span)?;
 
+        // Warn about fields that are ignored, once per file.
+        for (path, bytes) in paths.0.iter().zip(&data) {
+            let fields = parse_bib(path, bytes).at(span)?.unsupported;
+            if !fields.is_empty() {
+                vm.vt.tracer.warn(
+                    warning!(
+                        span,
+                        "{path} contains unsupported fields: {}",
+                        fields.join(", "),
+                    )
+                    .with_hints(["these fields are ignored".into()]),
+                );
+            }
+        }
+
+        // Load a CSL style given in place of a built-in one.
+        let csl = match args.items.iter().position(|arg| {
+            arg.name.as_deref() == Some("style")
+                && matches!(&arg.value.v, Value::Str(path) if path.ends_with(".csl"))
+        }) {
+            Some(i) => {
+                let Spanned { v, span } = args.items.remove(i).value;
+                let path: EcoString = v.cast().at(span)?;
+                let id = vm.location().join(&path).at(span)?;
+                let data = vm.world().file(id).at(span)?;
+                Some(CslStyle::parse(data).at(span)?)
+            }
+            None => None,
+        };
+
         paths
     )]
     pub path: BibPaths,
@@ -75,6 +110,12 @@ pub struct BibliographyElem {
     #[parse(data)]
     pub data: Vec<Bytes>,
 
+    /// The CSL style loaded from the `style` argument, if any.
+    #[internal]
+    #[required]
+    #[parse(csl)]
+    pub csl: Option<CslStyle>,
+
     /// The title of the bibliography.
     ///
     /// - When set to `{auto}`, an appropriate title for the [text
@@ -90,8 +131,34 @@ pub struct BibliographyElem {
     pub title: Option<Smart<Content>>,
 
     /// The bibliography style.
+    ///
+    /// Either one of the built-in styles or a path to a
+    /// [CSL](https://citationstyles.org/) file. A CSL style determines the
+    /// format of both the citations and the reference list, so the citation
+    /// style is ignored when one is used. Paths to CSL files can only be given
+    /// in the bibliography call itself, not in set rules.
+    ///
+    /// ```example
+    /// #set page(width: 200pt)
+    /// @arrgh, @netwok[p.~5]
+    ///
+    /// #bibliography(
+    ///   "works.bib",
+    ///   style: "numeric.csl",
+    /// )
+    /// ```
     #[default(BibliographyStyle::Ieee)]
     pub style: BibliographyStyle,
+
+    /// The language setting where the bibliography is.
+    #[internal]
+    #[synthesized]
+    pub lang: Lang,
+
+    /// The region setting where the bibliography is.
+    #[internal]
+    #[synthesized]
+    pub region: Option<Region>,
 }
 
 /// A list of bibliography file paths.
@@ -154,6 +221,8 @@ impl BibliographyElem {
 impl Synthesize for BibliographyElem {
     fn synthesize(&mut self, _vt: &mut Vt, styles: StyleChain) -> SourceResult<()> {
         self.push_style(self.style(styles));
+        self.push_lang(TextElem::lang_in(styles));
+        self.push_region(TextElem::region_in(styles));
         Ok(())
     }
 }
@@ -204,10 +273,12 @@ impl Show for BibliographyElem {
                     entries.push(reference.clone());
                 }
 
-                seq.push(
-                    Content::sequence(entries)
-                        .styled(ParElem::set_hanging_indent(INDENT.into())),
-                );
+                let mut entries = Content::sequence(entries);
+                if works.hanging_indent {
+                    entries = entries.styled(ParElem::set_hanging_indent(INDENT.into()));
+                }
+
+                seq.push(entries);
             }
 
             Ok(Content::sequence(seq))
@@ -365,6 +436,8 @@ impl Synthesize for CiteElem {
         self.push_supplement(self.supplement(styles));
         self.push_brackets(self.brackets(styles));
         self.push_style(self.style(styles));
+        self.push_lang(TextElem::lang_in(styles));
+        self.push_region(TextElem::region_in(styles));
         Ok(())
     }
 }
@@ -423,6 +496,7 @@ impl CitationStyle {
 struct Works {
     citations: HashMap<Location, Option<Content>>,
     references: Vec<(Option<Content>, Content)>,
+    hanging_indent: bool,
 }
 
 impl Works {
@@ -450,6 +524,29 @@ impl Works {
 fn create(bibliography: BibliographyElem, citations: Vec<CiteElem>) -> Arc<Works> {
     let span = bibliography.span();
     let entries = load(&bibliography.path(), &bibliography.data()).unwrap();
+    if let Some(csl) = bibliography.csl() {
+        return create_csl(&bibliography, &csl, &entries, citations);
+    }
+
+    // The built-in styles format accessed dates in English. For other
+    // languages, the dates are added to the references separately.
+    let (lang, region) = (bibliography.lang(), bibliography.region());
+    let mut accessed = HashMap::new();
+    let entries: Vec<Entry> = entries
+        .iter()
+        .cloned()
+        .map(|mut entry| {
+            let Some(mut url) = entry.url().cloned() else { return entry };
+            let date = url.visit_date.as_ref();
+            if let Some(text) = date.and_then(|date| local_accessed(date, lang, region)) {
+                accessed.insert(entry.key().to_string(), text);
+                url.visit_date = None;
+                entry.set_url(url);
+            }
+            entry
+        })
+        .collect();
+
     let style = bibliography.style(StyleChain::default());
     let bib_location = bibliography.0.location().unwrap();
     let ref_location = |target: &Entry| {
@@ -585,7 +682,12 @@ fn create(bibliography: BibliographyElem, citations: Vec<CiteElem>) -> Arc<Works
                     .backlinked(backlink)
             });
 
+            let key = reference.entry.key();
             let mut reference = format_display_string(&reference.display, None, span);
+            if let Some(text) = accessed.get(key) {
+                reference += TextElem::packed(eco_format!(" {text}"));
+            }
+
             if prefix.is_none() {
                 reference = reference.backlinked(backlink);
             }
@@ -594,7 +696,99 @@ fn create(bibliography: BibliographyElem, citations: Vec<CiteElem>) -> Arc<Works
         })
         .collect();
 
-    Arc::new(Works { citations, references })
+    Arc::new(Works { citations, references, hanging_indent: true })
+}
+
+/// Generate all citations and the whole bibliography with a CSL style.
+fn create_csl(
+    bibliography: &BibliographyElem,
+    csl: &CslStyle,
+    entries: &[Entry],
+    citations: Vec<CiteElem>,
+) -> Arc<Works> {
+    let span = bibliography.span();
+    let bib_location = bibliography.0.location().unwrap();
+    let ref_location = |target: &Entry| {
+        let i = entries
+            .iter()
+            .position(|entry| entry.key() == target.key())
+            .unwrap_or_default();
+        bib_location.variant(i)
+    };
+
+    let mut ids = HashMap::new();
+    let mut locations = vec![];
+    let mut requests = vec![];
+    for citation in &citations {
+        let location = citation.0.location().unwrap();
+        let cited = citation
+            .keys()
+            .into_iter()
+            .map(|key| entries.iter().find(|entry| entry.key() == key))
+            .collect::<Option<Vec<_>>>();
+
+        let Some(cited) = cited else {
+            locations.push((location, false));
+            continue;
+        };
+
+        for entry in &cited {
+            ids.entry(entry.key()).or_insert(location);
+        }
+
+        locations.push((location, true));
+        requests.push(CslCitation {
+            entries: cited,
+            supplement: citation.supplement(StyleChain::default()),
+            brackets: citation.brackets(StyleChain::default()),
+        });
+    }
+
+    let rendered = csl.render(&requests, span, &|entry, content| {
+        content.linked(Destination::Location(ref_location(entry)))
+    });
+
+    let mut rendered_citations = rendered.citations.into_iter();
+    let citations = locations
+        .into_iter()
+        .map(|(location, found)| {
+            let content = found.then(|| rendered_citations.next()).flatten();
+            let content = content.map(|content| {
+                if csl.is_note() {
+                    FootnoteElem::with_content(content).pack()
+                } else {
+                    content
+                }
+            });
+            (location, content)
+        })
+        .collect();
+
+    let references = rendered
+        .references
+        .into_iter()
+        .map(|(entry, prefix, mut reference)| {
+            let backlink = ref_location(entry);
+            let prefix = prefix.map(|prefix| {
+                // Link to the first citation.
+                prefix
+                    .linked(Destination::Location(ids[entry.key()]))
+                    .backlinked(backlink)
+            });
+
+            if prefix.is_none() {
+                reference = reference.backlinked(backlink);
+            }
+
+            (prefix, reference)
+        })
+        .collect();
+
+    Arc::new(Works {
+        citations,
+        references,
+        hanging_indent: rendered.hanging_indent,
+    })
 }
 
 /// Load bibliography entries from a path.
@@ -604,9 +798,7 @@ fn load(paths: &BibPaths, data: &[Bytes]) -> StrResult<EcoVec<hayagriva::Entry>>
 
     // We might have multiple bib/yaml files
     for (path, bytes) in paths.0.iter().zip(data) {
-        let src = std::str::from_utf8(bytes).map_err(|_| FileError::InvalidUtf8)?;
-        let entries = parse_bib(path, src)?;
-        result.extend(entries);
+        result.extend(parse_bib(path, bytes)?.entries);
     }
 
     // Biblatex only checks for duplicate keys within files
@@ -628,24 +820,210 @@ fn load(paths: &BibPaths, data: &[Bytes]) -> StrResult<EcoVec<hayagriva::Entry>>
     }
 }
 
-/// Parse a bibliography file (bib/yml/yaml)
-fn parse_bib(path_str: &str, src: &str) -> StrResult<Vec<hayagriva::Entry>> {
+/// A parsed bibliography file.
+#[derive(Clone)]
+struct BibFile {
+    /// The file's entries.
+    entries: Vec<hayagriva::Entry>,
+    /// The fields of a BibLaTeX file that are ignored, formatted for a
+    /// warning.
+    unsupported: Vec<EcoString>,
+}
+
+/// Parse a bibliography file (bib/yml/yaml).
+///
+/// The file is parsed only once, both to load its entries and to find the
+/// fields that are ignored.
+#[comemo::memoize]
+fn parse_bib(path_str: &str, data: &Bytes) -> StrResult<BibFile> {
+    let src = std::str::from_utf8(data).map_err(|_| FileError::InvalidUtf8)?;
     let path = Path::new(path_str);
     let ext = path.extension().and_then(OsStr::to_str).unwrap_or_default();
     match ext.to_lowercase().as_str() {
         "yml" | "yaml" => {
-            hayagriva::io::from_yaml_str(src).map_err(format_hayagriva_error)
+            let entries =
+                hayagriva::io::from_yaml_str(src).map_err(format_hayagriva_error)?;
+            Ok(BibFile { entries, unsupported: vec![] })
+        }
+        "bib" => {
+            let error = |error| format_biblatex_error(path_str, src, error);
+            let mut bibliography = biblatex::Bibliography::parse(src)
+                .map_err(|err| error(BibLaTeXError::Parse(err)))?;
+            let unsupported = unsupported_fields(&bibliography);
+
+            // The fields filled in keep their spans, so errors still point to
+            // the right lines.
+            prepare_biblatex(&mut bibliography);
+            let entries = bibliography
+                .iter()
+                .map(|entry| {
+                    hayagriva::Entry::try_from(entry)
+                        .map_err(|err| error(BibLaTeXError::Type(err)))
+                })
+                .collect::<StrResult<_>>()?;
+
+            Ok(BibFile { entries, unsupported })
         }
-        "bib" => hayagriva::io::from_biblatex_str(src).map_err(|err| {
-            err.into_iter()
-                .next()
-                .map(|error| format_biblatex_error(path_str, src, error))
-                .unwrap_or_else(|| eco_format!("failed to parse {path_str}"))
-        }),
         _ => bail!("unknown bibliography format (must be .yml/.yaml or .bib)"),
     }
 }
 
+/// BibLaTeX fields that Hayagriva understands or that are expected to be
+/// ignored.
+#[rustfmt::skip]
+const BIBLATEX_FIELDS: &[&str] = &[
+    "abstract", "addendum", "address", "annotation", "archiveprefix", "author",
+    "booksubtitle", "booktitle", "booktitleaddon", "chapter", "crossref", "date", "day",
+    "doi", "edition", "editor", "editora", "editorb", "editorc", "eid", "eprint",
+    "eprintclass", "eprinttype", "eventdate", "eventtitle", "file", "howpublished",
+    "ids", "institution", "isbn", "isrn", "issn", "issue", "issuetitle", "journal",
+    "journaltitle", "keywords", "langid", "language", "location", "maintitle", "month",
+    "note", "number", "organization", "origdate", "pages", "pagetotal", "primaryclass",
+    "publisher", "school", "series", "shorttitle", "subtitle", "title", "titleaddon",
+    "translator", "type", "url", "urldate", "venue", "version", "volume", "volumes",
+    "xdata", "year",
+];
+
+/// BibLaTeX fields that are never inherited through `crossref` or `xdata`.
+#[rustfmt::skip]
+const UNINHERITED_FIELDS: &[&str] = &[
+    "crossref", "entryset", "entrysubtype", "execute", "ids", "label", "options",
+    "presort", "related", "relatedoptions", "relatedstring", "relatedtype", "shorthand",
+    "shorthandintro", "sortkey", "xdata", "xref",
+];
+
+/// Find the fields of a BibLaTeX file that are not supported, formatted for
+/// a warning.
+fn unsupported_fields(bibliography: &biblatex::Bibliography) -> Vec<EcoString> {
+    let mut fields: Vec<_> = bibliography
+        .iter()
+        .flat_map(|entry| entry.fields.keys())
+        .filter(|field| !BIBLATEX_FIELDS.contains(&field.as_str()))
+        .map(|field| eco_format!("`{field}`"))
+        .collect();
+
+    fields.sort();
+    fields.dedup();
+    fields
+}
+
+/// Fill in BibLaTeX fields that would otherwise be lost when converting to
+/// Hayagriva entries.
+///
+/// - Fields of `crossref` and `xdata` parents are inherited.
+/// - arXiv identifiers and the DOIs of `@misc` entries become the URL of
+///   entries without one.
+fn prepare_biblatex(bibliography: &mut biblatex::Bibliography) {
+    use biblatex::{Chunk, ChunksExt, EntryType, Spanned};
+
+    let parents: HashMap<String, _> = bibliography
+        .iter()
+        .map(|entry| (entry.key.clone(), entry.fields.clone()))
+        .collect();
+
+    for entry in bibliography.iter_mut() {
+        for kind in ["crossref", "xdata"] {
+            let Some(keys) = entry.get(kind).map(|chunks| chunks.format_verbatim())
+            else {
+                continue;
+            };
+
+            for key in keys.split(',').map(str::trim) {
+                let Some(fields) = parents.get(key) else { continue };
+                for (field, value) in fields {
+                    if UNINHERITED_FIELDS.contains(&field.as_str()) {
+                        continue;
+                    }
+
+                    // Titles of books become the book titles of their parts.
+                    let contained = matches!(
+                        entry.entry_type,
+                        EntryType::InBook
+                            | EntryType::InCollection
+                            | EntryType::InProceedings
+                            | EntryType::InReference
+                    );
+                    let target = match field.as_str() {
+                        "title" if kind == "crossref" && contained => "booktitle",
+                        "subtitle" if kind == "crossref" && contained => "booksubtitle",
+                        other => other,
+                    };
+
+                    if !entry.fields.contains_key(target) {
+                        entry.fields.insert(target.into(), value.clone());
+                    }
+                }
+            }
+        }
+
+        if entry.fields.contains_key("url") {
+            continue;
+        }
+
+        let field = |name: &str| entry.get(name).map(|chunks| chunks.format_verbatim());
+        let archive = field("eprinttype").or_else(|| field("archiveprefix"));
+        let url = match (field("eprint"), archive) {
+            (Some(id), Some(archive)) if archive.eq_ignore_ascii_case("arxiv") => {
+                format!("https://arxiv.org/abs/{id}")
+            }
+            _ => match field("doi") {
+                Some(doi) if entry.entry_type == EntryType::Misc => {
+                    format!("https://doi.org/{doi}")
+                }
+                _ => continue,
+            },
+        };
+
+        let url = vec![Spanned { v: Chunk::Verbatim(url), span: 0..0 }];
+        entry.fields.insert("url".into(), url);
+    }
+}
+
+/// The sentence stating when a URL was accessed, in a language whose date
+/// formatting differs from the English one of the built-in styles.
+fn local_accessed(
+    date: &hayagriva::types::Date,
+    lang: Lang,
+    region: Option<Region>,
+) -> Option<EcoString> {
+    let year = date.year;
+    let month = date.month.map(|month| month + 1);
+    let day = date.day.map(|day| day + 1);
+    let numeric = |separator: &str| match (day, month) {
+        (Some(day), Some(month)) => {
+            eco_format!("{day:02}{separator}{month:02}{separator}{year}")
+        }
+        (None, Some(month)) => eco_format!("{month:02}{separator}{year}"),
+        _ => eco_format!("{year}"),
+    };
+
+    Some(match lang {
+        Lang::CHINESE => {
+            let mut text = eco_format!("{year}年");
+            if let Some(month) = month {
+                text.push_str(&eco_format!("{month}月"));
+                if let Some(day) = day {
+                    text.push_str(&eco_format!("{day}日"));
+                }
+            }
+            if option_eq(region, "TW") {
+                eco_format!("存取於{text}。")
+            } else {
+                eco_format!("访问于{text}。")
+            }
+        }
+        Lang::DUTCH => eco_format!("Geraadpleegd op {}.", numeric("-")),
+        Lang::FRENCH => eco_format!("Consulté le {}.", numeric("/")),
+        Lang::GERMAN => eco_format!("Abgerufen am {}.", numeric(".")),
+        Lang::ITALIAN => eco_format!("Consultato il {}.", numeric("/")),
+        Lang::POLISH => eco_format!("Dostęp {}.", numeric(".")),
+        Lang::PORTUGUESE => eco_format!("Acesso em {}.", numeric("/")),
+        Lang::RUSSIAN => eco_format!("Дата обращения: {}.", numeric(".")),
+        Lang::SPANISH => eco_format!("Consultado el {}.", numeric("/")),
+        _ => return None,
+    })
+}
+
 /// Format a Hayagriva loading error.
 fn format_hayagriva_error(error: YamlBibliographyError) -> EcoString {
     eco_format!("{error}")
@@ -722,3 +1100,58 @@ fn format_display_string(
 
     Content::sequence(seq)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parse the file with the BibLaTeX fields that are carried over.
+    fn fields() -> BibFile {
+        let data = include_bytes!("../../../assets/files/fields.bib");
+        parse_bib("fields.bib", &Bytes::from_static(data)).unwrap()
+    }
+
+    fn find<'a>(entries: &'a [Entry], key: &str) -> &'a Entry {
+        entries.iter().find(|entry| entry.key() == key).unwrap()
+    }
+
+    #[test]
+    fn test_bib_unsupported_fields() {
+        assert_eq!(fields().unsupported, ["`mood`"]);
+    }
+
+    #[test]
+    fn test_bib_links() {
+        let entries = fields().entries;
+        let url = |key| find(&entries, key).url().unwrap().value.as_str().to_string();
+        assert_eq!(url("transformers"), "https://arxiv.org/abs/2201.01234");
+        assert_eq!(url("dataset"), "https://doi.org/10.5281/zenodo.1234567");
+    }
+
+    #[test]
+    fn test_bib_crossref_inheritance() {
+        let entries = fields().entries;
+        let attention = find(&entries, "attention");
+        let parent = &attention.parents().unwrap()[0];
+        assert_eq!(
+            parent.title().unwrap().canonical.value,
+            "Proceedings of the International Conference on Machine Learning",
+        );
+
+        let year = attention.date().or_else(|| parent.date()).map(|date| date.year);
+        assert_eq!(year, Some(2021));
+    }
+
+    #[test]
+    fn test_bib_accessed_date() {
+        let entries = fields().entries;
+        let url = find(&entries, "website").url().unwrap();
+        let date = url.visit_date.as_ref().unwrap();
+        assert_eq!((date.year, date.month, date.day), (2023, Some(4), Some(16)));
+
+        let local = |lang, region| local_accessed(date, lang, region);
+        assert_eq!(local(Lang::ENGLISH, None), None);
+        assert_eq!(local(Lang::GERMAN, None).unwrap(), "Abgerufen am 17.05.2023.");
+        assert_eq!(local(Lang::CHINESE, None).unwrap(), "访问于2023年5月17日。");
+    }
+}
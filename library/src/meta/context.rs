@@ -82,10 +82,9 @@ impl Show for LocateElem {
 
 /// Provides access to active styles.
 ///
-/// The styles are currently opaque and only useful in combination with the
-/// [`measure`]($func/measure) function. See its documentation for more details.
-/// In the future, the provided styles might also be directly accessed to look
-/// up styles defined by [set rules]($styling/#set-rules).
+/// The styles can be passed to the [`measure`]($func/measure) function to
+/// measure content in the style context it appears in. See its documentation
+/// for more details.
 ///
 /// ```example
 /// #let thing(body) = style(styles => {
@@ -97,6 +96,41 @@ impl Show for LocateElem {
 /// #thing[Welcome]
 /// ```
 ///
+/// The styles can also be used to look up the properties defined by
+/// [set rules]($styling/#set-rules) with the `get` method. This makes it
+/// possible to adapt content to the page or text it ends up in.
+///
+/// ```example
+/// #set page(width: 160pt, height: auto)
+/// #style(styles => {
+///   let width = styles.get(page, "width")
+///   if width < 10cm [Narrow page] else [Wide page]
+/// })
+/// ```
+///
+/// Since the function is only called once the styles are known, it sees the
+/// styles of the place where the content returned by `style` ends up. The
+/// content it returns is then realized in turn, so [show rules]($styling/#show-rules)
+/// apply to it as usual. A show rule that wraps its element in `style` sees
+/// the styles at that element, including the ones that the show rule itself
+/// set. Content returned by the function that contains another `style`
+/// call is evaluated within the outer one. If this nesting never ends, for
+/// example because the function returns content that calls it again, the
+/// process is stopped with an error.
+///
+/// ## Methods
+/// ### get()
+/// Returns the value of an element's property in these styles. This is the
+/// value of the innermost set rule for the property or, if no set rule
+/// applies, its default value. The value is returned just like it was given
+/// to the set rule, so relative lengths like `{2em}` are not resolved.
+///
+/// - element: function (positional, required)
+///   The element function whose property to look up, for example `text`.
+/// - field: string (positional, required)
+///   The name of the property, for example `{"size"}`.
+/// - returns: any
+///
 /// Display: Style
 /// Category: meta
 #[func]
@@ -108,8 +142,10 @@ pub fn style(
     /// `style` appears in the document. That makes it possible to generate
     /// content that depends on the style context it appears in.
     func: Func,
+    /// The callsite span.
+    span: Span,
 ) -> Content {
-    StyleElem::new(func).pack()
+    StyleElem::new(func).pack().spanned(span)
 }
 
 /// Executes a style access.
@@ -121,15 +157,34 @@ struct StyleElem {
     /// The function to call with the styles.
     #[required]
     func: Func,
+
+    /// How many style accesses this one is nested in.
+    #[internal]
+    #[default(0)]
+    depth: usize,
 }
 
 impl Show for StyleElem {
     #[tracing::instrument(name = "StyleElem::show", skip_all)]
     fn show(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
-        Ok(self.func().call_vt(vt, [styles.to_map()])?.display())
+        let depth = self.depth(styles);
+        if depth >= MAX_STYLE_DEPTH {
+            let hint = "the function may return content that calls `style` again";
+            bail!(error!(self.span(), "maximum style nesting depth exceeded")
+                .with_hints([hint.into()]));
+        }
+
+        Ok(self
+            .func()
+            .call_vt(vt, [styles.to_map()])?
+            .display()
+            .styled(Self::set_depth(depth + 1)))
     }
 }
 
+/// The maximum number of nested style accesses.
+const MAX_STYLE_DEPTH: usize = 64;
+
 /// Provides access to the current outer container's (or page's, if none) size
 /// (width and height).
 ///
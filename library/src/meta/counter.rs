@@ -4,7 +4,8 @@ use std::str::FromStr;
 use ecow::{eco_vec, EcoVec};
 use smallvec::{smallvec, SmallVec};
 use typst::eval::Tracer;
-use typst::model::DelayedErrors;
+use typst::model::{DelayedErrors, Depth};
+use typst::Limits;
 
 use super::{FigureElem, HeadingElem, Numbering, NumberingPattern};
 use crate::layout::PageElem;
@@ -352,6 +353,22 @@ impl Counter {
         Ok(state)
     }
 
+    /// Get the value of the state at the end of the given physical page.
+    pub fn at_page(&self, vt: &mut Vt, page: NonZeroUsize) -> SourceResult<CounterState> {
+        let sequence = self.sequence(vt)?;
+        let (mut state, stop) = sequence
+            .iter()
+            .rev()
+            .find(|(_, stop)| *stop <= page)
+            .unwrap_or(&sequence[0])
+            .clone();
+        if self.is_page() {
+            let delta = page.get().saturating_sub(stop.get());
+            state.step(NonZeroUsize::ONE, delta);
+        }
+        Ok(state)
+    }
+
     /// Get the value of the state at the final location.
     pub fn final_(&self, vt: &mut Vt, _: Location) -> SourceResult<CounterState> {
         let sequence = self.sequence(vt)?;
@@ -402,6 +419,7 @@ impl Counter {
             vt.locator.track(),
             TrackedMut::reborrow_mut(&mut vt.delayed),
             TrackedMut::reborrow_mut(&mut vt.tracer),
+            vt.limits,
         )
     }
 
@@ -414,14 +432,18 @@ impl Counter {
         locator: Tracked<Locator>,
         delayed: TrackedMut<DelayedErrors>,
         tracer: TrackedMut<Tracer>,
+        limits: Limits,
     ) -> SourceResult<EcoVec<(CounterState, NonZeroUsize)>> {
         let mut locator = Locator::chained(locator);
+        let depth = Depth::default();
         let mut vt = Vt {
             world,
             introspector,
             locator: &mut locator,
             delayed,
             tracer,
+            limits,
+            depth: depth.track(),
         };
         let mut state = CounterState(match &self.0 {
             // special case, because pages always start at one.
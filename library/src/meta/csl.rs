@@ -0,0 +1,2073 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+use std::sync::Arc;
+
+use hayagriva::types::{Date, EntryType, FormatString, NumOrStr, Person};
+use hayagriva::Entry;
+use typst::diag::{format_xml_like_error, FileError};
+use typst::util::{hash128, Bytes};
+
+use super::{LinkElem, LinkTarget};
+use crate::prelude::*;
+use crate::text::{SuperElem, TextElem};
+
+/// A citation style in the Citation Style Language (CSL).
+///
+/// Supports the subset of CSL 1.0 that is needed for the common styles:
+/// Macros, names with et-al rules, dates, groups, conditions, sorting,
+/// disambiguation with year suffixes, and the `hanging-indent` and
+/// `second-field-align` bibliography options. Terms default to English but
+/// can be overridden by the style's `locale` element.
+#[derive(Clone)]
+pub struct CslStyle(Arc<Repr>);
+
+/// The internal representation of a CSL style.
+struct Repr {
+    /// The raw file the style was parsed from.
+    data: Bytes,
+    /// A hash of the raw file.
+    hash: u128,
+    /// Whether citations are placed in footnotes.
+    note: bool,
+    /// The style's macros by name.
+    macros: HashMap<EcoString, Vec<Node>>,
+    /// Terms defined by the style, overriding the default English ones.
+    terms: HashMap<(EcoString, TermForm), (EcoString, EcoString)>,
+    /// Whether the style renders the year suffix explicitly.
+    explicit_year_suffix: bool,
+    /// How to format citations.
+    citation: Layout,
+    /// How to format the reference list.
+    bibliography: Option<Layout>,
+}
+
+impl CslStyle {
+    /// Parse a CSL style from the raw contents of a `.csl` file.
+    pub fn parse(data: Bytes) -> StrResult<Self> {
+        parse(data)
+    }
+
+    /// Whether citations are placed in footnotes.
+    pub fn is_note(&self) -> bool {
+        self.0.note
+    }
+
+    /// Render the given citations and the references to all cited entries.
+    ///
+    /// The `link` function is applied to each cited entry's part of a
+    /// citation.
+    pub(super) fn render<'a>(
+        &'a self,
+        citations: &[CslCitation<'a>],
+        span: Span,
+        link: &dyn Fn(&Entry, Content) -> Content,
+    ) -> CslRendered<'a> {
+        let style = &*self.0;
+
+        // Collect the cited entries in the order of their first citation.
+        let mut cited: Vec<&'a Entry> = vec![];
+        for citation in citations {
+            for &entry in &citation.entries {
+                if !cited.iter().any(|prev| prev.key() == entry.key()) {
+                    cited.push(entry);
+                }
+            }
+        }
+
+        let mut renderer = Renderer {
+            style,
+            numbers: cited.iter().enumerate().map(|(i, e)| (e.key(), i + 1)).collect(),
+            suffixes: HashMap::new(),
+        };
+
+        // Citation numbers follow the order of the bibliography unless it is
+        // sorted by them.
+        let mut order = cited.clone();
+        if let Some(layout) = &style.bibliography {
+            if !layout.sort.is_empty() {
+                let by_number = layout.sort.iter().any(SortKey::is_citation_number);
+                if !by_number {
+                    order = renderer.sort(layout, order);
+                    renderer.numbers =
+                        order.iter().enumerate().map(|(i, e)| (e.key(), i + 1)).collect();
+                }
+            }
+        }
+
+        // Distinguish entries whose citations would look the same.
+        if style.citation.options.add_year_suffix {
+            let mut groups: Vec<(String, Vec<&'a str>)> = vec![];
+            for &entry in &order {
+                let mut ctx =
+                    Context::new(entry, &style.citation, false, Position::First);
+                let text =
+                    plain(&renderer.render_nodes(&mut ctx, &style.citation.children));
+                match groups.iter_mut().find(|(prev, _)| *prev == text) {
+                    Some((_, keys)) => keys.push(entry.key()),
+                    None => groups.push((text, vec![entry.key()])),
+                }
+            }
+
+            for (_, keys) in groups.into_iter().filter(|(_, keys)| keys.len() > 1) {
+                for (i, key) in keys.into_iter().enumerate() {
+                    renderer.suffixes.insert(key, year_suffix(i));
+                }
+            }
+        }
+
+        let layout = &style.citation;
+        let mut seen = HashSet::new();
+        let mut previous = None;
+        let mut rendered_citations = vec![];
+        for citation in citations {
+            let len = citation.entries.len();
+            let entries = if layout.sort.is_empty() {
+                citation.entries.clone()
+            } else {
+                renderer.sort(layout, citation.entries.clone())
+            };
+
+            // The supplement belongs to the last entry given by the user, even
+            // if sorting moves it.
+            let last = citation.entries.last().map(|entry| entry.key());
+            let mut supplement = citation.supplement.clone();
+            let mut items = vec![];
+            for entry in entries {
+                let locator = Some(entry.key()) == last && supplement.is_some();
+                let position = if seen.insert(entry.key()) {
+                    Position::First
+                } else if len == 1 && previous == Some(entry.key()) {
+                    if locator {
+                        Position::IbidWithLocator
+                    } else {
+                        Position::Ibid
+                    }
+                } else {
+                    Position::Subsequent
+                };
+
+                let mut ctx = Context::new(entry, layout, locator, position);
+                let runs = renderer.render_nodes(&mut ctx, &layout.children);
+                let runs = renderer.format(&layout.display.without_affixes(), runs);
+                let mut taken = if locator { supplement.take() } else { None };
+                let mut content = to_content(runs, &mut taken, span);
+
+                // Keep the supplement even if the style has no locator.
+                if let Some(supplement) = taken {
+                    content += TextElem::packed(", ") + supplement;
+                }
+
+                items.push(link(entry, content));
+            }
+
+            previous = (len == 1).then(|| citation.entries[0].key());
+
+            let mut seq = vec![];
+            if citation.brackets && !layout.display.prefix.is_empty() {
+                seq.push(TextElem::packed(layout.display.prefix.clone()));
+            }
+            for (i, item) in items.into_iter().enumerate() {
+                if i > 0 {
+                    seq.push(TextElem::packed(layout.delimiter.clone()));
+                }
+                seq.push(item);
+            }
+            if citation.brackets && !layout.display.suffix.is_empty() {
+                seq.push(TextElem::packed(layout.display.suffix.clone()));
+            }
+
+            rendered_citations.push(Content::sequence(seq));
+        }
+
+        let mut references = vec![];
+        let mut hanging_indent = false;
+        if let Some(layout) = &style.bibliography {
+            hanging_indent = layout.options.hanging_indent;
+            let entries =
+                if layout.sort.is_empty() { order } else { renderer.sort(layout, order) };
+
+            for entry in entries {
+                let mut ctx = Context::new(entry, layout, false, Position::First);
+                let split = if layout.options.second_field_align {
+                    layout.children.len().min(1)
+                } else {
+                    0
+                };
+
+                let first = renderer.render_nodes(&mut ctx, &layout.children[..split]);
+                let rest = renderer.render_nodes(&mut ctx, &layout.children[split..]);
+                let (prefix, reference) = if split > 0 {
+                    let first = renderer.format(&layout.display.without_suffix(), first);
+                    let rest = renderer.format(&layout.display.without_prefix(), rest);
+                    (Some(to_content(first, &mut None, span)), rest)
+                } else {
+                    let mut runs = first;
+                    runs.extend(rest);
+                    (None, renderer.format(&layout.display, runs))
+                };
+
+                references.push((entry, prefix, to_content(reference, &mut None, span)));
+            }
+        }
+
+        CslRendered {
+            citations: rendered_citations,
+            references,
+            hanging_indent,
+        }
+    }
+}
+
+/// A citation of one or multiple entries.
+pub(super) struct CslCitation<'a> {
+    /// The cited entries.
+    pub entries: Vec<&'a Entry>,
+    /// A supplement for the last cited entry, used as its locator.
+    pub supplement: Option<Content>,
+    /// Whether to add the prefix and suffix of the citation layout.
+    pub brackets: bool,
+}
+
+/// Citations and references rendered with a CSL style.
+pub(super) struct CslRendered<'a> {
+    /// One rendered citation per requested citation.
+    pub citations: Vec<Content>,
+    /// The references in bibliography order, each with the entry it belongs
+    /// to and its first field if that one is aligned separately.
+    pub references: Vec<(&'a Entry, Option<Content>, Content)>,
+    /// Whether the references should have a hanging indent.
+    pub hanging_indent: bool,
+}
+
+/// Parse a CSL style only once.
+#[comemo::memoize]
+fn parse(data: Bytes) -> StrResult<CslStyle> {
+    let text = std::str::from_utf8(&data).map_err(|_| FileError::InvalidUtf8)?;
+    let document = roxmltree::Document::parse(text)
+        .map_err(|err| format_xml_like_error("CSL file", err))?;
+
+    let root = document.root_element();
+    if !root.has_tag_name("style") {
+        bail!("CSL file must have a `style` root element");
+    }
+
+    let inherited = NameOptions::parse(root, "name-delimiter")?;
+    let mut macros = HashMap::new();
+    let mut terms = HashMap::new();
+    let mut citation = None;
+    let mut bibliography = None;
+    for child in root.children().filter(XmlNode::is_element) {
+        match child.tag_name().name() {
+            "info" => {
+                let dependent = child.children().any(|link| {
+                    link.has_tag_name("link")
+                        && link.attribute("rel") == Some("independent-parent")
+                });
+                if dependent {
+                    bail!("dependent CSL styles are not supported");
+                }
+            }
+            "locale" => parse_terms(child, &mut terms),
+            "macro" => {
+                let Some(name) = child.attribute("name") else {
+                    bail!("CSL macro must have a name");
+                };
+                macros.insert(name.into(), parse_children(child)?);
+            }
+            "citation" => citation = Some(Layout::parse(child, &inherited)?),
+            "bibliography" => bibliography = Some(Layout::parse(child, &inherited)?),
+            _ => {}
+        }
+    }
+
+    let Some(citation) = citation else {
+        bail!("CSL style must contain a `citation` element");
+    };
+
+    // Check that all used macros exist, so that rendering cannot fail.
+    let mut explicit_year_suffix = false;
+    let mut nodes: Vec<&Node> = macros.values().flatten().collect();
+    nodes.extend(&citation.children);
+    nodes.extend(bibliography.iter().flat_map(|layout| &layout.children));
+    for sort in [Some(&citation), bibliography.as_ref()].into_iter().flatten() {
+        for key in &sort.sort {
+            if let SortSource::Macro(name) = &key.source {
+                if !macros.contains_key(name) {
+                    bail!("CSL style uses undefined macro `{name}`");
+                }
+            }
+        }
+    }
+
+    while let Some(node) = nodes.pop() {
+        match node {
+            Node::Text(TextSource::Macro(name), _) if !macros.contains_key(name) => {
+                bail!("CSL style uses undefined macro `{name}`");
+            }
+            Node::Text(TextSource::Variable(name), _) if name == "year-suffix" => {
+                explicit_year_suffix = true;
+            }
+            Node::Names(names) => nodes.extend(&names.substitute),
+            Node::Group(children, _, _) => nodes.extend(children),
+            Node::Choose(branches, otherwise) => {
+                nodes.extend(branches.iter().flat_map(|(_, children)| children));
+                nodes.extend(otherwise);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(CslStyle(Arc::new(Repr {
+        hash: hash128(&data),
+        data,
+        note: root.attribute("class") == Some("note"),
+        macros,
+        terms,
+        explicit_year_suffix,
+        citation,
+        bibliography,
+    })))
+}
+
+type XmlNode<'a, 'i> = roxmltree::Node<'a, 'i>;
+
+/// Parse the terms of a CSL locale.
+fn parse_terms(
+    locale: XmlNode,
+    terms: &mut HashMap<(EcoString, TermForm), (EcoString, EcoString)>,
+) {
+    let defs = locale
+        .children()
+        .filter(|child| child.has_tag_name("terms"))
+        .flat_map(|child| child.children())
+        .filter(|child| child.has_tag_name("term"));
+
+    for term in defs {
+        let Some(name) = term.attribute("name") else { continue };
+        let form = TermForm::parse(term.attribute("form"));
+        let text = |tag: &str| {
+            term.children()
+                .find(|child| child.has_tag_name(tag))
+                .and_then(|child| child.text())
+                .map(EcoString::from)
+        };
+
+        let (single, multiple) = match (text("single"), text("multiple")) {
+            (Some(single), Some(multiple)) => (single, multiple),
+            _ => {
+                let text: EcoString = term.text().unwrap_or_default().trim().into();
+                (text.clone(), text)
+            }
+        };
+
+        terms.insert((name.into(), form), (single, multiple));
+    }
+}
+
+/// Parse the rendering elements below a node.
+fn parse_children(node: XmlNode) -> StrResult<Vec<Node>> {
+    let mut nodes = vec![];
+    for child in node.children().filter(XmlNode::is_element) {
+        if let Some(node) = Node::parse(child)? {
+            nodes.push(node);
+        }
+    }
+    Ok(nodes)
+}
+
+/// How to format citations or references.
+struct Layout {
+    /// Options that apply to the whole layout.
+    options: Options,
+    /// How to sort the entries.
+    sort: Vec<SortKey>,
+    /// The formatting of each rendered entry.
+    display: Display,
+    /// The delimiter between multiple entries of a citation.
+    delimiter: EcoString,
+    /// The rendering elements.
+    children: Vec<Node>,
+}
+
+impl Layout {
+    /// Parse a `citation` or `bibliography` element.
+    fn parse(node: XmlNode, inherited: &NameOptions) -> StrResult<Self> {
+        let options = Options {
+            names: NameOptions::parse(node, "name-delimiter")?.inherit(inherited),
+            add_year_suffix: node.attribute("disambiguate-add-year-suffix")
+                == Some("true"),
+            hanging_indent: node.attribute("hanging-indent") == Some("true"),
+            second_field_align: node
+                .attribute("second-field-align")
+                .map_or(false, |value| value != "false"),
+        };
+
+        let mut sort = vec![];
+        for key in node
+            .children()
+            .filter(|child| child.has_tag_name("sort"))
+            .flat_map(|child| child.children())
+            .filter(|child| child.has_tag_name("key"))
+        {
+            let source = match (key.attribute("variable"), key.attribute("macro")) {
+                (Some(variable), _) => SortSource::Variable(variable.into()),
+                (None, Some(name)) => SortSource::Macro(name.into()),
+                (None, None) => bail!("CSL sort key must have a variable or a macro"),
+            };
+            let descending = key.attribute("sort") == Some("descending");
+            sort.push(SortKey { source, descending });
+        }
+
+        // Prefer a layout without a locale restriction.
+        let layouts: Vec<_> =
+            node.children().filter(|child| child.has_tag_name("layout")).collect();
+        let Some(layout) = layouts
+            .iter()
+            .find(|layout| layout.attribute("locale").is_none())
+            .or(layouts.first())
+        else {
+            bail!("CSL {} must contain a `layout` element", node.tag_name().name());
+        };
+
+        Ok(Self {
+            options,
+            sort,
+            display: Display::parse(*layout),
+            delimiter: layout.attribute("delimiter").unwrap_or_default().into(),
+            children: parse_children(*layout)?,
+        })
+    }
+}
+
+/// Options of a citation or bibliography layout.
+#[derive(Default)]
+struct Options {
+    /// Inheritable name options.
+    names: NameOptions,
+    /// Whether to distinguish ambiguous citations with a year suffix.
+    add_year_suffix: bool,
+    /// Whether references have a hanging indent.
+    hanging_indent: bool,
+    /// Whether the first field of a reference is aligned separately.
+    second_field_align: bool,
+}
+
+/// Options for formatting lists of names.
+#[derive(Debug, Default, Clone)]
+struct NameOptions {
+    and: Option<And>,
+    delimiter: Option<EcoString>,
+    delimiter_precedes_last: Option<Precedes>,
+    et_al_min: Option<usize>,
+    et_al_use_first: Option<usize>,
+    initialize_with: Option<EcoString>,
+    name_as_sort_order: Option<SortOrder>,
+    sort_separator: Option<EcoString>,
+    form: Option<NameForm>,
+}
+
+impl NameOptions {
+    /// Parse name options from the attributes of a node. The delimiter
+    /// between names is read from the given attribute.
+    fn parse(node: XmlNode, delimiter: &str) -> StrResult<Self> {
+        let number = |attr: &str| -> StrResult<Option<usize>> {
+            node.attribute(attr)
+                .map(|value| {
+                    value.parse().map_err(|_| {
+                        eco_format!("CSL attribute `{attr}` must be a number")
+                    })
+                })
+                .transpose()
+        };
+
+        Ok(Self {
+            and: node.attribute("and").map(|value| match value {
+                "symbol" => And::Symbol,
+                _ => And::Text,
+            }),
+            delimiter: node.attribute(delimiter).map(Into::into),
+            delimiter_precedes_last: node.attribute("delimiter-precedes-last").map(
+                |value| match value {
+                    "always" => Precedes::Always,
+                    "never" => Precedes::Never,
+                    "after-inverted-name" => Precedes::AfterInvertedName,
+                    _ => Precedes::Contextual,
+                },
+            ),
+            et_al_min: number("et-al-min")?,
+            et_al_use_first: number("et-al-use-first")?,
+            initialize_with: node.attribute("initialize-with").map(Into::into),
+            name_as_sort_order: node.attribute("name-as-sort-order").map(|value| {
+                match value {
+                    "all" => SortOrder::All,
+                    _ => SortOrder::First,
+                }
+            }),
+            sort_separator: node.attribute("sort-separator").map(Into::into),
+            form: node.attribute("form").map(|value| match value {
+                "short" => NameForm::Short,
+                "count" => NameForm::Count,
+                _ => NameForm::Long,
+            }),
+        })
+    }
+
+    /// Fill unset options from a parent.
+    fn inherit(self, parent: &Self) -> Self {
+        Self {
+            and: self.and.or(parent.and),
+            delimiter: self.delimiter.or_else(|| parent.delimiter.clone()),
+            delimiter_precedes_last: self
+                .delimiter_precedes_last
+                .or(parent.delimiter_precedes_last),
+            et_al_min: self.et_al_min.or(parent.et_al_min),
+            et_al_use_first: self.et_al_use_first.or(parent.et_al_use_first),
+            initialize_with: self
+                .initialize_with
+                .or_else(|| parent.initialize_with.clone()),
+            name_as_sort_order: self.name_as_sort_order.or(parent.name_as_sort_order),
+            sort_separator: self.sort_separator.or_else(|| parent.sort_separator.clone()),
+            form: self.form.or(parent.form),
+        }
+    }
+}
+
+/// How to join the last two names.
+#[derive(Debug, Copy, Clone)]
+enum And {
+    Text,
+    Symbol,
+}
+
+/// When to put a delimiter before the last name.
+#[derive(Debug, Copy, Clone)]
+enum Precedes {
+    Contextual,
+    AfterInvertedName,
+    Always,
+    Never,
+}
+
+/// Which names to display family name first.
+#[derive(Debug, Copy, Clone)]
+enum SortOrder {
+    First,
+    All,
+}
+
+/// How to display names.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum NameForm {
+    Long,
+    Short,
+    Count,
+}
+
+/// A key by which entries are sorted.
+struct SortKey {
+    source: SortSource,
+    descending: bool,
+}
+
+impl SortKey {
+    /// Whether this key sorts by citation number.
+    fn is_citation_number(&self) -> bool {
+        matches!(&self.source, SortSource::Variable(name) if name == "citation-number")
+    }
+}
+
+/// What entries are sorted by.
+enum SortSource {
+    Variable(EcoString),
+    Macro(EcoString),
+}
+
+/// A rendering element.
+enum Node {
+    Text(TextSource, Display),
+    Number(EcoString, Display),
+    Label(EcoString, TermForm, Plural, Display),
+    Names(Box<Names>),
+    Date(Box<DateNode>),
+    Group(Vec<Node>, EcoString, Display),
+    Choose(Vec<(Condition, Vec<Node>)>, Vec<Node>),
+}
+
+impl Node {
+    /// Parse a rendering element. Returns `None` for unknown elements.
+    fn parse(node: XmlNode) -> StrResult<Option<Self>> {
+        let display = Display::parse(node);
+        let variable = || -> StrResult<EcoString> {
+            match node.attribute("variable") {
+                Some(variable) => Ok(variable.into()),
+                None => {
+                    bail!("CSL `{}` element must have a variable", node.tag_name().name())
+                }
+            }
+        };
+
+        Ok(Some(match node.tag_name().name() {
+            "text" => {
+                let source = if let Some(variable) = node.attribute("variable") {
+                    TextSource::Variable(variable.into())
+                } else if let Some(name) = node.attribute("macro") {
+                    TextSource::Macro(name.into())
+                } else if let Some(term) = node.attribute("term") {
+                    TextSource::Term(
+                        term.into(),
+                        TermForm::parse(node.attribute("form")),
+                        node.attribute("plural") == Some("true"),
+                    )
+                } else if let Some(value) = node.attribute("value") {
+                    TextSource::Value(value.into())
+                } else {
+                    bail!(
+                        "CSL `text` element must have a variable, macro, term, or value"
+                    );
+                };
+                Self::Text(source, display)
+            }
+            "number" => Self::Number(variable()?, display),
+            "label" => Self::Label(
+                node.attribute("variable").unwrap_or("locator").into(),
+                TermForm::parse(node.attribute("form")),
+                match node.attribute("plural") {
+                    Some("always") => Plural::Always,
+                    Some("never") => Plural::Never,
+                    _ => Plural::Contextual,
+                },
+                display,
+            ),
+            "names" => Self::Names(Box::new(Names::parse(node, display)?)),
+            "date" => Self::Date(Box::new(DateNode::parse(node, variable()?, display))),
+            "group" => Self::Group(
+                parse_children(node)?,
+                node.attribute("delimiter").unwrap_or_default().into(),
+                display,
+            ),
+            "choose" => {
+                let mut branches = vec![];
+                let mut otherwise = vec![];
+                for child in node.children().filter(XmlNode::is_element) {
+                    match child.tag_name().name() {
+                        "if" | "else-if" => branches
+                            .push((Condition::parse(child), parse_children(child)?)),
+                        "else" => otherwise = parse_children(child)?,
+                        _ => {}
+                    }
+                }
+                Self::Choose(branches, otherwise)
+            }
+            _ => return Ok(None),
+        }))
+    }
+}
+
+/// What a text element displays.
+enum TextSource {
+    Variable(EcoString),
+    Macro(EcoString),
+    Term(EcoString, TermForm, bool),
+    Value(EcoString),
+}
+
+/// The form of a term.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+enum TermForm {
+    Long,
+    Short,
+    Verb,
+    VerbShort,
+    Symbol,
+}
+
+impl TermForm {
+    /// Parse the form of a term, defaulting to the long form.
+    fn parse(form: Option<&str>) -> Self {
+        match form {
+            Some("short") => Self::Short,
+            Some("verb") => Self::Verb,
+            Some("verb-short") => Self::VerbShort,
+            Some("symbol") => Self::Symbol,
+            _ => Self::Long,
+        }
+    }
+
+    /// The form to fall back to if a term doesn't exist in this form.
+    fn fallback(self) -> Option<Self> {
+        match self {
+            Self::Long => None,
+            Self::Short | Self::Verb => Some(Self::Long),
+            Self::VerbShort => Some(Self::Verb),
+            Self::Symbol => Some(Self::Short),
+        }
+    }
+}
+
+/// When a label is pluralized.
+#[derive(Debug, Copy, Clone)]
+enum Plural {
+    Contextual,
+    Always,
+    Never,
+}
+
+/// A names element.
+struct Names {
+    variables: Vec<EcoString>,
+    options: NameOptions,
+    delimiter: EcoString,
+    et_al: EcoString,
+    label: Option<(TermForm, Display)>,
+    substitute: Vec<Node>,
+    display: Display,
+}
+
+impl Names {
+    /// Parse a names element.
+    fn parse(node: XmlNode, display: Display) -> StrResult<Self> {
+        let mut names = Self {
+            variables: node
+                .attribute("variable")
+                .unwrap_or_default()
+                .split_whitespace()
+                .map(Into::into)
+                .collect(),
+            options: NameOptions::default(),
+            delimiter: node.attribute("delimiter").unwrap_or(", ").into(),
+            et_al: "et-al".into(),
+            label: None,
+            substitute: vec![],
+            display,
+        };
+
+        for child in node.children().filter(XmlNode::is_element) {
+            match child.tag_name().name() {
+                "name" => names.options = NameOptions::parse(child, "delimiter")?,
+                "et-al" => {
+                    names.et_al = child.attribute("term").unwrap_or("et-al").into()
+                }
+                "label" => {
+                    names.label = Some((
+                        TermForm::parse(child.attribute("form")),
+                        Display::parse(child),
+                    ))
+                }
+                "substitute" => names.substitute = parse_children(child)?,
+                _ => {}
+            }
+        }
+
+        Ok(names)
+    }
+}
+
+/// A date element.
+struct DateNode {
+    variable: EcoString,
+    parts: Vec<DatePart>,
+    delimiter: EcoString,
+    display: Display,
+}
+
+impl DateNode {
+    /// Parse a date element.
+    fn parse(node: XmlNode, variable: EcoString, display: Display) -> Self {
+        let mut parts: Vec<_> = node
+            .children()
+            .filter(|child| child.has_tag_name("date-part"))
+            .map(|child| DatePart {
+                name: match child.attribute("name") {
+                    Some("day") => DatePartName::Day,
+                    Some("month") => DatePartName::Month,
+                    _ => DatePartName::Year,
+                },
+                form: child.attribute("form").unwrap_or_default().into(),
+                display: Display::parse(child),
+            })
+            .collect();
+
+        let mut delimiter = node.attribute("delimiter").unwrap_or_default().into();
+        if parts.is_empty() {
+            let part = |name, form: &str, suffix: &str| DatePart {
+                name,
+                form: form.into(),
+                display: Display { suffix: suffix.into(), ..Display::default() },
+            };
+
+            match node.attribute("form") {
+                Some("numeric") => {
+                    parts = vec![
+                        part(DatePartName::Month, "numeric", ""),
+                        part(DatePartName::Day, "numeric", ""),
+                        part(DatePartName::Year, "", ""),
+                    ];
+                    delimiter = "/".into();
+                }
+                Some(_) => {
+                    parts = vec![
+                        part(DatePartName::Month, "long", ""),
+                        part(DatePartName::Day, "numeric", ","),
+                        part(DatePartName::Year, "", ""),
+                    ];
+                    delimiter = " ".into();
+                }
+                None => parts = vec![part(DatePartName::Year, "", "")],
+            }
+        }
+
+        Self { variable, parts, delimiter, display }
+    }
+}
+
+/// A part of a date element.
+struct DatePart {
+    name: DatePartName,
+    form: EcoString,
+    display: Display,
+}
+
+/// Which part of a date to display.
+#[derive(Debug, Copy, Clone)]
+enum DatePartName {
+    Year,
+    Month,
+    Day,
+}
+
+/// The condition of a branch in a choose element.
+struct Condition {
+    tests: Vec<Test>,
+    mode: Match,
+}
+
+impl Condition {
+    /// Parse the condition of an `if` or `else-if` element.
+    fn parse(node: XmlNode) -> Self {
+        let mut tests = vec![];
+        let mut add = |attr: &str, test: fn(EcoString) -> Test| {
+            if let Some(value) = node.attribute(attr) {
+                tests.extend(value.split_whitespace().map(|value| test(value.into())));
+            }
+        };
+
+        add("type", Test::Type);
+        add("variable", Test::Variable);
+        add("is-numeric", Test::IsNumeric);
+        add("position", Test::Position);
+        add("locator", |_| Test::Locator);
+        add("is-uncertain-date", |_| Test::Never);
+        if node.attribute("disambiguate") == Some("true") {
+            tests.push(Test::Never);
+        }
+
+        let mode = match node.attribute("match") {
+            Some("any") => Match::Any,
+            Some("none") => Match::None,
+            _ => Match::All,
+        };
+
+        Self { tests, mode }
+    }
+}
+
+/// A single test of a condition.
+enum Test {
+    Type(EcoString),
+    Variable(EcoString),
+    IsNumeric(EcoString),
+    Position(EcoString),
+    Locator,
+    /// A test that is not supported and thus never passes.
+    Never,
+}
+
+/// How the tests of a condition are combined.
+#[derive(Debug, Copy, Clone)]
+enum Match {
+    All,
+    Any,
+    None,
+}
+
+/// Formatting attributes of a rendering element.
+#[derive(Debug, Default, Clone)]
+struct Display {
+    prefix: EcoString,
+    suffix: EcoString,
+    italic: bool,
+    bold: bool,
+    small_caps: bool,
+    superscript: bool,
+    quotes: bool,
+    strip_periods: bool,
+    text_case: Option<TextCase>,
+}
+
+impl Display {
+    /// Parse the formatting attributes of a node.
+    fn parse(node: XmlNode) -> Self {
+        Self {
+            prefix: node.attribute("prefix").unwrap_or_default().into(),
+            suffix: node.attribute("suffix").unwrap_or_default().into(),
+            italic: matches!(node.attribute("font-style"), Some("italic" | "oblique")),
+            bold: node.attribute("font-weight") == Some("bold"),
+            small_caps: node.attribute("font-variant") == Some("small-caps"),
+            superscript: node.attribute("vertical-align") == Some("sup"),
+            quotes: node.attribute("quotes") == Some("true"),
+            strip_periods: node.attribute("strip-periods") == Some("true"),
+            text_case: node.attribute("text-case").and_then(TextCase::parse),
+        }
+    }
+
+    /// The same formatting without the prefix and suffix.
+    fn without_affixes(&self) -> Self {
+        Self { prefix: EcoString::new(), ..self.without_suffix() }
+    }
+
+    /// The same formatting without the prefix.
+    fn without_prefix(&self) -> Self {
+        Self { prefix: EcoString::new(), ..self.clone() }
+    }
+
+    /// The same formatting without the suffix.
+    fn without_suffix(&self) -> Self {
+        Self { suffix: EcoString::new(), ..self.clone() }
+    }
+}
+
+/// A change of letter case.
+#[derive(Debug, Copy, Clone)]
+enum TextCase {
+    Lowercase,
+    Uppercase,
+    CapitalizeFirst,
+    CapitalizeAll,
+    Title,
+    Sentence,
+}
+
+impl TextCase {
+    /// Parse the value of a `text-case` attribute.
+    fn parse(value: &str) -> Option<Self> {
+        Some(match value {
+            "lowercase" => Self::Lowercase,
+            "uppercase" => Self::Uppercase,
+            "capitalize-first" => Self::CapitalizeFirst,
+            "capitalize-all" => Self::CapitalizeAll,
+            "title" => Self::Title,
+            "sentence" => Self::Sentence,
+            _ => return None,
+        })
+    }
+
+    /// Apply the case to rendered runs.
+    fn apply(self, runs: &mut [Run]) {
+        const STOP_WORDS: &[&str] = &[
+            "a", "an", "and", "as", "at", "but", "by", "for", "from", "in", "into",
+            "nor", "of", "on", "or", "the", "to", "with",
+        ];
+
+        let mut first_word = true;
+        let mut word_start = true;
+        for run in runs {
+            let text = match self {
+                Self::Lowercase => run.text.to_lowercase(),
+                Self::Uppercase => run.text.to_uppercase(),
+                Self::CapitalizeFirst | Self::Sentence => {
+                    let mut text = String::new();
+                    for c in run.text.chars() {
+                        if first_word && c.is_alphabetic() {
+                            text.extend(c.to_uppercase());
+                            first_word = false;
+                        } else {
+                            text.push(c);
+                        }
+                    }
+                    text
+                }
+                Self::CapitalizeAll | Self::Title => {
+                    let mut text = String::new();
+                    let mut rest = run.text.as_str();
+                    while let Some(c) = rest.chars().next() {
+                        if word_start && c.is_alphabetic() {
+                            let word =
+                                rest.split(|c: char| !c.is_alphabetic()).next().unwrap();
+                            let stop = matches!(self, Self::Title)
+                                && !first_word
+                                && STOP_WORDS.contains(&word);
+                            if stop {
+                                text.push_str(word);
+                            } else {
+                                text.extend(c.to_uppercase());
+                                text.push_str(&word[c.len_utf8()..]);
+                            }
+                            rest = &rest[word.len()..];
+                            word_start = false;
+                            first_word = false;
+                            continue;
+                        }
+                        word_start = c.is_whitespace();
+                        text.push(c);
+                        rest = &rest[c.len_utf8()..];
+                    }
+                    text
+                }
+            };
+            run.text = text.into();
+        }
+    }
+}
+
+/// The position of a citation relative to earlier citations of the same
+/// entry.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Position {
+    First,
+    Subsequent,
+    Ibid,
+    IbidWithLocator,
+}
+
+/// A piece of rendered text with consistent formatting.
+#[derive(Debug, Default, Clone)]
+struct Run {
+    text: EcoString,
+    format: Format,
+    /// Whether this run is a placeholder for the citation's supplement.
+    supplement: bool,
+}
+
+impl Run {
+    /// Create a run with default formatting.
+    fn new(text: impl Into<EcoString>) -> Self {
+        Self { text: text.into(), ..Self::default() }
+    }
+}
+
+/// The formatting of a run.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct Format {
+    italic: bool,
+    bold: bool,
+    small_caps: bool,
+    superscript: bool,
+    link: Option<EcoString>,
+}
+
+/// Whether the runs render to nothing.
+fn is_empty(runs: &[Run]) -> bool {
+    runs.iter().all(|run| run.text.is_empty() && !run.supplement)
+}
+
+/// The plain text of the runs.
+fn plain(runs: &[Run]) -> String {
+    runs.iter().map(|run| run.text.as_str()).collect()
+}
+
+/// Convert runs into content, replacing the supplement placeholder with the
+/// supplement.
+fn to_content(runs: Vec<Run>, supplement: &mut Option<Content>, span: Span) -> Content {
+    let mut merged: Vec<Run> = vec![];
+    for run in runs {
+        match merged.last_mut() {
+            Some(last)
+                if !last.supplement && !run.supplement && last.format == run.format =>
+            {
+                last.text.push_str(&run.text);
+            }
+            _ => merged.push(run),
+        }
+    }
+
+    let mut seq = vec![];
+    for run in merged {
+        let mut content = if run.supplement {
+            supplement.take().unwrap_or_default()
+        } else if run.text.is_empty() {
+            continue;
+        } else {
+            TextElem::packed(run.text).spanned(span)
+        };
+
+        let format = run.format;
+        if let Some(url) = format.link {
+            content =
+                LinkElem::new(LinkTarget::Dest(Destination::Url(url)), content).pack();
+        }
+        if format.italic {
+            content = content.emph();
+        }
+        if format.bold {
+            content = content.strong();
+        }
+        if format.small_caps {
+            content = content.styled(TextElem::set_smallcaps(true));
+        }
+        if format.superscript {
+            content = SuperElem::new(content).pack();
+        }
+
+        seq.push(content);
+    }
+
+    Content::sequence(seq)
+}
+
+/// How deeply macros may call each other, to stop recursive macros.
+const MAX_MACRO_DEPTH: usize = 32;
+
+/// Renders entries with a style.
+struct Renderer<'a> {
+    style: &'a Repr,
+    /// The citation number of each entry.
+    numbers: HashMap<&'a str, usize>,
+    /// The year suffix of each ambiguous entry.
+    suffixes: HashMap<&'a str, EcoString>,
+}
+
+/// The state while rendering one entry.
+struct Context<'a> {
+    entry: &'a Entry,
+    layout: &'a Layout,
+    /// Whether the entry is cited with a locator.
+    locator: bool,
+    position: Position,
+    /// How many variables were looked up in the current group.
+    called: usize,
+    /// How many of those variables were non-empty.
+    rendered: usize,
+    /// The variables that were rendered so far.
+    used: Vec<EcoString>,
+    /// Variables that were substituted for names and are not rendered again.
+    suppressed: HashSet<EcoString>,
+    /// Whether the year suffix was already rendered.
+    suffixed: bool,
+    /// How many macros are currently being expanded.
+    depth: usize,
+}
+
+impl<'a> Context<'a> {
+    fn new(
+        entry: &'a Entry,
+        layout: &'a Layout,
+        locator: bool,
+        position: Position,
+    ) -> Self {
+        Self {
+            entry,
+            layout,
+            locator,
+            position,
+            called: 0,
+            rendered: 0,
+            used: vec![],
+            suppressed: HashSet::new(),
+            suffixed: false,
+            depth: 0,
+        }
+    }
+}
+
+impl<'a> Renderer<'a> {
+    /// Render a sequence of elements.
+    fn render_nodes(&self, ctx: &mut Context<'a>, nodes: &'a [Node]) -> Vec<Run> {
+        nodes.iter().flat_map(|node| self.render(ctx, node)).collect()
+    }
+
+    /// Render a single element.
+    fn render(&self, ctx: &mut Context<'a>, node: &'a Node) -> Vec<Run> {
+        match node {
+            Node::Text(source, display) => {
+                let runs = match source {
+                    TextSource::Variable(name) => self.render_variable(ctx, name),
+                    TextSource::Macro(name) => match self.style.macros.get(name) {
+                        Some(nodes) if ctx.depth < MAX_MACRO_DEPTH => {
+                            ctx.depth += 1;
+                            let runs = self.render_nodes(ctx, nodes);
+                            ctx.depth -= 1;
+                            runs
+                        }
+                        _ => vec![],
+                    },
+                    TextSource::Term(name, form, plural) => {
+                        vec![Run::new(self.term(name, *form, *plural))]
+                    }
+                    TextSource::Value(value) => vec![Run::new(value.clone())],
+                };
+                self.format(display, runs)
+            }
+            Node::Number(name, display) => {
+                let runs = self.render_variable(ctx, name);
+                self.format(display, runs)
+            }
+            Node::Label(variable, form, plural, display) => {
+                // The supplement of a citation brings its own label.
+                if variable == "locator" {
+                    return vec![];
+                }
+
+                let Some((value, _)) = self.variable(ctx, variable) else {
+                    return vec![];
+                };
+
+                let plural = match plural {
+                    Plural::Always => true,
+                    Plural::Never => false,
+                    Plural::Contextual => value.contains(['–', '-', ',', '&']),
+                };
+
+                let runs = vec![Run::new(self.term(variable, *form, plural))];
+                self.format(display, runs)
+            }
+            Node::Names(names) => self.render_names(ctx, names),
+            Node::Date(date) => self.render_date(ctx, date),
+            Node::Group(children, delimiter, display) => {
+                let (called, rendered) = (ctx.called, ctx.rendered);
+                ctx.called = 0;
+                ctx.rendered = 0;
+
+                let outputs: Vec<_> = children
+                    .iter()
+                    .map(|child| self.render(ctx, child))
+                    .filter(|runs| !is_empty(runs))
+                    .collect();
+
+                // Groups whose variables are all empty are suppressed.
+                let suppressed = ctx.called > 0 && ctx.rendered == 0;
+                let nonempty = ctx.rendered > 0;
+                ctx.called = called + usize::from(ctx.called > 0);
+                ctx.rendered = rendered + usize::from(nonempty);
+                if suppressed {
+                    return vec![];
+                }
+
+                self.format(display, join(outputs, delimiter))
+            }
+            Node::Choose(branches, otherwise) => {
+                for (condition, children) in branches {
+                    if self.test(ctx, condition) {
+                        return self.render_nodes(ctx, children);
+                    }
+                }
+                self.render_nodes(ctx, otherwise)
+            }
+        }
+    }
+
+    /// Render a variable as text.
+    fn render_variable(&self, ctx: &mut Context, name: &EcoString) -> Vec<Run> {
+        ctx.called += 1;
+        let Some((text, link)) = self.variable(ctx, name) else { return vec![] };
+        ctx.rendered += 1;
+        ctx.used.push(name.clone());
+        if name == "year-suffix" {
+            ctx.suffixed = true;
+        }
+
+        if name == "locator" {
+            return vec![Run { supplement: true, ..Run::default() }];
+        }
+
+        let mut run = Run::new(text);
+        run.format.link = link;
+        vec![run]
+    }
+
+    /// Render a names element.
+    fn render_names(&self, ctx: &mut Context<'a>, names: &'a Names) -> Vec<Run> {
+        let options = names.options.clone().inherit(&ctx.layout.options.names);
+        ctx.called += 1;
+
+        let mut outputs = vec![];
+        for variable in &names.variables {
+            if ctx.suppressed.contains(variable) {
+                continue;
+            }
+
+            let persons = persons(ctx.entry, variable);
+            if persons.is_empty() {
+                continue;
+            }
+
+            let mut runs =
+                vec![Run::new(self.format_names(&options, &names.et_al, &persons))];
+            if let Some((form, display)) = &names.label {
+                let term = self.term(variable, *form, persons.len() > 1);
+                runs.extend(self.format(display, vec![Run::new(term)]));
+            }
+
+            ctx.used.push(variable.clone());
+            outputs.push(runs);
+        }
+
+        if !outputs.is_empty() {
+            ctx.rendered += 1;
+            return self.format(&names.display, join(outputs, &names.delimiter));
+        }
+
+        // Render the first non-empty substitute and suppress the variables it
+        // used in the rest of the entry.
+        for node in &names.substitute {
+            let used = ctx.used.len();
+            let runs = self.render(ctx, node);
+            if !is_empty(&runs) {
+                let substituted: Vec<_> = ctx.used.drain(used..).collect();
+                ctx.suppressed.extend(substituted);
+                ctx.rendered += 1;
+                return self.format(&names.display, runs);
+            }
+        }
+
+        vec![]
+    }
+
+    /// Format a list of persons.
+    fn format_names(
+        &self,
+        options: &NameOptions,
+        et_al: &str,
+        persons: &[&Person],
+    ) -> EcoString {
+        let mut shown = persons.len();
+        if let (Some(min), Some(first)) = (options.et_al_min, options.et_al_use_first) {
+            if persons.len() >= min {
+                shown = first.clamp(1, persons.len());
+            }
+        }
+
+        if options.form == Some(NameForm::Count) {
+            return eco_format!("{shown}");
+        }
+
+        let truncated = shown < persons.len();
+        let delimiter = options.delimiter.as_deref().unwrap_or(", ");
+        let inverted = |i: usize| match options.name_as_sort_order {
+            Some(SortOrder::All) => true,
+            Some(SortOrder::First) => i == 0,
+            None => false,
+        };
+
+        let mut out = EcoString::new();
+        for (i, person) in persons[..shown].iter().enumerate() {
+            if i > 0 {
+                let last = i + 1 == shown && !truncated;
+                match options.and.filter(|_| last) {
+                    Some(and) => {
+                        let precedes = match options
+                            .delimiter_precedes_last
+                            .unwrap_or(Precedes::Contextual)
+                        {
+                            Precedes::Contextual => shown > 2,
+                            Precedes::AfterInvertedName => inverted(i - 1),
+                            Precedes::Always => true,
+                            Precedes::Never => false,
+                        };
+                        out.push_str(if precedes { delimiter } else { " " });
+                        match and {
+                            And::Text => {
+                                out.push_str(&self.term("and", TermForm::Long, false))
+                            }
+                            And::Symbol => out.push('&'),
+                        }
+                        out.push(' ');
+                    }
+                    None => out.push_str(delimiter),
+                }
+            }
+            out.push_str(&format_person(person, options, inverted(i)));
+        }
+
+        if truncated {
+            out.push_str(if shown > 1 { delimiter } else { " " });
+            out.push_str(&self.term(et_al, TermForm::Long, false));
+        }
+
+        out
+    }
+
+    /// Render a date element.
+    fn render_date(&self, ctx: &mut Context, node: &DateNode) -> Vec<Run> {
+        ctx.called += 1;
+        let Some(date) = date(ctx.entry, &node.variable) else { return vec![] };
+        ctx.rendered += 1;
+        ctx.used.push(node.variable.clone());
+
+        let mut runs = vec![];
+        for part in &node.parts {
+            let text = match part.name {
+                DatePartName::Year => {
+                    let mut year = eco_format!("{}", date.year);
+                    if node.variable == "issued"
+                        && !self.style.explicit_year_suffix
+                        && !ctx.suffixed
+                    {
+                        if let Some(suffix) = self.suffixes.get(ctx.entry.key()) {
+                            year.push_str(suffix);
+                            ctx.suffixed = true;
+                        }
+                    }
+                    Some(year)
+                }
+                DatePartName::Month => date.month.map(|month| {
+                    let month = usize::from(month) + 1;
+                    match part.form.as_str() {
+                        "numeric" => eco_format!("{month}"),
+                        "numeric-leading-zeros" => eco_format!("{month:02}"),
+                        "short" => self.term(&month_term(month), TermForm::Short, false),
+                        _ => self.term(&month_term(month), TermForm::Long, false),
+                    }
+                }),
+                DatePartName::Day => date.day.map(|day| {
+                    let day = usize::from(day) + 1;
+                    match part.form.as_str() {
+                        "numeric-leading-zeros" => eco_format!("{day:02}"),
+                        "ordinal" => eco_format!("{day}{}", ordinal_suffix(day)),
+                        _ => eco_format!("{day}"),
+                    }
+                }),
+            };
+
+            let Some(text) = text else { continue };
+            if !runs.is_empty() {
+                runs.push(Run::new(node.delimiter.clone()));
+            }
+            runs.extend(self.format(&part.display, vec![Run::new(text)]));
+        }
+
+        self.format(&node.display, runs)
+    }
+
+    /// Whether a condition holds for the entry.
+    fn test(&self, ctx: &Context, condition: &Condition) -> bool {
+        let mut results = condition.tests.iter().map(|test| match test {
+            Test::Type(kind) => csl_type(ctx.entry) == kind,
+            Test::Variable(name) => {
+                self.variable(ctx, name).is_some()
+                    || !persons(ctx.entry, name).is_empty()
+                    || date(ctx.entry, name).is_some()
+            }
+            Test::IsNumeric(name) => self
+                .variable(ctx, name)
+                .map_or(false, |(value, _)| is_numeric(&value)),
+            Test::Position(position) => match position.as_str() {
+                "first" => ctx.position == Position::First,
+                "subsequent" | "near-note" => ctx.position != Position::First,
+                "ibid" => {
+                    matches!(ctx.position, Position::Ibid | Position::IbidWithLocator)
+                }
+                "ibid-with-locator" => ctx.position == Position::IbidWithLocator,
+                _ => false,
+            },
+            Test::Locator => ctx.locator,
+            Test::Never => false,
+        });
+
+        match condition.mode {
+            Match::All => results.all(|result| result),
+            Match::Any => results.any(|result| result),
+            Match::None => !results.any(|result| result),
+        }
+    }
+
+    /// Look up the value of a text or number variable and possibly a link
+    /// for it.
+    fn variable(
+        &self,
+        ctx: &Context,
+        name: &str,
+    ) -> Option<(EcoString, Option<EcoString>)> {
+        if ctx.suppressed.contains(name) {
+            return None;
+        }
+
+        let entry = ctx.entry;
+        let parent = || entry.parents().into_iter().flatten();
+        let text = match name {
+            "title" => entry.title().map(|v| v.csl()),
+            "container-title" => {
+                parent().find_map(|parent| parent.title()).map(|v| v.csl())
+            }
+            "publisher" => lookup(entry, |e| e.publisher().map(|v| v.csl())),
+            "publisher-place" => lookup(entry, |e| e.location().map(|v| v.csl())),
+            "volume" => lookup(entry, |e| e.volume().map(|v| v.csl())),
+            "issue" => lookup(entry, |e| e.issue().map(|v| v.csl())),
+            "edition" => lookup(entry, |e| e.edition().map(|v| v.csl())),
+            "page" => entry.page_range().map(|v| v.csl()),
+            "DOI" => {
+                let doi = entry.doi().map(|v| v.csl())?;
+                let link = eco_format!("https://doi.org/{doi}");
+                return Some((doi, Some(link)));
+            }
+            "URL" => {
+                let url: EcoString = entry.url()?.value.as_str().into();
+                return Some((url.clone(), Some(url)));
+            }
+            "citation-number" => {
+                self.numbers.get(entry.key()).map(|n| eco_format!("{n}"))
+            }
+            "citation-label" => Some(citation_label(entry)),
+            "year-suffix" => self.suffixes.get(entry.key()).cloned(),
+            "locator" => ctx.locator.then(EcoString::new),
+            _ => None,
+        };
+
+        text.filter(|text| !text.is_empty() || name == "locator")
+            .map(|text| (text, None))
+    }
+
+    /// Look up a term.
+    fn term(&self, name: &str, form: TermForm, plural: bool) -> EcoString {
+        let mut current = Some(form);
+        while let Some(form) = current {
+            if let Some((single, multiple)) = self.style.terms.get(&(name.into(), form)) {
+                return if plural { multiple.clone() } else { single.clone() };
+            }
+            if let Some((single, multiple)) = default_term(name, form) {
+                return if plural { multiple.into() } else { single.into() };
+            }
+            current = form.fallback();
+        }
+        EcoString::new()
+    }
+
+    /// Apply formatting to rendered runs.
+    fn format(&self, display: &Display, mut runs: Vec<Run>) -> Vec<Run> {
+        if is_empty(&runs) {
+            return vec![];
+        }
+
+        if let Some(case) = display.text_case {
+            case.apply(&mut runs);
+        }
+
+        for run in &mut runs {
+            if display.strip_periods {
+                run.text = run.text.replace('.', "").into();
+            }
+            run.format.italic |= display.italic;
+            run.format.bold |= display.bold;
+            run.format.small_caps |= display.small_caps;
+            run.format.superscript |= display.superscript;
+        }
+
+        if display.quotes {
+            runs.insert(0, Run::new(self.term("open-quote", TermForm::Long, false)));
+            runs.push(Run::new(self.term("close-quote", TermForm::Long, false)));
+        }
+
+        if !display.prefix.is_empty() {
+            runs.insert(0, Run::new(display.prefix.clone()));
+        }
+
+        if !display.suffix.is_empty() {
+            // Avoid doubled periods, e.g. after abbreviations.
+            let mut suffix = display.suffix.as_str();
+            if suffix.starts_with('.') && plain(&runs).ends_with('.') {
+                suffix = &suffix[1..];
+            }
+            runs.push(Run::new(suffix));
+        }
+
+        runs
+    }
+
+    /// Sort entries by the keys of a layout.
+    fn sort(&self, layout: &'a Layout, entries: Vec<&'a Entry>) -> Vec<&'a Entry> {
+        let mut keyed: Vec<_> = entries
+            .into_iter()
+            .map(|entry| {
+                let values: Vec<_> = layout
+                    .sort
+                    .iter()
+                    .map(|key| self.sort_value(layout, key, entry))
+                    .collect();
+                (values, entry)
+            })
+            .collect();
+
+        keyed.sort_by(|(a, _), (b, _)| {
+            for (key, (x, y)) in layout.sort.iter().zip(a.iter().zip(b)) {
+                // Empty values always come last.
+                let ordering = match (x, y) {
+                    (Some(x), Some(y)) => {
+                        let ordering = x.to_lowercase().cmp(&y.to_lowercase());
+                        if key.descending {
+                            ordering.reverse()
+                        } else {
+                            ordering
+                        }
+                    }
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => Ordering::Equal,
+                };
+
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            Ordering::Equal
+        });
+
+        keyed.into_iter().map(|(_, entry)| entry).collect()
+    }
+
+    /// The value by which an entry is sorted for a key.
+    fn sort_value(
+        &self,
+        layout: &'a Layout,
+        key: &SortKey,
+        entry: &'a Entry,
+    ) -> Option<String> {
+        let mut ctx = Context::new(entry, layout, false, Position::First);
+        match &key.source {
+            SortSource::Variable(name) => {
+                let persons = persons(entry, name);
+                if !persons.is_empty() {
+                    let names: Vec<_> = persons
+                        .iter()
+                        .map(|person| {
+                            let given = person.given_name.as_deref().unwrap_or_default();
+                            format!("{} {given}", person.name)
+                        })
+                        .collect();
+                    return Some(names.join(", "));
+                }
+
+                if let Some(date) = date(entry, name) {
+                    let month = date.month.map_or(0, |month| month + 1);
+                    let day = date.day.map_or(0, |day| day + 1);
+                    return Some(format!("{:06}{month:02}{day:02}", date.year));
+                }
+
+                let (value, _) = self.variable(&ctx, name)?;
+                Some(if is_numeric(&value) {
+                    let digits: String =
+                        value.chars().take_while(char::is_ascii_digit).collect();
+                    format!("{digits:0>10}")
+                } else {
+                    value.into()
+                })
+            }
+            SortSource::Macro(name) => {
+                let nodes = self.style.macros.get(name)?;
+                let text = plain(&self.render_nodes(&mut ctx, nodes));
+                (!text.is_empty()).then_some(text)
+            }
+        }
+    }
+}
+
+/// Join rendered outputs with a delimiter.
+fn join(outputs: Vec<Vec<Run>>, delimiter: &str) -> Vec<Run> {
+    let mut runs = vec![];
+    for (i, output) in outputs.into_iter().enumerate() {
+        if i > 0 && !delimiter.is_empty() {
+            runs.push(Run::new(delimiter));
+        }
+        runs.extend(output);
+    }
+    runs
+}
+
+/// Format a single person's name.
+fn format_person(person: &Person, options: &NameOptions, inverted: bool) -> EcoString {
+    let mut family = EcoString::new();
+    if let Some(prefix) = &person.prefix {
+        family.push_str(prefix);
+        family.push(' ');
+    }
+    family.push_str(&person.name);
+
+    let Some(given) = &person.given_name else { return family };
+    if options.form == Some(NameForm::Short) {
+        return family;
+    }
+
+    let given = match &options.initialize_with {
+        Some(with) => initialize(given, with),
+        None => given.as_str().into(),
+    };
+
+    let mut name = if inverted {
+        let separator = options.sort_separator.as_deref().unwrap_or(", ");
+        eco_format!("{family}{separator}{given}")
+    } else {
+        eco_format!("{given} {family}")
+    };
+
+    if let Some(suffix) = &person.suffix {
+        name.push_str(", ");
+        name.push_str(suffix);
+    }
+
+    name
+}
+
+/// Abbreviate given names to their initials.
+fn initialize(given: &str, with: &str) -> EcoString {
+    let spaced = with.ends_with(' ');
+    let with = with.trim_end();
+    let mut out = EcoString::new();
+    for (i, part) in given.split_whitespace().enumerate() {
+        if i > 0 && spaced {
+            out.push(' ');
+        }
+        for (k, piece) in part.split('-').enumerate() {
+            let Some(c) = piece.chars().next() else { continue };
+            if k > 0 {
+                out.push('-');
+            }
+            out.push(c);
+            out.push_str(with);
+        }
+    }
+    out
+}
+
+/// The persons of a names variable.
+fn persons<'a>(entry: &'a Entry, variable: &str) -> Vec<&'a Person> {
+    match variable {
+        "author" => entry.authors().into_iter().flatten().collect(),
+        "editor" => {
+            let own: Vec<_> = entry.editors().into_iter().flatten().collect();
+            if !own.is_empty() {
+                return own;
+            }
+            entry
+                .parents()
+                .into_iter()
+                .flatten()
+                .flat_map(|parent| parent.editors().into_iter().flatten())
+                .collect()
+        }
+        _ => vec![],
+    }
+}
+
+/// The value of a date variable.
+fn date<'a>(entry: &'a Entry, variable: &str) -> Option<&'a Date> {
+    match variable {
+        "issued" => entry
+            .date()
+            .or_else(|| entry.parents().into_iter().flatten().find_map(|p| p.date())),
+        "accessed" => entry.url().and_then(|url| url.visit_date.as_ref()),
+        _ => None,
+    }
+}
+
+/// Look up a field of an entry, falling back to its parents.
+fn lookup<T>(entry: &Entry, f: impl Fn(&Entry) -> Option<T>) -> Option<T> {
+    f(entry).or_else(|| entry.parents().into_iter().flatten().find_map(&f))
+}
+
+/// The CSL type of an entry.
+fn csl_type(entry: &Entry) -> &'static str {
+    match entry.entry_type() {
+        EntryType::Article => "article-journal",
+        EntryType::Book | EntryType::Proceedings => "book",
+        EntryType::Chapter => "chapter",
+        EntryType::Thesis => "thesis",
+        EntryType::Report => "report",
+        EntryType::Web => "webpage",
+        _ => "document",
+    }
+}
+
+/// An alphanumerical label for an entry, like `Ast20`.
+fn citation_label(entry: &Entry) -> EcoString {
+    let mut label: EcoString = match entry.authors().into_iter().flatten().next() {
+        Some(person) => {
+            person.name.chars().filter(|c| c.is_alphabetic()).take(3).collect()
+        }
+        None => entry.key().chars().take(3).collect(),
+    };
+
+    if let Some(date) = entry.date() {
+        let year = date.year.rem_euclid(100);
+        label.push_str(&eco_format!("{year:02}"));
+    }
+
+    label
+}
+
+/// Whether a value is numeric in the sense of CSL.
+fn is_numeric(value: &str) -> bool {
+    let mut digits = false;
+    for c in value.chars() {
+        if c.is_ascii_digit() {
+            digits = true;
+        } else if !matches!(c, '-' | '–' | ',' | '&' | ' ') {
+            return false;
+        }
+    }
+    digits
+}
+
+/// The year suffix for the `i`-th of multiple ambiguous entries.
+fn year_suffix(i: usize) -> EcoString {
+    let letter = char::from(b'a' + (i % 26) as u8);
+    std::iter::repeat(letter).take(i / 26 + 1).collect()
+}
+
+/// The term name for a month.
+fn month_term(month: usize) -> EcoString {
+    eco_format!("month-{month:02}")
+}
+
+/// The English ordinal suffix of a number.
+fn ordinal_suffix(n: usize) -> &'static str {
+    match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    }
+}
+
+/// The default English terms.
+fn default_term(name: &str, form: TermForm) -> Option<(&'static str, &'static str)> {
+    const MONTHS: [(&str, &str); 12] = [
+        ("January", "Jan."),
+        ("February", "Feb."),
+        ("March", "Mar."),
+        ("April", "Apr."),
+        ("May", "May"),
+        ("June", "June"),
+        ("July", "July"),
+        ("August", "Aug."),
+        ("September", "Sept."),
+        ("October", "Oct."),
+        ("November", "Nov."),
+        ("December", "Dec."),
+    ];
+
+    if let Some(month) = name.strip_prefix("month-") {
+        let (long, short) = *MONTHS.get(month.parse::<usize>().ok()?.checked_sub(1)?)?;
+        return match form {
+            TermForm::Long => Some((long, long)),
+            TermForm::Short => Some((short, short)),
+            _ => None,
+        };
+    }
+
+    Some(match (name, form) {
+        ("and", TermForm::Symbol) => ("&", "&"),
+        ("and", TermForm::Long) => ("and", "and"),
+        ("et-al", TermForm::Long) => ("et al.", "et al."),
+        ("and others", TermForm::Long) => ("and others", "and others"),
+        ("editor", TermForm::Short) => ("ed.", "eds."),
+        ("editor", TermForm::Long) => ("editor", "editors"),
+        ("editor", TermForm::VerbShort) => ("ed.", "ed."),
+        ("editor", TermForm::Verb) => ("edited by", "edited by"),
+        ("page", TermForm::Short) => ("p.", "pp."),
+        ("page", TermForm::Long) => ("page", "pages"),
+        ("volume", TermForm::Short) => ("vol.", "vols."),
+        ("volume", TermForm::Long) => ("volume", "volumes"),
+        ("issue", TermForm::Short) => ("no.", "nos."),
+        ("issue", TermForm::Long) => ("issue", "issues"),
+        ("edition", TermForm::Short) => ("ed.", "eds."),
+        ("edition", TermForm::Long) => ("edition", "editions"),
+        ("in", TermForm::Long) => ("in", "in"),
+        ("accessed", TermForm::Long) => ("accessed", "accessed"),
+        ("retrieved", TermForm::Long) => ("retrieved", "retrieved"),
+        ("from", TermForm::Long) => ("from", "from"),
+        ("available at", TermForm::Long) => ("available at", "available at"),
+        ("ibid", TermForm::Long) => ("ibid.", "ibid."),
+        ("no date", TermForm::Short) => ("n.d.", "n.d."),
+        ("no date", TermForm::Long) => ("no date", "no date"),
+        ("open-quote", TermForm::Long) => ("“", "“"),
+        ("close-quote", TermForm::Long) => ("”", "”"),
+        ("open-inner-quote", TermForm::Long) => ("‘", "‘"),
+        ("close-inner-quote", TermForm::Long) => ("’", "’"),
+        _ => return None,
+    })
+}
+
+/// Conversion of entry fields into text.
+trait Field {
+    fn csl(&self) -> EcoString;
+}
+
+impl Field for FormatString {
+    fn csl(&self) -> EcoString {
+        self.canonical.value.as_str().into()
+    }
+}
+
+impl Field for NumOrStr {
+    fn csl(&self) -> EcoString {
+        eco_format!("{self}")
+    }
+}
+
+impl Field for Range<i64> {
+    fn csl(&self) -> EcoString {
+        if self.end > self.start {
+            eco_format!("{}–{}", self.start, self.end)
+        } else {
+            eco_format!("{}", self.start)
+        }
+    }
+}
+
+impl Field for str {
+    fn csl(&self) -> EcoString {
+        self.into()
+    }
+}
+
+impl Field for String {
+    fn csl(&self) -> EcoString {
+        self.as_str().into()
+    }
+}
+
+impl Debug for CslStyle {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.pad("CslStyle(..)")
+    }
+}
+
+impl Eq for CslStyle {}
+
+impl PartialEq for CslStyle {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.hash == other.0.hash
+    }
+}
+
+impl Hash for CslStyle {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u128(self.0.hash);
+    }
+}
+
+cast! {
+    CslStyle,
+    self => self.0.data.clone().into_value(),
+    v: Bytes => Self::parse(v)?,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An author-date style with et-al rules, year suffixes, locators, and a
+    /// hanging indent.
+    const STYLE: &str = r#"
+<style xmlns="http://purl.org/net/xbiblio/csl" class="in-text" version="1.0">
+  <macro name="author">
+    <names variable="author">
+      <name form="short" and="symbol" et-al-min="3" et-al-use-first="1"/>
+    </names>
+  </macro>
+  <citation disambiguate-add-year-suffix="true">
+    <layout prefix="(" suffix=")" delimiter="; ">
+      <group delimiter=", ">
+        <text macro="author"/>
+        <group>
+          <date variable="issued"><date-part name="year"/></date>
+          <text variable="year-suffix"/>
+        </group>
+        <text variable="locator"/>
+      </group>
+    </layout>
+  </citation>
+  <bibliography hanging-indent="true">
+    <layout suffix=".">
+      <group delimiter=", ">
+        <text macro="author"/>
+        <text variable="title"/>
+      </group>
+    </layout>
+  </bibliography>
+</style>
+"#;
+
+    const ENTRIES: &str = r#"
+many:
+  type: Article
+  title: Many Authors
+  author: ["Doe, Jane", "Roe, Richard", "Poe, Edgar"]
+  date: 2020
+two:
+  type: Article
+  title: Two Authors
+  author: ["Doe, Jane", "Roe, Richard"]
+  date: 2021
+first:
+  type: Book
+  title: First Book
+  author: ["Smith, Anna"]
+  date: 2019
+second:
+  type: Book
+  title: Second Book
+  author: ["Smith, Anna"]
+  date: 2019
+"#;
+
+    /// Render citations of the given keys, each with an optional supplement,
+    /// and return the plain text of the citations and the references.
+    fn render(
+        citations: &[(&[&str], Option<&str>)],
+    ) -> (Vec<EcoString>, Vec<EcoString>, bool) {
+        let style = CslStyle::parse(Bytes::from_static(STYLE.as_bytes())).unwrap();
+        let entries = hayagriva::io::from_yaml_str(ENTRIES).unwrap();
+        let find = |key: &str| entries.iter().find(|entry| entry.key() == key).unwrap();
+        let citations: Vec<_> = citations
+            .iter()
+            .map(|&(keys, supplement)| CslCitation {
+                entries: keys.iter().map(|&key| find(key)).collect(),
+                supplement: supplement.map(TextElem::packed),
+                brackets: true,
+            })
+            .collect();
+
+        let rendered = style.render(&citations, Span::detached(), &|_, content| content);
+        let citations = rendered.citations.iter().map(Content::plain_text).collect();
+        let references = rendered
+            .references
+            .iter()
+            .map(|(_, prefix, reference)| {
+                let mut text =
+                    prefix.as_ref().map(Content::plain_text).unwrap_or_default();
+                text.push_str(&reference.plain_text());
+                text
+            })
+            .collect();
+        (citations, references, rendered.hanging_indent)
+    }
+
+    #[test]
+    fn test_csl_et_al() {
+        let (citations, references, _) = render(&[(&["many"], None), (&["two"], None)]);
+        assert_eq!(citations, ["(Doe et al., 2020)", "(Doe & Roe, 2021)"]);
+        assert_eq!(references[0], "Doe et al., Many Authors.");
+    }
+
+    #[test]
+    fn test_csl_disambiguation() {
+        let (citations, ..) = render(&[(&["first"], None), (&["second", "many"], None)]);
+        assert_eq!(citations, ["(Smith, 2019a)", "(Smith, 2019b; Doe et al., 2020)"]);
+    }
+
+    #[test]
+    fn test_csl_locator() {
+        let (citations, ..) = render(&[(&["two"], Some("p. 5")), (&["first"], None)]);
+        assert_eq!(citations, ["(Doe & Roe, 2021, p. 5)", "(Smith, 2019)"]);
+    }
+
+    #[test]
+    fn test_csl_hanging_indent() {
+        let (_, references, hanging_indent) = render(&[(&["first", "two"], None)]);
+        assert!(hanging_indent);
+        assert_eq!(references, ["Smith, First Book.", "Doe & Roe, Two Authors."]);
+    }
+}
@@ -1,3 +1,6 @@
+use typst::eval::Datetime;
+
+use super::{Counter, CounterKey, Numbering};
 use crate::layout::{LayoutRoot, PageElem};
 use crate::prelude::*;
 
@@ -29,6 +32,19 @@ pub struct DocumentElem {
     /// The document's authors.
     pub author: Author,
 
+    /// The document's keywords.
+    pub keywords: Keywords,
+
+    /// The document's creation date.
+    ///
+    /// If this is `{auto}` (default), the current date and time is used. If
+    /// this is `{none}`, no creation date is embedded.
+    ///
+    /// ```example
+    /// #set document(date: datetime(year: 2023, month: 6, day: 19))
+    /// ```
+    pub date: Smart<Option<Datetime>>,
+
     /// The page runs.
     #[internal]
     #[variadic]
@@ -66,14 +82,68 @@ impl LayoutRoot for DocumentElem {
             }
         }
 
+        let date = match self.date(styles) {
+            Smart::Auto => vt.world.now(None),
+            Smart::Custom(date) => date,
+        };
+
+        let page_labels = page_labels(vt, &pages)?;
+
         Ok(Document {
             pages,
             title: self.title(styles),
             author: self.author(styles).0,
+            keywords: self.keywords(styles).0,
+            date,
+            page_labels,
         })
     }
 }
 
+/// Determine the labels of the pages from their numbering and the value of
+/// the page counter on them.
+///
+/// If no page is numbered, there are no labels and viewers fall back to the
+/// physical page numbers. Otherwise, pages whose numbering has no label
+/// equivalent are labelled with the decimal value of the page counter.
+fn page_labels(vt: &mut Vt, pages: &[Frame]) -> SourceResult<Vec<PageLabel>> {
+    let numberings: Vec<Option<Numbering>> = pages
+        .iter()
+        .map(|page| {
+            page.items()
+                .find_map(|(_, item)| match item {
+                    FrameItem::Meta(Meta::PageNumbering(value), _) => Some(value),
+                    _ => None,
+                })
+                .and_then(|value| value.clone().cast::<Option<Numbering>>().ok())
+                .flatten()
+        })
+        .collect();
+
+    if numberings.iter().all(Option::is_none) {
+        return Ok(vec![]);
+    }
+
+    let counter = Counter::new(CounterKey::Page);
+    let mut labels = Vec::with_capacity(pages.len());
+    for (i, numbering) in numberings.iter().enumerate() {
+        let page = NonZeroUsize::ONE.saturating_add(i);
+        let number = counter.at_page(vt, page)?.first();
+        labels.push(
+            numbering
+                .as_ref()
+                .and_then(|numbering| numbering.page_label(number))
+                .unwrap_or_else(|| PageLabel {
+                    style: Some(PageLabelStyle::Arabic),
+                    prefix: None,
+                    offset: NonZeroUsize::new(number),
+                }),
+        );
+    }
+
+    Ok(labels)
+}
+
 /// A list of authors.
 #[derive(Debug, Default, Clone, Hash)]
 pub struct Author(Vec<EcoString>);
@@ -84,3 +154,14 @@ cast! {
     v: EcoString => Self(vec![v]),
     v: Array => Self(v.into_iter().map(Value::cast).collect::<StrResult<_>>()?),
 }
+
+/// A list of keywords.
+#[derive(Debug, Default, Clone, Hash)]
+pub struct Keywords(Vec<EcoString>);
+
+cast! {
+    Keywords,
+    self => self.0.into_value(),
+    v: EcoString => Self(vec![v]),
+    v: Array => Self(v.into_iter().map(Value::cast).collect::<StrResult<_>>()?),
+}
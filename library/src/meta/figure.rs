@@ -3,7 +3,7 @@ use std::str::FromStr;
 use super::{
     Count, Counter, CounterKey, CounterUpdate, LocalName, Numbering, NumberingPattern,
 };
-use crate::layout::{BlockElem, VElem};
+use crate::layout::{BlockElem, PlaceElem, VElem};
 use crate::meta::{Outlinable, Refable, Supplement};
 use crate::prelude::*;
 use crate::text::TextElem;
@@ -83,6 +83,33 @@ pub struct FigureElem {
     #[required]
     pub body: Content,
 
+    /// The figure's placement on the page.
+    ///
+    /// - `{none}`: The figure stays in-flow exactly where it was specified
+    ///   like other content.
+    /// - `{auto}`: The figure picks `{top}` or `{bottom}` depending on which
+    ///   is closer.
+    /// - `{top}`: The figure floats to the top of the page.
+    /// - `{bottom}`: The figure floats to the bottom of the page.
+    ///
+    /// A floating figure that doesn't fit onto the current page is moved to
+    /// the next one, together with its caption. Use
+    /// [`place.flush`]($func/place.flush) to place all pending floating figures
+    /// before continuing.
+    ///
+    /// ```example
+    /// #set page(height: 200pt)
+    ///
+    /// = Introduction
+    /// #figure(
+    ///   placement: bottom,
+    ///   caption: [A glacier],
+    ///   image("glacier.jpg", width: 60%),
+    /// )
+    /// #lorem(60)
+    /// ```
+    pub placement: Option<Smart<VerticalAlign>>,
+
     /// The figure's caption.
     pub caption: Option<Content>,
 
@@ -244,10 +271,25 @@ impl Show for FigureElem {
         }
 
         // Wrap the contents in a block.
-        Ok(BlockElem::new()
+        realized = BlockElem::new()
             .with_body(Some(realized))
             .pack()
-            .aligned(Axes::with_x(Some(Align::Center.into()))))
+            .aligned(Axes::with_x(Some(Align::Center.into())));
+
+        // Wrap in a float.
+        if let Some(placement) = self.placement(styles) {
+            let align = match placement {
+                Smart::Auto => None,
+                Smart::Custom(VerticalAlign(align)) => Some(align),
+            };
+
+            realized = PlaceElem::new(realized)
+                .with_float(true)
+                .with_alignment(Axes::new(Some(Align::Center.into()), align))
+                .pack();
+        }
+
+        Ok(realized)
     }
 }
 
@@ -1,7 +1,10 @@
 use comemo::Prehashed;
+use std::collections::HashMap;
 use std::str::FromStr;
 
-use super::{Counter, Numbering, NumberingPattern};
+use smallvec::smallvec;
+
+use super::{Counter, CounterState, Numbering, NumberingPattern};
 use crate::layout::{HElem, ParElem};
 use crate::meta::{Count, CounterUpdate};
 use crate::prelude::*;
@@ -73,9 +76,8 @@ pub struct FootnoteElem {
     /// How to number footnotes.
     ///
     /// By default, the footnote numbering continues throughout your document.
-    /// If you prefer per-page footnote numbering, you can reset the footnote
-    /// [counter]($func/counter) in the page [header]($func/page.header). In the
-    /// future, there might be a simpler way to achieve this.
+    /// If you prefer per-page footnote numbering, see the
+    /// [`per-page`]($func/footnote.per-page) property.
     ///
     /// ```example
     /// #set footnote(numbering: "*")
@@ -87,6 +89,23 @@ pub struct FootnoteElem {
     #[default(Numbering::Pattern(NumberingPattern::from_str("1").unwrap()))]
     pub numbering: Numbering,
 
+    /// Whether to restart the footnote numbering on each page.
+    ///
+    /// If `{true}`, the first footnote on each page is numbered with one. This
+    /// only affects the displayed numbers, the footnote
+    /// [counter]($func/counter) still counts all footnotes in the document.
+    ///
+    /// ```example
+    /// #set page(height: 100pt)
+    /// #set footnote(per-page: true)
+    ///
+    /// A #footnote[First page]
+    /// #pagebreak()
+    /// B #footnote[Second page]
+    /// ```
+    #[default(false)]
+    pub per_page: bool,
+
     /// The content to put into the footnote. Can also be the label of another
     /// footnote this one should point to.
     #[required]
@@ -130,11 +149,44 @@ impl FootnoteElem {
             _ => Ok(self.0.location().unwrap()),
         }
     }
+
+    /// Determine the number of the footnote declared at the given location.
+    pub fn number(&self, vt: &mut Vt, loc: Location) -> SourceResult<CounterState> {
+        if !self.per_page(StyleChain::default()) {
+            return Counter::of(Self::func()).at(vt, loc);
+        }
+
+        // The location is always that of a declaration, which is numbered.
+        let count = per_page_numbers(vt.introspector).get(&loc).copied().unwrap_or(0);
+        Ok(CounterState(smallvec![count]))
+    }
+}
+
+/// Number the footnote declarations on each page, starting at one.
+///
+/// This walks all footnotes once, so that showing the notes of a page doesn't
+/// query the document again for each of them.
+#[comemo::memoize]
+fn per_page_numbers(introspector: Tracked<Introspector>) -> HashMap<Location, usize> {
+    let mut counts = HashMap::<NonZeroUsize, usize>::new();
+    let mut numbers = HashMap::new();
+    for elem in introspector.query(&Selector::Elem(FootnoteElem::func(), None)) {
+        let Some(loc) = elem.location() else { continue };
+        if elem.to::<FootnoteElem>().map_or(true, |note| note.is_ref()) {
+            continue;
+        }
+
+        let count = counts.entry(introspector.page(loc)).or_default();
+        *count += 1;
+        numbers.insert(loc, *count);
+    }
+    numbers
 }
 
 impl Synthesize for FootnoteElem {
     fn synthesize(&mut self, _vt: &mut Vt, styles: StyleChain) -> SourceResult<()> {
         self.push_numbering(self.numbering(styles));
+        self.push_per_page(self.per_page(styles));
         Ok(())
     }
 }
@@ -145,8 +197,7 @@ impl Show for FootnoteElem {
         Ok(vt.delayed(|vt| {
             let loc = self.declaration_location(vt).at(self.span())?;
             let numbering = self.numbering(styles);
-            let counter = Counter::of(Self::func());
-            let num = counter.at(vt, loc)?.display(vt, &numbering)?;
+            let num = self.number(vt, loc)?.display(vt, &numbering)?;
             let sup = SuperElem::new(num).pack();
             let hole = HElem::new(Abs::zero().into()).with_weak(true).pack();
             let loc = loc.variant(1);
@@ -267,9 +318,8 @@ impl Show for FootnoteEntry {
         let note = self.note();
         let number_gap = Em::new(0.05);
         let numbering = note.numbering(StyleChain::default());
-        let counter = Counter::of(FootnoteElem::func());
         let loc = note.0.location().unwrap();
-        let num = counter.at(vt, loc)?.display(vt, &numbering)?;
+        let num = note.number(vt, loc)?.display(vt, &numbering)?;
         let sup = SuperElem::new(num)
             .pack()
             .linked(Destination::Location(loc))
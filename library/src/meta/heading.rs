@@ -93,6 +93,14 @@ pub struct HeadingElem {
     #[default(true)]
     pub outlined: bool,
 
+    /// Whether the heading is kept on the same page as the content after it.
+    ///
+    /// A sticky heading moves to the next page if not enough lines of the
+    /// following paragraph (see its [`orphans`]($func/par.orphans)) or not
+    /// the whole following unbreakable block fit after it.
+    #[default(true)]
+    pub sticky: bool,
+
     /// The heading's title.
     #[required]
     pub body: Content,
@@ -134,6 +142,7 @@ impl Show for HeadingElem {
 impl Finalize for HeadingElem {
     fn finalize(&self, realized: Content, styles: StyleChain) -> Content {
         let level = self.level(styles).get();
+        let sticky = self.sticky(styles);
         let scale = match level {
             1 => 1.4,
             2 => 1.2,
@@ -149,7 +158,7 @@ impl Finalize for HeadingElem {
         styles.set(TextElem::set_weight(FontWeight::BOLD));
         styles.set(BlockElem::set_above(VElem::block_around(above.into())));
         styles.set(BlockElem::set_below(VElem::block_around(below.into())));
-        styles.set(BlockElem::set_sticky(true));
+        styles.set(BlockElem::set_sticky(sticky));
         realized.styled_with_map(styles)
     }
 }
@@ -3,6 +3,7 @@
 mod bibliography;
 mod context;
 mod counter;
+mod csl;
 mod document;
 mod figure;
 mod footnote;
@@ -17,6 +18,7 @@ mod state;
 pub use self::bibliography::*;
 pub use self::context::*;
 pub use self::counter::*;
+pub use self::csl::*;
 pub use self::document::*;
 pub use self::figure::*;
 pub use self::footnote::*;
@@ -36,9 +36,12 @@ use crate::text::Case;
 pub fn numbering(
     /// Defines how the numbering works.
     ///
-    /// **Counting symbols** are `1`, `a`, `A`, `i`, `I`, `い`, `イ`, `א`, `가`,
-    /// `ㄱ`, and `*`. They are replaced by the number in the sequence, in the
-    /// given case.
+    /// **Counting symbols** are `1`, `a`, `A`, `i`, `I`, `α`, `Α`, `①`, `一`,
+    /// `壹`, `い`, `イ`, `א`, `가`, `ㄱ`, and `*`. They are replaced by the
+    /// number in the sequence, in the given case.
+    ///
+    /// The `①` character counts with circled numbers up to fifty and falls
+    /// back to plain numbers beyond.
     ///
     /// The `*` character means that symbols should be used to count, in the
     /// order of `*`, `†`, `‡`, `§`, `¶`, and `‖`. If there are more than six
@@ -99,6 +102,30 @@ impl Numbering {
         })
     }
 
+    /// Express this numbering as a PDF page label for a page with the given
+    /// number.
+    ///
+    /// Returns `None` for numbering functions and for patterns whose counting
+    /// symbol has no equivalent page label style.
+    pub fn page_label(&self, number: usize) -> Option<PageLabel> {
+        let Self::Pattern(pattern) = self else { return None };
+        let (prefix, kind, case) = pattern.pieces.first()?;
+        let style = match (kind, case) {
+            (NumberingKind::Arabic, _) => PageLabelStyle::Arabic,
+            (NumberingKind::Roman, Case::Lower) => PageLabelStyle::LowerRoman,
+            (NumberingKind::Roman, Case::Upper) => PageLabelStyle::UpperRoman,
+            (NumberingKind::Letter, Case::Lower) => PageLabelStyle::LowerAlpha,
+            (NumberingKind::Letter, Case::Upper) => PageLabelStyle::UpperAlpha,
+            _ => return None,
+        };
+
+        Some(PageLabel {
+            style: Some(style),
+            prefix: (!prefix.is_empty()).then(|| prefix.clone()),
+            offset: NonZeroUsize::new(number),
+        })
+    }
+
     /// Trim the prefix suffix if this is a pattern.
     pub fn trimmed(mut self) -> Self {
         if let Self::Pattern(pattern) = &mut self {
@@ -233,7 +260,7 @@ cast! {
             pat.push_str(prefix);
             let mut c = kind.to_char();
             if *case == Case::Upper {
-                c = c.to_ascii_uppercase();
+                c = c.to_uppercase().next().unwrap_or(c);
             }
             pat.push(c);
         }
@@ -250,6 +277,8 @@ enum NumberingKind {
     Letter,
     Roman,
     Symbol,
+    Greek,
+    Circled,
     Hebrew,
     SimplifiedChinese,
     // TODO: Pick the numbering pattern based on languages choice.
@@ -273,6 +302,8 @@ impl NumberingKind {
             'a' => NumberingKind::Letter,
             'i' => NumberingKind::Roman,
             '*' => NumberingKind::Symbol,
+            'α' | 'Α' => NumberingKind::Greek,
+            '①' => NumberingKind::Circled,
             'א' => NumberingKind::Hebrew,
             '一' | '壹' => NumberingKind::SimplifiedChinese,
             'い' => NumberingKind::HiraganaIroha,
@@ -290,6 +321,8 @@ impl NumberingKind {
             Self::Letter => 'a',
             Self::Roman => 'i',
             Self::Symbol => '*',
+            Self::Greek => 'α',
+            Self::Circled => '①',
             Self::Hebrew => 'א',
             Self::SimplifiedChinese => '一',
             Self::TraditionalChinese => '一',
@@ -390,6 +423,32 @@ impl NumberingKind {
                 let amount = ((n - 1) / SYMBOLS.len()) + 1;
                 std::iter::repeat(symbol).take(amount).collect()
             }
+            Self::Greek => zeroless::<24>(
+                |x| {
+                    let c = [
+                        'α', 'β', 'γ', 'δ', 'ε', 'ζ', 'η', 'θ', 'ι', 'κ', 'λ', 'μ', 'ν',
+                        'ξ', 'ο', 'π', 'ρ', 'σ', 'τ', 'υ', 'φ', 'χ', 'ψ', 'ω',
+                    ][x];
+                    match case {
+                        Case::Lower => c,
+                        Case::Upper => c.to_uppercase().next().unwrap_or(c),
+                    }
+                },
+                n,
+            ),
+            Self::Circled => {
+                let c = match n {
+                    0 => Some('⓪'),
+                    1..=20 => char::from_u32(0x2460 + n as u32 - 1),
+                    21..=35 => char::from_u32(0x3251 + n as u32 - 21),
+                    36..=50 => char::from_u32(0x32B1 + n as u32 - 36),
+                    _ => None,
+                };
+                match c {
+                    Some(c) => c.into(),
+                    None => eco_format!("{n}"),
+                }
+            }
             Self::Hebrew => {
                 if n == 0 {
                     return '-'.into();
@@ -449,7 +508,7 @@ impl NumberingKind {
                     Case::Upper => ChineseCase::Upper,
                 };
 
-                match (n as u8).to_chinese(
+                match (n as u64).to_chinese(
                     match l {
                         Self::SimplifiedChinese => ChineseVariant::Simple,
                         Self::TraditionalChinese => ChineseVariant::Traditional,
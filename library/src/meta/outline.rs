@@ -5,9 +5,12 @@ use typst::util::option_eq;
 use super::{
     Counter, CounterKey, HeadingElem, LocalName, Numbering, NumberingPattern, Refable,
 };
-use crate::layout::{BoxElem, HElem, HideElem, ParbreakElem, RepeatElem, Spacing};
+use crate::layout::{
+    BlockElem, BoxElem, FirstLineIndent, HElem, HideElem, ParElem, ParbreakElem,
+    RepeatElem, Spacing, VElem,
+};
 use crate::prelude::*;
-use crate::text::{LinebreakElem, SpaceElem, TextElem};
+use crate::text::{SpaceElem, TextElem};
 
 /// A table of contents, figures, or other elements.
 ///
@@ -139,6 +142,9 @@ pub struct OutlineElem {
     ///   while `{n => [→ ] * n}` would indent with one arrow per nesting
     ///   level.
     ///
+    /// With any kind of indent, the continuation lines of entries that are too
+    /// long for one line are aligned after the entry's numbering.
+    ///
     /// *Migration hints:*  Specifying `{true}` (equivalent to `{auto}`) or
     /// `{false}` (equivalent to `{none}`) for this option is deprecated and
     /// will be removed in a future release.
@@ -206,6 +212,7 @@ impl Show for OutlineElem {
         let depth = self.depth(styles).unwrap_or(NonZeroUsize::new(usize::MAX).unwrap());
 
         let mut ancestors: Vec<&Content> = vec![];
+        let mut entries = vec![];
         let elems = vt.introspector.query(&self.target(styles).0);
 
         for elem in &elems {
@@ -233,16 +240,54 @@ impl Show for OutlineElem {
                 ancestors.pop();
             }
 
-            OutlineIndent::apply(&indent, vt, &ancestors, &mut seq, self.span())?;
+            let mut line = vec![];
+            OutlineIndent::apply(&indent, vt, &ancestors, &mut line, self.span())?;
+
+            // Align the continuation lines of long entries after the numbering.
+            let mut prefix = Content::sequence(line.clone());
+            let outlinable = elem.with::<dyn Outlinable>().unwrap();
+            if let Some(numbering) = outlinable.numbering() {
+                prefix += outlinable
+                    .counter()
+                    .at(vt, elem.location().unwrap())?
+                    .display(vt, &numbering)?
+                    + SpaceElem::new().pack();
+            }
+
+            // Add the overridable outline entry.
+            line.push(entry.pack());
+            let mut line = Content::sequence(line)
+                .styled(ParElem::set_first_line_indent(FirstLineIndent::default()));
+
+            if !prefix.is_empty() {
+                // The empty box keeps the trailing space from being trimmed.
+                let pod = Regions::one(Size::splat(Abs::inf()), Axes::splat(false));
+                let hang = (prefix + BoxElem::new().pack())
+                    .measure(vt, styles, pod)?
+                    .into_frame()
+                    .width();
+                line = line.styled(ParElem::set_hanging_indent(hang.into()));
+            }
 
-            // Add the overridable outline entry, followed by a line break.
-            seq.push(entry.pack());
-            seq.push(LinebreakElem::new().pack());
+            entries.push(line);
 
             ancestors.push(elem);
         }
 
-        seq.push(ParbreakElem::new().pack());
+        // Each entry is its own paragraph, spaced like the lines of a single
+        // one.
+        let spacing = VElem::block_spacing(ParElem::leading_in(styles).into());
+        let count = entries.len();
+        for (i, mut entry) in entries.into_iter().enumerate() {
+            if i > 0 {
+                entry = entry.styled(BlockElem::set_above(spacing.clone()));
+            }
+            if i + 1 < count {
+                entry = entry.styled(BlockElem::set_below(spacing.clone()));
+            }
+            seq.push(entry);
+            seq.push(ParbreakElem::new().pack());
+        }
 
         Ok(Content::sequence(seq))
     }
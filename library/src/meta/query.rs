@@ -62,6 +62,48 @@ use crate::prelude::*;
 /// #lorem(15)
 /// ```
 ///
+/// ## Elements on the current page { #current-page }
+/// Every location knows its page, so the results of a query can also be
+/// restricted to the page of the current location. The header below shows the
+/// title of the chapter that starts on the current page or, if there is none,
+/// of the last chapter before it. Together with the
+/// [final value]($func/counter.final) of the page counter, the footer shows
+/// the page number in the form "Page 1 of 2".
+///
+/// ```example
+/// >>> #set page(
+/// >>>   width: 240pt,
+/// >>>   height: 180pt,
+/// >>>   margin: (y: 35pt, rest: 15pt),
+/// >>> )
+/// #set page(
+///   header: locate(loc => {
+///     let chapters = heading.where(level: 1)
+///     let here = query(chapters, loc)
+///       .filter(it => it.location().page() == loc.page())
+///     let before = query(chapters.before(loc), loc)
+///     if here != () {
+///       emph(here.first().body)
+///     } else if before != () {
+///       emph(before.last().body)
+///     }
+///   }),
+///   footer: locate(loc => {
+///     let page = counter(page)
+///     align(right)[
+///       Page #page.at(loc).first()
+///       of #page.final(loc).first()
+///     ]
+///   }),
+/// )
+///
+/// = Introduction
+/// #lorem(40)
+///
+/// = Background
+/// #lorem(20)
+/// ```
+///
 /// ## A word of caution { #caution }
 /// To resolve all your queries, Typst evaluates and layouts parts of the
 /// document multiple times. However, there is no guarantee that your queries
@@ -74,7 +116,8 @@ use crate::prelude::*;
 /// Typst sees that the query's result has changed and processes it again. This
 /// time, `count` is `2` and two `Fake` headings are generated. This goes on and
 /// on. As we can see, the output has five headings. This is because Typst
-/// simply gives up after five attempts.
+/// simply gives up after five attempts and warns that the layout did not
+/// converge.
 ///
 /// In general, you should try not to write queries that affect themselves.
 /// The same words of caution also apply to other introspection features like
@@ -1,4 +1,11 @@
-use super::{BibliographyElem, CiteElem, Counter, Figurable, Numbering};
+use std::str::FromStr;
+
+use typst::diag::{closest_match, SourceError, Tracepoint};
+
+use super::{
+    BibliographyElem, CiteElem, Counter, CounterKey, Figurable, Numbering,
+    NumberingPattern,
+};
 use crate::meta::FootnoteElem;
 use crate::prelude::*;
 use crate::text::TextElem;
@@ -117,6 +124,26 @@ pub struct RefElem {
     /// ```
     pub supplement: Smart<Option<Supplement>>,
 
+    /// The kind of reference to produce.
+    ///
+    /// - `{"normal"}`: A reference to the element itself, such as "Section 1".
+    ///   This is the default.
+    /// - `{"page"}`: A reference to the page the element is on, such as
+    ///   "page 2". Works with any labelled element and uses the page
+    ///   numbering of the target's page. A custom supplement replaces the
+    ///   word "page".
+    ///
+    /// ```example
+    /// #set page(numbering: "1")
+    /// #set heading(numbering: "1.")
+    ///
+    /// = Introduction <intro>
+    /// For more details, see
+    /// @intro on #ref(<intro>, form: "page").
+    /// ```
+    #[default(RefForm::Normal)]
+    pub form: RefForm,
+
     /// A synthesized citation.
     #[synthesized]
     pub citation: Option<CiteElem>,
@@ -147,7 +174,7 @@ impl Synthesize for RefElem {
 impl Show for RefElem {
     #[tracing::instrument(name = "RefElem::show", skip_all)]
     fn show(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
-        Ok(vt.delayed(|vt| {
+        let realized = vt.delayed(|vt| {
             let target = self.target();
             let elem = vt.introspector.query_label(&self.target());
             let span = self.span();
@@ -160,7 +187,29 @@ impl Show for RefElem {
                 return Ok(self.to_citation(vt, styles)?.pack().spanned(span));
             }
 
-            let elem = elem.at(span)?;
+            let elem = elem.map_err(|message| {
+                // Point to all places the label is attached to.
+                let mut error = SourceError::new(span, message);
+                let found = vt.introspector.query(&Selector::Label(target.clone()));
+                for elem in &found {
+                    let point = Tracepoint::Label(target.0.clone());
+                    error.trace.push(Spanned::new(point, elem.span()));
+                }
+
+                // Suggest a similar label if there is none at all.
+                if found.is_empty() {
+                    if let Some(similar) = similar_label(vt, &target) {
+                        let hint = eco_format!("did you mean `@{similar}`?");
+                        error = error.with_hints([hint]);
+                    }
+                }
+
+                Box::new(vec![error])
+            })?;
+
+            if self.form(styles) == RefForm::Page {
+                return self.to_page_ref(vt, styles, &elem);
+            }
 
             if elem.func() == FootnoteElem::func() {
                 return Ok(FootnoteElem::with_label(target).pack().spanned(span));
@@ -213,11 +262,53 @@ impl Show for RefElem {
             }
 
             Ok(content.linked(Destination::Location(elem.location().unwrap())))
-        }))
+        });
+
+        // References are displayed as they are, even in case-transformed text.
+        Ok(realized.styled(TextElem::set_case(None)))
     }
 }
 
 impl RefElem {
+    /// Turn the reference into a reference to the page of the referenced
+    /// element.
+    fn to_page_ref(
+        &self,
+        vt: &mut Vt,
+        styles: StyleChain,
+        elem: &Content,
+    ) -> SourceResult<Content> {
+        let location = elem.location().unwrap();
+        let numbering = vt
+            .introspector
+            .page_numbering(location)
+            .cast::<Option<Numbering>>()
+            .unwrap()
+            .unwrap_or_else(|| {
+                Numbering::Pattern(NumberingPattern::from_str("1").unwrap())
+            });
+
+        let numbers = Counter::new(CounterKey::Page)
+            .at(vt, location)?
+            .display(vt, &numbering.trimmed())?;
+
+        let supplement = match self.supplement(styles) {
+            Smart::Auto => TextElem::packed(page_name(
+                TextElem::lang_in(styles),
+                TextElem::region_in(styles),
+            )),
+            Smart::Custom(None) => Content::empty(),
+            Smart::Custom(Some(supplement)) => supplement.resolve(vt, [elem.clone()])?,
+        };
+
+        let mut content = numbers;
+        if !supplement.is_empty() {
+            content = supplement + TextElem::packed("\u{a0}") + content;
+        }
+
+        Ok(content.linked(Destination::Location(location)))
+    }
+
     /// Turn the reference into a citation.
     pub fn to_citation(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<CiteElem> {
         let mut elem = CiteElem::new(vec![self.target().0]);
@@ -232,6 +323,47 @@ impl RefElem {
     }
 }
 
+/// The kind of a reference.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum RefForm {
+    /// A reference to the element itself.
+    Normal,
+    /// A reference to the page the element is on.
+    Page,
+}
+
+/// The name of a page in the given language, used in page references.
+fn page_name(lang: Lang, _: Option<Region>) -> &'static str {
+    match lang {
+        Lang::BOKMÅL | Lang::DANISH | Lang::NYNORSK => "side",
+        Lang::CHINESE => "页",
+        Lang::CZECH => "strana",
+        Lang::DUTCH | Lang::ITALIAN => "pagina",
+        Lang::FRENCH => "page",
+        Lang::GERMAN => "Seite",
+        Lang::POLISH => "strona",
+        Lang::PORTUGUESE | Lang::SPANISH => "página",
+        Lang::RUSSIAN => "страница",
+        Lang::SWEDISH => "sida",
+        Lang::TURKISH => "sayfa",
+        Lang::UKRAINIAN => "сторінка",
+        Lang::ENGLISH | _ => "page",
+    }
+}
+
+/// Find the label in the document or its bibliography that is most similar
+/// to a label that could not be found.
+fn similar_label(vt: &Vt, target: &Label) -> Option<EcoString> {
+    let mut candidates: Vec<EcoString> =
+        vt.introspector.labels().into_iter().map(|label| label.0).collect();
+    candidates.extend(
+        BibliographyElem::keys(vt.introspector)
+            .into_iter()
+            .map(|(key, _)| key),
+    );
+    closest_match(&target.0, candidates.iter().map(EcoString::as_str)).map(Into::into)
+}
+
 /// Additional content for a reference.
 pub enum Supplement {
     Content(Content),
@@ -2,7 +2,8 @@ use std::fmt::{self, Debug, Formatter, Write};
 
 use ecow::{eco_vec, EcoVec};
 use typst::eval::Tracer;
-use typst::model::DelayedErrors;
+use typst::model::{DelayedErrors, Depth};
+use typst::Limits;
 
 use crate::prelude::*;
 
@@ -311,6 +312,7 @@ impl State {
             vt.locator.track(),
             TrackedMut::reborrow_mut(&mut vt.delayed),
             TrackedMut::reborrow_mut(&mut vt.tracer),
+            vt.limits,
         )
     }
 
@@ -323,14 +325,18 @@ impl State {
         locator: Tracked<Locator>,
         delayed: TrackedMut<DelayedErrors>,
         tracer: TrackedMut<Tracer>,
+        limits: Limits,
     ) -> SourceResult<EcoVec<Value>> {
         let mut locator = Locator::chained(locator);
+        let depth = Depth::default();
         let mut vt = Vt {
             world,
             introspector,
             locator: &mut locator,
             delayed,
             tracer,
+            limits,
+            depth: depth.track(),
         };
         let mut state = self.init.clone();
         let mut stops = eco_vec![state.clone()];
@@ -10,7 +10,10 @@ pub use comemo::{Track, Tracked, TrackedMut};
 #[doc(no_inline)]
 pub use ecow::{eco_format, EcoString};
 #[doc(no_inline)]
-pub use typst::diag::{bail, error, At, Hint, SourceResult, StrResult};
+pub use typst::diag::{
+    bail, error, warning, At, Hint, HintedStrResult, HintedString, SourceResult,
+    StrResult,
+};
 #[doc(no_inline)]
 pub use typst::doc::*;
 #[doc(no_inline)]
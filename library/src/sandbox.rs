@@ -0,0 +1,198 @@
+//! An in-memory environment for compiling documents.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use comemo::Prehashed;
+use ecow::EcoString;
+use typst::diag::{FileError, FileResult, PackageError};
+use typst::eval::{Datetime, Library};
+use typst::file::{DirEntry, FileId, LazyFileStore};
+use typst::font::{Font, FontBook};
+use typst::package::PackageSpec;
+use typst::syntax::Source;
+use typst::util::Bytes;
+use typst::World;
+
+/// A world that holds all of its files and fonts in memory.
+///
+/// This makes it easy to embed the compiler without implementing [`World`]:
+/// Create a sandbox from the main source text, add the files the document
+/// needs with [`with_file`](Self::with_file) and fonts with
+/// [`with_fonts`](Self::with_fonts) and pass it to [`typst::compile`]. The
+/// sandbox never touches the real file system or clock, so it also works in
+/// WebAssembly and doctests.
+pub struct SandboxWorld {
+    library: Prehashed<Library>,
+    book: Prehashed<FontBook>,
+    fonts: Vec<Font>,
+    main: Source,
+    files: HashMap<FileId, Bytes>,
+    store: LazyFileStore,
+    today: Option<Datetime>,
+    now: Option<Datetime>,
+    packages: Vec<(PackageSpec, Option<EcoString>)>,
+}
+
+impl SandboxWorld {
+    /// Create a new sandbox with the given main source text.
+    ///
+    /// The main file is located at `/main.typ`. Initially, the sandbox has no
+    /// fonts, no other files, and no current date or time.
+    pub fn new(main: &str) -> Self {
+        let id = FileId::new(None, Path::new("/main.typ"));
+        Self {
+            library: Prehashed::new(crate::build()),
+            book: Prehashed::new(FontBook::new()),
+            fonts: vec![],
+            main: Source::new(id, main.into()),
+            files: HashMap::new(),
+            store: LazyFileStore::new(),
+            today: None,
+            now: None,
+            packages: vec![],
+        }
+    }
+
+    /// Add a file at a path relative to the sandbox's root.
+    ///
+    /// The file can then be imported, included, or read from the main file
+    /// just like a file on disk.
+    pub fn with_file(mut self, path: impl AsRef<Path>, data: impl Into<Bytes>) -> Self {
+        let id = FileId::new(None, &Path::new("/").join(path));
+        self.files.insert(id, data.into());
+        self.store.invalidate(id);
+        self
+    }
+
+    /// Make fonts available to the document.
+    pub fn with_fonts(mut self, fonts: impl IntoIterator<Item = Font>) -> Self {
+        self.fonts.extend(fonts);
+        self.book = Prehashed::new(FontBook::from_fonts(&self.fonts));
+        self
+    }
+
+    /// Set the date returned by `{datetime.today()}`.
+    pub fn with_today(mut self, today: Datetime) -> Self {
+        self.today = Some(today);
+        self
+    }
+
+    /// Set the date and time returned by `{datetime.now()}`, which is also
+    /// used as the document's creation date.
+    pub fn with_now(mut self, now: Datetime) -> Self {
+        self.now = Some(now);
+        self
+    }
+
+    /// Set the packages that are offered when completing package imports.
+    ///
+    /// The sandbox cannot load packages, so importing them still fails.
+    pub fn with_packages(
+        mut self,
+        packages: impl IntoIterator<Item = (PackageSpec, Option<EcoString>)>,
+    ) -> Self {
+        self.packages.extend(packages);
+        self
+    }
+
+    /// Find the data of a file other than the main file.
+    fn data(&self, id: FileId) -> FileResult<Bytes> {
+        if let Some(spec) = id.package() {
+            return Err(FileError::Package(PackageError::NotFound(spec.clone())));
+        }
+
+        self.files
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| FileError::NotFound(id.path().into()))
+    }
+}
+
+impl World for SandboxWorld {
+    fn library(&self) -> &Prehashed<Library> {
+        &self.library
+    }
+
+    fn book(&self) -> &Prehashed<FontBook> {
+        &self.book
+    }
+
+    fn main(&self) -> Source {
+        self.main.clone()
+    }
+
+    fn source(&self, id: FileId) -> FileResult<Source> {
+        if id == self.main.id() {
+            return Ok(self.main.clone());
+        }
+
+        self.store.source(id, |id| self.data(id))
+    }
+
+    fn file(&self, id: FileId) -> FileResult<Bytes> {
+        if id == self.main.id() {
+            return Ok(self.main.text().as_bytes().into());
+        }
+
+        self.data(id)
+    }
+
+    fn font(&self, index: usize) -> Option<Font> {
+        self.fonts.get(index).cloned()
+    }
+
+    fn today(&self, _: Option<i64>) -> Option<Datetime> {
+        self.today
+    }
+
+    fn now(&self, _: Option<i64>) -> Option<Datetime> {
+        self.now
+    }
+
+    fn entries(&self, dir: FileId) -> Option<Vec<DirEntry>> {
+        if dir.package().is_some() {
+            return None;
+        }
+
+        let dir = Path::new("/").join(dir.path());
+        let mut entries = vec![];
+        for id in self.files.keys().copied().chain([self.main.id()]) {
+            let Ok(rest) = id.path().strip_prefix(&dir) else { continue };
+            let mut components = rest.components();
+            let Some(first) = components.next() else { continue };
+            let entry = DirEntry {
+                name: first.as_os_str().to_string_lossy().into(),
+                is_dir: components.next().is_some(),
+            };
+
+            if !entries.contains(&entry) {
+                entries.push(entry);
+            }
+        }
+
+        Some(entries)
+    }
+
+    fn packages(&self) -> &[(PackageSpec, Option<EcoString>)] {
+        &self.packages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sandbox_keeps_unchanged_sources() {
+        let id = FileId::new(None, Path::new("/a.typ"));
+        let world = SandboxWorld::new("").with_file("a.typ", b"#let x = 1".as_slice());
+        let first = world.source(id).unwrap();
+
+        let world = world.with_file("a.typ", b"#let x = 1".as_slice());
+        assert!(std::ptr::eq(first.root(), world.source(id).unwrap().root()));
+
+        let world = world.with_file("a.typ", b"#let x = 2".as_slice());
+        assert_eq!(world.source(id).unwrap().text(), "#let x = 2");
+    }
+}
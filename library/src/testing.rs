@@ -0,0 +1,31 @@
+//! Helpers for the unit tests of this crate.
+
+// Not every test uses every helper.
+#![allow(dead_code)]
+
+use typst::doc::{Frame, FrameItem};
+use typst::font::Font;
+use typst::util::Bytes;
+
+/// The fonts that tests which inspect the laid out text use.
+pub fn fonts() -> Vec<Font> {
+    let data = include_bytes!("../../assets/fonts/LinLibertine_R.ttf");
+    Font::iter(Bytes::from_static(data)).collect()
+}
+
+/// The text in a frame, in the order of the frame's items.
+pub fn text(frame: &Frame) -> String {
+    fn collect(frame: &Frame, text: &mut String) {
+        for (_, item) in frame.items() {
+            match item {
+                FrameItem::Group(group) => collect(&group.frame, text),
+                FrameItem::Text(item) => text.push_str(&item.text),
+                _ => {}
+            }
+        }
+    }
+
+    let mut text = String::new();
+    collect(frame, &mut text);
+    text
+}
@@ -1,7 +1,7 @@
 use kurbo::{BezPath, Line, ParamCurve};
 use ttf_parser::{GlyphId, OutlineBuilder};
 
-use super::TextElem;
+use super::{TextEdge, TextElem};
 use crate::prelude::*;
 
 /// Underlines text.
@@ -64,6 +64,16 @@ pub struct UnderlineElem {
     #[default(true)]
     pub evade: bool,
 
+    /// Whether the line is placed behind the content it underlines.
+    ///
+    /// ```example
+    /// #set underline(stroke: (thickness: 1em, paint: maroon, cap: "round"))
+    /// #underline(background: true)[This is stylized.] \
+    /// #underline(background: false)[This is partially hidden.]
+    /// ```
+    #[default(false)]
+    pub background: bool,
+
     /// The content to underline.
     #[required]
     pub body: Content,
@@ -78,6 +88,7 @@ impl Show for UnderlineElem {
             offset: self.offset(styles),
             extent: self.extent(styles),
             evade: self.evade(styles),
+            background: self.background(styles),
         })))
     }
 }
@@ -148,6 +159,16 @@ pub struct OverlineElem {
     #[default(true)]
     pub evade: bool,
 
+    /// Whether the line is placed behind the content it overlines.
+    ///
+    /// ```example
+    /// #set overline(stroke: (thickness: 1em, paint: maroon, cap: "round"))
+    /// #overline(background: true)[This is stylized.] \
+    /// #overline(background: false)[This is partially hidden.]
+    /// ```
+    #[default(false)]
+    pub background: bool,
+
     /// The content to add a line over.
     #[required]
     pub body: Content,
@@ -162,6 +183,7 @@ impl Show for OverlineElem {
             offset: self.offset(styles),
             extent: self.extent(styles),
             evade: self.evade(styles),
+            background: self.background(styles),
         })))
     }
 }
@@ -217,6 +239,16 @@ pub struct StrikeElem {
     #[resolve]
     pub extent: Length,
 
+    /// Whether the line is placed behind the content it strikes through.
+    ///
+    /// ```example
+    /// #set strike(stroke: red)
+    /// #strike(background: true)[This is behind.] \
+    /// #strike(background: false)[This is in front.]
+    /// ```
+    #[default(false)]
+    pub background: bool,
+
     /// The content to strike through.
     #[required]
     pub body: Content,
@@ -231,11 +263,90 @@ impl Show for StrikeElem {
             offset: self.offset(styles),
             extent: self.extent(styles),
             evade: false,
+            background: self.background(styles),
+        })))
+    }
+}
+
+/// Highlights text with a background color.
+///
+/// ## Example { #example }
+/// ```example
+/// This is #highlight[important].
+/// ```
+///
+/// Display: Highlight
+/// Category: text
+#[element(Show)]
+pub struct HighlightElem {
+    /// The color to highlight the text with.
+    ///
+    /// ```example
+    /// This is #highlight(fill: blue)[with blue].
+    /// ```
+    #[default(Color::Rgba(RgbaColor::new(0xFF, 0xFD, 0x11, 0xFF)).into())]
+    pub fill: Paint,
+
+    /// The top end of the background rectangle. Takes the same values as the
+    /// [text's top edge]($func/text.top-edge).
+    ///
+    /// ```example
+    /// #set highlight(top-edge: "ascender")
+    /// #highlight[a] #highlight[aib]
+    ///
+    /// #set highlight(top-edge: "x-height")
+    /// #highlight[a] #highlight[aib]
+    /// ```
+    #[default(TextEdge::Metric(VerticalFontMetric::Ascender))]
+    pub top_edge: TextEdge,
+
+    /// The bottom end of the background rectangle. Takes the same values as
+    /// the [text's bottom edge]($func/text.bottom-edge).
+    ///
+    /// ```example
+    /// #set highlight(bottom-edge: "descender")
+    /// #highlight[a] #highlight[ap]
+    ///
+    /// #set highlight(bottom-edge: "baseline")
+    /// #highlight[a] #highlight[ap]
+    /// ```
+    #[default(TextEdge::Metric(VerticalFontMetric::Descender))]
+    pub bottom_edge: TextEdge,
+
+    /// The amount by which to extend the background to the sides beyond
+    /// (or within if negative) the content.
+    ///
+    /// ```example
+    /// A long #highlight(extent: 4pt)[background].
+    /// ```
+    #[resolve]
+    pub extent: Length,
+
+    /// The content that should be highlighted.
+    #[required]
+    pub body: Content,
+}
+
+impl Show for HighlightElem {
+    #[tracing::instrument(name = "HighlightElem::show", skip_all)]
+    fn show(&self, _: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
+        Ok(self.body().styled(TextElem::set_deco(Decoration {
+            line: DecoLine::Highlight {
+                fill: self.fill(styles),
+                top_edge: self.top_edge(styles),
+                bottom_edge: self.bottom_edge(styles),
+            },
+            stroke: PartialStroke::default(),
+            offset: Smart::Auto,
+            extent: self.extent(styles),
+            evade: false,
+            background: true,
         })))
     }
 }
 
-/// Defines a line that is positioned over, under or on top of text.
+/// Defines a line that is positioned over, under or on top of text or a
+/// background that highlights it.
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct Decoration {
     pub line: DecoLine,
@@ -243,6 +354,7 @@ pub struct Decoration {
     pub offset: Smart<Abs>,
     pub extent: Abs,
     pub evade: bool,
+    pub background: bool,
 }
 
 impl Fold for Decoration {
@@ -259,11 +371,12 @@ cast! {
 }
 
 /// A kind of decorative line.
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub enum DecoLine {
     Underline,
     Strikethrough,
     Overline,
+    Highlight { fill: Paint, top_edge: TextEdge, bottom_edge: TextEdge },
 }
 
 /// Add line decorations to a single run of shaped text.
@@ -276,10 +389,24 @@ pub(super) fn decorate(
     width: Abs,
 ) {
     let font_metrics = text.font.metrics();
-    let metrics = match deco.line {
+    let metrics = match &deco.line {
         DecoLine::Strikethrough => font_metrics.strikethrough,
         DecoLine::Overline => font_metrics.overline,
         DecoLine::Underline => font_metrics.underline,
+        DecoLine::Highlight { fill, top_edge, bottom_edge } => {
+            let edge = |edge: TextEdge| match edge {
+                TextEdge::Metric(metric) => font_metrics.vertical(metric).at(text.size),
+                TextEdge::Length(length) => length.abs + length.em.at(text.size),
+            };
+
+            let top = edge(*top_edge);
+            let bottom = edge(*bottom_edge);
+            let size = Size::new(width + 2.0 * deco.extent, top - bottom);
+            let origin = Point::new(pos.x - deco.extent, pos.y - shift - top);
+            let shape = Geometry::Rect(size).filled(fill.clone());
+            frame.push(origin, FrameItem::Shape(shape, Span::detached()));
+            return;
+        }
     };
 
     let offset = deco.offset.unwrap_or(-metrics.position.at(text.size)) - shift;
@@ -0,0 +1,209 @@
+use std::collections::HashMap;
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use typst::util::hash128;
+
+use crate::prelude::*;
+
+/// How many characters must at least precede a hyphen.
+const LEFT_MIN: usize = 2;
+
+/// How many characters must at least follow a hyphen.
+const RIGHT_MIN: usize = 3;
+
+/// Custom hyphenation patterns in TeX format.
+///
+/// Used for languages for which no built-in patterns exist.
+#[derive(Clone)]
+pub struct HyphenationPatterns(Arc<Repr>);
+
+/// The internal representation of hyphenation patterns.
+struct Repr {
+    /// The source text the patterns were parsed from.
+    source: Str,
+    /// A hash of the source text.
+    hash: u128,
+    /// Maps letter sequences to the hyphenation levels before, between, and
+    /// after their letters.
+    patterns: HashMap<String, Vec<u8>>,
+    /// The length of the longest pattern in characters.
+    longest: usize,
+    /// Maps words to their explicitly given hyphenation points, as character
+    /// offsets.
+    exceptions: HashMap<String, Vec<usize>>,
+}
+
+impl HyphenationPatterns {
+    /// Parse hyphenation patterns in TeX format.
+    ///
+    /// The patterns must be placed in a `\patterns{...}` group. Exceptions can
+    /// optionally be given in a `\hyphenation{...}` group, with the
+    /// hyphenation points of each word marked by hyphens. Comments start with
+    /// `%`.
+    pub fn parse(source: Str) -> StrResult<Self> {
+        parse(source)
+    }
+
+    /// Split a word into syllables.
+    pub fn hyphenate<'a>(&self, word: &'a str) -> Vec<&'a str> {
+        let lower: Vec<char> = word.to_lowercase().chars().collect();
+        if lower.len() != word.chars().count() {
+            return vec![word];
+        }
+
+        let key: String = lower.iter().collect();
+        let points = match self.0.exceptions.get(&key) {
+            Some(points) => points.clone(),
+            None => self.points(&lower),
+        };
+
+        let mut syllables = vec![];
+        let mut start = 0;
+        let mut points = points.into_iter().peekable();
+        for (i, (offset, _)) in word.char_indices().enumerate() {
+            if points.next_if_eq(&i).is_some() && offset > start {
+                syllables.push(&word[start..offset]);
+                start = offset;
+            }
+        }
+
+        syllables.push(&word[start..]);
+        syllables
+    }
+
+    /// Find the hyphenation points of a lowercase word with Liang's
+    /// algorithm.
+    fn points(&self, word: &[char]) -> Vec<usize> {
+        let n = word.len();
+        if n < LEFT_MIN + RIGHT_MIN {
+            return vec![];
+        }
+
+        let dotted: Vec<char> = std::iter::once('.')
+            .chain(word.iter().copied())
+            .chain(['.'])
+            .collect();
+
+        // The level at index `i` applies to the point before `dotted[i]`.
+        let mut levels = vec![0; dotted.len() + 1];
+        let mut key = String::new();
+        for i in 0..dotted.len() {
+            key.clear();
+            for &c in &dotted[i..dotted.len().min(i + self.0.longest)] {
+                key.push(c);
+                if let Some(values) = self.0.patterns.get(&key) {
+                    for (k, &value) in values.iter().enumerate() {
+                        levels[i + k] = levels[i + k].max(value);
+                    }
+                }
+            }
+        }
+
+        // The point before `word[k]` is the one before `dotted[k + 1]`.
+        (LEFT_MIN..=n - RIGHT_MIN)
+            .filter(|&k| levels[k + 1] % 2 == 1)
+            .collect()
+    }
+}
+
+/// Parse hyphenation patterns only once, as pattern files tend to be large.
+#[comemo::memoize]
+fn parse(source: Str) -> StrResult<HyphenationPatterns> {
+    let text: String = source
+        .lines()
+        .map(|line| line.split('%').next().unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let Some(group) = find_group(&text, "patterns") else {
+        bail!("hyphenation patterns must contain a `\\patterns` group");
+    };
+
+    let mut patterns = HashMap::new();
+    let mut longest = 0;
+    for pattern in group.split_whitespace() {
+        let mut letters = String::new();
+        let mut levels = vec![0];
+        for c in pattern.chars() {
+            if let Some(digit) = c.to_digit(10) {
+                *levels.last_mut().unwrap() = digit as u8;
+            } else if c.is_alphabetic() || c == '.' || c == '\'' {
+                letters.push(c);
+                levels.push(0);
+            } else {
+                bail!("invalid hyphenation pattern `{pattern}`");
+            }
+        }
+
+        if letters.is_empty() {
+            bail!("invalid hyphenation pattern `{pattern}`");
+        }
+
+        longest = longest.max(letters.chars().count());
+        patterns.insert(letters, levels);
+    }
+
+    let mut exceptions = HashMap::new();
+    for word in find_group(&text, "hyphenation")
+        .unwrap_or_default()
+        .split_whitespace()
+    {
+        let mut letters = String::new();
+        let mut points = vec![];
+        for part in word.split('-') {
+            if !letters.is_empty() {
+                points.push(letters.chars().count());
+            }
+            letters.push_str(&part.to_lowercase());
+        }
+
+        if letters.is_empty() || letters.chars().any(|c| !c.is_alphabetic()) {
+            bail!("invalid hyphenation exception `{word}`");
+        }
+
+        exceptions.insert(letters, points);
+    }
+
+    Ok(HyphenationPatterns(Arc::new(Repr {
+        hash: hash128(&source),
+        source,
+        patterns,
+        longest,
+        exceptions,
+    })))
+}
+
+/// Find the contents of the group started by the given TeX command.
+fn find_group<'a>(text: &'a str, command: &str) -> Option<&'a str> {
+    let start = text.find(&format!("\\{command}{{"))? + command.len() + 2;
+    let end = text[start..].find('}').map_or(text.len(), |end| start + end);
+    Some(&text[start..end])
+}
+
+impl Debug for HyphenationPatterns {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.pad("HyphenationPatterns(..)")
+    }
+}
+
+impl Eq for HyphenationPatterns {}
+
+impl PartialEq for HyphenationPatterns {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.hash == other.0.hash
+    }
+}
+
+impl Hash for HyphenationPatterns {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u128(self.0.hash);
+    }
+}
+
+cast! {
+    HyphenationPatterns,
+    self => self.0.source.clone().into_value(),
+    v: Str => Self::parse(v)?,
+}
@@ -1,3 +1,7 @@
+use std::ops::Range;
+
+use unicode_segmentation::UnicodeSegmentation;
+
 use super::TextElem;
 use crate::prelude::*;
 
@@ -189,11 +193,16 @@ impl Fold for Toggle {
 
 /// Converts text or content to lowercase.
 ///
+/// Content is transformed according to the casing rules of its
+/// [language]($func/text.lang), so that, for instance, a Turkish `I` becomes a
+/// dotless `ı`. Raw text, equations, and references are left as they are.
+///
 /// ## Example { #example }
 /// ```example
 /// #lower("ABC") \
 /// #lower[*My Text*] \
-/// #lower[already low]
+/// #lower[already low] \
+/// #lower("KIŞ", lang: "tr")
 /// ```
 ///
 /// Display: Lowercase
@@ -202,17 +211,28 @@ impl Fold for Toggle {
 pub fn lower(
     /// The text to convert to lowercase.
     text: Caseable,
+    /// The language whose casing rules are applied to a string.
+    #[named]
+    lang: Option<Lang>,
 ) -> Caseable {
-    case(text, Case::Lower)
+    case(text, Case::Lower, lang, Smart::Auto)
 }
 
 /// Converts text or content to uppercase.
 ///
+/// Like [`lower`]($func/lower), this respects the casing rules of the text's
+/// language and leaves raw text, equations, and references as they are. It
+/// can also be used as a show rule.
+///
 /// ## Example { #example }
 /// ```example
 /// #upper("abc") \
 /// #upper[*my text*] \
-/// #upper[ALREADY HIGH]
+/// #upper[ALREADY HIGH] \
+/// #upper("istanbul", lang: "tr")
+///
+/// #show heading: upper
+/// = Quiet `code`
 /// ```
 ///
 /// Display: Uppercase
@@ -221,16 +241,79 @@ pub fn lower(
 pub fn upper(
     /// The text to convert to uppercase.
     text: Caseable,
+    /// The language whose casing rules are applied to a string.
+    #[named]
+    lang: Option<Lang>,
+) -> Caseable {
+    case(text, Case::Upper, lang, Smart::Auto)
+}
+
+/// Converts text or content to title case.
+///
+/// The first letter of each word is capitalized, except for minor words like
+/// articles and conjunctions, which are lowercased unless they start the
+/// title. Letters after the first one are left as they are, so acronyms stay
+/// in capitals. This is independent of [`smallcaps`]($func/smallcaps) and can
+/// be combined with it.
+///
+/// ## Example { #example }
+/// ```example
+/// #title-case("the lord of the rings") \
+/// #title-case[a tale of TWO cities] \
+/// #title-case("le rouge et le noir", lang: "fr")
+///
+/// #show heading: title-case
+/// = the history of the NATO alliance
+/// ```
+///
+/// Display: Title Case
+/// Category: text
+#[func]
+pub fn title_case(
+    /// The text to convert to title case.
+    text: Caseable,
+    /// The language whose casing rules and minor words are applied to a
+    /// string.
+    #[named]
+    lang: Option<Lang>,
+    /// The words that are kept in lowercase.
+    ///
+    /// - When set to `{auto}`, the minor words of the text's language are
+    ///   used. Currently, there are minor words for English, Dutch, French,
+    ///   German, Italian, Portuguese, and Spanish.
+    /// - An array of words is used regardless of the language.
+    /// - A dictionary maps language codes to arrays of words. Languages that
+    ///   are not specified fall back to their defaults.
+    ///
+    /// ```example
+    /// #title-case(minor-words: ("of", "vs"))[batman vs superman of gotham]
+    /// ```
+    #[named]
+    #[default]
+    minor_words: Smart<MinorWords>,
 ) -> Caseable {
-    case(text, Case::Upper)
+    case(text, Case::Title, lang, minor_words)
 }
 
 /// Change the case of text.
-fn case(text: Caseable, case: Case) -> Caseable {
+fn case(
+    text: Caseable,
+    case: Case,
+    lang: Option<Lang>,
+    minor_words: Smart<MinorWords>,
+) -> Caseable {
     match text {
-        Caseable::Str(v) => Caseable::Str(case.apply(&v).into()),
+        Caseable::Str(v) => {
+            let lang = lang.unwrap_or(Lang::ENGLISH);
+            Caseable::Str(case.apply(&v, lang, &minor_words).into())
+        }
         Caseable::Content(v) => {
-            Caseable::Content(v.styled(TextElem::set_case(Some(case))))
+            let mut styles = Styles::new();
+            styles.set(TextElem::set_case(Some(case)));
+            if case == Case::Title {
+                styles.set(TextElem::set_minor_words(minor_words));
+            }
+            Caseable::Content(v.styled_with_map(styles))
         }
     }
 }
@@ -258,25 +341,182 @@ pub enum Case {
     Lower,
     /// Everything is uppercased.
     Upper,
+    /// The first letter of each word except for minor words is uppercased.
+    Title,
 }
 
 impl Case {
     /// Apply the case to a string.
-    pub fn apply(self, text: &str) -> String {
-        match self {
-            Self::Lower => text.to_lowercase(),
-            Self::Upper => text.to_uppercase(),
+    pub fn apply(
+        self,
+        text: &str,
+        lang: Lang,
+        minor_words: &Smart<MinorWords>,
+    ) -> String {
+        let mut output = String::with_capacity(text.len());
+        self.transform(text, lang, minor_words, true, |_, cased| output.push_str(cased));
+        output
+    }
+
+    /// Apply the case to a string one grapheme cluster at a time such that
+    /// the transformed text can be mapped back to the original one.
+    ///
+    /// For each cluster, `f` is called with its byte range in `text` and its
+    /// transformed form. For title case, `first` determines whether the text
+    /// is at the start of a title.
+    pub fn transform(
+        self,
+        text: &str,
+        lang: Lang,
+        minor_words: &Smart<MinorWords>,
+        mut first: bool,
+        mut f: impl FnMut(Range<usize>, &str),
+    ) {
+        let mut buf = String::new();
+        for (start, word) in text.split_word_bound_indices() {
+            let is_word = word.chars().any(char::is_alphabetic);
+            let case = match self {
+                Self::Title if !is_word => None,
+                Self::Title => {
+                    let first = std::mem::replace(&mut first, false);
+                    if !first && !is_acronym(word) && is_minor(minor_words, lang, word) {
+                        Some(Self::Lower)
+                    } else {
+                        Some(Self::Title)
+                    }
+                }
+                _ => Some(self),
+            };
+
+            for (i, (offset, cluster)) in word.grapheme_indices(true).enumerate() {
+                let range = start + offset..start + offset + cluster.len();
+                let is_letter = |c: Option<char>| c.map_or(false, char::is_alphabetic);
+                let last = case == Some(Self::Lower)
+                    && is_letter(text[..range.start].chars().next_back())
+                    && !is_letter(text[range.end..].chars().next());
+
+                buf.clear();
+                for c in cluster.chars() {
+                    match case {
+                        Some(Self::Lower) => lower_char(&mut buf, c, lang, last),
+                        Some(Self::Upper) => upper_char(&mut buf, c, lang),
+                        Some(Self::Title) if i == 0 => upper_char(&mut buf, c, lang),
+                        _ => buf.push(c),
+                    }
+                }
+                f(range, &buf);
+            }
         }
     }
 }
 
+/// Push the lowercase form of a character, where `last` indicates whether
+/// it ends a word that is longer than it.
+fn lower_char(buf: &mut String, c: char, lang: Lang, last: bool) {
+    match (c, lang.as_str()) {
+        ('I', "tr" | "az") => buf.push('ı'),
+        ('İ', "tr" | "az") => buf.push('i'),
+        ('Σ', _) if last => buf.push('ς'),
+        _ => buf.extend(c.to_lowercase()),
+    }
+}
+
+/// Push the uppercase form of a character.
+fn upper_char(buf: &mut String, c: char, lang: Lang) {
+    match (c, lang.as_str()) {
+        ('i', "tr" | "az") => buf.push('İ'),
+        _ => buf.extend(c.to_uppercase()),
+    }
+}
+
+/// Whether a word is written in capitals, like an acronym.
+fn is_acronym(word: &str) -> bool {
+    let mut letters = word.chars().filter(|c| c.is_alphabetic());
+    letters.clone().nth(1).is_some() && letters.all(char::is_uppercase)
+}
+
+/// Whether a word is a minor word in title case.
+fn is_minor(minor_words: &Smart<MinorWords>, lang: Lang, word: &str) -> bool {
+    let word = word.to_lowercase();
+    let custom = match minor_words {
+        Smart::Custom(MinorWords::All(words)) => Some(words),
+        Smart::Custom(MinorWords::ByLang(langs)) => {
+            langs.iter().find(|(l, _)| *l == lang).map(|(_, words)| words)
+        }
+        Smart::Auto => None,
+    };
+
+    match custom {
+        Some(words) => words.iter().any(|w| w.to_lowercase().as_str() == word),
+        None => default_minor_words(lang).contains(&word.as_str()),
+    }
+}
+
+/// The default minor words of a language.
+fn default_minor_words(lang: Lang) -> &'static [&'static str] {
+    match lang.as_str() {
+        "en" => &[
+            "a", "an", "and", "as", "at", "but", "by", "for", "from", "in", "into",
+            "nor", "of", "on", "or", "per", "the", "to", "via", "vs", "with",
+        ],
+        "de" => &[
+            "am", "an", "auf", "aus", "der", "die", "das", "des", "dem", "den", "ein",
+            "eine", "für", "im", "in", "mit", "oder", "und", "von", "zu", "zum", "zur",
+        ],
+        "es" => &[
+            "a", "con", "de", "del", "el", "en", "la", "las", "los", "o", "para", "por",
+            "un", "una", "y",
+        ],
+        "fr" => &[
+            "à", "au", "aux", "de", "des", "du", "en", "et", "la", "le", "les", "ou",
+            "par", "pour", "sur", "un", "une",
+        ],
+        "it" => &[
+            "a", "al", "con", "da", "del", "della", "di", "e", "il", "in", "la", "le",
+            "lo", "o", "per", "su", "un", "una",
+        ],
+        "nl" => &["de", "een", "en", "het", "in", "met", "of", "op", "te", "van", "voor"],
+        "pt" => &[
+            "a", "as", "com", "da", "das", "de", "do", "dos", "e", "em", "na", "no", "o",
+            "os", "ou", "para", "por", "um", "uma",
+        ],
+        _ => &[],
+    }
+}
+
+/// Words that are kept in lowercase in title case.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum MinorWords {
+    /// The same words for all languages.
+    All(Vec<EcoString>),
+    /// Words for specific languages.
+    ByLang(Vec<(Lang, Vec<EcoString>)>),
+}
+
+cast! {
+    MinorWords,
+    self => match self {
+        Self::All(words) => words.into_value(),
+        Self::ByLang(langs) => langs
+            .into_iter()
+            .map(|(lang, words)| (lang.as_str().into(), words.into_value()))
+            .collect::<Dict>()
+            .into_value(),
+    },
+    words: Vec<EcoString> => Self::All(words),
+    dict: Dict => Self::ByLang(dict
+        .into_iter()
+        .map(|(key, value)| Ok((key.parse()?, value.cast()?)))
+        .collect::<StrResult<_>>()?),
+}
+
 /// Displays text in small capitals.
 ///
 /// _Note:_ This enables the OpenType `smcp` feature for the font. Not all fonts
-/// support this feature. Sometimes smallcaps are part of a dedicated font and
-/// sometimes they are not available at all. In the future, this function will
-/// support selecting a dedicated smallcaps font as well as synthesizing
-/// smallcaps from normal letters, but this is not yet implemented.
+/// support this feature. If a font does not, Typst synthesizes small capitals
+/// by scaling down the uppercase forms of lowercase letters and emits a
+/// warning. Synthesized small capitals look lighter than real ones, so prefer
+/// a font that supports them where possible.
 ///
 /// ## Example { #example }
 /// ```example
@@ -328,3 +568,33 @@ pub fn lorem(
 ) -> Str {
     lipsum::lipsum(words).replace("--", "–").into()
 }
+
+#[cfg(test)]
+mod tests {
+    use typst::eval::Tracer;
+
+    use crate::sandbox::SandboxWorld;
+    use crate::testing::{fonts, text};
+
+    #[test]
+    fn test_case_of_content() {
+        let world = SandboxWorld::new(
+            "#set heading(numbering: \"1.\")\n\
+             #show heading: title-case\n\
+             = the lord of _the_ rings <intro>\n\
+             #upper[see `code`, $\"ab\"$ and @intro]\n\n\
+             #text(lang: \"tr\", upper[istanbul])",
+        )
+        .with_fonts(fonts());
+        let mut tracer = Tracer::default();
+        let document = typst::compile(&world, &mut tracer).unwrap();
+        let page = text(&document.pages[0]).replace(char::is_whitespace, "");
+
+        // Title case continues across elements and skips the numbering.
+        assert!(page.starts_with("1.TheLordoftheRings"));
+
+        // Raw text, equations, and references are left as they are.
+        assert!(page.contains("SEEcode,abANDSection1"));
+        assert!(page.ends_with("İSTANBUL"));
+    }
+}
@@ -1,14 +1,18 @@
 //! Text handling.
 
 mod deco;
+mod hyphenation;
 mod misc;
+mod quote;
 mod quotes;
 mod raw;
 mod shaping;
 mod shift;
 
 pub use self::deco::*;
+pub use self::hyphenation::*;
 pub use self::misc::*;
+pub use self::quote::*;
 pub use self::quotes::*;
 pub use self::raw::*;
 pub use self::shaping::*;
@@ -25,16 +29,19 @@ pub(super) fn define(global: &mut Scope) {
     global.define("text", TextElem::func());
     global.define("linebreak", LinebreakElem::func());
     global.define("smartquote", SmartQuoteElem::func());
+    global.define("quote", QuoteElem::func());
     global.define("strong", StrongElem::func());
     global.define("emph", EmphElem::func());
     global.define("lower", lower_func());
     global.define("upper", upper_func());
+    global.define("title-case", title_case_func());
     global.define("smallcaps", smallcaps_func());
     global.define("sub", SubElem::func());
     global.define("super", SuperElem::func());
     global.define("underline", UnderlineElem::func());
     global.define("strike", StrikeElem::func());
     global.define("overline", OverlineElem::func());
+    global.define("highlight", HighlightElem::func());
     global.define("raw", RawElem::func());
     global.define("lorem", lorem_func());
 }
@@ -188,6 +195,20 @@ pub struct TextElem {
     #[resolve]
     pub tracking: Length,
 
+    /// Whether to apply [tracking]($func/text.tracking) between the last
+    /// letter of a word and directly following punctuation like a period or
+    /// a closing parenthesis.
+    ///
+    /// ```example
+    /// #set text(tracking: 3pt)
+    /// Tracked, spaced.
+    ///
+    /// #set text(track-punctuation: false)
+    /// Tracked, spaced.
+    /// ```
+    #[default(true)]
+    pub track_punctuation: bool,
+
     /// The amount of space between words.
     ///
     /// Can be given as an absolute length, but also relative to the width of
@@ -216,6 +237,13 @@ pub struct TextElem {
     /// Whether certain glyphs can hang over into the margin in justified text.
     /// This can make justification visually more pleasing.
     ///
+    /// Punctuation, quotes, and dashes protrude by a fraction of their width
+    /// at the start and end of lines. Instead of `{true}`, you can also pass a
+    /// dictionary that maps characters to arrays with the fractions by which
+    /// they protrude at the start and at the end of a line. Characters that
+    /// are not in the dictionary keep their default protrusion. Protrusion
+    /// only moves the lines' contents and never changes where lines break.
+    ///
     /// ```example
     /// #set par(justify: true)
     /// This justified text has a hyphen in
@@ -228,9 +256,34 @@ pub struct TextElem {
     /// the paragraph's first line. Hanging
     /// the hyphen slightly into the margin
     /// results in a clearer paragraph edge.
+    ///
+    /// #set text(overhang: ("-": (0%, 100%)))
+    /// This justified text has a hyphen in
+    /// the paragraph's first line. Hanging
+    /// the hyphen slightly into the margin
+    /// results in a clearer paragraph edge.
     /// ```
-    #[default(true)]
-    pub overhang: bool,
+    #[default(Overhang::from(true))]
+    pub overhang: Overhang,
+
+    /// Whether to automatically insert spacing between CJK and Latin
+    /// characters.
+    ///
+    /// If set to `{auto}`, a quarter of an em of space is inserted where a
+    /// Chinese or Japanese character directly borders a Latin letter or a
+    /// digit, as recommended by the Requirements for Chinese Text Layout. No
+    /// space is inserted at the start and end of a line or if there already is
+    /// a space. By default, no spacing is inserted.
+    ///
+    /// ```example
+    /// #set text(lang: "zh", font: "Noto Serif CJK SC")
+    /// 第4章介绍Typst排版
+    ///
+    /// #set text(cjk-latin-spacing: auto)
+    /// 第4章介绍Typst排版
+    /// ```
+    #[default(Smart::Custom(None))]
+    pub cjk_latin_spacing: Smart<Option<Never>>,
 
     /// The top end of the conceptual frame around the text used for layout and
     /// positioning. This affects the size of containers that hold text.
@@ -338,6 +391,23 @@ pub struct TextElem {
     #[resolve]
     pub hyphenate: Hyphenate,
 
+    /// Custom hyphenation patterns in TeX format, used for languages for which
+    /// Typst has no built-in patterns.
+    ///
+    /// The patterns must be given in a `\patterns{...}` group. Words with
+    /// explicit hyphenation points can additionally be listed in a
+    /// `\hyphenation{...}` group. Pattern files are typically loaded with the
+    /// [`read`]($func/read) function.
+    ///
+    /// ```typ
+    /// #set text(
+    ///   lang: "xx",
+    ///   hyphenate: true,
+    ///   hyphenation-patterns: read("hyph-xx.tex"),
+    /// )
+    /// ```
+    pub hyphenation_patterns: Option<HyphenationPatterns>,
+
     /// Whether to apply kerning.
     ///
     /// When enabled, specific letter pairings move closer together or further
@@ -506,6 +576,10 @@ pub struct TextElem {
     #[internal]
     pub case: Option<Case>,
 
+    /// The words that are kept in lowercase in title case.
+    #[internal]
+    pub minor_words: Smart<MinorWords>,
+
     /// Whether small capital glyphs should be used. ("smcp")
     #[internal]
     #[default(false)]
@@ -520,11 +594,11 @@ impl TextElem {
 }
 
 impl Construct for TextElem {
-    fn construct(_: &mut Vm, args: &mut Args) -> SourceResult<Content> {
+    fn construct(vm: &mut Vm, args: &mut Args) -> SourceResult<Content> {
         // The text constructor is special: It doesn't create a text element.
         // Instead, it leaves the passed argument structurally unchanged, but
         // styles all text in it.
-        let styles = Self::set(args)?;
+        let styles = Self::set(vm, args)?;
         let body = args.expect::<Content>("body")?;
         Ok(body.styled_with_map(styles))
     }
@@ -682,6 +756,90 @@ impl Resolve for Hyphenate {
     }
 }
 
+/// How far characters hang into the margins at the start and end of lines.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct Overhang {
+    /// Whether characters hang into the margins at all.
+    pub enabled: bool,
+    /// Characters with custom protrusion at the start and end of lines, as a
+    /// fraction of their advance.
+    pub custom: Vec<(char, Ratio, Ratio)>,
+}
+
+impl Overhang {
+    /// The fractions of its advance by which a character protrudes into the
+    /// margin at the start and end of a line.
+    pub fn factors(&self, c: char) -> (f64, f64) {
+        if !self.enabled {
+            return (0.0, 0.0);
+        }
+
+        if let Some(&(_, start, end)) = self.custom.iter().find(|&&(k, ..)| k == c) {
+            return (start.get(), end.get());
+        }
+
+        match c {
+            // Dashes.
+            '–' | '—' => (0.2, 0.2),
+            '-' => (0.0, 0.55),
+
+            // Punctuation.
+            '.' | ',' => (0.0, 0.8),
+            ':' | ';' => (0.0, 0.3),
+
+            // Quotes.
+            '“' | '”' | '„' | '‘' | '’' | '‚' | '"' | '\'' => (0.5, 0.5),
+            '«' | '»' | '‹' | '›' => (0.3, 0.3),
+
+            // Arabic
+            '\u{60C}' | '\u{6D4}' => (0.0, 0.4),
+
+            _ => (0.0, 0.0),
+        }
+    }
+}
+
+impl From<bool> for Overhang {
+    fn from(enabled: bool) -> Self {
+        Self { enabled, custom: vec![] }
+    }
+}
+
+cast! {
+    Overhang,
+    self => if self.custom.is_empty() {
+        self.enabled.into_value()
+    } else {
+        self.custom
+            .into_iter()
+            .map(|(c, start, end)| {
+                (c.into(), array![start, end].into_value())
+            })
+            .collect::<Dict>()
+            .into_value()
+    },
+    v: bool => Self::from(v),
+    values: Dict => Self {
+        enabled: true,
+        custom: values
+            .into_iter()
+            .map(|(k, v)| {
+                let mut chars = k.chars();
+                let (Some(c), None) = (chars.next(), chars.next()) else {
+                    bail!("overhang keys must be single characters");
+                };
+                let (start, end) = match v.cast::<Array>()?.as_slice() {
+                    [start, end] => {
+                        (start.clone().cast::<Ratio>()?, end.clone().cast::<Ratio>()?)
+                    }
+                    _ => bail!("overhang must be specified as an array of two ratios"),
+                };
+                Ok((c, start, end))
+            })
+            .collect::<StrResult<_>>()?,
+    },
+}
+
 /// A stylistic set in a font.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct StylisticSet(u8);
@@ -0,0 +1,182 @@
+use super::{SmartQuoteElem, SpaceElem, TextElem};
+use crate::layout::{BlockElem, PadElem, VElem};
+use crate::meta::CiteElem;
+use crate::prelude::*;
+
+/// Displays a quote alongside an optional attribution.
+///
+/// Inline quotes are wrapped in language-aware [smart
+/// quotes]($func/smartquote) that alternate between double and single quotes
+/// when nested. Block quotes are set apart from the surrounding text and
+/// display their attribution below the quote.
+///
+/// ## Example { #example }
+/// ```example
+/// Plato is often misquoted as the author of #quote[I know that I know
+/// nothing], however, this is a derivation from his original quote:
+///
+/// #set quote(block: true)
+///
+/// #quote(attribution: [Plato])[
+///   ... ἔοικα γοῦν τούτου γε σμικρῷ τινι αὐτῷ τούτῳ σοφώτερος εἶναι, ὅτι
+///   ἃ μὴ οἶδα οὐδὲ οἴομαι εἰδέναι.
+/// ]
+/// #quote(attribution: [from the Henry Cary literal translation of 1897])[
+///   ... I seem, then, in just this little thing to be wiser than this man at
+///   any rate, that what I do not know I do not think I know either.
+/// ]
+/// ```
+///
+/// Display: Quote
+/// Category: text
+#[element(Show)]
+pub struct QuoteElem {
+    /// Whether this is a block quote.
+    ///
+    /// ```example
+    /// An inline citation would look like
+    /// this: #quote(
+    ///   attribution: [René Descartes]
+    /// )[
+    ///   cogito, ergo sum
+    /// ], and a block quote like this:
+    /// #quote(
+    ///   block: true,
+    ///   attribution: [JFK]
+    /// )[
+    ///   Ich bin ein Berliner.
+    /// ]
+    /// ```
+    #[default(false)]
+    pub block: bool,
+
+    /// Whether double quotes should be added around this quote.
+    ///
+    /// The double quotes used are inferred from the `quotes` property on
+    /// [smartquote]($func/smartquote), which is affected by the `lang`
+    /// property on [text]($func/text).
+    ///
+    /// - `{true}`: Wrap this quote in double quotes.
+    /// - `{false}`: Do not wrap this quote in double quotes.
+    /// - `{auto}`: Infer whether to wrap this quote in double quotes based on
+    ///   the `block` property. If `block` is `{false}`, double quotes are
+    ///   automatically added.
+    ///
+    /// ```example
+    /// #set text(lang: "de")
+    ///
+    /// Ich bin ein Berliner.
+    /// #quote[Ich bin ein Berliner.]
+    ///
+    /// #quote(block: true, quotes: true)[
+    ///   Ich bin ein Berliner.
+    /// ]
+    /// ```
+    pub quotes: Smart<bool>,
+
+    /// The attribution of this quote, usually the author or source. Can be a
+    /// label pointing to a bibliography entry or any content. By default only
+    /// displayed for block quotes, but can be changed using a `{show}` rule.
+    ///
+    /// ```example
+    /// #quote(block: true, attribution: [René Descartes])[
+    ///   cogito, ergo sum
+    /// ]
+    ///
+    /// #show quote.where(block: false): it => {
+    ///   ["] + it.body + ["]
+    ///   if it.attribution != none [ (#it.attribution)]
+    /// }
+    ///
+    /// #quote(
+    ///   attribution: link("https://typst.app/home")[typst.com]
+    /// )[
+    ///   Compose papers faster
+    /// ]
+    ///
+    /// #set quote(block: true)
+    ///
+    /// #quote(attribution: <tolkien54>)[
+    ///   You cannot pass... I am a servant of the Secret Fire, wielder of the
+    ///   flame of Anor. You cannot pass. The dark fire will not avail you,
+    ///   flame of Udûn. Go back to the Shadow! You cannot pass.
+    /// ]
+    ///
+    /// #bibliography("works.bib")
+    /// ```
+    pub attribution: Option<Attribution>,
+
+    /// The quote.
+    #[required]
+    pub body: Content,
+}
+
+/// Attribution for a [quote](QuoteElem).
+#[derive(Debug, Clone, Hash)]
+pub enum Attribution {
+    /// Arbitrary content, usually the author or source.
+    Content(Content),
+    /// A label pointing to a bibliography entry.
+    Label(Label),
+}
+
+cast! {
+    Attribution,
+    self => match self {
+        Self::Content(content) => content.into_value(),
+        Self::Label(label) => label.into_value(),
+    },
+    label: Label => Self::Label(label),
+    content: Content => Self::Content(content),
+}
+
+impl Attribution {
+    /// Turn the attribution into displayable content. Labels become
+    /// citations of the bibliography entry they point to.
+    fn realize(self, span: Span) -> Content {
+        match self {
+            Self::Content(content) => content,
+            Self::Label(label) => CiteElem::new(vec![label.0]).pack().spanned(span),
+        }
+    }
+}
+
+impl Show for QuoteElem {
+    #[tracing::instrument(name = "QuoteElem::show", skip_all)]
+    fn show(&self, _: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
+        let mut realized = self.body();
+        let block = self.block(styles);
+
+        if self.quotes(styles).unwrap_or(!block) {
+            let quote = SmartQuoteElem::new().with_double(true).pack();
+            realized = Content::sequence([quote.clone(), realized, quote]);
+        }
+
+        let attribution = self.attribution(styles);
+        if block {
+            realized = BlockElem::new().with_body(Some(realized)).pack();
+
+            if let Some(attribution) = attribution {
+                let attribution = attribution.realize(self.span());
+                let dash = TextElem::packed("—");
+                realized += VElem::weak(Em::new(0.5).into()).pack();
+                realized += BlockElem::new()
+                    .with_body(Some(
+                        (dash + SpaceElem::new().pack() + attribution)
+                            .aligned(Axes::with_x(Some(GenAlign::End))),
+                    ))
+                    .pack();
+            }
+
+            realized = PadElem::new(realized)
+                .with_left(Em::new(1.0).into())
+                .with_right(Em::new(1.0).into())
+                .pack();
+        } else if let Some(Attribution::Label(label)) = attribution {
+            realized +=
+                SpaceElem::new().pack() + Attribution::Label(label).realize(self.span());
+        }
+
+        Ok(realized)
+    }
+}
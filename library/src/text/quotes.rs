@@ -16,6 +16,8 @@ use crate::prelude::*;
 ///
 /// #set text(lang: "fr")
 /// "C'est entre guillemets."
+///
+/// "Nested 'quotes' alternate between "double and single" quotes."
 /// ```
 ///
 /// ## Syntax { #syntax }
@@ -42,26 +44,84 @@ pub struct SmartQuoteElem {
     /// ```
     #[default(true)]
     pub enabled: bool,
+
+    /// The quotes to use.
+    ///
+    /// - When set to `{auto}`, the appropriate quotes for the
+    ///   [text language]($func/text.lang) will be used. This is the default.
+    /// - Custom quotes can be passed as a dictionary with the keys `single`
+    ///   and/or `double`, each mapping to an array of an opening and a
+    ///   closing quote. Quotes that are not specified fall back to those of
+    ///   the text language.
+    ///
+    /// ```example
+    /// #set smartquote(quotes: (double: ("»", "«")))
+    /// "Reversed guillemets" and 'normal single quotes'.
+    /// ```
+    pub quotes: Smart<QuoteDict>,
+}
+
+/// Custom opening and closing quotes.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct QuoteDict {
+    /// The opening and closing single quote.
+    pub single: Option<[EcoString; 2]>,
+    /// The opening and closing double quote.
+    pub double: Option<[EcoString; 2]>,
+}
+
+cast! {
+    QuoteDict,
+    self => {
+        let mut dict = Dict::new();
+        if let Some([open, close]) = self.single {
+            dict.insert("single".into(), array![open, close].into_value());
+        }
+        if let Some([open, close]) = self.double {
+            dict.insert("double".into(), array![open, close].into_value());
+        }
+        dict.into_value()
+    },
+    mut dict: Dict => {
+        let mut take = |key| dict.take(key).ok().map(cast_quote_pair).transpose();
+        let single = take("single")?;
+        let double = take("double")?;
+        dict.finish(&["single", "double"])?;
+        Self { single, double }
+    },
+}
+
+/// Cast a value to a pair of an opening and a closing quote.
+fn cast_quote_pair(value: Value) -> StrResult<[EcoString; 2]> {
+    match value.cast::<Array>()?.as_slice() {
+        [open, close] => Ok([open.clone().cast()?, close.clone().cast()?]),
+        _ => bail!("expected an array of an opening and a closing quote"),
+    }
 }
 
 /// State machine for smart quote substitution.
 #[derive(Debug, Clone)]
 pub struct Quoter {
-    /// How many quotes have been opened.
-    quote_depth: usize,
+    /// The quotes that are currently open, innermost last. For each quote,
+    /// this stores whether it was written as a double quote and whether it
+    /// is displayed as one.
+    opened: Vec<(bool, bool)>,
     /// Whether an opening quote might follow.
     expect_opening: bool,
     /// Whether the last character was numeric.
     last_num: bool,
+    /// Whether the last character was a letter.
+    last_alphabetic: bool,
 }
 
 impl Quoter {
     /// Start quoting.
     pub fn new() -> Self {
         Self {
-            quote_depth: 0,
+            opened: vec![],
             expect_opening: true,
             last_num: false,
+            last_alphabetic: false,
         }
     }
 
@@ -69,6 +129,7 @@ impl Quoter {
     pub fn last(&mut self, c: char) {
         self.expect_opening = is_ignorable(c) || is_opening_bracket(c);
         self.last_num = c.is_numeric();
+        self.last_alphabetic = c.is_alphabetic();
     }
 
     /// Process and substitute a quote.
@@ -80,13 +141,28 @@ impl Quoter {
     ) -> &'a str {
         let peeked = peeked.unwrap_or(' ');
         if self.expect_opening {
-            self.quote_depth += 1;
-            quotes.open(double)
-        } else if self.quote_depth > 0
-            && (peeked.is_ascii_punctuation() || is_ignorable(peeked))
+            // A quote nested directly in one of the same kind is displayed
+            // in the other style, so that the levels can be told apart.
+            let displayed = match self.opened.last() {
+                Some(&(_, outer)) if outer == double => !double,
+                _ => double,
+            };
+            self.opened.push((double, displayed));
+            quotes.open(displayed)
+        } else if !self.opened.is_empty()
+            && (is_closing_punctuation(peeked) || is_ignorable(peeked))
         {
-            self.quote_depth -= 1;
-            quotes.close(double)
+            // A single quote at the end of a word that doesn't close any
+            // single quote is an apostrophe, as in "the students' books".
+            if !double
+                && self.last_alphabetic
+                && self.opened.iter().all(|&(written, _)| written)
+            {
+                return quotes.fallback(double);
+            }
+
+            let (written, displayed) = self.opened.pop().unwrap();
+            quotes.close(if written == double { displayed } else { double })
         } else if self.last_num {
             quotes.prime(double)
         } else {
@@ -109,6 +185,11 @@ fn is_opening_bracket(c: char) -> bool {
     matches!(c, '(' | '{' | '[')
 }
 
+fn is_closing_punctuation(c: char) -> bool {
+    c.is_ascii_punctuation()
+        || matches!(c, '…' | '–' | '—' | '’' | '”' | '»' | '›' | '\u{FFFC}')
+}
+
 /// Decides which quotes to substitute smart quotes with.
 pub struct Quotes<'s> {
     /// The opening single quote.
@@ -159,6 +240,23 @@ impl<'s> Quotes<'s> {
         }
     }
 
+    /// Create a new `Quotes` struct for a language and region, where quotes
+    /// from the given custom quotes take precedence.
+    pub fn new(custom: &'s Smart<QuoteDict>, lang: Lang, region: Option<Region>) -> Self {
+        let mut quotes = Self::from_lang(lang, region);
+        if let Smart::Custom(custom) = custom {
+            if let Some([open, close]) = &custom.single {
+                quotes.single_open = open;
+                quotes.single_close = close;
+            }
+            if let Some([open, close]) = &custom.double {
+                quotes.double_open = open;
+                quotes.double_close = close;
+            }
+        }
+        quotes
+    }
+
     /// The opening quote.
     fn open(&self, double: bool) -> &'s str {
         if double {
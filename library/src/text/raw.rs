@@ -1,11 +1,14 @@
+use std::sync::Arc;
+
 use once_cell::sync::Lazy;
 use syntect::highlighting as synt;
 use typst::syntax::{self, LinkedNode};
+use typst::util::Bytes;
 
 use super::{
     FontFamily, FontList, Hyphenate, LinebreakElem, SmartQuoteElem, TextElem, TextSize,
 };
-use crate::layout::BlockElem;
+use crate::layout::{BlockElem, BoxElem, ParElem, ParbreakElem, VElem};
 use crate::meta::{Figurable, LocalName};
 use crate::prelude::*;
 
@@ -37,6 +40,10 @@ use crate::prelude::*;
 /// Display: Raw Text / Code
 /// Category: text
 #[element(Synthesize, Show, Finalize, LocalName, Figurable, PlainText)]
+#[scope(
+    scope.define("line", RawLine::func());
+    scope
+)]
 pub struct RawElem {
     /// The raw text.
     ///
@@ -126,6 +133,116 @@ pub struct RawElem {
     /// ````
     #[default(HorizontalAlign(GenAlign::Start))]
     pub align: HorizontalAlign,
+
+    /// One or multiple additional syntax definitions to load. The syntax
+    /// definitions should be in the
+    /// [`sublime-syntax` file format](https://www.sublimetext.com/docs/syntax.html).
+    ///
+    /// Languages defined in these files take precedence over the built-in
+    /// ones and can refer to the built-in ones by their scope.
+    ///
+    /// ````example
+    /// #set raw(syntaxes: "SExpressions.sublime-syntax")
+    ///
+    /// ```sexp
+    /// (defun factorial (x)
+    ///   (if (zerop x)
+    ///     ; with a comment
+    ///     1
+    ///     (* x (factorial (- x 1)))))
+    /// ```
+    /// ````
+    #[parse(
+        let (syntaxes, syntaxes_data) = parse_syntaxes(vm, args)?;
+        syntaxes
+    )]
+    #[default]
+    pub syntaxes: SyntaxPaths,
+
+    /// The raw file data of the additional syntax definitions.
+    #[internal]
+    #[parse(syntaxes_data)]
+    #[default]
+    pub syntaxes_data: Vec<Bytes>,
+
+    /// The theme to use for syntax highlighting. Theme files should be in the
+    /// [`tmTheme` file format](https://www.sublimetext.com/docs/color_schemes_tmtheme.html).
+    ///
+    /// The theme's foreground color is applied to all raw text and its
+    /// background color, if any, fills raw blocks. Set this to `{none}` to
+    /// return to the built-in theme.
+    ///
+    /// ````example
+    /// #set raw(theme: "halcyon.tmTheme")
+    ///
+    /// ```typ
+    /// = Chapter 1
+    /// #let hi = "Hello World"
+    /// ```
+    /// ````
+    #[parse(
+        let (theme_path, theme_data) = parse_theme(vm, args)?;
+        theme_path
+    )]
+    pub theme: Option<EcoString>,
+
+    /// The raw file data of the theme.
+    #[internal]
+    #[parse(theme_data)]
+    pub theme_data: Option<Bytes>,
+
+    /// The lines to highlight, counted from one. Each entry is either a line
+    /// number or an array of line numbers, such as `{range(5, 8)}`.
+    ///
+    /// Highlighted lines are shown with a light background. Show rules on
+    /// [`raw.line`]($func/raw.line) can style them differently by checking
+    /// the line's `highlighted` field.
+    ///
+    /// ````example
+    /// #set raw(highlight-lines: (2, range(4, 6)))
+    ///
+    /// ```rust
+    /// fn main() {
+    ///     let a = 1;
+    ///     let b = 2;
+    ///     let c = a + b;
+    ///     println!("{c}");
+    /// }
+    /// ```
+    /// ````
+    #[default]
+    pub highlight_lines: HighlightedLines,
+
+    /// The number of columns between two tab stops. A tab advances the text
+    /// to the next tab stop, measured from the start of its line.
+    ///
+    /// ````example
+    /// #set raw(tab-size: 8)
+    /// ```tsv
+    /// Year	Month	Day
+    /// 2000	2	3
+    /// 2001	2	1
+    /// 2002	3	10
+    /// ```
+    /// ````
+    #[default(NonZeroUsize::new(2).unwrap())]
+    pub tab_size: NonZeroUsize,
+
+    /// Whether lines that are too long for a raw block continue at the
+    /// indentation of the line they belong to when they wrap. This option is
+    /// ignored if this is not a raw block.
+    ///
+    /// ````example
+    /// #set raw(wrap: true)
+    /// #set block(width: 120pt)
+    ///
+    /// ```py
+    /// def greet(name):
+    ///     print("Hello, " + name + "! How are you doing today?")
+    /// ```
+    /// ````
+    #[default(false)]
+    pub wrap: bool,
 }
 
 impl RawElem {
@@ -154,59 +271,153 @@ impl Synthesize for RawElem {
 
 impl Show for RawElem {
     #[tracing::instrument(name = "RawElem::show", skip_all)]
-    fn show(&self, _: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
-        let text = self.text();
+    fn show(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
+        let text = expand_tabs(&self.text(), self.tab_size(styles).get());
         let lang = self.lang(styles).as_ref().map(|s| s.to_lowercase());
-        let foreground = THEME
+        let syntaxes = load_syntaxes(&self.syntaxes(styles), &self.syntaxes_data(styles))
+            .at(self.span())?;
+        let custom = match self.theme_data(styles) {
+            Some(data) => Some(load_theme(data).at(self.span())?),
+            None => None,
+        };
+
+        let theme = custom.as_deref().unwrap_or(&THEME);
+        let foreground = theme
             .settings
             .foreground
             .map(to_typst)
             .map_or(Color::BLACK, Color::from);
 
-        let mut realized = if matches!(lang.as_deref(), Some("typ" | "typst" | "typc")) {
+        // The highlighted pieces of each line.
+        let mut lines: Vec<Vec<Content>> = vec![];
+        if matches!(lang.as_deref(), Some("typ" | "typst" | "typc")) {
             let root = match lang.as_deref() {
                 Some("typc") => syntax::parse_code(&text),
                 _ => syntax::parse(&text),
             };
 
-            let mut seq = vec![];
-            let highlighter = synt::Highlighter::new(&THEME);
+            // Nodes may span multiple lines, so we split them at newlines.
+            let mut current = vec![];
+            let highlighter = synt::Highlighter::new(theme);
             highlight_themed(
                 &LinkedNode::new(&root),
                 vec![],
                 &highlighter,
                 &mut |node, style| {
-                    seq.push(styled(&text[node.range()], foreground.into(), style));
+                    for (i, piece) in text[node.range()].split('\n').enumerate() {
+                        if i != 0 {
+                            lines.push(std::mem::take(&mut current));
+                        }
+
+                        let piece = piece.trim_end_matches('\r');
+                        if !piece.is_empty() {
+                            current.push(styled(piece, foreground.into(), style));
+                        }
+                    }
                 },
             );
 
-            Content::sequence(seq)
+            lines.push(current);
         } else if let Some(syntax) =
-            lang.and_then(|token| SYNTAXES.find_syntax_by_token(&token))
+            lang.as_ref().and_then(|token| syntaxes.find_syntax_by_token(token))
         {
-            let mut seq = vec![];
-            let mut highlighter = syntect::easy::HighlightLines::new(syntax, &THEME);
-            for (i, line) in text.lines().enumerate() {
-                if i != 0 {
-                    seq.push(LinebreakElem::new().pack());
-                }
-
-                for (style, piece) in
-                    highlighter.highlight_line(line, &SYNTAXES).into_iter().flatten()
-                {
-                    seq.push(styled(piece, foreground.into(), style));
-                }
+            let mut highlighter = syntect::easy::HighlightLines::new(syntax, theme);
+            for line in text.lines() {
+                lines.push(
+                    highlighter
+                        .highlight_line(line, &syntaxes)
+                        .into_iter()
+                        .flatten()
+                        .map(|(style, piece)| styled(piece, foreground.into(), style))
+                        .collect(),
+                );
+            }
+        } else {
+            if let Some(lang) = lang.as_deref().filter(|&lang| lang != "text") {
+                vt.tracer.warn(
+                    warning!(self.span(), "unknown language `{lang}`").with_hints([
+                        "the text is displayed without syntax highlighting".into(),
+                    ]),
+                );
             }
 
-            Content::sequence(seq)
+            lines.extend(text.lines().map(|line| vec![TextElem::packed(line)]));
+        }
+
+        // A trailing newline doesn't start another line.
+        let texts: Vec<&str> = text.lines().collect();
+        lines.truncate(texts.len());
+
+        // When wrapping with a hanging indent, each line becomes a paragraph
+        // of its own and the indentation is measured in the raw font.
+        let block = self.block(styles);
+        let wrap = block && self.wrap(styles);
+        let advance = if wrap {
+            let probe = self.finalize(TextElem::packed("0"), styles);
+            let pod = Regions::one(Axes::splat(Abs::inf()), Axes::splat(false));
+            probe.measure(vt, styles, pod)?.into_frame().width()
         } else {
-            TextElem::packed(text)
+            Abs::zero()
         };
 
-        if self.block(styles) {
+        let count = texts.len() as i64;
+        let highlighted = self.highlight_lines(styles);
+        let mut seq = vec![];
+        for (i, (line, body)) in texts.into_iter().zip(lines).enumerate() {
+            if i != 0 {
+                seq.push(if wrap {
+                    ParbreakElem::new().pack()
+                } else {
+                    LinebreakElem::new().pack()
+                });
+            }
+
+            let number = i as i64 + 1;
+            let mut realized = RawLine::new(
+                number,
+                count,
+                line.into(),
+                Content::sequence(body),
+                highlighted.contains(i + 1),
+            )
+            .spanned(self.span())
+            .pack();
+
+            let indent = line.len() - line.trim_start_matches(' ').len();
+            if wrap && indent > 0 {
+                let hang = advance * indent as f64;
+                realized = realized.styled(ParElem::set_hanging_indent(hang.into()));
+            }
+
+            seq.push(realized);
+        }
+
+        let mut realized = Content::sequence(seq);
+
+        // Lines of a wrapped raw block are spaced like lines in a paragraph.
+        if wrap {
+            let leading = VElem::block_around(ParElem::leading_in(styles).into());
+            let mut map = Styles::new();
+            map.set(BlockElem::set_above(leading.clone()));
+            map.set(BlockElem::set_below(leading));
+            realized = realized.styled_with_map(map);
+        }
+
+        // Apply the colors of a custom theme.
+        if custom.is_some() {
+            realized = realized.styled(TextElem::set_fill(foreground.into()));
+        }
+
+        if block {
             // Align the text before inserting it into the block.
             realized = realized.aligned(Axes::with_x(Some(self.align(styles).into())));
-            realized = BlockElem::new().with_body(Some(realized)).pack();
+            let mut block = BlockElem::new().with_body(Some(realized));
+            if let Some(background) =
+                custom.as_ref().and_then(|theme| theme.settings.background)
+            {
+                block.push_fill(Some(Color::from(to_typst(background)).into()));
+            }
+            realized = block.pack();
         }
 
         Ok(realized)
@@ -216,12 +427,13 @@ impl Show for RawElem {
 impl Finalize for RawElem {
     fn finalize(&self, realized: Content, _: StyleChain) -> Content {
         let mut styles = Styles::new();
-        styles.set(TextElem::set_overhang(false));
+        styles.set(TextElem::set_overhang(false.into()));
         styles.set(TextElem::set_hyphenate(Hyphenate(Smart::Custom(false))));
         styles.set(TextElem::set_size(TextSize(Em::new(0.8).into())));
         styles
             .set(TextElem::set_font(FontList(vec![FontFamily::new("DejaVu Sans Mono")])));
         styles.set(SmartQuoteElem::set_enabled(false));
+        styles.set(TextElem::set_case(None));
         realized.styled_with_map(styles)
     }
 }
@@ -261,6 +473,231 @@ impl PlainText for RawElem {
     }
 }
 
+/// A highlighted line of raw text.
+///
+/// This is a helper element that is synthesized by [`raw`]($func/raw)
+/// elements. You can use it in show rules to style individual lines, for
+/// example to number them.
+///
+/// ````example
+/// #show raw.line: it => {
+///   text(gray, str(it.number))
+///   h(1em)
+///   it.body
+/// }
+///
+/// ```rust
+/// fn main() {
+///     println!("Hello World!");
+/// }
+/// ```
+/// ````
+///
+/// Display: Raw Text / Code Line
+/// Category: text
+#[element(Show, PlainText)]
+pub struct RawLine {
+    /// The line number of the raw line inside of the raw block, starting
+    /// at 1.
+    #[required]
+    pub number: i64,
+
+    /// The total number of lines in the raw block.
+    #[required]
+    pub count: i64,
+
+    /// The line of raw text.
+    #[required]
+    pub text: EcoString,
+
+    /// The highlighted raw text.
+    #[required]
+    pub body: Content,
+
+    /// Whether the line was selected with the raw element's
+    /// [`highlight-lines`]($func/raw.highlight-lines) option.
+    #[required]
+    pub highlighted: bool,
+}
+
+impl Show for RawLine {
+    #[tracing::instrument(name = "RawLine::show", skip_all)]
+    fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        let body = self.body();
+        if !self.highlighted() {
+            return Ok(body);
+        }
+
+        Ok(BoxElem::new()
+            .with_body(Some(body))
+            .with_fill(Some(HIGHLIGHT.into()))
+            .pack())
+    }
+}
+
+impl PlainText for RawLine {
+    fn plain_text(&self, text: &mut EcoString) {
+        text.push_str(&self.text());
+    }
+}
+
+/// A list of paths to syntax definition files.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub struct SyntaxPaths(Vec<EcoString>);
+
+cast! {
+    SyntaxPaths,
+    self => self.0.into_value(),
+    v: EcoString => Self(vec![v]),
+    v: Array => Self(v.into_iter().map(Value::cast).collect::<StrResult<_>>()?),
+}
+
+/// The lines of a raw block that should be highlighted.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub struct HighlightedLines(Vec<NonZeroUsize>);
+
+impl HighlightedLines {
+    /// Whether the line with the given number is highlighted.
+    pub fn contains(&self, number: usize) -> bool {
+        self.0.iter().any(|line| line.get() == number)
+    }
+}
+
+cast! {
+    HighlightedLines,
+    self => self.0.into_value(),
+    v: NonZeroUsize => Self(vec![v]),
+    v: Array => {
+        let mut lines = vec![];
+        for entry in v {
+            match entry {
+                Value::Array(array) => {
+                    for line in array {
+                        lines.push(line.cast()?);
+                    }
+                }
+                entry => lines.push(entry.cast()?),
+            }
+        }
+        Self(lines)
+    },
+}
+
+/// Load the syntax definition files given to the `syntaxes` argument.
+fn parse_syntaxes(
+    vm: &mut Vm,
+    args: &mut Args,
+) -> SourceResult<(Option<SyntaxPaths>, Option<Vec<Bytes>>)> {
+    let Some(Spanned { v: paths, span }) =
+        args.named::<Spanned<SyntaxPaths>>("syntaxes")?
+    else {
+        return Ok((None, None));
+    };
+
+    let data = paths
+        .0
+        .iter()
+        .map(|path| {
+            let id = vm.location().join(path).at(span)?;
+            vm.world().file(id).at(span)
+        })
+        .collect::<SourceResult<Vec<_>>>()?;
+
+    // Check that the syntax definitions are valid.
+    load_syntaxes(&paths, &data).at(span)?;
+
+    Ok((Some(paths), Some(data)))
+}
+
+/// Load the theme file given to the `theme` argument.
+#[allow(clippy::type_complexity)]
+fn parse_theme(
+    vm: &mut Vm,
+    args: &mut Args,
+) -> SourceResult<(Option<Option<EcoString>>, Option<Option<Bytes>>)> {
+    let Some(Spanned { v: path, span }) =
+        args.named::<Spanned<Option<EcoString>>>("theme")?
+    else {
+        return Ok((None, None));
+    };
+
+    let Some(path) = path else {
+        return Ok((Some(None), Some(None)));
+    };
+
+    let id = vm.location().join(&path).at(span)?;
+    let data = vm.world().file(id).at(span)?;
+
+    // Check that the theme is valid.
+    load_theme(data.clone()).at(span)?;
+
+    Ok((Some(Some(path)), Some(Some(data))))
+}
+
+/// Build a syntax set from the built-in syntaxes and additional syntax
+/// definition files.
+#[comemo::memoize]
+fn load_syntaxes(
+    paths: &SyntaxPaths,
+    data: &[Bytes],
+) -> StrResult<Arc<syntect::parsing::SyntaxSet>> {
+    if data.is_empty() {
+        return Ok(Arc::new(SYNTAXES.clone()));
+    }
+
+    let mut builder = SYNTAXES.clone().into_builder();
+    for (path, bytes) in paths.0.iter().zip(data) {
+        let src = std::str::from_utf8(bytes).map_err(|_| "file is not valid utf-8")?;
+        let definition = syntect::parsing::SyntaxDefinition::load_from_str(
+            src, false, None,
+        )
+        .map_err(|err| eco_format!("failed to parse syntax file `{path}` ({err})"))?;
+        builder.add(definition);
+    }
+
+    Ok(Arc::new(builder.build()))
+}
+
+/// Parse a theme file.
+#[comemo::memoize]
+fn load_theme(data: Bytes) -> StrResult<Arc<synt::Theme>> {
+    let mut cursor = std::io::Cursor::new(data.as_slice());
+    synt::ThemeSet::load_from_reader(&mut cursor)
+        .map(Arc::new)
+        .map_err(|err| eco_format!("failed to parse theme file ({err})"))
+}
+
+/// Expand tabs so that each of them advances to the next tab stop.
+fn expand_tabs(text: &str, tab_size: usize) -> EcoString {
+    if !text.contains('\t') {
+        return text.into();
+    }
+
+    let mut expanded = EcoString::with_capacity(text.len());
+    let mut column = 0;
+    for c in text.chars() {
+        match c {
+            '\t' => {
+                let width = tab_size - column % tab_size;
+                for _ in 0..width {
+                    expanded.push(' ');
+                }
+                column += width;
+            }
+            '\n' => {
+                expanded.push(c);
+                column = 0;
+            }
+            _ => {
+                expanded.push(c);
+                column += 1;
+            }
+        }
+    }
+
+    expanded
+}
+
 /// Highlight a syntax node in a theme by calling `f` with ranges and their
 /// styles.
 fn highlight_themed<F>(
@@ -346,6 +783,9 @@ fn to_syn(RgbaColor { r, g, b, a }: RgbaColor) -> synt::Color {
 static SYNTAXES: Lazy<syntect::parsing::SyntaxSet> =
     Lazy::new(|| syntect::dumps::from_binary(include_bytes!("../../assets/syntect.bin")));
 
+/// The background of highlighted lines.
+const HIGHLIGHT: Color = Color::Rgba(RgbaColor::new(0xFF, 0xF3, 0xA8, 0xFF));
+
 /// The default theme used for syntax highlighting.
 pub static THEME: Lazy<synt::Theme> = Lazy::new(|| synt::Theme {
     name: Some("Typst Light".into()),
@@ -4,6 +4,7 @@ use std::str::FromStr;
 
 use az::SaturatingAs;
 use rustybuzz::{Feature, Tag, UnicodeBuffer};
+use typst::diag::warning;
 use typst::font::{Font, FontStyle, FontVariant};
 use typst::util::SliceExt;
 use unicode_script::{Script, UnicodeScript};
@@ -55,6 +56,9 @@ pub struct ShapedGlyph {
     pub y_offset: Em,
     /// The adjustability of the glyph.
     pub adjustability: Adjustability,
+    /// The factor by which the glyph is scaled relative to the text size.
+    /// This is only different from one for synthesized small capitals.
+    pub scale: f64,
     /// The byte range of this glyph's cluster in the full paragraph. A cluster
     /// is a sequence of one or multiple glyphs that cannot be separated and
     /// must always be treated as a union.
@@ -100,9 +104,7 @@ impl ShapedGlyph {
     }
 
     pub fn is_cjk_script(&self) -> bool {
-        use Script::*;
-        // U+30FC: Katakana-Hiragana Prolonged Sound Mark
-        matches!(self.c.script(), Hiragana | Katakana | Han) || self.c == '\u{30FC}'
+        is_cjk_char(self.c)
     }
 
     pub fn is_cjk_punctuation(&self) -> bool {
@@ -236,8 +238,10 @@ impl<'a> ShapedText<'a> {
         let decos = TextElem::deco_in(self.styles);
         let fill = TextElem::fill_in(self.styles);
 
-        for ((font, y_offset), group) in
-            self.glyphs.as_ref().group_by_key(|g| (g.font.clone(), g.y_offset))
+        for ((font, y_offset, scale), group) in self
+            .glyphs
+            .as_ref()
+            .group_by_key(|g| (g.font.clone(), g.y_offset, g.scale))
         {
             let mut range = group[0].range.clone();
             for glyph in group {
@@ -271,12 +275,15 @@ impl<'a> ShapedText<'a> {
                     frame.size_mut().x += justification_left.at(self.size)
                         + justification_right.at(self.size);
 
+                    // The glyph's metrics are relative to the text size, but
+                    // the text item is scaled for synthesized small caps.
                     Glyph {
                         id: glyph.glyph_id,
-                        x_advance: glyph.x_advance
+                        x_advance: (glyph.x_advance
                             + justification_left
-                            + justification_right,
-                        x_offset: glyph.x_offset + justification_left,
+                            + justification_right)
+                            / scale,
+                        x_offset: (glyph.x_offset + justification_left) / scale,
                         range: (glyph.range.start - range.start).saturating_as()
                             ..(glyph.range.end - range.start).saturating_as(),
                         span: glyph.span,
@@ -286,18 +293,22 @@ impl<'a> ShapedText<'a> {
 
             let item = TextItem {
                 font,
-                size: self.size,
+                size: self.size * scale,
                 lang,
                 fill: fill.clone(),
                 text: self.text[range.start - self.base..range.end - self.base].into(),
                 glyphs,
             };
 
-            let layer = frame.layer();
             let width = item.width();
 
-            // Apply line decorations.
-            for deco in &decos {
+            // Apply line decorations, those in the background first.
+            for deco in decos.iter().filter(|deco| deco.background) {
+                decorate(&mut frame, deco, &item, shift, pos, width);
+            }
+
+            let layer = frame.layer();
+            for deco in decos.iter().filter(|deco| !deco.background) {
                 decorate(&mut frame, deco, &item, shift, pos, width);
             }
 
@@ -446,6 +457,7 @@ impl<'a> ShapedText<'a> {
                 x_offset: Em::zero(),
                 y_offset: Em::zero(),
                 adjustability: Adjustability::default(),
+                scale: 1.0,
                 range,
                 safe_to_break: true,
                 c: '-',
@@ -520,6 +532,70 @@ impl Debug for ShapedText<'_> {
     }
 }
 
+/// Warn about the characters in shaped text which are rendered as tofus
+/// because no font in the book contains them.
+pub fn warn_missing_glyphs(vt: &mut Vt, shaped: &ShapedText) {
+    let world = vt.world;
+    let book = world.book();
+    let mut seen = vec![];
+    for glyph in shaped.glyphs.iter() {
+        let span = glyph.span.0;
+        if glyph.glyph_id != 0 || span.is_detached() || seen.contains(&glyph.c) {
+            continue;
+        }
+
+        seen.push(glyph.c);
+        let covered = book
+            .families()
+            .flat_map(|(_, infos)| infos)
+            .any(|info| info.coverage.contains(glyph.c as u32));
+        if covered {
+            continue;
+        }
+
+        let families = TextElem::font_in(shaped.styles)
+            .into_iter()
+            .map(|family| eco_format!("\"{}\"", family.as_str()))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        vt.tracer.warn_once(
+            warning!(
+                span,
+                "no font contains the character U+{:04X} (tried {})",
+                glyph.c as u32,
+                families,
+            )
+            .with_hints(["try installing a font that contains this character".into()]),
+        );
+    }
+}
+
+/// Warn about small capitals which are synthesized because the font does not
+/// support them.
+pub fn warn_synthesized_smallcaps(vt: &mut Vt, shaped: &ShapedText) {
+    let Some(glyph) = shaped.glyphs.iter().find(|glyph| glyph.scale != 1.0) else {
+        return;
+    };
+
+    let span = glyph.span.0;
+    if span.is_detached() {
+        return;
+    }
+
+    vt.tracer.warn_once(
+        warning!(
+            span,
+            "font \"{}\" does not support small capitals",
+            glyph.font.info().family,
+        )
+        .with_hints([
+            "small capitals are synthesized by scaling down uppercase letters".into(),
+            "try using a font that supports the `smcp` feature".into(),
+        ]),
+    );
+}
+
 /// Holds shaping results and metadata common to all shaped segments.
 struct ShapingContext<'a, 'v> {
     vt: &'a Vt<'v>,
@@ -567,6 +643,10 @@ pub fn shape<'a>(
     track_and_space(&mut ctx);
     calculate_adjustability(&mut ctx, lang, region);
 
+    if TextElem::cjk_latin_spacing_in(styles).is_auto() && dir.is_positive() {
+        add_cjk_latin_spacing(&mut ctx.glyphs);
+    }
+
     #[cfg(debug_assertions)]
     assert_all_glyphs_in_range(&ctx.glyphs, text, base..(base + text.len()));
     #[cfg(debug_assertions)]
@@ -626,9 +706,21 @@ fn shape_segment(
 
     ctx.used.push(font.clone());
 
-    // Fill the buffer with our text.
+    // Synthesize small capitals if the font doesn't have real ones.
+    let synthesize = TextElem::smallcaps_in(ctx.styles) && !has_smallcaps(&font);
+
+    // Fill the buffer with our text. For synthesized small caps, lowercase
+    // letters are replaced by their uppercase forms, but keep their clusters.
     let mut buffer = UnicodeBuffer::new();
-    buffer.push_str(text);
+    if synthesize {
+        for (i, c) in text.char_indices() {
+            for upper in c.to_uppercase() {
+                buffer.add(upper, i as u32);
+            }
+        }
+    } else {
+        buffer.push_str(text);
+    }
     buffer.set_language(language(ctx.styles));
     buffer.set_direction(match ctx.dir {
         Dir::LTR => rustybuzz::Direction::LeftToRight,
@@ -658,17 +750,22 @@ fn shape_segment(
                     .and_then(|last| infos.get(last))
                     .map_or(text.len(), |info| info.cluster as usize);
 
+            let c = text[cluster..].chars().next().unwrap();
+            let scale =
+                if synthesize && c.is_lowercase() { smallcaps_scale(&font) } else { 1.0 };
+
             ctx.glyphs.push(ShapedGlyph {
                 font: font.clone(),
                 glyph_id: info.glyph_id as u16,
                 // TODO: Don't ignore y_advance.
-                x_advance: font.to_em(pos[i].x_advance),
-                x_offset: font.to_em(pos[i].x_offset),
-                y_offset: font.to_em(pos[i].y_offset),
+                x_advance: font.to_em(pos[i].x_advance) * scale,
+                x_offset: font.to_em(pos[i].x_offset) * scale,
+                y_offset: font.to_em(pos[i].y_offset) * scale,
                 adjustability: Adjustability::default(),
+                scale,
                 range: start..end,
                 safe_to_break: !info.unsafe_to_break(),
-                c: text[cluster..].chars().next().unwrap(),
+                c,
                 span: ctx.spans.span_at(start),
             });
         } else {
@@ -731,6 +828,7 @@ fn shape_tofus(ctx: &mut ShapingContext, base: usize, text: &str, font: Font) {
             x_offset: Em::zero(),
             y_offset: Em::zero(),
             adjustability: Adjustability::default(),
+            scale: 1.0,
             range: start..end,
             safe_to_break: true,
             c,
@@ -747,6 +845,7 @@ fn shape_tofus(ctx: &mut ShapingContext, base: usize, text: &str, font: Font) {
 /// Apply tracking and spacing to the shaped glyphs.
 fn track_and_space(ctx: &mut ShapingContext) {
     let tracking = Em::from_length(TextElem::tracking_in(ctx.styles), ctx.size);
+    let track_punctuation = TextElem::track_punctuation_in(ctx.styles);
     let spacing =
         TextElem::spacing_in(ctx.styles).map(|abs| Em::from_length(abs, ctx.size));
 
@@ -761,15 +860,44 @@ fn track_and_space(ctx: &mut ShapingContext) {
             glyph.x_advance = spacing.relative_to(glyph.x_advance);
         }
 
-        if glyphs
-            .peek()
-            .map_or(false, |next| glyph.range.start != next.range.start)
-        {
+        if glyphs.peek().map_or(false, |next| {
+            glyph.range.start != next.range.start
+                && (track_punctuation || !is_trailing_punctuation(next.c))
+        }) {
             glyph.x_advance += tracking;
         }
     }
 }
 
+/// Whether the character is punctuation which directly follows a word.
+fn is_trailing_punctuation(c: char) -> bool {
+    matches!(
+        c,
+        '.' | ',' | ':' | ';' | '!' | '?' | '…' | ')' | ']' | '}' | '”' | '»' | '›'
+    )
+}
+
+/// Whether the font has real small capitals.
+fn has_smallcaps(font: &Font) -> bool {
+    font.ttf()
+        .tables()
+        .gsub
+        .map_or(false, |gsub| gsub.features.find(Tag::from_bytes(b"smcp")).is_some())
+}
+
+/// The factor by which uppercase letters are scaled to synthesize small
+/// capitals, chosen such that they are as tall as the font's lowercase
+/// letters.
+fn smallcaps_scale(font: &Font) -> f64 {
+    let metrics = font.metrics();
+    let (x_height, cap_height) = (metrics.x_height.get(), metrics.cap_height.get());
+    if x_height > 0.0 && cap_height > x_height {
+        x_height / cap_height
+    } else {
+        0.75
+    }
+}
+
 pub fn is_gb_style(lang: Lang, region: Option<Region>) -> bool {
     // Most CJK variants, including zh-CN, ja-JP, zh-SG, zh-MY use GB-style punctuation,
     // while zh-HK and zh-TW use alternative style. We default to use GB-style.
@@ -810,6 +938,39 @@ fn calculate_adjustability(ctx: &mut ShapingContext, lang: Lang, region: Option<
     }
 }
 
+/// Add spacing between neighbouring CJK and Latin glyphs, according to the
+/// Requirements for Chinese Text Layout, section 3.2.2 "Mixed Text Composition
+/// in Horizontal Writing Mode".
+fn add_cjk_latin_spacing(glyphs: &mut [ShapedGlyph]) {
+    let spacing = Em::new(0.25);
+    for i in 1..glyphs.len() {
+        let (prev, next) = (&glyphs[i - 1], &glyphs[i]);
+        if prev.range == next.range {
+            continue;
+        }
+
+        if prev.is_cjk_script() && is_latin_char(next.c) {
+            glyphs[i - 1].x_advance += spacing;
+        } else if is_latin_char(prev.c) && next.is_cjk_script() {
+            let glyph = &mut glyphs[i];
+            glyph.x_advance += spacing;
+            glyph.x_offset += spacing;
+        }
+    }
+}
+
+/// Whether the character is a Chinese or Japanese character.
+pub fn is_cjk_char(c: char) -> bool {
+    use Script::*;
+    // U+30FC: Katakana-Hiragana Prolonged Sound Mark
+    matches!(c.script(), Hiragana | Katakana | Han) || c == '\u{30FC}'
+}
+
+/// Whether the character is a Latin letter or a digit.
+pub fn is_latin_char(c: char) -> bool {
+    c.is_ascii_digit() || (c.is_alphabetic() && c.script() == Script::Latin)
+}
+
 /// Difference between non-breaking and normal space.
 fn nbsp_delta(font: &Font) -> Option<Em> {
     let space = font.ttf().glyph_index(' ')?.0;
@@ -1,4 +1,7 @@
-use super::{variant, SpaceElem, TextElem, TextSize};
+use rustybuzz::{Feature, Tag, UnicodeBuffer};
+use typst::font::Font;
+
+use super::{variant, FontFeatures, SpaceElem, TextElem, TextSize};
 use crate::prelude::*;
 
 /// Renders text in subscript.
@@ -16,9 +19,11 @@ use crate::prelude::*;
 pub struct SubElem {
     /// Whether to prefer the dedicated subscript characters of the font.
     ///
-    /// If this is enabled, Typst first tries to transform the text to subscript
-    /// codepoints. If that fails, it falls back to rendering lowered and shrunk
-    /// normal letters.
+    /// If this is enabled, Typst first tries to use the font's `subs` OpenType
+    /// feature and then to transform the text to subscript codepoints. If
+    /// the font lacks a dedicated glyph for any of the characters, it falls
+    /// back to rendering lowered and shrunk normal letters for the whole text,
+    /// so that real and synthesized subscripts are never mixed.
     ///
     /// ```example
     /// N#sub(typographic: true)[1]
@@ -28,13 +33,13 @@ pub struct SubElem {
     pub typographic: bool,
 
     /// The baseline shift for synthetic subscripts. Does not apply if
-    /// `typographic` is true and the font has subscript codepoints for the
+    /// `typographic` is true and the font has subscript glyphs for the
     /// given `body`.
     #[default(Em::new(0.2).into())]
     pub baseline: Length,
 
     /// The font size for synthetic subscripts. Does not apply if
-    /// `typographic` is true and the font has subscript codepoints for the
+    /// `typographic` is true and the font has subscript glyphs for the
     /// given `body`.
     #[default(TextSize(Em::new(0.6).into()))]
     pub size: TextSize,
@@ -50,11 +55,7 @@ impl Show for SubElem {
         let body = self.body();
         let mut transformed = None;
         if self.typographic(styles) {
-            if let Some(text) = search_text(&body, true) {
-                if is_shapable(vt, &text, styles) {
-                    transformed = Some(TextElem::packed(text));
-                }
-            }
+            transformed = typographic(vt, &body, styles, true);
         };
 
         Ok(transformed.unwrap_or_else(|| {
@@ -79,9 +80,11 @@ impl Show for SubElem {
 pub struct SuperElem {
     /// Whether to prefer the dedicated superscript characters of the font.
     ///
-    /// If this is enabled, Typst first tries to transform the text to
-    /// superscript codepoints. If that fails, it falls back to rendering
-    /// raised and shrunk normal letters.
+    /// If this is enabled, Typst first tries to use the font's `sups` OpenType
+    /// feature and then to transform the text to superscript codepoints. If
+    /// the font lacks a dedicated glyph for any of the characters, it falls
+    /// back to rendering raised and shrunk normal letters for the whole text,
+    /// so that real and synthesized superscripts are never mixed.
     ///
     /// ```example
     /// N#super(typographic: true)[1]
@@ -91,13 +94,13 @@ pub struct SuperElem {
     pub typographic: bool,
 
     /// The baseline shift for synthetic superscripts. Does not apply if
-    /// `typographic` is true and the font has superscript codepoints for the
+    /// `typographic` is true and the font has superscript glyphs for the
     /// given `body`.
     #[default(Em::new(-0.5).into())]
     pub baseline: Length,
 
     /// The font size for synthetic superscripts. Does not apply if
-    /// `typographic` is true and the font has superscript codepoints for the
+    /// `typographic` is true and the font has superscript glyphs for the
     /// given `body`.
     #[default(TextSize(Em::new(0.6).into()))]
     pub size: TextSize,
@@ -113,11 +116,7 @@ impl Show for SuperElem {
         let body = self.body();
         let mut transformed = None;
         if self.typographic(styles) {
-            if let Some(text) = search_text(&body, false) {
-                if is_shapable(vt, &text, styles) {
-                    transformed = Some(TextElem::packed(text));
-                }
-            }
+            transformed = typographic(vt, &body, styles, false);
         };
 
         Ok(transformed.unwrap_or_else(|| {
@@ -148,21 +147,81 @@ fn search_text(content: &Content, sub: bool) -> Option<EcoString> {
     }
 }
 
-/// Checks whether the first retrievable family contains all code points of the
-/// given string.
-fn is_shapable(vt: &Vt, text: &str, styles: StyleChain) -> bool {
+/// Try to display `content` with the dedicated sub- or superscript glyphs of
+/// the font.
+///
+/// The decision is made for the whole content instead of for each glyph:
+/// If a single character lacks a dedicated glyph, all of it is synthesized.
+fn typographic(
+    vt: &Vt,
+    content: &Content,
+    styles: StyleChain,
+    sub: bool,
+) -> Option<Content> {
+    let font = first_font(vt, styles)?;
+    let tag = Tag::from_bytes(if sub { b"subs" } else { b"sups" });
+    if collect_text(content).map_or(false, |text| has_feature_glyphs(&font, &text, tag)) {
+        let features = FontFeatures(vec![(tag, 1)]);
+        return Some(content.clone().styled(TextElem::set_features(features)));
+    }
+
+    let text = search_text(content, sub)?;
+    text.chars()
+        .all(|c| font.ttf().glyph_index(c).is_some())
+        .then(|| TextElem::packed(text))
+}
+
+/// Collect the plain text of `content` if it only consists of `Text`, `Space`,
+/// and `Empty` leafs, possibly styled.
+fn collect_text(content: &Content) -> Option<EcoString> {
+    if content.is::<SpaceElem>() {
+        Some(' '.into())
+    } else if let Some(elem) = content.to::<TextElem>() {
+        Some(elem.text())
+    } else if let Some(children) = content.to_sequence() {
+        let mut full = EcoString::new();
+        for item in children {
+            full.push_str(&collect_text(item)?);
+        }
+        Some(full)
+    } else if let Some((child, _)) = content.to_styled() {
+        collect_text(child)
+    } else {
+        None
+    }
+}
+
+/// Checks whether the OpenType feature with the given tag substitutes a
+/// dedicated glyph for every non-whitespace character of the text.
+fn has_feature_glyphs(font: &Font, text: &str, tag: Tag) -> bool {
+    let shape = |c: char, features: &[Feature]| {
+        let mut buffer = UnicodeBuffer::new();
+        buffer.push_str(c.encode_utf8(&mut [0; 4]));
+        let output = rustybuzz::shape(font.rusty(), features, buffer);
+        output
+            .glyph_infos()
+            .iter()
+            .map(|info| info.glyph_id)
+            .collect::<Vec<_>>()
+    };
+
+    let feature = Feature::new(tag, 1, ..);
+    let mut chars = text.chars().filter(|c| !c.is_whitespace()).peekable();
+    chars.peek().is_some()
+        && chars.all(|c| {
+            font.ttf().glyph_index(c).is_some() && shape(c, &[]) != shape(c, &[feature])
+        })
+}
+
+/// The first font of the current families that is available.
+fn first_font(vt: &Vt, styles: StyleChain) -> Option<Font> {
     let world = vt.world;
-    for family in TextElem::font_in(styles) {
-        if let Some(font) = world
+    TextElem::font_in(styles).into_iter().find_map(|family| {
+        world
             .book()
             .select(family.as_str(), variant(styles))
             .and_then(|id| world.font(id))
-        {
-            return text.chars().all(|c| font.ttf().glyph_index(c).is_some());
-        }
-    }
-
-    false
+    })
 }
 
 /// Convert a string to sub- or superscript codepoints if all characters
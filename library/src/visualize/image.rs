@@ -10,7 +10,9 @@ use crate::text::families;
 
 /// A raster or vector graphic.
 ///
-/// Supported formats are PNG, JPEG, GIF and SVG.
+/// Supported formats are PNG, JPEG, GIF and SVG. Unless a width or height is
+/// given, raster images are sized according to the resolution stored in their
+/// metadata, falling back to 72 dots per inch.
 ///
 /// _Note:_ Work on SVG export is ongoing and there might be visual inaccuracies
 /// in the resulting PDF. Make sure to double-check embedded SVG images. If you
@@ -33,13 +35,22 @@ use crate::text::families;
 /// Category: visualize
 #[element(Layout, LocalName, Figurable)]
 pub struct ImageElem {
-    /// Path to an image file.
+    /// Path to an image file or the raw [bytes]($type/bytes) of an image.
+    ///
+    /// When bytes are given, the format of the image is detected from its
+    /// data and the path is empty.
     #[required]
     #[parse(
-        let Spanned { v: path, span } =
-            args.expect::<Spanned<EcoString>>("path to image file")?;
-        let id = vm.location().join(&path).at(span)?;
-        let data = vm.world().file(id).at(span)?;
+        let Spanned { v: source, span } =
+            args.expect::<Spanned<ImageSource>>("path to image file or bytes")?;
+        let (path, data) = match source {
+            ImageSource::Path(path) => {
+                let id = vm.location().join(&path).at(span)?;
+                let data = vm.world().file(id).at(span)?;
+                (path, data)
+            }
+            ImageSource::Bytes(data) => (EcoString::new(), data),
+        };
         path
     )]
     pub path: EcoString,
@@ -60,8 +71,36 @@ pub struct ImageElem {
     pub alt: Option<EcoString>,
 
     /// How the image should adjust itself to a given area.
+    ///
+    /// With `{"cover"}`, the parts of the image that don't fit into the area
+    /// are cut off.
+    ///
+    /// ```example
+    /// #set image(width: 60pt, height: 30pt)
+    /// #image("tiger.jpg", fit: "cover")
+    /// #image("tiger.jpg", fit: "contain")
+    /// #image("tiger.jpg", fit: "stretch")
+    /// ```
     #[default(ImageFit::Cover)]
     pub fit: ImageFit,
+
+    /// The format of the image. Can be `{"png"}`, `{"jpg"}`, `{"gif"}`, or
+    /// `{"svg"}`.
+    ///
+    /// By default, the format is determined by the file extension and, if
+    /// that is unknown, by the image's data. Set this if the extension doesn't
+    /// match the actual format of the file.
+    ///
+    /// ```example
+    /// #image("tiger.jpg", format: "jpg", width: 40pt)
+    /// ```
+    pub format: Smart<ImageFormat>,
+
+    /// Which page of the image to display, starting at one. For animated
+    /// GIFs, this selects a frame of the animation. Other formats only have a
+    /// single page.
+    #[default(NonZeroUsize::ONE)]
+    pub page: NonZeroUsize,
 }
 
 impl Layout for ImageElem {
@@ -72,23 +111,32 @@ impl Layout for ImageElem {
         styles: StyleChain,
         regions: Regions,
     ) -> SourceResult<Fragment> {
-        let ext = Path::new(self.path().as_str())
-            .extension()
-            .and_then(OsStr::to_str)
-            .unwrap_or_default()
-            .to_lowercase();
-
-        let format = match ext.as_str() {
-            "png" => ImageFormat::Raster(RasterFormat::Png),
-            "jpg" | "jpeg" => ImageFormat::Raster(RasterFormat::Jpg),
-            "gif" => ImageFormat::Raster(RasterFormat::Gif),
-            "svg" | "svgz" => ImageFormat::Vector(VectorFormat::Svg),
-            _ => bail!(self.span(), "unknown image format"),
+        let format = match self.format(styles) {
+            Smart::Custom(format) => format,
+            Smart::Auto => {
+                let ext = Path::new(self.path().as_str())
+                    .extension()
+                    .and_then(OsStr::to_str)
+                    .unwrap_or_default()
+                    .to_lowercase();
+
+                match ext.as_str() {
+                    "png" => ImageFormat::Raster(RasterFormat::Png),
+                    "jpg" | "jpeg" => ImageFormat::Raster(RasterFormat::Jpg),
+                    "gif" => ImageFormat::Raster(RasterFormat::Gif),
+                    "svg" | "svgz" => ImageFormat::Vector(VectorFormat::Svg),
+                    _ => match detect_format(&self.data()) {
+                        Some(format) => format,
+                        None => bail!(self.span(), "unknown image format"),
+                    },
+                }
+            }
         };
 
         let image = Image::with_fonts(
             self.data(),
             format,
+            self.page(styles).get() - 1,
             vt.world,
             families(styles).next().as_ref().map(|f| f.as_str()),
             self.alt(styles),
@@ -108,6 +156,7 @@ impl Layout for ImageElem {
         let pxw = image.width() as f64;
         let pxh = image.height() as f64;
         let px_ratio = pxw / pxh;
+        let dpi = image.dpi().unwrap_or(72.0);
         let wide = px_ratio > region_ratio;
 
         // The space into which the image will be placed according to its fit.
@@ -118,7 +167,7 @@ impl Layout for ImageElem {
         } else if region.y.is_finite() {
             Size::new(region.x.min(region.y * px_ratio), region.y)
         } else {
-            Size::new(Abs::pt(pxw), Abs::pt(pxh))
+            Size::new(Abs::inches(pxw / dpi), Abs::inches(pxh / dpi))
         };
 
         // Compute the actual size of the fitted image.
@@ -143,7 +192,7 @@ impl Layout for ImageElem {
 
         // Create a clipping group if only part of the image should be visible.
         if fit == ImageFit::Cover && !target.fits(fitted) {
-            frame.clip();
+            frame.clip(Path::rect(frame.size()));
         }
 
         // Apply metadata.
@@ -184,6 +233,37 @@ impl LocalName for ImageElem {
 
 impl Figurable for ImageElem {}
 
+/// Determine the format of an image from its data.
+fn detect_format(data: &[u8]) -> Option<ImageFormat> {
+    Some(if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        ImageFormat::Raster(RasterFormat::Png)
+    } else if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        ImageFormat::Raster(RasterFormat::Jpg)
+    } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        ImageFormat::Raster(RasterFormat::Gif)
+    } else if data.starts_with(&[0x1F, 0x8B])
+        || std::str::from_utf8(data).map_or(false, |text| text.contains("<svg"))
+    {
+        ImageFormat::Vector(VectorFormat::Svg)
+    } else {
+        return None;
+    })
+}
+
+/// The source of an image: A path to an image file or its raw bytes.
+pub enum ImageSource {
+    /// A path to an image file.
+    Path(EcoString),
+    /// The raw data of an image.
+    Bytes(Bytes),
+}
+
+cast! {
+    ImageSource,
+    v: EcoString => Self::Path(v),
+    v: Bytes => Self::Bytes(v),
+}
+
 /// How an image should adjust itself to a given area.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
 pub enum ImageFit {
@@ -1,4 +1,3 @@
-use kurbo::{CubicBez, ParamCurveExtrema};
 use typst::eval::Reflect;
 
 use crate::prelude::*;
@@ -7,6 +6,9 @@ use PathVertex::{AllControlPoints, MirroredControlPoint, Vertex};
 
 /// A path through a list of points, connected by Bezier curves.
 ///
+/// Two vertices without control points between them are connected by a
+/// straight line.
+///
 /// ## Example { #example }
 /// ```example
 /// #path(
@@ -25,11 +27,24 @@ use PathVertex::{AllControlPoints, MirroredControlPoint, Vertex};
 pub struct PathElem {
     /// How to fill the path. See the
     /// [rectangle's documentation]($func/rect.fill) for more details.
-    ///
-    /// Currently all paths are filled according to the
-    /// [non-zero winding rule](https://en.wikipedia.org/wiki/Nonzero-rule).
     pub fill: Option<Paint>,
 
+    /// Which parts of a self-intersecting path to fill.
+    ///
+    /// - `{"non-zero"}`: Fill everything the path winds around, following the
+    ///   [non-zero winding rule](https://en.wikipedia.org/wiki/Nonzero-rule).
+    /// - `{"even-odd"}`: Leave out regions that are enclosed an even number of
+    ///   times, following the
+    ///   [even-odd rule](https://en.wikipedia.org/wiki/Even-odd_rule).
+    ///
+    /// ```example
+    /// #let star = ((20pt, 0pt), (32pt, 36pt), (1pt, 13pt), (39pt, 13pt), (8pt, 36pt))
+    /// #path(fill: blue, closed: true, ..star)
+    /// #path(fill: blue, closed: true, fill-rule: "even-odd", ..star)
+    /// ```
+    #[default(FillRule::NonZero)]
+    pub fill_rule: FillRule,
+
     /// How to stroke the path. This can be:
     ///
     /// See the [line's documentation]($func/line.stroke) for more details. Can
@@ -83,9 +98,8 @@ impl Layout for PathElem {
         let vertices: Vec<PathVertex> = self.vertices();
         let points: Vec<Point> = vertices.iter().map(|c| resolve(c.vertex())).collect();
 
-        let mut size = Size::zero();
         if points.is_empty() {
-            return Ok(Fragment::frame(Frame::new(size)));
+            return Ok(Fragment::frame(Frame::new(Size::zero())));
         }
 
         // Only create a path if there are more than zero points.
@@ -93,26 +107,18 @@ impl Layout for PathElem {
         let mut path = Path::new();
         path.move_to(points[0]);
 
-        let mut add_cubic =
-            |from_point: Point, to_point: Point, from: PathVertex, to: PathVertex| {
-                let from_control_point = resolve(from.control_point_from()) + from_point;
-                let to_control_point = resolve(to.control_point_to()) + to_point;
-                path.cubic_to(from_control_point, to_control_point, to_point);
-
-                let p0 = kurbo::Point::new(from_point.x.to_raw(), from_point.y.to_raw());
-                let p1 = kurbo::Point::new(
-                    from_control_point.x.to_raw(),
-                    from_control_point.y.to_raw(),
-                );
-                let p2 = kurbo::Point::new(
-                    to_control_point.x.to_raw(),
-                    to_control_point.y.to_raw(),
-                );
-                let p3 = kurbo::Point::new(to_point.x.to_raw(), to_point.y.to_raw());
-                let extrema = CubicBez::new(p0, p1, p2, p3).bounding_box();
-                size.x.set_max(Abs::raw(extrema.x1));
-                size.y.set_max(Abs::raw(extrema.y1));
-            };
+        let mut add_segment = |from_point: Point,
+                               to_point: Point,
+                               from: PathVertex,
+                               to: PathVertex| {
+            let from_control = resolve(from.control_point_from());
+            let to_control = resolve(to.control_point_to());
+            if from_control == Point::zero() && to_control == Point::zero() {
+                path.line_to(to_point);
+            } else {
+                path.cubic_to(from_control + from_point, to_control + to_point, to_point);
+            }
+        };
 
         for (vertex_window, point_window) in vertices.windows(2).zip(points.windows(2)) {
             let from = vertex_window[0];
@@ -120,7 +126,7 @@ impl Layout for PathElem {
             let from_point = point_window[0];
             let to_point = point_window[1];
 
-            add_cubic(from_point, to_point, from, to);
+            add_segment(from_point, to_point, from, to);
         }
 
         if self.closed(styles) {
@@ -129,7 +135,7 @@ impl Layout for PathElem {
             let from_point = *points.last().unwrap();
             let to_point = points[0];
 
-            add_cubic(from_point, to_point, from, to);
+            add_segment(from_point, to_point, from, to);
             path.close_path();
         }
 
@@ -141,14 +147,34 @@ impl Layout for PathElem {
             Smart::Custom(stroke) => stroke.map(PartialStroke::unwrap_or_default),
         };
 
-        let mut frame = Frame::new(size);
-        let shape = Shape { geometry: Geometry::Path(path), stroke, fill };
-        frame.push(Point::zero(), FrameItem::Shape(shape, self.span()));
+        let fill_rule = self.fill_rule(styles);
+        let shape = Shape {
+            geometry: Geometry::Path(path),
+            fill,
+            fill_rule,
+            stroke,
+        };
+        let frame = shape_frame(shape, self.span());
 
         Ok(Fragment::frame(frame))
     }
 }
 
+/// Create a frame for a path-like shape.
+///
+/// The frame contains both the frame's origin, to which the shape's points
+/// are relative, and the shape including the extrema of its curves and its
+/// stroke. If the shape extends to the top or left of the origin, it is
+/// shifted accordingly.
+pub(super) fn shape_frame(shape: Shape, span: Span) -> Frame {
+    let (origin, size) = shape.bbox();
+    let min = origin.min(Point::zero());
+    let max = (origin + size.to_point()).max(Point::zero());
+    let mut frame = Frame::new((max - min).to_size());
+    frame.push(-min, FrameItem::Shape(shape, span));
+    frame
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum PathVertex {
     Vertex(Axes<Rel<Length>>),
@@ -1,3 +1,6 @@
+use std::f64::consts::PI;
+
+use super::path::shape_frame;
 use crate::prelude::*;
 
 /// A closed polygon.
@@ -19,14 +22,20 @@ use crate::prelude::*;
 /// Display: Polygon
 /// Category: visualize
 #[element(Layout)]
+#[scope(
+    scope.define("regular", polygon_regular_func());
+    scope
+)]
 pub struct PolygonElem {
     /// How to fill the polygon. See the
     /// [rectangle's documentation]($func/rect.fill) for more details.
-    ///
-    /// Currently all polygons are filled according to the
-    /// [non-zero winding rule](https://en.wikipedia.org/wiki/Nonzero-rule).
     pub fill: Option<Paint>,
 
+    /// Which parts of a self-intersecting polygon to fill. See the
+    /// [path's documentation]($func/path.fill-rule) for more details.
+    #[default(FillRule::NonZero)]
+    pub fill_rule: FillRule,
+
     /// How to stroke the polygon. This can be:
     ///
     /// See the [line's documentation]($func/line.stroke) for more details. Can
@@ -61,12 +70,9 @@ impl Layout for PolygonElem {
             })
             .collect();
 
-        let size = points.iter().fold(Point::zero(), |max, c| c.max(max)).to_size();
-        let mut frame = Frame::new(size);
-
         // Only create a path if there are more than zero points.
         if points.is_empty() {
-            return Ok(Fragment::frame(frame));
+            return Ok(Fragment::frame(Frame::new(Size::zero())));
         }
 
         // Prepare fill and stroke.
@@ -85,9 +91,89 @@ impl Layout for PolygonElem {
         }
         path.close_path();
 
-        let shape = Shape { geometry: Geometry::Path(path), stroke, fill };
-        frame.push(Point::zero(), FrameItem::Shape(shape, self.span()));
+        let fill_rule = self.fill_rule(styles);
+        let shape = Shape {
+            geometry: Geometry::Path(path),
+            fill,
+            fill_rule,
+            stroke,
+        };
+        Ok(Fragment::frame(shape_frame(shape, self.span())))
+    }
+}
 
-        Ok(Fragment::frame(frame))
+/// Creates a regular polygon.
+///
+/// The vertices lie evenly spaced on a circle. The polygon is oriented such
+/// that its bottom edge is horizontal.
+///
+/// ## Example { #example }
+/// ```example
+/// #polygon.regular(
+///   fill: blue.lighten(80%),
+///   stroke: blue,
+///   size: 30pt,
+///   vertices: 3,
+/// )
+/// #polygon.regular(size: 30pt, vertices: 6)
+/// ```
+///
+/// Display: Regular Polygon
+/// Category: visualize
+#[func]
+pub fn polygon_regular(
+    /// How to fill the polygon. See the
+    /// [polygon's documentation]($func/polygon.fill) for more details.
+    #[external]
+    #[named]
+    #[default]
+    fill: Option<Paint>,
+    /// How to stroke the polygon. See the
+    /// [polygon's documentation]($func/polygon.stroke) for more details.
+    #[external]
+    #[named]
+    #[default]
+    stroke: Smart<Option<PartialStroke>>,
+    /// The diameter of the circle the vertices lie on.
+    #[named]
+    #[default(Em::one().into())]
+    size: Length,
+    /// The number of vertices. Must be at least three.
+    #[named]
+    #[default(Spanned::new(3, Span::detached()))]
+    vertices: Spanned<u64>,
+    /// The remaining arguments.
+    args: Args,
+) -> SourceResult<Content> {
+    let mut args = args;
+    let n = vertices.v;
+    if n < 3 {
+        bail!(vertices.span, "polygon must have at least 3 vertices");
     }
+
+    // Start at the angle that puts the last edge at the bottom.
+    let angles: Vec<f64> = (0..n)
+        .map(|i| PI / 2.0 + PI / n as f64 + 2.0 * PI * i as f64 / n as f64)
+        .collect();
+    let min_x = angles.iter().map(|a| a.cos()).fold(f64::INFINITY, f64::min);
+    let min_y = angles.iter().map(|a| a.sin()).fold(f64::INFINITY, f64::min);
+    let points = angles
+        .iter()
+        .map(|a| {
+            let x = size * ((a.cos() - min_x) / 2.0);
+            let y = size * ((a.sin() - min_y) / 2.0);
+            Axes::new(x.into(), y.into())
+        })
+        .collect();
+
+    let mut elem = PolygonElem::new(points);
+    if let Some(fill) = args.named("fill")? {
+        elem.push_fill(fill);
+    }
+    if let Some(stroke) = args.named("stroke")? {
+        elem.push_stroke(stroke);
+    }
+    args.finish()?;
+
+    Ok(elem.pack())
 }
@@ -522,7 +522,9 @@ fn create_set_impl(element: &Elem) -> TokenStream {
 
     quote! {
         impl ::typst::model::Set for #ident {
+            #[allow(unused_variables)]
             fn set(
+                vm: &mut ::typst::eval::Vm,
                 args: &mut ::typst::eval::Args,
             ) -> ::typst::diag::SourceResult<::typst::model::Styles> {
                 let mut styles = ::typst::model::Styles::new();
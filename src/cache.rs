@@ -0,0 +1,149 @@
+//! Statistics about and limits for memoized layout results.
+//!
+//! Evaluation and layout results are memoized by comemo across compilations.
+//! comemo itself only supports evicting results by age, so the layout entry
+//! points record how often they are called and how often they actually had
+//! to run. That gives hosts [statistics](CacheStats) about the last
+//! compilation and lets them [cap](set_frame_cap) the number of cached frames.
+//!
+//! comemo's caches are local to the thread a compilation runs on and so are
+//! the statistics and the frame cap: They describe and limit the cache of the
+//! calling thread only.
+
+use std::cell::Cell;
+
+thread_local! {
+    /// The number of layout calls and layout runs in the compilation that
+    /// last ran on this thread.
+    static CALLS: Cell<(usize, usize)> = Cell::new((0, 0));
+
+    /// The number of frames that were produced by layout runs on this thread
+    /// since its cached results were last evicted.
+    static FRAMES: Cell<usize> = Cell::new(0);
+
+    /// The maximum number of frames cached on this thread, or `usize::MAX` if
+    /// there is none.
+    static FRAME_CAP: Cell<usize> = Cell::new(usize::MAX);
+}
+
+/// Statistics about the cache usage of layout.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct CacheStats {
+    /// How often layout was served from the cache in the last compilation
+    /// on this thread.
+    pub hits: usize,
+    /// How often layout had to run in the last compilation on this thread.
+    pub misses: usize,
+    /// The number of frames that were produced by layout runs on this thread
+    /// since all of its cached results were last evicted.
+    ///
+    /// This is an upper bound of the number of cached frames: comemo doesn't
+    /// report which results an eviction by age removed.
+    pub frames: usize,
+}
+
+/// Retrieve the current statistics.
+pub fn stats() -> CacheStats {
+    let (calls, misses) = CALLS.with(Cell::get);
+    CacheStats {
+        hits: calls.saturating_sub(misses),
+        misses,
+        frames: FRAMES.with(Cell::get),
+    }
+}
+
+/// Limit the number of frames cached on this thread.
+///
+/// When more frames than this were produced since the last full eviction,
+/// [`evict`] evicts all cached results, regardless of their age. With `None`,
+/// which is the default, only the age is considered.
+pub fn set_frame_cap(cap: Option<usize>) {
+    FRAME_CAP.with(|frame_cap| frame_cap.set(cap.unwrap_or(usize::MAX)));
+}
+
+/// Evict cached results of this thread that were not used in the last
+/// `max_age` compilations, or all of them if the frame cap is exceeded.
+pub fn evict(max_age: usize) {
+    let exceeded = FRAMES.with(Cell::get) > FRAME_CAP.with(Cell::get);
+    let max_age = if exceeded { 0 } else { max_age };
+
+    comemo::evict(max_age);
+    if max_age == 0 {
+        FRAMES.with(|frames| frames.set(0));
+    }
+}
+
+/// Reset the hit and miss counts of this thread at the start of a
+/// compilation.
+pub(crate) fn reset() {
+    CALLS.with(|calls| calls.set((0, 0)));
+}
+
+/// Record a call to a memoized layout entry point.
+#[doc(hidden)]
+pub fn record_call() {
+    CALLS.with(|calls| {
+        let (count, misses) = calls.get();
+        calls.set((count + 1, misses));
+    });
+}
+
+/// Record that a memoized layout entry point had to run and produced
+/// `frames` frames.
+#[doc(hidden)]
+pub fn record_miss(frames: usize) {
+    CALLS.with(|calls| {
+        let (count, misses) = calls.get();
+        calls.set((count, misses + 1));
+    });
+    FRAMES.with(|count| count.set(count.get() + frames));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_stats() {
+        evict(0);
+        reset();
+        record_call();
+        record_miss(3);
+        record_call();
+        record_call();
+        assert_eq!(stats(), CacheStats { hits: 2, misses: 1, frames: 3 });
+
+        // A new compilation resets the calls, but not the cached frames.
+        reset();
+        assert_eq!(stats(), CacheStats { hits: 0, misses: 0, frames: 3 });
+    }
+
+    #[test]
+    fn test_cache_frame_cap() {
+        set_frame_cap(None);
+        evict(0);
+        record_miss(5);
+
+        // Evicting by age keeps the frames that may still be cached.
+        evict(10);
+        assert_eq!(stats().frames, 5);
+
+        // Below the cap, only the age is considered.
+        set_frame_cap(Some(5));
+        evict(10);
+        assert_eq!(stats().frames, 5);
+
+        // Exceeding the cap evicts everything regardless of age.
+        record_miss(1);
+        evict(10);
+        assert_eq!(stats().frames, 0);
+
+        // Without a cap, only a full eviction resets the frames.
+        set_frame_cap(None);
+        record_miss(7);
+        evict(10);
+        assert_eq!(stats().frames, 7);
+        evict(0);
+        assert_eq!(stats().frames, 0);
+    }
+}
@@ -2,13 +2,15 @@
 
 use std::fmt::{self, Display, Formatter};
 use std::io;
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::str::Utf8Error;
 use std::string::FromUtf8Error;
 
 use comemo::Tracked;
+use serde::Serialize;
 
-use crate::file::PackageSpec;
+use crate::package::PackageSpec;
 use crate::syntax::{Span, Spanned};
 use crate::World;
 
@@ -62,6 +64,22 @@ macro_rules! __error {
 
 #[doc(inline)]
 pub use crate::__error as error;
+
+/// Construct a [`SourceError`] with severity `Warning`.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __warning {
+    ($span:expr, $fmt:literal $(, $arg:expr)* $(,)?) => {
+        $crate::diag::SourceError::new(
+            $span,
+            $crate::diag::eco_format!($fmt, $($arg),*),
+        )
+        .with_severity($crate::diag::Severity::Warning)
+    };
+}
+
+#[doc(inline)]
+pub use crate::__warning as warning;
 #[doc(hidden)]
 pub use ecow::{eco_format, EcoString};
 
@@ -74,6 +92,10 @@ pub type SourceResult<T> = Result<T, Box<Vec<SourceError>>>;
 /// were detached.
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct SourceError {
+    /// Whether the diagnostic is an error or a warning.
+    pub severity: Severity,
+    /// Why the error occurred.
+    pub kind: ErrorKind,
     /// The span of the erroneous node in the source code.
     pub span: Span,
     /// A diagnostic message describing the problem.
@@ -89,6 +111,8 @@ impl SourceError {
     /// Create a new, bare error.
     pub fn new(span: Span, message: impl Into<EcoString>) -> Self {
         Self {
+            severity: Severity::Error,
+            kind: ErrorKind::Regular,
             span,
             trace: vec![],
             message: message.into(),
@@ -96,11 +120,124 @@ impl SourceError {
         }
     }
 
+    /// Create the error with which a [cancelled](crate::CancellationToken)
+    /// compilation is aborted.
+    pub fn cancelled(span: Span) -> Self {
+        Self {
+            kind: ErrorKind::Cancelled,
+            ..Self::new(span, "compilation was cancelled")
+        }
+    }
+
+    /// Changes the severity of the diagnostic.
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+
     /// Adds user-facing hints to the error.
     pub fn with_hints(mut self, hints: impl IntoIterator<Item = EcoString>) -> Self {
         self.hints.extend(hints);
         self
     }
+
+    /// Resolve the spans of the error to locations in their source files.
+    ///
+    /// Spans on their own are only meaningful together with the sources they
+    /// point into. The resolved error is self-contained and can thus be
+    /// serialized for consumption by external tools.
+    pub fn resolve(&self, world: &dyn World) -> ResolvedError {
+        ResolvedError {
+            severity: self.severity,
+            message: self.message.clone(),
+            location: ResolvedSpan::new(world, self.span),
+            trace: self
+                .trace
+                .iter()
+                .map(|point| ResolvedTracepoint {
+                    message: eco_format!("{}", point.v),
+                    location: ResolvedSpan::new(world, point.span),
+                })
+                .collect(),
+            hints: self.hints.clone(),
+        }
+    }
+}
+
+/// A [`SourceError`] whose spans were resolved to file locations.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize)]
+pub struct ResolvedError {
+    /// Whether the diagnostic is an error or a warning.
+    pub severity: Severity,
+    /// A diagnostic message describing the problem.
+    pub message: EcoString,
+    /// Where the problem occurred. `None` if the span was detached.
+    pub location: Option<ResolvedSpan>,
+    /// The trace of function calls leading to the error, innermost first.
+    pub trace: Vec<ResolvedTracepoint>,
+    /// Additonal hints to the user, indicating how this error could be avoided
+    /// or worked around.
+    pub hints: Vec<EcoString>,
+}
+
+/// A part of a [resolved error's](ResolvedError) trace.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize)]
+pub struct ResolvedTracepoint {
+    /// A message describing the tracepoint.
+    pub message: EcoString,
+    /// Where the tracepoint is located. `None` if the span was detached.
+    pub location: Option<ResolvedSpan>,
+}
+
+/// A span resolved to a range in a file.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize)]
+pub struct ResolvedSpan {
+    /// The file, including its package if it is part of one.
+    pub file: EcoString,
+    /// The byte range in the file.
+    pub range: Range<usize>,
+    /// The range in UTF-16 code units, as used by the Language Server
+    /// Protocol.
+    pub utf16_range: Range<usize>,
+}
+
+impl ResolvedSpan {
+    /// Resolve a span, returning `None` if it is detached or does not point
+    /// into any known source file.
+    fn new(world: &dyn World, span: Span) -> Option<Self> {
+        if span.is_detached() {
+            return None;
+        }
+
+        let source = world.source(span.id()).ok()?;
+        let range = source.find(span)?.range();
+        let start = source.byte_to_utf16(range.start)?;
+        let end = source.byte_to_utf16(range.end)?;
+        let file = eco_format!("{}", span.id());
+
+        Some(Self { file, range, utf16_range: start..end })
+    }
+}
+
+/// The severity of a [`SourceError`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// A fatal error.
+    Error,
+    /// A non-fatal warning.
+    Warning,
+}
+
+/// Why a [`SourceError`] occurred.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ErrorKind {
+    /// A problem with the compiled document or its inputs.
+    #[default]
+    Regular,
+    /// The compilation was cancelled. The other errors of the compilation
+    /// are meaningless then.
+    Cancelled,
 }
 
 /// A part of an error's [trace](SourceError::trace).
@@ -112,6 +249,8 @@ pub enum Tracepoint {
     Show(EcoString),
     /// A module import.
     Import,
+    /// A place a label is attached to.
+    Label(EcoString),
 }
 
 impl Display for Tracepoint {
@@ -126,6 +265,9 @@ impl Display for Tracepoint {
             Tracepoint::Show(name) => {
                 write!(f, "error occurred while applying show rule to this {name}")
             }
+            Tracepoint::Label(name) => {
+                write!(f, "label `<{name}>` is attached here")
+            }
             Tracepoint::Import => {
                 write!(f, "error occurred while importing this module")
             }
@@ -181,10 +323,13 @@ pub trait At<T> {
 
 impl<T, S> At<T> for Result<T, S>
 where
-    S: Into<EcoString>,
+    S: Into<HintedString>,
 {
     fn at(self, span: Span) -> SourceResult<T> {
-        self.map_err(|message| Box::new(vec![SourceError::new(span, message)]))
+        self.map_err(|error| {
+            let HintedString { message, hints } = error.into();
+            Box::new(vec![SourceError::new(span, message).with_hints(hints)])
+        })
     }
 }
 
@@ -201,11 +346,21 @@ pub struct HintedString {
     pub hints: Vec<EcoString>,
 }
 
-impl<T> At<T> for Result<T, HintedString> {
-    fn at(self, span: Span) -> SourceResult<T> {
-        self.map_err(|diags| {
-            Box::new(vec![SourceError::new(span, diags.message).with_hints(diags.hints)])
-        })
+impl From<EcoString> for HintedString {
+    fn from(message: EcoString) -> Self {
+        Self { message, hints: vec![] }
+    }
+}
+
+impl From<&str> for HintedString {
+    fn from(message: &str) -> Self {
+        Self { message: message.into(), hints: vec![] }
+    }
+}
+
+impl From<String> for HintedString {
+    fn from(message: String) -> Self {
+        Self { message: message.into(), hints: vec![] }
     }
 }
 
@@ -248,6 +403,10 @@ pub enum FileError {
     InvalidUtf8,
     /// The package the file is part of could not be loaded.
     Package(PackageError),
+    /// The file could not be retrieved through the network.
+    NetworkFailed(EcoString),
+    /// The file is only available online, but the network may not be used.
+    NotAvailableOffline,
     /// Another error.
     Other,
 }
@@ -258,6 +417,14 @@ impl FileError {
         match error.kind() {
             io::ErrorKind::NotFound => Self::NotFound(path.into()),
             io::ErrorKind::PermissionDenied => Self::AccessDenied,
+            io::ErrorKind::TimedOut
+            | io::ErrorKind::ConnectionRefused
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::NotConnected
+            | io::ErrorKind::AddrNotAvailable => {
+                Self::NetworkFailed(eco_format!("{error}"))
+            }
             io::ErrorKind::InvalidData
                 if error.to_string().contains("stream did not contain valid UTF-8") =>
             {
@@ -266,6 +433,19 @@ impl FileError {
             _ => Self::Other,
         }
     }
+
+    /// Hints to the user on how the error could be resolved.
+    pub fn hints(&self) -> Vec<EcoString> {
+        match self {
+            Self::NetworkFailed(_) | Self::Package(PackageError::NetworkFailed) => {
+                vec!["check your internet connection and try again".into()]
+            }
+            Self::NotAvailableOffline => {
+                vec!["connect to the internet once to download the file".into()]
+            }
+            _ => vec![],
+        }
+    }
 }
 
 impl std::error::Error for FileError {}
@@ -281,6 +461,12 @@ impl Display for FileError {
             Self::NotSource => f.pad("not a typst source file"),
             Self::InvalidUtf8 => f.pad("file is not valid utf-8"),
             Self::Package(error) => error.fmt(f),
+            Self::NetworkFailed(reason) => {
+                write!(f, "failed to load file (network failed: {reason})")
+            }
+            Self::NotAvailableOffline => {
+                f.pad("failed to load file (not available offline)")
+            }
             Self::Other => f.pad("failed to load file"),
         }
     }
@@ -310,6 +496,21 @@ impl From<FileError> for EcoString {
     }
 }
 
+impl From<FileError> for HintedString {
+    fn from(error: FileError) -> Self {
+        Self {
+            message: eco_format!("{error}"),
+            hints: error.hints(),
+        }
+    }
+}
+
+impl From<PackageError> for HintedString {
+    fn from(error: PackageError) -> Self {
+        FileError::Package(error).into()
+    }
+}
+
 /// A result type with a package-related error.
 pub type PackageResult<T> = Result<T, PackageError>;
 
@@ -374,3 +575,117 @@ pub fn format_xml_like_error(format: &str, error: roxmltree::Error) -> EcoString
         _ => eco_format!("failed to parse {format}"),
     }
 }
+
+/// Find the candidate that is closest to a name that could not be resolved,
+/// for suggestions like "did you mean `intro`?".
+///
+/// Only candidates within a few edits of the name are considered, and never
+/// ones that would replace it entirely. Among equally close candidates, the
+/// first one wins.
+pub fn closest_match<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let len = name.chars().count();
+    let max = (len / 3).max(1).min(len.saturating_sub(1));
+    candidates
+        .into_iter()
+        .filter(|&candidate| candidate != name)
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|&(distance, _)| distance <= max)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// The number of character insertions, deletions, and substitutions needed to
+/// turn one string into the other.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestWorld;
+
+    #[test]
+    fn test_closest_match() {
+        assert_eq!(edit_distance("intro", "intro"), 0);
+        assert_eq!(edit_distance("itnro", "intro"), 2);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+
+        let labels = ["intro", "setup", "results"];
+        assert_eq!(closest_match("intr", labels), Some("intro"));
+        assert_eq!(closest_match("stup", labels), Some("setup"));
+        assert_eq!(closest_match("resluts", labels), Some("results"));
+        assert_eq!(closest_match("conclusion", labels), None);
+        assert_eq!(closest_match("intro", labels), None);
+        assert_eq!(closest_match("x", ["h", "xy"]), None);
+        assert_eq!(closest_match("xy", ["h", "xz"]), Some("xz"));
+    }
+
+    #[test]
+    fn test_file_error_from_io() {
+        let path = Path::new("/data.csv");
+        let timeout = io::Error::new(io::ErrorKind::TimedOut, "timed out");
+        let error = FileError::from_io(timeout, path);
+        assert_eq!(error, FileError::NetworkFailed("timed out".into()));
+
+        let hinted = HintedString::from(error);
+        assert_eq!(hinted.message, "failed to load file (network failed: timed out)");
+        assert_eq!(hinted.hints, ["check your internet connection and try again"]);
+
+        let missing = io::Error::from(io::ErrorKind::NotFound);
+        assert_eq!(FileError::from_io(missing, path), FileError::NotFound(path.into()));
+        assert!(FileError::NotFound(path.into()).hints().is_empty());
+    }
+
+    #[test]
+    fn test_resolved_error_json() {
+        let world = TestWorld::new("#let ä() = f\n#ä()");
+        let mut tracer = crate::eval::Tracer::default();
+        let errors = crate::compile(&world, &mut tracer).unwrap_err();
+        let error = errors[0].clone().with_hints(["define `f` first".into()]);
+        assert_eq!(
+            serde_json::to_string(&error.resolve(&world)).unwrap(),
+            concat!(
+                r#"{"severity":"error","message":"unknown variable: f","#,
+                r#""location":{"file":"/main.typ","range":{"start":12,"end":13},"#,
+                r#""utf16_range":{"start":11,"end":12}},"#,
+                r#""trace":[{"message":"error occurred in this call of function `ä`","#,
+                r#""location":{"file":"/main.typ","range":{"start":15,"end":19},"#,
+                r#""utf16_range":{"start":14,"end":17}}}],"#,
+                r#""hints":["define `f` first"]}"#,
+            ),
+        );
+
+        let warning =
+            warning!(error.span, "variable is shadowed").with_hints(["rename it".into()]);
+        assert_eq!(
+            serde_json::to_string(&warning.resolve(&world)).unwrap(),
+            concat!(
+                r#"{"severity":"warning","message":"variable is shadowed","#,
+                r#""location":{"file":"/main.typ","range":{"start":12,"end":13},"#,
+                r#""utf16_range":{"start":11,"end":12}},"#,
+                r#""trace":[],"hints":["rename it"]}"#,
+            ),
+        );
+
+        // Detached spans have no location.
+        let detached = SourceError::new(Span::detached(), "failed");
+        let resolved = serde_json::to_value(detached.resolve(&world)).unwrap();
+        assert!(resolved["location"].is_null());
+    }
+}
@@ -8,15 +8,17 @@ use std::sync::Arc;
 
 use ecow::EcoString;
 
-use crate::eval::{cast, dict, Dict, Value};
+use crate::eval::{cast, dict, Datetime, Dict, Value};
 use crate::font::Font;
 use crate::geom::{
     self, rounded_rect, Abs, Align, Axes, Color, Corners, Dir, Em, Geometry, Length,
-    Numeric, Paint, Point, Rel, RgbaColor, Shape, Sides, Size, Stroke, Transform,
+    Numeric, Paint, Path, Point, Rel, RgbaColor, Shape, Sides, Size, Stroke, Transform,
 };
 use crate::image::Image;
+use crate::info::DocumentInfo;
 use crate::model::{Content, Location, MetaElem, StyleChain};
 use crate::syntax::Span;
+use crate::util::hash128;
 
 /// A finished document with metadata and page frames.
 #[derive(Debug, Default, Clone, Hash)]
@@ -27,6 +29,60 @@ pub struct Document {
     pub title: Option<EcoString>,
     /// The document's author.
     pub author: Vec<EcoString>,
+    /// The document's keywords.
+    pub keywords: Vec<EcoString>,
+    /// The document's creation date.
+    pub date: Option<Datetime>,
+    /// The labels of the pages, matching their logical numbering. This is
+    /// either empty or has one entry per page.
+    pub page_labels: Vec<PageLabel>,
+}
+
+impl Document {
+    /// Find the page and position on it at which the output for a span
+    /// starts. The page is given as an index into `pages`.
+    pub fn position_of(&self, span: Span) -> Option<(usize, Point)> {
+        self.pages
+            .iter()
+            .enumerate()
+            .find_map(|(i, page)| Some((i, page.position_of(span)?)))
+    }
+
+    /// Summarize the document's pages, headings, links, fonts, and text for
+    /// build tooling.
+    pub fn info(&self) -> DocumentInfo {
+        DocumentInfo::new(self)
+    }
+}
+
+/// How a page is labelled in a PDF viewer.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct PageLabel {
+    /// The style of the page's number. If this is `None`, the label consists
+    /// only of the prefix.
+    pub style: Option<PageLabelStyle>,
+    /// Text to display before the page's number.
+    pub prefix: Option<EcoString>,
+    /// The page's number in its style. If this is `None`, the label starts
+    /// counting at one.
+    pub offset: Option<NonZeroUsize>,
+}
+
+/// The numbering style of a page label.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum PageLabelStyle {
+    /// Decimal arabic numerals (1, 2, 3).
+    Arabic,
+    /// Lowercase roman numerals (i, ii, iii).
+    LowerRoman,
+    /// Uppercase roman numerals (I, II, III).
+    UpperRoman,
+    /// Lowercase letters (a to z for the first 26 pages, aa to zz and so on
+    /// for the next).
+    LowerAlpha,
+    /// Uppercase letters (A to Z for the first 26 pages, AA to ZZ and so on
+    /// for the next).
+    UpperAlpha,
 }
 
 /// A finished layout with items at fixed positions.
@@ -115,6 +171,15 @@ impl Frame {
     pub fn items(&self) -> std::slice::Iter<'_, (Point, FrameItem)> {
         self.items.iter()
     }
+
+    /// A 128-bit hash of the frame's full contents.
+    ///
+    /// Frames with equal fingerprints look exactly the same, so exporters and
+    /// previews can use it to skip pages that didn't change between two
+    /// compilations.
+    pub fn fingerprint(&self) -> u128 {
+        hash128(self)
+    }
 }
 
 /// Insert items and subframes.
@@ -229,6 +294,39 @@ impl Frame {
         }
     }
 
+    /// Remove everything but the metadata about elements and page numberings
+    /// from the frame.
+    ///
+    /// The metadata from subframes is moved into this frame at its
+    /// transformed position, so introspecting the stripped frame yields the
+    /// same results as before.
+    pub fn strip(&mut self) {
+        let mut items = vec![];
+        self.collect_meta(Transform::identity(), &mut items);
+        self.items = Arc::new(items);
+    }
+
+    /// Collect introspectable metadata with transformed positions.
+    fn collect_meta(&self, ts: Transform, out: &mut Vec<(Point, FrameItem)>) {
+        for (pos, item) in self.items() {
+            match item {
+                FrameItem::Group(group) => {
+                    let ts = ts
+                        .pre_concat(Transform::translate(pos.x, pos.y))
+                        .pre_concat(group.transform);
+                    group.frame.collect_meta(ts, out);
+                }
+                FrameItem::Meta(
+                    meta @ (Meta::Elem(_) | Meta::PageNumbering(_)),
+                    size,
+                ) => {
+                    out.push((pos.transform(ts), FrameItem::Meta(meta.clone(), *size)));
+                }
+                _ => {}
+            }
+        }
+    }
+
     /// Resize the frame to a new size, distributing new space according to the
     /// given alignments.
     pub fn resize(&mut self, target: Size, aligns: Axes<Align>) {
@@ -313,10 +411,10 @@ impl Frame {
         }
     }
 
-    /// Clip the contents of a frame to its size.
-    pub fn clip(&mut self) {
+    /// Clip the contents of a frame to a path in its coordinate system.
+    pub fn clip(&mut self, clip_path: Path) {
         if !self.is_empty() {
-            self.group(|g| g.clips = true);
+            self.group(|g| g.clip_path = Some(clip_path));
         }
     }
 
@@ -390,6 +488,117 @@ impl Frame {
     }
 }
 
+/// Mapping between positions and source locations.
+impl Frame {
+    /// Find the span of the topmost text run, shape, or image at a point.
+    pub fn lookup(&self, point: Point) -> Option<Span> {
+        self.lookup_offset(point).map(|(span, _)| span)
+    }
+
+    /// Like [`lookup`](Self::lookup), but also returns the byte offset in
+    /// the text of the span's node that is closest to the point. The offset
+    /// is only meaningful for text and zero otherwise.
+    pub fn lookup_offset(&self, point: Point) -> Option<(Span, usize)> {
+        let mut origins = vec![];
+        self.origins(Transform::identity(), &mut origins);
+        origins.iter().rev().find_map(|origin| {
+            if origin.span.is_detached() {
+                return None;
+            }
+
+            let local = point.transform(origin.ts.invert()?);
+            let Point { x, y } = local - origin.pos;
+            if x < Abs::zero()
+                || y < Abs::zero()
+                || x > origin.size.x
+                || y > origin.size.y
+            {
+                return None;
+            }
+
+            let offset =
+                if x > origin.size.x / 2.0 { origin.text.end } else { origin.text.start };
+
+            Some((origin.span, offset))
+        })
+    }
+
+    /// Find the position of the first text run, shape, or image that
+    /// originates from a span.
+    pub fn position_of(&self, span: Span) -> Option<Point> {
+        if span.is_detached() {
+            return None;
+        }
+
+        let mut origins = vec![];
+        self.origins(Transform::identity(), &mut origins);
+        origins
+            .iter()
+            .find(|origin| origin.span == span)
+            .map(|origin| origin.pos.transform(origin.ts))
+    }
+
+    /// Collect the parts of the frame that originate from source code in the
+    /// order they are painted.
+    fn origins(&self, ts: Transform, out: &mut Vec<Origin>) {
+        for (pos, item) in self.items() {
+            match item {
+                FrameItem::Group(group) => {
+                    let ts = ts
+                        .pre_concat(Transform::translate(pos.x, pos.y))
+                        .pre_concat(group.transform);
+                    group.frame.origins(ts, out);
+                }
+                FrameItem::Text(text) => {
+                    let mut x = pos.x;
+                    for glyph in &text.glyphs {
+                        let width = glyph.x_advance.at(text.size);
+                        let (span, offset) = glyph.span;
+                        let start = usize::from(offset);
+                        out.push(Origin {
+                            span,
+                            text: start..start + glyph.range().len(),
+                            pos: Point::new(x, pos.y - text.size),
+                            size: Size::new(width, text.size),
+                            ts,
+                        });
+                        x += width;
+                    }
+                }
+                FrameItem::Shape(shape, span) => {
+                    if let Geometry::Rect(size) = shape.geometry {
+                        out.push(Origin { span: *span, text: 0..0, pos: *pos, size, ts });
+                    }
+                }
+                FrameItem::Image(_, size, span) => {
+                    out.push(Origin {
+                        span: *span,
+                        text: 0..0,
+                        pos: *pos,
+                        size: *size,
+                        ts,
+                    });
+                }
+                FrameItem::Meta(..) => {}
+            }
+        }
+    }
+}
+
+/// A part of a frame that originates from source code.
+struct Origin {
+    /// The span of the node the part originates from.
+    span: Span,
+    /// The byte range of the part in the text of the span's node.
+    text: Range<usize>,
+    /// The top-left corner of the part in the local coordinate system.
+    pos: Point,
+    /// The size of the part.
+    size: Size,
+    /// The transformation from the local to the frame's coordinate system.
+    ts: Transform,
+}
+
 impl Debug for Frame {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         f.write_str("Frame ")?;
@@ -433,8 +642,9 @@ pub struct GroupItem {
     pub frame: Frame,
     /// A transformation to apply to the group.
     pub transform: Transform,
-    /// Whether the frame should be a clipping boundary.
-    pub clips: bool,
+    /// A path in the coordinate system of the group's frame to which the
+    /// group's contents are clipped.
+    pub clip_path: Option<Path>,
 }
 
 impl GroupItem {
@@ -443,7 +653,7 @@ impl GroupItem {
         Self {
             frame,
             transform: Transform::identity(),
-            clips: false,
+            clip_path: None,
         }
     }
 }
@@ -477,6 +687,16 @@ impl TextItem {
     pub fn width(&self) -> Abs {
         self.glyphs.iter().map(|g| g.x_advance).sum::<Em>().at(self.size)
     }
+
+    /// The top-left corner and size of the text run's box, which spans the
+    /// run's width and the font's ascender and descender. The corner is
+    /// relative to the start of the run's baseline.
+    pub fn bbox(&self) -> (Point, Size) {
+        let metrics = self.font.metrics();
+        let ascender = metrics.ascender.at(self.size);
+        let descender = metrics.descender.at(self.size);
+        (Point::with_y(-ascender), Size::new(self.width(), ascender - descender))
+    }
 }
 
 impl Debug for TextItem {
@@ -701,7 +921,11 @@ impl From<Position> for Dict {
 
 #[cfg(test)]
 mod tests {
+    use std::path::Path;
+
     use super::*;
+    use crate::file::FileId;
+    use crate::geom::Ratio;
     use crate::util::option_eq;
 
     #[test]
@@ -711,6 +935,37 @@ mod tests {
         assert!(!option_eq(region, "AB"));
     }
 
+    #[test]
+    fn test_frame_lookup() {
+        let id = FileId::new(None, Path::new("/test.typ"));
+        let (a, b) = (Span::new(id, 2), Span::new(id, 3));
+        let rect = |span| {
+            let size = Size::splat(Abs::pt(10.0));
+            FrameItem::Shape(Geometry::Rect(size).filled(Color::BLACK.into()), span)
+        };
+
+        let mut inner = Frame::new(Size::splat(Abs::pt(10.0)));
+        inner.push(Point::zero(), rect(b));
+        inner.transform(Transform::scale(Ratio::new(2.0), Ratio::one()));
+
+        let mut frame = Frame::new(Size::splat(Abs::pt(100.0)));
+        frame.push(Point::zero(), rect(a));
+        frame.push_frame(Point::splat(Abs::pt(40.0)), inner);
+
+        let pt = |x, y| Point::new(Abs::pt(x), Abs::pt(y));
+        assert_eq!(frame.lookup(pt(5.0, 5.0)), Some(a));
+        assert_eq!(frame.lookup(pt(55.0, 45.0)), Some(b));
+        assert_eq!(frame.lookup(pt(55.0, 55.0)), None);
+        assert_eq!(frame.position_of(b), Some(pt(40.0, 40.0)));
+
+        let document = Document {
+            pages: vec![Frame::default(), frame],
+            ..Default::default()
+        };
+        assert_eq!(document.position_of(a), Some((1, Point::zero())));
+        assert_eq!(document.position_of(Span::detached()), None);
+    }
+
     #[test]
     fn test_document_is_send() {
         fn ensure_send<T: Send>() {}
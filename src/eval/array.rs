@@ -4,8 +4,10 @@ use std::ops::{Add, AddAssign};
 
 use ecow::{eco_format, EcoString, EcoVec};
 
-use super::{ops, Args, CastInfo, FromValue, Func, IntoValue, Reflect, Value, Vm};
-use crate::diag::{At, SourceResult, StrResult};
+use super::{
+    ops, Args, CastInfo, Dict, FromValue, Func, IntoValue, Reflect, Str, Value, Vm,
+};
+use crate::diag::{bail, At, SourceResult, StrResult};
 use crate::syntax::Span;
 use crate::util::pretty_array_like;
 
@@ -398,6 +400,62 @@ impl Array {
             .map(|(i, value)| array![i, value.clone()].into_value())
             .collect()
     }
+
+    /// Split the array into chunks of `size` items. The last chunk may be
+    /// shorter if the length isn't a multiple of `size`.
+    pub fn chunks(&self, size: i64) -> StrResult<Self> {
+        let size = positive(size, "chunk size")?;
+        Ok(self
+            .as_slice()
+            .chunks(size)
+            .map(|chunk| Value::Array(chunk.iter().cloned().collect()))
+            .collect())
+    }
+
+    /// All contiguous windows of `size` items. Empty if the array has fewer
+    /// than `size` items.
+    pub fn windows(&self, size: i64) -> StrResult<Self> {
+        let size = positive(size, "window size")?;
+        Ok(self
+            .as_slice()
+            .windows(size)
+            .map(|window| Value::Array(window.iter().cloned().collect()))
+            .collect())
+    }
+
+    /// Remove duplicate items, keeping the first occurrence of each. Two
+    /// items are duplicates if they, or their keys if a key function is
+    /// given, are equal.
+    pub fn dedup(&self, vm: &mut Vm, key: Option<Func>) -> SourceResult<Self> {
+        let mut keys = EcoVec::with_capacity(self.0.len());
+        let mut vec = EcoVec::with_capacity(self.0.len());
+        for value in self.iter() {
+            let key = match &key {
+                Some(f) => f.call_vm(vm, Args::new(f.span(), [value.clone()]))?,
+                None => value.clone(),
+            };
+            if !keys.iter().any(|other| ops::equal(other, &key)) {
+                keys.push(key);
+                vec.push(value.clone());
+            }
+        }
+        Ok(vec.into())
+    }
+
+    /// Convert an array of key-value pairs into a dictionary.
+    pub fn to_dict(&self) -> StrResult<Dict> {
+        self.iter()
+            .map(|value| {
+                let Value::Array(pair) = value else {
+                    bail!("expected (string, any) pair, found {}", value.type_name());
+                };
+                let [key, value] = pair.as_slice() else {
+                    bail!("expected pair with two items, found {} items", pair.len());
+                };
+                Ok((key.clone().cast::<Str>()?, value.clone()))
+            })
+            .collect()
+    }
 }
 
 impl Debug for Array {
@@ -492,6 +550,14 @@ fn array_is_empty() -> EcoString {
     "array is empty".into()
 }
 
+/// Ensure that a size argument is at least one.
+fn positive(size: i64, what: &str) -> StrResult<usize> {
+    usize::try_from(size)
+        .ok()
+        .filter(|&size| size > 0)
+        .ok_or_else(|| eco_format!("{what} must be positive, found {size}"))
+}
+
 /// The out of bounds access error message.
 #[cold]
 fn out_of_bounds(index: i64, len: usize) -> EcoString {
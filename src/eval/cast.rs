@@ -6,7 +6,7 @@ use std::ops::Add;
 use ecow::EcoString;
 
 use super::Value;
-use crate::diag::{At, SourceResult, StrResult};
+use crate::diag::{At, HintedStrResult, SourceResult, StrResult};
 use crate::syntax::{Span, Spanned};
 use crate::util::separated_list;
 
@@ -149,6 +149,12 @@ impl<T: IntoValue> IntoResult for StrResult<T> {
     }
 }
 
+impl<T: IntoValue> IntoResult for HintedStrResult<T> {
+    fn into_result(self, span: Span) -> SourceResult<Value> {
+        self.map(IntoValue::into_value).at(span)
+    }
+}
+
 impl<T: IntoValue> IntoResult for SourceResult<T> {
     fn into_result(self, _: Span) -> SourceResult<Value> {
         self.map(IntoValue::into_value)
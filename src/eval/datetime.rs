@@ -1,3 +1,4 @@
+use std::cmp::Ordering;
 use std::fmt;
 use std::fmt::{Debug, Formatter};
 use std::hash::Hash;
@@ -6,7 +7,8 @@ use ecow::{eco_format, EcoString, EcoVec};
 use time::error::{Format, InvalidFormatDescription};
 use time::{format_description, PrimitiveDateTime};
 
-use crate::eval::cast;
+use crate::diag::{bail, HintedStrResult, HintedString, StrResult};
+use crate::eval::{cast, Duration};
 use crate::util::pretty_array_like;
 
 /// A datetime object that represents either a date, a time or a combination of
@@ -134,6 +136,65 @@ impl Datetime {
         let time = time::Time::from_hms(hour, minute, second).ok()?;
         Some(Datetime::Datetime(PrimitiveDateTime::new(date, time)))
     }
+
+    /// Add a duration to the datetime.
+    ///
+    /// Only the whole days of the duration are added to a date, and times
+    /// wrap around at midnight.
+    pub fn add_duration(self, duration: Duration) -> HintedStrResult<Self> {
+        let duration = time::Duration::from(duration);
+        match self {
+            Datetime::Date(date) => date.checked_add(duration).map(Datetime::Date),
+            Datetime::Time(time) => Some(Datetime::Time(time + duration)),
+            Datetime::Datetime(datetime) => {
+                datetime.checked_add(duration).map(Datetime::Datetime)
+            }
+        }
+        .ok_or_else(out_of_range)
+    }
+
+    /// Subtract a duration from the datetime.
+    pub fn sub_duration(self, duration: Duration) -> HintedStrResult<Self> {
+        let duration = time::Duration::from(duration);
+        match self {
+            Datetime::Date(date) => date.checked_sub(duration).map(Datetime::Date),
+            Datetime::Time(time) => Some(Datetime::Time(time - duration)),
+            Datetime::Datetime(datetime) => {
+                datetime.checked_sub(duration).map(Datetime::Datetime)
+            }
+        }
+        .ok_or_else(out_of_range)
+    }
+
+    /// The duration from another datetime of the same kind to this one.
+    pub fn since(self, other: Self) -> StrResult<Duration> {
+        Ok(match (self, other) {
+            (Datetime::Date(a), Datetime::Date(b)) => a - b,
+            (Datetime::Time(a), Datetime::Time(b)) => a - b,
+            (Datetime::Datetime(a), Datetime::Datetime(b)) => a - b,
+            (a, b) => bail!("cannot subtract {} from {}", b.kind(), a.kind()),
+        }
+        .into())
+    }
+
+    /// Compare with another datetime of the same kind.
+    pub fn compare(&self, other: &Self) -> StrResult<Ordering> {
+        Ok(match (self, other) {
+            (Datetime::Date(a), Datetime::Date(b)) => a.cmp(b),
+            (Datetime::Time(a), Datetime::Time(b)) => a.cmp(b),
+            (Datetime::Datetime(a), Datetime::Datetime(b)) => a.cmp(b),
+            (a, b) => bail!("cannot compare {} and {}", a.kind(), b.kind()),
+        })
+    }
+
+    /// What the datetime consists of.
+    fn kind(&self) -> &'static str {
+        match self {
+            Datetime::Date(_) => "date",
+            Datetime::Time(_) => "time",
+            Datetime::Datetime(_) => "datetime",
+        }
+    }
 }
 
 impl Debug for Datetime {
@@ -157,6 +218,14 @@ cast! {
     type Datetime: "datetime",
 }
 
+/// The error for arithmetic that leaves the range of representable dates.
+fn out_of_range() -> HintedString {
+    HintedString {
+        message: "resulting datetime is out of range".into(),
+        hints: vec!["datetimes must lie between the years -9999 and 9999".into()],
+    }
+}
+
 /// Format the `Format` error of the time crate in an appropriate way.
 fn format_time_format_error(error: Format) -> EcoString {
     match error {
@@ -57,6 +57,16 @@ impl Dict {
         self.0.get(key).or(default).ok_or_else(|| missing_key_no_default(key))
     }
 
+    /// A hint listing the keys of the dictionary, for errors about a missing
+    /// key. Returns `None` if the dictionary is empty.
+    pub fn keys_hint(&self) -> Option<EcoString> {
+        if self.is_empty() {
+            return None;
+        }
+        let keys: Vec<_> = self.0.keys().map(|key| eco_format!("{key:?}")).collect();
+        Some(eco_format!("available keys are {}", separated_list(&keys, "and")))
+    }
+
     /// Mutably borrow the value the given `key` maps to.
     pub fn at_mut(&mut self, key: &str) -> StrResult<&mut Value> {
         Arc::make_mut(&mut self.0)
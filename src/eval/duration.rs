@@ -0,0 +1,101 @@
+use std::fmt::{self, Debug, Formatter};
+
+use ecow::{eco_format, EcoVec};
+
+use crate::eval::cast;
+use crate::util::pretty_array_like;
+
+/// A span of time, like the difference between two datetimes.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Duration(time::Duration);
+
+impl Duration {
+    /// Create a duration from a number of seconds.
+    pub fn from_seconds(seconds: i64) -> Self {
+        Self(time::Duration::seconds(seconds))
+    }
+
+    /// The duration in seconds.
+    pub fn seconds(&self) -> f64 {
+        self.0.as_seconds_f64()
+    }
+
+    /// The duration in minutes.
+    pub fn minutes(&self) -> f64 {
+        self.seconds() / 60.0
+    }
+
+    /// The duration in hours.
+    pub fn hours(&self) -> f64 {
+        self.seconds() / 3_600.0
+    }
+
+    /// The duration in days.
+    pub fn days(&self) -> f64 {
+        self.seconds() / 86_400.0
+    }
+
+    /// The duration in weeks.
+    pub fn weeks(&self) -> f64 {
+        self.seconds() / 604_800.0
+    }
+
+    /// Add two durations, returning `None` on overflow.
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        self.0.checked_add(other.0).map(Self)
+    }
+
+    /// Subtract a duration, returning `None` on overflow.
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.0.checked_sub(other.0).map(Self)
+    }
+
+    /// Negate the duration, returning `None` on overflow.
+    pub fn checked_neg(self) -> Option<Self> {
+        time::Duration::ZERO.checked_sub(self.0).map(Self)
+    }
+}
+
+impl From<time::Duration> for Duration {
+    fn from(duration: time::Duration) -> Self {
+        Self(duration)
+    }
+}
+
+impl From<Duration> for time::Duration {
+    fn from(duration: Duration) -> Self {
+        duration.0
+    }
+}
+
+impl Debug for Duration {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        // Split the duration into its largest whole components, all of which
+        // have the sign of the duration.
+        let mut rest = self.0.whole_seconds();
+        let mut pieces = EcoVec::new();
+        for (name, unit) in [
+            ("weeks", 604_800),
+            ("days", 86_400),
+            ("hours", 3_600),
+            ("minutes", 60),
+            ("seconds", 1),
+        ] {
+            let count = rest / unit;
+            if count != 0 {
+                pieces.push(eco_format!("{name}: {count}"));
+                rest -= count * unit;
+            }
+        }
+
+        if pieces.is_empty() {
+            pieces.push("seconds: 0".into());
+        }
+
+        write!(f, "duration{}", &pretty_array_like(&pieces, false))
+    }
+}
+
+cast! {
+    type Duration: "duration",
+}
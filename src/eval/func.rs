@@ -12,10 +12,10 @@ use super::{
 };
 use crate::diag::{bail, SourceResult, StrResult};
 use crate::file::FileId;
-use crate::model::{DelayedErrors, ElemFunc, Introspector, Locator, Vt};
+use crate::model::{DelayedErrors, Depth, ElemFunc, Introspector, Locator, Vt};
 use crate::syntax::ast::{self, AstNode, Expr, Ident};
 use crate::syntax::{Span, SyntaxNode};
-use crate::World;
+use crate::{Limits, World};
 
 /// An evaluatable function.
 #[derive(Clone, Hash)]
@@ -32,6 +32,8 @@ pub struct Func {
 enum Repr {
     /// A native Rust function.
     Native(&'static NativeFunc),
+    /// A function provided by the host application.
+    Host(Arc<HostFunc>),
     /// A function for an element.
     Elem(ElemFunc),
     /// A user-defined closure.
@@ -45,6 +47,7 @@ impl Func {
     pub fn name(&self) -> Option<&str> {
         match &self.repr {
             Repr::Native(native) => Some(native.info.name),
+            Repr::Host(host) => Some(host.info.name),
             Repr::Elem(func) => Some(func.info().name),
             Repr::Closure(closure) => closure.name.as_deref(),
             Repr::With(arc) => arc.0.name(),
@@ -55,6 +58,7 @@ impl Func {
     pub fn info(&self) -> Option<&FuncInfo> {
         match &self.repr {
             Repr::Native(native) => Some(&native.info),
+            Repr::Host(host) => Some(&host.info),
             Repr::Elem(func) => Some(func.info()),
             Repr::Closure(_) => None,
             Repr::With(arc) => arc.0.info(),
@@ -66,6 +70,16 @@ impl Func {
         self.span
     }
 
+    /// The span to which errors about the function's return value should
+    /// point: The body for closures and the function itself otherwise.
+    pub(super) fn output_span(&self) -> Span {
+        match &self.repr {
+            Repr::Closure(closure) => closure.body.span(),
+            Repr::With(arc) => arc.0.output_span(),
+            _ => self.span,
+        }
+    }
+
     /// Attach a span to this function if it doesn't already have one.
     pub fn spanned(mut self, span: Span) -> Self {
         if self.span.is_detached() {
@@ -88,6 +102,20 @@ impl Func {
                 args.finish()?;
                 Ok(value)
             }
+            Repr::Host(host) => {
+                let span = args.span;
+                let value = (host.func)(&mut args).map_err(|mut errors| {
+                    // Errors the host didn't attach a span to point to the call.
+                    for error in errors.iter_mut() {
+                        if error.span.is_detached() {
+                            error.span = span;
+                        }
+                    }
+                    errors
+                })?;
+                args.finish()?;
+                Ok(value)
+            }
             Repr::Elem(func) => {
                 let value = func.construct(vm, &mut args)?;
                 args.finish()?;
@@ -107,6 +135,8 @@ impl Func {
                     vm.vt.locator.track(),
                     TrackedMut::reborrow_mut(&mut vm.vt.delayed),
                     TrackedMut::reborrow_mut(&mut vm.vt.tracer),
+                    vm.vt.limits,
+                    vm.vt.depth,
                     vm.depth + 1,
                     args,
                 )
@@ -134,6 +164,8 @@ impl Func {
             locator: &mut locator,
             delayed: TrackedMut::reborrow_mut(&mut vt.delayed),
             tracer: TrackedMut::reborrow_mut(&mut vt.tracer),
+            limits: vt.limits,
+            depth: vt.depth,
         };
         let mut vm = Vm::new(vt, route.track(), FileId::detached(), scopes);
         let args = Args::new(self.span(), args);
@@ -164,6 +196,13 @@ impl Func {
                     field
                 )
             }),
+            Repr::Host(func) => func.info.scope.get(field).ok_or_else(|| {
+                eco_format!(
+                    "function `{}` does not contain field `{}`",
+                    func.info.name,
+                    field
+                )
+            }),
             Repr::Elem(func) => func.info().scope.get(field).ok_or_else(|| {
                 eco_format!(
                     "function `{}` does not contain field `{}`",
@@ -239,6 +278,110 @@ cast! {
     self => Value::Func(self.into()),
 }
 
+/// A Typst function provided by the application that embeds Typst.
+///
+/// Unlike a [`NativeFunc`], a host function may capture state, for example a
+/// database connection. Because evaluation is memoized, host functions are
+/// compared and hashed by their name and version only: A host function must
+/// return the same output for the same arguments and its version must be
+/// bumped whenever its behaviour changes.
+///
+/// Errors the function returns without a span are reported at the call site.
+pub struct HostFunc {
+    /// The function's implementation.
+    func: Box<dyn Fn(&mut Args) -> SourceResult<Value> + Send + Sync>,
+    /// Details about the function.
+    info: FuncInfo,
+    /// The version of the function's behaviour.
+    version: u64,
+}
+
+impl HostFunc {
+    /// Create a new host function with a name and a version.
+    ///
+    /// The implementation receives the call's arguments and can parse them
+    /// with [`Args::expect`], [`Args::named`] and friends.
+    pub fn new<F>(name: &'static str, version: u64, func: F) -> Self
+    where
+        F: Fn(&mut Args) -> SourceResult<Value> + Send + Sync + 'static,
+    {
+        Self {
+            func: Box::new(func),
+            info: FuncInfo {
+                name,
+                display: name,
+                keywords: None,
+                category: "host",
+                docs: "",
+                params: vec![],
+                returns: CastInfo::Any,
+                scope: Scope::new(),
+            },
+            version,
+        }
+    }
+
+    /// Set the display name shown in tooltips.
+    pub fn with_display(mut self, display: &'static str) -> Self {
+        self.info.display = display;
+        self
+    }
+
+    /// Set the documentation shown in tooltips and autocompletion.
+    pub fn with_docs(mut self, docs: &'static str) -> Self {
+        self.info.docs = docs;
+        self
+    }
+
+    /// Describe a parameter for tooltips, signature help and autocompletion.
+    pub fn with_param(mut self, param: ParamInfo) -> Self {
+        self.info.params.push(param);
+        self
+    }
+
+    /// Describe the values the function can return.
+    pub fn with_returns(mut self, returns: CastInfo) -> Self {
+        self.info.returns = returns;
+        self
+    }
+
+    /// The function's name.
+    pub fn name(&self) -> &'static str {
+        self.info.name
+    }
+
+    /// The version of the function's behaviour.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+}
+
+impl PartialEq for HostFunc {
+    fn eq(&self, other: &Self) -> bool {
+        self.info.name == other.info.name && self.version == other.version
+    }
+}
+
+impl Eq for HostFunc {}
+
+impl Hash for HostFunc {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.info.name.hash(state);
+        self.version.hash(state);
+    }
+}
+
+impl From<HostFunc> for Func {
+    fn from(host: HostFunc) -> Self {
+        Repr::Host(Arc::new(host)).into()
+    }
+}
+
+cast! {
+    HostFunc,
+    self => Value::Func(self.into()),
+}
+
 /// Details about a function.
 #[derive(Debug, Clone)]
 pub struct FuncInfo {
@@ -332,6 +475,8 @@ impl Closure {
         locator: Tracked<Locator>,
         delayed: TrackedMut<DelayedErrors>,
         tracer: TrackedMut<Tracer>,
+        limits: Limits,
+        layout_depth: Tracked<Depth>,
         depth: usize,
         mut args: Args,
     ) -> SourceResult<Value> {
@@ -353,6 +498,8 @@ impl Closure {
             locator: &mut locator,
             delayed,
             tracer,
+            limits,
+            depth: layout_depth,
         };
 
         // Prepare VM.
@@ -6,7 +6,7 @@ use comemo::Tracked;
 use ecow::EcoString;
 use std::sync::OnceLock;
 
-use super::{Args, Dynamic, Module, Value, Vm};
+use super::{Args, Dynamic, HostFunc, Module, Value, Vm};
 use crate::diag::SourceResult;
 use crate::doc::Document;
 use crate::geom::{Abs, Dir};
@@ -27,6 +27,20 @@ pub struct Library {
     pub items: LangItems,
 }
 
+impl Library {
+    /// Make a function provided by the host application available in all
+    /// documents under its name.
+    ///
+    /// In debug builds, this panics if the global scope already contains a
+    /// definition with the same name.
+    #[track_caller]
+    pub fn with_function(mut self, func: HostFunc) -> Self {
+        let name = func.name();
+        self.global.scope_mut().define(name, func);
+        self
+    }
+}
+
 /// Definition of library items the language is aware of.
 #[derive(Clone)]
 pub struct LangItems {
@@ -102,6 +116,8 @@ pub struct LangItems {
     pub math_frac: fn(num: Content, denom: Content) -> Content,
     /// A root in math: `√x`, `∛x` or `∜x`.
     pub math_root: fn(index: Option<Content>, radicand: Content) -> Content,
+    /// An inline placeholder for markup that failed to evaluate.
+    pub error_placeholder: fn(message: EcoString, span: Span) -> Content,
     /// Dispatch a method on a library value.
     pub library_method: fn(
         vm: &mut Vm,
@@ -149,6 +165,7 @@ impl Hash for LangItems {
         self.math_accent.hash(state);
         self.math_frac.hash(state);
         self.math_root.hash(state);
+        self.error_placeholder.hash(state);
         (self.library_method as usize).hash(state);
     }
 }
@@ -4,9 +4,10 @@ use ecow::EcoString;
 
 use super::{Args, IntoValue, Str, Value, Vm};
 use crate::diag::{At, SourceResult};
-use crate::eval::Datetime;
-use crate::model::{Location, Selector};
+use crate::eval::{Datetime, Duration, Plugin, Version};
+use crate::model::{ElemFunc, Location, Selector};
 use crate::syntax::Span;
+use crate::util::Bytes;
 
 /// Call a method on a value.
 pub fn call(
@@ -20,10 +21,19 @@ pub fn call(
     let missing = || Err(missing_method(name, method)).at(span);
 
     let output = match value {
+        Value::Float(float) => match method {
+            "is-nan" => float.is_nan().into_value(),
+            "is-infinite" => float.is_infinite().into_value(),
+            _ => return missing(),
+        },
+
         Value::Color(color) => match method {
             "lighten" => color.lighten(args.expect("amount")?).into_value(),
             "darken" => color.darken(args.expect("amount")?).into_value(),
+            "saturate" => color.saturate(args.expect("amount")?).into_value(),
+            "desaturate" => color.desaturate(args.expect("amount")?).into_value(),
             "negate" => color.negate().into_value(),
+            "components" => color.components().into_value(),
             _ => return missing(),
         },
 
@@ -84,6 +94,29 @@ pub fn call(
             _ => return missing(),
         },
 
+        Value::Styles(styles) => match method {
+            "get" => {
+                let func: ElemFunc = args.expect("element")?;
+                let field: Str = args.expect("field")?;
+                styles.get(func, &field).at(span)?
+            }
+            _ => return missing(),
+        },
+
+        Value::Bytes(bytes) => match method {
+            "len" => bytes.len().into_value(),
+            "at" => bytes.at(args.expect("index")?, args.named("default")?).at(span)?,
+            "slice" => {
+                let start = args.expect("start")?;
+                let mut end = args.eat()?;
+                if end.is_none() {
+                    end = args.named("count")?.map(|c: i64| start + c);
+                }
+                bytes.slice(start, end).at(span)?.into_value()
+            }
+            _ => return missing(),
+        },
+
         Value::Array(array) => match method {
             "len" => array.len().into_value(),
             "first" => array.first().at(span)?.clone(),
@@ -123,6 +156,12 @@ pub fn call(
             "sorted" => array.sorted(vm, span, args.named("key")?)?.into_value(),
             "zip" => array.zip(args.expect("other")?).into_value(),
             "enumerate" => array.enumerate().into_value(),
+            "chunks" => array.chunks(args.expect("chunk size")?).at(span)?.into_value(),
+            "windows" => {
+                array.windows(args.expect("window size")?).at(span)?.into_value()
+            }
+            "dedup" => array.dedup(vm, args.named("key")?)?.into_value(),
+            "to-dict" => array.to_dict().at(span)?.into_value(),
             _ => return missing(),
         },
 
@@ -198,6 +237,23 @@ pub fn call(
                     "second" => datetime.second().into_value(),
                     _ => return missing(),
                 }
+            } else if let Some(&duration) = dynamic.downcast::<Duration>() {
+                match method {
+                    "seconds" => duration.seconds().into_value(),
+                    "minutes" => duration.minutes().into_value(),
+                    "hours" => duration.hours().into_value(),
+                    "days" => duration.days().into_value(),
+                    "weeks" => duration.weeks().into_value(),
+                    _ => return missing(),
+                }
+            } else if let Some(version) = dynamic.downcast::<Version>() {
+                match method {
+                    "at" => version.at(args.expect("index")?).at(span)?.into_value(),
+                    _ => return missing(),
+                }
+            } else if let Some(plugin) = dynamic.downcast::<Plugin>() {
+                let bytes = args.all::<Bytes>()?;
+                plugin.call(method, bytes).at(span)?.into_value()
             } else {
                 return (vm.items.library_method)(vm, &dynamic, method, args, span);
             }
@@ -294,7 +350,15 @@ fn missing_method(type_name: &str, method: &str) -> String {
 /// List the available methods for a type and whether they take arguments.
 pub fn methods_on(type_name: &str) -> &[(&'static str, bool)] {
     match type_name {
-        "color" => &[("lighten", true), ("darken", true), ("negate", false)],
+        "float" => &[("is-nan", false), ("is-infinite", false)],
+        "color" => &[
+            ("lighten", true),
+            ("darken", true),
+            ("saturate", true),
+            ("desaturate", true),
+            ("negate", false),
+            ("components", false),
+        ],
         "string" => &[
             ("len", false),
             ("at", true),
@@ -314,6 +378,8 @@ pub fn methods_on(type_name: &str) -> &[(&'static str, bool)] {
             ("starts-with", true),
             ("trim", true),
         ],
+        "bytes" => &[("len", false), ("at", true), ("slice", true)],
+        "styles" => &[("get", true)],
         "content" => &[
             ("func", false),
             ("has", true),
@@ -346,6 +412,10 @@ pub fn methods_on(type_name: &str) -> &[(&'static str, bool)] {
             ("sorted", false),
             ("enumerate", false),
             ("zip", true),
+            ("chunks", true),
+            ("windows", true),
+            ("dedup", false),
+            ("to-dict", false),
         ],
         "dictionary" => &[
             ("at", true),
@@ -356,6 +426,14 @@ pub fn methods_on(type_name: &str) -> &[(&'static str, bool)] {
             ("remove", true),
             ("values", false),
         ],
+        "duration" => &[
+            ("seconds", false),
+            ("minutes", false),
+            ("hours", false),
+            ("days", false),
+            ("weeks", false),
+        ],
+        "version" => &[("at", true)],
         "function" => &[("where", true), ("with", true)],
         "arguments" => &[("named", false), ("pos", false)],
         "location" => &[("page", false), ("position", false), ("page-numbering", false)],
@@ -15,14 +15,18 @@ mod value;
 mod args;
 mod auto;
 mod datetime;
+mod duration;
 mod func;
 mod int;
 mod methods;
 mod module;
 mod none;
 pub mod ops;
+mod plugin;
 mod scope;
 mod symbol;
+mod ty;
+mod version;
 
 #[doc(hidden)]
 pub use {
@@ -43,19 +47,22 @@ pub use self::cast::{
 };
 pub use self::datetime::Datetime;
 pub use self::dict::{dict, Dict};
-pub use self::func::{Func, FuncInfo, NativeFunc, Param, ParamInfo};
+pub use self::duration::Duration;
+pub use self::func::{Func, FuncInfo, HostFunc, NativeFunc, Param, ParamInfo};
 pub use self::library::{set_lang_items, LangItems, Library};
 pub use self::methods::methods_on;
 pub use self::module::Module;
 pub use self::none::NoneValue;
+pub use self::plugin::Plugin;
 pub use self::scope::{Scope, Scopes};
 pub use self::str::{format_str, Regex, Str};
 pub use self::symbol::Symbol;
+pub use self::ty::TypeValue;
 pub use self::value::{Dynamic, Type, Value};
+pub use self::version::Version;
 
 use std::collections::HashSet;
 use std::mem;
-use std::path::Path;
 
 use comemo::{Track, Tracked, TrackedMut, Validate};
 use ecow::{EcoString, EcoVec};
@@ -63,19 +70,20 @@ use unicode_segmentation::UnicodeSegmentation;
 
 use self::func::{CapturesVisitor, Closure};
 use crate::diag::{
-    bail, error, At, SourceError, SourceResult, StrResult, Trace, Tracepoint,
+    bail, error, warning, At, HintedString, SourceError, SourceResult, StrResult, Trace,
+    Tracepoint,
 };
-use crate::file::{FileId, PackageManifest, PackageSpec};
+use crate::file::FileId;
 use crate::model::{
-    Content, DelayedErrors, Introspector, Label, Locator, Recipe, ShowableSelector,
-    Styles, Transform, Unlabellable, Vt,
+    Content, DelayedErrors, Depth, Introspector, Label, Locator, Recipe,
+    ShowableSelector, Styles, Transform, Unlabellable, Vt,
 };
+use crate::package::PackageRequest;
+use crate::profile::{self, ProfileEntry, ProfileKind};
 use crate::syntax::ast::{self, AstNode};
 use crate::syntax::{parse_code, Source, Span, Spanned, SyntaxKind, SyntaxNode};
-use crate::World;
-
-const MAX_ITERATIONS: usize = 10_000;
-const MAX_CALL_DEPTH: usize = 64;
+use crate::util::hash128;
+use crate::{CancellationToken, Limits, World};
 
 /// Evaluate a source file and return the resulting module.
 #[comemo::memoize]
@@ -84,6 +92,7 @@ pub fn eval(
     world: Tracked<dyn World + '_>,
     route: Tracked<Route>,
     tracer: TrackedMut<Tracer>,
+    limits: Limits,
     source: &Source,
 ) -> SourceResult<Module> {
     // Prevent cyclic evaluation.
@@ -100,12 +109,15 @@ pub fn eval(
     let mut locator = Locator::default();
     let introspector = Introspector::default();
     let mut delayed = DelayedErrors::default();
+    let depth = Depth::default();
     let vt = Vt {
         world,
         introspector: introspector.track(),
         locator: &mut locator,
         delayed: delayed.track_mut(),
         tracer,
+        limits,
+        depth: depth.track(),
     };
 
     // Prepare VM.
@@ -136,6 +148,7 @@ pub fn eval(
 #[comemo::memoize]
 pub fn eval_string(
     world: Tracked<dyn World + '_>,
+    limits: Limits,
     code: &str,
     span: Span,
 ) -> SourceResult<Value> {
@@ -152,12 +165,15 @@ pub fn eval_string(
     let mut locator = Locator::default();
     let mut delayed = DelayedErrors::default();
     let introspector = Introspector::default();
+    let depth = Depth::default();
     let vt = Vt {
         world,
         introspector: introspector.track(),
         locator: &mut locator,
         delayed: delayed.track_mut(),
         tracer: tracer.track_mut(),
+        limits,
+        depth: depth.track(),
     };
 
     // Prepare VM.
@@ -317,11 +333,18 @@ impl<'a> Route<'a> {
     }
 }
 
-/// Traces which values existed for an expression at a span.
+/// Traces warnings and which values existed for an expression at a span.
 #[derive(Default, Clone)]
 pub struct Tracer {
     span: Option<Span>,
     values: Vec<Value>,
+    warnings: Vec<SourceError>,
+    warnings_set: HashSet<u128>,
+    cancellation: Option<CancellationToken>,
+    profile: Vec<ProfileEntry>,
+    recover: bool,
+    errors: Vec<SourceError>,
+    errors_set: HashSet<u128>,
 }
 
 impl Tracer {
@@ -330,13 +353,45 @@ impl Tracer {
 
     /// Create a new tracer, possibly with a span under inspection.
     pub fn new(span: Option<Span>) -> Self {
-        Self { span, values: vec![] }
+        Self { span, ..Default::default() }
     }
 
     /// Get the traced values.
     pub fn finish(self) -> Vec<Value> {
         self.values
     }
+
+    /// The warnings emitted during compilation.
+    pub fn warnings(&self) -> &[SourceError] {
+        &self.warnings
+    }
+
+    /// The errors that the compilation recovered from by replacing the
+    /// failing markup with placeholders.
+    pub fn errors(&self) -> &[SourceError] {
+        &self.errors
+    }
+
+    /// The profiling report of the last compilation, if profiling was
+    /// enabled for it.
+    pub fn profile(&self) -> &[ProfileEntry] {
+        &self.profile
+    }
+
+    /// Set the token through which the compilation can be cancelled.
+    pub(crate) fn set_cancellation(&mut self, token: Option<CancellationToken>) {
+        self.cancellation = token;
+    }
+
+    /// Set whether to recover from errors in markup.
+    pub(crate) fn set_recover(&mut self, recover: bool) {
+        self.recover = recover;
+    }
+
+    /// Store the profiling report of a compilation.
+    pub(crate) fn set_profile(&mut self, profile: Vec<ProfileEntry>) {
+        self.profile = profile;
+    }
 }
 
 #[comemo::track]
@@ -356,6 +411,58 @@ impl Tracer {
             self.values.push(v);
         }
     }
+
+    /// Whether the compilation was cancelled.
+    ///
+    /// Since this is a tracked call, results that were memoized after a
+    /// cancellation are not reused by later compilations.
+    pub fn cancelled(&self) -> bool {
+        self.cancellation
+            .as_ref()
+            .map_or(false, CancellationToken::is_cancelled)
+    }
+
+    /// Whether errors in markup should be replaced with placeholders instead
+    /// of aborting the compilation.
+    ///
+    /// A cancelled compilation never recovers.
+    pub fn recovering(&self) -> bool {
+        self.recover && !self.cancelled()
+    }
+
+    /// Record errors that the compilation recovered from, unless identical
+    /// ones were already recorded.
+    pub fn recovered(&mut self, errors: Vec<SourceError>) {
+        for error in errors {
+            // Like warnings, the same errors can come up in multiple passes.
+            let hash = hash128(&(error.span, &error.message));
+            if self.errors_set.insert(hash) {
+                self.errors.push(error);
+            }
+        }
+    }
+
+    /// Emit a warning, unless an identical one was already emitted.
+    pub fn warn(&mut self, warning: SourceError) {
+        // Layout runs multiple times, so the same warning can come up
+        // repeatedly.
+        let hash = hash128(&(warning.span, &warning.message));
+        if self.warnings_set.insert(hash) {
+            self.warnings.push(warning);
+        }
+    }
+
+    /// Emit a warning, unless one with the same message was already emitted
+    /// for any span.
+    ///
+    /// This is for problems that could otherwise be reported for every
+    /// occurrence of something throughout a document.
+    pub fn warn_once(&mut self, warning: SourceError) {
+        let hash = hash128(&warning.message);
+        if self.warnings_set.insert(hash) {
+            self.warnings.push(warning);
+        }
+    }
 }
 
 /// Evaluate an expression.
@@ -385,32 +492,37 @@ fn eval_markup(
 
     while let Some(expr) = exprs.next() {
         match expr {
-            ast::Expr::Set(set) => {
-                let styles = set.eval(vm)?;
-                if vm.flow.is_some() {
-                    break;
-                }
+            ast::Expr::Set(set) => match set.eval(vm) {
+                Ok(styles) => {
+                    if vm.flow.is_some() {
+                        break;
+                    }
 
-                seq.push(eval_markup(vm, exprs)?.styled_with_map(styles))
-            }
-            ast::Expr::Show(show) => {
-                let recipe = show.eval(vm)?;
-                if vm.flow.is_some() {
-                    break;
+                    seq.push(eval_markup(vm, exprs)?.styled_with_map(styles))
                 }
+                Err(errors) => seq.push(recover(vm, errors)?),
+            },
+            ast::Expr::Show(show) => match show.eval(vm) {
+                Ok(recipe) => {
+                    if vm.flow.is_some() {
+                        break;
+                    }
 
-                let tail = eval_markup(vm, exprs)?;
-                seq.push(tail.styled_with_recipe(vm, recipe)?)
-            }
-            expr => match expr.eval(vm)? {
-                Value::Label(label) => {
+                    let tail = eval_markup(vm, exprs)?;
+                    seq.push(tail.styled_with_recipe(vm, recipe)?)
+                }
+                Err(errors) => seq.push(recover(vm, errors)?),
+            },
+            expr => match expr.eval(vm) {
+                Ok(Value::Label(label)) => {
                     if let Some(elem) =
                         seq.iter_mut().rev().find(|node| !node.can::<dyn Unlabellable>())
                     {
                         *elem = mem::take(elem).labelled(label);
                     }
                 }
-                value => seq.push(value.display().spanned(expr.span())),
+                Ok(value) => seq.push(value.display().spanned(expr.span())),
+                Err(errors) => seq.push(recover(vm, errors)?),
             },
         }
 
@@ -426,6 +538,20 @@ fn eval_markup(
     Ok(Content::sequence(seq))
 }
 
+/// Replace markup that failed to evaluate with an inline placeholder if the
+/// compilation recovers from errors.
+fn recover(vm: &mut Vm, errors: Box<Vec<SourceError>>) -> SourceResult<Content> {
+    if !vm.vt.tracer.recovering() || errors.is_empty() {
+        return Err(errors);
+    }
+
+    // The placeholder points to the first error, so that clicking it in a
+    // preview jumps there.
+    let SourceError { span, message, .. } = errors[0].clone();
+    vm.vt.tracer.recovered(*errors);
+    Ok((vm.items.error_placeholder)(message, span))
+}
+
 impl Eval for ast::Expr {
     type Output = Value;
 
@@ -577,7 +703,16 @@ impl Eval for ast::Strong {
 
     #[tracing::instrument(name = "Strong::eval", skip_all)]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
-        Ok((vm.items.strong)(self.body().eval(vm)?))
+        let body = self.body();
+        if body.exprs().next().is_none() {
+            let hint =
+                "using multiple consecutive stars (e.g. **) has no additional effect";
+            vm.vt.tracer.warn(
+                warning!(self.span(), "no text within stars").with_hints([hint.into()]),
+            );
+        }
+
+        Ok((vm.items.strong)(body.eval(vm)?))
     }
 }
 
@@ -586,7 +721,17 @@ impl Eval for ast::Emph {
 
     #[tracing::instrument(name = "Emph::eval", skip_all)]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
-        Ok((vm.items.emph)(self.body().eval(vm)?))
+        let body = self.body();
+        if body.exprs().next().is_none() {
+            let hint =
+                "using multiple consecutive underscores (e.g. __) has no additional effect";
+            vm.vt.tracer.warn(
+                warning!(self.span(), "no text within underscores")
+                    .with_hints([hint.into()]),
+            );
+        }
+
+        Ok((vm.items.emph)(body.eval(vm)?))
     }
 }
 
@@ -862,6 +1007,8 @@ fn eval_code(
 
     while let Some(expr) = exprs.next() {
         let span = expr.span();
+        vm.vt.check_cancelled(span)?;
+
         let value = match expr {
             ast::Expr::Set(set) => {
                 let styles = set.eval(vm)?;
@@ -1008,7 +1155,7 @@ impl Eval for ast::Binary {
             ast::BinOp::Geq => self.apply(vm, ops::geq),
             ast::BinOp::In => self.apply(vm, ops::in_),
             ast::BinOp::NotIn => self.apply(vm, ops::not_in),
-            ast::BinOp::Assign => self.assign(vm, |_, b| Ok(b)),
+            ast::BinOp::Assign => self.assign(vm, |_, b| StrResult::Ok(b)),
             ast::BinOp::AddAssign => self.assign(vm, ops::add),
             ast::BinOp::SubAssign => self.assign(vm, ops::sub),
             ast::BinOp::MulAssign => self.assign(vm, ops::mul),
@@ -1019,11 +1166,14 @@ impl Eval for ast::Binary {
 
 impl ast::Binary {
     /// Apply a basic binary operation.
-    fn apply(
+    fn apply<E>(
         &self,
         vm: &mut Vm,
-        op: fn(Value, Value) -> StrResult<Value>,
-    ) -> SourceResult<Value> {
+        op: fn(Value, Value) -> Result<Value, E>,
+    ) -> SourceResult<Value>
+    where
+        Result<Value, E>: At<Value>,
+    {
         let lhs = self.lhs().eval(vm)?;
 
         // Short-circuit boolean operations.
@@ -1038,11 +1188,14 @@ impl ast::Binary {
     }
 
     /// Apply an assignment operation.
-    fn assign(
+    fn assign<E>(
         &self,
         vm: &mut Vm,
-        op: fn(Value, Value) -> StrResult<Value>,
-    ) -> SourceResult<Value> {
+        op: fn(Value, Value) -> Result<Value, E>,
+    ) -> SourceResult<Value>
+    where
+        Result<Value, E>: At<Value>,
+    {
         let rhs = self.rhs().eval(vm)?;
         let lhs = self.lhs();
 
@@ -1070,7 +1223,16 @@ impl Eval for ast::FieldAccess {
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let value = self.target().eval(vm)?;
         let field = self.field();
-        value.field(&field).at(field.span())
+        value
+            .field(&field)
+            .map_err(|message| {
+                let hint = match &value {
+                    Value::Dict(dict) => dict.keys_hint(),
+                    _ => None,
+                };
+                HintedString { message, hints: hint.into_iter().collect() }
+            })
+            .at(field.span())
     }
 }
 
@@ -1080,7 +1242,9 @@ impl Eval for ast::FuncCall {
     #[tracing::instrument(name = "FuncCall::eval", skip_all)]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let span = self.span();
-        if vm.depth >= MAX_CALL_DEPTH {
+        let _timer = profile::timer(ProfileKind::Call, span);
+        vm.vt.check_cancelled(span)?;
+        if vm.depth >= vm.vt.limits.max_call_depth {
             bail!(span, "maximum function call depth exceeded");
         }
 
@@ -1471,7 +1635,7 @@ impl Eval for ast::SetRule {
             })
             .at(target.span())?;
         let args = self.args().eval(vm)?;
-        Ok(target.set(args)?.spanned(self.span()))
+        Ok(target.set(vm, args)?.spanned(self.span()))
     }
 }
 
@@ -1531,7 +1695,7 @@ impl Eval for ast::WhileLoop {
                 && !can_diverge(body.as_untyped())
             {
                 bail!(condition.span(), "condition is always true");
-            } else if i >= MAX_ITERATIONS {
+            } else if i >= vm.vt.limits.max_loop_iterations {
                 bail!(self.span(), "loop seems to be infinite");
             }
 
@@ -1751,29 +1915,35 @@ fn import(
     // Handle package and file imports.
     let path = path.as_str();
     if path.starts_with('@') {
-        let spec = path.parse::<PackageSpec>().at(span)?;
-        import_package(vm, spec, span)
+        let request = path.parse::<PackageRequest>().at(span)?;
+        import_package(vm, request, span)
     } else {
         import_file(vm, path, span)
     }
 }
 
 /// Import an external package.
-fn import_package(vm: &mut Vm, spec: PackageSpec, span: Span) -> SourceResult<Module> {
-    // Evaluate the manifest.
-    let manifest_id = FileId::new(Some(spec.clone()), Path::new("/typst.toml"));
-    let bytes = vm.world().file(manifest_id).at(span)?;
-    let manifest = PackageManifest::parse(&bytes).at(span)?;
-    manifest.validate(&spec).at(span)?;
+fn import_package(
+    vm: &mut Vm,
+    request: PackageRequest,
+    span: Span,
+) -> SourceResult<Module> {
+    // Resolve the version and validate the manifest.
+    let (spec, manifest) = crate::package::resolve(vm.world(), &request).at(span)?;
 
     // Evaluate the entry point.
-    let entrypoint = Path::new("/").join(manifest.package.entrypoint.as_str());
-    let entrypoint_id = FileId::new(Some(spec), &entrypoint);
-    let source = vm.world().source(entrypoint_id).at(span)?;
+    let id = manifest.entrypoint(&spec);
+    let source = vm.world().source(id).at(span)?;
     let point = || Tracepoint::Import;
-    Ok(eval(vm.world(), vm.route, TrackedMut::reborrow_mut(&mut vm.vt.tracer), &source)
-        .trace(vm.world(), point, span)?
-        .with_name(manifest.package.name))
+    Ok(eval(
+        vm.world(),
+        vm.route,
+        TrackedMut::reborrow_mut(&mut vm.vt.tracer),
+        vm.vt.limits,
+        &source,
+    )
+    .trace(vm.world(), point, span)?
+    .with_name(manifest.package.name))
 }
 
 /// Import a file from a path.
@@ -1790,8 +1960,14 @@ fn import_file(vm: &mut Vm, path: &str, span: Span) -> SourceResult<Module> {
 
     // Evaluate the file.
     let point = || Tracepoint::Import;
-    eval(world, vm.route, TrackedMut::reborrow_mut(&mut vm.vt.tracer), &source)
-        .trace(world, point, span)
+    eval(
+        world,
+        vm.route,
+        TrackedMut::reborrow_mut(&mut vm.vt.tracer),
+        vm.vt.limits,
+        &source,
+    )
+    .trace(world, point, span)
 }
 
 impl Eval for ast::LoopBreak {
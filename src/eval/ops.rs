@@ -5,15 +5,17 @@ use std::fmt::Debug;
 
 use ecow::eco_format;
 
-use super::{format_str, Regex, Value};
-use crate::diag::{bail, StrResult};
-use crate::geom::{Axes, Axis, GenAlign, Length, Numeric, PartialStroke, Rel, Smart};
+use super::{format_str, Datetime, Duration, Regex, TypeValue, Value, Version};
+use crate::diag::{bail, HintedStrResult, StrResult};
+use crate::geom::{
+    Axes, Axis, GenAlign, Gradient, Length, Numeric, PartialStroke, Pattern, Rel, Smart,
+};
 use Value::*;
 
 /// Bail with a type mismatch error.
 macro_rules! mismatch {
     ($fmt:expr, $($value:expr),* $(,)?) => {
-        return Err(eco_format!($fmt, $($value.type_name()),*))
+        return Err(eco_format!($fmt, $($value.type_name()),*).into())
     };
 }
 
@@ -31,6 +33,8 @@ pub fn join(lhs: Value, rhs: Value) -> StrResult<Value> {
         (Content(a), Str(b)) => Content(a + item!(text)(b.into())),
         (Str(a), Content(b)) => Content(item!(text)(a.into()) + b),
         (Symbol(a), Content(b)) => Content(item!(text)(a.get().into()) + b),
+        (Str(a), Dyn(b)) if b.is::<TypeValue>() => Str(format_str!("{a}{b:?}")),
+        (Dyn(a), Str(b)) if a.is::<TypeValue>() => Str(format_str!("{a:?}{b}")),
         (Array(a), Array(b)) => Array(a + b),
         (Dict(a), Dict(b)) => Dict(a + b),
         (a, b) => mismatch!("cannot join {} with {}", a, b),
@@ -61,12 +65,16 @@ pub fn neg(value: Value) -> StrResult<Value> {
         Ratio(v) => Ratio(-v),
         Relative(v) => Relative(-v),
         Fraction(v) => Fraction(-v),
+        Dyn(v) if v.is::<Duration>() => {
+            let duration = *v.downcast::<Duration>().unwrap();
+            Value::dynamic(duration.checked_neg().ok_or("value is too large")?)
+        }
         v => mismatch!("cannot apply '-' to {}", v),
     })
 }
 
 /// Compute the sum of two values.
-pub fn add(lhs: Value, rhs: Value) -> StrResult<Value> {
+pub fn add(lhs: Value, rhs: Value) -> HintedStrResult<Value> {
     Ok(match (lhs, rhs) {
         (a, None) => a,
         (None, b) => b,
@@ -113,13 +121,39 @@ pub fn add(lhs: Value, rhs: Value) -> StrResult<Value> {
             })
         }
 
+        (Dyn(gradient), Length(thickness)) | (Length(thickness), Dyn(gradient))
+            if gradient.is::<Gradient>() =>
+        {
+            Value::dynamic(PartialStroke {
+                paint: Smart::Custom(
+                    gradient.downcast::<Gradient>().unwrap().clone().into(),
+                ),
+                thickness: Smart::Custom(thickness),
+                ..PartialStroke::default()
+            })
+        }
+
+        (Dyn(pattern), Length(thickness)) | (Length(thickness), Dyn(pattern))
+            if pattern.is::<Pattern>() =>
+        {
+            Value::dynamic(PartialStroke {
+                paint: Smart::Custom(
+                    pattern.downcast::<Pattern>().unwrap().clone().into(),
+                ),
+                thickness: Smart::Custom(thickness),
+                ..PartialStroke::default()
+            })
+        }
+
         (Dyn(a), Dyn(b)) => {
             // 1D alignments can be summed into 2D alignments.
             if let (Some(&a), Some(&b)) =
                 (a.downcast::<GenAlign>(), b.downcast::<GenAlign>())
             {
                 if a.axis() == b.axis() {
-                    return Err(eco_format!("cannot add two {:?} alignments", a.axis()));
+                    return Err(
+                        eco_format!("cannot add two {:?} alignments", a.axis()).into()
+                    );
                 }
 
                 return Ok(Value::dynamic(match a.axis() {
@@ -128,6 +162,21 @@ pub fn add(lhs: Value, rhs: Value) -> StrResult<Value> {
                 }));
             };
 
+            // Durations can be added to each other and to datetimes.
+            if let Some(&b) = b.downcast::<Duration>() {
+                if let Some(&a) = a.downcast::<Duration>() {
+                    return Ok(Value::dynamic(
+                        a.checked_add(b).ok_or("value is too large")?,
+                    ));
+                } else if let Some(&a) = a.downcast::<Datetime>() {
+                    return Ok(Value::dynamic(a.add_duration(b)?));
+                }
+            } else if let (Some(&a), Some(&b)) =
+                (a.downcast::<Duration>(), b.downcast::<Datetime>())
+            {
+                return Ok(Value::dynamic(b.add_duration(a)?));
+            }
+
             mismatch!("cannot add {} and {}", a, b);
         }
 
@@ -136,7 +185,7 @@ pub fn add(lhs: Value, rhs: Value) -> StrResult<Value> {
 }
 
 /// Compute the difference of two values.
-pub fn sub(lhs: Value, rhs: Value) -> StrResult<Value> {
+pub fn sub(lhs: Value, rhs: Value) -> HintedStrResult<Value> {
     Ok(match (lhs, rhs) {
         (Int(a), Int(b)) => Int(a.checked_sub(b).ok_or("value is too large")?),
         (Int(a), Float(b)) => Float(a as f64 - b),
@@ -159,6 +208,24 @@ pub fn sub(lhs: Value, rhs: Value) -> StrResult<Value> {
 
         (Fraction(a), Fraction(b)) => Fraction(a - b),
 
+        (Dyn(a), Dyn(b)) => {
+            if let Some(&b) = b.downcast::<Duration>() {
+                if let Some(&a) = a.downcast::<Duration>() {
+                    return Ok(Value::dynamic(
+                        a.checked_sub(b).ok_or("value is too large")?,
+                    ));
+                } else if let Some(&a) = a.downcast::<Datetime>() {
+                    return Ok(Value::dynamic(a.sub_duration(b)?));
+                }
+            } else if let (Some(&a), Some(&b)) =
+                (a.downcast::<Datetime>(), b.downcast::<Datetime>())
+            {
+                return Ok(Value::dynamic(a.since(b)?));
+            }
+
+            mismatch!("cannot subtract {1} from {0}", a, b);
+        }
+
         (a, b) => mismatch!("cannot subtract {1} from {0}", a, b),
     })
 }
@@ -347,6 +414,7 @@ pub fn equal(lhs: &Value, rhs: &Value) -> bool {
         (Color(a), Color(b)) => a == b,
         (Symbol(a), Symbol(b)) => a == b,
         (Str(a), Str(b)) => a == b,
+        (Bytes(a), Bytes(b)) => a == b,
         (Label(a), Label(b)) => a == b,
         (Content(a), Content(b)) => a == b,
         (Array(a), Array(b)) => a == b,
@@ -364,6 +432,15 @@ pub fn equal(lhs: &Value, rhs: &Value) -> bool {
         (&Relative(a), &Length(b)) => a.abs == b && a.rel.is_zero(),
         (&Relative(a), &Ratio(b)) => a.rel == b && a.abs.is_zero(),
 
+        // Types compare equal to their name and their constructor.
+        (Dyn(a), Str(b)) | (Str(b), Dyn(a)) => {
+            a.downcast::<TypeValue>().map_or(false, |ty| ty.name() == b.as_str())
+        }
+        (Dyn(a), Func(b)) | (Func(b), Dyn(a)) => a
+            .downcast::<TypeValue>()
+            .and_then(TypeValue::constructor)
+            .map_or(false, |constructor| constructor == b),
+
         _ => false,
     }
 }
@@ -389,6 +466,24 @@ pub fn compare(lhs: &Value, rhs: &Value) -> StrResult<Ordering> {
         (Relative(a), Length(b)) if a.rel.is_zero() => try_cmp_values(&a.abs, b)?,
         (Relative(a), Ratio(b)) if a.abs.is_zero() => a.rel.cmp(b),
 
+        (Dyn(a), Dyn(b)) => {
+            if let (Some(a), Some(b)) =
+                (a.downcast::<Datetime>(), b.downcast::<Datetime>())
+            {
+                return a.compare(b);
+            } else if let (Some(a), Some(b)) =
+                (a.downcast::<Duration>(), b.downcast::<Duration>())
+            {
+                return Ok(a.cmp(b));
+            } else if let (Some(a), Some(b)) =
+                (a.downcast::<Version>(), b.downcast::<Version>())
+            {
+                return Ok(a.cmp(b));
+            }
+
+            mismatch!("cannot compare {} and {}", lhs, rhs);
+        }
+
         _ => mismatch!("cannot compare {} and {}", lhs, rhs),
     })
 }
@@ -422,6 +517,7 @@ pub fn contains(lhs: &Value, rhs: &Value) -> Option<bool> {
     match (lhs, rhs) {
         (Str(a), Str(b)) => Some(b.as_str().contains(a.as_str())),
         (Dyn(a), Str(b)) => a.downcast::<Regex>().map(|regex| regex.is_match(b)),
+        (Str(a), Dyn(b)) => b.downcast::<TypeValue>().map(|ty| ty.name() == a.as_str()),
         (Str(a), Dict(b)) => Some(b.contains(a)),
         (a, Array(b)) => Some(b.contains(a)),
         _ => Option::None,
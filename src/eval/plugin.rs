@@ -0,0 +1,286 @@
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use ecow::{eco_format, EcoString};
+use wasmi::core::{TrapCode, ValueType};
+use wasmi::{
+    Caller, Config, Engine, Extern, ExternType, Linker, Module, Store, StoreLimits,
+    StoreLimitsBuilder,
+};
+
+use super::cast;
+use crate::diag::{bail, StrResult};
+use crate::util::Bytes;
+
+/// How many units of fuel a single plugin call may consume. Roughly one unit
+/// is spent per executed WebAssembly instruction.
+const FUEL: u64 = 250_000_000;
+
+/// How many bytes of linear memory a plugin may allocate.
+const MEMORY_LIMIT: usize = 256 * 1024 * 1024;
+
+/// The module that host functions are imported from.
+const ENV: &str = "typst_env";
+
+/// A plugin loaded from WebAssembly code.
+///
+/// Plugins communicate with the host through a minimal protocol: An exported
+/// function receives the lengths of its byte arguments as 32-bit integers,
+/// fetches the arguments themselves through the imported
+/// `wasm_minimal_protocol_write_args_to_buffer` function and hands its output
+/// to `wasm_minimal_protocol_send_result_to_host`. It returns zero on success
+/// and one if the output is an error message.
+///
+/// Each call runs in a fresh instance without access to the outside world
+/// (WASI is not available) and with bounded memory and fuel, so calls are
+/// deterministic and can be cached.
+#[derive(Clone)]
+pub struct Plugin(Arc<Repr>);
+
+/// The internal representation of a plugin.
+struct Repr {
+    /// The raw WebAssembly code.
+    bytes: Bytes,
+    /// The compiled module.
+    module: Module,
+    /// The names of the exported functions.
+    functions: Vec<EcoString>,
+}
+
+/// The state of a single plugin call.
+struct StoreData {
+    /// The arguments passed to the called function.
+    args: Vec<Bytes>,
+    /// The output the function sent to the host.
+    output: Vec<u8>,
+    /// An out of bounds memory access the plugin attempted.
+    memory_error: Option<MemoryError>,
+    /// The resource limits of the call.
+    limits: StoreLimits,
+}
+
+/// An out of bounds access to a plugin's memory.
+struct MemoryError {
+    offset: usize,
+    length: usize,
+    write: bool,
+}
+
+impl Plugin {
+    /// Compile a plugin from WebAssembly code.
+    #[comemo::memoize]
+    pub fn new(bytes: Bytes) -> StrResult<Plugin> {
+        let engine = Engine::new(Config::default().consume_fuel(true));
+        let module = Module::new(&engine, bytes.as_slice())
+            .map_err(|err| eco_format!("failed to load WebAssembly module ({err})"))?;
+
+        let exports_memory = module.exports().any(|export| {
+            export.name() == "memory" && matches!(export.ty(), ExternType::Memory(_))
+        });
+        if !exports_memory {
+            bail!("plugin does not export its memory");
+        }
+
+        let functions = module
+            .exports()
+            .filter(|export| matches!(export.ty(), ExternType::Func(_)))
+            .map(|export| export.name().into())
+            .collect();
+
+        // Instantiate once to surface missing imports and traps in the start
+        // function right away.
+        instantiate(&module, vec![])?;
+
+        Ok(Self(Arc::new(Repr { bytes, module, functions })))
+    }
+
+    /// Call the exported function with the given name.
+    #[comemo::memoize]
+    pub fn call(&self, name: &str, args: Vec<Bytes>) -> StrResult<Bytes> {
+        if !self.0.functions.iter().any(|func| func == name) {
+            bail!("plugin does not contain a function called `{name}`");
+        }
+
+        let (mut store, instance) = instantiate(&self.0.module, args)?;
+        let func = instance.get_func(&store, name).unwrap();
+        let ty = func.ty(&store);
+
+        // Check the function's signature.
+        if ty.params().iter().any(|&param| param != ValueType::I32) {
+            bail!(
+                "plugin function `{name}` has a parameter that is not a 32-bit integer"
+            );
+        }
+        if ty.results() != [ValueType::I32] {
+            bail!("plugin function `{name}` does not return exactly one 32-bit integer");
+        }
+
+        // Check the number of arguments.
+        let expected = ty.params().len();
+        let given = store.data().args.len();
+        if expected != given {
+            bail!(
+                "plugin function takes {expected} argument{}, but {given} {} given",
+                if expected == 1 { "" } else { "s" },
+                if given == 1 { "was" } else { "were" },
+            );
+        }
+
+        // The lengths of the arguments are passed as 32-bit integers.
+        let lengths = store
+            .data()
+            .args
+            .iter()
+            .map(|arg| match i32::try_from(arg.len()) {
+                Ok(len) => Ok(wasmi::Value::I32(len)),
+                Err(_) => bail!(
+                    "plugin argument is too large ({} bytes, must be less than 2 GiB)",
+                    arg.len()
+                ),
+            })
+            .collect::<StrResult<Vec<_>>>()?;
+
+        let mut code = wasmi::Value::I32(-1);
+        if let Err(err) = func.call(&mut store, &lengths, std::slice::from_mut(&mut code))
+        {
+            if matches!(
+                &err,
+                wasmi::Error::Trap(trap) if trap.trap_code() == Some(TrapCode::OutOfFuel)
+            ) {
+                bail!("plugin function `{name}` exceeded its execution budget");
+            }
+            bail!("plugin panicked: {err}");
+        }
+
+        if let Some(MemoryError { offset, length, write }) =
+            store.data_mut().memory_error.take()
+        {
+            let kind = if write { "write" } else { "read" };
+            bail!(
+                "plugin tried to {kind} out of bounds: \
+                 {length} bytes at offset {offset:#x}"
+            );
+        }
+
+        // Interpret the returned code.
+        let output = std::mem::take(&mut store.data_mut().output);
+        match code {
+            wasmi::Value::I32(0) => {}
+            wasmi::Value::I32(1) => match std::str::from_utf8(&output) {
+                Ok(message) => bail!("plugin errored with: {message}"),
+                Err(_) => {
+                    bail!("plugin errored, but did not return a valid error message")
+                }
+            },
+            _ => bail!("plugin did not respect the protocol"),
+        }
+
+        Ok(output.into())
+    }
+
+    /// The names of the functions the plugin exports.
+    pub fn functions(&self) -> impl Iterator<Item = &EcoString> {
+        self.0.functions.iter()
+    }
+}
+
+impl Debug for Plugin {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.pad("plugin(..)")
+    }
+}
+
+impl PartialEq for Plugin {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.bytes == other.0.bytes
+    }
+}
+
+impl Hash for Plugin {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.bytes.hash(state);
+    }
+}
+
+cast! {
+    type Plugin: "plugin",
+}
+
+/// Create a fresh, resource-limited instance of a plugin's module.
+fn instantiate(
+    module: &Module,
+    args: Vec<Bytes>,
+) -> StrResult<(Store<StoreData>, wasmi::Instance)> {
+    let engine = module.engine();
+    let limits = StoreLimitsBuilder::new().memory_size(MEMORY_LIMIT).build();
+    let data = StoreData { args, output: vec![], memory_error: None, limits };
+
+    let mut store = Store::new(engine, data);
+    store.limiter(|data| &mut data.limits);
+    store.add_fuel(FUEL).unwrap();
+
+    let mut linker = Linker::new(engine);
+    linker
+        .func_wrap(
+            ENV,
+            "wasm_minimal_protocol_write_args_to_buffer",
+            wasm_minimal_protocol_write_args_to_buffer,
+        )
+        .unwrap();
+    linker
+        .func_wrap(
+            ENV,
+            "wasm_minimal_protocol_send_result_to_host",
+            wasm_minimal_protocol_send_result_to_host,
+        )
+        .unwrap();
+
+    let instance = linker
+        .instantiate(&mut store, module)
+        .and_then(|pre| pre.start(&mut store))
+        .map_err(|err| eco_format!("failed to instantiate plugin ({err})"))?;
+
+    Ok((store, instance))
+}
+
+/// Write the arguments of the current call to the plugin's memory, one after
+/// another, starting at `ptr`.
+fn wasm_minimal_protocol_write_args_to_buffer(mut caller: Caller<StoreData>, ptr: u32) {
+    let memory = caller.get_export("memory").and_then(Extern::into_memory).unwrap();
+    let args = std::mem::take(&mut caller.data_mut().args);
+    let mut offset = ptr as usize;
+    for arg in args {
+        if memory.write(&mut caller, offset, arg.as_slice()).is_err() {
+            caller.data_mut().memory_error =
+                Some(MemoryError { offset, length: arg.len(), write: true });
+            return;
+        }
+        offset += arg.len();
+    }
+}
+
+/// Read the output of the current call from `len` bytes of the plugin's memory
+/// starting at `ptr`.
+fn wasm_minimal_protocol_send_result_to_host(
+    mut caller: Caller<StoreData>,
+    ptr: u32,
+    len: u32,
+) {
+    let memory = caller.get_export("memory").and_then(Extern::into_memory).unwrap();
+    let (offset, length) = (ptr as usize, len as usize);
+
+    // Check the bounds before allocating the buffer, so that a bogus length
+    // can't make us allocate gigabytes.
+    let size = memory.data(&caller).len();
+    if offset.checked_add(length).map_or(true, |end| end > size) {
+        caller.data_mut().memory_error =
+            Some(MemoryError { offset, length, write: false });
+        return;
+    }
+
+    let mut buffer = std::mem::take(&mut caller.data_mut().output);
+    buffer.resize(length, 0);
+    memory.read(&caller, offset, &mut buffer).unwrap();
+    caller.data_mut().output = buffer;
+}
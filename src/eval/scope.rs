@@ -5,7 +5,7 @@ use std::hash::Hash;
 use ecow::{eco_format, EcoString};
 
 use super::{IntoValue, Library, Value};
-use crate::diag::{bail, StrResult};
+use crate::diag::{bail, closest_match, HintedStrResult, HintedString, StrResult};
 
 /// A stack of scopes.
 #[derive(Debug, Default, Clone)]
@@ -37,44 +37,69 @@ impl<'a> Scopes<'a> {
     }
 
     /// Try to access a variable immutably.
-    pub fn get(&self, var: &str) -> StrResult<&Value> {
+    pub fn get(&self, var: &str) -> HintedStrResult<&Value> {
         std::iter::once(&self.top)
             .chain(self.scopes.iter().rev())
             .chain(self.base.map(|base| base.global.scope()))
             .find_map(|scope| scope.get(var))
-            .ok_or_else(|| unknown_variable(var))
+            .ok_or_else(|| self.unknown_variable(var, false))
     }
 
     /// Try to access a variable immutably in math.
-    pub fn get_in_math(&self, var: &str) -> StrResult<&Value> {
+    pub fn get_in_math(&self, var: &str) -> HintedStrResult<&Value> {
         std::iter::once(&self.top)
             .chain(self.scopes.iter().rev())
             .chain(self.base.map(|base| base.math.scope()))
             .find_map(|scope| scope.get(var))
-            .ok_or_else(|| eco_format!("unknown variable: {}", var))
+            .ok_or_else(|| self.unknown_variable(var, true))
     }
 
     /// Try to access a variable mutably.
-    pub fn get_mut(&mut self, var: &str) -> StrResult<&mut Value> {
+    pub fn get_mut(&mut self, var: &str) -> HintedStrResult<&mut Value> {
+        if !self.local(var) {
+            return Err(match self.base.and_then(|base| base.global.scope().get(var)) {
+                Some(_) => eco_format!("cannot mutate a constant: {}", var).into(),
+                None => self.unknown_variable(var, false),
+            });
+        }
+
         std::iter::once(&mut self.top)
             .chain(&mut self.scopes.iter_mut().rev())
             .find_map(|scope| scope.get_mut(var))
-            .ok_or_else(|| {
-                match self.base.and_then(|base| base.global.scope().get(var)) {
-                    Some(_) => eco_format!("cannot mutate a constant: {}", var),
-                    _ => unknown_variable(var),
-                }
-            })?
+            .unwrap()
+            .map_err(Into::into)
     }
-}
 
-/// The error message when a variable is not found.
-#[cold]
-fn unknown_variable(var: &str) -> EcoString {
-    if var.contains('-') {
-        eco_format!("unknown variable: {} - if you meant to use subtraction, try adding spaces around the minus sign.", var)
-    } else {
-        eco_format!("unknown variable: {}", var)
+    /// Whether a variable is defined in one of the non-global scopes.
+    fn local(&self, var: &str) -> bool {
+        std::iter::once(&self.top)
+            .chain(self.scopes.iter())
+            .any(|scope| scope.get(var).is_some())
+    }
+
+    /// The error when a variable is not found, with a suggestion of a similar
+    /// name that is in scope.
+    #[cold]
+    fn unknown_variable(&self, var: &str, math: bool) -> HintedString {
+        let mut error = HintedString::from(if var.contains('-') {
+            eco_format!("unknown variable: {} - if you meant to use subtraction, try adding spaces around the minus sign.", var)
+        } else {
+            eco_format!("unknown variable: {}", var)
+        });
+
+        let base = self.base.map(|base| match math {
+            true => base.math.scope(),
+            false => base.global.scope(),
+        });
+        let names = std::iter::once(&self.top)
+            .chain(self.scopes.iter().rev())
+            .chain(base)
+            .flat_map(|scope| scope.iter().map(|(name, _)| name.as_str()));
+        if let Some(similar) = closest_match(var, names) {
+            error.hints.push(eco_format!("did you mean `{similar}`?"));
+        }
+
+        error
     }
 }
 
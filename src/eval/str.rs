@@ -283,7 +283,8 @@ impl Str {
                 Replacement::Str(s) => output.push_str(s),
                 Replacement::Func(func) => {
                     let args = Args::new(func.span(), [dict]);
-                    let piece = func.call_vm(vm, args)?.cast::<Str>().at(func.span())?;
+                    let piece =
+                        func.call_vm(vm, args)?.cast::<Str>().at(func.output_span())?;
                     output.push_str(&piece);
                 }
             }
@@ -0,0 +1,64 @@
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{Hash, Hasher};
+
+use super::{cast, Func, Value};
+
+/// The type of a value, as returned by the `type` function.
+///
+/// Types compare equal by name. A type may additionally carry the function
+/// that constructs or converts into values of the type, such that, for
+/// example, `{type(1) == int}` holds.
+#[derive(Clone)]
+pub struct TypeValue {
+    /// The name of the type.
+    name: &'static str,
+    /// The constructor of the type, if it has one.
+    constructor: Option<Func>,
+}
+
+impl TypeValue {
+    /// The type of the given value.
+    pub fn of(value: &Value) -> Self {
+        Self { name: value.type_name(), constructor: None }
+    }
+
+    /// Attach the function that constructs values of this type.
+    pub fn with_constructor(mut self, constructor: Func) -> Self {
+        self.constructor = Some(constructor);
+        self
+    }
+
+    /// The name of the type.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The constructor of the type, if it has one.
+    pub fn constructor(&self) -> Option<&Func> {
+        self.constructor.as_ref()
+    }
+}
+
+impl Debug for TypeValue {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(self.name)
+    }
+}
+
+impl PartialEq for TypeValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Eq for TypeValue {}
+
+impl Hash for TypeValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+    }
+}
+
+cast! {
+    type TypeValue: "type",
+}
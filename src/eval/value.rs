@@ -9,7 +9,7 @@ use siphasher::sip128::{Hasher128, SipHasher13};
 
 use super::{
     cast, format_str, ops, Args, Array, CastInfo, Content, Dict, FromValue, Func,
-    IntoValue, Module, Reflect, Str, Symbol,
+    IntoValue, Module, Reflect, Str, Symbol, TypeValue, Version,
 };
 use crate::diag::StrResult;
 use crate::geom::{Abs, Angle, Color, Em, Fr, Length, Ratio, Rel};
@@ -132,6 +132,13 @@ impl Value {
             Self::Content(content) => content.at(field, None),
             Self::Module(module) => module.get(field).cloned(),
             Self::Func(func) => func.get(field).cloned(),
+            Self::Dyn(dynamic) => match dynamic.downcast::<Version>() {
+                Some(version) => version.field(field).map(IntoValue::into_value),
+                None => Err(eco_format!(
+                    "cannot access fields on type {}",
+                    dynamic.type_name()
+                )),
+            },
             v => Err(eco_format!("cannot access fields on type {}", v.type_name())),
         }
     }
@@ -417,10 +424,53 @@ primitive! { Content: "content",
 primitive! { Styles: "styles", Styles }
 primitive! { Array: "array", Array }
 primitive! { Dict: "dictionary", Dict }
-primitive! { Func: "function", Func }
 primitive! { Args: "arguments", Args }
 primitive! { Module: "module", Module }
 
+impl Type for Func {
+    const TYPE_NAME: &'static str = "function";
+}
+
+impl Reflect for Func {
+    fn describe() -> CastInfo {
+        CastInfo::Type(Self::TYPE_NAME)
+    }
+
+    fn castable(value: &Value) -> bool {
+        match value {
+            Value::Func(_) => true,
+            Value::Dyn(dynamic) => dynamic
+                .downcast::<TypeValue>()
+                .map_or(false, |ty| ty.constructor().is_some()),
+            _ => false,
+        }
+    }
+}
+
+impl IntoValue for Func {
+    fn into_value(self) -> Value {
+        Value::Func(self)
+    }
+}
+
+impl FromValue for Func {
+    fn from_value(value: Value) -> StrResult<Self> {
+        // Types with a constructor can be called like the constructor itself.
+        if let Value::Dyn(dynamic) = &value {
+            if let Some(constructor) =
+                dynamic.downcast::<TypeValue>().and_then(TypeValue::constructor)
+            {
+                return Ok(constructor.clone());
+            }
+        }
+
+        match value {
+            Value::Func(v) => Ok(v),
+            v => Err(eco_format!("expected function, found {}", v.type_name())),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
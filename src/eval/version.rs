@@ -0,0 +1,127 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Display, Formatter, Write};
+use std::hash::{Hash, Hasher};
+
+use ecow::{eco_format, EcoVec};
+
+use crate::diag::StrResult;
+use crate::eval::cast;
+
+/// A version with an arbitrary number of components.
+///
+/// Missing trailing components are treated as zero, so `1.2` and `1.2.0`
+/// are equal.
+#[derive(Clone)]
+pub struct Version(EcoVec<u32>);
+
+impl Version {
+    /// Create a version from its components.
+    pub fn new(components: impl IntoIterator<Item = u32>) -> Self {
+        Self(components.into_iter().collect())
+    }
+
+    /// The version of the compiler.
+    pub fn compiler() -> Self {
+        Self::new([
+            env!("CARGO_PKG_VERSION_MAJOR").parse().unwrap(),
+            env!("CARGO_PKG_VERSION_MINOR").parse().unwrap(),
+            env!("CARGO_PKG_VERSION_PATCH").parse().unwrap(),
+        ])
+    }
+
+    /// The component at the given index or zero if there is none.
+    pub fn component(&self, index: usize) -> u32 {
+        self.0.get(index).copied().unwrap_or(0)
+    }
+
+    /// The major version.
+    pub fn major(&self) -> u32 {
+        self.component(0)
+    }
+
+    /// The minor version.
+    pub fn minor(&self) -> u32 {
+        self.component(1)
+    }
+
+    /// The patch version.
+    pub fn patch(&self) -> u32 {
+        self.component(2)
+    }
+
+    /// Access a component by its name.
+    pub fn field(&self, field: &str) -> StrResult<u32> {
+        match field {
+            "major" => Ok(self.major()),
+            "minor" => Ok(self.minor()),
+            "patch" => Ok(self.patch()),
+            _ => Err(eco_format!("version does not have field {field:?}")),
+        }
+    }
+
+    /// Access a component by its index. Negative indices count from the back.
+    pub fn at(&self, index: i64) -> StrResult<u32> {
+        let len = self.0.len();
+        usize::try_from(index)
+            .ok()
+            .or_else(|| index.checked_add(len as i64).and_then(|i| i.try_into().ok()))
+            .and_then(|i| self.0.get(i).copied())
+            .ok_or_else(|| {
+                eco_format!("component index out of bounds (index: {index}, len: {len})")
+            })
+    }
+
+    /// The components without trailing zeros.
+    fn significant(&self) -> &[u32] {
+        let len = self.0.iter().rposition(|&c| c != 0).map_or(0, |i| i + 1);
+        &self.0[..len]
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.significant() == other.significant()
+    }
+}
+
+impl Eq for Version {}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.significant().cmp(other.significant())
+    }
+}
+
+impl Hash for Version {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.significant().hash(state);
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for (i, component) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_char('.')?;
+            }
+            write!(f, "{component}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for Version {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+cast! {
+    type Version: "version",
+}
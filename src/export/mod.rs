@@ -2,6 +2,12 @@
 
 mod pdf;
 mod render;
+mod svg;
+mod text;
 
-pub use self::pdf::pdf;
-pub use self::render::render;
+pub use self::pdf::{pdf, PdfOptions, PdfStandard};
+pub use self::render::{
+    render, render_bands, render_document, render_region, RenderedPage,
+};
+pub use self::svg::{svg, svg_merged};
+pub use self::text::text;
@@ -8,7 +8,7 @@ use unicode_general_category::GeneralCategory;
 
 use super::{deflate, EmExt, PdfContext, RefExt};
 use crate::font::Font;
-use crate::util::{Bytes, SliceExt};
+use crate::util::{hash128, Bytes, SliceExt};
 
 const CMAP_NAME: Name = Name(b"Custom");
 const SYSTEM_INFO: SystemInfo = SystemInfo {
@@ -36,7 +36,8 @@ pub fn write_fonts(ctx: &mut PdfContext) {
             .find_name(name_id::POST_SCRIPT_NAME)
             .unwrap_or_else(|| "unknown".to_string());
 
-        let base_font = eco_format!("ABCDEF+{}", postscript_name);
+        let subset_tag = subset_tag(glyph_set);
+        let base_font = eco_format!("{subset_tag}+{postscript_name}");
         let base_font = Name(base_font.as_bytes());
 
         // Write the base font object referencing the CID font.
@@ -162,6 +163,22 @@ fn subset_font(font: &Font, glyphs: &[u16]) -> Bytes {
     deflate(data).into()
 }
 
+/// Produce a unique six letter tag for a glyph set.
+///
+/// The tag distinguishes different subsets of the same font, which is required
+/// for them to coexist, for example when merging PDF files.
+fn subset_tag(glyph_set: &BTreeMap<u16, EcoString>) -> EcoString {
+    const LEN: usize = 6;
+    const BASE: u128 = 26;
+    let mut hash = hash128(&glyph_set.keys().collect::<Vec<_>>());
+    let mut letters = [b'A'; LEN];
+    for letter in &mut letters {
+        *letter = b'A' + (hash % BASE) as u8;
+        hash /= BASE;
+    }
+    std::str::from_utf8(&letters).unwrap().into()
+}
+
 /// Create a /ToUnicode CMap.
 fn create_cmap(
     ttf: &ttf_parser::Face,
@@ -202,3 +219,46 @@ fn create_cmap(
 
     cmap
 }
+
+#[cfg(test)]
+mod tests {
+    use ttf_parser::OutlineBuilder;
+
+    use super::*;
+
+    /// Discards a glyph's outline.
+    struct Sink;
+
+    impl OutlineBuilder for Sink {
+        fn move_to(&mut self, _: f32, _: f32) {}
+        fn line_to(&mut self, _: f32, _: f32) {}
+        fn quad_to(&mut self, _: f32, _: f32, _: f32, _: f32) {}
+        fn curve_to(&mut self, _: f32, _: f32, _: f32, _: f32, _: f32, _: f32) {}
+        fn close(&mut self) {}
+    }
+
+    #[test]
+    fn test_subset_cjk_font() {
+        let data = include_bytes!("../../../assets/fonts/NotoSerifCJKsc-Regular.otf");
+        let font = Font::new(Bytes::from_static(data), 0).unwrap();
+        let glyphs: Vec<u16> = "你好世界"
+            .chars()
+            .filter_map(|c| font.ttf().glyph_index(c))
+            .map(|id| id.0)
+            .collect();
+        assert_eq!(glyphs.len(), 4);
+
+        // The subset is a small fraction of the font, even before
+        // compression.
+        let subset =
+            subsetter::subset(data, 0, subsetter::Profile::pdf(&glyphs)).unwrap();
+        assert!(subset.len() < data.len() / 20);
+        assert!(subset_font(&font, &glyphs).len() < deflate(data).len() / 20);
+
+        // The glyphs keep their ids and outlines.
+        let face = ttf_parser::Face::parse(&subset, 0).unwrap();
+        for &id in &glyphs {
+            assert!(face.outline_glyph(GlyphId(id), &mut Sink).is_some());
+        }
+    }
+}
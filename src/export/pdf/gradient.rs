@@ -0,0 +1,124 @@
+use pdf_writer::{Finish, Name};
+
+use super::PdfContext;
+use crate::geom::{Gradient, GradientGeometry, Ratio, RgbaColor, Size, Transform};
+
+/// A gradient as it is placed on a page.
+///
+/// PDF shading patterns live in the default coordinate space of the page, so
+/// the same gradient needs a separate pattern for each bounding box it is
+/// spread over.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct PdfGradient {
+    /// The gradient.
+    pub gradient: Gradient,
+    /// The transform from the bounding box's coordinates to the page's
+    /// default coordinate space.
+    pub transform: Transform,
+    /// The size of the bounding box.
+    pub size: Size,
+}
+
+/// Embed all used gradients as shading patterns into the PDF.
+#[tracing::instrument(skip_all)]
+pub fn write_gradients(ctx: &mut PdfContext) {
+    for PdfGradient { gradient, transform, size } in ctx.gradient_map.items() {
+        let pattern_ref = ctx.alloc.bump();
+        ctx.gradient_refs.push(pattern_ref);
+
+        let Transform { sx, ky, kx, sy, tx, ty } = *transform;
+        let mut pattern = ctx.writer.indirect(pattern_ref).dict();
+        pattern.pair(Name(b"Type"), Name(b"Pattern"));
+        pattern.pair(Name(b"PatternType"), 2);
+        pattern.insert(Name(b"Matrix")).array().items([
+            sx.get() as f32,
+            ky.get() as f32,
+            kx.get() as f32,
+            sy.get() as f32,
+            tx.to_pt() as f32,
+            ty.to_pt() as f32,
+        ]);
+
+        let mut shading = pattern.insert(Name(b"Shading")).dict();
+        shading.pair(Name(b"ColorSpace"), Name(b"DeviceRGB"));
+        match gradient.geometry(*size) {
+            GradientGeometry::Linear(start, end) => {
+                shading.pair(Name(b"ShadingType"), 2);
+                shading
+                    .insert(Name(b"Coords"))
+                    .array()
+                    .items([start.x, start.y, end.x, end.y].map(|v| v.to_pt() as f32));
+            }
+            GradientGeometry::Radial(center, radius) => {
+                let (x, y, r) = (center.x.to_pt(), center.y.to_pt(), radius.to_pt());
+                shading.pair(Name(b"ShadingType"), 3);
+                shading
+                    .insert(Name(b"Coords"))
+                    .array()
+                    .items([x, y, 0.0, x, y, r].map(|v| v as f32));
+            }
+        }
+        shading.insert(Name(b"Extend")).array().items([true, true]);
+
+        // Stitch the segments between the stops together, leaving out empty
+        // segments at hard color changes.
+        let segments = segments(&gradient.sampled_stops());
+        let mut function = shading.insert(Name(b"Function")).dict();
+        function.pair(Name(b"FunctionType"), 3);
+        function.insert(Name(b"Domain")).array().items([0.0_f32, 1.0]);
+        function
+            .insert(Name(b"Bounds"))
+            .array()
+            .items(segments.iter().skip(1).map(|(start, _, _)| *start));
+        function
+            .insert(Name(b"Encode"))
+            .array()
+            .items(segments.iter().flat_map(|_| [0.0_f32, 1.0]));
+
+        let mut functions = function.insert(Name(b"Functions")).array();
+        for (_, from, to) in &segments {
+            let mut exponential = functions.push().dict();
+            exponential.pair(Name(b"FunctionType"), 2);
+            exponential.insert(Name(b"Domain")).array().items([0.0_f32, 1.0]);
+            exponential.insert(Name(b"C0")).array().items(components(*from));
+            exponential.insert(Name(b"C1")).array().items(components(*to));
+            exponential.pair(Name(b"N"), 1.0_f32);
+            exponential.finish();
+        }
+
+        functions.finish();
+        function.finish();
+        shading.finish();
+        pattern.finish();
+    }
+}
+
+/// Split the unit interval into segments between stops, each given by its
+/// start and the colors at its ends.
+fn segments(stops: &[(RgbaColor, Ratio)]) -> Vec<(f32, RgbaColor, RgbaColor)> {
+    let (first, _) = stops[0];
+    let (last, end) = stops[stops.len() - 1];
+
+    let mut segments = vec![];
+    if stops[0].1.get() > 0.0 {
+        segments.push((0.0, first, first));
+    }
+
+    for pair in stops.windows(2) {
+        let [(c0, t0), (c1, t1)] = [pair[0], pair[1]];
+        if t1.get() > t0.get() {
+            segments.push((t0.get() as f32, c0, c1));
+        }
+    }
+
+    if end.get() < 1.0 || segments.is_empty() {
+        segments.push((end.get() as f32, last, last));
+    }
+
+    segments
+}
+
+/// The RGB components of a color for a PDF function.
+fn components(color: RgbaColor) -> [f32; 3] {
+    [color.r, color.g, color.b].map(|c| c as f32 / 255.0)
+}
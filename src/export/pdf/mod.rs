@@ -1,38 +1,64 @@
 //! Exporting into PDF documents.
 
 mod font;
+mod gradient;
 mod image;
 mod outline;
 mod page;
+mod pattern;
+mod pdfa;
 
 use std::cmp::Eq;
 use std::collections::{BTreeMap, HashMap};
 use std::hash::Hash;
+use std::num::NonZeroUsize;
 
 use ecow::EcoString;
 use pdf_writer::types::Direction;
 use pdf_writer::{Finish, Name, PdfWriter, Ref, TextStr};
-use xmp_writer::{LangId, RenditionClass, XmpWriter};
+use xmp_writer::{DateTime, LangId, RenditionClass, XmpWriter};
 
+pub use self::pdfa::{PdfOptions, PdfStandard};
+
+use self::gradient::PdfGradient;
 use self::page::Page;
-use crate::doc::{Document, Lang};
+use self::pattern::PdfPattern;
+use crate::diag::SourceResult;
+use crate::doc::{Document, Lang, PageLabel, PageLabelStyle};
+use crate::eval::Datetime;
 use crate::font::Font;
-use crate::geom::{Abs, Dir, Em};
+use crate::geom::{Abs, Dir, Em, Pattern};
 use crate::image::Image;
 use crate::model::Introspector;
+use crate::util::hash128;
 
 /// Export a document into a PDF file.
 ///
-/// Returns the raw bytes making up the PDF file.
+/// Returns the raw bytes making up the PDF file. Fails if the document uses
+/// features that cannot be represented in the requested PDF standard.
 #[tracing::instrument(skip_all)]
-pub fn pdf(document: &Document) -> Vec<u8> {
-    let mut ctx = PdfContext::new(document);
+pub fn pdf(document: &Document, options: &PdfOptions) -> SourceResult<Vec<u8>> {
+    if options.standard == PdfStandard::A2b {
+        pdfa::validate(document)?;
+    }
+
+    let mut ctx = PdfContext::new(document, options.standard);
     page::construct_pages(&mut ctx, &document.pages);
     font::write_fonts(&mut ctx);
     image::write_images(&mut ctx);
+    gradient::write_gradients(&mut ctx);
+    pattern::write_patterns(&mut ctx);
     page::write_page_tree(&mut ctx);
     write_catalog(&mut ctx);
-    ctx.writer.finish()
+
+    // PDF/A requires a file identifier. It is derived from the document so
+    // that exporting the same document twice yields the same file.
+    if options.standard == PdfStandard::A2b {
+        let id = format!("{:032x}", hash128(document)).into_bytes();
+        ctx.writer.set_file_id((id.clone(), id));
+    }
+
+    Ok(ctx.writer.finish())
 }
 
 /// Identifies the color space definitions.
@@ -42,17 +68,25 @@ const D65_GRAY: Name<'static> = Name(b"d65gray");
 /// Context for exporting a whole PDF document.
 pub struct PdfContext<'a> {
     document: &'a Document,
+    standard: PdfStandard,
     introspector: Introspector,
     writer: PdfWriter,
     pages: Vec<Page>,
     page_heights: Vec<f32>,
     alloc: Ref,
     page_tree_ref: Ref,
+    global_resources_ref: Ref,
     font_refs: Vec<Ref>,
     image_refs: Vec<Ref>,
+    gradient_refs: Vec<Ref>,
+    pattern_refs: Vec<Ref>,
     page_refs: Vec<Ref>,
     font_map: Remapper<Font>,
     image_map: Remapper<Image>,
+    gradient_map: Remapper<PdfGradient>,
+    pattern_map: Remapper<PdfPattern>,
+    /// The compressed content streams of the patterns' tiles.
+    pattern_tiles: HashMap<Pattern, Vec<u8>>,
     /// For each font a mapping from used glyphs to their text representation.
     /// May contain multiple chars in case of ligatures or similar things. The
     /// same glyph can have a different text representation within one document,
@@ -64,22 +98,30 @@ pub struct PdfContext<'a> {
 }
 
 impl<'a> PdfContext<'a> {
-    fn new(document: &'a Document) -> Self {
+    fn new(document: &'a Document, standard: PdfStandard) -> Self {
         let mut alloc = Ref::new(1);
         let page_tree_ref = alloc.bump();
+        let global_resources_ref = alloc.bump();
         Self {
             document,
+            standard,
             introspector: Introspector::new(&document.pages),
             writer: PdfWriter::new(),
             pages: vec![],
             page_heights: vec![],
             alloc,
             page_tree_ref,
+            global_resources_ref,
             page_refs: vec![],
             font_refs: vec![],
             image_refs: vec![],
+            gradient_refs: vec![],
+            pattern_refs: vec![],
             font_map: Remapper::new(),
             image_map: Remapper::new(),
+            gradient_map: Remapper::new(),
+            pattern_map: Remapper::new(),
+            pattern_tiles: HashMap::new(),
             glyph_sets: HashMap::new(),
             languages: HashMap::new(),
         }
@@ -114,9 +156,33 @@ fn write_catalog(ctx: &mut PdfContext) {
 
     let authors = &ctx.document.author;
     if !authors.is_empty() {
-        info.author(TextStr(&authors.join(", ")));
-        xmp.creator(authors.iter().map(|s| s.as_str()));
+        let joined = authors.join(", ");
+        info.author(TextStr(&joined));
+
+        // PDF/A requires the XMP creator to match the info dictionary's author.
+        if ctx.standard == PdfStandard::A2b {
+            xmp.creator([joined.as_str()]);
+        } else {
+            xmp.creator(authors.iter().map(|s| s.as_str()));
+        }
     }
+
+    let keywords = &ctx.document.keywords;
+    if !keywords.is_empty() {
+        let joined = keywords.join(", ");
+        info.keywords(TextStr(&joined));
+        xmp.pdf_keywords(&joined);
+    }
+
+    if let Some(date) = ctx.document.date {
+        if let Some(pdf_date) = pdf_date(date) {
+            info.creation_date(pdf_date);
+        }
+        if let Some(xmp_date) = xmp_date(date) {
+            xmp.create_date(xmp_date);
+        }
+    }
+
     info.creator(TextStr("Typst"));
     info.finish();
     xmp.creator_tool("Typst");
@@ -126,19 +192,32 @@ fn write_catalog(ctx: &mut PdfContext) {
     xmp.rendition_class(RenditionClass::Proof);
     xmp.pdf_version("1.7");
 
+    if ctx.standard == PdfStandard::A2b {
+        xmp.pdfa_part("2");
+        xmp.pdfa_conformance("B");
+    }
+
     let xmp_buf = xmp.finish(None);
+
     let meta_ref = ctx.alloc.bump();
     let mut meta_stream = ctx.writer.stream(meta_ref, xmp_buf.as_bytes());
     meta_stream.pair(Name(b"Type"), Name(b"Metadata"));
     meta_stream.pair(Name(b"Subtype"), Name(b"XML"));
     meta_stream.finish();
 
+    let output_intent_ref =
+        (ctx.standard == PdfStandard::A2b).then(|| pdfa::write_output_intent(ctx));
+
     // Write the document catalog.
     let mut catalog = ctx.writer.catalog(ctx.alloc.bump());
     catalog.pages(ctx.page_tree_ref);
     catalog.viewer_preferences().direction(dir);
     catalog.pair(Name(b"Metadata"), meta_ref);
 
+    if let Some(output_intent_ref) = output_intent_ref {
+        catalog.insert(Name(b"OutputIntents")).array().item(output_intent_ref);
+    }
+
     if let Some(outline_root_id) = outline_root_id {
         catalog.outlines(outline_root_id);
     }
@@ -146,6 +225,94 @@ fn write_catalog(ctx: &mut PdfContext) {
     if let Some(lang) = lang {
         catalog.lang(TextStr(lang.as_str()));
     }
+
+    if !ctx.document.page_labels.is_empty() {
+        let mut labels = catalog.insert(Name(b"PageLabels")).dict();
+        let mut nums = labels.insert(Name(b"Nums")).array();
+        for (i, label) in page_label_ranges(&ctx.document.page_labels) {
+            nums.item(i as i32);
+            let mut dict = nums.push().dict();
+            if let Some(style) = label.style {
+                dict.pair(Name(b"S"), Name(page_label_style(style)));
+            }
+            if let Some(prefix) = &label.prefix {
+                dict.pair(Name(b"P"), TextStr(prefix));
+            }
+            if let Some(offset) = label.offset {
+                dict.pair(Name(b"St"), offset.get() as i32);
+            }
+        }
+    }
+}
+
+/// Find the pages at which a new page label range starts, that is, where the
+/// label does not continue the one of the previous page.
+fn page_label_ranges(labels: &[PageLabel]) -> Vec<(usize, &PageLabel)> {
+    let mut ranges: Vec<(usize, &PageLabel)> = vec![];
+    let mut prev: Option<&PageLabel> = None;
+    for (i, label) in labels.iter().enumerate() {
+        let number = |label: &PageLabel| label.offset.map_or(1, NonZeroUsize::get);
+        let continues = prev.map_or(false, |prev| {
+            label.style.is_some()
+                && prev.style == label.style
+                && prev.prefix == label.prefix
+                && number(prev) + 1 == number(label)
+        });
+        if !continues {
+            ranges.push((i, label));
+        }
+        prev = Some(label);
+    }
+    ranges
+}
+
+/// The PDF name of a page label style.
+fn page_label_style(style: PageLabelStyle) -> &'static [u8] {
+    match style {
+        PageLabelStyle::Arabic => b"D",
+        PageLabelStyle::LowerRoman => b"r",
+        PageLabelStyle::UpperRoman => b"R",
+        PageLabelStyle::LowerAlpha => b"a",
+        PageLabelStyle::UpperAlpha => b"A",
+    }
+}
+
+/// Convert a datetime to a PDF date.
+fn pdf_date(datetime: Datetime) -> Option<pdf_writer::Date> {
+    let year = datetime.year().filter(|&y| y >= 0)? as u16;
+
+    let mut pdf_date = pdf_writer::Date::new(year);
+    if let Some(month) = datetime.month() {
+        pdf_date = pdf_date.month(month);
+    }
+    if let Some(day) = datetime.day() {
+        pdf_date = pdf_date.day(day);
+    }
+    if let Some(hour) = datetime.hour() {
+        pdf_date = pdf_date.hour(hour);
+    }
+    if let Some(minute) = datetime.minute() {
+        pdf_date = pdf_date.minute(minute);
+    }
+    if let Some(second) = datetime.second() {
+        pdf_date = pdf_date.second(second);
+    }
+
+    Some(pdf_date)
+}
+
+/// Convert a datetime to an XMP date.
+fn xmp_date(datetime: Datetime) -> Option<DateTime> {
+    let year = datetime.year().filter(|&y| y >= 0)? as u16;
+    Some(DateTime {
+        year,
+        month: datetime.month(),
+        day: datetime.day(),
+        hour: datetime.hour(),
+        minute: datetime.minute(),
+        second: datetime.second(),
+        timezone: None,
+    })
 }
 
 /// Compress data with the DEFLATE algorithm.
@@ -233,3 +400,113 @@ impl RefExt for Ref {
         prev
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::doc::{Destination, Frame, FrameItem, GroupItem, Meta};
+    use crate::geom::{Angle, Point, Size, Transform};
+
+    /// A document with one empty page.
+    fn document() -> Document {
+        Document {
+            pages: vec![Frame::new(Size::splat(Abs::pt(100.0)))],
+            title: Some("Test".into()),
+            date: Datetime::from_ymd_hms(2023, 7, 1, 12, 30, 0),
+            ..Document::default()
+        }
+    }
+
+    #[test]
+    fn test_pdf_is_reproducible() {
+        let export = || pdf(&document(), &PdfOptions::default()).unwrap();
+        let first = export();
+        assert_eq!(first, export());
+
+        // The creation date is the document's, not the time of the export.
+        let text = String::from_utf8_lossy(&first);
+        assert!(text.contains("D:20230701123000"));
+    }
+
+    #[test]
+    fn test_pdfa_identification() {
+        let document = document();
+        let options = PdfOptions { standard: PdfStandard::A2b };
+        let pdf = pdf(&document, &options).unwrap();
+        let pdf = String::from_utf8_lossy(&pdf);
+        let id = format!("{:032x}", hash128(&document));
+        assert!(pdf.contains("/ID [") && pdf.contains(&id));
+        assert!(pdf.contains("pdfaid:part"));
+    }
+
+    #[test]
+    fn test_pdf_link_annotations() {
+        let link = |width: f64| {
+            let dest = Destination::Url("https://typst.app".into());
+            FrameItem::Meta(Meta::Link(dest), Size::new(Abs::pt(width), Abs::pt(10.0)))
+        };
+
+        // A link that wraps across two lines and one in a rotated group.
+        let mut inner = Frame::new(Size::new(Abs::pt(20.0), Abs::pt(10.0)));
+        inner.push(Point::zero(), link(20.0));
+        let mut group = GroupItem::new(inner);
+        group.transform = Transform::rotate(Angle::deg(90.0));
+
+        let mut page = Frame::new(Size::splat(Abs::pt(100.0)));
+        page.push(Point::splat(Abs::pt(10.0)), link(30.0));
+        page.push(Point::with_y(Abs::pt(30.0)), link(20.0));
+        page.push(Point::splat(Abs::pt(50.0)), FrameItem::Group(group));
+
+        let document = Document { pages: vec![page], ..Document::default() };
+        let pdf = pdf(&document, &PdfOptions::default()).unwrap();
+        let pdf = String::from_utf8_lossy(&pdf);
+
+        // The rounded numbers of all arrays with the given key.
+        let arrays = |key: &str| {
+            pdf.match_indices(&format!("/{key} ["))
+                .map(|(i, m)| {
+                    let rest = &pdf[i + m.len()..];
+                    rest[..rest.find(']').unwrap()]
+                        .split_whitespace()
+                        .map(|v| v.parse::<f32>().unwrap().round())
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // Each line of the wrapped link gets its own rectangle. The y-axis
+        // points upwards in PDF.
+        assert_eq!(pdf.matches("/Subtype /Link").count(), 3);
+        assert_eq!(
+            arrays("Rect"),
+            [[10.0, 90.0, 40.0, 80.0], [0.0, 70.0, 20.0, 60.0], [40.0, 50.0, 50.0, 30.0]]
+        );
+
+        // Only the rotated link needs a quadrilateral.
+        assert_eq!(
+            arrays("QuadPoints"),
+            [[40.0, 50.0, 40.0, 30.0, 50.0, 30.0, 50.0, 50.0]]
+        );
+    }
+
+    #[test]
+    fn test_page_label_ranges() {
+        let label = |style, number| PageLabel {
+            style: Some(style),
+            prefix: None,
+            offset: NonZeroUsize::new(number),
+        };
+
+        let labels = [
+            label(PageLabelStyle::LowerRoman, 1),
+            label(PageLabelStyle::LowerRoman, 2),
+            label(PageLabelStyle::Arabic, 1),
+            label(PageLabelStyle::Arabic, 2),
+            label(PageLabelStyle::Arabic, 5),
+        ];
+
+        let starts: Vec<_> =
+            page_label_ranges(&labels).into_iter().map(|(i, _)| i).collect();
+        assert_eq!(starts, [0, 2, 4]);
+    }
+}
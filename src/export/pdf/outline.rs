@@ -11,8 +11,15 @@ use crate::model::Content;
 pub fn write_outline(ctx: &mut PdfContext) -> Option<Ref> {
     let mut tree: Vec<HeadingNode> = vec![];
     for heading in ctx.introspector.query(&item!(heading_func).select()) {
+        if !heading.expect_field::<bool>("outlined") {
+            continue;
+        }
+
         let leaf = HeadingNode::leaf((*heading).clone());
 
+        // Descend as long as the last heading is of a lower level. Headings
+        // that skip levels are thus nested directly below the closest
+        // preceding heading of a lower level.
         let mut children = &mut tree;
         while children.last().map_or(false, |last| last.level < leaf.level) {
             children = &mut children.last_mut().unwrap().children;
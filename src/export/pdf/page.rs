@@ -1,16 +1,19 @@
-use ecow::eco_format;
+use ecow::{eco_format, EcoString};
 use pdf_writer::types::{
-    ActionType, AnnotationType, ColorSpaceOperand, LineCapStyle, LineJoinStyle,
+    ActionType, AnnotationFlags, AnnotationType, ColorSpaceOperand, LineCapStyle,
+    LineJoinStyle,
 };
-use pdf_writer::writers::ColorSpace;
+use pdf_writer::writers::{ColorSpace, Resources};
 use pdf_writer::{Content, Filter, Finish, Name, Rect, Ref, Str};
 
-use super::{deflate, AbsExt, EmExt, PdfContext, RefExt, D65_GRAY, SRGB};
+use super::gradient::PdfGradient;
+use super::pattern::PdfPattern;
+use super::{deflate, AbsExt, EmExt, PdfContext, PdfStandard, RefExt, D65_GRAY, SRGB};
 use crate::doc::{Destination, Frame, FrameItem, GroupItem, Meta, TextItem};
 use crate::font::Font;
 use crate::geom::{
-    self, Abs, Color, Em, Geometry, LineCap, LineJoin, Numeric, Paint, Point, Ratio,
-    Shape, Size, Stroke, Transform,
+    self, Abs, Color, Em, FillRule, Geometry, Gradient, LineCap, LineJoin, Numeric,
+    Paint, Pattern, Point, Ratio, RelativeTo, Shape, Size, Stroke, Transform,
 };
 use crate::image::Image;
 
@@ -31,7 +34,6 @@ pub fn construct_page(ctx: &mut PdfContext, frame: &Frame) {
 
     let mut ctx = PageContext {
         parent: ctx,
-        page_ref,
         content: Content::new(),
         state: State::default(),
         saves: vec![],
@@ -52,19 +54,45 @@ pub fn construct_page(ctx: &mut PdfContext, frame: &Frame) {
         ty: size.y,
     });
 
+    // The page is the container for parent-relative gradients.
+    ctx.state.container = (ctx.state.transform, size);
+
     // Encode the page into the content stream.
     write_frame(&mut ctx, frame);
 
     let page = Page {
         size,
         content: ctx.content,
-        id: ctx.page_ref,
+        id: page_ref,
         links: ctx.links,
     };
 
     ctx.parent.pages.push(page);
 }
 
+/// Construct the content stream of a pattern's tile.
+///
+/// The tile is encoded in its own coordinate system with the y-axis pointing
+/// downwards. The pattern's matrix maps it to wherever the pattern is used.
+pub fn construct_tile(ctx: &mut PdfContext, pattern: &Pattern) -> Vec<u8> {
+    let mut ctx = PageContext {
+        parent: ctx,
+        content: Content::new(),
+        state: State::default(),
+        saves: vec![],
+        bottom: 0.0,
+        links: vec![],
+    };
+
+    // The tile is the container for parent-relative gradients and patterns.
+    ctx.state.container = (Transform::identity(), pattern.size());
+
+    // Links in the tile are dropped since annotations can't be repeated
+    // together with the tiles.
+    write_frame(&mut ctx, pattern.frame());
+    ctx.content.finish()
+}
+
 /// Write the page tree.
 #[tracing::instrument(skip_all)]
 pub fn write_page_tree(ctx: &mut PdfContext) {
@@ -76,8 +104,12 @@ pub fn write_page_tree(ctx: &mut PdfContext) {
     pages
         .count(ctx.page_refs.len() as i32)
         .kids(ctx.page_refs.iter().copied());
+    pages.pair(Name(b"Resources"), ctx.global_resources_ref);
+    pages.finish();
 
-    let mut resources = pages.resources();
+    // The resources are shared by all pages and pattern tiles.
+    let mut resources =
+        ctx.writer.indirect(ctx.global_resources_ref).start::<Resources>();
     let mut spaces = resources.color_spaces();
     spaces.insert(SRGB).start::<ColorSpace>().srgb();
     spaces.insert(D65_GRAY).start::<ColorSpace>().d65_gray();
@@ -98,8 +130,20 @@ pub fn write_page_tree(ctx: &mut PdfContext) {
     }
 
     images.finish();
+
+    let mut patterns = resources.insert(Name(b"Pattern")).dict();
+    for (pattern_ref, gr) in ctx.gradient_map.pdf_indices(&ctx.gradient_refs) {
+        let name = eco_format!("Gr{}", gr);
+        patterns.pair(Name(name.as_bytes()), pattern_ref);
+    }
+
+    for (pattern_ref, pa) in ctx.pattern_map.pdf_indices(&ctx.pattern_refs) {
+        let name = eco_format!("Pa{}", pa);
+        patterns.pair(Name(name.as_bytes()), pattern_ref);
+    }
+
+    patterns.finish();
     resources.finish();
-    pages.finish();
 }
 
 /// Write a page tree node.
@@ -116,11 +160,19 @@ fn write_page(ctx: &mut PdfContext, page: Page) {
     page_writer.contents(content_id);
 
     let mut annotations = page_writer.annotations();
-    for (dest, rect) in page.links {
+    for (dest, rect, quad) in page.links {
         let mut annotation = annotations.push();
         annotation.subtype(AnnotationType::Link).rect(rect);
+        if let Some(quad) = quad {
+            annotation.quad_points(quad);
+        }
         annotation.border(0.0, 0.0, 0.0, None);
 
+        // PDF/A requires annotations to be printable.
+        if ctx.standard == PdfStandard::A2b {
+            annotation.flags(AnnotationFlags::PRINT);
+        }
+
         let pos = match dest {
             Destination::Url(uri) => {
                 annotation
@@ -161,26 +213,30 @@ pub struct Page {
     pub size: Size,
     /// The page's content stream.
     pub content: Content,
-    /// Links in the PDF coordinate system.
-    pub links: Vec<(Destination, Rect)>,
+    /// Links in the PDF coordinate system, with their exact quadrilateral if
+    /// they are rotated or skewed.
+    pub links: Vec<(Destination, Rect, Option<[f32; 8]>)>,
 }
 
 /// An exporter for the contents of a single PDF page.
 struct PageContext<'a, 'b> {
     parent: &'a mut PdfContext<'b>,
-    page_ref: Ref,
     content: Content,
     state: State,
     saves: Vec<State>,
     bottom: f32,
-    links: Vec<(Destination, Rect)>,
+    links: Vec<(Destination, Rect, Option<[f32; 8]>)>,
 }
 
 /// A simulated graphics state used to deduplicate graphics state changes and
-/// keep track of the current transformation matrix for link annotations.
+/// keep track of the current transformation matrix for link annotations,
+/// gradients, and patterns.
 #[derive(Debug, Default, Clone)]
 struct State {
     transform: Transform,
+    /// The transform and size of the container that parent-relative
+    /// gradients are spread over and parent-relative patterns are aligned to.
+    container: (Transform, Size),
     font: Option<(Font, Abs)>,
     fill: Option<Paint>,
     fill_space: Option<Name<'static>>,
@@ -221,16 +277,35 @@ impl PageContext<'_, '_> {
         }
     }
 
-    fn set_fill(&mut self, fill: &Paint) {
+    /// Set the fill for an element whose own bounding box is given by the
+    /// transform from its coordinates to the page and its size.
+    fn set_fill(&mut self, fill: &Paint, on_text: bool, own: (Transform, Size)) {
+        // Gradients and patterns depend on the position of the element, so
+        // they are always set anew.
+        let name = match fill {
+            Paint::Gradient(gradient) => Some(self.gradient(gradient, on_text, own)),
+            Paint::Pattern(pattern) => Some(self.pattern(pattern, on_text, own)),
+            Paint::Solid(_) => None,
+        };
+
+        if let Some(name) = name {
+            self.reset_fill_color_space();
+            self.content.set_fill_color_space(ColorSpaceOperand::Pattern);
+            self.content.set_fill_pattern(None, Name(name.as_bytes()));
+            self.state.fill = None;
+            return;
+        }
+
         if self.state.fill.as_ref() != Some(fill) {
             let f = |c| c as f32 / 255.0;
-            let Paint::Solid(color) = fill;
+            let Paint::Solid(color) = fill else { unreachable!() };
             match color {
                 Color::Luma(c) => {
                     self.set_fill_color_space(D65_GRAY);
                     self.content.set_fill_gray(f(c.0));
                 }
-                Color::Rgba(c) => {
+                Color::Rgba(_) | Color::Oklab(_) => {
+                    let c = color.to_rgba();
                     self.set_fill_color_space(SRGB);
                     self.content.set_fill_color([f(c.r), f(c.g), f(c.b)]);
                 }
@@ -243,6 +318,50 @@ impl PageContext<'_, '_> {
         }
     }
 
+    /// Register a gradient as a shading pattern for an element and return the
+    /// pattern's resource name.
+    fn gradient(
+        &mut self,
+        gradient: &Gradient,
+        on_text: bool,
+        own: (Transform, Size),
+    ) -> EcoString {
+        let (transform, size) = match gradient.unwrap_relative(on_text) {
+            RelativeTo::Self_ => own,
+            RelativeTo::Parent => self.state.container,
+        };
+
+        let pdf_gradient = PdfGradient { gradient: gradient.clone(), transform, size };
+        self.parent.gradient_map.insert(pdf_gradient.clone());
+        eco_format!("Gr{}", self.parent.gradient_map.map(pdf_gradient))
+    }
+
+    /// Register a pattern as a tiling pattern for an element and return the
+    /// pattern's resource name.
+    ///
+    /// The tile's content is encoded the first time a pattern is used, so
+    /// that the fonts and images it uses end up in the document's resources.
+    fn pattern(
+        &mut self,
+        pattern: &Pattern,
+        on_text: bool,
+        own: (Transform, Size),
+    ) -> EcoString {
+        let (transform, _) = match pattern.unwrap_relative(on_text) {
+            RelativeTo::Self_ => own,
+            RelativeTo::Parent => self.state.container,
+        };
+
+        if !self.parent.pattern_tiles.contains_key(pattern) {
+            let content = construct_tile(self.parent, pattern);
+            self.parent.pattern_tiles.insert(pattern.clone(), deflate(&content));
+        }
+
+        let pdf_pattern = PdfPattern { pattern: pattern.clone(), transform };
+        self.parent.pattern_map.insert(pdf_pattern.clone());
+        eco_format!("Pa{}", self.parent.pattern_map.map(pdf_pattern))
+    }
+
     fn set_fill_color_space(&mut self, space: Name<'static>) {
         if self.state.fill_space != Some(space) {
             self.content.set_fill_color_space(ColorSpaceOperand::Named(space));
@@ -254,8 +373,12 @@ impl PageContext<'_, '_> {
         self.state.fill_space = None;
     }
 
-    fn set_stroke(&mut self, stroke: &Stroke) {
-        if self.state.stroke.as_ref() != Some(stroke) {
+    /// Set the stroke for an element whose own bounding box is given by the
+    /// transform from its coordinates to the page and its size.
+    fn set_stroke(&mut self, stroke: &Stroke, own: (Transform, Size)) {
+        // Like for fills, gradients and patterns are always set anew.
+        let positioned = matches!(stroke.paint, Paint::Gradient(_) | Paint::Pattern(_));
+        if self.state.stroke.as_ref() != Some(stroke) || positioned {
             let Stroke {
                 paint,
                 thickness,
@@ -266,17 +389,29 @@ impl PageContext<'_, '_> {
             } = stroke;
 
             let f = |c| c as f32 / 255.0;
-            let Paint::Solid(color) = paint;
-            match color {
-                Color::Luma(c) => {
+            match paint {
+                Paint::Gradient(gradient) => {
+                    let name = self.gradient(gradient, false, own);
+                    self.reset_stroke_color_space();
+                    self.content.set_stroke_color_space(ColorSpaceOperand::Pattern);
+                    self.content.set_stroke_pattern(None, Name(name.as_bytes()));
+                }
+                Paint::Pattern(pattern) => {
+                    let name = self.pattern(pattern, false, own);
+                    self.reset_stroke_color_space();
+                    self.content.set_stroke_color_space(ColorSpaceOperand::Pattern);
+                    self.content.set_stroke_pattern(None, Name(name.as_bytes()));
+                }
+                Paint::Solid(Color::Luma(c)) => {
                     self.set_stroke_color_space(D65_GRAY);
                     self.content.set_stroke_gray(f(c.0));
                 }
-                Color::Rgba(c) => {
+                Paint::Solid(color @ (Color::Rgba(_) | Color::Oklab(_))) => {
+                    let c = color.to_rgba();
                     self.set_stroke_color_space(SRGB);
                     self.content.set_stroke_color([f(c.r), f(c.g), f(c.b)]);
                 }
-                Color::Cmyk(c) => {
+                Paint::Solid(Color::Cmyk(c)) => {
                     self.reset_stroke_color_space();
                     self.content.set_stroke_cmyk(f(c.c), f(c.m), f(c.y), f(c.k));
                 }
@@ -345,14 +480,13 @@ fn write_group(ctx: &mut PageContext, pos: Point, group: &GroupItem) {
     ctx.save_state();
     ctx.transform(translation.pre_concat(group.transform));
 
-    if group.clips {
-        let size = group.frame.size();
-        let w = size.x.to_f32();
-        let h = size.y.to_f32();
-        ctx.content.move_to(0.0, 0.0);
-        ctx.content.line_to(w, 0.0);
-        ctx.content.line_to(w, h);
-        ctx.content.line_to(0.0, h);
+    // Transformed and clipped groups are containers of their own.
+    if group.clip_path.is_some() || !group.transform.is_identity() {
+        ctx.state.container = (ctx.state.transform, group.frame.size());
+    }
+
+    if let Some(clip_path) = &group.clip_path {
+        write_path(ctx, 0.0, 0.0, clip_path);
         ctx.content.clip_nonzero();
         ctx.content.end_path();
     }
@@ -371,7 +505,10 @@ fn write_text(ctx: &mut PageContext, x: f32, y: f32, text: &TextItem) {
         glyph_set.entry(g.id).or_insert_with(|| segment.into());
     }
 
-    ctx.set_fill(&text.fill);
+    let (origin, size) = text.bbox();
+    let own =
+        Transform::translate(Abs::pt(x as f64) + origin.x, Abs::pt(y as f64) + origin.y);
+    ctx.set_fill(&text.fill, true, (ctx.state.transform.pre_concat(own), size));
     ctx.set_font(&text.font, text.size);
     ctx.content.begin_text();
 
@@ -430,12 +567,17 @@ fn write_shape(ctx: &mut PageContext, x: f32, y: f32, shape: &Shape) {
         return;
     }
 
+    let (origin, size) = shape.geometry.bbox();
+    let own =
+        Transform::translate(Abs::pt(x as f64) + origin.x, Abs::pt(y as f64) + origin.y);
+    let own = (ctx.state.transform.pre_concat(own), size);
+
     if let Some(fill) = &shape.fill {
-        ctx.set_fill(fill);
+        ctx.set_fill(fill, false, own);
     }
 
     if let Some(stroke) = stroke {
-        ctx.set_stroke(stroke);
+        ctx.set_stroke(stroke, own);
     }
 
     match shape.geometry {
@@ -459,9 +601,15 @@ fn write_shape(ctx: &mut PageContext, x: f32, y: f32, shape: &Shape) {
 
     match (&shape.fill, stroke) {
         (None, None) => unreachable!(),
-        (Some(_), None) => ctx.content.fill_nonzero(),
+        (Some(_), None) => match shape.fill_rule {
+            FillRule::NonZero => ctx.content.fill_nonzero(),
+            FillRule::EvenOdd => ctx.content.fill_even_odd(),
+        },
         (None, Some(_)) => ctx.content.stroke(),
-        (Some(_), Some(_)) => ctx.content.fill_nonzero_and_stroke(),
+        (Some(_), Some(_)) => match shape.fill_rule {
+            FillRule::NonZero => ctx.content.fill_nonzero_and_stroke(),
+            FillRule::EvenOdd => ctx.content.fill_even_odd_and_stroke(),
+        },
     };
 }
 
@@ -521,14 +669,17 @@ fn write_link(ctx: &mut PageContext, pos: Point, dest: &Destination, size: Size)
     let mut max_x = -Abs::inf();
     let mut max_y = -Abs::inf();
 
-    // Compute the bounding box of the transformed link.
-    for point in [
-        pos,
-        pos + Point::with_x(size.x),
+    // The corners in counterclockwise order, starting at the bottom left.
+    let corners = [
         pos + Point::with_y(size.y),
         pos + size.to_point(),
-    ] {
-        let t = point.transform(ctx.state.transform);
+        pos + Point::with_x(size.x),
+        pos,
+    ]
+    .map(|point| point.transform(ctx.state.transform));
+
+    // Compute the bounding box of the transformed link.
+    for t in corners {
         min_x.set_min(t.x);
         min_y.set_min(t.y);
         max_x.set_max(t.x);
@@ -541,7 +692,15 @@ fn write_link(ctx: &mut PageContext, pos: Point, dest: &Destination, size: Size)
     let y2 = min_y.to_f32();
     let rect = Rect::new(x1, y1, x2, y2);
 
-    ctx.links.push((dest.clone(), rect));
+    // Viewers that support them use the quad points to restrict the clickable
+    // area of rotated links to the link itself instead of its bounding box.
+    let ts = ctx.state.transform;
+    let quad = (!ts.kx.is_zero() || !ts.ky.is_zero()).then(|| {
+        let [a, b, c, d] = corners;
+        [a.x, a.y, b.x, b.y, c.x, c.y, d.x, d.y].map(|v| v.to_f32())
+    });
+
+    ctx.links.push((dest.clone(), rect, quad));
 }
 
 impl From<&LineCap> for LineCapStyle {
@@ -0,0 +1,52 @@
+use pdf_writer::{Filter, Finish, Name, Rect};
+
+use super::{AbsExt, PdfContext, RefExt};
+use crate::geom::{Pattern, Transform};
+
+/// A pattern as it is placed on a page or in another pattern's tile.
+///
+/// PDF tiling patterns live in the default coordinate space of whatever uses
+/// them, so the same pattern needs a separate object for each bounding box it
+/// is aligned to.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct PdfPattern {
+    /// The pattern.
+    pub pattern: Pattern,
+    /// The transform from the pattern's coordinates to the default coordinate
+    /// space of the page or tile using it.
+    pub transform: Transform,
+}
+
+/// Embed all used patterns as tiling patterns into the PDF.
+#[tracing::instrument(skip_all)]
+pub fn write_patterns(ctx: &mut PdfContext) {
+    for PdfPattern { pattern, transform } in ctx.pattern_map.items() {
+        let pattern_ref = ctx.alloc.bump();
+        ctx.pattern_refs.push(pattern_ref);
+
+        let size = pattern.size();
+        let step = pattern.step();
+        let Transform { sx, ky, kx, sy, tx, ty } = *transform;
+
+        let data = &ctx.pattern_tiles[pattern];
+        let mut stream = ctx.writer.stream(pattern_ref, data);
+        stream.filter(Filter::FlateDecode);
+        stream.pair(Name(b"Type"), Name(b"Pattern"));
+        stream.pair(Name(b"PatternType"), 1);
+        stream.pair(Name(b"PaintType"), 1);
+        stream.pair(Name(b"TilingType"), 1);
+        stream.pair(Name(b"BBox"), Rect::new(0.0, 0.0, size.x.to_f32(), size.y.to_f32()));
+        stream.pair(Name(b"XStep"), step.x.to_f32());
+        stream.pair(Name(b"YStep"), step.y.to_f32());
+        stream.pair(Name(b"Resources"), ctx.global_resources_ref);
+        stream.insert(Name(b"Matrix")).array().items([
+            sx.get() as f32,
+            ky.get() as f32,
+            kx.get() as f32,
+            sy.get() as f32,
+            tx.to_f32(),
+            ty.to_f32(),
+        ]);
+        stream.finish();
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::HashSet;
+
+use pdf_writer::{Filter, Finish, Name, Ref, TextStr};
+use ttf_parser::Permissions;
+
+use super::{deflate, PdfContext, RefExt};
+use crate::diag::{error, SourceError, SourceResult};
+use crate::doc::{Document, Frame, FrameItem};
+use crate::font::Font;
+use crate::geom::{Color, Paint};
+use crate::syntax::Span;
+
+/// The ICC profile embedded as the output intent of PDF/A files.
+const SRGB_ICC: &[u8] = include_bytes!("../../../assets/icc/sRGB-v2.icc");
+
+/// The registered name of the output condition described by the profile.
+const SRGB_CONDITION: &str = "sRGB IEC61966-2.1";
+
+/// A PDF standard the exported file should conform to.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum PdfStandard {
+    /// Plain PDF 1.7 without any further restrictions.
+    #[default]
+    Pdf17,
+    /// PDF/A-2b, the archival profile that guarantees faithful visual
+    /// reproduction.
+    A2b,
+}
+
+/// Settings for PDF export.
+#[derive(Debug, Default, Clone, Hash)]
+pub struct PdfOptions {
+    /// The standard the exported file should conform to.
+    pub standard: PdfStandard,
+}
+
+/// Check that the document only uses features that can be represented in
+/// PDF/A.
+#[tracing::instrument(skip_all)]
+pub fn validate(document: &Document) -> SourceResult<()> {
+    let mut validator = Validator::default();
+    for page in &document.pages {
+        validator.frame(page);
+    }
+
+    if validator.errors.is_empty() {
+        Ok(())
+    } else {
+        Err(Box::new(validator.errors))
+    }
+}
+
+/// Collects the violations of the PDF/A profile in a document.
+#[derive(Default)]
+struct Validator {
+    /// The violations found so far.
+    errors: Vec<SourceError>,
+    /// Spans for which a color violation was already reported.
+    colors: HashSet<Span>,
+    /// Fonts that were already checked.
+    fonts: HashSet<Font>,
+}
+
+impl Validator {
+    /// Check all items in a frame.
+    fn frame(&mut self, frame: &Frame) {
+        for (_, item) in frame.items() {
+            match item {
+                FrameItem::Group(group) => self.frame(&group.frame),
+                FrameItem::Text(text) => {
+                    let span = text.glyphs.first().map_or(Span::detached(), |g| g.span.0);
+                    self.paint(&text.fill, span);
+                    self.font(&text.font, span);
+                }
+                FrameItem::Shape(shape, span) => {
+                    if let Some(fill) = &shape.fill {
+                        self.paint(fill, *span);
+                    }
+                    if let Some(stroke) = &shape.stroke {
+                        self.paint(&stroke.paint, *span);
+                    }
+                }
+                FrameItem::Image(..) | FrameItem::Meta(..) => {}
+            }
+        }
+    }
+
+    /// Check that a paint can be reproduced with the sRGB output intent.
+    fn paint(&mut self, paint: &Paint, span: Span) {
+        // The tiles of patterns are subject to the same restrictions as the
+        // rest of the document.
+        if let Paint::Pattern(pattern) = paint {
+            self.frame(pattern.frame());
+        }
+
+        // Gradients are always embedded in RGB.
+        if matches!(paint, Paint::Solid(Color::Cmyk(_))) && self.colors.insert(span) {
+            self.errors.push(
+                error!(span, "PDF/A export does not support CMYK colors")
+                    .with_hints(["specify the color in RGB or grayscale instead".into()]),
+            );
+        }
+    }
+
+    /// Check that a font may be embedded.
+    fn font(&mut self, font: &Font, span: Span) {
+        if !self.fonts.insert(font.clone()) {
+            return;
+        }
+
+        if font.ttf().permissions() == Some(Permissions::Restricted) {
+            self.errors.push(error!(
+                span,
+                "the license of font \"{}\" does not allow embedding it",
+                font.info().family,
+            ));
+        }
+    }
+}
+
+/// Write the sRGB output intent and return a reference to it.
+#[tracing::instrument(skip_all)]
+pub fn write_output_intent(ctx: &mut PdfContext) -> Ref {
+    let profile_ref = ctx.alloc.bump();
+    let data = deflate(SRGB_ICC);
+    let mut profile = ctx.writer.icc_profile(profile_ref, &data);
+    profile.n(3);
+    profile.filter(Filter::FlateDecode);
+    profile.finish();
+
+    let intent_ref = ctx.alloc.bump();
+    let mut intent = ctx.writer.indirect(intent_ref).dict();
+    intent.pair(Name(b"Type"), Name(b"OutputIntent"));
+    intent.pair(Name(b"S"), Name(b"GTS_PDFA1"));
+    intent.pair(Name(b"OutputConditionIdentifier"), TextStr(SRGB_CONDITION));
+    intent.pair(Name(b"Info"), TextStr(SRGB_CONDITION));
+    intent.pair(Name(b"DestOutputProfile"), profile_ref);
+    intent.finish();
+
+    intent_ref
+}
@@ -1,5 +1,6 @@
 //! Rendering into raster images.
 
+use std::collections::HashMap;
 use std::io::Read;
 use std::sync::Arc;
 
@@ -10,10 +11,10 @@ use tiny_skia as sk;
 use ttf_parser::{GlyphId, OutlineBuilder};
 use usvg::{NodeExt, TreeParsing};
 
-use crate::doc::{Frame, FrameItem, GroupItem, Meta, TextItem};
+use crate::doc::{Document, Frame, FrameItem, GroupItem, Meta, TextItem};
 use crate::geom::{
-    self, Abs, Color, Geometry, LineCap, LineJoin, Paint, PathItem, Shape, Size, Stroke,
-    Transform,
+    self, Abs, Color, FillRule, Geometry, Gradient, GradientGeometry, LineCap, LineJoin,
+    Paint, PathItem, Pattern, Point, RelativeTo, Shape, Size, Stroke, Transform,
 };
 use crate::image::{DecodedImage, Image};
 
@@ -30,15 +31,148 @@ pub fn render(frame: &Frame, pixel_per_pt: f32, fill: Color) -> sk::Pixmap {
     canvas.fill(fill.into());
 
     let ts = sk::Transform::from_scale(pixel_per_pt, pixel_per_pt);
-    render_frame(&mut canvas, ts, None, frame);
+    render_frame(&mut canvas, ts, Container::new(ts, frame), None, frame);
 
     canvas
 }
 
+/// Export a rectangular region of a frame into a raster image.
+///
+/// The region is given by its top-left corner and size in the frame's
+/// coordinate system. This is useful for tiled rendering of zoomed-in pages,
+/// where only the visible part of a page needs to be rasterized.
+pub fn render_region(
+    frame: &Frame,
+    pixel_per_pt: f32,
+    fill: Color,
+    pos: Point,
+    size: Size,
+) -> sk::Pixmap {
+    let pxw = (pixel_per_pt * size.x.to_f32()).round().max(1.0) as u32;
+    let pxh = (pixel_per_pt * size.y.to_f32()).round().max(1.0) as u32;
+
+    let mut canvas = sk::Pixmap::new(pxw, pxh).unwrap();
+    canvas.fill(fill.into());
+
+    let ts = sk::Transform::from_scale(pixel_per_pt, pixel_per_pt)
+        .pre_translate(-pos.x.to_f32(), -pos.y.to_f32());
+    render_frame(&mut canvas, ts, Container::new(ts, frame), None, frame);
+
+    canvas
+}
+
+/// Export a frame into a sequence of horizontal raster strips.
+///
+/// This renders the frame at the given number of pixels per point, but instead
+/// of allocating a single pixel buffer for the whole frame, it renders strips
+/// of at most `band_height` pixels one after another and hands each of them to
+/// the `sink` together with its vertical pixel offset. This keeps the memory
+/// usage bounded for very large frames.
+pub fn render_bands(
+    frame: &Frame,
+    pixel_per_pt: f32,
+    fill: Color,
+    band_height: u32,
+    mut sink: impl FnMut(u32, sk::Pixmap),
+) {
+    // Each band is rendered with a few extra rows above and below that are
+    // cropped afterwards, so that anti-aliasing at the band boundaries behaves
+    // exactly as if the frame was rendered in one piece.
+    const OVERLAP: u32 = 2;
+
+    let size = frame.size();
+    let pxw = (pixel_per_pt * size.x.to_f32()).round().max(1.0) as u32;
+    let pxh = (pixel_per_pt * size.y.to_f32()).round().max(1.0) as u32;
+    let band_height = band_height.max(1);
+
+    let mut y = 0;
+    while y < pxh {
+        let height = band_height.min(pxh - y);
+        let top = y.saturating_sub(OVERLAP);
+        let bottom = (y + height + OVERLAP).min(pxh);
+
+        let mut canvas = sk::Pixmap::new(pxw, bottom - top).unwrap();
+        canvas.fill(fill.into());
+
+        let ts = sk::Transform::from_scale(pixel_per_pt, pixel_per_pt)
+            .post_translate(0.0, -(top as f32));
+        render_frame(&mut canvas, ts, Container::new(ts, frame), None, frame);
+
+        let rect = sk::IntRect::from_xywh(0, (y - top) as i32, pxw, height).unwrap();
+        sink(y, canvas.clone_rect(rect).unwrap());
+        y += height;
+    }
+}
+
+/// Export all pages of a document into raster images.
+///
+/// Pages whose [fingerprint](Frame::fingerprint) matches one of the
+/// `previous` pages rendered with the same settings are not rendered again.
+/// Pass the result of the last call to skip unchanged pages in watch mode.
+pub fn render_document(
+    document: &Document,
+    pixel_per_pt: f32,
+    fill: Color,
+    previous: &[RenderedPage],
+) -> Vec<RenderedPage> {
+    let reusable: HashMap<u128, &RenderedPage> = previous
+        .iter()
+        .filter(|page| page.pixel_per_pt == pixel_per_pt && page.fill == fill)
+        .map(|page| (page.fingerprint, page))
+        .collect();
+
+    document
+        .pages
+        .iter()
+        .map(|page| {
+            let fingerprint = page.fingerprint();
+            match reusable.get(&fingerprint) {
+                Some(&rendered) => rendered.clone(),
+                None => RenderedPage {
+                    fingerprint,
+                    pixel_per_pt,
+                    fill,
+                    pixmap: Arc::new(render(page, pixel_per_pt, fill)),
+                },
+            }
+        })
+        .collect()
+}
+
+/// A page rendered by [`render_document`].
+#[derive(Debug, Clone)]
+pub struct RenderedPage {
+    /// The fingerprint of the page's frame.
+    pub fingerprint: u128,
+    /// The number of pixels per point the page was rendered at.
+    pub pixel_per_pt: f32,
+    /// The background the page was rendered on.
+    pub fill: Color,
+    /// The rendered page.
+    pub pixmap: Arc<sk::Pixmap>,
+}
+
+/// The frame that parent-relative gradients are spread over.
+#[derive(Copy, Clone)]
+struct Container {
+    /// The transform from the frame's coordinates to the canvas.
+    ts: sk::Transform,
+    /// The size of the frame.
+    size: Size,
+}
+
+impl Container {
+    /// The container for a frame rendered with the given transform.
+    fn new(ts: sk::Transform, frame: &Frame) -> Self {
+        Self { ts, size: frame.size() }
+    }
+}
+
 /// Render a frame into the canvas.
 fn render_frame(
     canvas: &mut sk::Pixmap,
     ts: sk::Transform,
+    container: Container,
     mask: Option<&sk::Mask>,
     frame: &Frame,
 ) {
@@ -49,13 +183,13 @@ fn render_frame(
 
         match item {
             FrameItem::Group(group) => {
-                render_group(canvas, ts, mask, group);
+                render_group(canvas, ts, container, mask, group);
             }
             FrameItem::Text(text) => {
-                render_text(canvas, ts, mask, text);
+                render_text(canvas, ts, container, mask, text);
             }
             FrameItem::Shape(shape, _) => {
-                render_shape(canvas, ts, mask, shape);
+                render_shape(canvas, ts, container, mask, shape);
             }
             FrameItem::Image(image, size, _) => {
                 render_image(canvas, ts, mask, image, *size);
@@ -74,27 +208,32 @@ fn render_frame(
 fn render_group(
     canvas: &mut sk::Pixmap,
     ts: sk::Transform,
+    container: Container,
     mask: Option<&sk::Mask>,
     group: &GroupItem,
 ) {
     let ts = ts.pre_concat(group.transform.into());
 
+    // Transformed and clipped groups are containers of their own.
+    let mut container = container;
+    if group.clip_path.is_some() || !group.transform.is_identity() {
+        container = Container::new(ts, &group.frame);
+    }
+
     let mut mask = mask;
     let storage;
-    if group.clips {
-        let size = group.frame.size();
-        let w = size.x.to_f32();
-        let h = size.y.to_f32();
-        if let Some(path) = sk::Rect::from_xywh(0.0, 0.0, w, h)
-            .map(sk::PathBuilder::from_rect)
-            .and_then(|path| path.transform(ts))
-        {
+    if let Some(clip_path) = &group.clip_path {
+        // Curved clip paths are anti-aliased, while rectangular ones are kept
+        // sharp so that they end on pixel boundaries.
+        let anti_alias =
+            clip_path.0.iter().any(|item| matches!(item, PathItem::CubicTo(..)));
+        if let Some(path) = convert_path(clip_path).and_then(|path| path.transform(ts)) {
             if let Some(mask) = mask {
                 let mut mask = mask.clone();
                 mask.intersect_path(
                     &path,
                     sk::FillRule::default(),
-                    false,
+                    anti_alias,
                     sk::Transform::default(),
                 );
                 storage = mask;
@@ -110,7 +249,7 @@ fn render_group(
                 mask.fill_path(
                     &path,
                     sk::FillRule::default(),
-                    false,
+                    anti_alias,
                     sk::Transform::default(),
                 );
                 storage = mask;
@@ -120,16 +259,23 @@ fn render_group(
         }
     }
 
-    render_frame(canvas, ts, mask, &group.frame);
+    render_frame(canvas, ts, container, mask, &group.frame);
 }
 
 /// Render a text run into the canvas.
 fn render_text(
     canvas: &mut sk::Pixmap,
     ts: sk::Transform,
+    container: Container,
     mask: Option<&sk::Mask>,
     text: &TextItem,
 ) {
+    let (origin, size) = text.bbox();
+    let own = (ts.pre_translate(origin.x.to_f32(), origin.y.to_f32()), size);
+    let texture = paint_texture(&text.fill, ts);
+    let fill =
+        |local| to_sk_paint(&text.fill, local, own, container, true, texture.as_deref());
+
     let mut x = 0.0;
     for glyph in &text.glyphs {
         let id = GlyphId(glyph.id);
@@ -138,7 +284,7 @@ fn render_text(
 
         render_svg_glyph(canvas, ts, mask, text, id)
             .or_else(|| render_bitmap_glyph(canvas, ts, mask, text, id))
-            .or_else(|| render_outline_glyph(canvas, ts, mask, text, id));
+            .or_else(|| render_outline_glyph(canvas, ts, mask, text, id, &fill));
 
         x += glyph.x_advance.at(text.size).to_f32();
     }
@@ -266,36 +412,47 @@ fn render_bitmap_glyph(
 }
 
 /// Render an outline glyph into the canvas. This is the "normal" case.
-fn render_outline_glyph(
+///
+/// The `fill` creates the paint for a glyph path drawn with the given
+/// transform.
+fn render_outline_glyph<'a>(
     canvas: &mut sk::Pixmap,
     ts: sk::Transform,
     mask: Option<&sk::Mask>,
     text: &TextItem,
     id: GlyphId,
+    fill: &dyn Fn(sk::Transform) -> sk::Paint<'a>,
 ) -> Option<()> {
     let ppem = text.size.to_f32() * ts.sy;
 
     // Render a glyph directly as a path. This only happens when the fast glyph
-    // rasterization can't be used due to very large text size or weird
-    // scale/skewing transforms.
-    if ppem > 100.0 || ts.kx != 0.0 || ts.ky != 0.0 || ts.sx != ts.sy {
+    // rasterization can't be used due to very large text size, weird
+    // scale/skewing transforms, or a gradient or pattern fill.
+    if ppem > 100.0
+        || ts.kx != 0.0
+        || ts.ky != 0.0
+        || ts.sx != ts.sy
+        || matches!(text.fill, Paint::Gradient(_) | Paint::Pattern(_))
+    {
         let path = {
             let mut builder = WrappedPathBuilder(sk::PathBuilder::new());
             text.font.ttf().outline_glyph(id, &mut builder)?;
             builder.0.finish()?
         };
 
-        let paint = (&text.fill).into();
         let rule = sk::FillRule::default();
 
         // Flip vertically because font design coordinate
         // system is Y-up.
         let scale = text.size.to_f32() / text.font.units_per_em() as f32;
         let ts = ts.pre_scale(scale, -scale);
-        canvas.fill_path(&path, &paint, rule, ts, mask);
+        canvas.fill_path(&path, &fill(ts), rule, ts, mask);
         return Some(());
     }
 
+    // Only solid fills remain at this point.
+    let Paint::Solid(color) = &text.fill else { return None };
+
     // Rasterize the glyph with `pixglyph`.
     // Try to retrieve a prepared glyph or prepare it from scratch if it
     // doesn't exist, yet.
@@ -308,7 +465,6 @@ fn render_outline_glyph(
         let mw = bitmap.width;
         let mh = bitmap.height;
 
-        let Paint::Solid(color) = text.fill;
         let c = color.to_rgba();
 
         // Pad the pixmap with 1 pixel in each dimension so that we do
@@ -348,7 +504,6 @@ fn render_outline_glyph(
         let bottom = top + mh;
 
         // Premultiply the text color.
-        let Paint::Solid(color) = text.fill;
         let c = color.to_rgba();
         let color = sk::ColorU8::from_rgba(c.r, c.g, c.b, 255).premultiply().get();
 
@@ -381,9 +536,13 @@ fn render_outline_glyph(
 fn render_shape(
     canvas: &mut sk::Pixmap,
     ts: sk::Transform,
+    container: Container,
     mask: Option<&sk::Mask>,
     shape: &Shape,
 ) -> Option<()> {
+    let (origin, size) = shape.geometry.bbox();
+    let own = (ts.pre_translate(origin.x.to_f32(), origin.y.to_f32()), size);
+
     let path = match shape.geometry {
         Geometry::Line(target) => {
             let mut builder = sk::PathBuilder::new();
@@ -400,12 +559,16 @@ fn render_shape(
     };
 
     if let Some(fill) = &shape.fill {
-        let mut paint: sk::Paint = fill.into();
+        let texture = paint_texture(fill, ts);
+        let mut paint = to_sk_paint(fill, ts, own, container, false, texture.as_deref());
         if matches!(shape.geometry, Geometry::Rect(_)) {
             paint.anti_alias = false;
         }
 
-        let rule = sk::FillRule::default();
+        let rule = match shape.fill_rule {
+            FillRule::NonZero => sk::FillRule::Winding,
+            FillRule::EvenOdd => sk::FillRule::EvenOdd,
+        };
         canvas.fill_path(&path, &paint, rule, ts, mask);
     }
 
@@ -433,7 +596,8 @@ fn render_shape(
 
                 sk::StrokeDash::new(dash_array, pattern.phase.to_f32())
             });
-            let paint = paint.into();
+            let texture = paint_texture(paint, ts);
+            let paint = to_sk_paint(paint, ts, own, container, false, texture.as_deref());
             let stroke = sk::Stroke {
                 width,
                 line_cap: line_cap.into(),
@@ -565,16 +729,135 @@ impl From<Transform> for sk::Transform {
     }
 }
 
-impl From<&Paint> for sk::Paint<'static> {
-    fn from(paint: &Paint) -> Self {
-        let mut sk_paint = sk::Paint::default();
-        let Paint::Solid(color) = *paint;
-        sk_paint.set_color(color.into());
-        sk_paint.anti_alias = true;
-        sk_paint
+/// Convert a Typst paint into a tiny-skia paint for something drawn with the
+/// `local` transform.
+///
+/// The `own` bounding box of the painted element and its container are given
+/// by the transform from their coordinates to the canvas and their size. A
+/// gradient is spread over one of them and a pattern is aligned to one of
+/// them. Patterns are drawn with the `texture` prepared by [`paint_texture`].
+fn to_sk_paint<'a>(
+    paint: &Paint,
+    local: sk::Transform,
+    own: (sk::Transform, Size),
+    container: Container,
+    on_text: bool,
+    texture: Option<&'a sk::Pixmap>,
+) -> sk::Paint<'a> {
+    let mut sk_paint = sk::Paint::default();
+    match paint {
+        Paint::Solid(color) => sk_paint.set_color((*color).into()),
+        Paint::Gradient(gradient) => {
+            let (ts, size) = match gradient.unwrap_relative(on_text) {
+                RelativeTo::Self_ => own,
+                RelativeTo::Parent => (container.ts, container.size),
+            };
+
+            // The shader is specified in the coordinates of the bounding box,
+            // but tiny-skia applies the drawing transform to it.
+            let ts = local.invert().unwrap_or_default().pre_concat(ts);
+            match gradient_shader(gradient, size, ts) {
+                Some(shader) => sk_paint.shader = shader,
+                None => sk_paint.set_color(gradient.stops()[0].0.into()),
+            }
+        }
+        Paint::Pattern(pattern) => {
+            let (ts, _) = match pattern.unwrap_relative(on_text) {
+                RelativeTo::Self_ => own,
+                RelativeTo::Parent => (container.ts, container.size),
+            };
+
+            let Some(texture) = texture else {
+                sk_paint.set_color(sk::Color::TRANSPARENT);
+                return sk_paint;
+            };
+
+            // The texture covers one step of the pattern, so its pixels must
+            // be scaled back to the size of the step.
+            let step = pattern.step();
+            let ts = local.invert().unwrap_or_default().pre_concat(ts).pre_scale(
+                step.x.to_f32() / texture.width() as f32,
+                step.y.to_f32() / texture.height() as f32,
+            );
+            sk_paint.shader = sk::Pattern::new(
+                texture.as_ref(),
+                sk::SpreadMode::Repeat,
+                sk::FilterQuality::Bilinear,
+                1.0,
+                ts,
+            );
+        }
+    }
+    sk_paint.anti_alias = true;
+    sk_paint
+}
+
+/// Create a tiny-skia shader for a gradient spread over a bounding box of the
+/// given size. The transform maps from the box's coordinates to the ones of
+/// the drawn path.
+fn gradient_shader(
+    gradient: &Gradient,
+    size: Size,
+    ts: sk::Transform,
+) -> Option<sk::Shader<'static>> {
+    let stops = gradient
+        .sampled_stops()
+        .into_iter()
+        .map(|(c, t)| {
+            sk::GradientStop::new(
+                t.get() as f32,
+                sk::Color::from_rgba8(c.r, c.g, c.b, c.a),
+            )
+        })
+        .collect();
+
+    let point = |p: Point| sk::Point::from_xy(p.x.to_f32(), p.y.to_f32());
+    let mode = sk::SpreadMode::Pad;
+    match gradient.geometry(size) {
+        GradientGeometry::Linear(start, end) => {
+            sk::LinearGradient::new(point(start), point(end), stops, mode, ts)
+        }
+        GradientGeometry::Radial(center, radius) => sk::RadialGradient::new(
+            point(center),
+            point(center),
+            radius.to_f32(),
+            stops,
+            mode,
+            ts,
+        ),
     }
 }
 
+/// Prepare the texture for a pattern paint that is drawn with the given
+/// transform, such that its tiles are rasterized at the canvas' resolution.
+///
+/// Returns `None` for other paints.
+fn paint_texture(paint: &Paint, ts: sk::Transform) -> Option<Arc<sk::Pixmap>> {
+    /// The maximum width and height of a texture in pixels.
+    const MAX_SIZE: f32 = 4096.0;
+
+    let Paint::Pattern(pattern) = paint else { return None };
+    let step = pattern.step();
+    let scale_x = ts.sx.hypot(ts.ky);
+    let scale_y = ts.kx.hypot(ts.sy);
+    let w = (scale_x * step.x.to_f32()).ceil().clamp(1.0, MAX_SIZE) as u32;
+    let h = (scale_y * step.y.to_f32()).ceil().clamp(1.0, MAX_SIZE) as u32;
+    pattern_texture(pattern, w, h)
+}
+
+/// Render one step of a pattern, that is a tile and the spacing after it,
+/// into a texture of the given size.
+#[comemo::memoize]
+fn pattern_texture(pattern: &Pattern, w: u32, h: u32) -> Option<Arc<sk::Pixmap>> {
+    let mut pixmap = sk::Pixmap::new(w, h)?;
+    let step = pattern.step();
+    let ts =
+        sk::Transform::from_scale(w as f32 / step.x.to_f32(), h as f32 / step.y.to_f32());
+    let frame = pattern.frame();
+    render_frame(&mut pixmap, ts, Container::new(ts, frame), None, frame);
+    Some(Arc::new(pixmap))
+}
+
 impl From<Color> for sk::Color {
     fn from(color: Color) -> Self {
         let c = color.to_rgba();
@@ -655,3 +938,67 @@ fn alpha_mul(color: u32, scale: u32) -> u32 {
     let ag = ((color >> 8) & mask) * scale;
     (rb & mask) | (ag & !mask)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::Span;
+    use crate::testing;
+
+    #[test]
+    fn test_render_document_reuses_unchanged_pages() {
+        let page = |color: Color| {
+            let size = Size::splat(Abs::pt(10.0));
+            let mut frame = Frame::new(size);
+            let shape = Geometry::Rect(size).filled(color.into());
+            frame.push(Point::zero(), FrameItem::Shape(shape, Span::detached()));
+            frame
+        };
+
+        let mut document = Document {
+            pages: vec![page(Color::BLUE), page(Color::BLACK)],
+            ..Document::default()
+        };
+        let first = render_document(&document, 1.0, Color::WHITE, &[]);
+
+        document.pages[1] = page(Color::PURPLE);
+        let second = render_document(&document, 1.0, Color::WHITE, &first);
+        assert!(Arc::ptr_eq(&first[0].pixmap, &second[0].pixmap));
+        assert!(!Arc::ptr_eq(&first[1].pixmap, &second[1].pixmap));
+
+        // Different settings require rendering again.
+        let third = render_document(&document, 2.0, Color::WHITE, &second);
+        assert!(!Arc::ptr_eq(&second[0].pixmap, &third[0].pixmap));
+        assert_eq!(third[0].pixmap.width(), 20);
+    }
+
+    #[test]
+    fn test_render_bands_without_seams() {
+        // Coordinates are exact in binary, so that translating the bands by
+        // whole pixels is lossless. The rectangle's anti-aliased vertical
+        // edges and the glyphs cross many band boundaries.
+        let mut frame = Frame::new(Size::new(Abs::pt(60.0), Abs::pt(40.0)));
+        let rect = Geometry::Rect(Size::new(Abs::pt(10.5), Abs::pt(30.125)))
+            .filled(Color::BLUE.into());
+        frame.push(
+            Point::new(Abs::pt(5.25), Abs::pt(2.625)),
+            FrameItem::Shape(rect, Span::detached()),
+        );
+        frame.push(
+            Point::new(Abs::pt(20.0), Abs::pt(23.5)),
+            FrameItem::Text(testing::text("Typst")),
+        );
+
+        let whole = render(&frame, 2.0, Color::WHITE);
+        let mut offsets = vec![];
+        let mut stitched = vec![];
+        render_bands(&frame, 2.0, Color::WHITE, 7, |y, band| {
+            assert_eq!(band.width(), whole.width());
+            offsets.push(y);
+            stitched.extend_from_slice(band.data());
+        });
+
+        assert_eq!(offsets, (0..80).step_by(7).collect::<Vec<_>>());
+        assert!(stitched == whole.data());
+    }
+}
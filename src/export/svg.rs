@@ -0,0 +1,833 @@
+//! Exporting into SVG files.
+
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter, Write};
+use std::io::Read;
+
+use base64::Engine;
+use ecow::{eco_format, EcoString};
+use ttf_parser::{GlyphId, OutlineBuilder};
+use usvg::TreeWriting;
+use xmlwriter::XmlWriter;
+
+use crate::doc::{Document, Frame, FrameItem, GroupItem, TextItem};
+use crate::font::Font;
+use crate::geom::{
+    Abs, FillRule, Geometry, Gradient, GradientGeometry, LineCap, LineJoin, Paint, Path,
+    PathItem, Pattern, Ratio, RelativeTo, Shape, Size, Stroke, Transform,
+};
+use crate::image::{DecodedImage, Image, ImageFormat, RasterFormat, VectorFormat};
+use crate::util::hash128;
+
+/// Export a frame into an SVG file.
+///
+/// Text is exported as glyph outlines and images are embedded into the file,
+/// so the result renders the same without access to the fonts or images.
+#[tracing::instrument(skip_all)]
+pub fn svg(frame: &Frame) -> String {
+    let mut renderer = SvgRenderer::new();
+    renderer.write_header(frame.size());
+    renderer.render_frame(frame, Transform::identity());
+    renderer.finalize()
+}
+
+/// Export all pages of a document into a single SVG file.
+///
+/// The pages are stacked vertically and the padding is added around and
+/// between them.
+#[tracing::instrument(skip_all)]
+pub fn svg_merged(document: &Document, padding: Abs) -> String {
+    let pages = &document.pages;
+    let width = 2.0 * padding + pages.iter().map(Frame::width).max().unwrap_or_default();
+    let height = padding + pages.iter().map(|page| page.height() + padding).sum::<Abs>();
+
+    let mut renderer = SvgRenderer::new();
+    renderer.write_header(Size::new(width, height));
+
+    let mut y = padding;
+    for page in pages {
+        renderer.render_frame(page, Transform::translate(padding, y));
+        y += page.height() + padding;
+    }
+
+    renderer.finalize()
+}
+
+/// Renders one or multiple frames into an SVG file.
+struct SvgRenderer {
+    /// The underlying XML writer.
+    xml: XmlWriter,
+    /// Glyphs that were used so far, emitted as definitions at the end.
+    glyphs: Deduplicator<RenderedGlyph>,
+    /// Clip paths that were used so far, emitted as definitions at the end.
+    /// The value is the data of an SVG path.
+    clip_paths: Deduplicator<EcoString>,
+    /// Gradients that were used so far, emitted as definitions at the end.
+    /// Each comes with the transform from its bounding box to the user space
+    /// of the element it paints and the size of the box.
+    gradients: Deduplicator<(Gradient, Transform, Size)>,
+    /// Patterns that were used so far, emitted as definitions at the end.
+    /// Each comes with the transform from the pattern space to the user
+    /// space of the element it paints.
+    patterns: Deduplicator<(Pattern, Transform)>,
+    /// The transform from the current item's coordinates to the root.
+    ts: Transform,
+    /// The transform and size of the container that parent-relative
+    /// gradients are spread over.
+    container: (Transform, Size),
+}
+
+/// A glyph prepared for reuse through `<use>` elements.
+///
+/// All coordinates are in font units with the y-axis pointing upwards.
+enum RenderedGlyph {
+    /// The data of an SVG path that traces the glyph's outline.
+    Path(EcoString),
+    /// An embedded bitmap or SVG image.
+    Image { url: EcoString, x: f64, y: f64, width: f64, height: f64 },
+}
+
+impl SvgRenderer {
+    /// Create a new renderer without any definitions.
+    fn new() -> Self {
+        Self {
+            xml: XmlWriter::new(xmlwriter::Options::default()),
+            glyphs: Deduplicator::new('g'),
+            clip_paths: Deduplicator::new('c'),
+            gradients: Deduplicator::new('r'),
+            patterns: Deduplicator::new('p'),
+            ts: Transform::identity(),
+            container: (Transform::identity(), Size::zero()),
+        }
+    }
+
+    /// Write the opening `<svg>` tag for a document of the given size.
+    fn write_header(&mut self, size: Size) {
+        self.xml.start_element("svg");
+        self.xml.write_attribute("class", "typst-doc");
+        self.xml.write_attribute_fmt(
+            "viewBox",
+            format_args!("0 0 {} {}", size.x.to_pt(), size.y.to_pt()),
+        );
+        self.xml.write_attribute("width", &size.x.to_pt());
+        self.xml.write_attribute("height", &size.y.to_pt());
+        self.xml.write_attribute("xmlns", "http://www.w3.org/2000/svg");
+        self.xml
+            .write_attribute("xmlns:xlink", "http://www.w3.org/1999/xlink");
+    }
+
+    /// Render a frame with a transformation.
+    fn render_frame(&mut self, frame: &Frame, ts: Transform) {
+        self.xml.start_element("g");
+        if !ts.is_identity() {
+            self.xml.write_attribute("transform", &SvgMatrix(ts));
+        }
+        self.ts = ts;
+        self.container = (ts, frame.size());
+        self.render_items(frame);
+        self.xml.end_element();
+    }
+
+    /// Render the items of a frame at their respective positions.
+    fn render_items(&mut self, frame: &Frame) {
+        for (pos, item) in frame.items() {
+            if matches!(item, FrameItem::Meta(..)) {
+                continue;
+            }
+
+            self.xml.start_element("g");
+            self.xml.write_attribute_fmt(
+                "transform",
+                format_args!("translate({} {})", pos.x.to_pt(), pos.y.to_pt()),
+            );
+
+            let prev = self.ts;
+            self.ts = prev.pre_concat(Transform::translate(pos.x, pos.y));
+
+            match item {
+                FrameItem::Group(group) => self.render_group(group),
+                FrameItem::Text(text) => self.render_text(text),
+                FrameItem::Shape(shape, _) => self.render_shape(shape),
+                FrameItem::Image(image, size, _) => self.render_image(image, *size),
+                FrameItem::Meta(..) => {}
+            }
+
+            self.ts = prev;
+            self.xml.end_element();
+        }
+    }
+
+    /// Render a group with optional transform and clipping.
+    fn render_group(&mut self, group: &GroupItem) {
+        self.xml.start_element("g");
+        self.xml.write_attribute("class", "typst-group");
+
+        if !group.transform.is_identity() {
+            self.xml.write_attribute("transform", &SvgMatrix(group.transform));
+        }
+
+        // The clip path lives in the coordinate system of the transformed
+        // group, just like the frame it clips.
+        if let Some(clip_path) = &group.clip_path {
+            let id = self
+                .clip_paths
+                .insert_with(hash128(clip_path), || convert_path(clip_path));
+            self.xml.write_attribute_fmt("clip-path", format_args!("url(#{id})"));
+        }
+
+        // Transformed and clipped groups are containers of their own.
+        let (prev_ts, prev_container) = (self.ts, self.container);
+        self.ts = self.ts.pre_concat(group.transform);
+        if group.clip_path.is_some() || !group.transform.is_identity() {
+            self.container = (self.ts, group.frame.size());
+        }
+
+        self.render_items(&group.frame);
+        (self.ts, self.container) = (prev_ts, prev_container);
+        self.xml.end_element();
+    }
+
+    /// Render a text run as references to glyph definitions.
+    fn render_text(&mut self, text: &TextItem) {
+        let upem = text.font.units_per_em();
+        let scale = text.size.to_pt() / upem;
+
+        // Flip vertically because the font design coordinate system is Y-up.
+        self.xml.start_element("g");
+        self.xml.write_attribute("class", "typst-text");
+        self.xml
+            .write_attribute_fmt("transform", format_args!("scale({scale} {})", -scale));
+
+        // Gradients and patterns are resolved in the user space of each glyph,
+        // so they must be set on the glyphs individually.
+        let (origin, size) = text.bbox();
+        let own = (self.ts.pre_concat(Transform::translate(origin.x, origin.y)), size);
+        let flip = Transform::scale(Ratio::new(scale), Ratio::new(-scale));
+        let gradient = matches!(text.fill, Paint::Gradient(_) | Paint::Pattern(_));
+        if !gradient {
+            self.write_fill(&text.fill, true, own, self.ts);
+        }
+
+        let mut x = Abs::zero();
+        for glyph in &text.glyphs {
+            let offset = x + glyph.x_offset.at(text.size);
+            if let Some(id) = self.glyph(&text.font, GlyphId(glyph.id)) {
+                self.xml.start_element("use");
+                self.xml.write_attribute_fmt("xlink:href", format_args!("#{id}"));
+                self.xml.write_attribute("x", &(offset.to_pt() / scale));
+                if gradient {
+                    let shift = Transform::translate(offset / scale, Abs::zero());
+                    let local = self.ts.pre_concat(flip).pre_concat(shift);
+                    self.write_fill(&text.fill, true, own, local);
+                }
+                self.xml.end_element();
+            }
+            x += glyph.x_advance.at(text.size);
+        }
+
+        self.xml.end_element();
+    }
+
+    /// Retrieve the id of a glyph's definition, preparing it if necessary.
+    ///
+    /// Returns `None` if the glyph has no visual representation.
+    fn glyph(&mut self, font: &Font, id: GlyphId) -> Option<EcoString> {
+        self.glyphs.try_insert_with(hash128(&(font, id.0)), || {
+            svg_glyph(font, id)
+                .or_else(|| bitmap_glyph(font, id))
+                .or_else(|| outline_glyph(font, id))
+        })
+    }
+
+    /// Render a geometrical shape.
+    fn render_shape(&mut self, shape: &Shape) {
+        self.xml.start_element("path");
+        self.xml.write_attribute("class", "typst-shape");
+
+        let (origin, size) = shape.geometry.bbox();
+        let own = (self.ts.pre_concat(Transform::translate(origin.x, origin.y)), size);
+
+        match &shape.fill {
+            Some(paint) => self.write_fill(paint, false, own, self.ts),
+            None => self.xml.write_attribute("fill", "none"),
+        }
+
+        if shape.fill.is_some() && shape.fill_rule == FillRule::EvenOdd {
+            self.xml.write_attribute("fill-rule", "evenodd");
+        }
+
+        if let Some(stroke) = &shape.stroke {
+            self.write_stroke(stroke, own);
+        }
+
+        self.xml.write_attribute("d", &convert_geometry(&shape.geometry));
+        self.xml.end_element();
+    }
+
+    /// Render a raster or SVG image, stretched to the given size.
+    fn render_image(&mut self, image: &Image, size: Size) {
+        self.xml.start_element("image");
+        self.xml.write_attribute("width", &size.x.to_pt());
+        self.xml.write_attribute("height", &size.y.to_pt());
+        self.xml.write_attribute("preserveAspectRatio", "none");
+        self.xml.write_attribute("xlink:href", &image_url(image));
+        self.xml.end_element();
+    }
+
+    /// Write the `fill` attributes for a paint.
+    ///
+    /// The painted element's own bounding box is given by the transform from
+    /// its coordinates to the root and its size. The `local` transform maps
+    /// from the user space of the element that references the paint to the
+    /// root.
+    fn write_fill(
+        &mut self,
+        paint: &Paint,
+        on_text: bool,
+        own: (Transform, Size),
+        local: Transform,
+    ) {
+        match paint {
+            Paint::Solid(color) => {
+                let c = color.to_rgba();
+                self.xml.write_attribute_fmt(
+                    "fill",
+                    format_args!("#{:02x}{:02x}{:02x}", c.r, c.g, c.b),
+                );
+                if c.a != 255 {
+                    self.xml.write_attribute("fill-opacity", &(c.a as f64 / 255.0));
+                }
+            }
+            Paint::Gradient(gradient) => {
+                let id = self.gradient(gradient, on_text, own, local);
+                self.xml.write_attribute_fmt("fill", format_args!("url(#{id})"));
+            }
+            Paint::Pattern(pattern) => {
+                let id = self.pattern(pattern, on_text, own, local);
+                self.xml.write_attribute_fmt("fill", format_args!("url(#{id})"));
+            }
+        }
+    }
+
+    /// Write the `stroke` attributes for a stroke of a shape with the given
+    /// bounding box.
+    fn write_stroke(&mut self, stroke: &Stroke, own: (Transform, Size)) {
+        match &stroke.paint {
+            Paint::Solid(color) => {
+                let c = color.to_rgba();
+                self.xml.write_attribute_fmt(
+                    "stroke",
+                    format_args!("#{:02x}{:02x}{:02x}", c.r, c.g, c.b),
+                );
+                if c.a != 255 {
+                    self.xml.write_attribute("stroke-opacity", &(c.a as f64 / 255.0));
+                }
+            }
+            Paint::Gradient(gradient) => {
+                let id = self.gradient(gradient, false, own, self.ts);
+                self.xml.write_attribute_fmt("stroke", format_args!("url(#{id})"));
+            }
+            Paint::Pattern(pattern) => {
+                let id = self.pattern(pattern, false, own, self.ts);
+                self.xml.write_attribute_fmt("stroke", format_args!("url(#{id})"));
+            }
+        }
+
+        self.xml.write_attribute("stroke-width", &stroke.thickness.to_pt());
+        self.xml.write_attribute(
+            "stroke-linecap",
+            match stroke.line_cap {
+                LineCap::Butt => "butt",
+                LineCap::Round => "round",
+                LineCap::Square => "square",
+            },
+        );
+        self.xml.write_attribute(
+            "stroke-linejoin",
+            match stroke.line_join {
+                LineJoin::Miter => "miter",
+                LineJoin::Round => "round",
+                LineJoin::Bevel => "bevel",
+            },
+        );
+        self.xml.write_attribute("stroke-miterlimit", &stroke.miter_limit.0);
+
+        if let Some(pattern) = &stroke.dash_pattern {
+            let mut array = EcoString::new();
+            for (i, length) in pattern.array.iter().enumerate() {
+                if i > 0 {
+                    array.push(' ');
+                }
+                write!(array, "{}", length.to_pt()).unwrap();
+            }
+            if !array.is_empty() {
+                self.xml.write_attribute("stroke-dasharray", &array);
+                self.xml.write_attribute("stroke-dashoffset", &pattern.phase.to_pt());
+            }
+        }
+    }
+
+    /// Retrieve the id of a gradient's definition for painting an element,
+    /// preparing it if necessary.
+    fn gradient(
+        &mut self,
+        gradient: &Gradient,
+        on_text: bool,
+        own: (Transform, Size),
+        local: Transform,
+    ) -> EcoString {
+        let (ts, size) = match gradient.unwrap_relative(on_text) {
+            RelativeTo::Self_ => own,
+            RelativeTo::Parent => self.container,
+        };
+
+        let ts = local.invert().unwrap_or_default().pre_concat(ts);
+        self.gradients
+            .insert_with(hash128(&(gradient, ts, size)), || (gradient.clone(), ts, size))
+    }
+
+    /// Retrieve the id of a pattern's definition for painting an element,
+    /// preparing it if necessary.
+    fn pattern(
+        &mut self,
+        pattern: &Pattern,
+        on_text: bool,
+        own: (Transform, Size),
+        local: Transform,
+    ) -> EcoString {
+        let (ts, _) = match pattern.unwrap_relative(on_text) {
+            RelativeTo::Self_ => own,
+            RelativeTo::Parent => self.container,
+        };
+
+        let ts = local.invert().unwrap_or_default().pre_concat(ts);
+        self.patterns
+            .insert_with(hash128(&(pattern, ts)), || (pattern.clone(), ts))
+    }
+
+    /// Write the collected definitions and finish the document.
+    fn finalize(mut self) -> String {
+        if !self.glyphs.is_empty()
+            || !self.clip_paths.is_empty()
+            || !self.gradients.is_empty()
+            || !self.patterns.is_empty()
+        {
+            self.xml.start_element("defs");
+
+            // Patterns are written first because their tiles may use further
+            // glyphs, clip paths, gradients, and patterns.
+            let mut i = 0;
+            while let Some((id, (pattern, ts))) = self.patterns.vec.get(i).cloned() {
+                let step = pattern.step();
+                self.xml.start_element("pattern");
+                self.xml.write_attribute("id", &id);
+                self.xml.write_attribute("patternUnits", "userSpaceOnUse");
+                self.xml.write_attribute("width", &step.x.to_pt());
+                self.xml.write_attribute("height", &step.y.to_pt());
+                if !ts.is_identity() {
+                    self.xml.write_attribute("patternTransform", &SvgMatrix(ts));
+                }
+
+                // The tile is its own root and container.
+                self.ts = Transform::identity();
+                self.container = (Transform::identity(), pattern.size());
+                self.render_items(pattern.frame());
+                self.xml.end_element();
+                i += 1;
+            }
+
+            for (id, glyph) in self.glyphs.iter() {
+                match glyph {
+                    RenderedGlyph::Path(path) => {
+                        self.xml.start_element("path");
+                        self.xml.write_attribute("id", id);
+                        self.xml.write_attribute("d", path);
+                        self.xml.end_element();
+                    }
+                    RenderedGlyph::Image { url, x, y, width, height } => {
+                        // Images are laid out with the y-axis pointing
+                        // downwards, so we have to undo the text's flip.
+                        self.xml.start_element("image");
+                        self.xml.write_attribute("id", id);
+                        self.xml.write_attribute("x", x);
+                        self.xml.write_attribute("y", y);
+                        self.xml.write_attribute("width", width);
+                        self.xml.write_attribute("height", height);
+                        self.xml.write_attribute("transform", "scale(1 -1)");
+                        self.xml.write_attribute("xlink:href", url);
+                        self.xml.end_element();
+                    }
+                }
+            }
+
+            for (id, path) in self.clip_paths.iter() {
+                self.xml.start_element("clipPath");
+                self.xml.write_attribute("id", id);
+                self.xml.start_element("path");
+                self.xml.write_attribute("d", path);
+                self.xml.end_element();
+                self.xml.end_element();
+            }
+
+            for (id, (gradient, ts, size)) in self.gradients.iter() {
+                match gradient.geometry(*size) {
+                    GradientGeometry::Linear(start, end) => {
+                        self.xml.start_element("linearGradient");
+                        self.xml.write_attribute("x1", &start.x.to_pt());
+                        self.xml.write_attribute("y1", &start.y.to_pt());
+                        self.xml.write_attribute("x2", &end.x.to_pt());
+                        self.xml.write_attribute("y2", &end.y.to_pt());
+                    }
+                    GradientGeometry::Radial(center, radius) => {
+                        self.xml.start_element("radialGradient");
+                        self.xml.write_attribute("cx", &center.x.to_pt());
+                        self.xml.write_attribute("cy", &center.y.to_pt());
+                        self.xml.write_attribute("r", &radius.to_pt());
+                    }
+                }
+
+                self.xml.write_attribute("id", id);
+                self.xml.write_attribute("gradientUnits", "userSpaceOnUse");
+                if !ts.is_identity() {
+                    self.xml.write_attribute("gradientTransform", &SvgMatrix(*ts));
+                }
+
+                for (c, offset) in gradient.sampled_stops() {
+                    self.xml.start_element("stop");
+                    self.xml.write_attribute("offset", &offset.get());
+                    self.xml.write_attribute_fmt(
+                        "stop-color",
+                        format_args!("#{:02x}{:02x}{:02x}", c.r, c.g, c.b),
+                    );
+                    if c.a != 255 {
+                        self.xml.write_attribute("stop-opacity", &(c.a as f64 / 255.0));
+                    }
+                    self.xml.end_element();
+                }
+
+                self.xml.end_element();
+            }
+
+            self.xml.end_element();
+        }
+
+        self.xml.end_document()
+    }
+}
+
+/// Prepare an SVG glyph, as found in color emoji fonts.
+fn svg_glyph(font: &Font, id: GlyphId) -> Option<RenderedGlyph> {
+    let mut data = font.ttf().glyph_svg_image(id)?;
+
+    // Decompress SVGZ.
+    let mut decoded = vec![];
+    if data.starts_with(&[0x1f, 0x8b]) {
+        let mut decoder = flate2::read::GzDecoder::new(data);
+        decoder.read_to_end(&mut decoded).ok()?;
+        data = &decoded;
+    }
+
+    let upem = font.units_per_em();
+    let ascender = font.metrics().ascender.get() * upem;
+    let mut xml = std::str::from_utf8(data).ok()?;
+    let patched;
+
+    // SVG glyphs are positioned relative to the baseline, so their content
+    // mostly lies above the default viewport. If the glyph doesn't define a
+    // view box itself, we use the em square below the ascender.
+    let document = roxmltree::Document::parse(xml).ok()?;
+    if !document.root_element().has_attribute("viewBox") {
+        let view_box = format!("<svg viewBox=\"0 {} {upem} {upem}\"", -ascender);
+        patched = xml.replacen("<svg", &view_box, 1);
+        xml = &patched;
+    }
+
+    Some(RenderedGlyph::Image {
+        url: data_url(ImageFormat::Vector(VectorFormat::Svg), xml.as_bytes()),
+        x: 0.0,
+        y: -ascender,
+        width: upem,
+        height: upem,
+    })
+}
+
+/// Prepare a bitmap glyph, picking the strike with the highest resolution.
+fn bitmap_glyph(font: &Font, id: GlyphId) -> Option<RenderedGlyph> {
+    let raster = font.ttf().glyph_raster_image(id, u16::MAX)?;
+    let image = Image::new(raster.data.into(), raster.format.into(), None).ok()?;
+
+    // Mirrors the positioning of bitmap glyphs in the raster exporter.
+    let upem = font.units_per_em();
+    let width = image.width() as f64;
+    let height = image.height() as f64;
+    Some(RenderedGlyph::Image {
+        url: data_url(image.format(), image.data()),
+        x: raster.x as f64 / width * upem,
+        y: -upem - raster.y as f64 / height * upem,
+        width: width / height * upem,
+        height: upem,
+    })
+}
+
+/// Prepare an outline glyph. This is the "normal" case.
+fn outline_glyph(font: &Font, id: GlyphId) -> Option<RenderedGlyph> {
+    let mut builder = SvgPathBuilder::default();
+    font.ttf().outline_glyph(id, &mut builder)?;
+    Some(RenderedGlyph::Path(builder.0))
+}
+
+/// Convert a geometry into the data of an SVG path.
+fn convert_geometry(geometry: &Geometry) -> EcoString {
+    let mut builder = SvgPathBuilder::default();
+    match geometry {
+        Geometry::Line(target) => {
+            builder.move_to(0.0, 0.0);
+            builder.line_to(target.x.to_pt() as f32, target.y.to_pt() as f32);
+        }
+        Geometry::Rect(size) => {
+            builder.rect(size.x.to_pt(), size.y.to_pt());
+        }
+        Geometry::Path(path) => return convert_path(path),
+    }
+    builder.0
+}
+
+/// Convert a path into the data of an SVG path.
+fn convert_path(path: &Path) -> EcoString {
+    let mut builder = SvgPathBuilder::default();
+    for item in &path.0 {
+        match item {
+            PathItem::MoveTo(p) => {
+                builder.move_to(p.x.to_pt() as f32, p.y.to_pt() as f32);
+            }
+            PathItem::LineTo(p) => {
+                builder.line_to(p.x.to_pt() as f32, p.y.to_pt() as f32);
+            }
+            PathItem::CubicTo(p1, p2, p3) => {
+                builder.curve_to(
+                    p1.x.to_pt() as f32,
+                    p1.y.to_pt() as f32,
+                    p2.x.to_pt() as f32,
+                    p2.y.to_pt() as f32,
+                    p3.x.to_pt() as f32,
+                    p3.y.to_pt() as f32,
+                );
+            }
+            PathItem::ClosePath => {
+                builder.close();
+            }
+        }
+    }
+    builder.0
+}
+
+/// Encode an image as a data URL.
+///
+/// Browsers only show the first frame of a GIF, so other frames are embedded
+/// as PNGs. SVGs are embedded with their text converted to paths, such that
+/// they don't depend on the fonts available to the viewer.
+fn image_url(image: &Image) -> EcoString {
+    match image.decoded().as_ref() {
+        DecodedImage::Raster(dynamic, _, _) if image.page() > 0 => {
+            let mut data = std::io::Cursor::new(vec![]);
+            if dynamic.write_to(&mut data, ::image::ImageFormat::Png).is_ok() {
+                let format = ImageFormat::Raster(RasterFormat::Png);
+                return data_url(format, &data.into_inner());
+            }
+        }
+        DecodedImage::Svg(tree) => {
+            let xml = tree.to_string(&usvg::XmlOptions::default());
+            return data_url(image.format(), xml.as_bytes());
+        }
+        _ => {}
+    }
+
+    data_url(image.format(), image.data())
+}
+
+/// Encode image data as a base64 data URL.
+fn data_url(format: ImageFormat, data: &[u8]) -> EcoString {
+    let mime = match format {
+        ImageFormat::Raster(RasterFormat::Png) => "image/png",
+        ImageFormat::Raster(RasterFormat::Jpg) => "image/jpeg",
+        ImageFormat::Raster(RasterFormat::Gif) => "image/gif",
+        ImageFormat::Vector(VectorFormat::Svg) => "image/svg+xml",
+    };
+
+    let mut url = eco_format!("data:{mime};base64,");
+    url.push_str(&base64::engine::general_purpose::STANDARD.encode(data));
+    url
+}
+
+/// Deduplicates definitions and assigns them short, unique ids.
+struct Deduplicator<T> {
+    /// The prefix of the generated ids.
+    kind: char,
+    /// The definitions in insertion order alongside their ids.
+    vec: Vec<(EcoString, T)>,
+    /// Maps from the hash of a definition to its id.
+    present: HashMap<u128, EcoString>,
+}
+
+impl<T> Deduplicator<T> {
+    /// Create a new, empty deduplicator with the given id prefix.
+    fn new(kind: char) -> Self {
+        Self { kind, vec: vec![], present: HashMap::new() }
+    }
+
+    /// Insert a definition with the given hash if it isn't present yet and
+    /// return its id.
+    fn insert_with<F>(&mut self, hash: u128, f: F) -> EcoString
+    where
+        F: FnOnce() -> T,
+    {
+        self.try_insert_with(hash, || Some(f())).unwrap()
+    }
+
+    /// Like [`insert_with`](Self::insert_with), but the definition may fail
+    /// to materialize, in which case nothing is inserted.
+    fn try_insert_with<F>(&mut self, hash: u128, f: F) -> Option<EcoString>
+    where
+        F: FnOnce() -> Option<T>,
+    {
+        if let Some(id) = self.present.get(&hash) {
+            return Some(id.clone());
+        }
+
+        let id = eco_format!("{}{}", self.kind, self.vec.len());
+        self.vec.push((id.clone(), f()?));
+        self.present.insert(hash, id.clone());
+        Some(id)
+    }
+
+    /// Whether no definitions were inserted.
+    fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// Iterate over the definitions in insertion order.
+    fn iter(&self) -> impl Iterator<Item = (&EcoString, &T)> {
+        self.vec.iter().map(|(id, v)| (id, v))
+    }
+}
+
+/// Displays as an SVG transformation matrix.
+struct SvgMatrix(Transform);
+
+impl Display for SvgMatrix {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let Transform { sx, ky, kx, sy, tx, ty } = self.0;
+        write!(
+            f,
+            "matrix({} {} {} {} {} {})",
+            sx.get(),
+            ky.get(),
+            kx.get(),
+            sy.get(),
+            tx.to_pt(),
+            ty.to_pt()
+        )
+    }
+}
+
+/// Builds the data of an SVG path, from glyph outlines or geometries.
+#[derive(Default)]
+struct SvgPathBuilder(EcoString);
+
+impl SvgPathBuilder {
+    /// Trace a rectangle with its origin in the top-left corner.
+    fn rect(&mut self, width: f64, height: f64) {
+        write!(self.0, "M 0 0 H {width} V {height} H 0 Z ").unwrap();
+    }
+}
+
+impl OutlineBuilder for SvgPathBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        write!(self.0, "M {x} {y} ").unwrap();
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        write!(self.0, "L {x} {y} ").unwrap();
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        write!(self.0, "Q {x1} {y1} {x} {y} ").unwrap();
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        write!(self.0, "C {x1} {y1} {x2} {y2} {x} {y} ").unwrap();
+    }
+
+    fn close(&mut self) {
+        write!(self.0, "Z ").unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geom::{Color, Point};
+    use crate::syntax::Span;
+    use crate::testing;
+    use crate::util::Bytes;
+
+    #[test]
+    fn test_svg_contains_text_shapes_and_images() {
+        let data = include_bytes!("../../assets/files/graph.png");
+        let format = ImageFormat::Raster(RasterFormat::Png);
+        let image = Image::new(Bytes::from_static(data), format, None).unwrap();
+        let square = Size::splat(Abs::pt(20.0));
+        let shape = Shape {
+            stroke: Some(Stroke::default()),
+            ..Geometry::Rect(square).filled(Color::BLUE.into())
+        };
+
+        let mut frame = Frame::new(Size::splat(Abs::pt(100.0)));
+        frame.push(
+            Point::new(Abs::pt(10.0), Abs::pt(20.0)),
+            FrameItem::Text(testing::text("Hi")),
+        );
+        frame.push(
+            Point::with_y(Abs::pt(40.0)),
+            FrameItem::Shape(shape, Span::detached()),
+        );
+        frame.push(
+            Point::with_y(Abs::pt(70.0)),
+            FrameItem::Image(image, square, Span::detached()),
+        );
+
+        let svg = svg(&frame);
+        let document = roxmltree::Document::parse(&svg).unwrap();
+        let root = document.root_element();
+        assert_eq!(root.tag_name().name(), "svg");
+        assert_eq!(root.attribute("viewBox"), Some("0 0 100 100"));
+
+        let elements = |name: &str| {
+            root.descendants()
+                .filter(|node| node.tag_name().name() == name)
+                .collect::<Vec<_>>()
+        };
+
+        // Each glyph references an outline that is defined once.
+        let glyphs = elements("use");
+        assert_eq!(glyphs.len(), 2);
+        for glyph in glyphs {
+            let href = glyph.attribute(("http://www.w3.org/1999/xlink", "href")).unwrap();
+            let id = href.strip_prefix('#').unwrap();
+            assert!(elements("path").iter().any(|path| path.attribute("id") == Some(id)));
+        }
+
+        let shapes: Vec<_> = elements("path")
+            .into_iter()
+            .filter(|path| path.attribute("class") == Some("typst-shape"))
+            .collect();
+        assert_eq!(shapes.len(), 1);
+        assert_eq!(shapes[0].attribute("fill"), Some("#0074d9"));
+        assert_eq!(shapes[0].attribute("stroke"), Some("#000000"));
+
+        let images = elements("image");
+        assert_eq!(images.len(), 1);
+        let href = images[0].attribute(("http://www.w3.org/1999/xlink", "href")).unwrap();
+        assert!(href.starts_with("data:image/png;base64,"));
+    }
+}
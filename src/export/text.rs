@@ -0,0 +1,163 @@
+//! Exporting into plain text.
+
+use crate::doc::{Document, Frame, FrameItem, TextItem};
+use crate::geom::{Abs, Point, Transform};
+
+/// Export a document into plain text.
+///
+/// The text runs of all pages are collected in reading order. Line and
+/// paragraph breaks are reconstructed from the vertical position of the runs
+/// and words that were hyphenated at the end of a line are joined back
+/// together. Decorative shapes and images are skipped.
+#[tracing::instrument(skip_all)]
+pub fn text(document: &Document) -> String {
+    let mut extractor = TextExtractor::default();
+    for page in &document.pages {
+        extractor.page_break = true;
+        extractor.frame(page, Transform::identity());
+    }
+    extractor.output
+}
+
+/// Collects the text runs of a document into a string.
+#[derive(Default)]
+struct TextExtractor {
+    /// The text extracted so far.
+    output: String,
+    /// The line the last run was placed on.
+    line: Option<Line>,
+    /// Whether a page boundary was crossed since the last run.
+    page_break: bool,
+}
+
+/// The line most recently written to the output.
+struct Line {
+    /// The baseline's vertical position on the page.
+    baseline: Abs,
+    /// The largest font size on the line.
+    size: Abs,
+    /// The horizontal position where the last run on the line ended.
+    end: Abs,
+    /// Whether the line ends with an automatically inserted hyphen.
+    hyphenated: bool,
+}
+
+impl TextExtractor {
+    /// Extract the text from a frame whose items are transformed with `ts`
+    /// onto the page.
+    fn frame(&mut self, frame: &Frame, ts: Transform) {
+        for (pos, item) in frame.items() {
+            match item {
+                FrameItem::Group(group) => {
+                    let ts = ts
+                        .pre_concat(Transform::translate(pos.x, pos.y))
+                        .pre_concat(group.transform);
+                    self.frame(&group.frame, ts);
+                }
+                FrameItem::Text(text) => self.text(pos.transform(ts), text),
+                FrameItem::Shape(..) | FrameItem::Image(..) | FrameItem::Meta(..) => {}
+            }
+        }
+    }
+
+    /// Extract a text run whose baseline starts at the given point.
+    fn text(&mut self, pos: Point, text: &TextItem) {
+        // Hyphens inserted by hyphenation don't map to any text.
+        let hyphenated = text.glyphs.last().map_or(false, |glyph| glyph.range.is_empty());
+        let end = pos.x + text.width();
+
+        let page_break = std::mem::take(&mut self.page_break);
+        if let Some(line) = &mut self.line {
+            let delta = pos.y - line.baseline;
+            let size = line.size.max(text.size);
+            if !page_break && delta.abs() < size / 2.0 {
+                // Insert a space between runs that are visually apart, for
+                // example in neighbouring table cells.
+                let gap = pos.x - line.end;
+                if gap > size / 3.0
+                    && !self.output.ends_with(char::is_whitespace)
+                    && !text.text.starts_with(char::is_whitespace)
+                {
+                    self.output.push(' ');
+                }
+                line.size = size;
+                line.end = end;
+                line.hyphenated = hyphenated;
+                self.output.push_str(&text.text);
+                return;
+            }
+
+            if !line.hyphenated {
+                let paragraph = page_break || delta > 1.6 * line.size;
+                self.output.push_str(if paragraph { "\n\n" } else { "\n" });
+            }
+        }
+
+        self.line = Some(Line { baseline: pos.y, size: text.size, end, hyphenated });
+        self.output.push_str(&text.text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::doc::{Glyph, GroupItem};
+    use crate::geom::{Angle, Em, Size};
+    use crate::syntax::Span;
+    use crate::testing;
+
+    fn pt(x: f64, y: f64) -> Point {
+        Point::new(Abs::pt(x), Abs::pt(y))
+    }
+
+    /// Extract the text from a single page.
+    fn extract(page: Frame) -> String {
+        text(&Document { pages: vec![page], ..Document::default() })
+    }
+
+    #[test]
+    fn test_text_line_and_paragraph_breaks() {
+        let mut page = Frame::new(Size::splat(Abs::pt(100.0)));
+        page.push(pt(0.0, 10.0), FrameItem::Text(testing::text("one")));
+        page.push(pt(0.0, 22.0), FrameItem::Text(testing::text("two")));
+        page.push(pt(0.0, 50.0), FrameItem::Text(testing::text("three")));
+        assert_eq!(extract(page), "one\ntwo\n\nthree");
+    }
+
+    #[test]
+    fn test_text_rejoins_hyphenated_words() {
+        // The hyphen that was inserted at the end of the first line maps to
+        // no text.
+        let mut first = testing::text("hy");
+        let id = first.font.ttf().glyph_index('-').unwrap().0;
+        first.glyphs.push(Glyph {
+            id,
+            x_advance: first.font.advance(id).unwrap_or(Em::zero()),
+            x_offset: Em::zero(),
+            range: 2..2,
+            span: (Span::detached(), 0),
+        });
+
+        let mut page = Frame::new(Size::splat(Abs::pt(100.0)));
+        page.push(pt(0.0, 10.0), FrameItem::Text(first));
+        page.push(pt(0.0, 22.0), FrameItem::Text(testing::text("phen")));
+        page.push(pt(0.0, 34.0), FrameItem::Text(testing::text("next")));
+        assert_eq!(extract(page), "hyphen\nnext");
+    }
+
+    #[test]
+    fn test_text_in_rotated_groups() {
+        let mut inner = Frame::new(Size::new(Abs::pt(40.0), Abs::pt(10.0)));
+        inner.push(Point::zero(), FrameItem::Text(testing::text("rotated")));
+        let mut group = GroupItem::new(inner);
+        group.transform = Transform::rotate(Angle::deg(30.0));
+
+        // The group's origin is on the first line, so its text continues the
+        // line after a gap.
+        let mut page = Frame::new(Size::splat(Abs::pt(100.0)));
+        page.push(pt(0.0, 10.0), FrameItem::Text(testing::text("Before")));
+        page.push(pt(50.0, 10.0), FrameItem::Group(group));
+        page.push(pt(0.0, 22.0), FrameItem::Text(testing::text("After")));
+        assert_eq!(extract(page), "Before rotated\nAfter");
+    }
+}
@@ -1,18 +1,18 @@
-//! File and package management.
+//! File management.
 
 use std::collections::HashMap;
 use std::fmt::{self, Debug, Display, Formatter};
+use std::mem;
 use std::path::{Path, PathBuf};
-use std::str::FromStr;
-use std::sync::RwLock;
+use std::sync::{Arc, Mutex, RwLock};
 
-use ecow::{eco_format, EcoString};
+use ecow::EcoString;
 use once_cell::sync::Lazy;
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::diag::{bail, FileError, StrResult};
-use crate::syntax::is_ident;
-use crate::util::PathExt;
+use crate::diag::{bail, FileError, FileResult, StrResult};
+use crate::package::PackageSpec;
+use crate::syntax::Source;
+use crate::util::{hash128, Bytes, PathExt};
 
 /// The global package-path interner.
 static INTERNER: Lazy<RwLock<Interner>> =
@@ -129,157 +129,259 @@ impl Debug for FileId {
     }
 }
 
-/// Identifies a package.
+/// An entry in a directory listing.
+///
+/// See [`World::entries`](crate::World::entries).
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
-pub struct PackageSpec {
-    /// The namespace the package lives in.
-    pub namespace: EcoString,
-    /// The name of the package within its namespace.
+pub struct DirEntry {
+    /// The name of the file or directory.
     pub name: EcoString,
-    /// The package's version.
-    pub version: Version,
+    /// Whether the entry is a directory.
+    pub is_dir: bool,
 }
 
-impl FromStr for PackageSpec {
-    type Err = EcoString;
+/// Caches the files of a [`World`](crate::World) and their parsed sources.
+///
+/// Files are loaded lazily through a user-provided function on first access.
+/// After [`reset`](Self::reset) or [`invalidate`](Self::invalidate), they are
+/// loaded again on their next access, but if their contents did not change,
+/// the previously returned [`Source`] or [`Bytes`] is returned again. This
+/// way, comemo can reuse the results of the previous compilation. Changed
+/// sources are reparsed in place, keeping the spans of unchanged parts.
+///
+/// The store can be shared between threads, for example by the compiler and
+/// the request handlers of a language server.
+///
+/// ```ignore
+/// struct MyWorld {
+///     root: PathBuf,
+///     store: LazyFileStore,
+///     // ...
+/// }
+///
+/// impl World for MyWorld {
+///     fn source(&self, id: FileId) -> FileResult<Source> {
+///         self.store.source(id, |id| self.read(id))
+///     }
+///
+///     fn file(&self, id: FileId) -> FileResult<Bytes> {
+///         self.store.file(id, |id| self.read(id))
+///     }
+///
+///     // ...
+/// }
+///
+/// impl MyWorld {
+///     fn read(&self, id: FileId) -> FileResult<Bytes> {
+///         let path = self.root.join_rooted(id.path());
+///         let path = path.ok_or(FileError::AccessDenied)?;
+///         let data = fs::read(&path).map_err(|e| FileError::from_io(e, &path))?;
+///         Ok(data.into())
+///     }
+///
+///     /// Called before each recompilation.
+///     fn reset(&self) {
+///         self.store.reset();
+///     }
+/// }
+/// ```
+#[derive(Default)]
+pub struct LazyFileStore {
+    slots: Mutex<HashMap<FileId, Arc<Mutex<FileSlot>>>>,
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut s = unscanny::Scanner::new(s);
-        if !s.eat_if('@') {
-            bail!("package specification must start with '@'");
-        }
+impl LazyFileStore {
+    /// Create a new, empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-        let namespace = s.eat_until('/');
-        if namespace.is_empty() {
-            bail!("package specification is missing namespace");
-        } else if !is_ident(namespace) {
-            bail!("`{namespace}` is not a valid package namespace");
-        }
+    /// Access the source file, loading it with `load` if necessary.
+    pub fn source(
+        &self,
+        id: FileId,
+        load: impl FnOnce(FileId) -> FileResult<Bytes>,
+    ) -> FileResult<Source> {
+        self.slot(id).lock().unwrap().source(|| load(id))
+    }
 
-        s.eat_if('/');
+    /// Access the file's raw data, loading it with `load` if necessary.
+    pub fn file(
+        &self,
+        id: FileId,
+        load: impl FnOnce(FileId) -> FileResult<Bytes>,
+    ) -> FileResult<Bytes> {
+        self.slot(id).lock().unwrap().file(|| load(id))
+    }
 
-        let name = s.eat_until(':');
-        if name.is_empty() {
-            bail!("package specification is missing name");
-        } else if !is_ident(name) {
-            bail!("`{name}` is not a valid package name");
+    /// Check all files for changes on their next access.
+    pub fn reset(&self) {
+        for slot in self.slots.lock().unwrap().values() {
+            slot.lock().unwrap().reset();
         }
+    }
 
-        s.eat_if(':');
-
-        let version = s.after();
-        if version.is_empty() {
-            bail!("package specification is missing version");
+    /// Check a single file for changes on its next access.
+    pub fn invalidate(&self, id: FileId) {
+        if let Some(slot) = self.slots.lock().unwrap().get(&id) {
+            slot.lock().unwrap().reset();
         }
-
-        Ok(Self {
-            namespace: namespace.into(),
-            name: name.into(),
-            version: version.parse()?,
-        })
     }
-}
 
-impl Display for PackageSpec {
-    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        write!(f, "@{}/{}:{}", self.namespace, self.name, self.version)
+    /// The slot for a file, which is created if it doesn't exist yet.
+    ///
+    /// The map is only locked while looking up the slot, so that different
+    /// files can be loaded concurrently.
+    fn slot(&self, id: FileId) -> Arc<Mutex<FileSlot>> {
+        let mut slots = self.slots.lock().unwrap();
+        slots
+            .entry(id)
+            .or_insert_with(|| Arc::new(Mutex::new(FileSlot::new(id))))
+            .clone()
     }
 }
 
-/// A package's version.
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
-pub struct Version {
-    /// The package's major version.
-    pub major: u32,
-    /// The package's minor version.
-    pub minor: u32,
-    /// The package's patch version.
-    pub patch: u32,
+/// Holds the lazily loaded data and source of a single file.
+///
+/// This is the building block of [`LazyFileStore`] and can be used on its own
+/// by worlds that need more control over how files are identified, for
+/// example to deduplicate paths that point to the same file.
+pub struct FileSlot {
+    id: FileId,
+    file: SlotCell<Bytes>,
+    source: SlotCell<Source>,
 }
 
-impl FromStr for Version {
-    type Err = EcoString;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s.split('.');
-        let mut next = |kind| {
-            let Some(part) = parts.next().filter(|s| !s.is_empty()) else {
-                bail!("version number is missing {kind} version");
-            };
-            part.parse::<u32>()
-                .map_err(|_| eco_format!("`{part}` is not a valid {kind} version"))
-        };
-
-        let major = next("major")?;
-        let minor = next("minor")?;
-        let patch = next("patch")?;
-        if let Some(rest) = parts.next() {
-            bail!("version number has unexpected fourth component: `{rest}`");
-        }
+impl FileSlot {
+    /// Create a new slot for a file that is not yet loaded.
+    pub fn new(id: FileId) -> Self {
+        Self { id, file: SlotCell::new(), source: SlotCell::new() }
+    }
 
-        Ok(Self { major, minor, patch })
+    /// The file the slot holds.
+    pub fn id(&self) -> FileId {
+        self.id
     }
-}
 
-impl Display for Version {
-    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    /// Check the file for changes on its next access.
+    pub fn reset(&mut self) {
+        self.file.accessed = false;
+        self.source.accessed = false;
     }
-}
 
-impl Serialize for Version {
-    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
-        s.collect_str(self)
+    /// Access the source file, loading it with `load` if necessary.
+    pub fn source(
+        &mut self,
+        load: impl FnOnce() -> FileResult<Bytes>,
+    ) -> FileResult<Source> {
+        let id = self.id;
+        self.source.get_or_init(load, |data, prev| {
+            let text = decode_utf8(&data)?;
+            Ok(match prev {
+                Some(mut source) => {
+                    source.replace(text.into());
+                    source
+                }
+                None => Source::new(id, text.into()),
+            })
+        })
     }
-}
 
-impl<'de> Deserialize<'de> for Version {
-    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
-        let string = EcoString::deserialize(d)?;
-        string.parse().map_err(serde::de::Error::custom)
+    /// Access the file's raw data, loading it with `load` if necessary.
+    pub fn file(
+        &mut self,
+        load: impl FnOnce() -> FileResult<Bytes>,
+    ) -> FileResult<Bytes> {
+        self.file.get_or_init(load, |data, _| Ok(data))
     }
 }
 
-/// A parsed package manifest.
-#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
-pub struct PackageManifest {
-    /// Details about the package itself.
-    pub package: PackageInfo,
+/// Lazily computes a value from a file's data and remembers the fingerprint
+/// of the data it was computed from.
+struct SlotCell<T> {
+    /// The computed value, if any.
+    data: Option<FileResult<T>>,
+    /// A hash of the loaded data the value was computed from.
+    fingerprint: u128,
+    /// Whether the value is known to be up to date.
+    accessed: bool,
 }
 
-impl PackageManifest {
-    /// Parse the manifest from raw bytes.
-    pub fn parse(bytes: &[u8]) -> StrResult<Self> {
-        let string = std::str::from_utf8(bytes).map_err(FileError::from)?;
-        toml::from_str(string).map_err(|err| {
-            eco_format!("package manifest is malformed: {}", err.message())
-        })
+impl<T: Clone> SlotCell<T> {
+    /// Create a new, empty cell.
+    fn new() -> Self {
+        Self { data: None, fingerprint: 0, accessed: false }
     }
 
-    /// Ensure that this manifest is indeed for the specified package.
-    pub fn validate(&self, spec: &PackageSpec) -> StrResult<()> {
-        if self.package.name != spec.name {
-            bail!("package manifest contains mismatched name `{}`", self.package.name);
+    /// Get the value or (re)compute it if it might be stale.
+    fn get_or_init(
+        &mut self,
+        load: impl FnOnce() -> FileResult<Bytes>,
+        f: impl FnOnce(Bytes, Option<T>) -> FileResult<T>,
+    ) -> FileResult<T> {
+        // If the value was accessed since the last reset, it's up to date.
+        if mem::replace(&mut self.accessed, true) {
+            if let Some(data) = &self.data {
+                return data.clone();
+            }
         }
 
-        if self.package.version != spec.version {
-            bail!(
-                "package manifest contains mismatched version {}",
-                self.package.version
-            );
+        // Otherwise, reload the data and only recompute the value if the
+        // data changed.
+        let result = load();
+        let fingerprint = hash128(&result);
+        if mem::replace(&mut self.fingerprint, fingerprint) == fingerprint {
+            if let Some(data) = &self.data {
+                return data.clone();
+            }
         }
 
-        Ok(())
+        let prev = self.data.take().and_then(Result::ok);
+        let value = result.and_then(|data| f(data, prev));
+        self.data = Some(value.clone());
+        value
     }
 }
 
-/// The `package` key in the manifest.
-#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
-pub struct PackageInfo {
-    /// The name of the package within its namespace.
-    pub name: EcoString,
-    /// The package's version.
-    pub version: Version,
-    /// The path of the entrypoint into the package.
-    pub entrypoint: EcoString,
+/// Decode UTF-8 text, skipping a leading byte order mark.
+fn decode_utf8(data: &[u8]) -> FileResult<&str> {
+    let data = data.strip_prefix(b"\xef\xbb\xbf").unwrap_or(data);
+    std::str::from_utf8(data).map_err(FileError::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(text: &'static str) -> impl FnOnce(FileId) -> FileResult<Bytes> {
+        move |_| Ok(text.as_bytes().into())
+    }
+
+    #[test]
+    fn test_lazy_file_store() {
+        fn sync<T: Send + Sync>() {}
+        sync::<LazyFileStore>();
+
+        let store = LazyFileStore::new();
+        let id = FileId::new(None, Path::new("/main.typ"));
+
+        let first = store.source(id, load("= Hello")).unwrap();
+        let cached = store.source(id, |_| panic!("should not reload")).unwrap();
+        assert!(std::ptr::eq(first.root(), cached.root()));
+
+        // Unchanged files keep their identity after a reset.
+        store.reset();
+        let unchanged = store.source(id, load("= Hello")).unwrap();
+        assert!(std::ptr::eq(first.root(), unchanged.root()));
+
+        // Changed files are reparsed.
+        store.invalidate(id);
+        let changed = store.source(id, load("= World")).unwrap();
+        assert_eq!(changed.text(), "= World");
+        assert_eq!(changed.id(), id);
+
+        store.reset();
+        let missing = store.file(id, |_| Err(FileError::NotFound("/main.typ".into())));
+        assert_eq!(missing, Err(FileError::NotFound("/main.typ".into())));
+    }
 }
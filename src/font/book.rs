@@ -14,12 +14,18 @@ pub struct FontBook {
     families: BTreeMap<String, Vec<usize>>,
     /// Metadata about each font in the collection.
     infos: Vec<FontInfo>,
+    /// Lowercased names of the families to prefer for fallback, in order.
+    fallback: Vec<String>,
 }
 
 impl FontBook {
     /// Create a new, empty font book.
     pub fn new() -> Self {
-        Self { families: BTreeMap::new(), infos: vec![] }
+        Self {
+            families: BTreeMap::new(),
+            infos: vec![],
+            fallback: vec![],
+        }
     }
 
     /// Create a font book for a collection of fonts.
@@ -31,6 +37,21 @@ impl FontBook {
         book
     }
 
+    /// Create a font book from metadata alone.
+    ///
+    /// Together with [`FontInfo::iter_fast`], this lets hosts index large font
+    /// collections quickly and defer the construction of a [`Font`] until
+    /// [`World::font`](crate::World::font) is first called for its index.
+    /// Font selection only depends on the metadata, so it behaves exactly as
+    /// with a book created [from fonts](Self::from_fonts).
+    pub fn from_infos(infos: impl IntoIterator<Item = FontInfo>) -> Self {
+        let mut book = Self::new();
+        for info in infos {
+            book.push(info);
+        }
+        book
+    }
+
     /// Insert metadata into the font book.
     pub fn push(&mut self, info: FontInfo) {
         let index = self.infos.len();
@@ -39,6 +60,16 @@ impl FontBook {
         self.infos.push(info);
     }
 
+    /// Set the families to try first when falling back to a font that is not
+    /// in the text's font list.
+    ///
+    /// The families are consulted in order, and the first one with a font
+    /// that covers the text wins. Only if none of them does, the fallback is
+    /// chosen among all fonts based on coverage.
+    pub fn set_fallback_priority(&mut self, families: &[&str]) {
+        self.fallback = families.iter().map(|family| family.to_lowercase()).collect();
+    }
+
     /// Get the font info for the given index.
     pub fn info(&self, index: usize) -> Option<&FontInfo> {
         self.infos.get(index)
@@ -78,9 +109,14 @@ impl FontBook {
     }
 
     /// Try to find and load a fallback font that
+    /// - is from the first [prioritized](Self::set_fallback_priority) family
+    ///   that is suitable for shaping the given `text`, or otherwise
     /// - is as close as possible to the font `like` (if any)
     /// - is as close as possible to the given `variant`
     /// - is suitable for shaping the given `text`
+    ///
+    /// Ties are broken by the order in which the fonts were added to the book,
+    /// so the selection is stable across compilations.
     pub fn select_fallback(
         &self,
         like: Option<&FontInfo>,
@@ -89,15 +125,18 @@ impl FontBook {
     ) -> Option<usize> {
         // Find the fonts that contain the text's first char ...
         let c = text.chars().next()?;
-        let ids = self
-            .infos
-            .iter()
-            .enumerate()
-            .filter(|(_, info)| info.coverage.contains(c as u32))
-            .map(|(index, _)| index);
+        let covers = |id: &usize| self.infos[*id].coverage.contains(c as u32);
+
+        // ... preferring the configured families ...
+        for family in &self.fallback {
+            let ids = self.select_family(family).filter(covers);
+            if let Some(id) = self.find_best_variant(like, variant, ids) {
+                return Some(id);
+            }
+        }
 
         // ... and find the best variant among them.
-        self.find_best_variant(like, variant, ids)
+        self.find_best_variant(like, variant, (0..self.infos.len()).filter(covers))
     }
 
     /// Find the font in the passed iterator that
@@ -195,8 +234,44 @@ impl FontInfo {
         })
     }
 
+    /// Compute metadata for all fonts in the given data, parsing as little of
+    /// it as possible.
+    ///
+    /// Only the tables that the metadata is derived from are read: `name`,
+    /// `OS/2`, and `cmap`, as well as `post` for monospace detection and the
+    /// mandatory `head`, `hhea`, and `maxp` tables. The results are the same
+    /// as with [`iter`](Self::iter).
+    pub fn iter_fast(data: &[u8]) -> impl Iterator<Item = FontInfo> + '_ {
+        let count = ttf_parser::fonts_in_collection(data).unwrap_or(1);
+        (0..count).filter_map(move |index| {
+            let raw = ttf_parser::RawFace::parse(data, index).ok()?;
+            let table = |tag: &[u8; 4]| raw.table(Tag::from_bytes(tag));
+            let tables = ttf_parser::RawFaceTables {
+                head: table(b"head")?,
+                hhea: table(b"hhea")?,
+                maxp: table(b"maxp")?,
+                name: table(b"name"),
+                os2: table(b"OS/2"),
+                cmap: table(b"cmap"),
+                post: table(b"post"),
+                ..Default::default()
+            };
+            let os2 = tables.os2;
+            let ttf = ttf_parser::Face::from_raw_tables(tables).ok()?;
+            Self::from_tables(&ttf, os2)
+        })
+    }
+
     /// Compute metadata for a single ttf-parser face.
     pub(super) fn from_ttf(ttf: &ttf_parser::Face) -> Option<Self> {
+        Self::from_tables(ttf, ttf.raw_face().table(Tag::from_bytes(b"OS/2")))
+    }
+
+    /// Compute metadata for a face, given the raw data of its `OS/2` table.
+    ///
+    /// The table is passed separately because faces that were constructed
+    /// from individual tables don't provide access to them.
+    fn from_tables(ttf: &ttf_parser::Face, os2: Option<&[u8]>) -> Option<Self> {
         // We cannot use Name ID 16 "Typographic Family", because for some
         // fonts it groups together more than just Style / Weight / Stretch
         // variants (e.g. Display variants of Noto fonts) and then some
@@ -267,11 +342,7 @@ impl FontInfo {
         flags.set(FontFlags::MONOSPACE, ttf.is_monospaced());
 
         // Determine whether this is a serif or sans-serif font.
-        if let Some(panose) = ttf
-            .raw_face()
-            .table(Tag::from_bytes(b"OS/2"))
-            .and_then(|os2| os2.get(32..45))
-        {
+        if let Some(panose) = os2.and_then(|os2| os2.get(32..45)) {
             if matches!(panose, [2, 2..=10, ..]) {
                 flags.insert(FontFlags::SERIF);
             }
@@ -512,6 +583,45 @@ mod tests {
         assert_eq!(typographic_family("Font Ultra Bold"), "Font");
     }
 
+    #[test]
+    fn test_fallback_priority() {
+        let info = |family: &str, chars: &str| FontInfo {
+            family: family.into(),
+            variant: FontVariant::default(),
+            flags: FontFlags::empty(),
+            coverage: Coverage::from_vec(chars.chars().map(|c| c as u32).collect()),
+        };
+
+        let mut book = FontBook::from_infos([
+            info("Pan", "a中😀"),
+            info("Emoji", "😀"),
+            info("CJK", "中"),
+        ]);
+
+        let select = |book: &FontBook, text| {
+            book.select_fallback(None, FontVariant::default(), text)
+        };
+
+        assert_eq!(select(&book, "中"), Some(0));
+        book.set_fallback_priority(&["Emoji", "cjk"]);
+        assert_eq!(select(&book, "中"), Some(2));
+        assert_eq!(select(&book, "😀"), Some(1));
+        assert_eq!(select(&book, "a"), Some(0));
+        assert_eq!(select(&book, "b"), None);
+    }
+
+    #[test]
+    fn test_font_info_iter_fast() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/fonts");
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let data = std::fs::read(entry.unwrap().path()).unwrap();
+            let eager: Vec<_> = FontInfo::iter(&data).collect();
+            let fast: Vec<_> = FontInfo::iter_fast(&data).collect();
+            assert!(!eager.is_empty());
+            assert_eq!(eager, fast);
+        }
+    }
+
     #[test]
     fn test_coverage() {
         #[track_caller]
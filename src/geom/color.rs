@@ -1,5 +1,7 @@
 use std::str::FromStr;
 
+use crate::eval::Cast;
+
 use super::*;
 
 /// A color in a dynamic format.
@@ -11,6 +13,8 @@ pub enum Color {
     Rgba(RgbaColor),
     /// An 8-bit CMYK color.
     Cmyk(CmykColor),
+    /// A color in the Oklab color space.
+    Oklab(OklabColor),
 }
 
 impl Color {
@@ -39,6 +43,7 @@ impl Color {
             Self::Luma(luma) => luma.to_rgba(),
             Self::Rgba(rgba) => rgba,
             Self::Cmyk(cmyk) => cmyk.to_rgba(),
+            Self::Oklab(oklab) => oklab.to_rgba(),
         }
     }
 
@@ -48,6 +53,7 @@ impl Color {
             Self::Luma(luma) => Self::Luma(luma.lighten(factor)),
             Self::Rgba(rgba) => Self::Rgba(rgba.lighten(factor)),
             Self::Cmyk(cmyk) => Self::Cmyk(cmyk.lighten(factor)),
+            Self::Oklab(oklab) => Self::Oklab(oklab.lighten(factor)),
         }
     }
 
@@ -57,15 +63,163 @@ impl Color {
             Self::Luma(luma) => Self::Luma(luma.darken(factor)),
             Self::Rgba(rgba) => Self::Rgba(rgba.darken(factor)),
             Self::Cmyk(cmyk) => Self::Cmyk(cmyk.darken(factor)),
+            Self::Oklab(oklab) => Self::Oklab(oklab.darken(factor)),
         }
     }
 
+    /// Increase the saturation of this color by the given factor.
+    ///
+    /// The saturation is adjusted in the HSL representation of the color.
+    /// Grayscale colors are not affected.
+    pub fn saturate(self, factor: Ratio) -> Self {
+        self.map_saturation(|s| s + (1.0 - s) * factor.get())
+    }
+
+    /// Decrease the saturation of this color by the given factor.
+    ///
+    /// The saturation is adjusted in the HSL representation of the color.
+    /// Grayscale colors are not affected.
+    pub fn desaturate(self, factor: Ratio) -> Self {
+        self.map_saturation(|s| s - s * factor.get())
+    }
+
     /// Negate this color.
     pub fn negate(self) -> Self {
         match self {
             Self::Luma(luma) => Self::Luma(luma.negate()),
             Self::Rgba(rgba) => Self::Rgba(rgba.negate()),
             Self::Cmyk(cmyk) => Self::Cmyk(cmyk.negate()),
+            Self::Oklab(oklab) => Self::Oklab(oklab.negate()),
+        }
+    }
+
+    /// The components of this color in its color model.
+    ///
+    /// Luma and RGBA components are integers, CMYK components are ratios and
+    /// Oklab colors consist of a lightness ratio, the `a` and `b` coordinates
+    /// as floats, and an alpha ratio.
+    pub fn components(self) -> Array {
+        let ratio = |c: u8| Ratio::new(c as f64 / 255.0);
+        match self {
+            Self::Luma(LumaColor(v)) => array![v as i64],
+            Self::Rgba(RgbaColor { r, g, b, a }) => {
+                array![r as i64, g as i64, b as i64, a as i64]
+            }
+            Self::Cmyk(CmykColor { c, m, y, k }) => {
+                array![ratio(c), ratio(m), ratio(y), ratio(k)]
+            }
+            Self::Oklab(OklabColor { l, a, b, alpha }) => {
+                array![Ratio::new(l), a, b, ratio(alpha)]
+            }
+        }
+    }
+
+    /// Mix colors with the given weights in a color space.
+    ///
+    /// The alpha channels are mixed alongside the other components. Since the
+    /// CMYK model has no alpha channel, colors mixed in it are opaque.
+    pub fn mix(
+        colors: impl IntoIterator<Item = (Self, f64)>,
+        space: ColorSpace,
+    ) -> StrResult<Self> {
+        let mut total = 0.0;
+        let mut acc = [0.0; 5];
+        for (color, weight) in colors {
+            if weight < 0.0 {
+                bail!("weights must not be negative");
+            }
+            let components = color.encode(space);
+            for (sum, v) in acc.iter_mut().zip(components) {
+                *sum += weight * v;
+            }
+            total += weight;
+        }
+
+        if total <= 0.0 {
+            bail!("sum of weights must be positive");
+        }
+
+        Ok(Self::decode(acc.map(|v| v / total), space))
+    }
+
+    /// Interpolate between this and another color in a color space.
+    pub fn interpolate(self, other: Self, t: f64, space: ColorSpace) -> Self {
+        let (a, b) = (self.encode(space), other.encode(space));
+        Self::decode([0, 1, 2, 3, 4].map(|i| a[i] + t * (b[i] - a[i])), space)
+    }
+
+    /// The components of this color in a color space, with the alpha value
+    /// as the last entry. Color spaces with only three components leave the
+    /// fourth one at zero.
+    fn encode(self, space: ColorSpace) -> [f64; 5] {
+        match space {
+            ColorSpace::Srgb => {
+                let [r, g, b, a] = self.to_srgb();
+                [r, g, b, 0.0, a]
+            }
+            ColorSpace::LinearRgb => {
+                let [r, g, b, a] = self.to_srgb();
+                let [r, g, b] = [r, g, b].map(srgb_to_linear);
+                [r, g, b, 0.0, a]
+            }
+            ColorSpace::Oklab => {
+                let OklabColor { l, a, b, alpha } = match self {
+                    Self::Oklab(oklab) => oklab,
+                    _ => OklabColor::from_srgb(self.to_srgb()),
+                };
+                [l, a, b, 0.0, alpha as f64 / 255.0]
+            }
+            ColorSpace::Cmyk => {
+                let CmykColor { c, m, y, k } = match self {
+                    Self::Luma(luma) => luma.to_cmyk(),
+                    Self::Cmyk(cmyk) => cmyk,
+                    _ => CmykColor::from_rgba(self.to_rgba()),
+                };
+                let f = |c: u8| c as f64 / 255.0;
+                [f(c), f(m), f(y), f(k), 1.0]
+            }
+        }
+    }
+
+    /// Create a color from its components in a color space.
+    fn decode([x, y, z, w, alpha]: [f64; 5], space: ColorSpace) -> Self {
+        let u = |v: f64| round_u8(v.clamp(0.0, 1.0) * 255.0);
+        match space {
+            ColorSpace::Srgb => RgbaColor::new(u(x), u(y), u(z), u(alpha)).into(),
+            ColorSpace::LinearRgb => {
+                let [r, g, b] = [x, y, z].map(linear_to_srgb);
+                RgbaColor::new(u(r), u(g), u(b), u(alpha)).into()
+            }
+            ColorSpace::Oklab => OklabColor::new(x, y, z, u(alpha)).into(),
+            ColorSpace::Cmyk => CmykColor::new(u(x), u(y), u(z), u(w)).into(),
+        }
+    }
+
+    /// The unrounded sRGB components of this color and its alpha value.
+    fn to_srgb(self) -> [f64; 4] {
+        match self {
+            Self::Oklab(oklab) => oklab.to_srgb(),
+            _ => {
+                let RgbaColor { r, g, b, a } = self.to_rgba();
+                [r, g, b, a].map(|c| c as f64 / 255.0)
+            }
+        }
+    }
+
+    /// Adjust the HSL saturation of this color, keeping its color model.
+    fn map_saturation(self, f: impl Fn(f64) -> f64) -> Self {
+        if let Self::Luma(_) = self {
+            return self;
+        }
+
+        let [r, g, b, alpha] = self.to_srgb();
+        let [h, s, l] = rgb_to_hsl([r, g, b]);
+        let [r, g, b] = hsl_to_rgb([h, f(s).clamp(0.0, 1.0), l]);
+        let srgb = [r, g, b, alpha];
+        match self {
+            Self::Oklab(_) => OklabColor::from_srgb(srgb).into(),
+            Self::Cmyk(_) => CmykColor::from_rgba(RgbaColor::from_srgb(srgb)).into(),
+            _ => RgbaColor::from_srgb(srgb).into(),
         }
     }
 }
@@ -76,10 +230,24 @@ impl Debug for Color {
             Self::Luma(c) => Debug::fmt(c, f),
             Self::Rgba(c) => Debug::fmt(c, f),
             Self::Cmyk(c) => Debug::fmt(c, f),
+            Self::Oklab(c) => Debug::fmt(c, f),
         }
     }
 }
 
+/// A color space in which colors can be mixed and interpolated.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum ColorSpace {
+    /// Interpolate the gamma-encoded sRGB components.
+    Srgb,
+    /// Interpolate the linear light intensities of the RGB components.
+    LinearRgb,
+    /// Interpolate in the perceptually uniform Oklab color space.
+    Oklab,
+    /// Interpolate the device CMYK components.
+    Cmyk,
+}
+
 /// An 8-bit grayscale color.
 #[derive(Copy, Clone, Eq, PartialEq, Hash)]
 pub struct LumaColor(pub u8);
@@ -154,6 +322,30 @@ impl RgbaColor {
         Self { r, g, b, a }
     }
 
+    /// Construct an RGBA color from a hue, saturation, and lightness.
+    pub fn from_hsl(hue: Angle, saturation: Ratio, lightness: Ratio, a: u8) -> Self {
+        let h = hue.to_deg().rem_euclid(360.0);
+        let [r, g, b] = hsl_to_rgb([h, saturation.get(), lightness.get()]);
+        Self::from_srgb([r, g, b, a as f64 / 255.0])
+    }
+
+    /// Construct an RGBA color from a hue, saturation, and value.
+    pub fn from_hsv(hue: Angle, saturation: Ratio, value: Ratio, a: u8) -> Self {
+        // Convert to HSL, which has the same hue.
+        let (s, v) = (saturation.get(), value.get());
+        let l = v * (1.0 - s / 2.0);
+        let s = if l <= 0.0 || l >= 1.0 { 0.0 } else { (v - l) / l.min(1.0 - l) };
+        let h = hue.to_deg().rem_euclid(360.0);
+        let [r, g, b] = hsl_to_rgb([h, s, l]);
+        Self::from_srgb([r, g, b, a as f64 / 255.0])
+    }
+
+    /// Round unrounded sRGB components and an alpha value.
+    fn from_srgb(srgb: [f64; 4]) -> Self {
+        let [r, g, b, a] = srgb.map(|c| round_u8(c.clamp(0.0, 1.0) * 255.0));
+        Self { r, g, b, a }
+    }
+
     /// Lighten this color by a factor.
     ///
     /// The alpha channel is not affected.
@@ -280,6 +472,21 @@ impl CmykColor {
         Self { c, m, y, k }
     }
 
+    /// Convert an RGBA color to CMYK, discarding the alpha channel.
+    ///
+    /// This is the inverse of [`to_rgba`](Self::to_rgba), putting as much of
+    /// the color as possible into the key component.
+    pub fn from_rgba(rgba: RgbaColor) -> Self {
+        let [r, g, b] = [rgba.r, rgba.g, rgba.b].map(|c| c as f64 / 255.0);
+        let k = 1.0 - r.max(g).max(b);
+        if k >= 1.0 {
+            return Self::new(0, 0, 0, u8::MAX);
+        }
+
+        let f = |c: f64| round_u8(255.0 * (1.0 - c - k) / (1.0 - k));
+        Self::new(f(r), f(g), f(b), round_u8(255.0 * k))
+    }
+
     /// Convert this color to RGBA.
     pub fn to_rgba(self) -> RgbaColor {
         let k = self.k as f64 / 255.0;
@@ -347,6 +554,173 @@ impl From<CmykColor> for Color {
     }
 }
 
+/// A color in the perceptually uniform Oklab color space.
+#[derive(Copy, Clone, PartialEq)]
+pub struct OklabColor {
+    /// The perceived lightness, between zero and one.
+    pub l: f64,
+    /// The position on the green-red axis.
+    pub a: f64,
+    /// The position on the blue-yellow axis.
+    pub b: f64,
+    /// The alpha channel.
+    pub alpha: u8,
+}
+
+impl OklabColor {
+    /// Construct a new Oklab color.
+    pub fn new(l: f64, a: f64, b: f64, alpha: u8) -> Self {
+        Self { l, a, b, alpha }
+    }
+
+    /// Construct an Oklab color from its polar form with a lightness, a
+    /// chroma, and a hue.
+    pub fn from_lch(l: f64, chroma: f64, hue: Angle, alpha: u8) -> Self {
+        Self::new(l, chroma * hue.cos(), chroma * hue.sin(), alpha)
+    }
+
+    /// Convert this color to RGBA.
+    ///
+    /// Colors outside of the sRGB gamut are clipped to it.
+    pub fn to_rgba(self) -> RgbaColor {
+        RgbaColor::from_srgb(self.to_srgb())
+    }
+
+    /// Lighten this color by a factor.
+    ///
+    /// The alpha channel is not affected.
+    pub fn lighten(self, factor: Ratio) -> Self {
+        Self { l: self.l + (1.0 - self.l) * factor.get(), ..self }
+    }
+
+    /// Darken this color by a factor.
+    ///
+    /// The alpha channel is not affected.
+    pub fn darken(self, factor: Ratio) -> Self {
+        Self { l: self.l - self.l * factor.get(), ..self }
+    }
+
+    /// Negate this color, mirroring its lightness and hue.
+    ///
+    /// The alpha channel is not affected.
+    pub fn negate(self) -> Self {
+        Self::new(1.0 - self.l, -self.a, -self.b, self.alpha)
+    }
+
+    /// Convert unrounded sRGB components and an alpha value to Oklab.
+    fn from_srgb([r, g, b, alpha]: [f64; 4]) -> Self {
+        let [l, a, b] = linear_to_oklab([r, g, b].map(srgb_to_linear));
+        Self::new(l, a, b, round_u8(alpha.clamp(0.0, 1.0) * 255.0))
+    }
+
+    /// The unrounded, but clipped sRGB components and alpha value.
+    fn to_srgb(self) -> [f64; 4] {
+        let linear = oklab_to_linear([self.l, self.a, self.b]);
+        let [r, g, b] = linear.map(|c| linear_to_srgb(c).clamp(0.0, 1.0));
+        [r, g, b, self.alpha as f64 / 255.0]
+    }
+}
+
+impl Eq for OklabColor {}
+
+impl Hash for OklabColor {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.l.to_bits().hash(state);
+        self.a.to_bits().hash(state);
+        self.b.to_bits().hash(state);
+        self.alpha.hash(state);
+    }
+}
+
+impl Debug for OklabColor {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "oklab({:.1}%, {:.3}, {:.3}", 100.0 * self.l, self.a, self.b)?;
+        if self.alpha != u8::MAX {
+            write!(f, ", {:.1}%", 100.0 * (self.alpha as f64 / 255.0))?;
+        }
+        f.write_str(")")
+    }
+}
+
+impl From<OklabColor> for Color {
+    fn from(oklab: OklabColor) -> Self {
+        Self::Oklab(oklab)
+    }
+}
+
+/// Convert HSL with a hue in degrees to sRGB.
+fn hsl_to_rgb([h, s, l]: [f64; 3]) -> [f64; 3] {
+    let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let f = |n: f64| {
+        let k = (n + h / 30.0) % 12.0;
+        l - c / 2.0 * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0)
+    };
+    [f(0.0), f(8.0), f(4.0)]
+}
+
+/// Convert sRGB to HSL with a hue in degrees.
+fn rgb_to_hsl([r, g, b]: [f64; 3]) -> [f64; 3] {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = (max + min) / 2.0;
+    let d = max - min;
+    if d <= 0.0 {
+        return [0.0, 0.0, l];
+    }
+
+    let s = d / (1.0 - (2.0 * l - 1.0).abs());
+    let h = if max == r {
+        60.0 * ((g - b) / d).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / d + 2.0)
+    } else {
+        60.0 * ((r - g) / d + 4.0)
+    };
+    [h, s, l]
+}
+
+/// Decode a gamma-encoded sRGB component.
+fn srgb_to_linear(c: f64) -> f64 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Gamma-encode a linear sRGB component.
+fn linear_to_srgb(c: f64) -> f64 {
+    if c <= 0.0031308 {
+        12.92 * c
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Convert linear sRGB to Oklab.
+fn linear_to_oklab([r, g, b]: [f64; 3]) -> [f64; 3] {
+    let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+    let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+    let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+    [
+        0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+        1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+        0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+    ]
+}
+
+/// Convert Oklab to linear sRGB.
+fn oklab_to_linear([l, a, b]: [f64; 3]) -> [f64; 3] {
+    let l_ = (l + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+    let m_ = (l - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+    let s_ = (l - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+    [
+        4.0767416621 * l_ - 3.3077115913 * m_ + 0.2309699292 * s_,
+        -1.2684380046 * l_ + 2.6097574011 * m_ - 0.3413193965 * s_,
+        -0.0041960863 * l_ - 0.7034186147 * m_ + 1.7076147010 * s_,
+    ]
+}
+
 /// Convert to the closest u8.
 fn round_u8(value: f64) -> u8 {
     value.round() as u8
@@ -0,0 +1,246 @@
+use std::sync::Arc;
+
+use crate::eval::{Cast, IntoValue};
+
+use super::*;
+
+/// How many colors are sampled between two stops of a gradient that is not
+/// interpolated in sRGB.
+const SAMPLES_PER_SEGMENT: usize = 16;
+
+/// A color gradient.
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub struct Gradient(Arc<Repr>);
+
+/// The internal representation of a gradient.
+#[derive(Eq, PartialEq, Hash)]
+struct Repr {
+    /// The shape of the gradient.
+    kind: GradientKind,
+    /// The colors and their offsets, in ascending order.
+    stops: Vec<(Color, Ratio)>,
+    /// The color space in which colors are interpolated.
+    space: ColorSpace,
+    /// The bounding box the gradient is spread over.
+    relative: Smart<RelativeTo>,
+}
+
+/// The shape of a gradient.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum GradientKind {
+    /// The colors change along a straight line with the given angle, which is
+    /// measured clockwise from the positive x-axis.
+    Linear { angle: Angle },
+    /// The colors change with the distance from a center point. The center is
+    /// relative to the bounding box and the radius is relative to half the
+    /// length of the bounding box's diagonal.
+    Radial { center: Axes<Ratio>, radius: Ratio },
+}
+
+/// The bounding box a gradient is spread over.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum RelativeTo {
+    /// The bounding box of the filled or stroked element itself.
+    #[string("self")]
+    Self_,
+    /// The bounding box of the surrounding container. This is the page,
+    /// unless the element is placed in a transformed or clipped container.
+    Parent,
+}
+
+/// The geometry of a gradient within its bounding box.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum GradientGeometry {
+    /// A linear gradient from the first to the second point.
+    Linear(Point, Point),
+    /// A radial gradient around a center point with the given radius.
+    Radial(Point, Abs),
+}
+
+impl Gradient {
+    /// Create a new gradient.
+    ///
+    /// Stops without an explicit offset are distributed evenly between their
+    /// neighbours. The first and last stop default to `0%` and `100%`.
+    pub fn new(
+        kind: GradientKind,
+        stops: Vec<(Color, Option<Ratio>)>,
+        space: ColorSpace,
+        relative: Smart<RelativeTo>,
+    ) -> StrResult<Self> {
+        if stops.len() < 2 {
+            bail!("a gradient must have at least two stops");
+        }
+
+        let mut offsets: Vec<Option<f64>> =
+            stops.iter().map(|(_, offset)| offset.map(Ratio::get)).collect();
+        let last = offsets.len() - 1;
+        offsets[0].get_or_insert(0.0);
+        offsets[last].get_or_insert(1.0);
+
+        let known: Vec<usize> =
+            (0..offsets.len()).filter(|&i| offsets[i].is_some()).collect();
+        for pair in known.windows(2) {
+            let (start, end) = (pair[0], pair[1]);
+            let (from, to) = (offsets[start].unwrap(), offsets[end].unwrap());
+            for (k, offset) in offsets.iter_mut().enumerate().take(end).skip(start + 1) {
+                let t = (k - start) as f64 / (end - start) as f64;
+                *offset = Some(from + t * (to - from));
+            }
+        }
+
+        let mut prev = 0.0;
+        for offset in offsets.iter().flatten() {
+            if !(0.0..=1.0).contains(offset) {
+                bail!("offset must be between 0% and 100%");
+            }
+            if *offset < prev {
+                bail!("offsets must be in ascending order");
+            }
+            prev = *offset;
+        }
+
+        let stops = stops
+            .into_iter()
+            .zip(offsets)
+            .map(|((color, _), offset)| (color, Ratio::new(offset.unwrap())))
+            .collect();
+
+        Ok(Self(Arc::new(Repr { kind, stops, space, relative })))
+    }
+
+    /// The shape of the gradient.
+    pub fn kind(&self) -> GradientKind {
+        self.0.kind
+    }
+
+    /// The colors and their offsets, in ascending order.
+    pub fn stops(&self) -> &[(Color, Ratio)] {
+        &self.0.stops
+    }
+
+    /// The color space in which colors are interpolated.
+    pub fn space(&self) -> ColorSpace {
+        self.0.space
+    }
+
+    /// The bounding box the gradient is spread over, as it was specified.
+    pub fn relative(&self) -> Smart<RelativeTo> {
+        self.0.relative
+    }
+
+    /// The bounding box the gradient is spread over. If it wasn't specified,
+    /// text fills span their container and everything else spans itself.
+    pub fn unwrap_relative(&self, on_text: bool) -> RelativeTo {
+        self.0.relative.unwrap_or(if on_text {
+            RelativeTo::Parent
+        } else {
+            RelativeTo::Self_
+        })
+    }
+
+    /// The color at the given offset.
+    pub fn sample(&self, t: Ratio) -> RgbaColor {
+        let t = t.get();
+        let stops = &self.0.stops;
+        let (first, last) = (&stops[0], &stops[stops.len() - 1]);
+        if t <= first.1.get() {
+            return first.0.to_rgba();
+        } else if t >= last.1.get() {
+            return last.0.to_rgba();
+        }
+
+        let i = stops.iter().rposition(|(_, offset)| offset.get() <= t).unwrap();
+        let (c0, t0) = stops[i];
+        let (c1, t1) = stops[i + 1];
+        let s = if t1.get() > t0.get() {
+            (t - t0.get()) / (t1.get() - t0.get())
+        } else {
+            1.0
+        };
+        c0.interpolate(c1, s, self.0.space).to_rgba()
+    }
+
+    /// The stops converted to sRGB such that linearly interpolating between
+    /// them in sRGB approximates the gradient.
+    ///
+    /// For gradients interpolated in other color spaces, additional stops are
+    /// sampled between the original ones.
+    pub fn sampled_stops(&self) -> Vec<(RgbaColor, Ratio)> {
+        let stops = &self.0.stops;
+        if self.0.space == ColorSpace::Srgb {
+            return stops.iter().map(|&(color, t)| (color.to_rgba(), t)).collect();
+        }
+
+        let mut sampled = vec![(stops[0].0.to_rgba(), stops[0].1)];
+        for pair in stops.windows(2) {
+            let [(c0, t0), (c1, t1)] = [pair[0], pair[1]];
+            for k in 1..=SAMPLES_PER_SEGMENT {
+                let s = k as f64 / SAMPLES_PER_SEGMENT as f64;
+                let color = c0.interpolate(c1, s, self.0.space).to_rgba();
+                let t = Ratio::new(t0.get() + s * (t1.get() - t0.get()));
+                sampled.push((color, t));
+            }
+        }
+
+        sampled
+    }
+
+    /// The geometry of the gradient within a bounding box of the given size
+    /// with its origin at the top-left.
+    pub fn geometry(&self, size: Size) -> GradientGeometry {
+        match self.0.kind {
+            GradientKind::Linear { angle } => {
+                let (sin, cos) = (angle.sin(), angle.cos());
+                let length = size.x * cos.abs() + size.y * sin.abs();
+                let center = size.to_point() * 0.5;
+                let delta = Point::new(length * cos, length * sin) * 0.5;
+                GradientGeometry::Linear(center - delta, center + delta)
+            }
+            GradientKind::Radial { center, radius } => {
+                let center = Point::new(center.x.of(size.x), center.y.of(size.y));
+                let diagonal = size.x.to_raw().hypot(size.y.to_raw());
+                GradientGeometry::Radial(center, Abs::raw(radius.get() * diagonal / 2.0))
+            }
+        }
+    }
+}
+
+impl Debug for Gradient {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self.0.kind {
+            GradientKind::Linear { .. } => f.write_str("gradient.linear(")?,
+            GradientKind::Radial { .. } => f.write_str("gradient.radial(")?,
+        }
+
+        for (i, (color, offset)) in self.0.stops.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "({color:?}, {offset:?})")?;
+        }
+
+        match self.0.kind {
+            GradientKind::Linear { angle } => write!(f, ", angle: {angle:?}")?,
+            GradientKind::Radial { center, radius } => write!(
+                f,
+                ", center: ({:?}, {:?}), radius: {radius:?}",
+                center.x, center.y
+            )?,
+        }
+
+        if self.0.space != ColorSpace::Srgb {
+            write!(f, ", space: {:?}", self.0.space.into_value())?;
+        }
+
+        if let Smart::Custom(relative) = self.0.relative {
+            write!(f, ", relative: {:?}", relative.into_value())?;
+        }
+
+        f.write_str(")")
+    }
+}
+
+cast! {
+    type Gradient: "gradient",
+}
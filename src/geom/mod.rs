@@ -12,9 +12,11 @@ mod dir;
 mod ellipse;
 mod em;
 mod fr;
+mod gradient;
 mod length;
 mod paint;
 mod path;
+mod pattern;
 mod point;
 mod ratio;
 mod rel;
@@ -31,21 +33,23 @@ pub use self::abs::{Abs, AbsUnit};
 pub use self::align::{Align, GenAlign, HorizontalAlign, VerticalAlign};
 pub use self::angle::{Angle, AngleUnit};
 pub use self::axes::{Axes, Axis};
-pub use self::color::{CmykColor, Color, LumaColor, RgbaColor};
+pub use self::color::{CmykColor, Color, ColorSpace, LumaColor, OklabColor, RgbaColor};
 pub use self::corners::{Corner, Corners};
 pub use self::dir::Dir;
 pub use self::ellipse::ellipse;
 pub use self::em::Em;
 pub use self::fr::Fr;
+pub use self::gradient::{Gradient, GradientGeometry, GradientKind, RelativeTo};
 pub use self::length::Length;
 pub use self::paint::Paint;
 pub use self::path::{Path, PathItem};
+pub use self::pattern::Pattern;
 pub use self::point::Point;
 pub use self::ratio::Ratio;
 pub use self::rel::Rel;
-pub use self::rounded::rounded_rect;
+pub use self::rounded::{rounded_path, rounded_rect};
 pub use self::scalar::Scalar;
-pub use self::shape::{Geometry, Shape};
+pub use self::shape::{FillRule, Geometry, Shape};
 pub use self::sides::{Side, Sides};
 pub use self::size::Size;
 pub use self::smart::Smart;
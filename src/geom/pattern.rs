@@ -0,0 +1,89 @@
+use std::sync::Arc;
+
+use comemo::Prehashed;
+
+use crate::doc::Frame;
+use crate::eval::{Cast, IntoValue};
+
+use super::*;
+
+/// A tiling pattern: a frame that is repeated to fill an area.
+///
+/// Values of this type are cheap to clone and hash.
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub struct Pattern(Arc<Prehashed<Repr>>);
+
+/// The internal representation of a pattern.
+#[derive(Hash)]
+struct Repr {
+    /// The laid out content of a single tile.
+    frame: Frame,
+    /// The gap between two neighbouring tiles.
+    spacing: Size,
+    /// The bounding box whose top-left corner the tiles are aligned to.
+    relative: Smart<RelativeTo>,
+}
+
+impl Pattern {
+    /// Create a new pattern from the frame of a single tile.
+    pub fn new(frame: Frame, spacing: Size, relative: Smart<RelativeTo>) -> Self {
+        Self(Arc::new(Prehashed::new(Repr { frame, spacing, relative })))
+    }
+
+    /// The laid out content of a single tile.
+    pub fn frame(&self) -> &Frame {
+        &self.0.frame
+    }
+
+    /// The size of a single tile.
+    pub fn size(&self) -> Size {
+        self.0.frame.size()
+    }
+
+    /// The gap between two neighbouring tiles.
+    pub fn spacing(&self) -> Size {
+        self.0.spacing
+    }
+
+    /// The distance from the start of one tile to the start of the next.
+    pub fn step(&self) -> Size {
+        self.size() + self.spacing()
+    }
+
+    /// The bounding box the tiles are aligned to, as it was specified.
+    pub fn relative(&self) -> Smart<RelativeTo> {
+        self.0.relative
+    }
+
+    /// The bounding box the tiles are aligned to. If it wasn't specified,
+    /// text fills use their container and everything else uses itself.
+    pub fn unwrap_relative(&self, on_text: bool) -> RelativeTo {
+        self.0.relative.unwrap_or(if on_text {
+            RelativeTo::Parent
+        } else {
+            RelativeTo::Self_
+        })
+    }
+}
+
+impl Debug for Pattern {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let Size { x, y } = self.size();
+        write!(f, "pattern(size: ({x:?}, {y:?})")?;
+
+        let spacing = self.spacing();
+        if !spacing.is_zero() {
+            write!(f, ", spacing: ({:?}, {:?})", spacing.x, spacing.y)?;
+        }
+
+        if let Smart::Custom(relative) = self.0.relative {
+            write!(f, ", relative: {:?}", relative.into_value())?;
+        }
+
+        f.write_str(", ..)")
+    }
+}
+
+cast! {
+    type Pattern: "pattern",
+}
@@ -12,6 +12,7 @@ pub fn rounded_rect(
         res.push(Shape {
             geometry: fill_geometry(size, radius),
             fill,
+            fill_rule: FillRule::default(),
             stroke: if stroke.is_uniform() { stroke.top.clone() } else { None },
         });
     }
@@ -19,7 +20,12 @@ pub fn rounded_rect(
     if !stroke.is_uniform() {
         for (path, stroke) in stroke_segments(size, radius, stroke) {
             if stroke.is_some() {
-                res.push(Shape { geometry: Geometry::Path(path), fill: None, stroke });
+                res.push(Shape {
+                    geometry: Geometry::Path(path),
+                    fill: None,
+                    fill_rule: FillRule::default(),
+                    stroke,
+                });
             }
         }
     }
@@ -27,15 +33,24 @@ pub fn rounded_rect(
     res
 }
 
+/// Produce a path that describes a rectangle with rounded corners.
+pub fn rounded_path(size: Size, radius: Corners<Abs>) -> Path {
+    if radius.iter().copied().all(Abs::is_zero) {
+        Path::rect(size)
+    } else {
+        let mut paths = stroke_segments(size, radius, Sides::splat(None));
+        assert_eq!(paths.len(), 1);
+        paths.pop().unwrap().0
+    }
+}
+
 /// Output the shape of the rectangle as a path or primitive rectangle,
 /// depending on whether it is rounded.
 fn fill_geometry(size: Size, radius: Corners<Abs>) -> Geometry {
     if radius.iter().copied().all(Abs::is_zero) {
         Geometry::Rect(size)
     } else {
-        let mut paths = stroke_segments(size, radius, Sides::splat(None));
-        assert_eq!(paths.len(), 1);
-        Geometry::Path(paths.pop().unwrap().0)
+        Geometry::Path(rounded_path(size, radius))
     }
 }
 
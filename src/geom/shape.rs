@@ -1,3 +1,5 @@
+use crate::eval::Cast;
+
 use super::*;
 
 /// A geometric shape with optional fill and stroke.
@@ -7,10 +9,38 @@ pub struct Shape {
     pub geometry: Geometry,
     /// The shape's background fill.
     pub fill: Option<Paint>,
+    /// The rule that decides which parts of the geometry are filled.
+    pub fill_rule: FillRule,
     /// The shape's border stroke.
     pub stroke: Option<Stroke>,
 }
 
+impl Shape {
+    /// The top-left corner and size of the smallest axis-aligned rectangle
+    /// that contains the shape, including its stroke.
+    ///
+    /// The stroke is assumed to extend by half its thickness beyond the
+    /// geometry. Sharp miter joins may reach a bit further.
+    pub fn bbox(&self) -> (Point, Size) {
+        let (origin, size) = self.geometry.bbox();
+        let Some(stroke) = &self.stroke else { return (origin, size) };
+        let half = Point::splat(stroke.thickness / 2.0);
+        (origin - half, size + 2.0 * half.to_size())
+    }
+}
+
+/// The rule that decides whether a point lies inside of a shape.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum FillRule {
+    /// A point is inside if the outline winds around it a non-zero number of
+    /// times, taking the outline's direction into account.
+    #[default]
+    NonZero,
+    /// A point is inside if any ray from it crosses the outline an odd number
+    /// of times.
+    EvenOdd,
+}
+
 /// A shape's geometry.
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub enum Geometry {
@@ -25,11 +55,105 @@ pub enum Geometry {
 impl Geometry {
     /// Fill the geometry without a stroke.
     pub fn filled(self, fill: Paint) -> Shape {
-        Shape { geometry: self, fill: Some(fill), stroke: None }
+        Shape {
+            geometry: self,
+            fill: Some(fill),
+            fill_rule: FillRule::default(),
+            stroke: None,
+        }
     }
 
     /// Stroke the geometry without a fill.
     pub fn stroked(self, stroke: Stroke) -> Shape {
-        Shape { geometry: self, fill: None, stroke: Some(stroke) }
+        Shape {
+            geometry: self,
+            fill: None,
+            fill_rule: FillRule::default(),
+            stroke: Some(stroke),
+        }
+    }
+
+    /// The top-left corner and size of the smallest axis-aligned rectangle
+    /// that contains the geometry.
+    ///
+    /// For paths, this takes the extrema of curves into account rather than
+    /// their control points.
+    pub fn bbox(&self) -> (Point, Size) {
+        let points = match self {
+            Self::Line(target) => vec![Point::zero(), *target],
+            Self::Rect(size) => vec![Point::zero(), size.to_point()],
+            Self::Path(path) => {
+                let mut points = vec![];
+                let mut cursor = Point::zero();
+                for item in &path.0 {
+                    match *item {
+                        PathItem::MoveTo(p) | PathItem::LineTo(p) => {
+                            points.push(p);
+                            cursor = p;
+                        }
+                        PathItem::CubicTo(p1, p2, p3) => {
+                            let curve = [cursor, p1, p2, p3];
+                            points.extend(cubic_extrema(curve, |p| p.x));
+                            points.extend(cubic_extrema(curve, |p| p.y));
+                            points.push(p3);
+                            cursor = p3;
+                        }
+                        PathItem::ClosePath => {}
+                    }
+                }
+                points
+            }
+        };
+
+        if points.is_empty() {
+            return (Point::zero(), Size::zero());
+        }
+
+        let mut min = Point::splat(Abs::inf());
+        let mut max = Point::splat(-Abs::inf());
+        for p in points {
+            min = min.min(p);
+            max = max.max(p);
+        }
+
+        (min, (max - min).to_size())
     }
 }
+
+/// The points at which a cubic Bézier curve has an extremum along the axis
+/// selected by `coord`, excluding its end points.
+fn cubic_extrema(
+    [p0, p1, p2, p3]: [Point; 4],
+    coord: fn(Point) -> Abs,
+) -> impl Iterator<Item = Point> {
+    let [c0, c1, c2, c3] = [p0, p1, p2, p3].map(|p| coord(p).to_raw());
+
+    // The roots of the derivative a t² + b t + c.
+    let a = -c0 + 3.0 * c1 - 3.0 * c2 + c3;
+    let b = 2.0 * (c0 - 2.0 * c1 + c2);
+    let c = c1 - c0;
+
+    let roots = if a.abs() < 1e-12 {
+        [(b.abs() > 1e-12).then(|| -c / b), None]
+    } else {
+        let discriminant = b * b - 4.0 * a * c;
+        if discriminant < 0.0 {
+            [None, None]
+        } else {
+            let sqrt = discriminant.sqrt();
+            [Some((-b + sqrt) / (2.0 * a)), Some((-b - sqrt) / (2.0 * a))]
+        }
+    };
+
+    roots
+        .into_iter()
+        .flatten()
+        .filter(|t| *t > 0.0 && *t < 1.0)
+        .map(move |t| {
+            let u = 1.0 - t;
+            p0 * (u * u * u)
+                + p1 * (3.0 * u * u * t)
+                + p2 * (3.0 * u * t * t)
+                + p3 * (t * t * t)
+        })
+}
@@ -1,4 +1,4 @@
-use crate::eval::{Cast, FromValue};
+use crate::eval::{dict, Cast, FromValue, IntoValue};
 
 use super::*;
 
@@ -186,7 +186,7 @@ impl Resolve for PartialStroke {
     }
 }
 
-impl Fold for PartialStroke<Abs> {
+impl<T> Fold for PartialStroke<T> {
     type Output = Self;
 
     fn fold(self, outer: Self::Output) -> Self::Output {
@@ -211,6 +211,10 @@ cast! {
         paint: Smart::Custom(color.into()),
         ..Default::default()
     },
+    gradient: Gradient => Self {
+        paint: Smart::Custom(gradient.into()),
+        ..Default::default()
+    },
     mut dict: Dict => {
         fn take<T: FromValue>(dict: &mut Dict, key: &str) -> StrResult<Smart<T>> {
             Ok(dict.take(key).ok().map(T::from_value)
@@ -322,6 +326,7 @@ impl Resolve for DashPattern {
 // https://tex.stackexchange.com/questions/45275/tikz-get-values-for-predefined-dash-patterns
 cast! {
     DashPattern,
+    self => dict! { "array" => self.array, "phase" => self.phase }.into_value(),
 
     "solid" => Vec::new().into(),
     "dotted" => vec![DashLength::LineWidth, Abs::pt(2.0).into()].into(),
@@ -348,12 +353,21 @@ cast! {
 }
 
 /// The length of a dash in a line dash pattern
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[derive(Clone, Eq, PartialEq, Hash)]
 pub enum DashLength<T = Length> {
     LineWidth,
     Length(T),
 }
 
+impl<T: Debug> Debug for DashLength<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::LineWidth => f.pad("\"dot\""),
+            Self::Length(v) => v.fmt(f),
+        }
+    }
+}
+
 impl From<Abs> for DashLength {
     fn from(l: Abs) -> Self {
         DashLength::Length(l.into())
@@ -382,6 +396,10 @@ impl Resolve for DashLength {
 
 cast! {
     DashLength,
+    self => match self {
+        Self::LineWidth => "dot".into_value(),
+        Self::Length(v) => v.into_value(),
+    },
     "dot" => Self::LineWidth,
     v: Length => Self::Length(v),
 }
@@ -47,6 +47,15 @@ impl Transform {
         }
     }
 
+    /// A skew transform, slanting the x-axis by `ax` and the y-axis by `ay`.
+    pub fn skew(ax: Angle, ay: Angle) -> Self {
+        Self {
+            kx: Ratio::new(ax.tan()),
+            ky: Ratio::new(ay.tan()),
+            ..Self::identity()
+        }
+    }
+
     /// Whether this is the identity transformation.
     pub fn is_identity(self) -> bool {
         self == Self::identity()
@@ -68,6 +77,26 @@ impl Transform {
     pub fn post_concat(self, next: Self) -> Self {
         next.pre_concat(self)
     }
+
+    /// The inverse transformation, if there is one.
+    pub fn invert(self) -> Option<Self> {
+        let (sx, ky, kx, sy) =
+            (self.sx.get(), self.ky.get(), self.kx.get(), self.sy.get());
+        let (tx, ty) = (self.tx.to_raw(), self.ty.to_raw());
+        let det = sx * sy - kx * ky;
+        if det.abs() < 1e-12 {
+            return None;
+        }
+
+        Some(Self {
+            sx: Ratio::new(sy / det),
+            ky: Ratio::new(-ky / det),
+            kx: Ratio::new(-kx / det),
+            sy: Ratio::new(sx / det),
+            tx: Abs::raw((kx * ty - sy * tx) / det),
+            ty: Abs::raw((ky * tx - sx * ty) / det),
+        })
+    }
 }
 
 impl Default for Transform {
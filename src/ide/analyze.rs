@@ -1,11 +1,11 @@
 use comemo::Track;
 use ecow::EcoString;
 
-use crate::doc::Frame;
+use crate::doc::{Document, Frame};
 use crate::eval::{eval, Module, Route, Tracer, Value};
-use crate::model::{Introspector, Label};
-use crate::syntax::{ast, LinkedNode, Source, SyntaxKind};
-use crate::World;
+use crate::model::{Introspector, Label, Location};
+use crate::syntax::{ast, LinkedNode, Source, Span, SyntaxKind};
+use crate::{Limits, World};
 
 /// Try to determine a set of possible values for an expression.
 pub fn analyze_expr(world: &(dyn World + 'static), node: &LinkedNode) -> Vec<Value> {
@@ -35,16 +35,20 @@ pub fn analyze_expr(world: &(dyn World + 'static), node: &LinkedNode) -> Vec<Val
 
             let route = Route::default();
             let mut tracer = Tracer::new(Some(node.span()));
+            let limits = Limits::default();
             typst::eval::eval(
                 world.track(),
                 route.track(),
                 tracer.track_mut(),
+                limits,
                 &world.main(),
             )
             .and_then(|module| {
                 typst::model::typeset(
                     world.track(),
                     tracer.track_mut(),
+                    limits,
+                    None,
                     &module.content(),
                 )
             })
@@ -67,7 +71,8 @@ pub fn analyze_import(
     let mut tracer = Tracer::default();
     let id = source.id().join(path).ok()?;
     let source = world.source(id).ok()?;
-    eval(world.track(), route.track(), tracer.track_mut(), &source).ok()
+    eval(world.track(), route.track(), tracer.track_mut(), Limits::default(), &source)
+        .ok()
 }
 
 /// Find all labels and details for them.
@@ -108,3 +113,59 @@ pub fn analyze_labels(
 
     (output, split)
 }
+
+/// Find all labels in a compiled document.
+///
+/// Returns the labels in document order alongside the location and span of
+/// the element they are attached to. A label that is attached to multiple
+/// elements is listed once for each of them.
+pub fn labels(document: &Document) -> Vec<(Label, Location, Span)> {
+    let introspector = Introspector::new(&document.pages);
+    introspector
+        .all()
+        .filter_map(|elem| Some((elem.label()?.clone(), elem.location()?, elem.span())))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::doc::{FrameItem, Meta};
+    use crate::file::FileId;
+    use crate::geom::{Point, Size};
+    use crate::model::{Content, Locator};
+
+    #[test]
+    fn test_labels() {
+        let id = FileId::new(None, Path::new("/test.typ"));
+        let mut locator = Locator::new();
+        let mut page = Frame::new(Size::zero());
+        let names = [Some("a"), None, Some("b"), Some("a")];
+        for (i, label) in names.into_iter().enumerate() {
+            let span = Span::new(id, 2 + i as u64);
+            let mut elem = Content::empty().spanned(span);
+            if let Some(label) = label {
+                elem = elem.labelled(Label(label.into()));
+            }
+            elem.set_location(locator.locate(i as u128));
+            page.push(Point::zero(), FrameItem::Meta(Meta::Elem(elem), Size::zero()));
+        }
+
+        // A label attached to multiple elements is listed for each of them.
+        let document = Document { pages: vec![page], ..Document::default() };
+        let found: Vec<_> = labels(&document)
+            .into_iter()
+            .map(|(label, _, span)| (label.0, span))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("a".into(), Span::new(id, 2)),
+                ("b".into(), Span::new(id, 4)),
+                ("a".into(), Span::new(id, 5)),
+            ]
+        );
+    }
+}
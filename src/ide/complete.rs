@@ -1,4 +1,5 @@
 use std::collections::{BTreeSet, HashSet};
+use std::path::Path;
 
 use ecow::{eco_format, EcoString};
 use if_chain::if_chain;
@@ -8,6 +9,7 @@ use super::analyze::analyze_labels;
 use super::{analyze_expr, analyze_import, plain_docs_sentence, summarize_font_family};
 use crate::doc::Frame;
 use crate::eval::{methods_on, CastInfo, Library, Scope, Value};
+use crate::file::FileId;
 use crate::syntax::{
     ast, is_id_continue, is_id_start, is_ident, LinkedNode, Source, SyntaxKind,
 };
@@ -31,6 +33,7 @@ pub fn autocomplete(
     let mut ctx = CompletionContext::new(world, frames, source, cursor, explicit)?;
 
     let _ = complete_comments(&mut ctx)
+        || complete_paths(&mut ctx)
         || complete_field_accesses(&mut ctx)
         || complete_imports(&mut ctx)
         || complete_rules(&mut ctx)
@@ -71,6 +74,8 @@ pub enum CompletionKind {
     Constant,
     /// A symbol.
     Symbol(char),
+    /// A file or directory path.
+    Path,
 }
 
 /// Complete in comments. Or rather, don't!
@@ -78,6 +83,81 @@ fn complete_comments(ctx: &mut CompletionContext) -> bool {
     matches!(ctx.leaf.kind(), SyntaxKind::LineComment | SyntaxKind::BlockComment)
 }
 
+/// Complete file paths and package specifications in strings:
+/// "#import "|"", "#include "sub/|"", "#image("pic|")", "#import "@pre|"".
+fn complete_paths(ctx: &mut CompletionContext) -> bool {
+    if ctx.leaf.kind() != SyntaxKind::Str {
+        return false;
+    }
+
+    // Only complete inside of the quotes.
+    let start = ctx.leaf.offset() + 1;
+    let text = ctx.leaf.text();
+    let closed = text.len() >= 2 && text.ends_with('"');
+    if ctx.cursor < start || (closed && ctx.cursor >= ctx.leaf.range().end) {
+        return false;
+    }
+
+    let Some(extensions) = path_extensions(&ctx.leaf) else { return false };
+
+    // Package specifications are not paths.
+    let typed = &ctx.text[start..ctx.cursor];
+    if typed.starts_with('@') {
+        if ctx.leaf.parent_kind() == Some(SyntaxKind::ModuleImport) {
+            ctx.from = start;
+            ctx.package_completions(typed.contains(':'));
+        }
+        return true;
+    }
+
+    let dir = match typed.rfind('/') {
+        Some(i) => &typed[..=i],
+        None => "",
+    };
+
+    ctx.from = start + dir.len();
+    ctx.path_completions(dir, extensions);
+    true
+}
+
+/// The file extensions that make sense at a path string, or `None` if the
+/// string is not a path.
+///
+/// An empty list means that all files are allowed.
+fn path_extensions(leaf: &LinkedNode) -> Option<&'static [&'static str]> {
+    let parent = leaf.parent()?;
+    if matches!(parent.kind(), SyntaxKind::ModuleImport | SyntaxKind::ModuleInclude) {
+        return Some(&["typ"]);
+    }
+
+    // Only the first positional argument of a file-loading function is a path.
+    if parent.kind() != SyntaxKind::Args {
+        return None;
+    }
+
+    let first = parent.children().find(|child| child.is::<ast::Expr>())?;
+    if first.offset() != leaf.offset() {
+        return None;
+    }
+
+    let ast::Expr::FuncCall(call) = parent.parent()?.cast::<ast::Expr>()? else {
+        return None;
+    };
+
+    let ast::Expr::Ident(callee) = call.callee() else { return None };
+    Some(match callee.as_str() {
+        "image" => &["png", "jpg", "jpeg", "gif", "svg"],
+        "read" => &[],
+        "csv" => &["csv"],
+        "cbor" => &["cbor"],
+        "json" => &["json"],
+        "toml" => &["toml"],
+        "yaml" => &["yaml", "yml"],
+        "xml" => &["xml"],
+        _ => return None,
+    })
+}
+
 /// Complete in markup mode.
 fn complete_markup(ctx: &mut CompletionContext) -> bool {
     // Bail if we aren't even in markup.
@@ -960,6 +1040,77 @@ impl<'a> CompletionContext<'a> {
         }
     }
 
+    /// Add completions for the entries of a directory relative to the current
+    /// file.
+    ///
+    /// Files are only included if they have one of the given extensions or
+    /// if the list of extensions is empty.
+    fn path_completions(&mut self, dir: &str, extensions: &[&str]) {
+        let Ok(id) = self.source.id().join(dir) else { return };
+        let Some(mut entries) = self.world.entries(id) else { return };
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+
+        for entry in entries {
+            // Hide hidden files unless explicitly requested.
+            if entry.name.starts_with('.') && !self.before.ends_with('.') {
+                continue;
+            }
+
+            if entry.is_dir {
+                self.completions.push(Completion {
+                    kind: CompletionKind::Path,
+                    label: eco_format!("{}/", entry.name),
+                    apply: None,
+                    detail: Some("Directory.".into()),
+                });
+                continue;
+            }
+
+            let matches = extensions.is_empty()
+                || Path::new(entry.name.as_str())
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .map_or(false, |ext| {
+                        extensions.iter().any(|e| e.eq_ignore_ascii_case(ext))
+                    });
+
+            let file = FileId::new(id.package().cloned(), &id.path().join(&*entry.name));
+            if matches && file != self.source.id() {
+                self.completions.push(Completion {
+                    kind: CompletionKind::Path,
+                    label: entry.name,
+                    apply: None,
+                    detail: None,
+                });
+            }
+        }
+    }
+
+    /// Add completions for the packages that the world knows of.
+    ///
+    /// Only the newest version of each package is included unless all versions
+    /// are requested.
+    fn package_completions(&mut self, all_versions: bool) {
+        let mut packages: Vec<_> = self.world.packages().iter().collect();
+        packages.sort_by(|(a, _), (b, _)| {
+            (&a.namespace, &a.name, b.version).cmp(&(&b.namespace, &b.name, a.version))
+        });
+        if !all_versions {
+            packages.dedup_by(|(a, _), (b, _)| {
+                a.namespace == b.namespace && a.name == b.name
+            });
+        }
+
+        for (spec, description) in packages {
+            self.completions.push(Completion {
+                kind: CompletionKind::Constant,
+                label: eco_format!("{spec}"),
+                apply: None,
+                detail: description.clone(),
+            });
+        }
+    }
+
     /// Add completions for raw block tags.
     fn raw_completions(&mut self) {
         for (name, mut tags) in (self.library.items.raw_languages)() {
@@ -1156,3 +1307,63 @@ impl<'a> CompletionContext<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::package::PackageSpec;
+    use crate::testing::TestWorld;
+
+    /// The completions at the cursor in the main file and where they start.
+    fn complete(world: &TestWorld, cursor: usize) -> (usize, Vec<Completion>) {
+        autocomplete(world, &[], &world.main(), cursor, false).unwrap()
+    }
+
+    /// The labels of the completions, in order.
+    fn labels(completions: &[Completion]) -> Vec<&str> {
+        completions.iter().map(|c| c.label.as_str()).collect()
+    }
+
+    #[test]
+    fn test_path_completions() {
+        let world = TestWorld::new("#image(\"\")")
+            .with_file("a.png", "")
+            .with_file("b.typ", "")
+            .with_file("sub/c.svg", "");
+        let (from, completions) = complete(&world, 8);
+        assert_eq!(from, 8);
+        assert_eq!(labels(&completions), ["a.png", "sub/"]);
+
+        let world = TestWorld::new("#cbor(\"\")")
+            .with_file("a.cbor", "")
+            .with_file("b.json", "");
+        let (_, completions) = complete(&world, 7);
+        assert_eq!(labels(&completions), ["a.cbor"]);
+    }
+
+    #[test]
+    fn test_package_completions() {
+        let spec = |s: &str| s.parse::<PackageSpec>().unwrap();
+        let packages = [
+            (spec("@preview/example:0.1.0"), None),
+            (spec("@preview/example:0.2.0"), Some(EcoString::from("An example."))),
+            (spec("@preview/other:1.0.0"), None),
+        ];
+
+        let world =
+            TestWorld::new("#import \"@preview/\"").with_packages(packages.clone());
+        let (from, completions) = complete(&world, 17);
+        assert_eq!(from, 9);
+        assert_eq!(
+            labels(&completions),
+            ["@preview/example:0.2.0", "@preview/other:1.0.0"]
+        );
+        assert_eq!(completions[0].detail.as_deref(), Some("An example."));
+
+        // Once a version is being typed, all versions are offered.
+        let world =
+            TestWorld::new("#import \"@preview/example:\"").with_packages(packages);
+        let (_, completions) = complete(&world, 26);
+        assert_eq!(completions.len(), 3);
+    }
+}
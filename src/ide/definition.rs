@@ -0,0 +1,358 @@
+use std::collections::HashSet;
+
+use comemo::Track;
+use ecow::EcoString;
+
+use crate::file::FileId;
+use crate::package::PackageRequest;
+use crate::syntax::ast::{self, AstNode};
+use crate::syntax::{LinkedNode, Source, Span, SyntaxKind, SyntaxNode};
+use crate::World;
+
+/// Find the definition of the identifier or reference at the cursor.
+///
+/// Identifiers resolve to the let binding, parameter, or loop variable that
+/// defines them, following imports into other files and through chains of
+/// re-exports. References resolve to the label they refer to.
+///
+/// Returns `None` if there is nothing to define at the cursor or the
+/// definition cannot be found, e.g. because it's part of the standard library.
+pub fn definition(world: &dyn World, source: &Source, cursor: usize) -> Option<Span> {
+    let leaf = LinkedNode::new(source.root()).leaf_at(cursor)?;
+    let mut resolver = Resolver::new(world);
+    match leaf.kind() {
+        SyntaxKind::Ident | SyntaxKind::MathIdent => resolver.ident(source.id(), &leaf),
+        SyntaxKind::RefMarker => {
+            let name = leaf.text().strip_prefix('@')?;
+            resolver.label(source.id(), name).or_else(|| {
+                resolver.visited.clear();
+                resolver.label(world.main().id(), name)
+            })
+        }
+        _ => None,
+    }
+}
+
+/// What a name is bound to.
+pub(super) enum Binding {
+    /// The definition at the given span.
+    Span(Span),
+    /// A whole module that was imported without specifying items.
+    Module(FileId),
+}
+
+/// Resolves names across files.
+pub(super) struct Resolver<'a> {
+    world: &'a dyn World,
+    /// The files that were already searched. Prevents us from getting stuck
+    /// in cyclic imports.
+    visited: HashSet<FileId>,
+}
+
+impl<'a> Resolver<'a> {
+    /// Create a new resolver.
+    pub(super) fn new(world: &'a dyn World) -> Self {
+        Self { world, visited: HashSet::new() }
+    }
+
+    /// Find the definition of an identifier.
+    pub(super) fn ident(&mut self, id: FileId, leaf: &LinkedNode) -> Option<Span> {
+        let name = leaf.text();
+        let parent = leaf.parent()?;
+
+        // An identifier that is being defined: "#let he|lper = 1".
+        if defines(leaf) {
+            return Some(leaf.span());
+        }
+
+        // A named argument: "#f(si|ze: 1pt)". Other names, like dictionary
+        // keys, have no definition.
+        if parent.kind() == SyntaxKind::Named && leaf.index() == 0 {
+            let args = parent.parent()?;
+            if args.kind() != SyntaxKind::Args {
+                return None;
+            }
+            return self.param(id, args, name);
+        }
+
+        // An item in an import list: "#import "utils.typ": he|lper".
+        if parent.kind() == SyntaxKind::ImportItems {
+            let import: ast::ModuleImport = parent.parent()?.cast()?;
+            let (target, _) = self.target(id, &import.source())?;
+            let binding = self.in_module(target, name)?;
+            return self.resolve(binding);
+        }
+
+        // A field of a module: "#utils.he|lper".
+        if parent.kind() == SyntaxKind::FieldAccess && leaf.index() > 0 {
+            let target = parent.children().next()?;
+            if target.kind() != SyntaxKind::Ident {
+                return None;
+            }
+
+            let Binding::Module(module) = self.in_scope(id, &target, target.text())?
+            else {
+                return None;
+            };
+
+            let binding = self.in_module(module, name)?;
+            return self.resolve(binding);
+        }
+
+        let binding = self.in_scope(id, leaf, name)?;
+        self.resolve(binding)
+    }
+
+    /// Find the parameter of a closure that a named argument refers to.
+    fn param(&mut self, id: FileId, args: &LinkedNode, name: &str) -> Option<Span> {
+        let callee = args.prev_sibling()?;
+        if callee.kind() != SyntaxKind::Ident {
+            return None;
+        }
+
+        let Binding::Span(def) = self.in_scope(id, &callee, callee.text())? else {
+            return None;
+        };
+
+        let closure = self.closure(def)?;
+        closure.params().children().find_map(|param| match param {
+            ast::Param::Named(named) if named.name().as_str() == name => {
+                Some(named.name().span())
+            }
+            _ => None,
+        })
+    }
+
+    /// Find the closure defined by the identifier at a span, either as its
+    /// name or as the name of the let binding it is assigned to.
+    pub(super) fn closure(&self, def: Span) -> Option<ast::Closure> {
+        let source = self.world.source(def.id()).ok()?;
+        let node = LinkedNode::new(source.root()).find(def)?;
+        let parent = node.parent()?;
+        match parent.cast::<ast::Closure>() {
+            Some(closure) => Some(closure),
+            None => match parent.cast::<ast::LetBinding>()?.init()? {
+                ast::Expr::Closure(closure) => Some(closure),
+                _ => None,
+            },
+        }
+    }
+
+    /// Find the binding of a name that is visible at a node by walking
+    /// backwards through the enclosing scopes.
+    pub(super) fn in_scope(
+        &mut self,
+        id: FileId,
+        node: &LinkedNode,
+        name: &str,
+    ) -> Option<Binding> {
+        let mut node = node.clone();
+        loop {
+            let mut prev = node.prev_sibling();
+            while let Some(sibling) = prev {
+                if let Some(binding) = self.binding(id, &sibling, name) {
+                    return Some(binding);
+                }
+                prev = sibling.prev_sibling();
+            }
+
+            let parent = node.parent()?.clone();
+            if let Some(span) = bound_by_parent(&parent, &node, name) {
+                return Some(Binding::Span(span));
+            }
+
+            node = parent;
+        }
+    }
+
+    /// Find the top-level binding of a name in another file.
+    fn in_module(&mut self, id: FileId, name: &str) -> Option<Binding> {
+        if !self.visited.insert(id) {
+            return None;
+        }
+
+        let source = self.world.source(id).ok()?;
+        let root = source.root();
+        root.children().rev().find_map(|child| self.binding(id, child, name))
+    }
+
+    /// Check whether a statement binds a name.
+    fn binding(&mut self, id: FileId, node: &SyntaxNode, name: &str) -> Option<Binding> {
+        if let Some(binding) = node.cast::<ast::LetBinding>() {
+            let ident =
+                binding.kind().idents().into_iter().find(|i| i.as_str() == name)?;
+            return Some(Binding::Span(ident.span()));
+        }
+
+        let import = node.cast::<ast::ModuleImport>()?;
+        match import.imports() {
+            None => {
+                let (target, module) = self.target(id, &import.source())?;
+                (module.as_str() == name).then_some(Binding::Module(target))
+            }
+            Some(ast::Imports::Wildcard) => {
+                let (target, _) = self.target(id, &import.source())?;
+                self.in_module(target, name)
+            }
+            Some(ast::Imports::Items(items)) => {
+                let ident = items.into_iter().find(|i| i.as_str() == name)?;
+                let resolved = self
+                    .target(id, &import.source())
+                    .and_then(|(target, _)| self.in_module(target, name));
+                Some(resolved.unwrap_or(Binding::Span(ident.span())))
+            }
+        }
+    }
+
+    /// Determine the file an import or include refers to, together with the
+    /// name of the resulting module.
+    ///
+    /// Only handles string literals.
+    pub(super) fn target(
+        &self,
+        id: FileId,
+        source: &ast::Expr,
+    ) -> Option<(FileId, EcoString)> {
+        let ast::Expr::Str(path) = source else { return None };
+        let path = path.get();
+        if path.starts_with('@') {
+            let request: PackageRequest = path.parse().ok()?;
+            let (spec, manifest) =
+                crate::package::resolve(self.world.track(), &request).ok()?;
+            Some((manifest.entrypoint(&spec), manifest.package.name))
+        } else {
+            let target = id.join(&path).ok()?;
+            let name = target.path().file_stem()?.to_string_lossy().into();
+            Some((target, name))
+        }
+    }
+
+    /// Turn a binding into the span of its definition.
+    fn resolve(&self, binding: Binding) -> Option<Span> {
+        match binding {
+            Binding::Span(span) => Some(span),
+            Binding::Module(id) => Some(self.world.source(id).ok()?.root().span()),
+        }
+    }
+
+    /// Find a label in a file or any of the files it imports or includes.
+    fn label(&mut self, id: FileId, name: &str) -> Option<Span> {
+        if !self.visited.insert(id) {
+            return None;
+        }
+
+        let source = self.world.source(id).ok()?;
+        let mut targets = vec![];
+        let mut stack = vec![source.root()];
+        while let Some(node) = stack.pop() {
+            match node.kind() {
+                SyntaxKind::Label
+                    if node.text().trim_start_matches('<').trim_end_matches('>')
+                        == name =>
+                {
+                    return Some(node.span());
+                }
+                SyntaxKind::ModuleImport => {
+                    let import: ast::ModuleImport = node.cast()?;
+                    targets.extend(self.target(id, &import.source()));
+                }
+                SyntaxKind::ModuleInclude => {
+                    let include: ast::ModuleInclude = node.cast()?;
+                    targets.extend(self.target(id, &include.source()));
+                }
+                _ => {}
+            }
+            stack.extend(node.children().rev());
+        }
+
+        targets.into_iter().find_map(|(target, _)| self.label(target, name))
+    }
+}
+
+/// Whether an identifier is defined at its position rather than used.
+pub(super) fn defines(leaf: &LinkedNode) -> bool {
+    let Some(parent) = leaf.parent() else { return false };
+    match parent.kind() {
+        SyntaxKind::LetBinding => leaf.prev_sibling_kind() == Some(SyntaxKind::Let),
+        SyntaxKind::ForLoop => leaf.prev_sibling_kind() == Some(SyntaxKind::For),
+        SyntaxKind::Closure => leaf.index() == 0,
+        SyntaxKind::Params => true,
+        SyntaxKind::Named => parent.parent().map_or(false, |grand| match grand.kind() {
+            SyntaxKind::Params => leaf.index() == 0,
+            SyntaxKind::Destructuring => leaf.index() > 0 && is_pattern(grand),
+            _ => false,
+        }),
+        SyntaxKind::Spread | SyntaxKind::Destructuring => is_pattern(parent),
+        _ => false,
+    }
+}
+
+/// Whether a node is part of a pattern that introduces bindings.
+fn is_pattern(node: &LinkedNode) -> bool {
+    match node.kind() {
+        SyntaxKind::Params => true,
+        SyntaxKind::Destructuring | SyntaxKind::Spread => {
+            node.parent().map_or(false, |parent| {
+                matches!(
+                    parent.kind(),
+                    SyntaxKind::LetBinding | SyntaxKind::ForLoop | SyntaxKind::Params
+                ) || (parent.kind() == SyntaxKind::Destructuring && is_pattern(parent))
+            })
+        }
+        _ => false,
+    }
+}
+
+/// Check whether a node binds a name for its child, like a closure for its
+/// body or a for loop for its body.
+fn bound_by_parent(parent: &LinkedNode, child: &LinkedNode, name: &str) -> Option<Span> {
+    let idents = if let Some(closure) = parent.cast::<ast::Closure>() {
+        if child.kind() == SyntaxKind::Params {
+            return None;
+        }
+
+        let mut idents: Vec<_> = closure.name().into_iter().collect();
+        for param in closure.params().children() {
+            match param {
+                ast::Param::Pos(pattern) => idents.extend(pattern.idents()),
+                ast::Param::Named(named) => idents.push(named.name()),
+                ast::Param::Sink(spread) => idents.extend(spread.name()),
+            }
+        }
+        idents
+    } else if let Some(for_loop) = parent.cast::<ast::ForLoop>() {
+        if for_loop.body().span() != child.span() {
+            return None;
+        }
+        for_loop.pattern().idents()
+    } else {
+        return None;
+    };
+
+    idents
+        .into_iter()
+        .find(|ident| ident.as_str() == name)
+        .map(|ident| ident.span())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::testing::TestWorld;
+
+    #[test]
+    fn test_definition_across_files() {
+        let main = "#import \"a.typ\": helper\n#helper()";
+        let world = TestWorld::new(main)
+            .with_file("a.typ", "#import \"b.typ\": *")
+            .with_file("b.typ", "#import \"a.typ\": *\n#let helper() = 1");
+
+        let cursor = main.rfind("helper").unwrap() + 1;
+        let span = definition(&world, &world.main(), cursor).unwrap();
+        let b = world.source(span.id()).unwrap();
+        assert_eq!(b.id().path(), Path::new("/b.typ"));
+        assert_eq!(&b.text()[span.range_in(&b)], "helper");
+    }
+}
@@ -0,0 +1,162 @@
+use std::ops::Range;
+
+use crate::syntax::ast::{self, AstNode};
+use crate::syntax::{LinkedNode, Source, SyntaxKind};
+
+/// Compute the regions of a source file that an editor can fold.
+///
+/// These are code and content blocks, block equations, the arguments of
+/// function calls, runs of list, enumeration, and term items, runs of line
+/// comments, block comments, and the sections of headings, which extend up
+/// to the next heading of the same or a higher level. Only regions spanning
+/// multiple lines are returned.
+///
+/// The regions are sorted by their start and any two of them are either
+/// nested or disjoint.
+pub fn folding_ranges(source: &Source) -> Vec<Range<usize>> {
+    let mut ranges = vec![];
+    collect(&LinkedNode::new(source.root()), &mut ranges);
+
+    let text = source.text();
+    ranges.retain(|range| text[range.clone()].trim_end().contains('\n'));
+    ranges.sort_by_key(|range| (range.start, std::cmp::Reverse(range.end)));
+    ranges.dedup();
+
+    // Drop regions that cross others. Due to how they are collected, this
+    // shouldn't happen, but editors reject such regions altogether.
+    let mut open: Vec<Range<usize>> = vec![];
+    ranges.retain(|range| {
+        while open.last().map_or(false, |last| last.end <= range.start) {
+            open.pop();
+        }
+        if open.last().map_or(false, |last| range.end > last.end) {
+            return false;
+        }
+        open.push(range.clone());
+        true
+    });
+
+    ranges
+}
+
+/// Collect the fold regions of a node and its descendants.
+fn collect(node: &LinkedNode, out: &mut Vec<Range<usize>>) {
+    match node.kind() {
+        SyntaxKind::CodeBlock
+        | SyntaxKind::ContentBlock
+        | SyntaxKind::Equation
+        | SyntaxKind::BlockComment => out.push(node.range()),
+        SyntaxKind::Args => {
+            let open = node.children().find(|c| c.kind() == SyntaxKind::LeftParen);
+            let close = node.children().find(|c| c.kind() == SyntaxKind::RightParen);
+            if let Some(open) = open {
+                let end = close.map_or(node.range().end, |close| close.range().end);
+                out.push(open.offset()..end);
+            }
+        }
+        SyntaxKind::Markup => {
+            sections(node, out);
+            runs(node, out);
+        }
+        SyntaxKind::Code => runs(node, out),
+        _ => {}
+    }
+
+    for child in node.children() {
+        collect(&child, out);
+    }
+}
+
+/// Collect the sections of the headings in a piece of markup.
+fn sections(markup: &LinkedNode, out: &mut Vec<Range<usize>>) {
+    let children: Vec<_> = markup.children().collect();
+    for (i, child) in children.iter().enumerate() {
+        let Some(heading) = child.cast::<ast::Heading>() else { continue };
+        let level = heading.level();
+
+        // A section ends before the next heading of the same or a higher
+        // level and excludes the whitespace in front of it.
+        let rest = &children[i + 1..];
+        let next = rest
+            .iter()
+            .position(|node| {
+                node.cast::<ast::Heading>()
+                    .map_or(false, |next| next.level() <= level)
+            })
+            .unwrap_or(rest.len());
+
+        let end = rest[..next]
+            .iter()
+            .rev()
+            .find(|node| !is_blank(node))
+            .map_or(child.range().end, |node| node.range().end);
+
+        out.push(child.offset()..end);
+    }
+}
+
+/// Collect runs of list items and line comments that directly follow each
+/// other.
+fn runs(node: &LinkedNode, out: &mut Vec<Range<usize>>) {
+    let mut run: Option<(SyntaxKind, Range<usize>)> = None;
+    for child in node.children() {
+        let kind = match child.kind() {
+            SyntaxKind::ListItem | SyntaxKind::EnumItem | SyntaxKind::TermItem => {
+                Some(child.kind())
+            }
+            SyntaxKind::LineComment => Some(SyntaxKind::LineComment),
+            // A single line break continues a run, but an empty line ends it.
+            SyntaxKind::Space if child.text().matches('\n').count() <= 1 => continue,
+            _ => None,
+        };
+
+        match (&mut run, kind) {
+            (Some((run_kind, range)), Some(kind)) if *run_kind == kind => {
+                range.end = child.range().end;
+            }
+            _ => {
+                if let Some((_, range)) = run.take() {
+                    out.push(range);
+                }
+                run = kind.map(|kind| (kind, child.range()));
+            }
+        }
+    }
+
+    if let Some((_, range)) = run {
+        out.push(range);
+    }
+}
+
+/// Whether a node consists of whitespace only.
+fn is_blank(node: &LinkedNode) -> bool {
+    matches!(node.kind(), SyntaxKind::Space | SyntaxKind::Parbreak)
+        || (node.children().len() == 0 && node.text().trim().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_folding_ranges() {
+        let text = "= A\n- x\n- y\n\n== B\n// one\n// two\n#f(\n  1,\n)\n= C\ntext";
+        let source = Source::detached(text);
+        let folded: Vec<_> = folding_ranges(&source)
+            .into_iter()
+            .map(|range| &text[range])
+            .collect();
+
+        assert_eq!(
+            folded,
+            [
+                "= A\n- x\n- y\n\n== B\n// one\n// two\n#f(\n  1,\n)",
+                "- x\n- y",
+                "== B\n// one\n// two\n#f(\n  1,\n)",
+                "// one\n// two",
+                "(\n  1,\n)",
+                "= C\ntext",
+            ]
+        );
+    }
+}
@@ -2,14 +2,30 @@
 
 mod analyze;
 mod complete;
+mod definition;
+mod folding;
 mod highlight;
 mod jump;
+mod rename;
+mod semantic;
+mod signature;
+mod symbols;
+mod text;
 mod tooltip;
 
-pub use self::analyze::analyze_labels;
+pub use self::analyze::{analyze_labels, labels};
 pub use self::complete::{autocomplete, Completion, CompletionKind};
+pub use self::definition::definition;
+pub use self::folding::folding_ranges;
 pub use self::highlight::{highlight, highlight_html, Tag};
 pub use self::jump::{jump_from_click, jump_from_cursor, Jump};
+pub use self::rename::rename;
+pub use self::semantic::{
+    semantic_tokens, semantic_tokens_in, Modifiers, SemanticToken, TokenKind,
+};
+pub use self::signature::{signature_help, SignatureHelp, SignatureParam};
+pub use self::symbols::{symbols, Symbol, SymbolKind};
+pub use self::text::{text_ranges, TextRange};
 pub use self::tooltip::{tooltip, Tooltip};
 
 use std::fmt::Write;
@@ -0,0 +1,185 @@
+use std::collections::HashSet;
+use std::ops::Range;
+
+use super::definition::Resolver;
+use crate::file::FileId;
+use crate::syntax::ast::{self, visit, Visitor};
+use crate::syntax::{is_id_continue, is_ident, LinkedNode, Source, SyntaxKind};
+use crate::World;
+
+/// Compute the edits for renaming the binding, named parameter, or label at
+/// the cursor.
+///
+/// Searches the main file, the given source file, and all files they
+/// transitively import or include. Returns the ranges that must be replaced
+/// with the new name, or `None` if there is nothing to rename at the cursor,
+/// the new name is invalid, or it would collide with an existing binding or
+/// label.
+pub fn rename(
+    world: &dyn World,
+    source: &Source,
+    cursor: usize,
+    new_name: &str,
+) -> Option<Vec<(FileId, Range<usize>)>> {
+    let leaf = LinkedNode::new(source.root()).leaf_at(cursor)?;
+    let sources = reachable(world, source);
+    match leaf.kind() {
+        SyntaxKind::Ident | SyntaxKind::MathIdent => {
+            rename_binding(world, &sources, source.id(), &leaf, new_name)
+        }
+        SyntaxKind::Label => {
+            let name = leaf.text().trim_start_matches('<').trim_end_matches('>');
+            rename_label(&sources, name, new_name)
+        }
+        SyntaxKind::RefMarker => {
+            let name = leaf.text().strip_prefix('@')?;
+            rename_label(&sources, name, new_name)
+        }
+        _ => None,
+    }
+}
+
+/// Rename all identifiers that resolve to the same definition.
+fn rename_binding(
+    world: &dyn World,
+    sources: &[Source],
+    id: FileId,
+    leaf: &LinkedNode,
+    new_name: &str,
+) -> Option<Vec<(FileId, Range<usize>)>> {
+    if !is_ident(new_name) || world.library().global.scope().get(new_name).is_some() {
+        return None;
+    }
+
+    let def = Resolver::new(world).ident(id, leaf)?;
+    let mut edits = vec![];
+    for source in sources {
+        let mut stack = vec![LinkedNode::new(source.root())];
+        while let Some(node) = stack.pop() {
+            if matches!(node.kind(), SyntaxKind::Ident | SyntaxKind::MathIdent)
+                && Resolver::new(world).ident(source.id(), &node) == Some(def)
+            {
+                // The new name must not already be visible at any of the
+                // renamed places, or the renamed identifiers would either
+                // shadow it or be shadowed by it.
+                if Resolver::new(world).in_scope(source.id(), &node, new_name).is_some() {
+                    return None;
+                }
+
+                edits.push((source.id(), node.range()));
+            }
+
+            stack.extend(node.children().rev());
+        }
+    }
+
+    Some(edits)
+}
+
+/// Rename a label together with all references to it.
+fn rename_label(
+    sources: &[Source],
+    name: &str,
+    new_name: &str,
+) -> Option<Vec<(FileId, Range<usize>)>> {
+    let valid = new_name.chars().all(|c| is_id_continue(c) || matches!(c, ':' | '.'))
+        && !new_name.ends_with([':', '.']);
+    if new_name.is_empty() || !valid {
+        return None;
+    }
+
+    let mut edits = vec![];
+    for source in sources {
+        let mut stack = vec![LinkedNode::new(source.root())];
+        while let Some(node) = stack.pop() {
+            let text = node.text();
+            let found = match node.kind() {
+                SyntaxKind::Label => text.trim_start_matches('<').trim_end_matches('>'),
+                SyntaxKind::RefMarker => text.trim_start_matches('@'),
+                _ => {
+                    stack.extend(node.children().rev());
+                    continue;
+                }
+            };
+
+            if found == new_name {
+                return None;
+            }
+
+            if found == name {
+                let start = node.offset() + 1;
+                edits.push((source.id(), start..start + name.len()));
+            }
+        }
+    }
+
+    Some(edits)
+}
+
+/// Collect the main file, the given file, and all files they transitively
+/// import or include.
+fn reachable(world: &dyn World, source: &Source) -> Vec<Source> {
+    let resolver = Resolver::new(world);
+    let mut seen = HashSet::new();
+    let mut sources = vec![];
+    let mut queue = vec![source.clone(), world.main()];
+    while let Some(source) = queue.pop() {
+        if !seen.insert(source.id()) {
+            continue;
+        }
+
+        let mut targets = Targets {
+            resolver: &resolver,
+            id: source.id(),
+            found: vec![],
+        };
+        visit::walk_node(&mut targets, source.root());
+        queue.extend(targets.found.into_iter().filter_map(|id| world.source(id).ok()));
+        sources.push(source);
+    }
+
+    sources
+}
+
+/// Collects the files a source file imports or includes.
+struct Targets<'a> {
+    resolver: &'a Resolver<'a>,
+    id: FileId,
+    found: Vec<FileId>,
+}
+
+impl Visitor for Targets<'_> {
+    fn visit_module_import(&mut self, import: &ast::ModuleImport) {
+        self.found
+            .extend(self.resolver.target(self.id, &import.source()).map(|(id, _)| id));
+        visit::walk_module_import(self, import);
+    }
+
+    fn visit_module_include(&mut self, include: &ast::ModuleInclude) {
+        self.found
+            .extend(self.resolver.target(self.id, &include.source()).map(|(id, _)| id));
+        visit::walk_module_include(self, include);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestWorld;
+
+    #[test]
+    fn test_rename() {
+        let world = TestWorld::new("#let x = 1\n#{ let x = 2; x }\n#x <intro> @intro");
+        let source = world.main();
+        let ranges = |cursor, new_name| {
+            let edits = rename(&world, &source, cursor, new_name)?;
+            Some(edits.into_iter().map(|(_, range)| range).collect::<Vec<_>>())
+        };
+
+        // Renaming the inner binding doesn't touch the outer one.
+        assert_eq!(ranges(19, "y"), Some(vec![18..19, 25..26]));
+        assert_eq!(ranges(31, "y"), Some(vec![5..6, 30..31]));
+        assert_eq!(ranges(36, "outro"), Some(vec![33..38, 41..46]));
+        assert_eq!(ranges(6, "text"), None);
+    }
+}
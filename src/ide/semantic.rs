@@ -0,0 +1,483 @@
+use std::ops::Range;
+
+use super::definition::{defines, Resolver};
+use super::{highlight, Tag};
+use crate::eval::Value;
+use crate::syntax::ast;
+use crate::syntax::{LinkedNode, Source, Span, SyntaxKind};
+use crate::util::StrExt;
+use crate::World;
+
+/// A classified piece of source text, positioned like the semantic tokens of
+/// the Language Server Protocol.
+///
+/// Positions are relative to the previous token (or to the start of the file
+/// for the first token) and measured in UTF-16 code units. Tokens never span
+/// multiple lines.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct SemanticToken {
+    /// The number of lines between the previous token and this one.
+    pub delta_line: u32,
+    /// The start column of the token, relative to the start of the previous
+    /// token if it is on the same line and to the start of the line otherwise.
+    pub delta_start: u32,
+    /// The length of the token.
+    pub length: u32,
+    /// What kind of token this is.
+    pub kind: TokenKind,
+    /// Additional properties of the token.
+    pub modifiers: Modifiers,
+}
+
+/// A kind of semantic token.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum TokenKind {
+    /// A line or block comment.
+    Comment,
+    /// Punctuation in code.
+    Punctuation,
+    /// An escape sequence or shorthand.
+    Escape,
+    /// Strong markup.
+    Strong,
+    /// Emphasized markup.
+    Emph,
+    /// A hyperlink.
+    Link,
+    /// Raw text.
+    Raw,
+    /// A label.
+    Label,
+    /// A reference to a label.
+    Ref,
+    /// A section heading.
+    Heading,
+    /// A marker of a list, enumeration, or term list.
+    ListMarker,
+    /// A term in a term list.
+    ListTerm,
+    /// The delimiters of an equation.
+    MathDelimiter,
+    /// An operator with special meaning in an equation.
+    MathOperator,
+    /// A keyword.
+    Keyword,
+    /// An operator in code.
+    Operator,
+    /// A numeric literal.
+    Number,
+    /// A string literal.
+    String,
+    /// A function or method.
+    Function,
+    /// A variable.
+    Variable,
+    /// A parameter of a function.
+    Parameter,
+    /// A field of a value or the key of a dictionary.
+    Property,
+    /// A module.
+    Namespace,
+    /// A syntax error.
+    Error,
+}
+
+impl TokenKind {
+    /// All token kinds, in the order of their [indices](Self::index).
+    ///
+    /// Together with the [names](Self::name), this forms the token type legend
+    /// a language server announces to its client.
+    pub const ALL: &'static [Self] = &[
+        Self::Comment,
+        Self::Punctuation,
+        Self::Escape,
+        Self::Strong,
+        Self::Emph,
+        Self::Link,
+        Self::Raw,
+        Self::Label,
+        Self::Ref,
+        Self::Heading,
+        Self::ListMarker,
+        Self::ListTerm,
+        Self::MathDelimiter,
+        Self::MathOperator,
+        Self::Keyword,
+        Self::Operator,
+        Self::Number,
+        Self::String,
+        Self::Function,
+        Self::Variable,
+        Self::Parameter,
+        Self::Property,
+        Self::Namespace,
+        Self::Error,
+    ];
+
+    /// The index of the token kind in [`ALL`](Self::ALL).
+    pub fn index(self) -> u32 {
+        self as u32
+    }
+
+    /// The name of the token kind. Uses the predefined names of the Language
+    /// Server Protocol where one fits.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Comment => "comment",
+            Self::Punctuation => "punct",
+            Self::Escape => "escape",
+            Self::Strong => "strong",
+            Self::Emph => "emph",
+            Self::Link => "link",
+            Self::Raw => "raw",
+            Self::Label => "label",
+            Self::Ref => "ref",
+            Self::Heading => "heading",
+            Self::ListMarker => "marker",
+            Self::ListTerm => "term",
+            Self::MathDelimiter => "delim",
+            Self::MathOperator => "mathOperator",
+            Self::Keyword => "keyword",
+            Self::Operator => "operator",
+            Self::Number => "number",
+            Self::String => "string",
+            Self::Function => "function",
+            Self::Variable => "variable",
+            Self::Parameter => "parameter",
+            Self::Property => "property",
+            Self::Namespace => "namespace",
+            Self::Error => "error",
+        }
+    }
+}
+
+impl From<Tag> for TokenKind {
+    fn from(tag: Tag) -> Self {
+        match tag {
+            Tag::Comment => Self::Comment,
+            Tag::Punctuation => Self::Punctuation,
+            Tag::Escape => Self::Escape,
+            Tag::Strong => Self::Strong,
+            Tag::Emph => Self::Emph,
+            Tag::Link => Self::Link,
+            Tag::Raw => Self::Raw,
+            Tag::Label => Self::Label,
+            Tag::Ref => Self::Ref,
+            Tag::Heading => Self::Heading,
+            Tag::ListMarker => Self::ListMarker,
+            Tag::ListTerm => Self::ListTerm,
+            Tag::MathDelimiter => Self::MathDelimiter,
+            Tag::MathOperator => Self::MathOperator,
+            Tag::Keyword => Self::Keyword,
+            Tag::Operator => Self::Operator,
+            Tag::Number => Self::Number,
+            Tag::String => Self::String,
+            Tag::Function => Self::Function,
+            Tag::Interpolated => Self::Variable,
+            Tag::Error => Self::Error,
+        }
+    }
+}
+
+bitflags::bitflags! {
+    /// Additional properties of a semantic token.
+    ///
+    /// The bit positions match the order of [`Modifiers::NAMES`].
+    #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+    pub struct Modifiers: u32 {
+        /// The identifier is being defined rather than used.
+        const DEFINITION = 1 << 0;
+        /// The value cannot be mutated from here, e.g. because it was
+        /// imported from another file or is a constant of the standard
+        /// library.
+        const READONLY = 1 << 1;
+        /// The value is part of the standard library.
+        const BUILTIN = 1 << 2;
+    }
+}
+
+impl Modifiers {
+    /// The names of the modifiers, forming the token modifier legend a
+    /// language server announces to its client.
+    pub const NAMES: [&'static str; 3] = ["definition", "readonly", "defaultLibrary"];
+}
+
+/// Classify the tokens of a whole source file.
+pub fn semantic_tokens(world: &dyn World, source: &Source) -> Vec<SemanticToken> {
+    semantic_tokens_in(world, source, 0..source.len_lines())
+}
+
+/// Classify the tokens on the given lines of a source file.
+///
+/// Only the syntax nodes overlapping the lines are visited, so editors can
+/// cheaply request just the visible part of a large file. The first token is
+/// still positioned relative to the start of the file.
+///
+/// Identifiers are classified by resolving them to their definition, across
+/// files if necessary. If that fails, e.g. because the value is only known
+/// after evaluation, their kind is guessed from the surrounding syntax.
+pub fn semantic_tokens_in(
+    world: &dyn World,
+    source: &Source,
+    lines: Range<usize>,
+) -> Vec<SemanticToken> {
+    let start = source.line_to_byte(lines.start).unwrap_or(source.len_bytes());
+    let end = source.line_to_byte(lines.end).unwrap_or(source.len_bytes());
+    let mut ctx = TokenContext { world, source, tokens: vec![], prev: (0, 0) };
+    ctx.visit(&LinkedNode::new(source.root()), start..end);
+    ctx.tokens
+}
+
+/// Collects the tokens of a source file.
+struct TokenContext<'a> {
+    world: &'a dyn World,
+    source: &'a Source,
+    tokens: Vec<SemanticToken>,
+    /// The line and UTF-16 column of the previous token.
+    prev: (usize, usize),
+}
+
+impl TokenContext<'_> {
+    /// Push the tokens of all leaves that overlap the byte range.
+    fn visit(&mut self, node: &LinkedNode, range: Range<usize>) {
+        let node_range = node.range();
+        if node_range.end <= range.start || node_range.start >= range.end {
+            return;
+        }
+
+        if node.children().len() > 0 {
+            for child in node.children() {
+                self.visit(&child, range.clone());
+            }
+            return;
+        }
+
+        if let Some((kind, modifiers)) = self.classify(node) {
+            self.push(node_range, range, kind, modifiers);
+        }
+    }
+
+    /// Push a token, splitting it into one token per line.
+    fn push(
+        &mut self,
+        token: Range<usize>,
+        range: Range<usize>,
+        kind: TokenKind,
+        modifiers: Modifiers,
+    ) {
+        let mut offset = token.start;
+        for line in self.source.text()[token].split('\n') {
+            let start = offset;
+            offset += line.len() + 1;
+
+            let text = line.trim_end_matches('\r');
+            if text.trim().is_empty() || start < range.start || start >= range.end {
+                continue;
+            }
+
+            let (Some(line), Some(utf16)) =
+                (self.source.byte_to_line(start), self.source.byte_to_utf16(start))
+            else {
+                continue;
+            };
+
+            let line_start = self.source.line_to_byte(line).unwrap_or(start);
+            let column = utf16 - self.source.byte_to_utf16(line_start).unwrap_or(utf16);
+            let (prev_line, prev_column) = self.prev;
+            let delta_start =
+                if line == prev_line { column - prev_column } else { column };
+
+            self.tokens.push(SemanticToken {
+                delta_line: (line - prev_line) as u32,
+                delta_start: delta_start as u32,
+                length: text.len_utf16() as u32,
+                kind,
+                modifiers,
+            });
+
+            self.prev = (line, column);
+        }
+    }
+
+    /// Determine the kind of a leaf.
+    fn classify(&self, leaf: &LinkedNode) -> Option<(TokenKind, Modifiers)> {
+        match leaf.kind() {
+            SyntaxKind::Ident | SyntaxKind::MathIdent => Some(self.ident(leaf)),
+
+            // An embedded identifier is classified together with its hashtag:
+            // "#helper".
+            SyntaxKind::Hashtag => match leaf.next_leaf() {
+                Some(next)
+                    if next.offset() == leaf.range().end
+                        && matches!(next.kind(), SyntaxKind::Ident) =>
+                {
+                    let (kind, modifiers) = self.ident(&next);
+                    Some((kind, modifiers - Modifiers::DEFINITION))
+                }
+                _ => highlight(leaf).map(|tag| (tag.into(), Modifiers::empty())),
+            },
+
+            // Leaves without a tag of their own take on the one of the markup
+            // they appear in, like the text of a heading.
+            _ => {
+                let mut node = Some(leaf);
+                while let Some(current) = node {
+                    if let Some(tag) = highlight(current) {
+                        return Some((tag.into(), Modifiers::empty()));
+                    }
+                    node = current.parent();
+                }
+                None
+            }
+        }
+    }
+
+    /// Determine the kind of an identifier.
+    fn ident(&self, leaf: &LinkedNode) -> (TokenKind, Modifiers) {
+        let mut modifiers = Modifiers::empty();
+        if defines(leaf) {
+            modifiers |= Modifiers::DEFINITION;
+        }
+
+        let id = self.source.id();
+        let called = highlight(leaf) == Some(Tag::Function);
+        let parent = leaf.parent();
+        let parent_kind = parent.map(|parent| parent.kind());
+        let is_field = parent_kind == Some(SyntaxKind::FieldAccess) && leaf.index() > 0;
+
+        let kind = if let Some(span) = Resolver::new(self.world).ident(id, leaf) {
+            if span.id() != id {
+                modifiers |= Modifiers::READONLY;
+            }
+            self.definition_kind(span).unwrap_or(TokenKind::Variable)
+        } else if parent_kind == Some(SyntaxKind::Named) && leaf.index() == 0 {
+            // Named arguments to functions that can't be resolved, like those
+            // of the standard library, and keys of dictionaries.
+            match parent.and_then(|parent| parent.parent_kind()) {
+                Some(SyntaxKind::Args) => TokenKind::Parameter,
+                _ => TokenKind::Property,
+            }
+        } else if is_field {
+            TokenKind::Property
+        } else if let Some(value) = self.builtin(leaf) {
+            modifiers |= Modifiers::BUILTIN;
+            match value {
+                Value::Func(_) => TokenKind::Function,
+                Value::Module(_) => TokenKind::Namespace,
+                _ => {
+                    modifiers |= Modifiers::READONLY;
+                    TokenKind::Variable
+                }
+            }
+        } else {
+            TokenKind::Variable
+        };
+
+        // Values that are called are functions even if they are bound by a
+        // plain let binding, like "#let f = g". Parameters stay parameters.
+        let kind = match kind {
+            TokenKind::Variable | TokenKind::Property if called => TokenKind::Function,
+            kind => kind,
+        };
+
+        (kind, modifiers)
+    }
+
+    /// Determine the kind of the identifier defined at a span.
+    fn definition_kind(&self, span: Span) -> Option<TokenKind> {
+        // Prefer the source we were given over the world's version of the
+        // same file, which may be outdated while the user is typing.
+        let other;
+        let source = if span.id() == self.source.id() {
+            self.source
+        } else {
+            other = self.world.source(span.id()).ok()?;
+            &other
+        };
+
+        let node = source.find(span)?;
+        if node.kind() == SyntaxKind::Markup {
+            return Some(TokenKind::Namespace);
+        }
+
+        let mut ancestor = node.parent()?.clone();
+        while matches!(
+            ancestor.kind(),
+            SyntaxKind::Named | SyntaxKind::Spread | SyntaxKind::Destructuring
+        ) {
+            ancestor = ancestor.parent()?.clone();
+        }
+
+        Some(match ancestor.kind() {
+            SyntaxKind::Params => TokenKind::Parameter,
+            SyntaxKind::Closure => TokenKind::Function,
+            SyntaxKind::LetBinding => {
+                let binding = ancestor.cast::<ast::LetBinding>()?;
+                match (binding.kind(), binding.init()) {
+                    (ast::LetBindingKind::Closure(_), _)
+                    | (_, Some(ast::Expr::Closure(_))) => TokenKind::Function,
+                    _ => TokenKind::Variable,
+                }
+            }
+            _ => TokenKind::Variable,
+        })
+    }
+
+    /// Look up an identifier in the standard library.
+    fn builtin(&self, leaf: &LinkedNode) -> Option<&Value> {
+        let library = self.world.library();
+        let name = leaf.text();
+        let math = match leaf.kind() {
+            SyntaxKind::MathIdent => library.math.scope().get(name),
+            _ => None,
+        };
+        math.or_else(|| library.global.scope().get(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestWorld;
+
+    #[test]
+    fn test_semantic_tokens() {
+        let world = TestWorld::new("#let f(x) = x\n#f(red)");
+        let source = world.main();
+        let tokens = semantic_tokens(&world, &source);
+        let kinds: Vec<_> = tokens.iter().map(|t| (t.kind, t.modifiers)).collect();
+        let def = Modifiers::DEFINITION;
+        let none = Modifiers::empty();
+        let builtin = Modifiers::BUILTIN | Modifiers::READONLY;
+        assert_eq!(
+            kinds,
+            [
+                (TokenKind::Keyword, none),
+                (TokenKind::Keyword, none),
+                (TokenKind::Function, def),
+                (TokenKind::Punctuation, none),
+                (TokenKind::Parameter, def),
+                (TokenKind::Punctuation, none),
+                (TokenKind::Operator, none),
+                (TokenKind::Parameter, none),
+                (TokenKind::Function, none),
+                (TokenKind::Function, none),
+                (TokenKind::Punctuation, none),
+                (TokenKind::Variable, builtin),
+                (TokenKind::Punctuation, none),
+            ]
+        );
+
+        let positions: Vec<_> = tokens
+            .iter()
+            .map(|t| (t.delta_line, t.delta_start, t.length))
+            .collect();
+        assert_eq!(positions[..3], [(0, 0, 1), (0, 1, 3), (0, 4, 1)]);
+        assert_eq!(positions[8], (1, 0, 1));
+
+        // Requesting a range still positions relative to the start of the file.
+        let tokens = semantic_tokens_in(&world, &source, 1..2);
+        assert_eq!(tokens.len(), 5);
+        assert_eq!((tokens[0].delta_line, tokens[0].delta_start), (1, 0));
+    }
+}
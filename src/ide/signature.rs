@@ -0,0 +1,300 @@
+use ecow::EcoString;
+
+use super::definition::Resolver;
+use super::plain_docs_sentence;
+use crate::eval::{CastInfo, Value};
+use crate::syntax::ast::{self, AstNode};
+use crate::syntax::{LinkedNode, Source, SyntaxKind};
+use crate::World;
+
+/// The signature of the function that is being called at the cursor.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SignatureHelp {
+    /// The name of the function.
+    pub name: EcoString,
+    /// The first sentence of the function's documentation, if it has some.
+    pub docs: Option<EcoString>,
+    /// The parameters of the function.
+    pub params: Vec<SignatureParam>,
+    /// The index of the parameter the argument at the cursor belongs to.
+    pub active: Option<usize>,
+    /// Whether the function accepts any number of arguments for one of its
+    /// parameters.
+    pub variadic: bool,
+}
+
+/// A parameter in a [`SignatureHelp`].
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct SignatureParam {
+    /// The parameter's name.
+    pub name: EcoString,
+    /// The types of values the parameter accepts. Unknown for user-defined
+    /// functions.
+    pub types: Option<EcoString>,
+    /// The parameter's default value, if it has one.
+    pub default: Option<EcoString>,
+    /// Whether the parameter can be given positionally.
+    pub positional: bool,
+    /// Whether the parameter can be given by name.
+    pub named: bool,
+    /// Whether the parameter can be given any number of times.
+    pub variadic: bool,
+    /// Whether the call already has a named argument for the parameter.
+    pub supplied: bool,
+}
+
+/// Describe the signature of the innermost function call whose argument list
+/// contains the cursor.
+///
+/// Works for functions of the standard library and for closures defined with
+/// let bindings, also when they are imported from another file. Returns
+/// `None` if the cursor is not in an argument list or the callee can't be
+/// determined without evaluation.
+pub fn signature_help(
+    world: &dyn World,
+    source: &Source,
+    cursor: usize,
+) -> Option<SignatureHelp> {
+    let leaf = LinkedNode::new(source.root()).leaf_at(cursor)?;
+    let args = enclosing_args(&leaf, cursor)?;
+    let callee = args.prev_sibling()?;
+
+    let mut help = closure_signature(world, source, &callee)
+        .or_else(|| library_signature(world, &callee))?;
+
+    // Mark the named arguments that are already there.
+    let call = args.cast::<ast::Args>()?;
+    for arg in call.items() {
+        if let ast::Arg::Named(named) = arg {
+            let name = named.name();
+            let param = help.params.iter_mut().find(|param| param.name == name.as_str());
+            if let Some(param) = param {
+                param.supplied = true;
+            }
+        }
+    }
+
+    help.active = active_param(&help.params, &args, cursor);
+    help.variadic = help.params.iter().any(|param| param.variadic);
+    Some(help)
+}
+
+/// Find the innermost argument list whose parentheses contain the cursor.
+fn enclosing_args<'a>(leaf: &LinkedNode<'a>, cursor: usize) -> Option<LinkedNode<'a>> {
+    let mut node = leaf.clone();
+    loop {
+        match node.kind() {
+            SyntaxKind::Args if node.parent_kind() == Some(SyntaxKind::FuncCall) => {
+                let mut children = node.children();
+                let open = children.next()?;
+                if open.kind() == SyntaxKind::LeftParen && cursor > open.offset() {
+                    let close = children.find(|c| c.kind() == SyntaxKind::RightParen);
+                    if close.map_or(true, |close| cursor <= close.offset()) {
+                        return Some(node);
+                    }
+                }
+            }
+            // The signature is not relevant within a block, even if it is
+            // passed as an argument.
+            SyntaxKind::CodeBlock | SyntaxKind::ContentBlock => return None,
+            _ => {}
+        }
+        node = node.parent()?.clone();
+    }
+}
+
+/// Describe the signature of a closure that the callee resolves to.
+fn closure_signature(
+    world: &dyn World,
+    source: &Source,
+    callee: &LinkedNode,
+) -> Option<SignatureHelp> {
+    let ident = callee_ident(callee)?;
+    let mut resolver = Resolver::new(world);
+    let def = resolver.ident(source.id(), &ident)?;
+    let closure = resolver.closure(def)?;
+
+    let params = closure
+        .params()
+        .children()
+        .map(|param| match param {
+            ast::Param::Pos(pattern) => SignatureParam {
+                name: pattern.as_untyped().clone().into_text(),
+                positional: true,
+                ..SignatureParam::default()
+            },
+            ast::Param::Named(named) => SignatureParam {
+                name: named.name().get().clone(),
+                default: Some(named.expr().as_untyped().clone().into_text()),
+                named: true,
+                ..SignatureParam::default()
+            },
+            ast::Param::Sink(spread) => SignatureParam {
+                name: spread.name().map(|name| name.get().clone()).unwrap_or_default(),
+                positional: true,
+                variadic: true,
+                ..SignatureParam::default()
+            },
+        })
+        .collect();
+
+    Some(SignatureHelp {
+        name: ident.text().clone(),
+        docs: None,
+        params,
+        active: None,
+        variadic: false,
+    })
+}
+
+/// Describe the signature of a function from the standard library.
+fn library_signature(world: &dyn World, callee: &LinkedNode) -> Option<SignatureHelp> {
+    let Value::Func(func) = library_value(world, callee)? else { return None };
+    let info = func.info()?;
+    let params = info
+        .params
+        .iter()
+        .map(|param| SignatureParam {
+            name: param.name.into(),
+            types: Some(describe(&param.cast)),
+            default: param.default.map(|default| default().repr().into()),
+            positional: param.positional,
+            named: param.named,
+            variadic: param.variadic,
+            supplied: false,
+        })
+        .collect();
+
+    Some(SignatureHelp {
+        name: info.name.into(),
+        docs: Some(plain_docs_sentence(info.docs)),
+        params,
+        active: None,
+        variadic: false,
+    })
+}
+
+/// Look up an identifier or a chain of field accesses in the standard
+/// library: "calc.pow".
+fn library_value(world: &dyn World, node: &LinkedNode) -> Option<Value> {
+    let library = world.library();
+    match node.kind() {
+        SyntaxKind::Ident => library.global.scope().get(node.text()).cloned(),
+        SyntaxKind::MathIdent => library
+            .math
+            .scope()
+            .get(node.text())
+            .or_else(|| library.global.scope().get(node.text()))
+            .cloned(),
+        SyntaxKind::FieldAccess => {
+            let target = node.children().next()?;
+            let field = node.children().last()?;
+            library_value(world, &target)?.field(field.text()).ok()
+        }
+        _ => None,
+    }
+}
+
+/// The identifier that names the called function: "f" in "f(..)" or
+/// "utils.f(..)".
+fn callee_ident<'a>(callee: &LinkedNode<'a>) -> Option<LinkedNode<'a>> {
+    match callee.kind() {
+        SyntaxKind::Ident | SyntaxKind::MathIdent => Some(callee.clone()),
+        SyntaxKind::FieldAccess => callee
+            .children()
+            .last()
+            .filter(|last| last.kind() == SyntaxKind::Ident),
+        _ => None,
+    }
+}
+
+/// Determine the parameter that the argument at the cursor belongs to.
+fn active_param(
+    params: &[SignatureParam],
+    args: &LinkedNode,
+    cursor: usize,
+) -> Option<usize> {
+    // Find the argument at the cursor and count the positional arguments
+    // before it.
+    let mut positional = 0;
+    let mut current: Option<LinkedNode> = None;
+    for child in args.children() {
+        if child.offset() >= cursor {
+            break;
+        }
+        match child.kind() {
+            SyntaxKind::Comma => {
+                if current.take().map_or(false, |prev| prev.kind() != SyntaxKind::Named) {
+                    positional += 1;
+                }
+            }
+            SyntaxKind::LeftParen => {}
+            kind if !kind.is_trivia() => current = Some(child),
+            _ => {}
+        }
+    }
+
+    // A named argument: "f(size: |)".
+    if let Some(named) = current.and_then(|node| node.cast::<ast::Named>()) {
+        let name = named.name();
+        return params
+            .iter()
+            .position(|param| param.named && param.name == name.as_str());
+    }
+
+    let mut candidates = params.iter().enumerate().filter(|(_, param)| param.positional);
+    candidates
+        .clone()
+        .nth(positional)
+        .or_else(|| candidates.find(|(_, param)| param.variadic))
+        .map(|(i, _)| i)
+}
+
+/// Describe the values a parameter accepts: "length | auto".
+fn describe(cast: &CastInfo) -> EcoString {
+    match cast {
+        CastInfo::Any => "any".into(),
+        CastInfo::Value(value, _) => value.repr().into(),
+        CastInfo::Type(ty) => (*ty).into(),
+        CastInfo::Union(options) => {
+            let mut buf = EcoString::new();
+            for (i, option) in options.iter().enumerate() {
+                if i > 0 {
+                    buf.push_str(" | ");
+                }
+                buf.push_str(&describe(option));
+            }
+            buf
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestWorld;
+
+    #[test]
+    fn test_signature_help() {
+        let main = "#import \"a.typ\": f\n#f(1, b: 3, )\n#f(text(size: 1pt))";
+        let world =
+            TestWorld::new(main).with_file("a.typ", "#let f(a, b: 2, ..rest) = a");
+        let source = world.main();
+        let help = |cursor| signature_help(&world, &source, cursor).unwrap();
+
+        let closure = help(main.find(", )").unwrap() + 2);
+        let names: Vec<_> = closure.params.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["a", "b", "rest"]);
+        assert_eq!(closure.params[1].default.as_deref(), Some("2"));
+        assert!(closure.params[1].supplied);
+        assert_eq!(closure.active, Some(2));
+        assert!(closure.variadic);
+
+        // The innermost call wins.
+        let library = help(main.find("1pt").unwrap());
+        assert_eq!(library.name, "text");
+        let active = &library.params[library.active.unwrap()];
+        assert_eq!(active.name, "size");
+        assert!(active.types.is_some());
+    }
+}
@@ -0,0 +1,237 @@
+use std::ops::Range;
+
+use ecow::EcoString;
+
+use crate::syntax::ast::{self, AstNode};
+use crate::syntax::{LinkedNode, Source, SyntaxKind, SyntaxNode};
+
+/// A named item in a source file, as shown in an editor's outline.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Symbol {
+    /// The name of the symbol.
+    pub name: EcoString,
+    /// What kind of item this is.
+    pub kind: SymbolKind,
+    /// The byte range of the whole item. For a heading, this is its whole
+    /// section, up to the next heading of the same or a higher level.
+    pub range: Range<usize>,
+    /// The byte range to select when navigating to the symbol, typically its
+    /// name.
+    pub selection_range: Range<usize>,
+    /// The symbols nested in this one.
+    pub children: Vec<Symbol>,
+}
+
+/// A kind of symbol.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum SymbolKind {
+    /// A section heading.
+    Heading,
+    /// A function defined with a let binding.
+    Function,
+    /// A variable defined with a let binding.
+    Variable,
+    /// A label.
+    Label,
+    /// A figure.
+    Figure,
+}
+
+/// Extract the symbols of a source file.
+///
+/// This works purely on the syntax tree, so it is cheap enough to run on
+/// every keystroke and deals well with partially edited files. Headings are
+/// nested by level and contain the other symbols of their section.
+pub fn symbols(source: &Source) -> Vec<Symbol> {
+    let mut flat = vec![];
+    collect(&LinkedNode::new(source.root()), &mut flat);
+    nest(flat, source.len_bytes())
+}
+
+/// Collect the symbols in a syntax tree in document order, together with
+/// their heading level (zero for symbols that aren't headings).
+fn collect(node: &LinkedNode, out: &mut Vec<(usize, Symbol)>) {
+    match node.kind() {
+        SyntaxKind::Heading => {
+            let Some(heading) = node.cast::<ast::Heading>() else { return };
+            let selection_range = node
+                .children()
+                .find(|child| child.kind() == SyntaxKind::Markup)
+                .map_or(node.range(), |body| body.range());
+            out.push((
+                heading.level().get(),
+                Symbol {
+                    name: plain_text(node),
+                    kind: SymbolKind::Heading,
+                    range: node.range(),
+                    selection_range,
+                    children: vec![],
+                },
+            ));
+        }
+        SyntaxKind::LetBinding => {
+            let Some(binding) = node.cast::<ast::LetBinding>() else { return };
+            let kind = match (binding.kind(), binding.init()) {
+                (ast::LetBindingKind::Closure(_), _)
+                | (_, Some(ast::Expr::Closure(_))) => SymbolKind::Function,
+                _ => SymbolKind::Variable,
+            };
+
+            for ident in binding.kind().idents() {
+                // Half-typed bindings have no name yet.
+                if ident.as_str().is_empty() {
+                    continue;
+                }
+
+                let Some(name) = node.find(ident.span()) else { continue };
+
+                out.push((
+                    0,
+                    Symbol {
+                        name: ident.get().clone(),
+                        kind,
+                        range: node.range(),
+                        selection_range: name.range(),
+                        children: vec![],
+                    },
+                ));
+            }
+        }
+        SyntaxKind::Label => {
+            let name = node.text().trim_start_matches('<').trim_end_matches('>');
+            out.push((
+                0,
+                Symbol {
+                    name: name.into(),
+                    kind: SymbolKind::Label,
+                    range: node.range(),
+                    selection_range: node.range(),
+                    children: vec![],
+                },
+            ));
+        }
+        SyntaxKind::FuncCall => {
+            let Some(callee) = node.children().next() else { return };
+            if callee.kind() == SyntaxKind::Ident && callee.text().as_str() == "figure" {
+                out.push((
+                    0,
+                    Symbol {
+                        name: figure_caption(node).unwrap_or_else(|| "figure".into()),
+                        kind: SymbolKind::Figure,
+                        range: node.range(),
+                        selection_range: callee.range(),
+                        children: vec![],
+                    },
+                ));
+            }
+        }
+        // Bindings in function bodies are local and thus not part of the
+        // outline.
+        SyntaxKind::Closure => return,
+        _ => {}
+    }
+
+    for child in node.children() {
+        collect(&child, out);
+    }
+}
+
+/// Nest symbols into the sections of the headings preceding them.
+fn nest(flat: Vec<(usize, Symbol)>, end: usize) -> Vec<Symbol> {
+    let mut roots = vec![];
+    let mut open: Vec<(usize, Symbol)> = vec![];
+    for (level, symbol) in flat {
+        if level == 0 {
+            match open.last_mut() {
+                Some((_, heading)) => heading.children.push(symbol),
+                None => roots.push(symbol),
+            }
+            continue;
+        }
+
+        while open.last().map_or(false, |&(open_level, _)| open_level >= level) {
+            close(&mut open, &mut roots, symbol.range.start);
+        }
+
+        open.push((level, symbol));
+    }
+
+    while !open.is_empty() {
+        close(&mut open, &mut roots, end);
+    }
+
+    roots
+}
+
+/// End the section of the innermost open heading at the given offset.
+fn close(open: &mut Vec<(usize, Symbol)>, roots: &mut Vec<Symbol>, end: usize) {
+    let Some((_, mut heading)) = open.pop() else { return };
+    heading.range.end = end;
+    match open.last_mut() {
+        Some((_, parent)) => parent.children.push(heading),
+        None => roots.push(heading),
+    }
+}
+
+/// The plain text of a figure's caption, if it has one.
+fn figure_caption(call: &SyntaxNode) -> Option<EcoString> {
+    let args = call.cast::<ast::FuncCall>()?.args();
+    args.items().find_map(|arg| match arg {
+        ast::Arg::Named(named) if named.name().as_str() == "caption" => {
+            Some(plain_text(named.expr().as_untyped()))
+        }
+        _ => None,
+    })
+}
+
+/// The text of a piece of markup without any markup syntax, code, or labels.
+fn plain_text(node: &SyntaxNode) -> EcoString {
+    fn walk(node: &SyntaxNode, buf: &mut String) {
+        match node.kind() {
+            SyntaxKind::Text | SyntaxKind::SmartQuote => buf.push_str(node.text()),
+            SyntaxKind::Space => buf.push(' '),
+            SyntaxKind::Raw => buf.push_str(node.text().trim_matches('`')),
+            SyntaxKind::Label | SyntaxKind::Hashtag => {}
+            _ => node.children().for_each(|child| walk(child, buf)),
+        }
+    }
+
+    let mut buf = String::new();
+    walk(node, &mut buf);
+    buf.split_whitespace().collect::<Vec<_>>().join(" ").into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbols() {
+        let source = Source::detached(
+            "= Intro <intro>\n#let x = 1\n#let\n== Details\n#let f(y) = { let z = y }\n= End",
+        );
+
+        let symbols = symbols(&source);
+        let names = |symbols: &[Symbol]| {
+            symbols.iter().map(|s| (s.name.as_str(), s.kind)).collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            names(&symbols),
+            [("Intro", SymbolKind::Heading), ("End", SymbolKind::Heading)]
+        );
+        assert_eq!(
+            names(&symbols[0].children),
+            [
+                ("intro", SymbolKind::Label),
+                ("x", SymbolKind::Variable),
+                ("Details", SymbolKind::Heading),
+            ]
+        );
+        assert_eq!(
+            names(&symbols[0].children[2].children),
+            [("f", SymbolKind::Function)]
+        );
+        assert_eq!(symbols[0].range.end, source.text().rfind('=').unwrap());
+    }
+}
@@ -0,0 +1,276 @@
+use std::ops::Range;
+
+use ecow::EcoString;
+
+use crate::doc::{Lang, Region};
+use crate::syntax::ast::{self, AstNode};
+use crate::syntax::{LinkedNode, Source, SyntaxKind};
+
+/// A contiguous run of prose in a source file.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct TextRange {
+    /// The byte range of the prose in the source file.
+    pub range: Range<usize>,
+    /// The prose with escape sequences and shorthands decoded and whitespace
+    /// collapsed into single spaces.
+    pub text: EcoString,
+    /// The language of the prose, if an enclosing `text` set rule or call
+    /// sets it with a literal argument.
+    pub lang: Option<Lang>,
+    /// The region of the prose, determined like the language.
+    pub region: Option<Region>,
+    /// The pieces that make up `text`, in order.
+    pieces: Vec<Piece>,
+}
+
+impl TextRange {
+    /// Map a byte offset in the normalized text back to a byte offset in the
+    /// source file.
+    ///
+    /// Offsets within a decoded escape sequence, shorthand, or collapsed
+    /// whitespace map to its start.
+    pub fn source_offset(&self, offset: usize) -> usize {
+        if offset >= self.text.len() {
+            return self.range.end;
+        }
+
+        let piece = &self.pieces[self.pieces.partition_point(|p| p.offset <= offset) - 1];
+        if piece.verbatim {
+            piece.source + (offset - piece.offset)
+        } else {
+            piece.source
+        }
+    }
+}
+
+/// A piece of a text range's normalized text.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+struct Piece {
+    /// Where the piece starts in the normalized text.
+    offset: usize,
+    /// Where the piece starts in the source file.
+    source: usize,
+    /// Whether the piece was copied verbatim from the source.
+    verbatim: bool,
+}
+
+/// Extract the prose of a source file, for example for spell checking.
+///
+/// Code, raw text, equations, URLs, labels, and references are left out. The
+/// ranges are split at markup structure like strong emphasis and headings,
+/// and they are sorted by their position in the source file.
+pub fn text_ranges(source: &Source) -> Vec<TextRange> {
+    let mut collector = Collector::default();
+    collector.code(&LinkedNode::new(source.root()), Language::default());
+    collector.ranges
+}
+
+/// Collects the text ranges of a source file.
+#[derive(Default)]
+struct Collector {
+    ranges: Vec<TextRange>,
+    current: Option<TextRange>,
+    space: Option<usize>,
+}
+
+impl Collector {
+    /// Collect the prose of a piece of markup.
+    fn markup(&mut self, markup: &LinkedNode, mut language: Language) {
+        for child in markup.children() {
+            match child.kind() {
+                SyntaxKind::Text | SyntaxKind::SmartQuote => {
+                    self.push(&child, child.text(), true, language);
+                }
+                SyntaxKind::Escape => {
+                    let c = child.cast::<ast::Escape>().unwrap().get();
+                    self.push(&child, c.encode_utf8(&mut [0; 4]), false, language);
+                }
+                SyntaxKind::Shorthand => {
+                    let c = child.cast::<ast::Shorthand>().unwrap().get();
+                    self.push(&child, c.encode_utf8(&mut [0; 4]), false, language);
+                }
+                SyntaxKind::Space => {
+                    if self.current.is_some() {
+                        self.space = Some(child.offset());
+                    }
+                }
+                SyntaxKind::Strong
+                | SyntaxKind::Emph
+                | SyntaxKind::Heading
+                | SyntaxKind::ListItem
+                | SyntaxKind::EnumItem
+                | SyntaxKind::TermItem => {
+                    self.finish();
+                    for inner in child.children() {
+                        if inner.kind() == SyntaxKind::Markup {
+                            self.markup(&inner, language);
+                        }
+                    }
+                }
+                _ => {
+                    self.finish();
+                    language = language.after(&child);
+                    self.code(&child, language);
+                }
+            }
+        }
+        self.finish();
+    }
+
+    /// Collect the prose in the content blocks of a piece of code.
+    fn code(&mut self, node: &LinkedNode, language: Language) {
+        match node.kind() {
+            SyntaxKind::Markup => self.markup(node, language),
+            SyntaxKind::Raw | SyntaxKind::Equation => {}
+            SyntaxKind::Code => {
+                let mut language = language;
+                for child in node.children() {
+                    language = language.after(&child);
+                    self.code(&child, language);
+                }
+            }
+            _ => {
+                let language = match node.cast::<ast::FuncCall>() {
+                    Some(call) if is_text(call.callee()) => language.apply(call.args()),
+                    _ => language,
+                };
+                for child in node.children() {
+                    self.code(&child, language);
+                }
+            }
+        }
+    }
+
+    /// Add a piece of prose to the current range, starting a new one if
+    /// necessary.
+    fn push(
+        &mut self,
+        node: &LinkedNode,
+        text: &str,
+        verbatim: bool,
+        language: Language,
+    ) {
+        let range = node.range();
+        let current = self.current.get_or_insert_with(|| TextRange {
+            range: range.clone(),
+            text: EcoString::new(),
+            lang: language.lang,
+            region: language.region,
+            pieces: vec![],
+        });
+
+        if let Some(source) = self.space.take() {
+            let offset = current.text.len();
+            current.pieces.push(Piece { offset, source, verbatim: false });
+            current.text.push(' ');
+        }
+
+        let offset = current.text.len();
+        current.pieces.push(Piece { offset, source: range.start, verbatim });
+        current.text.push_str(text);
+        current.range.end = range.end;
+    }
+
+    /// Finish the current range. Whitespace at its end is dropped.
+    fn finish(&mut self) {
+        self.space = None;
+        if let Some(range) = self.current.take() {
+            self.ranges.push(range);
+        }
+    }
+}
+
+/// The language settings in effect at some point of a source file.
+#[derive(Debug, Default, Copy, Clone)]
+struct Language {
+    lang: Option<Lang>,
+    region: Option<Region>,
+}
+
+impl Language {
+    /// The settings after a node, which may be an unconditional `text` set
+    /// rule.
+    fn after(self, node: &LinkedNode) -> Self {
+        match node.cast::<ast::SetRule>() {
+            Some(set) if set.condition().is_none() && is_text(set.target()) => {
+                self.apply(set.args())
+            }
+            _ => self,
+        }
+    }
+
+    /// Apply the `lang` and `region` arguments of a `text` set rule or call.
+    /// Arguments that aren't string literals make the setting unknown.
+    fn apply(mut self, args: ast::Args) -> Self {
+        for arg in args.items() {
+            let ast::Arg::Named(named) = arg else { continue };
+            let value = match named.expr() {
+                ast::Expr::Str(string) => Some(string.get()),
+                _ => None,
+            };
+            match named.name().as_str() {
+                "lang" => self.lang = value.and_then(|v| v.parse().ok()),
+                "region" => self.region = value.and_then(|v| v.parse().ok()),
+                _ => {}
+            }
+        }
+        self
+    }
+}
+
+/// Whether an expression refers to the `text` function.
+fn is_text(expr: ast::Expr) -> bool {
+    matches!(expr, ast::Expr::Ident(ident) if ident.as_str() == "text")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_ranges_normalization() {
+        let text = "Hello \\#1 -- world~ok `raw` $x$ next";
+        let ranges = text_ranges(&Source::detached(text));
+        let found: Vec<_> = ranges
+            .iter()
+            .map(|r| (&text[r.range.clone()], r.text.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [("Hello \\#1 -- world~ok", "Hello #1 – world\u{a0}ok"), ("next", "next")]
+        );
+
+        let range = &ranges[0];
+        assert_eq!(range.source_offset(0), 0);
+        assert_eq!(range.source_offset(range.text.find('#').unwrap()), 6);
+        assert_eq!(range.source_offset(range.text.find('1').unwrap()), 8);
+        assert_eq!(range.source_offset(range.text.find('–').unwrap() + 1), 10);
+        assert_eq!(range.source_offset(range.text.find("ok").unwrap()), 19);
+        assert_eq!(range.source_offset(range.text.len()), 21);
+    }
+
+    #[test]
+    fn test_text_ranges_language() {
+        let text = "#set text(lang: \"de\", region: \"AT\")\nHallo *Welt*\n\n\
+                    #text(lang: \"fr\")[Bonjour] #text(size: 8pt)[Hi]\n\n\
+                    https://typst.app and <label> @ref\n\n\
+                    #text(lang: l)[Unknown]";
+        let de = "de".parse::<Lang>().ok();
+        let fr = "fr".parse::<Lang>().ok();
+        let at = "AT".parse::<Region>().ok();
+        let ranges = text_ranges(&Source::detached(text));
+        let found: Vec<_> =
+            ranges.iter().map(|r| (r.text.as_str(), r.lang, r.region)).collect();
+        assert_eq!(
+            found,
+            [
+                ("Hallo", de, at),
+                ("Welt", de, at),
+                ("Bonjour", fr, at),
+                ("Hi", de, at),
+                ("and", de, at),
+                ("Unknown", None, at),
+            ]
+        );
+    }
+}
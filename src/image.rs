@@ -12,10 +12,11 @@ use image::codecs::gif::GifDecoder;
 use image::codecs::jpeg::JpegDecoder;
 use image::codecs::png::PngDecoder;
 use image::io::Limits;
-use image::{ImageDecoder, ImageResult};
+use image::{AnimationDecoder, ImageDecoder, ImageResult};
 use usvg::{TreeParsing, TreeTextToPath};
 
-use crate::diag::{format_xml_like_error, StrResult};
+use crate::diag::{bail, format_xml_like_error, StrResult};
+use crate::eval::{cast, Cast, IntoValue};
 use crate::font::Font;
 use crate::geom::Axes;
 use crate::util::Bytes;
@@ -34,6 +35,9 @@ struct Repr {
     data: Bytes,
     /// The format of the encoded `buffer`.
     format: ImageFormat,
+    /// The index of the page or frame that is displayed for formats that can
+    /// contain multiple of them.
+    page: usize,
     /// The size of the image.
     size: Axes<u32>,
     /// A loader for fonts referenced by an image (currently, only applies to
@@ -53,7 +57,7 @@ impl Image {
     ) -> StrResult<Self> {
         let loader = PreparedLoader::default();
         let decoded = match format {
-            ImageFormat::Raster(format) => decode_raster(&data, format)?,
+            ImageFormat::Raster(format) => decode_raster(&data, format, 0)?,
             ImageFormat::Vector(VectorFormat::Svg) => {
                 decode_svg(&data, (&loader as &dyn SvgFontLoader).track())?
             }
@@ -62,25 +66,31 @@ impl Image {
         Ok(Self(Arc::new(Prehashed::new(Repr {
             data,
             format,
+            page: 0,
             size: decoded.size(),
             loader,
             alt,
         }))))
     }
 
-    /// Create a font-dependant image from a buffer and a format.
+    /// Create a font-dependant image from a buffer and a format, displaying
+    /// the page or frame with the given index.
     #[comemo::memoize]
     pub fn with_fonts(
         data: Bytes,
         format: ImageFormat,
+        page: usize,
         world: Tracked<dyn World + '_>,
         fallback_family: Option<&str>,
         alt: Option<EcoString>,
     ) -> StrResult<Self> {
         let loader = WorldLoader::new(world, fallback_family);
         let decoded = match format {
-            ImageFormat::Raster(format) => decode_raster(&data, format)?,
+            ImageFormat::Raster(format) => decode_raster(&data, format, page)?,
             ImageFormat::Vector(VectorFormat::Svg) => {
+                if page > 0 {
+                    bail!("image has only one page");
+                }
                 decode_svg(&data, (&loader as &dyn SvgFontLoader).track())?
             }
         };
@@ -88,6 +98,7 @@ impl Image {
         Ok(Self(Arc::new(Prehashed::new(Repr {
             data,
             format,
+            page,
             size: decoded.size(),
             loader: loader.into_prepared(),
             alt,
@@ -104,6 +115,11 @@ impl Image {
         self.0.format
     }
 
+    /// The index of the displayed page or frame.
+    pub fn page(&self) -> usize {
+        self.0.page
+    }
+
     /// The size of the image in pixels.
     pub fn size(&self) -> Axes<u32> {
         self.0.size
@@ -119,6 +135,17 @@ impl Image {
         self.size().y
     }
 
+    /// The resolution of the image in dots per inch, if the image specifies
+    /// it in its metadata.
+    pub fn dpi(&self) -> Option<f64> {
+        match self.format() {
+            ImageFormat::Raster(RasterFormat::Png) => png_dpi(self.data()),
+            ImageFormat::Raster(RasterFormat::Jpg) => jpg_dpi(self.data()),
+            _ => None,
+        }
+        .filter(|dpi| dpi.is_finite() && *dpi > 0.0)
+    }
+
     /// A text describing the image.
     pub fn alt(&self) -> Option<&str> {
         self.0.alt.as_deref()
@@ -127,7 +154,9 @@ impl Image {
     /// The decoded version of the image.
     pub fn decoded(&self) -> Arc<DecodedImage> {
         match self.format() {
-            ImageFormat::Raster(format) => decode_raster(self.data(), format),
+            ImageFormat::Raster(format) => {
+                decode_raster(self.data(), format, self.page())
+            }
             ImageFormat::Vector(VectorFormat::Svg) => {
                 decode_svg(self.data(), (&self.0.loader as &dyn SvgFontLoader).track())
             }
@@ -140,6 +169,7 @@ impl Debug for Image {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         f.debug_struct("Image")
             .field("format", &self.format())
+            .field("page", &self.page())
             .field("width", &self.width())
             .field("height", &self.height())
             .field("alt", &self.alt())
@@ -157,7 +187,7 @@ pub enum ImageFormat {
 }
 
 /// A raster graphics format.
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
 pub enum RasterFormat {
     /// Raster format for illustrations and transparent graphics.
     Png,
@@ -168,12 +198,22 @@ pub enum RasterFormat {
 }
 
 /// A vector graphics format.
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
 pub enum VectorFormat {
     /// The vector graphics format of the web.
     Svg,
 }
 
+cast! {
+    ImageFormat,
+    self => match self {
+        Self::Raster(v) => v.into_value(),
+        Self::Vector(v) => v.into_value()
+    },
+    v: RasterFormat => Self::Raster(v),
+    v: VectorFormat => Self::Vector(v),
+}
+
 impl From<RasterFormat> for image::ImageFormat {
     fn from(format: RasterFormat) -> Self {
         match format {
@@ -232,9 +272,13 @@ impl DecodedImage {
 /// Raw data for of an ICC profile.
 pub struct IccProfile(pub Vec<u8>);
 
-/// Decode a raster image.
+/// Decode the page or frame with the given index of a raster image.
 #[comemo::memoize]
-fn decode_raster(data: &Bytes, format: RasterFormat) -> StrResult<Arc<DecodedImage>> {
+fn decode_raster(
+    data: &Bytes,
+    format: RasterFormat,
+    page: usize,
+) -> StrResult<Arc<DecodedImage>> {
     fn decode_with<'a, T: ImageDecoder<'a>>(
         decoder: ImageResult<T>,
     ) -> ImageResult<(image::DynamicImage, Option<IccProfile>)> {
@@ -245,7 +289,35 @@ fn decode_raster(data: &Bytes, format: RasterFormat) -> StrResult<Arc<DecodedIma
         Ok((dynamic, icc))
     }
 
+    /// Decode a single frame of an animated image.
+    fn decode_frame<'a, T: AnimationDecoder<'a>>(
+        decoder: ImageResult<T>,
+        index: usize,
+    ) -> StrResult<image::DynamicImage> {
+        let frames = decoder.map_err(format_image_error)?.into_frames();
+        let mut count = 0;
+        for frame in frames {
+            let frame = frame.map_err(format_image_error)?;
+            if count == index {
+                return Ok(image::DynamicImage::ImageRgba8(frame.into_buffer()));
+            }
+            count += 1;
+        }
+        if count == 1 {
+            bail!("image has only one page");
+        }
+        bail!("image has only {count} pages")
+    }
+
     let cursor = io::Cursor::new(data);
+    if page > 0 {
+        let dynamic = match format {
+            RasterFormat::Gif => decode_frame(GifDecoder::new(cursor), page)?,
+            RasterFormat::Jpg | RasterFormat::Png => bail!("image has only one page"),
+        };
+        return Ok(Arc::new(DecodedImage::Raster(dynamic, None, format)));
+    }
+
     let (dynamic, icc) = match format {
         RasterFormat::Jpg => decode_with(JpegDecoder::new(cursor)),
         RasterFormat::Png => decode_with(PngDecoder::new(cursor)),
@@ -256,6 +328,55 @@ fn decode_raster(data: &Bytes, format: RasterFormat) -> StrResult<Arc<DecodedIma
     Ok(Arc::new(DecodedImage::Raster(dynamic, icc, format)))
 }
 
+/// Read the resolution of a PNG image from its `pHYs` chunk.
+fn png_dpi(data: &[u8]) -> Option<f64> {
+    let mut rest = data.strip_prefix(b"\x89PNG\r\n\x1a\n")?;
+    while rest.len() >= 12 {
+        let len = u32::from_be_bytes(rest[..4].try_into().ok()?) as usize;
+        let kind = &rest[4..8];
+        let body = rest.get(8..8 + len)?;
+        match kind {
+            b"pHYs" if len >= 9 => {
+                // Only a unit of 1 (the meter) carries an absolute resolution.
+                if body[8] != 1 {
+                    return None;
+                }
+                let ppm = u32::from_be_bytes(body[..4].try_into().ok()?);
+                return Some(ppm as f64 * 0.0254);
+            }
+            b"IDAT" | b"IEND" => return None,
+            _ => {}
+        }
+        rest = rest.get(12 + len..)?;
+    }
+    None
+}
+
+/// Read the resolution of a JPEG image from its JFIF header.
+fn jpg_dpi(data: &[u8]) -> Option<f64> {
+    let mut rest = data.strip_prefix(&[0xFF, 0xD8])?;
+    while rest.len() >= 4 && rest[0] == 0xFF {
+        let marker = rest[1];
+        let len = u16::from_be_bytes([rest[2], rest[3]]) as usize;
+        let body = rest.get(4..2 + len)?;
+        match marker {
+            0xE0 if body.len() >= 12 && body.starts_with(b"JFIF\0") => {
+                let density = u16::from_be_bytes([body[8], body[9]]) as f64;
+                return match body[7] {
+                    1 => Some(density),
+                    2 => Some(density * 2.54),
+                    _ => None,
+                };
+            }
+            // The image data starts.
+            0xDA => return None,
+            _ => {}
+        }
+        rest = rest.get(2 + len..)?;
+    }
+    None
+}
+
 /// Decode an SVG image.
 #[comemo::memoize]
 fn decode_svg(
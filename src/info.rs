@@ -0,0 +1,381 @@
+//! Machine-readable summaries of compiled documents.
+//!
+//! The [summary](DocumentInfo) of a document is produced by walking its frames
+//! once. It can be serialized, for instance to let continuous integration
+//! check that a document has no external links or doesn't exceed a number of
+//! pages.
+
+use std::collections::{HashMap, HashSet};
+use std::num::NonZeroUsize;
+
+use ecow::EcoString;
+use serde::Serialize;
+use ttf_parser::name_id;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::doc::{Destination, Document, Frame, FrameItem, Meta};
+use crate::font::{Font, FontVariant};
+use crate::geom::{Abs, Point, Size, Transform};
+use crate::model::{Content, Introspector};
+
+/// A summary of a compiled document.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DocumentInfo {
+    /// The pages of the document, in order. Their number is the document's
+    /// page count.
+    pub pages: Vec<PageInfo>,
+    /// The top-level headings. Headings that skip levels are nested directly
+    /// below the closest preceding heading of a lower level.
+    pub headings: Vec<HeadingInfo>,
+    /// The links to URLs, in the order of the pages they are on.
+    pub links: Vec<LinkInfo>,
+    /// The fonts that glyphs are actually used from, sorted by family.
+    pub fonts: Vec<FontUsage>,
+    /// The number of words in the document's text.
+    pub words: usize,
+    /// The number of characters in the document's text, not counting
+    /// whitespace.
+    pub characters: usize,
+}
+
+/// The size of a page.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct PageInfo {
+    /// The width of the page in points.
+    pub width: f64,
+    /// The height of the page in points.
+    pub height: f64,
+}
+
+/// A heading and the headings nested below it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HeadingInfo {
+    /// The heading's body as plain text.
+    pub title: EcoString,
+    /// The heading's level, starting at one.
+    pub level: NonZeroUsize,
+    /// The physical page the heading is on, starting at one.
+    pub page: NonZeroUsize,
+    /// The headings nested below this one.
+    pub children: Vec<HeadingInfo>,
+}
+
+/// A link to a URL.
+///
+/// A link that is broken across lines has one entry per line.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LinkInfo {
+    /// The linked URL.
+    pub url: EcoString,
+    /// The physical page the link is on, starting at one.
+    pub page: NonZeroUsize,
+    /// The horizontal position of the link's clickable area from the left of
+    /// the page in points.
+    pub x: f64,
+    /// The vertical position of the link's clickable area from the top of the
+    /// page in points.
+    pub y: f64,
+    /// The width of the link's clickable area in points.
+    pub width: f64,
+    /// The height of the link's clickable area in points.
+    pub height: f64,
+}
+
+/// A font that glyphs are used from.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FontUsage {
+    /// The font's family.
+    pub family: EcoString,
+    /// The font's PostScript name, if it has one.
+    pub postscript_name: Option<EcoString>,
+    /// The font's style, weight, and stretch.
+    pub variant: FontVariant,
+    /// How many glyphs from the font are placed in the document.
+    pub glyphs: usize,
+    /// How many different glyphs from the font are used.
+    pub distinct_glyphs: usize,
+}
+
+impl DocumentInfo {
+    /// Summarize a document.
+    #[tracing::instrument(skip_all)]
+    pub fn new(document: &Document) -> Self {
+        let mut walker = Walker::default();
+        for (i, page) in document.pages.iter().enumerate() {
+            walker.page = NonZeroUsize::new(i + 1).unwrap();
+            walker.frame(page, Transform::identity());
+        }
+
+        let mut fonts: Vec<_> = walker
+            .fonts
+            .into_iter()
+            .map(|(font, (glyphs, distinct))| FontUsage {
+                family: font.info().family.as_str().into(),
+                postscript_name: font
+                    .find_name(name_id::POST_SCRIPT_NAME)
+                    .map(Into::into),
+                variant: font.info().variant,
+                glyphs,
+                distinct_glyphs: distinct.len(),
+            })
+            .collect();
+        fonts.sort_by(|a, b| {
+            (&a.family, &a.postscript_name).cmp(&(&b.family, &b.postscript_name))
+        });
+
+        let text = crate::export::text(document);
+        Self {
+            pages: document
+                .pages
+                .iter()
+                .map(|page| PageInfo {
+                    width: page.width().to_pt(),
+                    height: page.height().to_pt(),
+                })
+                .collect(),
+            headings: headings(&Introspector::new(&document.pages)),
+            links: walker.links,
+            fonts,
+            words: text.unicode_words().count(),
+            characters: text.chars().filter(|c| !c.is_whitespace()).count(),
+        }
+    }
+}
+
+/// Collects the links and fonts of a document's pages.
+struct Walker {
+    /// The page that is currently walked.
+    page: NonZeroUsize,
+    /// The links found so far.
+    links: Vec<LinkInfo>,
+    /// For each font, the number of glyphs and the distinct glyph ids used
+    /// from it.
+    fonts: HashMap<Font, (usize, HashSet<u16>)>,
+}
+
+impl Default for Walker {
+    fn default() -> Self {
+        Self {
+            page: NonZeroUsize::ONE,
+            links: vec![],
+            fonts: HashMap::new(),
+        }
+    }
+}
+
+impl Walker {
+    /// Walk a frame whose items are transformed with `ts` onto the page.
+    fn frame(&mut self, frame: &Frame, ts: Transform) {
+        for (pos, item) in frame.items() {
+            match item {
+                FrameItem::Group(group) => {
+                    let ts = ts
+                        .pre_concat(Transform::translate(pos.x, pos.y))
+                        .pre_concat(group.transform);
+                    self.frame(&group.frame, ts);
+                }
+                FrameItem::Text(text) => {
+                    let (count, distinct) =
+                        self.fonts.entry(text.font.clone()).or_default();
+                    *count += text.glyphs.len();
+                    distinct.extend(text.glyphs.iter().map(|glyph| glyph.id));
+                }
+                FrameItem::Meta(Meta::Link(Destination::Url(url)), size) => {
+                    self.link(url, *pos, *size, ts);
+                }
+                FrameItem::Shape(..) | FrameItem::Image(..) | FrameItem::Meta(..) => {}
+            }
+        }
+    }
+
+    /// Record a link whose area starts at `pos` in a frame that is transformed
+    /// with `ts` onto the page.
+    ///
+    /// The area of a rotated or skewed link is its bounding box on the page.
+    fn link(&mut self, url: &EcoString, pos: Point, size: Size, ts: Transform) {
+        let corners = [
+            pos,
+            pos + Point::with_x(size.x),
+            pos + Point::with_y(size.y),
+            pos + size.to_point(),
+        ]
+        .map(|point| point.transform(ts));
+
+        let mut min = Point::splat(Abs::inf());
+        let mut max = Point::splat(-Abs::inf());
+        for point in corners {
+            min = min.min(point);
+            max = max.max(point);
+        }
+
+        self.links.push(LinkInfo {
+            url: url.clone(),
+            page: self.page,
+            x: min.x.to_pt(),
+            y: min.y.to_pt(),
+            width: (max.x - min.x).to_pt(),
+            height: (max.y - min.y).to_pt(),
+        });
+    }
+}
+
+/// Build the tree of a document's headings.
+fn headings(introspector: &Introspector) -> Vec<HeadingInfo> {
+    let mut leaves = vec![];
+    for heading in introspector.query(&item!(heading_func).select()) {
+        let Some(location) = heading.location() else { continue };
+        leaves.push(HeadingInfo {
+            title: heading.expect_field::<Content>("body").plain_text().trim().into(),
+            level: heading.expect_field::<NonZeroUsize>("level"),
+            page: introspector.page(location),
+            children: vec![],
+        });
+    }
+    nest(leaves)
+}
+
+/// Nest headings below the closest preceding heading of a lower level.
+fn nest(headings: impl IntoIterator<Item = HeadingInfo>) -> Vec<HeadingInfo> {
+    let mut tree: Vec<HeadingInfo> = vec![];
+    for leaf in headings {
+        // Descend as long as the last heading is of a lower level.
+        let mut children = &mut tree;
+        while children.last().map_or(false, |last| last.level < leaf.level) {
+            children = &mut children.last_mut().unwrap().children;
+        }
+
+        children.push(leaf);
+    }
+    tree
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::doc::{Glyph, GroupItem, Lang, TextItem};
+    use crate::geom::{Angle, Color, Em};
+    use crate::syntax::Span;
+    use crate::util::Bytes;
+
+    fn pt(x: f64, y: f64) -> Point {
+        Point::new(Abs::pt(x), Abs::pt(y))
+    }
+
+    /// Walk the frames of the pages of a document.
+    fn walk(pages: &[Frame]) -> Walker {
+        let mut walker = Walker::default();
+        for (i, page) in pages.iter().enumerate() {
+            walker.page = NonZeroUsize::new(i + 1).unwrap();
+            walker.frame(page, Transform::identity());
+        }
+        walker
+    }
+
+    #[test]
+    fn test_info_links() {
+        let link = |url: &str| {
+            let size = Size::new(Abs::pt(20.0), Abs::pt(10.0));
+            FrameItem::Meta(Meta::Link(Destination::Url(url.into())), size)
+        };
+
+        let mut first = Frame::new(Size::splat(Abs::pt(100.0)));
+        first.push(pt(10.0, 20.0), link("https://typst.app"));
+
+        // A link in a rotated group has the group's offset and its bounding
+        // box on the page.
+        let mut inner = Frame::new(Size::splat(Abs::pt(50.0)));
+        inner.push(Point::zero(), link("https://example.com"));
+        let mut group = GroupItem::new(inner);
+        group.transform = Transform::rotate(Angle::deg(90.0));
+        let mut second = Frame::new(Size::splat(Abs::pt(100.0)));
+        second.push(pt(50.0, 5.0), FrameItem::Group(group));
+
+        let found: Vec<_> = walk(&[first, second])
+            .links
+            .iter()
+            .map(|link| {
+                let round = |v: f64| v.round();
+                (
+                    link.url.as_str(),
+                    link.page.get(),
+                    [round(link.x), round(link.y), round(link.width), round(link.height)],
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("https://typst.app", 1, [10.0, 20.0, 20.0, 10.0]),
+                ("https://example.com", 2, [40.0, 5.0, 10.0, 20.0]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_info_fonts() {
+        let data = include_bytes!("../assets/fonts/LinLibertine_R.ttf");
+        let font = Font::new(Bytes::from_static(data), 0).unwrap();
+        let text = |ids: &[u16]| {
+            let glyphs = ids
+                .iter()
+                .map(|&id| Glyph {
+                    id,
+                    x_advance: Em::one(),
+                    x_offset: Em::zero(),
+                    range: 0..1,
+                    span: (Span::detached(), 0),
+                })
+                .collect();
+            FrameItem::Text(TextItem {
+                font: font.clone(),
+                size: Abs::pt(10.0),
+                fill: Color::BLACK.into(),
+                lang: Lang::ENGLISH,
+                text: "a".repeat(ids.len()).into(),
+                glyphs,
+            })
+        };
+
+        let mut page = Frame::new(Size::splat(Abs::pt(100.0)));
+        page.push(Point::zero(), text(&[1, 2, 1]));
+        page.push(pt(0.0, 20.0), text(&[2, 3]));
+
+        let fonts = walk(&[page]).fonts;
+        assert_eq!(fonts.len(), 1);
+        let (glyphs, distinct) = &fonts[&font];
+        assert_eq!((*glyphs, distinct.len()), (5, 3));
+    }
+
+    #[test]
+    fn test_info_heading_tree() {
+        let heading = |title: &str, level| HeadingInfo {
+            title: title.into(),
+            level: NonZeroUsize::new(level).unwrap(),
+            page: NonZeroUsize::ONE,
+            children: vec![],
+        };
+
+        // The third-level heading skips a level and the last heading is of a
+        // lower level than the one it follows.
+        let tree = nest([
+            heading("Intro", 1),
+            heading("Details", 3),
+            heading("More", 2),
+            heading("Deeper", 3),
+            heading("End", 1),
+        ]);
+
+        fn titles(tree: &[HeadingInfo]) -> Vec<(&str, Vec<&str>)> {
+            tree.iter()
+                .map(|heading| {
+                    let children =
+                        heading.children.iter().map(|child| child.title.as_str());
+                    (heading.title.as_str(), children.collect())
+                })
+                .collect()
+        }
+
+        assert_eq!(titles(&tree), [("Intro", vec!["Details", "More"]), ("End", vec![])]);
+        assert_eq!(titles(&tree[0].children[1].children), [("Deeper", vec![])]);
+    }
+}
@@ -17,7 +17,7 @@
 //!   per page with items at fixed positions.
 //! - **Exporting:**
 //!   These frames can finally be exported into an output format (currently
-//!   supported are [PDF] and [raster images]).
+//!   supported are [PDF], [SVG], [raster images], and [plain text]).
 //!
 //! [tokens]: syntax::SyntaxKind
 //! [parsed]: syntax::parse
@@ -30,7 +30,9 @@
 //! [document]: doc::Document
 //! [frame]: doc::Frame
 //! [PDF]: export::pdf
+//! [SVG]: export::svg
 //! [raster images]: export::render
+//! [plain text]: export::text
 
 #![recursion_limit = "1000"]
 #![allow(clippy::comparison_chain)]
@@ -43,6 +45,7 @@ pub mod util;
 pub mod diag;
 #[macro_use]
 pub mod eval;
+pub mod cache;
 pub mod doc;
 pub mod export;
 pub mod file;
@@ -50,24 +53,76 @@ pub mod font;
 pub mod geom;
 pub mod ide;
 pub mod image;
+pub mod info;
+pub mod lint;
 pub mod model;
+pub mod package;
+pub mod profile;
 pub mod syntax;
+#[cfg(test)]
+mod testing;
+
+use std::ops::Range;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
 use comemo::{Prehashed, Track, TrackedMut};
+use ecow::EcoString;
 
+use crate::cache::CacheStats;
 use crate::diag::{FileResult, SourceResult};
-use crate::doc::Document;
+use crate::doc::{Document, Position};
 use crate::eval::{Datetime, Library, Route, Tracer};
-use crate::file::FileId;
+use crate::file::{DirEntry, FileId};
 use crate::font::{Font, FontBook};
+use crate::lint::{Lint, LintConfig};
+use crate::model::{Content, Introspector, Selector};
+use crate::package::{PackageSpec, Version};
+use crate::profile::ProfileOptions;
 use crate::syntax::Source;
 use crate::util::Bytes;
 
 /// Compile a source file into a fully layouted document.
-#[tracing::instrument(skip(world))]
-pub fn compile(world: &dyn World) -> SourceResult<Document> {
+///
+/// Warnings that are emitted during compilation are collected in the `tracer`
+/// and can be retrieved with [`Tracer::warnings`], regardless of whether
+/// compilation succeeded.
+#[tracing::instrument(skip(world, tracer))]
+pub fn compile(world: &dyn World, tracer: &mut Tracer) -> SourceResult<Document> {
+    compile_with(world, tracer, &CompileOptions::default())
+}
+
+/// Compile a source file into a fully layouted document with custom options.
+///
+/// If the compilation is cancelled through the options' [cancellation
+/// token](CancellationToken), this returns early with an error of
+/// [kind](diag::ErrorKind) `Cancelled`. If profiling is enabled, the report
+/// can be retrieved with [`Tracer::profile`] afterwards.
+#[tracing::instrument(skip(world, tracer, options))]
+pub fn compile_with(
+    world: &dyn World,
+    tracer: &mut Tracer,
+    options: &CompileOptions,
+) -> SourceResult<Document> {
+    let session = options.profile.map(profile::Session::start);
+    let result = compile_impl(world, tracer, options);
+    if let Some(session) = session {
+        tracer.set_profile(session.finish());
+    }
+    result
+}
+
+/// The compilation itself, which runs within the profiling session.
+fn compile_impl(
+    world: &dyn World,
+    tracer: &mut Tracer,
+    options: &CompileOptions,
+) -> SourceResult<Document> {
     let route = Route::default();
-    let mut tracer = Tracer::default();
+    let limits = options.limits;
+    cache::reset();
+    tracer.set_cancellation(options.cancellation.clone());
+    tracer.set_recover(options.recover);
 
     // Call `track` just once to keep comemo's ID stable.
     let world = world.track();
@@ -79,11 +134,159 @@ pub fn compile(world: &dyn World) -> SourceResult<Document> {
         world,
         route.track(),
         TrackedMut::reborrow_mut(&mut tracer),
+        limits,
         &world.main(),
     )?;
 
     // Typeset the module's contents.
-    model::typeset(world, tracer, &module.content())
+    model::typeset(world, tracer, limits, options.pages.clone(), &module.content())
+}
+
+/// Check a document for likely mistakes with all [lint rules](lint::Rule).
+///
+/// The main file is evaluated, but not typeset, so this also works for
+/// documents that fail to compile. Evaluation errors are not reported, they
+/// are part of the compilation's result.
+pub fn lint(world: &dyn World) -> Vec<Lint> {
+    lint_with(world, &LintConfig::default())
+}
+
+/// Check a document for likely mistakes with the rules enabled in the
+/// configuration.
+pub fn lint_with(world: &dyn World, config: &LintConfig) -> Vec<Lint> {
+    lint::lint(world, config)
+}
+
+/// Evict cached results that were not used in the last `max_age`
+/// compilations on this thread.
+///
+/// Evaluation and layout results are memoized across compilations to make
+/// recompiling an edited document fast. Long-running hosts like a watch
+/// process or a language server should call this after each compilation to
+/// keep memory usage bounded. Evicted results are recomputed when they are
+/// needed again. The fonts and files loaded by the [`World`] are owned by the
+/// host and not affected, so with a `max_age` of zero, the next compilation
+/// starts from scratch except for them.
+///
+/// If a [frame cap](cache::set_frame_cap) is set and more frames than that
+/// were laid out on this thread since the last full eviction, everything is
+/// evicted.
+pub fn evict_cache(max_age: usize) {
+    cache::evict(max_age);
+}
+
+/// Statistics about how well layout was served from the cache in the last
+/// compilation on this thread and how many frames may be cached on it.
+pub fn cache_stats() -> CacheStats {
+    cache::stats()
+}
+
+/// Settings for a compilation.
+#[derive(Debug, Default, Clone)]
+pub struct CompileOptions {
+    /// Guard rails against runaway compilations.
+    pub limits: Limits,
+    /// A token through which the compilation can be cancelled.
+    pub cancellation: Option<CancellationToken>,
+    /// The zero-based indices of the pages whose contents to produce, for
+    /// example to only preview a part of a long document. The other pages are
+    /// part of the document, but stay empty except for the metadata needed
+    /// for introspection. By default, all pages are produced.
+    pub pages: Option<Range<usize>>,
+    /// Whether to produce a partial document when evaluating markup fails.
+    ///
+    /// The failing markup is replaced with an inline placeholder showing the
+    /// error and the errors are collected in the tracer, from where they can
+    /// be retrieved with [`Tracer::errors`]. Other errors, like syntax errors
+    /// or a missing main file, still abort the compilation.
+    pub recover: bool,
+    /// Whether and how to measure where time is spent during compilation.
+    /// Profiling is disabled by default and has next to no overhead then.
+    pub profile: Option<ProfileOptions>,
+}
+
+/// A handle to cooperatively cancel a running compilation, for example from
+/// another thread when the compiled source became stale.
+///
+/// Cancelling is cheap to check for and does not invalidate the results that
+/// were cached before the cancellation.
+#[derive(Debug, Default, Clone)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Create a new token that is not yet cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel all compilations using this token.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the token was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Limits that guard against compilations that run away, for example due to
+/// infinite recursion in untrusted input.
+///
+/// The defaults are generous enough for all reasonable documents. The number
+/// of elements, the show rule depth, and the layout depth are not limited by
+/// default. Hosts that compile untrusted input should limit them as well.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Limits {
+    /// The maximum nesting depth of function calls.
+    pub max_call_depth: usize,
+    /// The maximum number of iterations of a single loop.
+    pub max_loop_iterations: usize,
+    /// The maximum number of elements in the content produced by evaluation.
+    /// Content created later by show rules is not counted.
+    pub max_elements: usize,
+    /// The maximum number of show rules that may successively apply to the
+    /// result of another show rule.
+    pub max_show_depth: usize,
+    /// The maximum nesting depth of layouts, for example of blocks in boxes
+    /// in blocks.
+    pub max_layout_depth: usize,
+    /// The maximum number of layout passes performed to let introspections
+    /// (like counters and queries) stabilize. Once this is reached, the
+    /// document of the last pass is returned.
+    pub max_layout_iterations: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_call_depth: 64,
+            max_loop_iterations: 10_000,
+            max_elements: usize::MAX,
+            max_show_depth: usize::MAX,
+            max_layout_depth: usize::MAX,
+            max_layout_iterations: 5,
+        }
+    }
+}
+
+/// Query a compiled document for all elements matching a selector.
+///
+/// This is the host-side equivalent of the `query` function available within
+/// documents. The elements are returned in document order alongside their
+/// final positions. Only locatable elements (like headings, figures, or
+/// elements with labels) can be found this way. To run multiple queries
+/// against the same document, create an [`Introspector`] once instead.
+pub fn query(document: &Document, selector: &Selector) -> Vec<(Content, Position)> {
+    let introspector = Introspector::new(&document.pages);
+    introspector
+        .query(selector)
+        .iter()
+        .map(|elem| {
+            let position = introspector.position(elem.location().unwrap());
+            (Content::clone(elem), position)
+        })
+        .collect()
 }
 
 /// The environment in which typesetting occurs.
@@ -125,4 +328,113 @@ pub trait World {
     /// If no offset is specified, the local date should be chosen. Otherwise,
     /// the UTC date should be chosen with the corresponding offset in hours.
     fn today(&self, offset: Option<i64>) -> Option<Datetime>;
+
+    /// Get the current date and time.
+    ///
+    /// The offset is interpreted like for [`today`](Self::today). This is used
+    /// for timestamps embedded into exported files, so hosts that want
+    /// reproducible builds should return a fixed value here (e.g. one derived
+    /// from `SOURCE_DATE_EPOCH`). The default implementation returns `None`,
+    /// so that existing worlds keep working: `{datetime.now()}` then fails and
+    /// exported files have no creation date.
+    fn now(&self, _offset: Option<i64>) -> Option<Datetime> {
+        None
+    }
+
+    /// List the entries of a directory.
+    ///
+    /// The directory is identified just like a file: By a package and a path
+    /// within the package or the project root. This is only used for
+    /// autocompletion of file paths, so the default implementation, which
+    /// returns `None`, is fine for worlds that don't support listing
+    /// directories.
+    fn entries(&self, _dir: FileId) -> Option<Vec<DirEntry>> {
+        None
+    }
+
+    /// List the versions of a package that are available.
+    ///
+    /// This is used to resolve imports with an incomplete version like
+    /// `{"@preview/foo:0.1"}` to the newest compatible version. Imports with a
+    /// complete version are loaded directly, so the default implementation,
+    /// which returns no versions, is fine for worlds that only support those.
+    fn package_versions(&self, _namespace: EcoString, _name: EcoString) -> Vec<Version> {
+        vec![]
+    }
+
+    /// List the packages that are available for import, along with their
+    /// descriptions.
+    ///
+    /// This is only used for autocompletion of package imports, so the default
+    /// implementation, which returns no packages, is fine for worlds that
+    /// don't know which packages exist.
+    fn packages(&self) -> &[(PackageSpec, Option<EcoString>)] {
+        &[]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diag::ErrorKind;
+    use crate::doc::FrameItem;
+    use crate::testing::TestWorld;
+
+    #[test]
+    fn test_compile_cancelled() {
+        let world = TestWorld::new("Cancelled");
+        let token = CancellationToken::new();
+        token.cancel();
+
+        let options = CompileOptions {
+            cancellation: Some(token),
+            ..CompileOptions::default()
+        };
+        let mut tracer = Tracer::default();
+        let errors = compile_with(&world, &mut tracer, &options).unwrap_err();
+        assert!(errors.iter().any(|error| error.kind == ErrorKind::Cancelled));
+
+        // Errors of a compilation without a cancelled token are regular.
+        let world = TestWorld::new("#foo");
+        let mut tracer = Tracer::default();
+        let errors = compile(&world, &mut tracer).unwrap_err();
+        assert!(errors.iter().all(|error| error.kind == ErrorKind::Regular));
+    }
+
+    #[test]
+    fn test_compile_recover() {
+        let world = TestWorld::new("Before #foo after\n\n#[inner #(1 + \"a\")]\n\nEnd");
+        let mut tracer = Tracer::default();
+        assert!(compile(&world, &mut tracer).is_err());
+
+        let options = CompileOptions { recover: true, ..CompileOptions::default() };
+        let mut tracer = Tracer::default();
+        let document = compile_with(&world, &mut tracer, &options).unwrap();
+
+        let messages: Vec<_> =
+            tracer.errors().iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, ["unknown variable: foo", "cannot add integer and string"]);
+
+        // The placeholders show the messages in place of the failing markup.
+        let text: Vec<String> = document
+            .pages
+            .iter()
+            .map(|page| {
+                page.items()
+                    .filter_map(|(_, item)| match item {
+                        FrameItem::Text(text) => Some(text.text.as_str()),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            text,
+            [
+                "Before unknown variable: foo after",
+                "inner cannot add integer and string",
+                "End",
+            ]
+        );
+    }
 }
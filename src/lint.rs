@@ -0,0 +1,840 @@
+//! Non-fatal checks for likely mistakes in a document.
+//!
+//! Linting looks at the syntax of the main file and of the files it imports
+//! or includes, and at the module the main file evaluates to. Unlike the
+//! warnings collected during compilation, it doesn't need the document to be
+//! typeset successfully.
+
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::path::Path;
+use std::str::FromStr;
+
+use comemo::Track;
+use ecow::{eco_format, EcoString};
+
+use crate::diag::{warning, SourceError};
+use crate::eval::{self, Module, Route, Tracer};
+use crate::geom::Abs;
+use crate::image::{Image, ImageFormat, RasterFormat, VectorFormat};
+use crate::syntax::ast::{self, AstNode};
+use crate::syntax::{Source, SyntaxKind, SyntaxNode};
+use crate::{Limits, World};
+
+/// Settings for linting.
+#[derive(Debug, Default, Clone)]
+pub struct LintConfig {
+    /// The rules not to check. By default, all rules are checked.
+    pub disabled: HashSet<Rule>,
+}
+
+impl LintConfig {
+    /// Whether a rule is checked.
+    pub fn is_enabled(&self, rule: Rule) -> bool {
+        !self.disabled.contains(&rule)
+    }
+}
+
+/// A diagnostic produced by a lint rule.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Lint {
+    /// The rule that produced the diagnostic.
+    pub rule: Rule,
+    /// The diagnostic itself, with severity `Warning`.
+    pub diagnostic: SourceError,
+}
+
+/// A lint rule.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Rule {
+    /// A `let` binding that is never used.
+    UnusedBinding,
+    /// An imported item that is never used.
+    UnusedImport,
+    /// A `let` binding that shadows a binding of an enclosing scope.
+    ShadowedBinding,
+    /// A heading without a title.
+    EmptyHeading,
+    /// A label that is never referenced.
+    UnreferencedLabel,
+    /// An image that is stretched to a different aspect ratio.
+    DistortedImage,
+    /// The use of a deprecated feature.
+    Deprecated,
+}
+
+impl Rule {
+    /// All rules, in the order in which they are checked.
+    pub const ALL: [Self; 7] = [
+        Self::UnusedBinding,
+        Self::UnusedImport,
+        Self::ShadowedBinding,
+        Self::EmptyHeading,
+        Self::UnreferencedLabel,
+        Self::DistortedImage,
+        Self::Deprecated,
+    ];
+
+    /// The identifier of the rule, like `unused-binding`.
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::UnusedBinding => "unused-binding",
+            Self::UnusedImport => "unused-import",
+            Self::ShadowedBinding => "shadowed-binding",
+            Self::EmptyHeading => "empty-heading",
+            Self::UnreferencedLabel => "unreferenced-label",
+            Self::DistortedImage => "distorted-image",
+            Self::Deprecated => "deprecated",
+        }
+    }
+
+    /// Check the rule, adding its diagnostics to the sink.
+    fn check(self, ctx: &Context, sink: &mut Vec<SourceError>) {
+        match self {
+            Self::UnusedBinding => unused(ctx, Kind::Let, sink),
+            Self::UnusedImport => unused(ctx, Kind::Import, sink),
+            Self::ShadowedBinding => shadowed(ctx, sink),
+            Self::EmptyHeading => for_each(ctx, |_, node| empty_heading(node, sink)),
+            Self::UnreferencedLabel => unreferenced_labels(ctx, sink),
+            Self::DistortedImage => {
+                for_each(ctx, |source, node| distorted_image(ctx, source, node, sink))
+            }
+            Self::Deprecated => {
+                for_each(ctx, |source, node| deprecated(ctx, source, node, sink))
+            }
+        }
+    }
+}
+
+impl FromStr for Rule {
+    type Err = EcoString;
+
+    /// Find a rule by its identifier.
+    fn from_str(id: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|rule| rule.id() == id)
+            .ok_or_else(|| eco_format!("unknown lint rule: {id}"))
+    }
+}
+
+/// Check the enabled rules.
+pub(crate) fn lint(world: &dyn World, config: &LintConfig) -> Vec<Lint> {
+    let main = world.main();
+    let mut tracer = Tracer::default();
+    let route = Route::default();
+    let module = eval::eval(
+        world.track(),
+        route.track(),
+        tracer.track_mut(),
+        Limits::default(),
+        &main,
+    )
+    .ok();
+
+    let ctx = Context { world, sources: sources(world, main), module };
+    let mut lints = vec![];
+    for rule in Rule::ALL {
+        if config.is_enabled(rule) {
+            let mut sink = vec![];
+            rule.check(&ctx, &mut sink);
+            lints.extend(sink.into_iter().map(|diagnostic| Lint { rule, diagnostic }));
+        }
+    }
+
+    lints
+}
+
+/// What the rules can inspect.
+struct Context<'a> {
+    /// The world the document lives in.
+    world: &'a dyn World,
+    /// The main file, followed by the files it depends on.
+    sources: Vec<Source>,
+    /// The module of the main file, if it could be evaluated.
+    module: Option<Module>,
+}
+
+impl Context<'_> {
+    /// Whether a name refers to a definition of the standard library at the
+    /// top level of a file.
+    ///
+    /// Only the main file's evaluated module is known, so names in other
+    /// files are assumed to refer to the standard library.
+    fn is_std(&self, source: &Source, name: &str) -> bool {
+        let Some(module) = &self.module else { return true };
+        if source.id() != self.sources[0].id() {
+            return true;
+        }
+
+        let global = self.world.library().global.scope().get(name);
+        module.scope().get(name).map_or(true, |value| Some(value) == global)
+    }
+}
+
+/// The main file and the files it imports or includes through literal paths,
+/// transitively. Package files are left out.
+fn sources(world: &dyn World, main: Source) -> Vec<Source> {
+    let mut sources = vec![main];
+    let mut i = 0;
+    while i < sources.len() {
+        let mut paths = vec![];
+        dependencies(sources[i].root(), &mut paths);
+        for path in paths {
+            let Ok(id) = sources[i].id().join(&path) else { continue };
+            if sources.iter().all(|source| source.id() != id) {
+                if let Ok(source) = world.source(id) {
+                    sources.push(source);
+                }
+            }
+        }
+        i += 1;
+    }
+    sources
+}
+
+/// Collect the literal paths of the imports and includes in a node.
+fn dependencies(node: &SyntaxNode, paths: &mut Vec<EcoString>) {
+    let path = match node.kind() {
+        SyntaxKind::ModuleImport => node.cast::<ast::ModuleImport>().map(|i| i.source()),
+        SyntaxKind::ModuleInclude => {
+            node.cast::<ast::ModuleInclude>().map(|i| i.source())
+        }
+        _ => None,
+    };
+
+    if let Some(ast::Expr::Str(path)) = path {
+        let path = path.get();
+        if !path.starts_with('@') {
+            paths.push(path);
+        }
+    }
+
+    for child in node.children() {
+        dependencies(child, paths);
+    }
+}
+
+/// Call a function for every node in every source file.
+fn for_each(ctx: &Context, mut f: impl FnMut(&Source, &SyntaxNode)) {
+    fn visit(
+        source: &Source,
+        node: &SyntaxNode,
+        f: &mut impl FnMut(&Source, &SyntaxNode),
+    ) {
+        f(source, node);
+        for child in node.children() {
+            visit(source, child, f);
+        }
+    }
+
+    for source in &ctx.sources {
+        visit(source, source.root(), &mut f);
+    }
+}
+
+/// Report bindings of the given kind that are never used.
+///
+/// The top-level bindings of files other than the main file are exported to
+/// their importers and thus not reported.
+fn unused(ctx: &Context, kind: Kind, sink: &mut Vec<SourceError>) {
+    for (i, source) in ctx.sources.iter().enumerate() {
+        for (k, ident) in Scopes::analyze(source, i == 0).unused {
+            if k != kind {
+                continue;
+            }
+
+            let name = ident.as_str();
+            let error = match kind {
+                Kind::Import => warning!(ident.span(), "unused import `{name}`")
+                    .with_hints(["remove it from the list of imported items".into()]),
+                _ => warning!(ident.span(), "unused variable `{name}`").with_hints([
+                    eco_format!("if this is intentional, rename it to `_{name}`"),
+                ]),
+            };
+            sink.push(error);
+        }
+    }
+}
+
+/// Report `let` bindings that shadow a binding of an enclosing scope.
+fn shadowed(ctx: &Context, sink: &mut Vec<SourceError>) {
+    for (i, source) in ctx.sources.iter().enumerate() {
+        for ident in Scopes::analyze(source, i == 0).shadowed {
+            let name = ident.as_str();
+            sink.push(
+                warning!(ident.span(), "`{name}` shadows a variable of an outer scope")
+                    .with_hints([
+                        "the outer variable is not accessible after this".into()
+                    ]),
+            );
+        }
+    }
+}
+
+/// Report a heading without a title.
+fn empty_heading(node: &SyntaxNode, sink: &mut Vec<SourceError>) {
+    let Some(heading) = node.cast::<ast::Heading>() else { return };
+    let empty = heading.body().as_untyped().children().all(|child| {
+        matches!(
+            child.kind(),
+            SyntaxKind::Space
+                | SyntaxKind::Parbreak
+                | SyntaxKind::Label
+                | SyntaxKind::LineComment
+                | SyntaxKind::BlockComment
+        )
+    });
+
+    if empty {
+        sink.push(
+            warning!(node.span(), "heading is empty")
+                .with_hints(["add a title or remove the heading".into()]),
+        );
+    }
+}
+
+/// Report labels that are attached to markup, but never referenced, used in
+/// code, or passed to the `label` function in any source file.
+fn unreferenced_labels(ctx: &Context, sink: &mut Vec<SourceError>) {
+    fn collect(
+        node: &SyntaxNode,
+        attached: &mut Vec<SyntaxNode>,
+        used: &mut HashSet<EcoString>,
+    ) {
+        match node.kind() {
+            SyntaxKind::Markup => {
+                for child in node.children() {
+                    if child.kind() == SyntaxKind::Label {
+                        attached.push(child.clone());
+                    } else {
+                        collect(child, attached, used);
+                    }
+                }
+                return;
+            }
+            SyntaxKind::Label => {
+                used.insert(node.cast::<ast::Label>().unwrap().get().into());
+            }
+            SyntaxKind::Ref => {
+                used.insert(node.cast::<ast::Ref>().unwrap().target().into());
+            }
+            SyntaxKind::FuncCall => {
+                let call = node.cast::<ast::FuncCall>().unwrap();
+                if is_ident(call.callee(), "label") {
+                    if let Some(ast::Arg::Pos(ast::Expr::Str(name))) =
+                        call.args().items().next()
+                    {
+                        used.insert(name.get());
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        for child in node.children() {
+            collect(child, attached, used);
+        }
+    }
+
+    let mut attached = vec![];
+    let mut used = HashSet::new();
+    for source in &ctx.sources {
+        collect(source.root(), &mut attached, &mut used);
+    }
+
+    for node in attached {
+        let name = node.cast::<ast::Label>().unwrap().get();
+        if !used.contains(name) {
+            sink.push(
+                warning!(node.span(), "label `<{name}>` is never referenced").with_hints(
+                    ["labels are only needed for references, show rules, and queries"
+                        .into()],
+                ),
+            );
+        }
+    }
+}
+
+/// Report an image call that stretches an image to an aspect ratio that
+/// differs from its natural one.
+///
+/// Only calls with a literal path, literal absolute width and height, and
+/// `{fit: "stretch"}` are checked because other images aren't distorted or
+/// their aspect ratio is only known after layout.
+fn distorted_image(
+    ctx: &Context,
+    source: &Source,
+    node: &SyntaxNode,
+    sink: &mut Vec<SourceError>,
+) {
+    let Some(call) = node.cast::<ast::FuncCall>() else { return };
+    if !is_ident(call.callee(), "image") || !ctx.is_std(source, "image") {
+        return;
+    }
+
+    let mut path = None;
+    let mut width = None;
+    let mut height = None;
+    let mut stretch = false;
+    for arg in call.args().items() {
+        match arg {
+            ast::Arg::Pos(ast::Expr::Str(string)) if path.is_none() => {
+                path = Some(string.get());
+            }
+            ast::Arg::Named(named) => match (named.name().as_str(), named.expr()) {
+                ("width", ast::Expr::Numeric(numeric)) => width = absolute(numeric),
+                ("height", ast::Expr::Numeric(numeric)) => height = absolute(numeric),
+                ("fit", ast::Expr::Str(string)) => stretch = string.get() == "stretch",
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    let (Some(path), Some(width), Some(height)) = (path, width, height) else { return };
+    if !stretch || width <= Abs::zero() || height <= Abs::zero() {
+        return;
+    }
+
+    let ext = Path::new(path.as_str())
+        .extension()
+        .and_then(OsStr::to_str)
+        .unwrap_or_default()
+        .to_lowercase();
+
+    let format = match ext.as_str() {
+        "png" => ImageFormat::Raster(RasterFormat::Png),
+        "jpg" | "jpeg" => ImageFormat::Raster(RasterFormat::Jpg),
+        "gif" => ImageFormat::Raster(RasterFormat::Gif),
+        "svg" | "svgz" => ImageFormat::Vector(VectorFormat::Svg),
+        _ => return,
+    };
+
+    let Ok(id) = source.id().join(&path) else { return };
+    let Ok(data) = ctx.world.file(id) else { return };
+    let Ok(image) = Image::new(data, format, None) else { return };
+    if image.width() == 0 || image.height() == 0 {
+        return;
+    }
+
+    let natural = image.width() as f64 / image.height() as f64;
+    let displayed = width / height;
+    if (displayed / natural - 1.0).abs() > 0.01 {
+        sink.push(warning!(node.span(), "image is distorted").with_hints([
+            eco_format!(
+                "the image has an aspect ratio of {natural:.2}, but is displayed \
+                 with one of {displayed:.2}"
+            ),
+            "remove the width or the height, or use `fit: \"contain\"`".into(),
+        ]));
+    }
+}
+
+/// The value of a numeric literal if it is an absolute length.
+fn absolute(numeric: ast::Numeric) -> Option<Abs> {
+    match numeric.get() {
+        (v, ast::Unit::Length(unit)) => Some(Abs::with_unit(v, unit)),
+        _ => None,
+    }
+}
+
+/// Arguments that are deprecated: the function, the parameter, the
+/// deprecated value as written, and the value to use instead.
+const DEPRECATED: &[(&str, &str, &str, &str)] =
+    &[("outline", "indent", "true", "auto"), ("outline", "indent", "false", "none")];
+
+/// Report deprecated arguments in a call or set rule.
+fn deprecated(
+    ctx: &Context,
+    source: &Source,
+    node: &SyntaxNode,
+    sink: &mut Vec<SourceError>,
+) {
+    let (callee, args) = match node.kind() {
+        SyntaxKind::FuncCall => {
+            let call = node.cast::<ast::FuncCall>().unwrap();
+            (call.callee(), call.args())
+        }
+        SyntaxKind::SetRule => {
+            let set = node.cast::<ast::SetRule>().unwrap();
+            (set.target(), set.args())
+        }
+        _ => return,
+    };
+
+    let ast::Expr::Ident(ident) = callee else { return };
+    if !ctx.is_std(source, ident.as_str()) {
+        return;
+    }
+
+    for arg in args.items() {
+        let ast::Arg::Named(named) = arg else { continue };
+        let value = named.expr().as_untyped().text().clone();
+        for &(func, param, old, new) in DEPRECATED {
+            if ident.as_str() == func
+                && named.name().as_str() == param
+                && value.as_str() == old
+            {
+                sink.push(
+                    warning!(named.span(), "`{param}: {old}` is deprecated")
+                        .with_hints([eco_format!("use `{param}: {new}` instead")]),
+                );
+            }
+        }
+    }
+}
+
+/// Whether an expression is the identifier with the given name.
+fn is_ident(expr: ast::Expr, name: &str) -> bool {
+    matches!(expr, ast::Expr::Ident(ident) if ident.as_str() == name)
+}
+
+/// The kind of a binding.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Kind {
+    /// A `let` binding.
+    Let,
+    /// An imported item.
+    Import,
+    /// A parameter of a closure or the pattern of a `for` loop.
+    Param,
+}
+
+/// A binding in a scope.
+struct Binding {
+    ident: ast::Ident,
+    kind: Kind,
+    used: bool,
+}
+
+/// Resolves the uses of variables in a source file to their bindings.
+#[derive(Default)]
+struct Scopes {
+    stack: Vec<Vec<Binding>>,
+    unused: Vec<(Kind, ast::Ident)>,
+    shadowed: Vec<ast::Ident>,
+}
+
+impl Scopes {
+    /// Analyze a source file. For the main file, unused top-level bindings
+    /// are reported, too.
+    fn analyze(source: &Source, main: bool) -> Self {
+        let mut scopes = Self::default();
+        scopes.enter();
+        scopes.walk(source.root());
+        if main {
+            scopes.exit();
+        }
+        scopes
+    }
+
+    /// Walk a node, resolving the variables in it.
+    fn walk(&mut self, node: &SyntaxNode) {
+        match node.kind() {
+            SyntaxKind::Ident | SyntaxKind::MathIdent => self.resolve(node.text()),
+            SyntaxKind::CodeBlock | SyntaxKind::ContentBlock => {
+                self.enter();
+                self.children(node);
+                self.exit();
+            }
+            SyntaxKind::FieldAccess => {
+                let access = node.cast::<ast::FieldAccess>().unwrap();
+                self.walk(access.target().as_untyped());
+            }
+            SyntaxKind::Named => {
+                let named = node.cast::<ast::Named>().unwrap();
+                self.walk(named.expr().as_untyped());
+            }
+            SyntaxKind::LetBinding => {
+                let binding = node.cast::<ast::LetBinding>().unwrap();
+                if let Some(init) = binding.init() {
+                    self.walk(init.as_untyped());
+                }
+                match binding.kind() {
+                    ast::LetBindingKind::Normal(pattern) => {
+                        self.pattern(pattern, Kind::Let)
+                    }
+                    ast::LetBindingKind::Closure(ident) => self.define(ident, Kind::Let),
+                }
+            }
+            SyntaxKind::Closure => {
+                let closure = node.cast::<ast::Closure>().unwrap();
+                self.enter();
+                for param in closure.params().children() {
+                    match param {
+                        ast::Param::Pos(pattern) => self.pattern(pattern, Kind::Param),
+                        ast::Param::Named(named) => {
+                            self.walk(named.expr().as_untyped());
+                            self.define(named.name(), Kind::Param);
+                        }
+                        ast::Param::Sink(spread) => {
+                            if let Some(ident) = spread.name() {
+                                self.define(ident, Kind::Param);
+                            }
+                        }
+                    }
+                }
+                self.walk(closure.body().as_untyped());
+                self.exit();
+            }
+            SyntaxKind::ForLoop => {
+                let for_loop = node.cast::<ast::ForLoop>().unwrap();
+                self.walk(for_loop.iter().as_untyped());
+                self.enter();
+                self.pattern(for_loop.pattern(), Kind::Param);
+                self.walk(for_loop.body().as_untyped());
+                self.exit();
+            }
+            SyntaxKind::ModuleImport => {
+                let import = node.cast::<ast::ModuleImport>().unwrap();
+                self.walk(import.source().as_untyped());
+                if let Some(ast::Imports::Items(items)) = import.imports() {
+                    for ident in items {
+                        self.define(ident, Kind::Import);
+                    }
+                }
+            }
+            _ => self.children(node),
+        }
+    }
+
+    /// Walk the children of a node.
+    fn children(&mut self, node: &SyntaxNode) {
+        for child in node.children() {
+            self.walk(child);
+        }
+    }
+
+    /// Define the variables of a pattern.
+    fn pattern(&mut self, pattern: ast::Pattern, kind: Kind) {
+        for ident in pattern.idents() {
+            self.define(ident, kind);
+        }
+    }
+
+    /// Define a variable in the innermost scope.
+    fn define(&mut self, ident: ast::Ident, kind: Kind) {
+        let (top, outer) = self.stack.split_last_mut().unwrap();
+        let name = ident.as_str();
+        if kind == Kind::Let
+            && !name.starts_with('_')
+            && outer.iter().flatten().any(|binding| binding.ident.as_str() == name)
+        {
+            self.shadowed.push(ident.clone());
+        }
+        top.push(Binding { ident, kind, used: false });
+    }
+
+    /// Mark the innermost binding of a variable as used.
+    fn resolve(&mut self, name: &str) {
+        let binding = self
+            .stack
+            .iter_mut()
+            .rev()
+            .flat_map(|scope| scope.iter_mut().rev())
+            .find(|binding| binding.ident.as_str() == name);
+        if let Some(binding) = binding {
+            binding.used = true;
+        }
+    }
+
+    /// Enter a new scope.
+    fn enter(&mut self) {
+        self.stack.push(vec![]);
+    }
+
+    /// Exit the innermost scope and record its unused bindings.
+    fn exit(&mut self) {
+        for binding in self.stack.pop().unwrap() {
+            if !binding.used
+                && binding.kind != Kind::Param
+                && !binding.ident.as_str().starts_with('_')
+            {
+                self.unused.push((binding.kind, binding.ident));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use comemo::Prehashed;
+
+    use super::*;
+    use crate::diag::{FileError, FileResult};
+    use crate::eval::{Datetime, Library};
+    use crate::file::FileId;
+    use crate::font::{Font, FontBook};
+    use crate::util::Bytes;
+
+    /// A world that only has files. The rules don't need anything else when
+    /// there is no evaluated module.
+    struct FileWorld(HashMap<FileId, Bytes>);
+
+    impl FileWorld {
+        fn new(files: &[(&str, &[u8])]) -> Self {
+            Self(
+                files
+                    .iter()
+                    .map(|&(path, data)| {
+                        (FileId::new(None, &Path::new("/").join(path)), data.into())
+                    })
+                    .collect(),
+            )
+        }
+    }
+
+    impl World for FileWorld {
+        fn library(&self) -> &Prehashed<Library> {
+            unimplemented!()
+        }
+
+        fn book(&self) -> &Prehashed<FontBook> {
+            unimplemented!()
+        }
+
+        fn main(&self) -> Source {
+            self.source(FileId::new(None, Path::new("/main.typ"))).unwrap()
+        }
+
+        fn source(&self, id: FileId) -> FileResult<Source> {
+            let text = String::from_utf8(self.file(id)?.to_vec()).unwrap();
+            Ok(Source::new(id, text))
+        }
+
+        fn file(&self, id: FileId) -> FileResult<Bytes> {
+            self.0
+                .get(&id)
+                .cloned()
+                .ok_or_else(|| FileError::NotFound(id.path().into()))
+        }
+
+        fn font(&self, _: usize) -> Option<Font> {
+            None
+        }
+
+        fn today(&self, _: Option<i64>) -> Option<Datetime> {
+            None
+        }
+    }
+
+    /// Check a rule on a main file and the files it depends on.
+    fn check(rule: Rule, files: &[(&str, &[u8])]) -> Vec<SourceError> {
+        let world = FileWorld::new(files);
+        let ctx = Context {
+            world: &world,
+            sources: sources(&world, world.main()),
+            module: None,
+        };
+        let mut sink = vec![];
+        rule.check(&ctx, &mut sink);
+        sink
+    }
+
+    /// The messages of a rule's diagnostics.
+    fn messages(rule: Rule, files: &[(&str, &[u8])]) -> Vec<String> {
+        check(rule, files)
+            .into_iter()
+            .map(|diagnostic| diagnostic.message.to_string())
+            .collect()
+    }
+
+    /// The messages of a rule's diagnostics for a main file.
+    fn main_messages(rule: Rule, main: &str) -> Vec<String> {
+        messages(rule, &[("main.typ", main.as_bytes())])
+    }
+
+    #[test]
+    fn test_lint_unused() {
+        let main = "#import \"util.typ\": helper, unused-item\n\
+                    #let used = 1\n\
+                    #let unused = 2\n\
+                    #let _ignored = 3\n\
+                    #helper(used)";
+        let util = "#let helper(x) = x\n\
+                    #let unused-item = 1\n\
+                    #let internal() = { let tmp = 1; 2 }";
+        let files = [("main.typ", main.as_bytes()), ("util.typ", util.as_bytes())];
+
+        // Top-level bindings of imported files are exported and thus used.
+        assert_eq!(
+            messages(Rule::UnusedBinding, &files),
+            ["unused variable `unused`", "unused variable `tmp`"]
+        );
+        assert_eq!(messages(Rule::UnusedImport, &files), ["unused import `unused-item`"]);
+    }
+
+    #[test]
+    fn test_lint_shadowed() {
+        let main = "#let used = 1\n#let f(x) = { let used = x; used }\n#f(used)";
+        assert_eq!(
+            main_messages(Rule::ShadowedBinding, main),
+            ["`used` shadows a variable of an outer scope"]
+        );
+    }
+
+    #[test]
+    fn test_lint_empty_heading() {
+        assert_eq!(
+            main_messages(Rule::EmptyHeading, "= Intro\n=\n== <a>"),
+            ["heading is empty", "heading is empty"]
+        );
+    }
+
+    #[test]
+    fn test_lint_unreferenced_label() {
+        let main = "= Intro <intro>\n\
+                    See @intro and text <orphan>\n\
+                    #label(\"called\")\n\
+                    Text <called>";
+        assert_eq!(
+            main_messages(Rule::UnreferencedLabel, main),
+            ["label `<orphan>` is never referenced"]
+        );
+    }
+
+    #[test]
+    fn test_lint_distorted_image() {
+        let main = "#image(\"graph.png\", width: 10cm, height: 10cm, fit: \"stretch\")\n\
+                    #image(\"graph.png\", width: 11.91cm, height: 3.41cm, fit: \"stretch\")\n\
+                    #image(\"graph.png\", width: 10cm, height: 10cm)";
+        let graph = include_bytes!("../assets/files/graph.png");
+        let found = check(
+            Rule::DistortedImage,
+            &[("main.typ", main.as_bytes()), ("graph.png", graph.as_slice())],
+        );
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].message.as_str(), "image is distorted");
+        assert_eq!(
+            found[0].hints[0].as_str(),
+            "the image has an aspect ratio of 3.49, but is displayed with one of 1.00"
+        );
+    }
+
+    #[test]
+    fn test_lint_deprecated() {
+        let main = "#outline(indent: true)\n\
+                    #set outline(indent: false)\n\
+                    #outline(indent: auto)";
+        assert_eq!(
+            main_messages(Rule::Deprecated, main),
+            ["`indent: true` is deprecated", "`indent: false` is deprecated"]
+        );
+    }
+
+    #[test]
+    fn test_lint_rules() {
+        for rule in Rule::ALL {
+            assert_eq!(rule.id().parse::<Rule>(), Ok(rule));
+        }
+
+        let mut config = LintConfig::default();
+        config.disabled.insert(Rule::Deprecated);
+        assert!(!config.is_enabled(Rule::Deprecated));
+        assert!(config.is_enabled(Rule::UnusedBinding));
+        assert!("unused".parse::<Rule>().is_err());
+    }
+}
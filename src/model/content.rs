@@ -149,6 +149,23 @@ impl Content {
         self
     }
 
+    /// Attach a span to the content if it doesn't already have one. For
+    /// sequences and styled elements, the span is instead attached to the
+    /// children, transitively.
+    pub fn spanned_deep(mut self, span: Span) -> Self {
+        if !self.is::<SequenceElem>() && !self.is::<StyledElem>() {
+            return self.spanned(span);
+        }
+
+        for attr in self.attrs.make_mut() {
+            if let Attr::Child(child) = attr {
+                child.update(|child| *child = std::mem::take(child).spanned_deep(span));
+            }
+        }
+
+        self
+    }
+
     /// Attach a field to the content.
     pub fn with_field(
         mut self,
@@ -404,6 +421,15 @@ impl Content {
         text
     }
 
+    /// Counts the elements in this content tree, including itself.
+    ///
+    /// Elements produced in `show` rules are not counted.
+    pub fn count(&self) -> usize {
+        let mut count = 0;
+        self.traverse(&mut |_| count += 1);
+        count
+    }
+
     /// Traverse this content.
     fn traverse<'a, F>(&'a self, f: &mut F)
     where
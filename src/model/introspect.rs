@@ -226,6 +226,23 @@ impl Introspector {
         self.elems.values().map(|(c, _)| c)
     }
 
+    /// Iterate over the locatable elements that are new or whose content or
+    /// position differs from the ones in a previous introspector.
+    pub fn changed<'a>(
+        &'a self,
+        previous: &'a Self,
+    ) -> impl Iterator<Item = &'a Prehashed<Content>> + 'a {
+        self.elems
+            .iter()
+            .filter(|(location, (elem, pos))| {
+                previous
+                    .elems
+                    .get(*location)
+                    .map_or(true, |(prev, prev_pos)| prev != elem || prev_pos != pos)
+            })
+            .map(|(_, (elem, _))| elem)
+    }
+
     /// Get an element by its location.
     fn get(&self, location: &Location) -> Option<&Prehashed<Content>> {
         self.elems.get(location).map(|(elem, _)| elem)
@@ -320,6 +337,18 @@ impl Introspector {
         found.ok_or_else(|| "label does not exist in the document".into())
     }
 
+    /// All distinct labels in the document, in the order in which they first
+    /// occur.
+    pub fn labels(&self) -> EcoVec<Label> {
+        let mut labels = EcoVec::new();
+        for label in self.all().filter_map(|elem| elem.label()) {
+            if !labels.contains(label) {
+                labels.push(label.clone());
+            }
+        }
+        labels
+    }
+
     /// The total number pages.
     pub fn pages(&self) -> NonZeroUsize {
         NonZeroUsize::new(self.pages).unwrap_or(NonZeroUsize::ONE)
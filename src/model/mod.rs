@@ -25,20 +25,30 @@ pub use self::styles::{
 };
 
 use std::mem::ManuallyDrop;
+use std::ops::Range;
 
 use comemo::{Track, Tracked, TrackedMut, Validate};
+use ecow::eco_format;
 
-use crate::diag::{SourceError, SourceResult};
+use crate::diag::{bail, error, warning, SourceError, SourceResult};
 use crate::doc::Document;
 use crate::eval::Tracer;
-use crate::World;
+use crate::syntax::Span;
+use crate::{Limits, World};
 
 /// Typeset content into a fully layouted document.
+///
+/// If a range of `pages` is given, only the pages with these zero-based
+/// indices keep their contents. All other pages are still laid out, so that
+/// counters, references, and queries are correct on the retained pages, but
+/// they are [stripped](crate::doc::Frame::strip) down to their metadata.
 #[comemo::memoize]
 #[tracing::instrument(skip(world, tracer, content))]
 pub fn typeset(
     world: Tracked<dyn World + '_>,
     mut tracer: TrackedMut<Tracer>,
+    limits: Limits,
+    pages: Option<Range<usize>>,
     content: &Content,
 ) -> SourceResult<Document> {
     tracing::info!("Starting typesetting");
@@ -46,16 +56,28 @@ pub fn typeset(
     let library = world.library();
     let styles = StyleChain::new(&library.styles);
 
+    // Guard against documents that are too large to lay out in reasonable
+    // time before doing any work.
+    let (count, max) = (content.count(), limits.max_elements);
+    if count > max {
+        let hint =
+            eco_format!("the document has {count} elements, at most {max} are allowed");
+        bail!(error!(content.span(), "maximum number of elements exceeded")
+            .with_hints([hint]));
+    }
+
     let mut iter = 0;
     let mut document;
     let mut delayed;
+    let depth = Depth::default();
 
     // We need `ManuallyDrop` until this lands in stable:
     // https://github.com/rust-lang/rust/issues/70919
     let mut introspector = ManuallyDrop::new(Introspector::new(&[]));
 
     // Relayout until all introspections stabilize.
-    // If that doesn't happen within five attempts, we give up.
+    // If that doesn't happen within the configured number of attempts, we
+    // give up.
     loop {
         tracing::info!("Layout iteration {iter}");
 
@@ -69,20 +91,46 @@ pub fn typeset(
             locator: &mut locator,
             introspector: introspector.track_with(&constraint),
             delayed: delayed.track_mut(),
+            limits,
+            depth: depth.track(),
         };
 
+        vt.check_cancelled(content.span())?;
+
         // Layout!
         let result = (library.items.layout)(&mut vt, content, styles)?;
 
-        // Drop the old introspector.
-        ManuallyDrop::into_inner(introspector);
+        // Keep the old introspector until the end of the iteration to find
+        // out what changed.
+        let previous = ManuallyDrop::into_inner(introspector);
 
         // Only now assign the document and construct the new introspector.
         document = result;
         introspector = ManuallyDrop::new(Introspector::new(&document.pages));
         iter += 1;
 
-        if iter >= 5 || introspector.validate(&constraint) {
+        if introspector.validate(&constraint) {
+            break;
+        }
+
+        if iter >= limits.max_layout_iterations {
+            // Point to the first element that still changed in the last
+            // attempt. If only something without a span changed, fall back
+            // to the first element of the document.
+            if let Some(span) = introspector
+                .changed(&previous)
+                .chain(introspector.all())
+                .map(|elem| elem.span())
+                .find(|span| !span.is_detached())
+            {
+                tracer.warn(
+                    warning!(span, "layout did not converge within {iter} attempts")
+                        .with_hints([
+                            "check if any states or queries are updating themselves"
+                                .into(),
+                        ]),
+                );
+            }
             break;
         }
     }
@@ -90,6 +138,15 @@ pub fn typeset(
     // Drop the introspector.
     ManuallyDrop::into_inner(introspector);
 
+    // Only retain the contents of the requested pages.
+    if let Some(pages) = pages {
+        for (i, page) in document.pages.iter_mut().enumerate() {
+            if !pages.contains(&i) {
+                page.strip();
+            }
+        }
+    }
+
     // Promote delayed errors.
     if !delayed.0.is_empty() {
         return Err(Box::new(delayed.0));
@@ -112,9 +169,24 @@ pub struct Vt<'a> {
     pub delayed: TrackedMut<'a, DelayedErrors>,
     /// The tracer for inspection of the values an expression produces.
     pub tracer: TrackedMut<'a, Tracer>,
+    /// Guard rails against runaway compilations.
+    pub limits: Limits,
+    /// How deeply the current layout is nested in other layouts.
+    pub depth: Tracked<'a, Depth<'a>>,
 }
 
 impl Vt<'_> {
+    /// Abort if the compilation was cancelled.
+    ///
+    /// The returned error is of [kind](crate::diag::ErrorKind) `Cancelled`. This is cheap
+    /// and should be called at safe points of long-running operations.
+    pub fn check_cancelled(&self, span: Span) -> SourceResult<()> {
+        if self.tracer.cancelled() {
+            return Err(Box::new(vec![SourceError::cancelled(span)]));
+        }
+        Ok(())
+    }
+
     /// Perform a fallible operation that does not immediately terminate further
     /// execution. Instead it produces a delayed error that is only promoted to
     /// a fatal one if it remains at the end of the introspection loop.
@@ -135,6 +207,39 @@ impl Vt<'_> {
     }
 }
 
+/// How deeply a layout is nested in other layouts.
+///
+/// Memoized layouts receive the depth as a chain of tracked links instead of a
+/// number. Their results thus only depend on whether the nesting stays within
+/// the limit and can be reused for identical content at other depths.
+#[derive(Default)]
+pub struct Depth<'a> {
+    /// The depth of the layout this one is nested in, if any.
+    ///
+    /// We need to override the constraint's lifetime here so that `Tracked` is
+    /// covariant over the constraint. If it becomes invariant, we're in for a
+    /// world of lifetime pain.
+    outer: Option<Tracked<'a, Self, <Depth<'static> as Validate>::Constraint>>,
+}
+
+impl<'a> Depth<'a> {
+    /// Nest one level deeper than `outer`.
+    pub fn nested(outer: Tracked<'a, Self>) -> Self {
+        Self { outer: Some(outer) }
+    }
+}
+
+#[comemo::track]
+impl<'a> Depth<'a> {
+    /// Whether this is nested at most `max` levels deep.
+    pub fn within(&self, max: usize) -> bool {
+        match self.outer {
+            Some(outer) => max > 0 && outer.within(max - 1),
+            None => true,
+        }
+    }
+}
+
 /// Holds delayed errors.
 #[derive(Default, Clone)]
 pub struct DelayedErrors(Vec<SourceError>);
@@ -146,3 +251,35 @@ impl DelayedErrors {
         self.0.push(error);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::doc::FrameItem;
+    use crate::testing::TestWorld;
+    use crate::CompileOptions;
+
+    #[test]
+    fn test_typeset_page_range() {
+        let world = TestWorld::new("First\n\nSecond\n\nThird <last>");
+        let options = CompileOptions { pages: Some(1..2), ..CompileOptions::default() };
+        let mut tracer = Tracer::default();
+        let document = crate::compile_with(&world, &mut tracer, &options).unwrap();
+        assert_eq!(document.pages.len(), 3);
+
+        let stripped = |i: usize| {
+            document.pages[i]
+                .items()
+                .all(|(_, item)| matches!(item, FrameItem::Meta(..)))
+        };
+        assert!(stripped(0));
+        assert!(!stripped(1));
+        assert!(stripped(2));
+
+        // Stripped pages can still be introspected.
+        let selector = Selector::Label(Label("last".into()));
+        let found = crate::query(&document, &selector);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].1.page.get(), 3);
+    }
+}
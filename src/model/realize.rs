@@ -1,4 +1,7 @@
-use super::{Content, ElemFunc, Element, MetaElem, Recipe, Selector, StyleChain, Vt};
+use super::{
+    Content, ElemFunc, Element, MetaElem, Recipe, Selector, StyleChain, Styles,
+    Transform, Vt,
+};
 use crate::diag::SourceResult;
 use crate::doc::Meta;
 use crate::util::hash128;
@@ -41,8 +44,11 @@ pub fn realize(
             elem.set_location(location);
         }
 
+        // Show-set rules for the element itself are already visible during
+        // synthesis, so that they can affect its synthesized fields.
+        let local = show_set_styles(target, styles);
         if let Some(elem) = elem.with_mut::<dyn Synthesize>() {
-            elem.synthesize(vt, styles)?;
+            elem.synthesize(vt, styles.chain(&local))?;
         }
 
         elem.mark_prepared();
@@ -95,6 +101,21 @@ pub fn realize(
     Ok(realized)
 }
 
+/// Collect the styles of the show-set rules that match the target.
+fn show_set_styles(target: &Content, styles: StyleChain) -> Styles {
+    let mut local = Styles::new();
+    for recipe in styles.recipes() {
+        if let Transform::Style(map) = &recipe.transform {
+            let direct =
+                matches!(recipe.selector, Some(Selector::Elem(..) | Selector::Label(_)));
+            if direct && recipe.applicable(target) {
+                local.apply(map.clone());
+            }
+        }
+    }
+    local
+}
+
 /// Try to apply a recipe to the target.
 fn try_apply(
     vt: &mut Vt,
@@ -151,14 +172,27 @@ fn try_apply(
             Ok(Some(Content::sequence(result)))
         }
 
+        Some(selector @ (Selector::Before { .. } | Selector::After { .. })) => {
+            // Only elements with a location can be found in the document.
+            let Some(location) = target.location() else {
+                return Ok(None);
+            };
+
+            if !vt
+                .introspector
+                .query(selector)
+                .iter()
+                .any(|elem| elem.location() == Some(location))
+            {
+                return Ok(None);
+            }
+
+            recipe.apply_vt(vt, target.clone().guarded(guard)).map(Some)
+        }
+
         // Not supported here.
         Some(
-            Selector::Or(_)
-            | Selector::And(_)
-            | Selector::Location(_)
-            | Selector::Can(_)
-            | Selector::Before { .. }
-            | Selector::After { .. },
+            Selector::Or(_) | Selector::And(_) | Selector::Location(_) | Selector::Can(_),
         ) => Ok(None),
 
         None => Ok(None),
@@ -4,11 +4,12 @@ use std::mem;
 use std::ptr;
 
 use comemo::Prehashed;
-use ecow::{eco_vec, EcoString, EcoVec};
+use ecow::{eco_format, eco_vec, EcoString, EcoVec};
 
 use super::{Content, ElemFunc, Element, Selector, Vt};
-use crate::diag::{SourceResult, Trace, Tracepoint};
+use crate::diag::{SourceResult, StrResult, Trace, Tracepoint};
 use crate::eval::{cast, Args, FromValue, Func, IntoValue, Value, Vm};
+use crate::profile::{self, ProfileKind};
 use crate::syntax::Span;
 
 /// A list of style properties.
@@ -40,6 +41,33 @@ impl Styles {
         self.0.pop();
     }
 
+    /// Look up the value of an element's settable property.
+    ///
+    /// Returns the value of the innermost set rule for the property or the
+    /// property's default value if no set rule applies.
+    pub fn get(&self, func: ElemFunc, name: &str) -> StrResult<Value> {
+        let param = func
+            .info()
+            .params
+            .iter()
+            .find(|param| param.name == name && param.settable)
+            .ok_or_else(|| {
+                eco_format!(
+                    "{} does not have a settable property `{}`",
+                    func.name(),
+                    name
+                )
+            })?;
+
+        StyleChain::new(self)
+            .properties::<Value>(func, param.name, None)
+            .next()
+            .or_else(|| param.default.map(|default| default()))
+            .ok_or_else(|| {
+                eco_format!("property `{}` does not have a default value", name)
+            })
+    }
+
     /// Apply outer styles. Like [`chain`](StyleChain::chain), but in-place.
     pub fn apply(&mut self, mut outer: Self) {
         outer.0.extend(mem::take(self).0.into_iter());
@@ -206,16 +234,24 @@ impl Recipe {
     }
 
     /// Whether the recipe is applicable to the target.
+    ///
+    /// For selectors that restrict to a region of the document, this only
+    /// checks the inner selector. Whether the target lies in the region is
+    /// determined when applying the recipe.
     pub fn applicable(&self, target: &Content) -> bool {
-        self.selector
-            .as_ref()
-            .map_or(false, |selector| selector.matches(target))
+        self.selector.as_ref().map_or(false, |selector| match selector {
+            Selector::Before { selector, .. } | Selector::After { selector, .. } => {
+                selector.matches(target)
+            }
+            _ => selector.matches(target),
+        })
     }
 
     /// Apply the recipe to the given content.
     pub fn apply_vm(&self, vm: &mut Vm, content: Content) -> SourceResult<Content> {
+        let _timer = profile::timer(ProfileKind::Show, self.span);
         match &self.transform {
-            Transform::Content(content) => Ok(content.clone()),
+            Transform::Content(content) => Ok(content.clone().spanned_deep(self.span)),
             Transform::Func(func) => {
                 let args = Args::new(self.span, [Value::Content(content.clone())]);
                 let mut result = func.call_vm(vm, args);
@@ -224,7 +260,7 @@ impl Recipe {
                     let point = || Tracepoint::Show(content.func().name().into());
                     result = result.trace(vm.world(), point, content.span());
                 }
-                Ok(result?.display())
+                Ok(result?.display().spanned_deep(self.span))
             }
             Transform::Style(styles) => Ok(content.styled_with_map(styles.clone())),
         }
@@ -232,15 +268,16 @@ impl Recipe {
 
     /// Apply the recipe to the given content.
     pub fn apply_vt(&self, vt: &mut Vt, content: Content) -> SourceResult<Content> {
+        let _timer = profile::timer(ProfileKind::Show, self.span);
         match &self.transform {
-            Transform::Content(content) => Ok(content.clone()),
+            Transform::Content(content) => Ok(content.clone().spanned_deep(self.span)),
             Transform::Func(func) => {
                 let mut result = func.call_vt(vt, [Value::Content(content.clone())]);
                 if self.selector.is_some() {
                     let point = || Tracepoint::Show(content.func().name().into());
                     result = result.trace(vt.world, point, content.span());
                 }
-                Ok(result?.display())
+                Ok(result?.display().spanned_deep(self.span))
             }
             Transform::Style(styles) => Ok(content.styled_with_map(styles.clone())),
         }
@@ -0,0 +1,438 @@
+//! Package manifests and the resolution of package imports.
+//!
+//! An import like `{"@preview/foo:0.1"}` names a package and a version bound.
+//! The bound is resolved to the newest compatible version that the [`World`]
+//! knows about, the package's `typst.toml` manifest is loaded and validated,
+//! and the result identifies the package's entrypoint. Hosts only need to
+//! list the available versions of a package through
+//! [`World::package_versions`] and provide the package's files through
+//! [`World::file`] and [`World::source`].
+
+use std::fmt::{self, Display, Formatter};
+use std::path::{Component, Path};
+use std::str::FromStr;
+
+use comemo::Tracked;
+use ecow::{eco_format, EcoString};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::diag::{bail, FileError, HintedStrResult, HintedString, StrResult};
+use crate::file::FileId;
+use crate::syntax::is_ident;
+use crate::World;
+
+/// Identifies a package.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct PackageSpec {
+    /// The namespace the package lives in.
+    pub namespace: EcoString,
+    /// The name of the package within its namespace.
+    pub name: EcoString,
+    /// The package's version.
+    pub version: Version,
+}
+
+impl FromStr for PackageSpec {
+    type Err = EcoString;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (namespace, name, version) = split_spec(s)?;
+        Ok(Self { namespace, name, version: version.parse()? })
+    }
+}
+
+impl Display for PackageSpec {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "@{}/{}:{}", self.namespace, self.name, self.version)
+    }
+}
+
+/// A package import as written in the source, whose version may be
+/// incomplete: `{"@preview/foo:0.1"}`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct PackageRequest {
+    /// The namespace the package lives in.
+    pub namespace: EcoString,
+    /// The name of the package within its namespace.
+    pub name: EcoString,
+    /// The versions of the package that are acceptable.
+    pub version: VersionBound,
+}
+
+impl PackageRequest {
+    /// Choose the newest of the given versions that satisfies the request.
+    ///
+    /// A request with a complete version asks for exactly that version, so it
+    /// is resolved without looking at the available versions.
+    pub fn select(
+        &self,
+        available: impl IntoIterator<Item = Version>,
+    ) -> Option<PackageSpec> {
+        let version = match self.version.exact() {
+            Some(version) => version,
+            None => available
+                .into_iter()
+                .filter(|&version| self.version.matches(version))
+                .max()?,
+        };
+
+        Some(PackageSpec {
+            namespace: self.namespace.clone(),
+            name: self.name.clone(),
+            version,
+        })
+    }
+}
+
+impl FromStr for PackageRequest {
+    type Err = EcoString;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (namespace, name, version) = split_spec(s)?;
+        Ok(Self { namespace, name, version: version.parse()? })
+    }
+}
+
+impl Display for PackageRequest {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "@{}/{}:{}", self.namespace, self.name, self.version)
+    }
+}
+
+/// Split a package specification into its namespace, name, and version.
+fn split_spec(s: &str) -> StrResult<(EcoString, EcoString, &str)> {
+    let mut s = unscanny::Scanner::new(s);
+    if !s.eat_if('@') {
+        bail!("package specification must start with '@'");
+    }
+
+    let namespace = s.eat_until('/');
+    if namespace.is_empty() {
+        bail!("package specification is missing namespace");
+    } else if !is_ident(namespace) {
+        bail!("`{namespace}` is not a valid package namespace");
+    }
+
+    s.eat_if('/');
+
+    let name = s.eat_until(':');
+    if name.is_empty() {
+        bail!("package specification is missing name");
+    } else if !is_ident(name) {
+        bail!("`{name}` is not a valid package name");
+    }
+
+    s.eat_if(':');
+
+    let version = s.after();
+    if version.is_empty() {
+        bail!("package specification is missing version");
+    }
+
+    Ok((namespace.into(), name.into(), version))
+}
+
+/// A package's version.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Version {
+    /// The package's major version.
+    pub major: u32,
+    /// The package's minor version.
+    pub minor: u32,
+    /// The package's patch version.
+    pub patch: u32,
+}
+
+impl Version {
+    /// The version of the compiler.
+    pub fn compiler() -> Self {
+        Self {
+            major: env!("CARGO_PKG_VERSION_MAJOR").parse().unwrap(),
+            minor: env!("CARGO_PKG_VERSION_MINOR").parse().unwrap(),
+            patch: env!("CARGO_PKG_VERSION_PATCH").parse().unwrap(),
+        }
+    }
+}
+
+impl FromStr for Version {
+    type Err = EcoString;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bound: VersionBound = s.parse()?;
+        let Some(minor) = bound.minor else {
+            bail!("version number is missing minor version");
+        };
+        let Some(patch) = bound.patch else {
+            bail!("version number is missing patch version");
+        };
+        Ok(Self { major: bound.major, minor, patch })
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl Serialize for Version {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Version {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let string = EcoString::deserialize(d)?;
+        string.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// A version whose trailing components may be missing: `0.1`.
+///
+/// Matches all versions that agree in the given components, following
+/// semantic versioning.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct VersionBound {
+    /// The required major version.
+    pub major: u32,
+    /// The required minor version, if any.
+    pub minor: Option<u32>,
+    /// The required patch version, if any. Only present if the minor version
+    /// is, too.
+    pub patch: Option<u32>,
+}
+
+impl VersionBound {
+    /// Whether the version satisfies the bound.
+    pub fn matches(self, version: Version) -> bool {
+        self.major == version.major
+            && self.minor.map_or(true, |minor| minor == version.minor)
+            && self.patch.map_or(true, |patch| patch == version.patch)
+    }
+
+    /// The single version matching the bound, if all components are given.
+    pub fn exact(self) -> Option<Version> {
+        Some(Version {
+            major: self.major,
+            minor: self.minor?,
+            patch: self.patch?,
+        })
+    }
+}
+
+impl FromStr for VersionBound {
+    type Err = EcoString;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split('.');
+        let mut next = |kind| match parts.next() {
+            None => Ok(None),
+            Some("") => bail!("version number is missing {kind} version"),
+            Some(part) => part
+                .parse::<u32>()
+                .map(Some)
+                .map_err(|_| eco_format!("`{part}` is not a valid {kind} version")),
+        };
+
+        let Some(major) = next("major")? else {
+            bail!("version number is missing major version");
+        };
+        let minor = next("minor")?;
+        let patch = next("patch")?;
+        if let Some(rest) = parts.next() {
+            bail!("version number has unexpected fourth component: `{rest}`");
+        }
+
+        Ok(Self { major, minor, patch })
+    }
+}
+
+impl Display for VersionBound {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.major)?;
+        if let Some(minor) = self.minor {
+            write!(f, ".{minor}")?;
+        }
+        if let Some(patch) = self.patch {
+            write!(f, ".{patch}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A parsed package manifest.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct PackageManifest {
+    /// Details about the package itself.
+    pub package: PackageInfo,
+}
+
+impl PackageManifest {
+    /// Parse the manifest from raw bytes.
+    pub fn parse(bytes: &[u8]) -> StrResult<Self> {
+        let string = std::str::from_utf8(bytes).map_err(FileError::from)?;
+        toml::from_str(string).map_err(|err| {
+            eco_format!("package manifest is malformed: {}", err.message())
+        })
+    }
+
+    /// Ensure that this manifest is indeed for the specified package and that
+    /// the package can be used with this compiler.
+    pub fn validate(&self, spec: &PackageSpec) -> HintedStrResult<()> {
+        if self.package.name != spec.name {
+            return Err(eco_format!(
+                "package manifest contains mismatched name `{}`",
+                self.package.name
+            )
+            .into());
+        }
+
+        if self.package.version != spec.version {
+            return Err(eco_format!(
+                "package manifest contains mismatched version {}",
+                self.package.version
+            )
+            .into());
+        }
+
+        if let Some(required) = self.package.compiler {
+            let current = Version::compiler();
+            if current < required {
+                return Err(HintedString {
+                    message: eco_format!(
+                        "package requires typst {required} or newer \
+                         (current version is {current})"
+                    ),
+                    hints: vec!["update typst to use this package".into()],
+                });
+            }
+        }
+
+        let entrypoint = Path::new(self.package.entrypoint.as_str());
+        if entrypoint.as_os_str().is_empty()
+            || !entrypoint
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(HintedString {
+                message: eco_format!(
+                    "package entrypoint `{}` is not a path within the package",
+                    self.package.entrypoint
+                ),
+                hints: vec![
+                    "the entrypoint must be a relative path like `lib.typ`".into()
+                ],
+            });
+        }
+
+        Ok(())
+    }
+
+    /// The file that is evaluated when the package is imported.
+    pub fn entrypoint(&self, spec: &PackageSpec) -> FileId {
+        let path = Path::new("/").join(self.package.entrypoint.as_str());
+        FileId::new(Some(spec.clone()), &path)
+    }
+}
+
+/// The `package` key in the manifest.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct PackageInfo {
+    /// The name of the package within its namespace.
+    pub name: EcoString,
+    /// The package's version.
+    pub version: Version,
+    /// The path of the entrypoint into the package.
+    pub entrypoint: EcoString,
+    /// The minimum version of the compiler the package works with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compiler: Option<Version>,
+}
+
+/// Resolve a package import to a concrete package and load its manifest.
+///
+/// Picks the newest version the world provides that satisfies the request
+/// and checks that the package's manifest is valid for it.
+pub fn resolve(
+    world: Tracked<dyn World + '_>,
+    request: &PackageRequest,
+) -> HintedStrResult<(PackageSpec, PackageManifest)> {
+    let available = if request.version.exact().is_some() {
+        vec![]
+    } else {
+        world.package_versions(request.namespace.clone(), request.name.clone())
+    };
+
+    let Some(spec) = request.select(available.iter().copied()) else {
+        let mut hints = vec![];
+        if !available.is_empty() {
+            let mut versions = available.clone();
+            versions.sort();
+            let list: Vec<_> = versions.iter().map(Version::to_string).collect();
+            hints.push(eco_format!("available versions are {}", list.join(", ")));
+        }
+        return Err(HintedString {
+            message: eco_format!("package not found (searched for {request})"),
+            hints,
+        });
+    };
+
+    let manifest_id = FileId::new(Some(spec.clone()), Path::new("/typst.toml"));
+    let bytes = world.file(manifest_id)?;
+    let manifest = PackageManifest::parse(&bytes)?;
+    manifest.validate(&spec)?;
+    Ok((spec, manifest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_bound() {
+        let bound: VersionBound = "0.1".parse().unwrap();
+        assert_eq!(bound.to_string(), "0.1");
+        assert!(bound.matches("0.1.4".parse().unwrap()));
+        assert!(!bound.matches("0.2.0".parse().unwrap()));
+        assert_eq!(bound.exact(), None);
+        assert!("1.".parse::<VersionBound>().is_err());
+        assert!("1.2.3.4".parse::<VersionBound>().is_err());
+        assert!("1.2".parse::<Version>().is_err());
+    }
+
+    #[test]
+    fn test_package_request_select() {
+        let versions = ["0.1.0", "0.1.3", "0.2.0", "1.0.0"]
+            .map(|version| version.parse::<Version>().unwrap());
+
+        let select = |text: &str| {
+            let request: PackageRequest = text.parse().unwrap();
+            request.select(versions).map(|spec| spec.to_string())
+        };
+
+        assert_eq!(select("@preview/foo:0.1").as_deref(), Some("@preview/foo:0.1.3"));
+        assert_eq!(select("@preview/foo:0").as_deref(), Some("@preview/foo:0.2.0"));
+        assert_eq!(select("@preview/foo:0.3"), None);
+        assert_eq!(select("@preview/foo:0.3.1").as_deref(), Some("@preview/foo:0.3.1"));
+    }
+
+    #[test]
+    fn test_manifest_validate() {
+        let spec: PackageSpec = "@preview/foo:0.1.0".parse().unwrap();
+        let manifest = |extra: &str| {
+            let toml =
+                format!("[package]\nname = \"foo\"\nversion = \"0.1.0\"\n{extra}\n");
+            PackageManifest::parse(toml.as_bytes()).unwrap()
+        };
+
+        assert!(manifest("entrypoint = \"src/lib.typ\"").validate(&spec).is_ok());
+        assert!(manifest("entrypoint = \"../lib.typ\"").validate(&spec).is_err());
+        assert!(manifest("entrypoint = \"/lib.typ\"").validate(&spec).is_err());
+
+        let future = manifest("entrypoint = \"lib.typ\"\ncompiler = \"999.0.0\"");
+        let error = future.validate(&spec).unwrap_err();
+        assert!(error.message.starts_with("package requires typst 999.0.0 or newer"));
+        assert_eq!(error.hints, ["update typst to use this package"]);
+    }
+}
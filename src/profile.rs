@@ -0,0 +1,214 @@
+//! Opt-in profiling of compilations.
+//!
+//! The profiler measures the same work that is instrumented with `tracing`
+//! spans, but aggregates the timings itself so that hosts get a structured
+//! [report](ProfileEntry) without having to install a subscriber. Enable it
+//! by setting [`CompileOptions::profile`](crate::CompileOptions::profile) and
+//! retrieve the report with [`Tracer::profile`](crate::eval::Tracer::profile).
+//!
+//! Work that is served from comemo's cache is not measured again, so the
+//! report shows where time was actually spent in a compilation.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::syntax::Span;
+
+/// The number of profiling sessions that are currently running on any thread.
+///
+/// Checking this is all that timers do when profiling is disabled.
+static SESSIONS: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    /// The measurements of the session running on this thread.
+    static PROFILER: RefCell<Option<Profiler>> = RefCell::new(None);
+}
+
+/// Settings for profiling a compilation.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ProfileOptions {
+    /// Entries whose total duration is below this threshold are left out of
+    /// the report.
+    pub threshold: Duration,
+}
+
+/// The aggregated timing of one kind of work at one place in the source.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ProfileEntry {
+    /// The span of the function call, show rule, or page.
+    pub span: Span,
+    /// What kind of work was measured.
+    pub kind: ProfileKind,
+    /// The total time spent, including nested entries.
+    pub duration: Duration,
+    /// How often the work was performed.
+    pub count: usize,
+}
+
+/// What kind of work a [`ProfileEntry`] measures.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ProfileKind {
+    /// The evaluation of a function call.
+    Call,
+    /// The application of a show rule.
+    Show,
+    /// The layout of a page run.
+    Page,
+}
+
+/// Start measuring a piece of work. The measurement ends when the returned
+/// timer is dropped.
+///
+/// Returns `None` when no profiling session is running on this thread.
+pub fn timer(kind: ProfileKind, span: Span) -> Option<Timer> {
+    if SESSIONS.load(Ordering::Relaxed) == 0 {
+        return None;
+    }
+
+    let active = PROFILER.with(|profiler| profiler.borrow().is_some());
+    active.then(|| Timer { kind, span, start: Instant::now() })
+}
+
+/// Measures a piece of work until it is dropped.
+pub struct Timer {
+    kind: ProfileKind,
+    span: Span,
+    start: Instant,
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        let elapsed = self.start.elapsed();
+        PROFILER.with(|profiler| {
+            if let Some(profiler) = profiler.borrow_mut().as_mut() {
+                let (duration, count) =
+                    profiler.entries.entry((self.span, self.kind)).or_default();
+                *duration += elapsed;
+                *count += 1;
+            }
+        });
+    }
+}
+
+/// Collects the measurements of a session.
+#[derive(Default)]
+struct Profiler {
+    entries: HashMap<(Span, ProfileKind), (Duration, usize)>,
+}
+
+/// A profiling session that collects the measurements on the current thread
+/// until it is finished.
+pub(crate) struct Session {
+    options: ProfileOptions,
+    outer: Option<Profiler>,
+    restored: bool,
+}
+
+impl Session {
+    /// Start a session on the current thread.
+    pub fn start(options: ProfileOptions) -> Self {
+        let outer = PROFILER.with(|profiler| profiler.replace(Some(Profiler::default())));
+        SESSIONS.fetch_add(1, Ordering::Relaxed);
+        Self { options, outer, restored: false }
+    }
+
+    /// Finish the session and produce its report, with the slowest entries
+    /// first.
+    pub fn finish(mut self) -> Vec<ProfileEntry> {
+        let profiler = self.restore().unwrap_or_default();
+        let mut report: Vec<_> = profiler
+            .entries
+            .into_iter()
+            .filter(|(_, (duration, _))| *duration >= self.options.threshold)
+            .map(|((span, kind), (duration, count))| ProfileEntry {
+                span,
+                kind,
+                duration,
+                count,
+            })
+            .collect();
+        report.sort_by(|a, b| b.duration.cmp(&a.duration));
+        report
+    }
+
+    /// Reinstate the profiler that was active before this session and return
+    /// this session's one.
+    fn restore(&mut self) -> Option<Profiler> {
+        if std::mem::replace(&mut self.restored, true) {
+            return None;
+        }
+        let outer = self.outer.take();
+        PROFILER.with(|profiler| profiler.replace(outer))
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        self.restore();
+        SESSIONS.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::file::FileId;
+
+    #[test]
+    fn test_profile_session() {
+        let id = FileId::new(None, Path::new("/test.typ"));
+        let (call, show) = (Span::new(id, 2), Span::new(id, 3));
+
+        // Without a session, nothing is measured.
+        assert!(timer(ProfileKind::Call, call).is_none());
+
+        let session = Session::start(ProfileOptions::default());
+        drop(timer(ProfileKind::Call, call));
+        drop(timer(ProfileKind::Call, call));
+        {
+            let _timer = timer(ProfileKind::Show, show);
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        // The slowest entry comes first and repeated work is aggregated.
+        let report = session.finish();
+        let found: Vec<_> = report
+            .iter()
+            .map(|entry| (entry.span, entry.kind, entry.count))
+            .collect();
+        assert_eq!(found, [(show, ProfileKind::Show, 1), (call, ProfileKind::Call, 2)]);
+        assert!(report[0].duration >= Duration::from_millis(5));
+        assert!(timer(ProfileKind::Call, call).is_none());
+    }
+
+    #[test]
+    fn test_profile_nested_sessions() {
+        let id = FileId::new(None, Path::new("/test.typ"));
+        let (outer_span, inner_span) = (Span::new(id, 2), Span::new(id, 3));
+
+        // A nested session only sees its own work and the outer one
+        // continues afterwards.
+        let outer = Session::start(ProfileOptions::default());
+        drop(timer(ProfileKind::Page, outer_span));
+        let inner = Session::start(ProfileOptions::default());
+        drop(timer(ProfileKind::Call, inner_span));
+        let report = inner.finish();
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].span, inner_span);
+
+        drop(timer(ProfileKind::Page, outer_span));
+        let report = outer.finish();
+        assert_eq!(report.len(), 1);
+        assert_eq!((report[0].span, report[0].count), (outer_span, 2));
+
+        // Entries below the threshold are left out.
+        let threshold = Duration::from_secs(3600);
+        let session = Session::start(ProfileOptions { threshold });
+        drop(timer(ProfileKind::Call, inner_span));
+        assert!(session.finish().is_empty());
+    }
+}
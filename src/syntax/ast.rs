@@ -2,6 +2,8 @@
 //!
 //! The AST is rooted in the [`Markup`] node.
 
+pub mod visit;
+
 use std::num::NonZeroUsize;
 use std::ops::Deref;
 
@@ -0,0 +1,246 @@
+//! Traversal of syntax trees.
+//!
+//! A [`Visitor`] has one method per kind of AST node, each of which by default
+//! continues with the matching `walk_*` function to visit the node's children.
+//! To act on some kind of node, override its method and call the `walk_*`
+//! function from it to keep descending. All typed nodes implement
+//! [`AstNode`], so their span is available in every callback.
+//!
+//! Descending is driven by the untyped children of a node rather than by the
+//! accessors of the typed nodes, so visitors keep working when new kinds of
+//! nodes are added: Children without a method of their own are skipped
+//! transparently.
+//!
+//! To transform a tree, implement [`VisitorMut`], which works directly on
+//! [`SyntaxNode`]s.
+
+use super::*;
+
+macro_rules! visitor {
+    (
+        exprs: { $($variant:ident($ty:ident) => $visit:ident, $walk:ident;)* }
+        others: { $($other:ident => $visit_other:ident, $walk_other:ident;)* }
+    ) => {
+        /// Visits the nodes of a typed syntax tree.
+        pub trait Visitor {
+            /// Visit an expression, dispatching to the method for its kind.
+            fn visit_expr(&mut self, expr: &Expr) {
+                walk_expr(self, expr)
+            }
+
+            $(
+                #[doc = concat!("Visit a [`", stringify!($ty), "`] node.")]
+                fn $visit(&mut self, node: &$ty) {
+                    $walk(self, node)
+                }
+            )*
+
+            $(
+                #[doc = concat!("Visit a [`", stringify!($other), "`] node.")]
+                fn $visit_other(&mut self, node: &$other) {
+                    $walk_other(self, node)
+                }
+            )*
+        }
+
+        /// Dispatch an expression to the visitor method for its kind.
+        pub fn walk_expr<V: Visitor + ?Sized>(visitor: &mut V, expr: &Expr) {
+            match expr {
+                $(Expr::$variant(node) => visitor.$visit(node),)*
+            }
+        }
+
+        $(
+            #[doc = concat!("Visit the children of a [`", stringify!($ty), "`] node.")]
+            pub fn $walk<V: Visitor + ?Sized>(visitor: &mut V, node: &$ty) {
+                walk_children(visitor, node.as_untyped());
+            }
+        )*
+
+        $(
+            #[doc = concat!("Visit the children of a [`", stringify!($other), "`] node.")]
+            pub fn $walk_other<V: Visitor + ?Sized>(visitor: &mut V, node: &$other) {
+                walk_children(visitor, node.as_untyped());
+            }
+        )*
+
+        /// Visit an untyped node with the visitor method for its kind.
+        ///
+        /// Nodes without a typed counterpart, like punctuation or the list of
+        /// items in an import, are skipped, but their children are visited.
+        pub fn walk_node<V: Visitor + ?Sized>(visitor: &mut V, node: &SyntaxNode) {
+            if let Some(expr) = Expr::cast_with_space(node) {
+                return visitor.visit_expr(&expr);
+            }
+
+            match node.kind() {
+                $(SyntaxKind::$other => {
+                    if let Some(node) = node.cast() {
+                        visitor.$visit_other(&node);
+                    }
+                })*
+                _ => walk_children(visitor, node),
+            }
+        }
+    };
+}
+
+visitor! {
+    exprs: {
+        Text(Text) => visit_text, walk_text;
+        Space(Space) => visit_space, walk_space;
+        Linebreak(Linebreak) => visit_linebreak, walk_linebreak;
+        Parbreak(Parbreak) => visit_parbreak, walk_parbreak;
+        Escape(Escape) => visit_escape, walk_escape;
+        Shorthand(Shorthand) => visit_shorthand, walk_shorthand;
+        SmartQuote(SmartQuote) => visit_smart_quote, walk_smart_quote;
+        Strong(Strong) => visit_strong, walk_strong;
+        Emph(Emph) => visit_emph, walk_emph;
+        Raw(Raw) => visit_raw, walk_raw;
+        Link(Link) => visit_link, walk_link;
+        Label(Label) => visit_label, walk_label;
+        Ref(Ref) => visit_ref, walk_ref;
+        Heading(Heading) => visit_heading, walk_heading;
+        List(ListItem) => visit_list_item, walk_list_item;
+        Enum(EnumItem) => visit_enum_item, walk_enum_item;
+        Term(TermItem) => visit_term_item, walk_term_item;
+        Equation(Equation) => visit_equation, walk_equation;
+        Math(Math) => visit_math, walk_math;
+        MathIdent(MathIdent) => visit_math_ident, walk_math_ident;
+        MathAlignPoint(MathAlignPoint) =>
+            visit_math_align_point, walk_math_align_point;
+        MathDelimited(MathDelimited) => visit_math_delimited, walk_math_delimited;
+        MathAttach(MathAttach) => visit_math_attach, walk_math_attach;
+        MathFrac(MathFrac) => visit_math_frac, walk_math_frac;
+        MathRoot(MathRoot) => visit_math_root, walk_math_root;
+        Ident(Ident) => visit_ident, walk_ident;
+        None(None) => visit_none, walk_none;
+        Auto(Auto) => visit_auto, walk_auto;
+        Bool(Bool) => visit_bool, walk_bool;
+        Int(Int) => visit_int, walk_int;
+        Float(Float) => visit_float, walk_float;
+        Numeric(Numeric) => visit_numeric, walk_numeric;
+        Str(Str) => visit_str, walk_str;
+        Code(CodeBlock) => visit_code_block, walk_code_block;
+        Content(ContentBlock) => visit_content_block, walk_content_block;
+        Parenthesized(Parenthesized) => visit_parenthesized, walk_parenthesized;
+        Array(Array) => visit_array, walk_array;
+        Dict(Dict) => visit_dict, walk_dict;
+        Unary(Unary) => visit_unary, walk_unary;
+        Binary(Binary) => visit_binary, walk_binary;
+        FieldAccess(FieldAccess) => visit_field_access, walk_field_access;
+        FuncCall(FuncCall) => visit_func_call, walk_func_call;
+        Closure(Closure) => visit_closure, walk_closure;
+        Let(LetBinding) => visit_let_binding, walk_let_binding;
+        DestructAssign(DestructAssignment) =>
+            visit_destruct_assignment, walk_destruct_assignment;
+        Set(SetRule) => visit_set_rule, walk_set_rule;
+        Show(ShowRule) => visit_show_rule, walk_show_rule;
+        Conditional(Conditional) => visit_conditional, walk_conditional;
+        While(WhileLoop) => visit_while_loop, walk_while_loop;
+        For(ForLoop) => visit_for_loop, walk_for_loop;
+        Import(ModuleImport) => visit_module_import, walk_module_import;
+        Include(ModuleInclude) => visit_module_include, walk_module_include;
+        Break(LoopBreak) => visit_loop_break, walk_loop_break;
+        Continue(LoopContinue) => visit_loop_continue, walk_loop_continue;
+        Return(FuncReturn) => visit_func_return, walk_func_return;
+    }
+    others: {
+        Markup => visit_markup, walk_markup;
+        Code => visit_code, walk_code;
+        Args => visit_args, walk_args;
+        Named => visit_named, walk_named;
+        Keyed => visit_keyed, walk_keyed;
+        Spread => visit_spread, walk_spread;
+        Params => visit_params, walk_params;
+        Destructuring => visit_destructuring, walk_destructuring;
+        Underscore => visit_underscore, walk_underscore;
+    }
+}
+
+/// Visit the children of an untyped node.
+pub fn walk_children<V: Visitor + ?Sized>(visitor: &mut V, node: &SyntaxNode) {
+    for child in node.children() {
+        walk_node(visitor, child);
+    }
+}
+
+/// Transforms an untyped syntax tree in place.
+///
+/// Nodes are cheaply cloned on write, so other references to the same tree
+/// are unaffected. When the transformation changes the tree's text, the
+/// lengths of the ancestors are updated, but spans of new nodes must be
+/// assigned by reparsing the resulting text (e.g. with
+/// [`Source::replace`](crate::syntax::Source::replace)).
+pub trait VisitorMut {
+    /// Visit a node. By default, visits its children.
+    fn visit_node_mut(&mut self, node: &mut SyntaxNode) {
+        walk_node_mut(self, node)
+    }
+}
+
+/// Visit the children of an untyped node mutably.
+pub fn walk_node_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut SyntaxNode) {
+    for i in 0..node.children().len() {
+        let child = &mut node.children_mut()[i];
+        let (prev_len, prev_descendants) = (child.len(), child.descendants());
+        visitor.visit_node_mut(child);
+        let (new_len, new_descendants) = (child.len(), child.descendants());
+        node.update_parent(prev_len, new_len, prev_descendants, new_descendants);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::parse;
+    use super::*;
+
+    #[test]
+    fn test_visitor() {
+        #[derive(Default)]
+        struct Calls {
+            called: Vec<EcoString>,
+            named: Vec<EcoString>,
+        }
+
+        impl Visitor for Calls {
+            fn visit_func_call(&mut self, call: &FuncCall) {
+                if let Expr::Ident(ident) = call.callee() {
+                    self.called.push(ident.get().clone());
+                }
+                walk_func_call(self, call);
+            }
+
+            fn visit_named(&mut self, named: &Named) {
+                self.named.push(named.name().get().clone());
+                walk_named(self, named);
+            }
+        }
+
+        let root = parse("= #f(a: g(1))\n*#h[#k()]*");
+        let mut calls = Calls::default();
+        walk_node(&mut calls, &root);
+        assert_eq!(calls.called, ["f", "g", "h", "k"]);
+        assert_eq!(calls.named, ["a"]);
+    }
+
+    #[test]
+    fn test_visitor_mut() {
+        struct Rename;
+
+        impl VisitorMut for Rename {
+            fn visit_node_mut(&mut self, node: &mut SyntaxNode) {
+                if node.kind() == SyntaxKind::Ident && node.text() == "x" {
+                    *node = SyntaxNode::leaf(SyntaxKind::Ident, "long");
+                }
+                walk_node_mut(self, node);
+            }
+        }
+
+        let mut root = parse("#let x = 1\n#(x + x)");
+        Rename.visit_node_mut(&mut root);
+        let text = root.clone().into_text();
+        assert_eq!(text, "#let long = 1\n#(long + long)");
+        assert_eq!(root.len(), text.len());
+    }
+}
@@ -0,0 +1,361 @@
+//! Canonical formatting of source code.
+
+use super::{Source, SyntaxKind, SyntaxNode};
+
+/// Configuration for [`format`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct FormatConfig {
+    /// The maximum width of a line, in characters. Argument lists, arrays,
+    /// dictionaries, and parameter lists that would exceed it are broken into
+    /// one item per line.
+    pub max_width: usize,
+    /// The number of spaces per level of indentation.
+    pub indent: usize,
+}
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        Self { max_width: 80, indent: 2 }
+    }
+}
+
+/// Format a source file.
+///
+/// Normalizes the whitespace in code: Indentation follows the nesting of
+/// blocks and parentheses, binary operators, commas, colons, and arrows are
+/// surrounded by consistent spacing, and overlong argument lists are wrapped.
+/// Markup and math are kept exactly as they are, except for the code embedded
+/// into them. Comments are preserved.
+///
+/// Only whitespace in code is changed, so the formatted source parses into an
+/// equivalent syntax tree and produces the same output. Files with syntax
+/// errors are returned unchanged.
+pub fn format(source: &Source, config: FormatConfig) -> String {
+    let root = source.root();
+    if root.erroneous() {
+        return source.text().into();
+    }
+
+    let mut formatter = Formatter::new(config, 0);
+    formatter.markup(root, 0);
+    formatter.out
+}
+
+/// Writes formatted source code.
+struct Formatter {
+    config: FormatConfig,
+    out: String,
+    /// The column at which the output starts.
+    offset: usize,
+    /// The number of line breaks to write before the next token.
+    newlines: usize,
+    /// Whether to write a space before the next token.
+    space: bool,
+}
+
+impl Formatter {
+    /// Create a formatter whose output starts at the given column.
+    fn new(config: FormatConfig, offset: usize) -> Self {
+        Self {
+            config,
+            out: String::new(),
+            offset,
+            newlines: 0,
+            space: false,
+        }
+    }
+
+    /// Write markup, leaving everything but embedded code untouched.
+    fn markup(&mut self, node: &SyntaxNode, indent: usize) {
+        if node.kind() == SyntaxKind::Equation || node.children().len() == 0 {
+            self.token(&node.clone().into_text(), indent);
+            return;
+        }
+
+        let mut embedded = false;
+        for child in node.children() {
+            if embedded {
+                self.code(child, indent);
+            } else {
+                self.markup(child, indent);
+            }
+            embedded = child.kind() == SyntaxKind::Hashtag;
+        }
+    }
+
+    /// Write a piece of code, indenting the lines it starts by `indent`.
+    fn code(&mut self, node: &SyntaxNode, indent: usize) {
+        match node.kind() {
+            _ if node.children().len() == 0 => self.token(node.text(), indent),
+            SyntaxKind::ContentBlock | SyntaxKind::Equation => self.markup(node, indent),
+            SyntaxKind::CodeBlock
+            | SyntaxKind::Parenthesized
+            | SyntaxKind::Destructuring => {
+                let inner = indent + self.config.indent;
+                self.sequence(node.kind(), node.children().as_slice(), inner, indent)
+            }
+            SyntaxKind::Args
+            | SyntaxKind::Array
+            | SyntaxKind::Dict
+            | SyntaxKind::Params => self.group(node, indent),
+            _ => self.sequence(node.kind(), node.children().as_slice(), indent, indent),
+        }
+    }
+
+    /// Write a list of items in parentheses, wrapping it if it is too long.
+    fn group(&mut self, node: &SyntaxNode, indent: usize) {
+        let kind = node.kind();
+        let inner = indent + self.config.indent;
+        let children = node.children().as_slice();
+        let open = children.iter().position(|c| c.kind() == SyntaxKind::LeftParen);
+        let close = children.iter().rposition(|c| c.kind() == SyntaxKind::RightParen);
+        let (Some(open), Some(close)) = (open, close) else {
+            self.sequence(kind, children, inner, indent);
+            return;
+        };
+
+        // Try to write the parenthesized part on the current line.
+        self.flush(indent);
+        let mut flat = Formatter::new(self.config, self.column());
+        flat.sequence(kind, &children[..=close], inner, indent);
+
+        // Groups which the user broke over multiple lines or which contain
+        // comments are kept as they are.
+        let keep = children.iter().any(|child| match child.kind() {
+            SyntaxKind::Space => child.text().contains('\n'),
+            kind => kind.is_trivia(),
+        });
+
+        let empty = children[open + 1..close].iter().all(|c| c.kind().is_trivia());
+        let width = flat.out.chars().count();
+        if keep
+            || empty
+            || flat.out.contains('\n')
+            || self.column() + width <= self.config.max_width
+        {
+            self.out.push_str(&flat.out);
+        } else {
+            // Put each item on its own line.
+            self.code(&children[open], indent);
+            for child in &children[open + 1..close] {
+                match child.kind() {
+                    SyntaxKind::Space => {}
+                    SyntaxKind::Comma => self.token(",", inner),
+                    _ => {
+                        self.newlines = 1;
+                        self.code(child, inner);
+                    }
+                }
+            }
+            self.newlines = 1;
+            self.code(&children[close], indent);
+        }
+
+        // Trailing content blocks directly follow the parentheses.
+        for child in &children[close + 1..] {
+            self.code(child, indent);
+        }
+    }
+
+    /// Write the children of a node, normalizing the whitespace between them.
+    ///
+    /// The children are indented by `inner`, but a closing delimiter at the
+    /// start of a line is indented by `outer`.
+    fn sequence(
+        &mut self,
+        parent: SyntaxKind,
+        children: &[SyntaxNode],
+        inner: usize,
+        outer: usize,
+    ) {
+        let mut prev: Option<&SyntaxNode> = None;
+        let mut had_space = false;
+        for child in children {
+            if child.kind() == SyntaxKind::Space {
+                let newlines = child.text().chars().filter(|&c| c == '\n').count();
+                self.newlines = self.newlines.max(newlines.min(2));
+                had_space = true;
+                continue;
+            }
+
+            if let Some(prev) = prev {
+                if self.newlines == 0 && spaced(parent, prev, child, had_space) {
+                    self.space = true;
+                }
+            }
+
+            let closing = matches!(
+                child.kind(),
+                SyntaxKind::RightBrace
+                    | SyntaxKind::RightParen
+                    | SyntaxKind::RightBracket
+            );
+
+            self.code(child, if closing { outer } else { inner });
+            prev = Some(child);
+            had_space = false;
+        }
+    }
+
+    /// Write a token, preceded by pending whitespace.
+    fn token(&mut self, text: &str, indent: usize) {
+        self.flush(indent);
+        self.out.push_str(text);
+    }
+
+    /// Write pending whitespace.
+    fn flush(&mut self, indent: usize) {
+        if self.newlines > 0 {
+            for _ in 0..self.newlines {
+                self.out.push('\n');
+            }
+            for _ in 0..indent {
+                self.out.push(' ');
+            }
+        } else if self.space {
+            self.out.push(' ');
+        }
+        self.newlines = 0;
+        self.space = false;
+    }
+
+    /// The column at which the next character will be written.
+    fn column(&self) -> usize {
+        match self.out.rfind('\n') {
+            Some(i) => self.out[i + 1..].chars().count(),
+            None => self.offset + self.out.chars().count(),
+        }
+    }
+}
+
+/// Whether two subsequent children of a node in code should be separated by
+/// a space.
+fn spaced(
+    parent: SyntaxKind,
+    prev: &SyntaxNode,
+    next: &SyntaxNode,
+    had_space: bool,
+) -> bool {
+    let (prev, next) = (prev.kind(), next.kind());
+    let is_comment =
+        |kind| matches!(kind, SyntaxKind::LineComment | SyntaxKind::BlockComment);
+
+    // Comments stay where they are.
+    if is_comment(prev) || is_comment(next) {
+        return had_space;
+    }
+
+    let colon = matches!(
+        parent,
+        SyntaxKind::Named
+            | SyntaxKind::Keyed
+            | SyntaxKind::ShowRule
+            | SyntaxKind::ModuleImport
+    );
+    let eq = matches!(
+        parent,
+        SyntaxKind::LetBinding | SyntaxKind::Closure | SyntaxKind::DestructAssignment
+    );
+
+    match (prev, next) {
+        (SyntaxKind::LeftParen, _) | (_, SyntaxKind::RightParen) if is_group(parent) => {
+            false
+        }
+        (_, SyntaxKind::Comma | SyntaxKind::Semicolon) => false,
+        (SyntaxKind::Comma | SyntaxKind::Semicolon, _) => true,
+        (_, SyntaxKind::Colon) if colon => false,
+        (SyntaxKind::Colon, _) if colon => true,
+        (SyntaxKind::Eq, _) | (_, SyntaxKind::Eq) if eq => true,
+        (SyntaxKind::Arrow, _) | (_, SyntaxKind::Arrow)
+            if parent == SyntaxKind::Closure =>
+        {
+            true
+        }
+        _ => parent == SyntaxKind::Binary || had_space,
+    }
+}
+
+/// Whether a node is a list of items in parentheses.
+fn is_group(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::Args
+            | SyntaxKind::Array
+            | SyntaxKind::Dict
+            | SyntaxKind::Params
+            | SyntaxKind::Parenthesized
+            | SyntaxKind::Destructuring
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::super::parse;
+    use super::*;
+
+    #[track_caller]
+    fn test(text: &str, expected: &str) {
+        let config = FormatConfig { max_width: 30, ..FormatConfig::default() };
+        assert_eq!(format(&Source::detached(text), config), expected);
+    }
+
+    /// Collect the non-whitespace nodes of a tree together with the text of
+    /// the leaves.
+    fn structure(node: &SyntaxNode, out: &mut Vec<(SyntaxKind, String)>) {
+        if node.kind() == SyntaxKind::Space {
+            return;
+        }
+
+        let text = if node.children().len() == 0 {
+            node.text().to_string()
+        } else {
+            String::new()
+        };
+        out.push((node.kind(), text));
+        for child in node.children() {
+            structure(child, out);
+        }
+    }
+
+    #[test]
+    fn test_format_code() {
+        test("#let x=1+2", "#let x = 1 + 2");
+        test("#f( a ,b:1 )[*body*]", "#f(a, b: 1)[*body*]");
+        test("#{\nlet f(x)=x\n\n\n  f(1)\n}", "#{\n  let f(x) = x\n\n  f(1)\n}");
+        test("#show heading:it=>it.body", "#show heading: it => it.body");
+        let commented = "#{\n  // a comment\n  [ *markup  stays* ]\n}";
+        test(commented, commented);
+        test(
+            "#set text(font: \"Linux Libertine\", size: 12pt)",
+            "#set text(\n  font: \"Linux Libertine\",\n  size: 12pt\n)",
+        );
+        test("#let x = (1 +", "#let x = (1 +");
+    }
+
+    #[test]
+    fn test_format_keeps_structure() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/typ");
+        let mut stack = vec![dir];
+        while let Some(path) = stack.pop() {
+            if path.is_dir() {
+                stack
+                    .extend(std::fs::read_dir(&path).unwrap().map(|e| e.unwrap().path()));
+                continue;
+            }
+
+            if path.extension().map_or(true, |ext| ext != "typ") {
+                continue;
+            }
+
+            let text = std::fs::read_to_string(&path).unwrap();
+            let formatted =
+                format(&Source::detached(text.clone()), FormatConfig::default());
+            let (mut expected, mut found) = (vec![], vec![]);
+            structure(&parse(&text), &mut expected);
+            structure(&parse(&formatted), &mut found);
+            assert!(expected == found, "structure changed: {}", path.display());
+        }
+    }
+}
@@ -10,7 +10,7 @@ use comemo::Prehashed;
 use super::ast::Markup;
 use super::reparser::reparse;
 use super::{is_newline, parse, LinkedNode, Span, SyntaxNode};
-use crate::diag::SourceResult;
+use crate::diag::{bail, SourceResult, StrResult};
 use crate::file::FileId;
 use crate::util::StrExt;
 
@@ -116,26 +116,44 @@ impl Source {
         let start_byte = replace.start;
         let start_utf16 = self.byte_to_utf16(start_byte).unwrap();
         let line = self.byte_to_line(start_byte).unwrap();
+        let removed_utf16 = self.0.text[replace.clone()].len_utf16();
+        let old_end = replace.end;
+        let new_end = start_byte + with.len();
 
         let inner = Arc::make_mut(&mut self.0);
 
         // Update the text itself.
         inner.text.update(|text| text.replace_range(replace.clone(), with));
 
+        // Line starts behind the replaced range are preceded by the same line
+        // break as before, so they only shift.
+        let tail = inner.lines.partition_point(|line| line.byte_idx <= old_end);
+        let shifted: Vec<_> = inner
+            .lines
+            .drain(tail..)
+            .map(|line| Line {
+                byte_idx: line.byte_idx - old_end + new_end,
+                utf16_idx: line.utf16_idx - removed_utf16 + with.len_utf16(),
+            })
+            .collect();
+
         // Remove invalidated line starts.
         inner.lines.truncate(line + 1);
 
         // Handle adjoining of \r and \n.
-        if inner.text[..start_byte].ends_with('\r') && with.starts_with('\n') {
+        if inner.text[..start_byte].ends_with('\r')
+            && inner.text[start_byte..].starts_with('\n')
+            && inner.lines.last().map(|line| line.byte_idx) == Some(start_byte)
+        {
             inner.lines.pop();
         }
 
-        // Recalculate the line starts after the edit.
-        inner.lines.extend(lines_from(
-            start_byte,
-            start_utf16,
-            &inner.text[start_byte..],
-        ));
+        // Recalculate the line starts within the replacement.
+        inner.lines.extend(
+            lines_from(start_byte, start_utf16, &inner.text[start_byte..])
+                .take_while(|line| line.byte_idx <= new_end),
+        );
+        inner.lines.extend(shifted);
 
         // Incrementally reparse the replaced range.
         inner
@@ -143,6 +161,46 @@ impl Source {
             .update(|root| reparse(root, &inner.text, replace, with.len()))
     }
 
+    /// Edit the source file by replacing multiple ranges at once.
+    ///
+    /// All ranges refer to the source before any of the edits. They may touch,
+    /// but must not overlap. Edits of the same range, like multiple insertions
+    /// at one position, are applied in the given order. The result is the same
+    /// as when applying the edits one after another, but the source is only
+    /// reparsed once.
+    ///
+    /// Returns the range in the new source that was ultimately reparsed or an
+    /// error, without editing anything, if two ranges overlap.
+    ///
+    /// The method panics if any range is out of bounds.
+    #[track_caller]
+    pub fn edit_batch(
+        &mut self,
+        edits: &[(Range<usize>, &str)],
+    ) -> StrResult<Range<usize>> {
+        let mut edits: Vec<_> = edits.iter().collect();
+        edits.sort_by_key(|(range, _)| (range.start, range.end));
+
+        let Some((first, _)) = edits.first() else { return Ok(0..0) };
+
+        // Build the replacement for the region spanning all edits.
+        let start = first.start;
+        let end = edits.iter().map(|(range, _)| range.end).max().unwrap_or(start);
+        let mut with = String::new();
+        let mut cursor = start;
+        for (range, text) in edits {
+            if range.start < cursor {
+                bail!("edits must not overlap");
+            }
+            with.push_str(&self.text()[cursor..range.start]);
+            with.push_str(text);
+            cursor = range.end;
+        }
+        with.push_str(&self.text()[cursor..end]);
+
+        Ok(self.edit(start..end, &with))
+    }
+
     /// Get the length of the file in UTF-8 encoded bytes.
     pub fn len_bytes(&self) -> usize {
         self.text().len()
@@ -196,6 +254,9 @@ impl Source {
     }
 
     /// Return the byte index at the UTF-16 code unit.
+    ///
+    /// Returns `None` if the index is out of bounds or points into the middle
+    /// of a surrogate pair.
     pub fn utf16_to_byte(&self, utf16_idx: usize) -> Option<usize> {
         let line = self.0.lines.get(
             match self.0.lines.binary_search_by_key(&utf16_idx, |line| line.utf16_idx) {
@@ -207,7 +268,7 @@ impl Source {
         let mut k = line.utf16_idx;
         for (i, c) in self.0.text[line.byte_idx..].char_indices() {
             if k >= utf16_idx {
-                return Some(line.byte_idx + i);
+                return (k == utf16_idx).then_some(line.byte_idx + i);
             }
             k += c.len_utf16();
         }
@@ -215,6 +276,13 @@ impl Source {
         (k == utf16_idx).then_some(self.0.text.len())
     }
 
+    /// Return the line and column of the byte index, as defined by
+    /// [`byte_to_line`](Self::byte_to_line) and
+    /// [`byte_to_column`](Self::byte_to_column).
+    pub fn byte_to_line_column(&self, byte_idx: usize) -> Option<(usize, usize)> {
+        Some((self.byte_to_line(byte_idx)?, self.byte_to_column(byte_idx)?))
+    }
+
     /// Return the byte position at which the given line starts.
     pub fn line_to_byte(&self, line_idx: usize) -> Option<usize> {
         self.0.lines.get(line_idx).map(|line| line.byte_idx)
@@ -230,17 +298,18 @@ impl Source {
     /// Return the byte index of the given (line, column) pair.
     ///
     /// The column defines the number of characters to go beyond the start of
-    /// the line.
+    /// the line. Returns `None` if the column lies beyond the end of the line,
+    /// not counting its line break.
     pub fn line_column_to_byte(
         &self,
         line_idx: usize,
         column_idx: usize,
     ) -> Option<usize> {
         let range = self.line_to_range(line_idx)?;
-        let line = self.get(range.clone())?;
+        let line = self.get(range.clone())?.trim_end_matches(is_newline);
         let mut chars = line.chars();
         for _ in 0..column_idx {
-            chars.next();
+            chars.next()?;
         }
         Some(range.start + (line.len() - chars.as_str().len()))
     }
@@ -374,6 +443,142 @@ mod tests {
         assert_eq!(source.utf16_to_byte(19), None);
     }
 
+    #[test]
+    fn test_source_file_mappings() {
+        let source = Source::detached(TEST);
+        let text = source.text();
+
+        // Compare against a naive recomputation at every character boundary.
+        let (mut utf16, mut line, mut column) = (0, 0, 0);
+        let mut chars = text.char_indices().peekable();
+        loop {
+            let (i, c) = chars.next().unwrap_or((text.len(), '\0'));
+            assert_eq!(source.byte_to_utf16(i), Some(utf16));
+            assert_eq!(source.utf16_to_byte(utf16), Some(i));
+            assert_eq!(source.byte_to_line_column(i), Some((line, column)));
+            if !text[..i].ends_with('\r') || !text[i..].starts_with('\n') {
+                // Between the two characters of a CRLF, there is no column.
+                assert_eq!(source.line_column_to_byte(line, column), Some(i));
+            }
+
+            if i == text.len() {
+                break;
+            }
+
+            utf16 += c.len_utf16();
+            let crlf = c == '\r' && chars.peek().map(|&(_, c)| c) == Some('\n');
+            if is_newline(c) && !crlf {
+                line += 1;
+                column = 0;
+            } else {
+                column += 1;
+            }
+        }
+
+        // Out-of-range input is rejected.
+        assert_eq!(source.byte_to_utf16(1), None);
+        assert_eq!(source.byte_to_line_column(1), None);
+        assert_eq!(source.byte_to_line_column(22), None);
+        assert_eq!(source.utf16_to_byte(8), None);
+        assert_eq!(source.line_column_to_byte(0, 6), None);
+        assert_eq!(source.line_column_to_byte(4, 0), None);
+        assert_eq!(source.line_to_range(3), Some(18..21));
+        assert_eq!(source.line_to_range(4), None);
+    }
+
+    #[test]
+    fn test_source_file_edit_lines() {
+        // A small deterministic pseudo-random number generator.
+        let mut seed = 0x9e37_79b9_u32;
+        let mut random = |bound: usize| {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            seed as usize % bound
+        };
+
+        // The incrementally updated line starts must match a recomputation.
+        let pieces = ["", "a", "\n", "\r", "\r\n", "\u{2028}", "💛", "ä\nb"];
+        let mut source = Source::detached("");
+        for _ in 0..2000 {
+            let text = source.text();
+            let bounds: Vec<_> =
+                text.char_indices().map(|(i, _)| i).chain([text.len()]).collect();
+            let start = random(bounds.len());
+            let end = (start + random(4)).min(bounds.len() - 1);
+            let with = pieces[random(pieces.len())];
+            source.edit(bounds[start]..bounds[end], with);
+            assert_eq!(source.0.lines, lines(source.text()), "text: {:?}", source.text());
+        }
+    }
+
+    #[test]
+    fn test_source_file_edit_batch() {
+        // A small deterministic pseudo-random number generator.
+        let mut seed = 0x2545_f491_u32;
+        let mut random = |bound: usize| {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            seed as usize % bound
+        };
+
+        let pieces =
+            ["", "a", " ", "\n", "\r\n", "*", "_", "#", "{", "}", "[", "]", "$", "💛"];
+        let base = "= A\n#let x = {\n  [*b* _c_]\n}\r\n$ a + b $ 💛 // d\n";
+        let bounds: Vec<_> =
+            base.char_indices().map(|(i, _)| i).chain([base.len()]).collect();
+
+        for _ in 0..500 {
+            // Pick non-overlapping ranges on character boundaries. Some ranges
+            // touch and some share their start with an insertion.
+            let mut edits = vec![];
+            let mut i = random(3);
+            while i < bounds.len() {
+                let j = (i + random(4)).min(bounds.len() - 1);
+                if random(4) == 0 {
+                    edits.push((bounds[i]..bounds[i], pieces[random(pieces.len())]));
+                }
+                edits.push((bounds[i]..bounds[j], pieces[random(pieces.len())]));
+                i = j + random(6);
+            }
+
+            // The batch may list the edits in any order.
+            for k in (1..edits.len()).rev() {
+                edits.swap(k, random(k + 1));
+            }
+
+            let mut sorted = edits.clone();
+            sorted.sort_by_key(|(range, _)| (range.start, range.end));
+            let mut naive = Source::detached(base);
+            for (range, with) in sorted.iter().rev() {
+                naive.edit(range.clone(), with);
+            }
+
+            let mut batched = Source::detached(base);
+            batched.edit_batch(&edits).unwrap();
+
+            let mut found = batched.root().clone();
+            let mut expected = parse(naive.text());
+            found.synthesize(Span::detached());
+            expected.synthesize(Span::detached());
+            assert_eq!(batched.text(), naive.text());
+            assert_eq!(batched.0.lines, lines(naive.text()));
+            assert_eq!(found, expected, "edits: {edits:?}");
+        }
+
+        // An insertion at the start of a replaced range goes before the
+        // replacement, regardless of the order of the edits.
+        let mut source = Source::detached("abcdef");
+        source.edit_batch(&[(3..5, "x"), (3..3, "y")]).unwrap();
+        assert_eq!(source.text(), "abcyxf");
+
+        // Overlapping edits are rejected without editing anything.
+        let mut source = Source::detached("abcdef");
+        assert!(source.edit_batch(&[(1..4, "x"), (3..5, "y")]).is_err());
+        assert_eq!(source.text(), "abcdef");
+    }
+
     #[test]
     fn test_source_file_roundtrip() {
         #[track_caller]
@@ -0,0 +1,283 @@
+//! Helpers for the unit tests of this crate.
+
+// Not every test binary uses every helper or generated element method.
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use comemo::Prehashed;
+use ecow::EcoString;
+
+use crate::diag::{bail, FileError, FileResult, SourceResult};
+use crate::doc::{Document, Frame, FrameItem, Glyph, Lang, Meta, TextItem};
+use crate::eval::{Datetime, LangItems, Library, Module, Scope};
+use crate::file::{DirEntry, FileId};
+use crate::font::{Font, FontBook};
+use crate::geom::{Abs, Color, Dir, Em, Length, Point, Size};
+use crate::model::{
+    element, Content, Element, MetaElem, StyleChain, Styles, Unlabellable, Vt,
+};
+use crate::package::PackageSpec;
+use crate::syntax::{Source, Span};
+use crate::util::{hash128, Bytes};
+use crate::World;
+
+/// The font of the text that tests construct.
+pub fn font() -> Font {
+    let data = include_bytes!("../assets/fonts/LinLibertine_R.ttf");
+    Font::new(Bytes::from_static(data), 0).unwrap()
+}
+
+/// A run of black 10pt text in the [test font](font), shaped one glyph per
+/// character.
+pub fn text(text: &str) -> TextItem {
+    let font = font();
+    let glyphs = text
+        .char_indices()
+        .map(|(i, c)| {
+            let id = font.ttf().glyph_index(c).map_or(0, |id| id.0);
+            let end = i + c.len_utf8();
+            Glyph {
+                id,
+                x_advance: font.advance(id).unwrap_or(Em::zero()),
+                x_offset: Em::zero(),
+                range: i as u16..end as u16,
+                span: (Span::detached(), 0),
+            }
+        })
+        .collect();
+
+    TextItem {
+        font,
+        size: Abs::pt(10.0),
+        fill: Color::BLACK.into(),
+        lang: Lang::ENGLISH,
+        text: text.into(),
+        glyphs,
+    }
+}
+
+/// A world that holds its source files in memory and has a minimal library
+/// of [test elements](TextElem).
+pub struct TestWorld {
+    library: Prehashed<Library>,
+    book: Prehashed<FontBook>,
+    main: FileId,
+    sources: HashMap<FileId, Source>,
+    packages: Vec<(PackageSpec, Option<EcoString>)>,
+}
+
+impl TestWorld {
+    /// Create a world with the given main source text at `/main.typ`.
+    pub fn new(main: &str) -> Self {
+        let id = FileId::new(None, Path::new("/main.typ"));
+        Self {
+            library: Prehashed::new(library()),
+            book: Prehashed::new(FontBook::from_fonts([&font()])),
+            main: id,
+            sources: HashMap::from([(id, Source::new(id, main.into()))]),
+            packages: vec![],
+        }
+    }
+
+    /// Add a source file at a path relative to the root.
+    pub fn with_file(mut self, path: &str, text: &str) -> Self {
+        let id = FileId::new(None, &Path::new("/").join(path));
+        self.sources.insert(id, Source::new(id, text.into()));
+        self
+    }
+
+    /// Set the packages that are offered when completing package imports.
+    pub fn with_packages(
+        mut self,
+        packages: impl IntoIterator<Item = (PackageSpec, Option<EcoString>)>,
+    ) -> Self {
+        self.packages.extend(packages);
+        self
+    }
+}
+
+impl World for TestWorld {
+    fn library(&self) -> &Prehashed<Library> {
+        &self.library
+    }
+
+    fn book(&self) -> &Prehashed<FontBook> {
+        &self.book
+    }
+
+    fn main(&self) -> Source {
+        self.sources[&self.main].clone()
+    }
+
+    fn source(&self, id: FileId) -> FileResult<Source> {
+        self.sources
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| FileError::NotFound(id.path().into()))
+    }
+
+    fn file(&self, id: FileId) -> FileResult<Bytes> {
+        Ok(self.source(id)?.text().as_bytes().into())
+    }
+
+    fn font(&self, index: usize) -> Option<Font> {
+        (index == 0).then(font)
+    }
+
+    fn today(&self, _: Option<i64>) -> Option<Datetime> {
+        None
+    }
+
+    fn entries(&self, dir: FileId) -> Option<Vec<DirEntry>> {
+        let dir = Path::new("/").join(dir.path());
+        let mut entries = vec![];
+        for id in self.sources.keys() {
+            let Ok(rest) = id.path().strip_prefix(&dir) else { continue };
+            let mut components = rest.components();
+            let Some(first) = components.next() else { continue };
+            let entry = DirEntry {
+                name: first.as_os_str().to_string_lossy().into(),
+                is_dir: components.next().is_some(),
+            };
+
+            if !entries.contains(&entry) {
+                entries.push(entry);
+            }
+        }
+
+        Some(entries)
+    }
+
+    fn packages(&self) -> &[(PackageSpec, Option<EcoString>)] {
+        &self.packages
+    }
+}
+
+/// The library of the [test world](TestWorld).
+///
+/// It defines the `text` function and the color `red`. All markup besides
+/// text, spaces, and paragraph breaks evaluates to its body or to nothing.
+fn library() -> Library {
+    let mut global = Scope::new();
+    global.define("text", TextElem::func());
+    global.define("red", Color::RED);
+
+    let items = LangItems {
+        layout,
+        em: |_| Abs::pt(10.0),
+        dir: |_| Dir::LTR,
+        space: || SpaceElem::new().pack(),
+        linebreak: || SpaceElem::new().pack(),
+        text: |text| TextElem::new(text).pack(),
+        text_func: TextElem::func(),
+        text_str: |content| Some(content.to::<TextElem>()?.text()),
+        smart_quote: |double| {
+            TextElem::new(if double { "\"" } else { "'" }.into()).pack()
+        },
+        parbreak: || ParbreakElem::new().pack(),
+        strong: |body| body,
+        emph: |body| body,
+        raw: |text, _, _| TextElem::new(text).pack(),
+        raw_languages: Vec::new,
+        link: |url| TextElem::new(url).pack(),
+        reference: |_, _| Content::empty(),
+        bibliography_keys: |_| vec![],
+        heading: |_, body| body,
+        heading_func: MetaElem::func(),
+        list_item: |body| body,
+        enum_item: |_, body| body,
+        term_item: |_, description| description,
+        equation: |body, _| body,
+        math_align_point: Content::empty,
+        math_delimited: |_, body, _| body,
+        math_attach: |base, _, _, _, _, _, _| base,
+        math_accent: |base, _| base,
+        math_frac: |num, _| num,
+        math_root: |_, radicand| radicand,
+        error_placeholder: |message, _| TextElem::new(message).pack(),
+        library_method: |_, _, method, _, span| {
+            bail!(span, "type has no method `{method}`")
+        },
+    };
+
+    Library {
+        global: Module::new("global").with_scope(global),
+        math: Module::new("math"),
+        styles: Styles::new(),
+        items,
+    }
+}
+
+/// Lays out the text of each paragraph on a page of its own, ignoring all
+/// styles.
+///
+/// Every piece of text is also an introspectable element, so that it can be
+/// queried by its label.
+fn layout(vt: &mut Vt, content: &Content, _: StyleChain) -> SourceResult<Document> {
+    fn visit(vt: &mut Vt, content: &Content, pages: &mut Vec<Frame>, x: &mut Abs) {
+        if let Some(children) = content.to_sequence() {
+            for child in children {
+                visit(vt, child, pages, x);
+            }
+        } else if let Some((child, _)) = content.to_styled() {
+            visit(vt, child, pages, x);
+        } else if content.is::<ParbreakElem>() {
+            pages.push(page());
+            *x = Abs::zero();
+        } else if content.is::<SpaceElem>() || content.is::<TextElem>() {
+            let string = content.to::<TextElem>().map_or(" ".into(), TextElem::text);
+            let item = text(&string);
+            let width = item.width();
+            let page = pages.last_mut().unwrap();
+
+            let mut elem = content.clone();
+            elem.set_location(vt.locator.locate(hash128(content)));
+            page.push(Point::with_x(*x), FrameItem::Meta(Meta::Elem(elem), Size::zero()));
+            page.push(Point::new(*x, Abs::pt(10.0)), FrameItem::Text(item));
+            *x += width;
+        }
+    }
+
+    let mut pages = vec![page()];
+    visit(vt, content, &mut pages, &mut Abs::zero());
+    Ok(Document { pages, ..Document::default() })
+}
+
+/// An empty page of the [test layout](layout).
+fn page() -> Frame {
+    Frame::new(Size::new(Abs::pt(200.0), Abs::pt(20.0)))
+}
+
+/// A piece of text in the test library.
+///
+/// Display: Text
+/// Category: test
+#[element]
+pub struct TextElem {
+    /// The text's font size. It is ignored by the test layout.
+    pub size: Length,
+
+    /// The text.
+    #[required]
+    pub text: EcoString,
+}
+
+/// A space in the test library.
+///
+/// Display: Space
+/// Category: test
+#[element(Unlabellable)]
+pub struct SpaceElem {}
+
+impl Unlabellable for SpaceElem {}
+
+/// A paragraph break in the test library, which starts a new page.
+///
+/// Display: Paragraph Break
+/// Category: test
+#[element(Unlabellable)]
+pub struct ParbreakElem {}
+
+impl Unlabellable for ParbreakElem {}
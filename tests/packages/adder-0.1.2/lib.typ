@@ -0,0 +1,2 @@
+#let add(x, y) = x + y
+#let version = "0.1.2"
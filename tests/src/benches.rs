@@ -7,7 +7,7 @@ use typst::font::{Font, FontBook};
 use typst::geom::Color;
 use typst::syntax::Source;
 use typst::util::Bytes;
-use typst::World;
+use typst::{Limits, World};
 use unscanny::Scanner;
 
 const TEXT: &str = include_str!("../typ/compiler/bench.typ");
@@ -62,8 +62,14 @@ fn bench_eval(iai: &mut Iai) {
     let route = typst::eval::Route::default();
     let mut tracer = typst::eval::Tracer::default();
     iai.run(|| {
-        typst::eval::eval(world.track(), route.track(), tracer.track_mut(), &world.source)
-            .unwrap()
+        typst::eval::eval(
+            world.track(),
+            route.track(),
+            tracer.track_mut(),
+            Limits::default(),
+            &world.source,
+        )
+        .unwrap()
     });
 }
 
@@ -75,21 +81,30 @@ fn bench_typeset(iai: &mut Iai) {
         world.track(),
         route.track(),
         tracer.track_mut(),
+        Limits::default(),
         &world.source,
     )
     .unwrap();
     let content = module.content();
-    iai.run(|| typst::model::typeset(world.track(), tracer.track_mut(), &content));
+    iai.run(|| {
+        typst::model::typeset(
+            world.track(),
+            tracer.track_mut(),
+            Limits::default(),
+            None,
+            &content,
+        )
+    });
 }
 
 fn bench_compile(iai: &mut Iai) {
     let world = BenchWorld::new();
-    iai.run(|| typst::compile(&world));
+    iai.run(|| typst::compile(&world, &mut typst::eval::Tracer::default()));
 }
 
 fn bench_render(iai: &mut Iai) {
     let world = BenchWorld::new();
-    let document = typst::compile(&world).unwrap();
+    let document = typst::compile(&world, &mut typst::eval::Tracer::default()).unwrap();
     iai.run(|| typst::export::render(&document.pages[0], 1.0, Color::WHITE))
 }
 
@@ -144,6 +159,10 @@ impl World for BenchWorld {
     }
 
     fn today(&self, _: Option<i64>) -> Option<Datetime> {
-        unimplemented!()
+        Some(Datetime::from_ymd(1970, 1, 1).unwrap())
+    }
+
+    fn now(&self, _: Option<i64>) -> Option<Datetime> {
+        Some(Datetime::from_ymd_hms(1970, 1, 1, 0, 0, 0).unwrap())
     }
 }
@@ -21,14 +21,19 @@ use typst::file::FileId;
 use unscanny::Scanner;
 use walkdir::WalkDir;
 
-use typst::diag::{bail, FileError, FileResult, StrResult};
+use typst::diag::{bail, EcoString, FileError, FileResult, Severity, StrResult};
 use typst::doc::{Document, Frame, FrameItem, Meta};
-use typst::eval::{eco_format, func, Datetime, Library, NoneValue, Value};
+use typst::eval::{
+    eco_format, func, Datetime, HostFunc, Library, NoneValue, ParamInfo, Reflect, Str,
+    Tracer, Value,
+};
+use typst::export::PdfOptions;
 use typst::font::{Font, FontBook};
 use typst::geom::{Abs, Color, RgbaColor, Smart};
+use typst::package::Version;
 use typst::syntax::{Source, Span, SyntaxNode};
 use typst::util::{Bytes, PathExt};
-use typst::World;
+use typst::{CompileOptions, Limits, World};
 use typst_library::layout::{Margin, PageElem};
 use typst_library::text::{TextElem, TextSize};
 
@@ -170,7 +175,10 @@ fn library() -> Library {
         NoneValue
     }
 
-    let mut lib = typst_library::build();
+    let mut lib = typst_library::build_with_inputs([
+        ("version".into(), "1.2.3".into()),
+        ("draft".into(), "true".into()),
+    ]);
 
     // Set page width to 120pt with 10pt margins, so that the inner page is
     // exactly 100pt wide. Page height is unbounded and font size is 10pt so
@@ -193,6 +201,33 @@ fn library() -> Library {
         .scope_mut()
         .define("forest", RgbaColor::new(0x43, 0xA1, 0x27, 0xFF));
 
+    // Hook up a host function that captures state.
+    let greeting = EcoString::from("Hello");
+    lib = lib.with_function(
+        HostFunc::new("host-greet", 1, move |args| {
+            let name: EcoString = args.expect("name")?;
+            let times: i64 = args.named("times")?.unwrap_or(1);
+            if times < 0 {
+                bail!(Span::detached(), "cannot greet a negative number of times");
+            }
+            let greeting = eco_format!("{greeting}, {name}!");
+            Ok(Value::Str(greeting.repeat(times as usize).into()))
+        })
+        .with_docs("Greets someone.")
+        .with_param(ParamInfo {
+            name: "name",
+            docs: "Who to greet.",
+            cast: EcoString::describe(),
+            default: None,
+            positional: true,
+            named: false,
+            variadic: false,
+            required: true,
+            settable: false,
+        })
+        .with_returns(Str::describe()),
+    );
+
     lib
 }
 
@@ -277,6 +312,22 @@ impl World for TestWorld {
     fn today(&self, _: Option<i64>) -> Option<Datetime> {
         Some(Datetime::from_ymd(1970, 1, 1).unwrap())
     }
+
+    fn now(&self, _: Option<i64>) -> Option<Datetime> {
+        Some(Datetime::from_ymd_hms(1970, 1, 1, 0, 0, 0).unwrap())
+    }
+
+    fn package_versions(&self, _: EcoString, name: EcoString) -> Vec<Version> {
+        let prefix = format!("{name}-");
+        fs::read_dir("packages")
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| {
+                let file_name = entry.ok()?.file_name();
+                file_name.to_str()?.strip_prefix(&prefix)?.parse().ok()
+            })
+            .collect()
+    }
 }
 
 impl TestWorld {
@@ -405,7 +456,7 @@ fn test(
     let document = Document { pages: frames, ..Default::default() };
     if compare_ever {
         if let Some(pdf_path) = pdf_path {
-            let pdf_data = typst::export::pdf(&document);
+            let pdf_data = typst::export::pdf(&document, &PdfOptions::default()).unwrap();
             fs::create_dir_all(pdf_path.parent().unwrap()).unwrap();
             fs::write(pdf_path, pdf_data).unwrap();
         }
@@ -515,35 +566,62 @@ fn test_part(
         let world = (world as &dyn World).track();
         let route = typst::eval::Route::default();
         let mut tracer = typst::eval::Tracer::default();
+        let limits = Limits::default();
         let module =
-            typst::eval::eval(world, route.track(), tracer.track_mut(), &source).unwrap();
+            typst::eval::eval(world, route.track(), tracer.track_mut(), limits, &source)
+                .unwrap();
         writeln!(output, "Model:\n{:#?}\n", module.content()).unwrap();
     }
 
-    let (mut frames, errors) = match typst::compile(world) {
-        Ok(document) => (document.pages, vec![]),
-        Err(errors) => (vec![], *errors),
+    // Recovered errors are checked just like the ones that abort the
+    // compilation. Show rules that produce the elements they match are not
+    // stopped by the default limits, so the tests limit them.
+    let options = CompileOptions {
+        limits: Limits {
+            max_show_depth: 64,
+            max_layout_depth: 72,
+            ..Limits::default()
+        },
+        recover: metadata.part_configuration.recover.unwrap_or(false),
+        ..CompileOptions::default()
     };
+    let mut tracer = Tracer::default();
+    let (mut frames, diagnostics) =
+        match typst::compile_with(world, &mut tracer, &options) {
+            Ok(document) => {
+                let mut diagnostics = tracer.warnings().to_vec();
+                diagnostics.extend(tracer.errors().iter().cloned());
+                (document.pages, diagnostics)
+            }
+            Err(errors) => {
+                let mut warnings = tracer.warnings().to_vec();
+                warnings.extend(*errors);
+                (vec![], warnings)
+            }
+        };
 
     // Don't retain frames if we don't wanna compare with reference images.
     if !compare_ref {
         frames.clear();
     }
 
-    // Map errors to range and message format, discard traces and errors from
-    // other files, collect hints.
+    // Map diagnostics to range and message format, discard traces and errors
+    // from other files, collect hints.
     //
     // This has one caveat: due to the format of the expected hints, we can not
     // verify if a hint belongs to a error or not. That should be irrelevant
     // however, as the line of the hint is still verified.
-    let actual_errors_and_hints: HashSet<UserOutput> = errors
+    let actual_errors_and_hints: HashSet<UserOutput> = diagnostics
         .into_iter()
         .inspect(|error| assert!(!error.span.is_detached()))
         .filter(|error| error.span.id() == source.id())
         .flat_map(|error| {
             let range = error.span.range(world);
-            let output_error =
-                UserOutput::Error(range.clone(), error.message.replace('\\', "/"));
+            let message = error.message.replace('\\', "/");
+            let output_error = match error.severity {
+                Severity::Error => UserOutput::Error(range.clone(), message),
+                Severity::Warning => UserOutput::Warning(range.clone(), message),
+            };
             let hints = error
                 .hints
                 .iter()
@@ -593,6 +671,7 @@ fn print_user_output(
 ) {
     let (range, message) = match &user_output {
         UserOutput::Error(r, m) => (r, m),
+        UserOutput::Warning(r, m) => (r, m),
         UserOutput::Hint(r, m) => (r, m),
     };
 
@@ -602,6 +681,7 @@ fn print_user_output(
     let end_col = 1 + source.byte_to_column(range.end).unwrap();
     let kind = match user_output {
         UserOutput::Error(_, _) => "Error",
+        UserOutput::Warning(_, _) => "Warning",
         UserOutput::Hint(_, _) => "Hint",
     };
     writeln!(output, "{kind}: {start_line}:{start_col}-{end_line}:{end_col}: {message}")
@@ -611,6 +691,7 @@ fn print_user_output(
 struct TestConfiguration {
     compare_ref: Option<bool>,
     validate_hints: Option<bool>,
+    recover: Option<bool>,
 }
 
 struct TestPartMetadata {
@@ -621,6 +702,7 @@ struct TestPartMetadata {
 #[derive(PartialEq, Eq, Debug, Hash)]
 enum UserOutput {
     Error(Range<usize>, String),
+    Warning(Range<usize>, String),
     Hint(Range<usize>, String),
 }
 
@@ -628,6 +710,7 @@ impl UserOutput {
     fn start(&self) -> usize {
         match self {
             UserOutput::Error(r, _) => r.start,
+            UserOutput::Warning(r, _) => r.start,
             UserOutput::Hint(r, _) => r.start,
         }
     }
@@ -636,6 +719,10 @@ impl UserOutput {
         UserOutput::Error(range, message)
     }
 
+    fn warning(range: Range<usize>, message: String) -> UserOutput {
+        UserOutput::Warning(range, message)
+    }
+
     fn hint(range: Range<usize>, message: String) -> UserOutput {
         UserOutput::Hint(range, message)
     }
@@ -644,12 +731,14 @@ impl UserOutput {
 fn parse_part_metadata(source: &Source) -> TestPartMetadata {
     let mut compare_ref = None;
     let mut validate_hints = None;
+    let mut recover = None;
     let mut expectations = HashSet::default();
 
     let lines: Vec<_> = source.text().lines().map(str::trim).collect();
     for (i, line) in lines.iter().enumerate() {
         compare_ref = get_flag_metadata(line, "Ref").or(compare_ref);
         validate_hints = get_flag_metadata(line, "Hints").or(validate_hints);
+        recover = get_flag_metadata(line, "Recover").or(recover);
 
         fn num(s: &mut Scanner) -> usize {
             s.eat_while(char::is_numeric).parse().unwrap()
@@ -667,12 +756,18 @@ fn parse_part_metadata(source: &Source) -> TestPartMetadata {
         };
 
         let error_factory: fn(Range<usize>, String) -> UserOutput = UserOutput::error;
+        let warning_factory: fn(Range<usize>, String) -> UserOutput = UserOutput::warning;
         let hint_factory: fn(Range<usize>, String) -> UserOutput = UserOutput::hint;
 
         let error_metadata = get_metadata(line, "Error").map(|s| (s, error_factory));
+        let get_warning_metadata =
+            || get_metadata(line, "Warning").map(|s| (s, warning_factory));
         let get_hint_metadata = || get_metadata(line, "Hint").map(|s| (s, hint_factory));
 
-        if let Some((expectation, factory)) = error_metadata.or_else(get_hint_metadata) {
+        if let Some((expectation, factory)) = error_metadata
+            .or_else(get_warning_metadata)
+            .or_else(get_hint_metadata)
+        {
             let mut s = Scanner::new(expectation);
             let start = pos(&mut s);
             let end = if s.eat_if('-') { pos(&mut s) } else { start };
@@ -683,7 +778,7 @@ fn parse_part_metadata(source: &Source) -> TestPartMetadata {
     }
 
     TestPartMetadata {
-        part_configuration: TestConfiguration { compare_ref, validate_hints },
+        part_configuration: TestConfiguration { compare_ref, validate_hints, recover },
         invariants: expectations,
     }
 }
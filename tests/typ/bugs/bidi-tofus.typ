@@ -2,6 +2,13 @@
 // right-to-left directions does not cause a crash.
 
 ---
+// Warning: 2-32 no font contains the character U+0590 (tried "linux libertine")
+// Hint: 2-32 try installing a font that contains this character
 #"\u{590}\u{591}\u{592}\u{593}"
 
+// Warning: 2-40 no font contains the character U+30000 (tried "linux libertine")
+// Warning: 2-40 no font contains the character U+30001 (tried "linux libertine")
+// Warning: 2-40 no font contains the character U+30002 (tried "linux libertine")
+// Warning: 2-40 no font contains the character U+30003 (tried "linux libertine")
+// Hint: 2-40 try installing a font that contains this character
 #"\u{30000}\u{30001}\u{30002}\u{30003}"
@@ -0,0 +1,51 @@
+// Test chunking, windowing, deduplication, and conversion of arrays.
+// Ref: false
+
+---
+// Test the `chunks` method.
+#test(().chunks(2), ())
+#test((1, 2, 3).chunks(1), ((1,), (2,), (3,)))
+#test((1, 2, 3, 4, 5).chunks(2), ((1, 2), (3, 4), (5,)))
+#test((1, 2).chunks(5), ((1, 2),))
+
+---
+// Error: 2-18 chunk size must be positive, found 0
+#(1, 2).chunks(0)
+
+---
+// Test the `windows` method.
+#test(().windows(1), ())
+#test((1, 2).windows(3), ())
+#test((1, 2, 3).windows(3), ((1, 2, 3),))
+#test((1, 2, 3, 4).windows(2), ((1, 2), (2, 3), (3, 4)))
+
+---
+// Error: 2-20 window size must be positive, found -1
+#(1, 2).windows(-1)
+
+---
+// Test the `dedup` method.
+#test(().dedup(), ())
+#test((1, 2, 1, 3, 2).dedup(), (1, 2, 3))
+#test((1, 1.0, "1").dedup(), (1, "1"))
+#test(("a", "B", "A", "b").dedup(key: lower), ("a", "B"))
+#test((1, -1, 2, -2, 3).dedup(key: calc.abs), (1, 2, 3))
+
+---
+// Test the `to-dict` method.
+#test(().to-dict(), (:))
+#test((("a", 1), ("b", 2)).to-dict(), (a: 1, b: 2))
+#test((("a", 1), ("a", 2)).to-dict(), (a: 2))
+#test((a: 1, b: 2).pairs().to-dict(), (a: 1, b: 2))
+
+---
+// Error: 2-18 expected (string, any) pair, found integer
+#(1, 2).to-dict()
+
+---
+// Error: 2-24 expected pair with two items, found 3 items
+#((1, 2, 3),).to-dict()
+
+---
+// Error: 2-21 expected string, found integer
+#((1, 2),).to-dict()
@@ -21,6 +21,27 @@
   a-1 = 2
 }
 
+---
+#{
+  let value = 1
+  // Error: 3-8 unknown variable: valeu
+  // Hint: 3-8 did you mean `value`?
+  valeu
+}
+
+---
+#{
+  let count = 0
+  // Error: 3-9 unknown variable: countt
+  // Hint: 3-9 did you mean `count`?
+  countt += 1
+}
+
+---
+// Error: 2-7 unknown variable: rectt
+// Hint: 2-7 did you mean `rect`?
+#rectt(width: 10pt)
+
 ---
 = Heading <intro>
 
@@ -0,0 +1,27 @@
+// Test functions provided by the host application.
+// Ref: false
+
+---
+#test(host-greet("World"), "Hello, World!")
+#test(host-greet("you", times: 2), "Hello, you!Hello, you!")
+#test(host-greet("nobody", times: 0), "")
+#test(host-greet.with("Typst")(), "Hello, Typst!")
+#test(type(host-greet), "function")
+#test(repr(host-greet), "host-greet")
+
+---
+// Errors without a span point to the call's arguments.
+// Error: 12-29 cannot greet a negative number of times
+#host-greet("me", times: -1)
+
+---
+// Error: 13-15 expected string, found integer
+#host-greet(12)
+
+---
+// Error: 12-14 missing argument: name
+#host-greet()
+
+---
+// Error: 19-21 unexpected argument
+#host-greet("me", 12)
@@ -40,9 +40,29 @@
 #import "@test/mypkg:": *
 
 ---
-// Error: 9-24 version number is missing minor version
+// Test import with an incomplete version.
+#import "@test/adder:0.1": version
+#test(version, "0.1.2")
+
+---
+// Error: 9-24 package not found (searched for @test/mypkg:0)
 #import "@test/mypkg:0": *
 
+---
+// Error: 9-26 package not found (searched for @test/adder:0.2)
+// Hint: 9-26 available versions are 0.1.0, 0.1.2
+#import "@test/adder:0.2": *
+
+---
+// Error: 9-29 package requires typst 99.0.0 or newer (current version is 0.5.0)
+// Hint: 9-29 update typst to use this package
+#import "@test/future:0.1.0": *
+
+---
+// Error: 9-29 package entrypoint `../lib.typ` is not a path within the package
+// Hint: 9-29 the entrypoint must be a relative path like `lib.typ`
+#import "@test/escape:0.1.0": *
+
 ---
 // Error: 9-29 `latest` is not a valid major version
 #import "@test/mypkg:latest": *
@@ -52,7 +72,7 @@
 #import "@test/mypkg:-3.0.0": *
 
 ---
-// Error: 9-26 version number is missing patch version
+// Error: 9-26 package not found (searched for @test/mypkg:0.3)
 #import "@test/mypkg:0.3": *
 
 ---
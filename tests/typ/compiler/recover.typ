@@ -0,0 +1,16 @@
+// Test replacing markup that fails to evaluate with error placeholders.
+// Ref: false
+
+---
+// Recover: true
+// Error: 9-16 unknown variable: missing
+Before #missing after.
+
+// Error: 4-11 cannot add integer and string
+The #(1 + "a") placeholder doesn't stop the rest of the document.
+
+---
+// Test that errors in nested markup are collected, too.
+// Recover: true
+// Error: 15-18 panicked with: 1
+*Strong #panic(1) text.*
@@ -0,0 +1,14 @@
+// Test that show rules producing the elements they match are stopped.
+// Ref: false
+
+---
+// Error: 22-38 maximum show rule depth exceeded
+// Hint: 22-38 check whether the show rule matches its own output
+#show heading: it => heading(it.body)
+= Heading
+
+---
+// Error: 28-44 maximum layout depth exceeded
+// Hint: 28-44 check whether a show rule produces the element it matches
+#show heading: it => block(heading(it.body))
+= Heading
@@ -0,0 +1,53 @@
+// Test show rules with where-clauses, regions, and regular expressions.
+// Ref: false
+
+---
+// Test that show rules can be limited to a region of the document.
+#let appendix = counter("appendix")
+#let main = counter("main")
+#show selector(heading).after(<appendix>): it => {
+  appendix.step()
+  it
+}
+#show selector(heading).before(<appendix>, inclusive: false): it => {
+  main.step()
+  it
+}
+
+= Introduction
+= Methods
+= Appendix <appendix>
+= Extra
+
+#locate(loc => {
+  test(appendix.final(loc), (2,))
+  test(main.final(loc), (2,))
+})
+
+---
+// Test show-set rules for a region between two labels.
+#show selector(heading).after(<start>).before(<end>): set text(red)
+= Before
+= Start <start>
+= Middle
+= End <end>
+= After
+
+---
+// Test selecting figures by their kind.
+#show figure.where(kind: table): it => {
+  test(it.body.func(), table)
+  it
+}
+
+#figure(table[A], caption: [A table])
+#figure(rect(), caption: [A rectangle])
+
+---
+// Test that regular expressions only transform the matched text.
+#show regex("\bTODO\b"): it => {
+  test(it.text, "TODO")
+  text(red, it)
+}
+
+TODO: Fix the TODOs and the TODO.
@@ -168,11 +168,29 @@
   else if m.start == 4 or m.start == 7 { "o" }
 }), "hello world")
 #test("aaa".replace("a", m => str(m.captures.len())), "000")
+#test("a1b2".replace(regex("\d"), m => str(int(m.text) * 2)), "a2b4")
+
+---
+// Test that the match objects of `replace`, `match`, and `matches` agree
+// and that replacement functions can capture variables.
+#let pattern = regex("(\w)(\d)")
+#let prefix = "#"
+#let seen = "x a1 b2".matches(pattern)
+#test("x a1 b2".match(pattern), seen.first())
+#test(seen.map(m => (m.start, m.end, m.text, m.captures)), (
+  (2, 4, "a1", ("a", "1")),
+  (5, 7, "b2", ("b", "2")),
+))
+#test("x a1 b2".replace(pattern, m => prefix + m.captures.rev().join()), "x #1a #2b")
 
 ---
-// Error: 23-24 expected string, found integer
+// Error: 28-29 expected string, found integer
 #"123".replace("123", m => 1)
 
+---
+// Error: 30-40 cannot add integer and string
+#"123".replace("123", m => { 1 + m.text })
+
 ---
 // Error: 23-32 expected string or function, found array
 #"123".replace("123", (1, 2, 3))
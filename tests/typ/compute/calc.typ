@@ -16,6 +16,24 @@
 ---
 #test(calc.round(calc.e, digits: 2), 2.72)
 #test(calc.round(calc.pi, digits: 2), 3.14)
+#test(calc.round(2.5), 3)
+#test(calc.round(-2.5), -3)
+#test(calc.round(-2.4), -2)
+#test(calc.round(-1.125, digits: 2), -1.13)
+#test(calc.round(7, digits: 0), 7)
+#test(calc.round(1234.5, digits: -2), 1200)
+#test(repr(calc.round(-0.4)), "0.0")
+#test(calc.round(0.1, digits: 500), 0.1)
+#test(calc.round(12.0, digits: -500), 0)
+
+---
+// Test float methods.
+#test(calc.nan.is-nan(), true)
+#test((1.5).is-nan(), false)
+#test(calc.inf.is-infinite(), true)
+#test((-calc.inf).is-infinite(), true)
+#test(calc.nan.is-infinite(), false)
+#test((1e300).is-infinite(), false)
 
 ---
 // Error: 6-10 expected boolean, integer, float, or string, found length
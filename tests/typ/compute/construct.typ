@@ -40,6 +40,60 @@
 // Error: 21-26 expected integer or ratio, found boolean
 #rgb(10%, 20%, 30%, false)
 
+---
+// Test Oklab colors.
+#test(repr(oklab(62.8%, 0.225, 0.126)), "oklab(62.8%, 0.225, 0.126)")
+#test(repr(oklab(70%, -0.1, -0.1, 50%)), "oklab(70.0%, -0.100, -0.100, 50.2%)")
+#test(repr(oklch(70%, 0.1, 90deg)), "oklab(70.0%, 0.000, 0.100)")
+#test(oklab(62.8%, 0.225, 0.126).components(), (62.8%, 0.225, 0.126, 100%))
+#test(color.mix(oklab(62.8%, 0.225, 0.126), space: "srgb"), rgb(255, 0, 0))
+#test(oklab(40%, 0.1, 0.1).lighten(50%), oklab(70%, 0.1, 0.1))
+#test(oklab(40%, 0.1, 0.1).negate(), oklab(60%, -0.1, -0.1))
+
+---
+// Test HSL and HSV colors.
+#test(color.hsl(0deg, 100%, 50%), rgb(255, 0, 0))
+#test(color.hsl(200deg, 80%, 40%), rgb(20, 129, 184))
+#test(color.hsl(-160deg, 80%, 40%, 50%), rgb(20, 129, 184, 128))
+#test(color.hsv(240deg, 100%, 100%), rgb(0, 0, 255))
+#test(color.hsv(30deg, 50%, 80%), rgb(204, 153, 102))
+
+---
+// Test mixing colors.
+#test(color.mix(rgb(0, 100, 200), rgb(100, 200, 0), space: "srgb"), rgb(50, 150, 100))
+#test(color.mix((rgb(0, 0, 0, 0), 1), (rgb(0, 0, 0, 100), 3), space: "srgb"), rgb(0, 0, 0, 75))
+#test(repr(color.mix(red, blue)), "oklab(61.1%, 0.075, -0.031)")
+#test(color.mix(red, (blue, 0%)), color.mix(red))
+#test(
+  color.mix(cmyk(0%, 40%, 0%, 40%), cmyk(40%, 80%, 40%, 0%), space: "cmyk"),
+  cmyk(20%, 60%, 20%, 20%),
+)
+
+---
+// Test saturation and components.
+#test(rgb(128, 64, 64).saturate(50%), rgb(160, 32, 32))
+#test(rgb(128, 64, 64).desaturate(50%), rgb(112, 80, 80))
+#test(luma(50%).saturate(50%), luma(50%))
+#test(rgb(10, 20, 30, 40).components(), (10, 20, 30, 40))
+#test(luma(20).components(), (20,))
+#test(repr(cmyk(10%, 20%, 30%, 40%).components()), "(10.2%, 20%, 30.2%, 40%)")
+
+---
+// Error: 8-12 ratio must be between 0% and 100%
+#oklab(-10%, 0, 0)
+
+---
+// Error: 2-13 expected at least one color to mix
+#color.mix()
+
+---
+// Error: 2-22 sum of weights must be positive
+#color.mix((red, 0%))
+
+---
+// Error: 12-23 expected a color or an array of a color and a weight
+#color.mix((red, 1, 2))
+
 ---
 // Ref: true
 #let envelope = symbol(
@@ -69,9 +123,44 @@
 #test(str(9223372036854775807, base: 36), "1y2p0ij32e8e7")
 #test(str(50.14), "50.14")
 #test(str(10 / 3).len() > 10, true)
+#test(str(1e21), "1000000000000000000000")
+#test(str(3.14159, precision: 2), "3.14")
+#test(str(-2.005, precision: 0), "-2")
+#test(str(-0.001, precision: 2), "0.00")
+#test(str(0.125, precision: 2), "0.13")
+#test(str(7, precision: 2), "7.00")
+
+---
+// Error: 22-23 precision is only supported for numbers
+#str("a", precision: 2)
+
+---
+// Error: 31-32 precision is only supported in base 10
+#str(10, base: 16, precision: 2)
+
+---
+// Error: 22-30 number of decimal places must be at most 17
+#str(1.5, precision: 99999999)
 
 ---
-// Error: 6-8 expected integer, float, label, or string, found content
+// Test number formatting.
+#test(format-number(1234567.891, decimals: 2, thousands: ","), "1,234,567.89")
+#test(format-number(0.5, decimals: 3, decimal: ","), "0,500")
+#test(format-number(-42, decimals: 1), "-42.0")
+#test(format-number(-1234, thousands: "."), "-1.234")
+#test(format-number(123, thousands: ","), "123")
+#test(format-number(1e21, thousands: " "), "1 000 000 000 000 000 000 000")
+#test(format-number(2.5, decimals: 0), "3")
+#test(format-number(-2.5, decimals: 0), "-3")
+#test(format-number(calc.inf, thousands: ","), "inf")
+#test(format-number(0.1, decimals: 17), "0.10000000000000001")
+
+---
+// Error: 29-31 number of decimal places must be at most 17
+#format-number(1, decimals: 18)
+
+---
+// Error: 6-8 expected integer, float, label, string, or bytes, found content
 #str([])
 
 ---
@@ -82,6 +171,62 @@
 // Error: 18-19 base is only supported for integers
 #str(1.23, base: 2)
 
+---
+// Test conversion of bytes to string.
+#test(str(bytes("Hello 😃")), "Hello 😃")
+#test(str(bytes((72, 105)), encoding: "utf-8"), "Hi")
+
+---
+// Error: 6-21 bytes are not valid utf-8 (invalid sequence at byte 0)
+#str(bytes((255, 0)))
+
+---
+// Error: 21-28 encoding is only supported for bytes
+#str("a", encoding: "utf-8")
+
+---
+// Test conversion to bytes and arrays.
+#test(bytes("hi"), bytes((104, 105)))
+#test(array(bytes("hi")), (104, 105))
+#test(array((1, 2)), (1, 2))
+#test(repr(bytes("😃")), "bytes(4)")
+#test(bytes(()).len(), 0)
+
+---
+// Test bytes methods.
+#let data = bytes((1, 2, 3, 255))
+#test(data.len(), 4)
+#test(data.at(0), 1)
+#test(data.at(-1), 255)
+#test(data.at(4, default: none), none)
+#test(data.slice(1, 3), bytes((2, 3)))
+#test(data.slice(-2), bytes((3, 255)))
+#test(array(data.slice(1, count: 2)), (2, 3))
+
+---
+// Error: 8-14 number must be between 0 and 255
+#bytes((256,))
+
+---
+// Error: 8-16 expected integer, found string
+#bytes((1, "a"))
+
+---
+// Error: 8-9 expected string, bytes, or array, found integer
+#bytes(1)
+
+---
+// Error: 2-18 byte index out of bounds (index: 2, len: 1) and no default value was specified
+#bytes("a").at(2)
+
+---
+// Error: 2-26 byte index out of bounds (index: 5, len: 3)
+#bytes("abc").slice(1, 5)
+
+---
+// Error: 8-9 expected bytes or array, found integer
+#array(1)
+
 ---
 // Test the unicode function.
 #test(str.from-unicode(97), "a")
@@ -160,6 +305,49 @@
 #test(datetime.today(offset: auto).display(), "1970-01-01")
 #test(datetime.today(offset: 2).display(), "1970-01-01")
 
+// Test now
+#test(datetime.now().display(), "1970-01-01 00:00:00")
+#test(datetime.now(offset: 2).hour(), 0)
+
+---
+// Test durations.
+#let d = duration(days: 14, hours: 3)
+#test(d.days(), 14.125)
+#test(d.hours(), 339.0)
+#test(repr(d), "duration(weeks: 2, hours: 3)")
+#test(repr(duration()), "duration(seconds: 0)")
+#test(repr(-duration(minutes: 90)), "duration(hours: -1, minutes: -30)")
+#test(duration(minutes: 90), duration(hours: 1, minutes: 30))
+#test(duration(hours: 1) + duration(minutes: 30), duration(minutes: 90))
+#test(duration(days: 1) > duration(hours: 23), true)
+
+// Test datetime arithmetic.
+#let start = datetime(year: 2023, month: 6, day: 25)
+#test((start + duration(days: 14)).display(), "2023-07-09")
+#test((duration(weeks: 1) + start).display(), "2023-07-02")
+#test((start - duration(days: 25)).display(), "2023-05-31")
+#test(datetime(year: 2023, month: 7, day: 9) - start, duration(weeks: 2))
+#test(start < datetime(year: 2023, month: 7, day: 1), true)
+#let late = datetime(hour: 23, minute: 0, second: 0)
+#test((late + duration(hours: 2)).display(), "01:00:00")
+
+---
+// Error: 3-83 cannot subtract time from date
+#(datetime(year: 2023, month: 1, day: 1) - datetime(hour: 1, minute: 0, second: 0))
+
+---
+// Error: 3-83 cannot compare date and time
+#(datetime(year: 2023, month: 1, day: 1) < datetime(hour: 1, minute: 0, second: 0))
+
+---
+// Error: 3-63 resulting datetime is out of range
+// Hint: 3-63 datetimes must lie between the years -9999 and 9999
+#(datetime(year: 9999, month: 12, day: 31) + duration(days: 1))
+
+---
+// Error: 10-38 duration is too large
+#duration(weeks: 9223372036854775807)
+
 ---
 // Error: 10-12 at least one of date or time must be fully specified
 #datetime()
@@ -6,6 +6,14 @@
 #let data = read("/hello.txt")
 #test(data, "Hello, world!")
 
+---
+// Test reading files as bytes.
+#let data = read("/hello.txt", encoding: none)
+#test(data.len(), 13)
+#test(data.at(0), 72)
+#test(str(data), "Hello, world!")
+#test(read("/bad.txt", encoding: none).len() > 0, true)
+
 ---
 // Error: 18-32 file not found (searched at /missing.txt)
 #let data = read("/missing.txt")
@@ -68,7 +76,7 @@
 ---
 // Test reading YAML data
 #let data = yaml("/yaml-types.yaml")
-#test(data.len(), 7)
+#test(data.len(), 9)
 #test(data.null_key, (none, none))
 #test(data.string, "text")
 #test(data.integer, 5)
@@ -77,12 +85,48 @@
 #test(data.seq, (1,2,3,4))
 #test(data.bool, false)
 #test(data.keys().contains("true"), false)
+#test(data.date, datetime(year: 2023, month: 6, day: 12))
+#test(data.datetime, datetime(
+  year: 2023, month: 6, day: 12,
+  hour: 12, minute: 30, second: 15,
+))
+
 ---
+// Test encoding YAML data.
+#let data = (name: "Typst", tags: ("fast", "fun"), day: datetime(year: 2023, month: 6, day: 12))
+#test(yaml.encode(data).contains("name: Typst"), true)
+#test(yaml.encode(data).contains("day: 2023-06-12"), true)
+
+---
+// Error: 14-20 cannot encode content as yaml
+#yaml.encode([text])
 
 ---
 // Error: 7-18 failed to parse yaml file: while parsing a flow sequence, expected ',' or ']' at line 2 column 1
 #yaml("/bad.yaml")
 
+---
+// Test reading CBOR data.
+#let data = cbor("/data.cbor")
+#test(data.len(), 5)
+#test(data.name, "Typst")
+#test(data.stars, 25000)
+#test(data.tags, ("fast", "fun"))
+#test(repr(data.data), "bytes(2)")
+#test(data.misc, (1, 2.5, true, none))
+
+---
+// Test encoding CBOR data.
+#test(repr(cbor.encode((a: 1))), "bytes(4)")
+
+---
+// Error: 7-18 failed to parse cbor file: syntax error at byte 2
+#cbor("/bad.cbor")
+
+---
+// Error: 14-20 cannot encode function as cbor
+#cbor.encode(x => x)
+
 ---
 // Test reading XML data.
 #let data = xml("/data.xml")
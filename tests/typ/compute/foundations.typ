@@ -25,6 +25,12 @@
 // Error: 7-24 panicked with: "this is wrong"
 #panic("this is wrong")
 
+---
+// Test panic with a hint.
+// Error: 7-35 panicked with: "oh no"
+// Hint: 7-35 try again
+#panic("oh no", hint: "try again")
+
 ---
 // Test failing assertions.
 // Error: 8-16 assertion failed
@@ -60,6 +66,31 @@
 // Error: 11-57 inequality assertion failed: must be different from 11
 #assert.ne(11, 11, message: "must be different from 11")
 
+---
+// Test failing assertions with hints.
+// Error: 8-42 assertion failed
+// Hint: 8-42 swap the operands
+#assert(1 > 2, hint: "swap the operands")
+
+---
+// Test failing assertions with hints.
+// Error: 11-41 equality assertion failed: value 1 was not equal to 2
+// Hint: 11-41 numbers differ
+#assert.eq(1, 2, hint: "numbers differ")
+
+---
+// Test warnings.
+// Warning: 6-15 plain
+#warn("plain")
+
+---
+// Test that repeated warnings are only reported once.
+#for i in range(3) {
+  // Warning: 7-46 check your input
+  // Hint: 7-46 it is odd
+  warn("check your input", hint: "it is odd")
+}
+
 ---
 // Test successful assertions.
 #assert(5 > 3)
@@ -120,3 +151,34 @@ _No relative giraffe!_
 ---
 // Error: 7-12 expected semicolon or line break
 #eval("1 2")
+
+---
+// Test type values.
+#test(type(1), int)
+#test(type(1.5), float)
+#test(type("hi"), str)
+#test(type((1,)), array)
+#test(type(1) != float, true)
+#test(type(1) == type(2), true)
+#test(type(1) != type("1"), true)
+#test(type(type(1)), "type")
+#test(type(type(1)), type)
+#test(type(type(1))(1), int)
+#test(str(type(1)), "integer")
+#test(repr(type(1)), "integer")
+#test(type(none) == none, false)
+
+---
+// Test calling types and using them as functions.
+#test(type(1)("12"), 12)
+#test(("1", "2").map(type(1)), (1, 2))
+
+---
+// Error: 2-12 expected function, found type
+#type(none)(1)
+
+---
+// Types can be joined with strings and are only in their exact name.
+#test("[" + type(12pt) + "]", "[length]")
+#test("alignment" in type(left), true)
+#test("align" in type(left), false)
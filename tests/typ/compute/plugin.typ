@@ -0,0 +1,52 @@
+// Test WebAssembly plugins.
+// Ref: false
+
+---
+#let p = plugin("/hello.wasm")
+#test(type(p), "plugin")
+#test(p.hello(), bytes("Hello from wasm!!!"))
+#test(p.echo(bytes("abc")), bytes("abc"))
+#test(str(p.concat(bytes("a, "), bytes("b"))), "a, b")
+#test(p.concat(bytes(()), bytes(())), bytes(()))
+
+---
+#let p = plugin("/hello.wasm")
+
+// Error: 2-17 plugin errored with: This is an error
+#p.returns_err()
+
+---
+#let p = plugin("/hello.wasm")
+
+// Error: 2-14 plugin did not respect the protocol
+#p.bad_code()
+
+---
+#let p = plugin("/hello.wasm")
+
+// Error: 2-13 plugin function `forever` exceeded its execution budget
+#p.forever()
+
+---
+#let p = plugin("/hello.wasm")
+
+// Error: 2-14 plugin tried to read out of bounds: 131072 bytes at offset 0x0
+#p.oob_read()
+
+---
+#let p = plugin("/hello.wasm")
+
+// Error: 2-10 plugin does not contain a function called `nope`
+#p.nope()
+
+---
+#let p = plugin("/hello.wasm")
+
+// Error: 2-21 plugin function takes 0 arguments, but 1 was given
+#p.hello(bytes("x"))
+
+---
+#let p = plugin("/hello.wasm")
+
+// Error: 9-14 expected bytes, found string
+#p.echo("abc")
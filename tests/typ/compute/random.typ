@@ -0,0 +1,45 @@
+// Test the deterministic random functions.
+// Ref: false
+
+---
+// The same seed always produces the same result.
+#test(random.integer(seed: 42, min: 1, max: 6), 2)
+#test(random.integer(seed: 42, min: 1, max: 6), 2)
+#test(range(8).map(i => random.integer(seed: i, min: 1, max: 6)), (2, 6, 5, 4, 5, 3, 3, 4))
+#test(random.integer(seed: -1, min: -10, max: 10), 4)
+#test(random.integer(seed: 42), 4456085495900499605)
+#test(random.integer(seed: 0, min: -9223372036854775807 - 1, max: 9223372036854775807), -2152535657050944081)
+#test(random.integer(seed: 5, min: 3, max: 3), 3)
+
+---
+// Shuffling keeps all items.
+#test(random.shuffle(seed: 3, range(10)), (3, 4, 0, 2, 1, 5, 6, 8, 7, 9))
+#test(random.shuffle(seed: 42, (1, 2, 3, 4, 5)), (4, 5, 3, 1, 2))
+#test(random.shuffle(seed: 1, ()), ())
+#test(random.shuffle(seed: 1, ("a",)), ("a",))
+
+---
+// Sampling picks distinct items and agrees with shuffling.
+#let names = ("Ana", "Ben", "Cleo", "Dan")
+#test(random.sample(seed: 7, names, 2), ("Dan", "Ben"))
+#test(random.sample(seed: 7, names, 4), random.shuffle(seed: 7, names))
+#test(random.sample(seed: 42, (1, 2, 3, 4, 5), 3), (4, 5, 3))
+#test(random.sample(seed: 7, names, 0), ())
+
+---
+// Error: 16-32 missing argument: seed
+// Hint: 16-32 random functions are deterministic and need a seed
+// Hint: 16-32 try passing one like `seed: 42`
+#random.integer(min: 1, max: 6)
+
+---
+// Error: 16-41 minimum must not be greater than maximum
+#random.integer(seed: 1, min: 6, max: 1)
+
+---
+// Error: 15-35 cannot sample 3 items from an array with 2 items
+#random.sample((1, 2), 3, seed: 1)
+
+---
+// Error: 24-26 number must be at least zero
+#random.sample((1, 2), -1, seed: 1)
@@ -0,0 +1,19 @@
+// Test inputs passed in by the host application.
+// Ref: false
+
+---
+#test(type(sys.inputs), "dictionary")
+#test(sys.inputs.version, "1.2.3")
+#test(sys.inputs.at("draft"), "true")
+#test(sys.inputs.at("missing", default: none), none)
+#test(sys.inputs.keys(), ("version", "draft"))
+
+---
+// Error: 13-20 dictionary does not contain key "missing" and no default value was specified
+// Hint: 13-20 available keys are "version" and "draft"
+#sys.inputs.missing
+
+---
+// Inputs are read-only.
+// Error: 4-7 cannot mutate a constant: sys
+#{ sys.inputs.insert("draft", "false") }
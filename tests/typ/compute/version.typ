@@ -0,0 +1,55 @@
+// Test versions.
+// Ref: false
+
+---
+#let v = version(0, 8, 1)
+#test(v.major, 0)
+#test(v.minor, 8)
+#test(v.patch, 1)
+#test(version(3).patch, 0)
+#test(v.at(1), 8)
+#test(v.at(-1), 1)
+#test(repr(v), "0.8.1")
+#test(str(version(1, 10)), "1.10")
+#test(type(v), version)
+
+---
+// Missing trailing components count as zero.
+#test(version(1, 2), version(1, 2, 0))
+#test(version(1, 2, 0, 0) == version(1, 2), true)
+#test(version(1, 2) != version(1, 2, 1), true)
+#test(version(1, 2) < version(1, 2, 1), true)
+#test(version(1, 10) > version(1, 9, 9), true)
+#test(version(2) >= version(2, 0, 0), true)
+#test(version(0, 9) <= version(0, 8, 9), false)
+#test((version(2), version(1, 5), version(1, 10)).sorted(), (version(1, 5), version(1, 10), version(2)))
+
+---
+// The compiler's version.
+#test(type(sys.version), version)
+#test(sys.version > version(0, 0, 1), true)
+#test(sys.version.minor, sys.version.at(1))
+
+---
+// Error: 3-29 cannot compare version and string
+#(version(0, 8, 0) < "0.9.0")
+
+---
+// Error: 3-17 cannot compare version and integer
+#(version(1) > 0)
+
+---
+// Error: 9-11 version must have at least one component
+#version()
+
+---
+// Error: 13-15 number must be at least zero
+#version(1, -2)
+
+---
+// Error: 2-21 component index out of bounds (index: 3, len: 2)
+#version(1, 2).at(3)
+
+---
+// Error: 16-21 version does not have field "build"
+#version(1, 2).build
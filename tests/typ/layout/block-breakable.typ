@@ -0,0 +1,35 @@
+// Test that blocks whose content can't start in the current region move to
+// the next one as a whole.
+// Ref: false
+
+---
+// Test that a breakable block starting with an unbreakable one moves along
+// with the heading before it.
+#set page(height: 100pt, margin: 10pt)
+First <first>
+#v(40pt)
+= Title
+#block(inset: 5pt, fill: aqua, block(breakable: false, height: 40pt)[Kept]) <kept>
+
+#locate(loc => {
+  let page = label => query(label, loc).first().location().page()
+  test(page(<first>), 1)
+  test(page(heading), 2)
+  test(page(<kept>), 2)
+})
+
+---
+// Test that a breakable block with room for its first part stays.
+#set page(height: 100pt, margin: 10pt)
+First
+#v(20pt)
+#block(inset: 5pt, fill: aqua)[
+  #block(breakable: false, height: 20pt)[Stays] <stays>
+  #block(breakable: false, height: 40pt)[Moves] <moves>
+]
+
+#locate(loc => {
+  let page = label => query(label, loc).first().location().page()
+  test(page(<stays>), 1)
+  test(page(<moves>), 2)
+})
@@ -0,0 +1,29 @@
+// Test clipping to rounded corners.
+// Ref: false
+
+---
+// Test clipping an image to a box with rounded corners.
+#box(clip: true, radius: 4pt, image("/tiger.jpg", width: 60pt))
+#box(clip: true, radius: (top-left: 50%, bottom-right: 10pt), image("/tiger.jpg", width: 60pt))
+
+---
+// Test that the clip doesn't change the box's size.
+#style(styles => {
+  let clipped = measure(box(clip: true, radius: 5pt, rect(width: 30pt, height: 20pt)), styles)
+  test(clipped.width, 30pt)
+  test(clipped.height, 20pt)
+})
+
+---
+// Test nested and transformed clips.
+#box(clip: true, radius: 10pt, stroke: 1pt + black)[
+  #box(clip: true, radius: 50%, rect(width: 40pt, height: 40pt, fill: red))
+]
+#rotate(30deg, box(clip: true, radius: 6pt, rect(width: 30pt, height: 30pt, fill: blue)))
+
+---
+// Test clipping text in a block with rounded corners.
+#block(width: 5em, height: 2em, clip: true, radius: 1em, stroke: 1pt + black)[
+  But, soft! what light through yonder window breaks? It is the east, and Juliet
+  is the sun.
+]
@@ -0,0 +1,44 @@
+// Test balanced columns and weak column breaks.
+// Ref: false
+
+---
+// Test that balanced columns end at the same height.
+#set block(spacing: 0pt)
+#let body = range(4).map(_ => block(height: 10pt, width: 100%)).join()
+#style(styles => {
+  let ragged = measure(box(width: 100pt, columns(2, body)), styles)
+  let balanced = measure(box(width: 100pt, columns(2, balance: true, body)), styles)
+  test(ragged.height, 40pt)
+  test(balanced.height, 20pt)
+})
+
+---
+// Test that unbreakable blocks prevent a perfect balance.
+#set block(spacing: 0pt)
+#style(styles => {
+  let body = [
+    #block(height: 30pt, width: 100%, breakable: false)
+    #block(height: 10pt, width: 100%)
+  ]
+  let size = measure(box(width: 100pt, columns(2, balance: true, body)), styles)
+  test(size.height, 30pt)
+})
+
+---
+// Test balancing only the last page.
+#set page(height: 100pt, margin: 10pt, columns: 2)
+#set columns(balance: true)
+#set block(spacing: 0pt)
+#for _ in range(5) {
+  block(height: 30pt, width: 100%)
+}
+#locate(loc => test(loc.page(), 2))
+
+---
+// Test that a weak column break in an empty column is ignored.
+#set page(height: 60pt, width: 100pt, margin: 0pt, columns: 2)
+#colbreak(weak: true)
+#locate(loc => test(loc.position().x, 0pt))
+A
+#colbreak(weak: true)
+#locate(loc => test(loc.position().x > 0pt, true))
@@ -0,0 +1,89 @@
+// Test configurable widow and orphan prevention and sticky headings.
+// Ref: false
+
+---
+// Test that more than two lines move to the next page together.
+#set page(height: 100pt, margin: 10pt)
+#set text(top-edge: 10pt, bottom-edge: 0pt)
+#set par(leading: 10pt, orphans: 3)
+#v(35pt)
+#locate(loc => test(loc.page(), 2)) A \ B \ C \ D
+
+---
+// Test that two lines fit with the default.
+#set page(height: 100pt, margin: 10pt)
+#set text(top-edge: 10pt, bottom-edge: 0pt)
+#set par(leading: 10pt)
+#v(35pt)
+#locate(loc => test(loc.page(), 1)) A \ B \ C \ D
+
+---
+// Test that a single line may stay behind.
+#set page(height: 100pt, margin: 10pt)
+#set text(top-edge: 10pt, bottom-edge: 0pt)
+#set par(leading: 10pt, orphans: 1)
+#v(65pt)
+#locate(loc => test(loc.page(), 1)) A \ B \ C \ D
+
+---
+// Test that the last two lines stay together with the default.
+#set page(height: 100pt, margin: 10pt)
+#set text(top-edge: 10pt, bottom-edge: 0pt)
+#set par(leading: 10pt)
+#v(15pt)
+A \ B \ #locate(loc => test(loc.page(), 1)) C \ D \ E
+
+---
+// Test that the last three lines stay together.
+#set page(height: 100pt, margin: 10pt)
+#set text(top-edge: 10pt, bottom-edge: 0pt)
+#set par(leading: 10pt, widows: 3)
+#v(15pt)
+A \ B \ #locate(loc => test(loc.page(), 2)) C \ D \ E
+
+---
+// Test that a heading moves along with the start of its paragraph.
+#set page(height: 100pt, margin: 10pt)
+#set text(top-edge: 10pt, bottom-edge: 0pt)
+#set par(leading: 10pt)
+#v(45pt)
+= #locate(loc => test(loc.page(), 2)) Heading
+A \ B
+
+---
+// Test that a heading that isn't sticky stays behind.
+#set page(height: 100pt, margin: 10pt)
+#set text(top-edge: 10pt, bottom-edge: 0pt)
+#set par(leading: 10pt)
+#set heading(sticky: false)
+#v(45pt)
+= #locate(loc => test(loc.page(), 1)) Heading
+A \ B
+
+---
+// Test that a heading moves along with an unbreakable block.
+#set page(height: 100pt, margin: 10pt)
+#set text(top-edge: 10pt, bottom-edge: 0pt)
+#v(40pt)
+= #locate(loc => test(loc.page(), 2)) Heading
+#block(breakable: false, height: 30pt)
+
+---
+// Test that a heading moves along with a figure.
+#set page(height: 100pt, margin: 10pt)
+#set text(top-edge: 10pt, bottom-edge: 0pt)
+#v(40pt)
+= #locate(loc => test(loc.page(), 2)) Heading
+#figure(rect(height: 30pt))
+
+---
+// Test that a breakable block starts right after the heading.
+#set page(height: 100pt, margin: 10pt)
+#set text(top-edge: 10pt, bottom-edge: 0pt)
+#v(40pt)
+= #locate(loc => test(loc.page(), 1)) Heading
+#block(breakable: true, height: 30pt)
+
+---
+// Error: 20-21 number must be positive
+#set par(orphans: 0)
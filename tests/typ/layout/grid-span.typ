@@ -0,0 +1,119 @@
+// Test cells spanning multiple columns and rows.
+// Ref: false
+
+---
+// Test that a cell spanning columns takes up their width.
+#style(styles => {
+  let size = measure(grid(
+    columns: (20pt, 30pt),
+    grid.cell(colspan: 2, box(width: 100%, height: 10pt)),
+    box(width: 5pt, height: 5pt),
+  ), styles)
+  test(size.width, 50pt)
+  test(size.height, 15pt)
+})
+
+---
+// Test that auto columns grow to fit a spanning cell.
+#style(styles => {
+  let size = measure(grid(
+    columns: (auto, auto),
+    grid.cell(colspan: 2, box(width: 40pt)),
+    box(width: 10pt),
+    box(width: 10pt),
+  ), styles)
+  test(size.width, 40pt)
+})
+
+---
+// Test that auto rows grow to fit a spanning cell.
+#style(styles => {
+  let size = measure(grid(
+    columns: 2,
+    grid.cell(rowspan: 2, box(height: 30pt)),
+    box(height: 5pt),
+    box(height: 5pt),
+  ), styles)
+  test(size.height, 30pt)
+})
+
+---
+// Test that cells skip the slots covered by spanning cells.
+#style(styles => {
+  let size = measure(grid(
+    columns: 2,
+    grid.cell(rowspan: 2, box(width: 10pt, height: 10pt)),
+    box(width: 20pt, height: 10pt),
+    box(width: 20pt, height: 10pt),
+  ), styles)
+  test(size.width, 30pt)
+  test(size.height, 20pt)
+})
+
+---
+// Test that spanning cells cover the gutters between their tracks.
+#style(styles => {
+  let size = measure(grid(
+    columns: (10pt, 10pt),
+    gutter: 5pt,
+    grid.cell(colspan: 2, box(width: 100%, height: 5pt)),
+  ), styles)
+  test(size.width, 25pt)
+})
+
+---
+// Test spanning cells in tables, with gutter and right-to-left text.
+#set text(dir: rtl)
+#table(
+  columns: 3,
+  gutter: 2pt,
+  fill: (x, y) => if calc.even(x + y) { luma(230) },
+  table.cell(colspan: 2)[A], [B],
+  table.cell(rowspan: 2)[C], [D], [E],
+  [F], [G],
+)
+
+---
+// Test that rows spanned by a cell are kept together.
+#set page(height: 100pt, margin: 10pt)
+#block(height: 50pt)
+#table(
+  columns: 2,
+  table.cell(rowspan: 2, block(height: 40pt)), [A],
+  [B],
+)
+
+---
+// Error: 19-43 cell's colspan (3) exceeds the number of columns (2)
+#grid(columns: 2, grid.cell(colspan: 3)[A])
+
+---
+// Error: 25-50 cell's colspan (3) exceeds the number of columns (2)
+#table(columns: 2, [A], table.cell(colspan: 3)[B])
+
+---
+// Test that spanning cells create the rows they need.
+#style(styles => {
+  let size = measure(grid(
+    columns: 3,
+    grid.cell(rowspan: 3, box(height: 30pt)),
+  ), styles)
+  test(size.height, 30pt)
+})
+
+---
+// Test that cells in table headers and footers can span rows.
+#table(
+  columns: 2,
+  table.header(table.cell(rowspan: 2)[A], [B], [C]),
+  [D], [E],
+  table.footer(table.cell(rowspan: 2)[F], [G], [H]),
+)
+
+---
+// Error: 20-56 cell's rowspan (100000000000) exceeds the maximum of 4096 rows
+#table(columns: 1, table.cell(rowspan: 100000000000)[A])
+
+---
+// Error: 26-27 number must be positive
+#grid(grid.cell(colspan: 0)[A])
@@ -0,0 +1,58 @@
+// Test cycling list markers and the other options for nested lists.
+// Ref: false
+
+---
+#let a = box(width: 4pt, height: 4pt)
+#let b = box(width: 8pt, height: 4pt)
+#set list(marker: (a, b), indent: 0pt, body-indent: 0pt)
+
+#style(styles => {
+  let one = measure(list(box(width: 0pt)), styles)
+  let two = measure(list(list(box(width: 0pt))), styles)
+  let three = measure(list(list(list(box(width: 0pt)))), styles)
+  test(one.width, 4pt)
+  test(two.width, 12pt)
+  test(three.width, 16pt)
+})
+
+---
+// Test that the numbers of an enumeration are as wide as the widest one.
+#set enum(indent: 0pt, body-indent: 0pt)
+#style(styles => {
+  let short = measure(enum(start: 8, [A], [A]), styles)
+  let long = measure(enum(start: 9, [A], [A]), styles)
+  let ten = measure(enum(start: 10, [A]), styles)
+  test(long.width, ten.width)
+  test(short.width < long.width, true)
+})
+
+---
+// Test that `tight` and `spacing` only change the space between items.
+#set par(leading: 4pt)
+#set list(marker: [], indent: 0pt, body-indent: 0pt)
+#style(styles => {
+  let item = box(width: 10pt, height: 10pt)
+  test(measure(list(item, item), styles).height, 24pt)
+  test(measure(list(tight: false, spacing: 6pt, item, item), styles).height, 26pt)
+  test(measure(list(tight: false, spacing: 6pt, item), styles).height, 10pt)
+})
+
+---
+// Test the options together: full numbers for nested enumerations that are
+// aligned at their end, cycled markers, and term lists with a separator and
+// a hanging indent.
+#set enum(numbering: "1.a.i.", full: true, number-align: end)
+#set list(marker: ([•], [--], [∗]))
+#set terms(separator: [: ], hanging-indent: 2em)
+
+9. Nine
++ Ten
+  + Nested
+    + Deeper
+
+- One
+  - Two
+    - Three
+      - Four
+
+/ Term: A description that is long enough to wrap onto a second line.
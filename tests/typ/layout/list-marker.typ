@@ -7,7 +7,8 @@
 - B
 
 ---
-// Test that last item is repeated.
+// Test that markers are cycled.
+// Ref: false
 #set list(marker: ([--], [•]))
 - A
   - B
@@ -0,0 +1,45 @@
+// Test measuring content.
+// Ref: false
+
+---
+#style(styles => {
+  let (width, height) = measure(box(width: 20pt, height: 10pt), styles)
+  test(width, 20pt)
+  test(height, 10pt)
+})
+
+---
+// Test that the styles affect the measurement.
+#let body = [Hello]
+#style(styles => {
+  let small = measure(body, styles)
+  let big = measure(text(20pt, body), styles)
+  test(small.width < big.width, true)
+  test(small.height < big.height, true)
+})
+
+---
+// Test measuring in math.
+$ a + #style(styles => {
+  let (width,) = measure($a/b$, styles)
+  test(width > 0pt, true)
+}) $
+
+---
+// Test measuring within a show rule.
+#show heading: it => style(styles => {
+  let (width,) = measure(it.body, styles)
+  test(width > 0pt, true)
+  it.body
+})
+
+= Heading
+
+---
+// Test that measured content does not end up in the document.
+#let c = counter("measured")
+#style(styles => {
+  let size = measure(c.step(), styles)
+  test(size.width, 0pt)
+})
+#locate(loc => test(c.at(loc), (0,)))
@@ -0,0 +1,47 @@
+// Test that two-sided margins and overlays are resolved against the
+// physical page.
+// Ref: false
+
+---
+#let check(x) = locate(loc => test(loc.position().x, x))
+#set page(width: 100pt, height: 60pt, margin: (inside: 30pt, outside: 10pt))
+#check(30pt)
+#pagebreak()
+#check(10pt)
+#pagebreak()
+#check(30pt)
+
+// Changing the page properties starts a new page, which is even.
+#set page(margin: (inside: 20pt, outside: 5pt))
+#check(5pt)
+#pagebreak()
+#check(20pt)
+
+// With right binding, even pages are bound on the left.
+#set page(binding: right)
+#check(20pt)
+#pagebreak()
+#check(5pt)
+
+---
+// The foreground sees the page counter after updates on its page.
+#set page(height: 60pt, foreground: locate(loc => {
+  test(counter(page).at(loc), (loc.page() + 10,))
+}))
+#counter(page).update(11)
+A
+#pagebreak()
+B
+
+---
+// Test that the margins and the marginals alternate with the pages.
+#set page(
+  width: 80pt,
+  height: 50pt,
+  margin: (inside: 25pt, outside: 5pt, y: 15pt),
+  fill: luma(240),
+  header: locate(loc => align(if calc.odd(loc.page()) { right } else { left })[H]),
+  numbering: "1",
+)
+#set par(justify: true)
+#lorem(12)
@@ -21,3 +21,24 @@ Seventh
 First
 #pagebreak(to: "odd")
 Third
+
+---
+// Test that the blank page gets the blank header and the footer and that it is
+// counted.
+// Ref: false
+#set page(
+  width: 80pt,
+  height: 40pt,
+  header: [Head],
+  blank-header: none,
+  footer: counter(page).display(),
+)
+First
+#pagebreak(to: "odd")
+Third <third>
+
+#locate(loc => {
+  let third = query(<third>, loc).first().location()
+  test(third.page(), 3)
+  test(counter(page).at(third), (3,))
+})
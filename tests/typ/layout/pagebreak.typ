@@ -43,3 +43,16 @@ First
 #page[Second]
 #pagebreak(weak: true)
 #page[Third]
+
+---
+// Test that weak pagebreaks after a strong one don't produce more pages, even
+// with weak spacing in between.
+// Ref: false
+#set page(width: 80pt, height: 30pt)
+First
+#pagebreak()
+#pagebreak(weak: true)
+#v(1em, weak: true)
+#pagebreak(weak: true)
+Second
+#locate(loc => test(counter(page).final(loc), (2,)))
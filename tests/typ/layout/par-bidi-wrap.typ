@@ -0,0 +1,25 @@
+// Test line breaking of mixed-direction paragraphs.
+// Ref: false
+
+---
+// Test wrapping Hebrew text with embedded Latin text and numbers.
+#set page(width: 80pt)
+#set text(lang: "he", font: ("Linux Libertine", "Noto Serif Hebrew"))
+שלום עולם 123 456 hello world שלום עולם abc def מילים נוספות 2023
+
+---
+// Test wrapping Arabic text in a left-to-right paragraph.
+#set page(width: 80pt)
+#set text(font: ("PT Sans", "Noto Sans Arabic"))
+Rain المطر هو الحياة and life الحياة تمطر is rain.
+
+---
+// Test that the start of a right-to-left paragraph is on the right.
+#set page(width: 100pt, margin: 0pt)
+#set text(dir: rtl)
+#box(width: 10pt)#locate(loc => test(loc.position().x > 50pt, true))
+
+---
+// Test that the start of a left-to-right paragraph is on the left.
+#set page(width: 100pt, margin: 0pt)
+#box(width: 10pt)#locate(loc => test(loc.position().x < 50pt, true))
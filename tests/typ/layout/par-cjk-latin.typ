@@ -0,0 +1,45 @@
+// Test CJK line breaking rules and spacing between CJK and Latin text.
+// Ref: false
+
+---
+// Test that spacing is inserted between CJK and Latin text.
+#set text(lang: "zh", font: ("Linux Libertine", "Noto Serif CJK SC"))
+#style(styles => {
+  let width(body) = measure(body, styles).width
+  for body in ([中abc], [abc中], [中123]) {
+    let spaced = width(text(cjk-latin-spacing: auto, body))
+    test(spaced > width(body), true)
+  }
+})
+
+---
+// Test that no spacing is inserted next to an existing space.
+#set text(lang: "zh", font: ("Linux Libertine", "Noto Serif CJK SC"))
+#style(styles => {
+  let width(body) = measure(body, styles).width
+  test(width(text(cjk-latin-spacing: auto)[中 abc]), width[中 abc])
+})
+
+---
+// Test that lines don't start with a prolonged sound mark.
+#set text(lang: "ja", font: "Noto Serif CJK JP")
+#style(styles => {
+  let height(body) = measure(box(width: 2em, body), styles).height
+  test(height[中文ー中] > height[中文中文], true)
+})
+
+---
+// Test mixed Chinese and English paragraphs at a narrow measure.
+#set page(width: 80pt)
+#set par(justify: true)
+#set text(lang: "zh", font: ("Linux Libertine", "Noto Serif CJK SC"))
+#set text(cjk-latin-spacing: auto)
+在Typst中，使用`set`规则可以设置文本的字体。它支持English和中文混排，例如第2章、第3节。
+
+---
+// Test that full-width punctuation which can't start a line stays at the end
+// of the previous one, where it is compressed to half its width.
+#set page(width: 60pt)
+#set par(justify: true)
+#set text(lang: "zh", font: "Noto Serif CJK SC")
+中文排版，标点符号。「引号」和（括号）。
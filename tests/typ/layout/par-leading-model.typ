@@ -0,0 +1,40 @@
+// Test the baseline leading model and hanging indents.
+// Ref: false
+
+---
+#let approx(a, b) = calc.abs((a - b) / 1pt) < 0.001
+
+#style(styles => {
+  let one = measure([A], styles).height
+  let measure-par(grid-snap: false, body) = measure({
+    set par(leading: 20pt, leading-model: "baseline", grid-snap: grid-snap)
+    body
+  }, styles).height
+
+  // The baselines of two lines are exactly one leading apart.
+  test(approx(measure-par[A \ A], one + 20pt), true)
+
+  // Tall content pushes the next line as far as needed ...
+  test(approx(measure-par[A \ #box(height: 30pt)], one + 30pt), true)
+
+  // ... or by whole multiples of the leading when snapping to the grid.
+  test(approx(measure-par(grid-snap: true)[A \ #box(height: 30pt)], one + 40pt), true)
+  test(approx(measure-par(grid-snap: true)[A \ A], one + 20pt), true)
+})
+
+---
+// Test that the leading model is preserved across page breaks.
+#set page(height: 60pt)
+#set par(leading: 14pt, leading-model: "baseline", grid-snap: true)
+#lorem(20) $display(sum_(k=1)^n k)$ #lorem(10)
+
+---
+// Test hanging indents for bibliography-like paragraphs.
+#set par(hanging-indent: 1.5em)
+#lorem(15)
+
+Knuth, D. E. (1984). The TeXbook. Addison-Wesley.
+
+---
+// Error: 25-31 expected "gap" or "baseline"
+#set par(leading-model: "lead")
@@ -0,0 +1,62 @@
+// Test paragraph spacing and indenting all paragraphs.
+// Ref: false
+
+---
+#let approx(a, b) = calc.abs((a - b) / 1pt) < 0.001
+
+// Test that paragraph spacing only applies between paragraphs.
+#style(styles => {
+  let line = measure(block[A], styles).height
+  let pars = measure(block[
+    #set par(spacing: 0pt)
+    A
+
+    B
+  ], styles)
+  test(approx(pars.height, 2 * line), true)
+
+  let list = measure(block[
+    #set par(spacing: 0pt)
+    #set block(spacing: 10pt)
+    A
+    - B
+  ], styles)
+  test(approx(list.height, 2 * line + 10pt), true)
+})
+
+---
+// Test that `auto` spaces paragraphs like blocks.
+#style(styles => {
+  let line = measure(block[A], styles).height
+  let pars = measure(block[
+    #set par(spacing: 10pt)
+    #set par(spacing: auto)
+    #set block(spacing: 5pt)
+    A
+
+    B
+  ], styles)
+  test(pars.height, 2 * line + 5pt)
+})
+
+---
+// Test indenting the first paragraph, too.
+#style(styles => {
+  let plain = measure([A], styles)
+  let indented = measure([#set par(first-line-indent: 10pt); A], styles)
+  let all = measure([#set par(first-line-indent: (amount: 10pt, all: true)); A], styles)
+  test(indented.width, plain.width)
+  test(all.width, plain.width + 10pt)
+})
+
+---
+// Test indenting all paragraphs after a heading.
+#set par(first-line-indent: (amount: 1em, all: true), spacing: 0.65em)
+= Chapter
+The first paragraph is indented.
+
+And so is the second one.
+
+---
+// Error: 29-52 unexpected key "al", valid keys are "amount" and "all"
+#set par(first-line-indent: (amount: 1em, al: true))
@@ -0,0 +1,52 @@
+// Test floating placement.
+// Ref: false
+
+---
+// Test that floats move to the top and bottom of the page.
+#set page(height: 100pt, margin: 10pt)
+#locate(loc => test(loc.position().y > 30pt, true))
+#place(top, float: true, block(height: 20pt, locate(loc => {
+  test(loc.position().y, 10pt)
+})))
+#place(bottom, float: true, block(height: 20pt, locate(loc => {
+  test(loc.position().y, 70pt)
+})))
+
+---
+// Test that a float that doesn't fit moves to the next page, without
+// reordering with later floats.
+#set page(height: 100pt, margin: 10pt)
+#block(height: 40pt)
+#place(top, float: true, block(height: 50pt, locate(loc => {
+  test(loc.page(), 2)
+})))
+#place(top, float: true, block(height: 5pt, locate(loc => {
+  test(loc.page(), 2)
+})))
+#locate(loc => test(loc.page(), 1))
+
+---
+// Test flushing pending floats.
+#set page(height: 100pt, margin: 10pt)
+#block(height: 40pt)
+#place(float: true, block(height: 50pt))
+#place.flush()
+#locate(loc => test(loc.page(), 2))
+
+---
+// Test floating figures.
+#set page(height: 200pt, margin: 10pt)
+See @fig.
+#figure(
+  placement: bottom,
+  block(height: 40pt, width: 100%),
+  caption: [A figure],
+) <fig>
+#locate(loc => {
+  let fig = query(<fig>, loc).first()
+  test(fig.location().position().y > 100pt, true)
+})
+
+---
+// Error: 2-32 floating placement must be `auto`, `top`, or `bottom`
+#place(horizon, float: true)[A]
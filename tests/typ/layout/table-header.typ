@@ -0,0 +1,58 @@
+// Test repeated table headers and footers.
+// Ref: false
+
+---
+// Test that a long table repeats its header and footer on each page.
+#set page(height: 120pt, margin: 10pt)
+#table(
+  columns: 2,
+  fill: (x, y) => if calc.odd(y) { luma(230) },
+  table.header[*Name*][*Value*],
+  ..range(12).map(n => ([Item #n], [#(n * n)])).flatten(),
+  table.footer(table.cell(colspan: 2)[Continued]),
+)
+#locate(loc => test(counter(page).at(loc).first() > 1, true))
+
+---
+// Test that the header does not count towards the row sizes.
+#style(styles => {
+  let size = measure(table(
+    rows: 20pt,
+    inset: 0pt,
+    stroke: none,
+    table.header(box(height: 5pt)),
+    [A], [B],
+  ), styles)
+  test(size.height, 45pt)
+})
+
+---
+// Test a header and footer without any other cells.
+#table(table.header[A], table.footer[B])
+
+---
+#set page(height: 50pt, margin: 10pt)
+
+// Error: 2-47 header and footer do not fit into the region
+// Hint: 2-47 they are repeated in every region, so they must leave space for other rows
+#table(table.header(block(height: 60pt)), [A])
+
+---
+// Error: 25-40 table can only have one header
+#table(table.header[A], table.header[B])
+
+---
+// Error: 13-28 header must come before all other cells
+#table([A], table.header[B])
+
+---
+// Error: 25-28 footer must come after all other cells
+#table(table.footer[A], [B])
+
+---
+// Error: 21-36 cannot nest headers and footers
+#table(table.header(table.footer[A]))
+
+---
+// Error: 2-17 header can only be used as a direct child of a table
+#table.header[A]
@@ -0,0 +1,53 @@
+// Test the stroke constructor and per-cell table strokes.
+// Ref: false
+
+---
+// Test the stroke constructor.
+#test(type(stroke(paint: red)), "stroke")
+#test(repr(stroke(paint: red)), "rgb(\"#ff4136\")")
+#test(repr(stroke(paint: red, thickness: 2pt)), "2pt + rgb(\"#ff4136\")")
+#test(
+  repr(stroke(paint: blue, thickness: 1pt, dash: "dashed")),
+  "(paint: rgb(\"#0074d9\"), thickness: 1pt, dash: (array: (3pt, 3pt), phase: 0pt))",
+)
+#test(
+  repr(stroke(cap: "round", dash: (array: (2pt, "dot"), phase: 1pt))),
+  "(cap: \"round\", dash: (array: (2pt, \"dot\"), phase: 1pt))",
+)
+#line(length: 20pt, stroke: stroke(thickness: 2pt, join: "bevel", miter-limit: 2.0))
+
+---
+// Test per-side strokes.
+#table(
+  columns: 3,
+  stroke: (x: none, bottom: 0.5pt + black),
+  [A], [B], [C],
+  [D], [E], [F],
+)
+
+// Test strokes per column.
+#table(
+  columns: 2,
+  stroke: (red, (left: blue, right: 2pt)),
+  [A], [B],
+)
+
+// Test strokes per cell, with a spanning cell and empty slots.
+#table(
+  columns: 3,
+  stroke: (col, row) => if row == 0 {
+    (bottom: (paint: blue, thickness: 1pt, dash: "dashed"))
+  } else if calc.even(col) {
+    (rest: stroke(paint: rgb(255, 0, 0, 128)))
+  },
+  table.cell(colspan: 2)[A], [B],
+  [C],
+)
+
+// Test that set rules fold into the stroke.
+#set table(stroke: red)
+#table(stroke: 2pt, [A], [B])
+
+---
+// Error: 14-22 expected "butt", "round", or "square"
+#stroke(cap: "pointy")
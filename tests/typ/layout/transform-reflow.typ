@@ -0,0 +1,55 @@
+// Test transforms that affect the layout and skews.
+// Ref: false
+
+---
+#let approx(a, b) = calc.abs((a - b) / 1pt) < 0.001
+#let body = rect(width: 40pt, height: 10pt)
+
+// Without reflow, the layout doesn't see the transform.
+#style(styles => {
+  let size = measure(rotate(90deg, body), styles)
+  test(approx(size.width, 40pt) and approx(size.height, 10pt), true)
+})
+
+// With reflow, the bounding box of the transformed body is used.
+#style(styles => {
+  let size = measure(rotate(90deg, reflow: true, body), styles)
+  test(approx(size.width, 10pt) and approx(size.height, 40pt), true)
+
+  let size = measure(rotate(45deg, reflow: true, body), styles)
+  test(approx(size.width, 50pt / calc.sqrt(2)), true)
+  test(approx(size.height, 50pt / calc.sqrt(2)), true)
+
+  let size = measure(scale(x: 50%, y: -200%, reflow: true, body), styles)
+  test(approx(size.width, 20pt) and approx(size.height, 20pt), true)
+
+  let size = measure(skew(x: 45deg, reflow: true, body), styles)
+  test(approx(size.width, 50pt) and approx(size.height, 10pt), true)
+})
+
+// The origin and nested transforms work together with reflow.
+#style(styles => {
+  let size = measure(rotate(90deg, origin: top + left, reflow: true, body), styles)
+  test(approx(size.width, 10pt) and approx(size.height, 40pt), true)
+
+  let twice = rotate(90deg, reflow: true, rotate(90deg, reflow: true, body))
+  let size = measure(twice, styles)
+  test(approx(size.width, 40pt) and approx(size.height, 10pt), true)
+
+  let size = measure(scale(200%, reflow: true, skew(y: 45deg, reflow: true, body)), styles)
+  test(approx(size.width, 80pt) and approx(size.height, 100pt), true)
+})
+
+---
+// Test rotated table header cells with reflow.
+#table(
+  columns: 3,
+  ..([Name], [Height], [Weight]).map(rotate.with(-90deg, reflow: true)),
+  [A], [1.80m], [75kg],
+)
+
+---
+// Test skews and transformed links.
+#skew(x: -12deg)[This is some fake italic text.]
+#skew(y: 10deg, origin: top + left, reflow: true, link("https://typst.app")[Link])
+#rotate(90deg, reflow: true, link("https://typst.app")[Rotated link])
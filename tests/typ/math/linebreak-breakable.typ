@@ -0,0 +1,48 @@
+// Test line breaking of long equations.
+// Ref: false
+
+---
+// Test that equations are not broken by default.
+#style(styles => {
+  let eq = $ a + b + c + d + e + f + g + h + i + j + k = l $
+  let wide = measure(eq, styles)
+  let narrow = measure(box(width: 60pt, eq), styles)
+  test(narrow.height, wide.height)
+})
+
+---
+// Test that a wide display equation is broken at its operators.
+#set math.equation(breakable: true)
+#style(styles => {
+  let eq = $ a + b + c + d + e + f + g + h + i + j + k = l $
+  let wide = measure(eq, styles)
+  let narrow = measure(box(width: 60pt, eq), styles)
+  test(narrow.height > wide.height, true)
+})
+
+---
+// Test that a breakable inline equation can be broken across lines.
+#style(styles => {
+  let eq = $a + b + c + d + e + f + g + h + i + j + k = l$
+  let whole = measure(box(width: 60pt, eq), styles)
+  let broken = measure(box(width: 60pt, {
+    set math.equation(breakable: true)
+    eq
+  }), styles)
+  test(broken.height > whole.height, true)
+})
+
+---
+// Test breaking display and inline equations at relations and operators.
+#set page(width: 80pt)
+#set math.equation(breakable: true)
+$ a + b + c + d + e + f = g + h + i $
+Given $a + b + c + d = e + f + g$ here.
+
+---
+#set page(width: 80pt)
+#set math.equation(breakable: true)
+
+// Warning: 1-38 equation is too wide and cannot be broken
+// Hint: 1-38 equations can only be broken at top-level relations and binary operators
+$ (a + b + c + d + e + f) / (g + h) $
@@ -0,0 +1,52 @@
+// Test sub-numbering, suppression, and placement of equation numbers.
+// Ref: false
+
+---
+#let eq = counter(math.equation)
+#set math.equation(numbering: "(1a)")
+#show heading: it => {
+  eq.update(0)
+  it
+}
+
+// Test that a multi-line equation is counted once.
+$ a &= b + c \
+    &= d $
+#locate(loc => test(eq.at(loc), (1,)))
+
+// Test that a show-set rule can suppress the number.
+#show <plain>: set math.equation(numbering: none)
+$ e = f $ <plain>
+#locate(loc => test(eq.at(loc), (1,)))
+
+// Test that references still use the equation's own number.
+$ g = h $ <g>
+#locate(loc => test(eq.at(query(<g>, loc).first().location()), (2,)))
+
+// Test that headings can reset the counter.
+= Section
+$ i = j $
+#locate(loc => test(eq.at(loc), (1,)))
+
+---
+// Test that the number of a wide equation is moved below it.
+#style(styles => {
+  let body = $ a + b + c + d + e + f $
+  let plain = measure(box(width: 60pt, body), styles)
+  let numbered = measure(
+    box(width: 60pt, math.equation(block: true, numbering: "(1)", body.body)),
+    styles,
+  )
+  test(numbered.height > plain.height, true)
+})
+
+---
+// Test sub-numbering, suppression, and a number that moves below a wide
+// equation.
+#set page(width: 100pt)
+#set math.equation(numbering: "(1a)")
+#show <plain>: set math.equation(numbering: none)
+$ a &= b \
+    &= c $
+$ d = e $ <plain>
+$ f + g + h + i + j + k $
@@ -0,0 +1,56 @@
+// Test physical units in math.
+// Ref: false
+
+---
+// Test that a unit after a number is separated by a thin space.
+#style(styles => {
+  let number = measure($3$, styles)
+  let unit = measure($unit("m")$, styles)
+  let both = measure($3 unit("m")$, styles)
+  let tight = measure($3 #h(0pt) unit("m")$, styles)
+  test(both.width > number.width + unit.width, true)
+  test(both.width < number.width + unit.width + 2pt, true)
+  test(tight.width, number.width + unit.width)
+})
+
+---
+// Test that unit symbols are upright.
+#style(styles => {
+  let unit = measure($unit("m")$, styles)
+  test(unit.width, measure($upright(m)$, styles).width)
+})
+
+---
+// Test products, quotients, and exponents.
+#style(styles => {
+  let product = measure($unit("kg m")$, styles)
+  let starred = measure($unit("kg*m")$, styles)
+  test(product.width, starred.width)
+  let power = measure($unit("m^2")$, styles)
+  let inverse = measure($unit("m^-2")$, styles)
+  test(inverse.width > power.width, true)
+})
+
+---
+$unit("m/s^2") unit("kg·m^2/s^2") unit("µm")$
+
+---
+// Test units after numbers, in fractions, and in text.
+$ 3 unit("m") quad 9.81 unit("m/s^2") quad (2 unit("kg m"))/(4 unit("s^-2")) $
+The speed is $299792458 unit("m/s")$.
+
+---
+// Error: 7-9 expected unit symbol
+$unit("")$
+
+---
+// Error: 7-12 expected integer exponent in unit
+$unit("m^x")$
+
+---
+// Error: 7-14 unit symbol can only have one exponent
+$unit("m^2^3")$
+
+---
+// Error: 7-13 expected unit symbol, found `/`
+$unit("m//s")$
@@ -0,0 +1,21 @@
+// Test bibliographies with CSL styles.
+// Ref: false
+
+---
+#set page(width: 200pt)
+See @netwok, @arrgh[p. 5], and #cite("netwok", "quark").
+#bibliography("/works.bib", style: "/numeric.csl")
+
+---
+// Test disambiguation and hanging indent.
+#set page(width: 200pt)
+@arrgh and @quark agree with @netwok[ch. 2].
+#bibliography("/works.bib", title: none, style: "/author-date.csl")
+
+---
+// Error: 36-49 file not found (searched at typ/meta/missing.csl)
+#bibliography("/works.bib", style: "missing.csl")
+
+---
+// Error: 36-46 CSL style must contain a `citation` element
+#bibliography("/works.bib", style: "/bad.csl")
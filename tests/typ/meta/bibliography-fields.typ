@@ -0,0 +1,20 @@
+// Test the BibLaTeX fields that are carried over into references.
+// Ref: false
+
+---
+#set page(width: 200pt)
+@attention, @transformers, @dataset, @website
+
+// Warning: 15-28 /fields.bib contains unsupported fields: `mood`
+// Hint: 15-28 these fields are ignored
+#bibliography("/fields.bib")
+
+---
+// Test that accessed dates are formatted for the text language.
+#set page(width: 200pt)
+#set text(lang: "de")
+@website
+
+// Warning: 15-28 /fields.bib contains unsupported fields: `mood`
+// Hint: 15-28 these fields are ignored
+#bibliography("/fields.bib")
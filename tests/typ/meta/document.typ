@@ -16,6 +16,16 @@ What's up?
 #set document(author: (123,))
 What's up?
 
+---
+// Keywords and dates are fine, too.
+// Ref: false
+#set document(keywords: ("a", "b"), date: datetime(year: 2023, month: 6, day: 19))
+#set document(keywords: "c", date: none)
+
+---
+// Error: 21-27 expected datetime, none, or auto, found string
+#set document(date: "2023")
+
 ---
 Hello
 
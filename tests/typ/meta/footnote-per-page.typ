@@ -0,0 +1,13 @@
+// Test per-page footnote numbering.
+// Ref: false
+
+---
+#set page(height: 100pt)
+#set footnote(per-page: true)
+
+A #footnote[One] <a>
+B #footnote[Two]
+#pagebreak()
+C #footnote[Three]
+Referencing @a.
+#locate(loc => test(counter(footnote).at(loc), (3,)))
@@ -0,0 +1,46 @@
+// Test additional numbering kinds and how patterns compose.
+// Ref: false
+
+---
+// Test Greek letters, circled numbers, and Chinese numerals.
+#test(numbering("α", 1), "α")
+#test(numbering("Α.α", 2, 25), "Β.αα")
+#test(numbering("①", 1), "①")
+#test(numbering("①", 21), "㉑")
+#test(numbering("①", 50), "㊿")
+#test(numbering("①", 51), "51")
+#test(numbering("一", 3), "三")
+#test(numbering("一", 300), "三百")
+
+---
+// Test that multiple counting symbols zip the levels and that the suffix is
+// only added once.
+#test(numbering("1.1.a)", 1, 2, 3), "1.2.c)")
+#test(numbering("1.1.a)", 1), "1)")
+#test(numbering("1.1.a)", 1, 2, 3, 4), "1.2.c.d)")
+#test(numbering("*", 7), "**")
+
+---
+// Test that closures receive all counter levels.
+#set heading(numbering: (..nums) => nums.pos().map(str).join("-") + ":")
+= One
+== Two
+#locate(loc => {
+  let levels = counter(heading).at(loc)
+  test(levels, (1, 1))
+  test(numbering((..nums) => nums.pos().len(), ..levels), 2)
+})
+
+---
+// Test the new kinds in lists and headings.
+#set enum(numbering: "①")
++ First
++ Second
+
+#set enum(numbering: "一、")
++ 甲
++ 乙
+
+#set heading(numbering: "Α.α")
+= Alpha
+== Beta
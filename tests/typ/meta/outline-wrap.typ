@@ -0,0 +1,25 @@
+// Test wrapping of long outline entries.
+// Ref: false
+
+---
+#set page(width: 120pt)
+#set heading(numbering: "1.1.")
+#outline(indent: auto)
+
+= A heading with a title that is much too long for one line
+== Another heading that also needs more than a single line
+= Short
+
+---
+#set page(width: 120pt)
+#show outline.entry: it => {
+  test(it.level, 1)
+  test(it.element.func(), figure)
+  it
+}
+
+#outline(target: figure, indent: 1em)
+#figure(
+  rect(),
+  caption: [A caption that is long enough that it has to wrap],
+)
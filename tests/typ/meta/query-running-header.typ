@@ -0,0 +1,47 @@
+// Test running headers with the current chapter and the final page count.
+// Ref: false
+
+---
+#let chapter(loc) = {
+  let chapters = heading.where(level: 1)
+  let here = query(chapters, loc).filter(it => it.location().page() == loc.page())
+  let before = query(chapters.before(loc), loc)
+  if here != () {
+    here.first().body
+  } else if before != () {
+    before.last().body
+  }
+}
+
+#let expected = ([Intro], [Intro], [Middle], [End])
+
+#set page(
+  height: 100pt,
+  margin: (y: 30pt, x: 20pt),
+  header: locate(loc => {
+    test(chapter(loc), expected.at(loc.page() - 1))
+    chapter(loc)
+  }),
+  footer: locate(loc => {
+    test(counter(page).final(loc), (4,))
+    [Page #counter(page).at(loc).first() of #counter(page).final(loc).first()]
+  }),
+)
+
+= Intro
+#lorem(3)
+#pagebreak()
+#lorem(5)
+#pagebreak()
+= Middle
+#pagebreak()
+= End
+
+---
+// Warning: 4:12-4:18 layout did not converge within 5 attempts
+// Hint: 4:12-4:18 check if any states or queries are updating themselves
+= Real
+#locate(loc => {
+  let count = query(heading, loc).len()
+  count * [= Fake]
+})
@@ -0,0 +1,48 @@
+// Test page references and reference fields.
+// Ref: false
+
+---
+#set page(height: 80pt, numbering: "i")
+#set heading(numbering: "1.")
+
+= Introduction <intro>
+#pagebreak()
+See @intro on #ref(<intro>, form: "page").
+#ref(<intro>, form: "page", supplement: [p.])
+#ref(<intro>, form: "page", supplement: none)
+
+// Page references work with any labelled element.
+#text[Somewhere] <somewhere>
+#ref(<somewhere>, form: "page")
+
+---
+// Test access to the fields in a show rule.
+#set heading(numbering: "1.")
+#show ref: it => {
+  test(it.target, <intro>)
+  test(it.form, "page")
+  it
+}
+
+= Introduction <intro>
+#ref(<intro>, form: "page")
+
+---
+#set figure(supplement: [Fig.])
+#figure([A], caption: [B]) <fig>
+#show ref: it => {
+  let el = it.element
+  if el != none and el.func() == figure {
+    test(el.supplement, [Fig.])
+  }
+  it
+}
+@fig
+
+---
+// Error: 21-27 expected "normal" or "page"
+#ref(<intro>, form: "line")
+
+---
+// Error: 2-26 label does not exist in the document
+#ref(<foo>, form: "page")
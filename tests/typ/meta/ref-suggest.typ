@@ -0,0 +1,25 @@
+// Test suggestions for references to labels that don't exist.
+// Ref: false
+
+---
+= Introduction <intro>
+= Setup <setup>
+
+// Error: 1-6 label does not exist in the document
+// Hint: 1-6 did you mean `@intro`?
+@intr
+
+---
+= Setup <setup>
+#figure([A], caption: [B]) <results>
+
+// Error: 1-8 label does not exist in the document
+// Hint: 1-8 did you mean `@results`?
+@reslts
+
+---
+= Introduction <intro>
+
+// Nothing is similar enough.
+// Error: 1-12 label does not exist in the document
+@conclusion
@@ -0,0 +1,58 @@
+// Test looking up styles.
+// Ref: false
+
+---
+// Test default values.
+#style(styles => {
+  test(styles.get(text, "size"), 11pt)
+  test(styles.get(text, "fill"), black)
+  test(styles.get(page, "width"), 210mm)
+})
+
+---
+// Test values from set rules.
+#set text(14pt, fill: red)
+#set page(paper: "a5")
+#style(styles => {
+  test(styles.get(text, "size"), 14pt)
+  test(styles.get(text, "fill"), red)
+  test(styles.get(page, "width") < 15cm, true)
+})
+
+---
+// Test that the innermost set rule wins.
+#set text(size: 8pt)
+#[
+  #set text(size: 12pt)
+  #style(styles => test(styles.get(text, "size"), 12pt))
+]
+#style(styles => test(styles.get(text, "size"), 8pt))
+
+---
+// Test that the styles of a show rule are visible.
+#show heading: it => {
+  set text(fill: blue)
+  style(styles => test(styles.get(text, "fill"), blue))
+}
+
+= Heading
+
+---
+// Test that relative lengths are not resolved.
+#set text(2em)
+#style(styles => test(styles.get(text, "size"), 2em))
+
+---
+// Error: 18-42 text does not have a settable property `nope`
+#style(styles => styles.get(text, "nope"))
+
+---
+// Error: 29-37 expected element function
+#style(styles => styles.get(calc.abs, "size"))
+
+---
+// Test that endless nesting terminates.
+// Error: 17-27 maximum style nesting depth exceeded
+// Hint: 17-27 the function may return content that calls `style` again
+#let f() = style(s => f())
+#f()
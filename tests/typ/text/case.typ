@@ -1,4 +1,4 @@
-// Test the `upper` and `lower` functions.
+// Test the `upper`, `lower`, and `title-case` functions.
 // Ref: false
 
 ---
@@ -10,3 +10,39 @@
 ---
 // Error: 8-9 expected string or content, found integer
 #upper(1)
+
+---
+// Test locale-specific casing.
+#test(upper("istanbul", lang: "tr"), "İSTANBUL")
+#test(lower("KIŞ", lang: "tr"), "kış")
+#test(lower("KIŞ"), "kiş")
+#test(lower("ΟΔΟΣ ΣΑΣ"), "οδος σας")
+
+---
+// Test title case.
+#test(title-case("the lord of the rings"), "The Lord of the Rings")
+#test(title-case("a tale of TWO cities"), "A Tale of TWO Cities")
+#test(title-case("the rise OF NATO"), "The Rise OF NATO")
+#test(title-case("1. the well-known story"), "1. The Well-Known Story")
+#test(title-case("le rouge et le noir", lang: "fr"), "Le Rouge et le Noir")
+#test(title-case("istanbul and izmir", lang: "tr"), "İstanbul And İzmir")
+
+---
+// Test custom minor words.
+#test(title-case(minor-words: ("vs",), "batman vs superman of gotham"), "Batman vs Superman Of Gotham")
+#test(title-case(minor-words: (fr: ("et",)), "war and peace"), "War and Peace")
+#test(title-case(minor-words: (fr: ("et",)), "la guerre et la paix", lang: "fr"), "La Guerre et La Paix")
+
+---
+// Test that title case continues across elements and skips the numbering and
+// that raw text, equations, and references keep their case.
+#set heading(numbering: "1.")
+#show heading: title-case
+= the lord of _the_ rings <intro>
+#upper[see `code`, $"ab"$ and @intro]
+
+#text(lang: "tr", upper[istanbul])
+
+---
+// Error: 26-39 expected two or three letter language code (ISO 639-1/2/3)
+#title-case(minor-words: (english: ()), "x")
@@ -0,0 +1,40 @@
+// Test background decorations and highlighting.
+// Ref: false
+
+---
+// Test the defaults of the fields.
+#show highlight: it => {
+  test(it.fill, rgb("#fffd11"))
+  test(it.top-edge, "ascender")
+  test(it.bottom-edge, "descender")
+  test(it.extent, 0pt)
+  it
+}
+
+#highlight[Highlighted]
+
+---
+// Test lines in front of and behind the text.
+#set underline(stroke: (thickness: 1em, paint: maroon, cap: "round"))
+#underline(background: true)[Behind] \
+#underline(background: false)[In front] \
+#strike(background: true, stroke: 6pt + aqua)[Behind] \
+#overline(background: true, evade: false)[Behind]
+
+---
+// Test custom edges and extents.
+#highlight(top-edge: "x-height", bottom-edge: "baseline")[ypx]
+#highlight(top-edge: 1em, bottom-edge: -0.5em, extent: 2pt)[ypx]
+#highlight(fill: aqua)[#underline(evade: true)[gypsy queen]]
+
+---
+// Test that each line gets its own segment, also across soft hyphens.
+#set page(width: 80pt)
+#set text(hyphenate: true)
+#highlight[Hello world, this is a long highlighted text.]
+#underline[Hyphen\u{ad}ation of the beauti\u{ad}ful words]
+#strike(background: true)[Super\u{ad}cali\u{ad}fragilistic]
+
+---
+// Error: 19-21 expected color, gradient, or pattern, found integer
+#highlight(fill: 12)[Hi]
@@ -41,3 +41,15 @@ World
 // Error: 11-12 unclosed delimiter
 // Error: 3-4 unclosed delimiter
 #[_Cannot *be interleaved]
+
+---
+// Ref: false
+// Warning: 1-3 no text within stars
+// Hint: 1-3 using multiple consecutive stars (e.g. **) has no additional effect
+**
+
+---
+// Ref: false
+// Warning: 1-3 no text within underscores
+// Hint: 1-3 using multiple consecutive underscores (e.g. __) has no additional effect
+__
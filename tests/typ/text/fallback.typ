@@ -17,4 +17,7 @@ Aب😀🏞سمB
 01️⃣2
 
 // Tofus are rendered with the first font.
+// Warning: 1-6 no font contains the character U+12F2 (tried "linux libertine")
+// Warning: 1-6 no font contains the character U+121E (tried "linux libertine")
+// Hint: 1-6 try installing a font that contains this character
 A🐈ዲሞB
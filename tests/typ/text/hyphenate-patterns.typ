@@ -0,0 +1,51 @@
+// Test custom hyphenation patterns.
+// Ref: false
+
+---
+#let patterns = "
+% Break before every `b`.
+\\patterns{ 1b }
+\\hyphenation{ aaa-aaaa }
+"
+
+#set text(lang: "xx", hyphenate: true)
+#style(styles => {
+  let plain = measure(box(width: 1pt)[aaabaaa], styles).height
+  let hyphenated = measure(
+    box(width: 1pt, text(hyphenation-patterns: patterns)[aaabaaa]),
+    styles,
+  ).height
+  let exception = measure(
+    box(width: 1pt, text(hyphenation-patterns: patterns)[aaaaaaa]),
+    styles,
+  ).height
+  test(hyphenated > plain, true)
+  test(exception, hyphenated)
+})
+
+---
+// Test that the custom patterns and exceptions are used for breaking lines.
+#set page(width: 40pt)
+#set text(
+  lang: "xx",
+  hyphenate: true,
+  hyphenation-patterns: "\\patterns{ 1b }\\hyphenation{ aaa-aaaa }",
+)
+aaabaaa aaaaaaa
+
+---
+// Test that built-in patterns take precedence.
+#set text(lang: "en", hyphenate: true, hyphenation-patterns: "\\patterns{1b}")
+#style(styles => {
+  let custom = measure(box(width: 1pt)[abbbbbbc], styles)
+  let builtin = measure(box(width: 1pt, text(hyphenation-patterns: none)[abbbbbbc]), styles)
+  test(custom.height, builtin.height)
+})
+
+---
+// Error: 33-53 hyphenation patterns must contain a `\patterns` group
+#set text(hyphenation-patterns: "\\hyphenation{a-b}")
+
+---
+// Error: 33-50 invalid hyphenation pattern `a!b`
+#set text(hyphenation-patterns: "\\patterns{a!b}")
@@ -0,0 +1,34 @@
+// Test configurable character protrusion.
+// Ref: false
+
+---
+// Test that custom factors round-trip through the style chain.
+#set text(overhang: ("-": (0%, 100%), "»": (50%, 0%)))
+#style(styles => {
+  let overhang = styles.get(text, "overhang")
+  test(overhang.at("-"), (0%, 100%))
+  test(overhang.at("»"), (50%, 0%))
+})
+
+---
+// Test that protrusion works for both directions.
+#set page(width: 130pt, margin: 15pt)
+#set par(justify: true)
+#set text(overhang: ("“": (100%, 0%), "”": (0%, 100%)))
+“This is a quote that is long enough to get wrapped, hopefully.”
+
+#set text(lang: "ar", font: "Noto Sans Arabic")
+مرحبًا بكم في هذا النص، الذي يجب أن يكون طويلًا بما فيه الكفاية.
+
+---
+// Test that protrusion can be disabled.
+#set text(overhang: false)
+#style(styles => test(styles.get(text, "overhang"), false))
+
+---
+// Error: 21-38 overhang keys must be single characters
+#set text(overhang: ("ab": (0%, 50%)))
+
+---
+// Error: 21-34 overhang must be specified as an array of two ratios
+#set text(overhang: ("-": (50%,)))
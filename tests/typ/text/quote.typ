@@ -0,0 +1,43 @@
+// Test the quote element.
+// Ref: false
+
+---
+// Test the fields.
+#show quote: it => {
+  test(it.block, true)
+  test(it.quotes, auto)
+  test(it.attribution, [Plato])
+  test(it.body, [I know that I know nothing.])
+}
+
+#quote(block: true, attribution: [Plato])[I know that I know nothing.]
+
+---
+// Test that a label attribution is kept as a label.
+#show quote: it => test(it.attribution, <netwok>)
+#quote(attribution: <netwok>)[A network]
+
+---
+// Test inline, block, and nested quotes.
+#quote[The #quote[nested] quote.]
+
+#quote(quotes: false)[Unquoted.]
+
+#set quote(block: true)
+#quote(attribution: [Someone])[A block quote.]
+#quote(quotes: true)[A quoted block quote.]
+
+---
+// Test citing a bibliography entry as the attribution.
+#quote(attribution: <netwok>)[An inline quote]
+#quote(block: true, attribution: <tolkien54>)[You cannot pass.]
+#bibliography("/works.bib")
+
+---
+// Error: 2-37 bibliography does not contain this key
+#quote(attribution: <nope>)[Nothing]
+#bibliography("/works.bib")
+
+---
+// Error: 21-26 expected label, content, or none, found integer
+#quote(attribution: 12345)[Nothing]
@@ -0,0 +1,53 @@
+// Test nested and custom smart quotes.
+// Ref: false
+
+---
+#let same(a, b) = style(styles => {
+  test(measure(a, styles).width, measure(b, styles).width)
+})
+
+// Test that nested quotes of the same kind alternate.
+#same(["a "b" c"], [“a ‘b’ c”])
+#same(['a 'b' c'], [‘a “b” c’])
+#same(["a "b "c" b" a"], [“a ‘b “c” b’ a”])
+
+// Test the secondary style of other languages.
+#same(text(lang: "de")["a "b" c"], text(lang: "de")[„a ‚b‘ c“])
+#same(text(lang: "de", region: "CH")["a "b" c"], text(lang: "de", region: "CH")[«a ‹b› c»])
+
+// Test apostrophes inside and at the end of words.
+#same(["the students' books"], [“the students’ books”])
+#same(["don't"], [“don’t”])
+
+// Test quotes next to punctuation and math.
+#same(["Hi"--- she said], [“Hi”--- she said])
+#same(["$x$" is], [“$x$” is])
+
+// Test custom quotes.
+#set smartquote(quotes: (double: ("»", "«")))
+#same(["a 'b' c"], [»a ‘b’ c«])
+
+// Test that disabling smart quotes for a span works.
+#same(
+  ["a #[#set smartquote(enabled: false); "] b"],
+  [“a #[#set smartquote(enabled: false); "] b”],
+)
+
+---
+// Test nested quotes in different languages and with custom quotes.
+"a "b "c" b" a" and 'a 'b' a'
+
+#text(lang: "de")["a "b" c"] \
+#text(lang: "de", region: "CH")["a "b" c"] \
+#text(lang: "fr")["a "b" c"]
+
+#set smartquote(quotes: (double: ("»", "«"), single: ("›", "‹")))
+"a "b" c" and the students' books
+
+---
+// Error: 25-41 expected an array of an opening and a closing quote
+#set smartquote(quotes: (single: ("'",)))
+
+---
+// Error: 25-45 unexpected key "triple", valid keys are "single" and "double"
+#set smartquote(quotes: (triple: ("a", "b")))
@@ -0,0 +1,93 @@
+// Test custom syntaxes and themes, and raw lines.
+// Ref: false
+
+---
+// Test a custom syntax definition.
+#set raw(syntaxes: ("/SExpressions.sublime-syntax",))
+
+```sexp
+(defun square (x)
+  ; Multiply x with itself.
+  (* x x))
+```
+
+---
+// Test a custom theme.
+#set raw(theme: "/halcyon.tmTheme")
+
+```typ
+= Chapter 1
+#let hi = "Hello World"
+```
+
+```rust
+fn main() {}
+```
+
+---
+// Test returning to the built-in theme.
+#set raw(theme: "/halcyon.tmTheme")
+#set raw(theme: none)
+```py
+print("Hi")
+```
+
+---
+// Test the fields of raw lines.
+#show raw.line: it => {
+  test(it.count, 3)
+  test(it.text, ("one", "two", "three").at(it.number - 1))
+  test(it.highlighted, it.number == 2)
+  it
+}
+
+#raw(block: true, highlight-lines: 2, "one\ntwo\nthree")
+
+---
+// Test highlighting several lines.
+#show raw.line: it => {
+  test(it.highlighted, it.number in (1, 3, 4))
+  it
+}
+
+#set raw(highlight-lines: (1, range(3, 5)))
+
+```rust
+let a = 1;
+let b = 2;
+let c = 3;
+let d = 4;
+let e = 5;
+```
+
+---
+// A trailing newline doesn't create another line.
+#show raw.line: it => {
+  test(it.count, 1)
+  it
+}
+
+#raw("single line\n")
+
+---
+// Warning: 1-3:4 unknown language `unknown`
+// Hint: 1-3:4 the text is displayed without syntax highlighting
+```unknown
+Hi
+```
+
+---
+// Error: 20-45 file not found (searched at /missing.sublime-syntax)
+#set raw(syntaxes: "/missing.sublime-syntax")
+
+---
+// Error: 20-30 file is not valid utf-8
+#set raw(syntaxes: "/bad.txt")
+
+---
+// Error: 17-35 file not found (searched at /missing.tmTheme)
+#set raw(theme: "/missing.tmTheme")
+
+---
+// Error: 27-28 number must be positive
+#set raw(highlight-lines: 0)
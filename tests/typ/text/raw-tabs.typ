@@ -0,0 +1,58 @@
+// Test tab expansion and wrapping in raw blocks.
+// Ref: false
+
+---
+// Tabs advance to the next tab stop.
+#let lines = ("a   b", "    c", "abc d", "        e")
+#show raw.line: it => {
+  test(it.text, lines.at(it.number - 1))
+  it
+}
+
+#raw(block: true, tab-size: 4, "a\tb\n\tc\nabc\td\n\t\te")
+
+---
+// Test the default tab size.
+#show raw.line: it => {
+  test(it.text, "  x = 1")
+  it
+}
+
+```py
+	x = 1
+```
+
+---
+// Test that tabs inside highlighted tokens are expanded.
+#show raw.line: it => {
+  test(it.text, ("let s = \"a  b\";", "if x {", "    y", "}").at(it.number - 1))
+  it
+}
+
+#raw(block: true, lang: "rust", tab-size: 4, "let s = \"a\tb\";\nif x {\n\ty\n}")
+
+---
+// Test wrapping with a hanging indent.
+#set raw(wrap: true)
+#set block(width: 120pt)
+
+```py
+def greet(name):
+    print("Hello, " + name + "! How are you doing today?")
+
+    return name
+```
+
+---
+// Test that the hanging indent doesn't change short lines.
+#let plain = raw(block: true, "def f():\n    return 1")
+#let wrapped = raw(block: true, wrap: true, "def f():\n    return 1")
+#style(styles => {
+  let a = measure(plain, styles)
+  let b = measure(wrapped, styles)
+  test(a.width, b.width)
+})
+
+---
+// Error: 20-21 number must be positive
+#set raw(tab-size: 0)
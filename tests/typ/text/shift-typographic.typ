@@ -0,0 +1,28 @@
+// Test typographic sub- and superscripts in different contexts.
+// Ref: false
+
+---
+// Characters without dedicated glyphs make the whole script synthesized.
+H#sub[2]O, x#super[2], x#super[2a], x#sub[2 + x]
+
+---
+// Test inside of links, headings, and table cells.
+#link("https://typst.app")[x#super[2] and H#sub[2]O]
+
+= H#sub[2]O and x#super[n]
+
+#table(
+  columns: 2,
+  [x#super[1]], [x#sub[#text(red)[1]]],
+  [x#super[st]], [x#sub[ij]],
+)
+
+---
+// The synthesized script does not change the line height.
+#set super(typographic: false)
+#set sub(typographic: false)
+#style(styles => {
+  let plain = measure([x], styles)
+  test(measure([x#super[2]], styles).height, plain.height)
+  test(measure([x#sub[2]], styles).height, plain.height)
+})
@@ -0,0 +1,21 @@
+// Test synthesized small capitals.
+// Ref: false
+
+---
+// Test that small caps are synthesized for fonts without `smcp`.
+#set text(font: "IBM Plex Sans")
+#style(styles => {
+  let real = measure(upper[abc], styles)
+  // Warning: 39-42 font "IBM Plex Sans" does not support small capitals
+  // Hint: 39-42 small capitals are synthesized by scaling down uppercase letters
+  // Hint: 39-42 try using a font that supports the `smcp` feature
+  let synthesized = measure(smallcaps[abc], styles)
+  test(synthesized.width < real.width, true)
+})
+
+---
+// Warning: 12-17 font "IBM Plex Sans" does not support small capitals
+// Hint: 12-17 small capitals are synthesized by scaling down uppercase letters
+// Hint: 12-17 try using a font that supports the `smcp` feature
+#set text(font: "IBM Plex Sans")
+#smallcaps[Hello]
@@ -0,0 +1,28 @@
+// Test tracking together with punctuation and justification.
+// Ref: false
+
+---
+#let approx(a, b) = calc.abs((a - b) / 1pt) < 0.001
+
+// Test that punctuation can be excluded from tracking.
+#style(styles => {
+  let tracked = measure(text(tracking: 2pt)[a.], styles)
+  let untracked = measure(text(tracking: 2pt, track-punctuation: false)[a.], styles)
+  test(approx(tracked.width - untracked.width, 2pt), true)
+})
+
+// Test that word spacing scales the space.
+#style(styles => {
+  let normal = measure([a b], styles)
+  let wide = measure(text(spacing: 200%)[a b], styles)
+  let space = normal.width - measure([ab], styles).width
+  test(approx(wide.width - normal.width, space), true)
+})
+
+---
+// Test that justification still fills the line with tracking applied.
+#set page(width: 120pt, height: auto, margin: 10pt)
+#set par(justify: true)
+#set text(tracking: 1pt)
+A few words#locate(loc => test(calc.abs((loc.position().x - 110pt) / 1pt) < 0.01, true))#linebreak(justify: true)
+Tracked, spaced.
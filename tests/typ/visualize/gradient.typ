@@ -0,0 +1,57 @@
+// Test gradients.
+// Ref: false
+
+---
+// Test construction and repr.
+#test(type(gradient(red, blue)), "gradient")
+#test(gradient(red, blue), gradient.linear(red, blue))
+#test(
+  repr(gradient(red, blue)),
+  "gradient.linear((rgb(\"#ff4136\"), 0%), (rgb(\"#0074d9\"), 100%), angle: 0deg)",
+)
+#test(
+  repr(gradient.linear(red, (green, 20%), blue, angle: 90deg, space: "oklab")),
+  "gradient.linear((rgb(\"#ff4136\"), 0%), (rgb(\"#2ecc40\"), 20%), (rgb(\"#0074d9\"), 100%), angle: 90deg, space: \"oklab\")",
+)
+#test(
+  repr(gradient.radial(white, black, center: (20%, 30%), relative: "parent")),
+  "gradient.radial((rgb(\"#ffffff\"), 0%), (rgb(\"#000000\"), 100%), center: (20%, 30%), radius: 100%, relative: \"parent\")",
+)
+
+---
+// Test that stops without offsets are spread evenly.
+#test(
+  gradient(red, green, (blue, 80%), white),
+  gradient((red, 0%), (green, 40%), (blue, 80%), (white, 100%)),
+)
+
+---
+// Test gradients as paints.
+#rect(fill: gradient(red, blue), stroke: gradient(blue, red) + 2pt)
+#rect(stroke: (paint: gradient.radial(red, blue), thickness: 2pt))
+#text(fill: gradient.linear(red, blue, relative: "self"))[Text]
+#line(length: 20pt, stroke: gradient(red, blue))
+
+---
+// Error: 2-15 a gradient must have at least two stops
+#gradient(red)
+
+---
+// Error: 2-29 offset must be between 0% and 100%
+#gradient(red, (blue, 120%))
+
+---
+// Error: 2-35 offsets must be in ascending order
+#gradient((red, 50%), (blue, 20%))
+
+---
+// Error: 16-32 a color stop must be a color or an array of a color and an offset
+#gradient(red, (blue, 10%, 20%))
+
+---
+// Error: 29-34 expected "srgb", "linear-rgb", "oklab", or "cmyk"
+#gradient(red, blue, space: "hsl")
+
+---
+// Error: 2-40 radius must be positive
+#gradient.radial(red, blue, radius: 0%)
@@ -0,0 +1,39 @@
+// Test image formats, pages, and natural sizes.
+// Ref: false
+
+---
+// Test overriding the format.
+#image("/tiger.jpg", format: "jpg", width: 20pt)
+#image(read("/monkey.svg", encoding: none), format: "svg", width: 20pt)
+
+---
+#let approx(a, b) = calc.abs((a - b) / 1pt) < 0.001
+
+// Test that the resolution in the metadata determines the natural size.
+#style(styles => {
+  // The PNG specifies 5669 pixels per meter.
+  let graph = measure(image("/graph.png"), styles)
+  test(approx(graph.width, 1191pt * 72 / (5669 * 0.0254)), true)
+  test(approx(graph.height, 341pt * 72 / (5669 * 0.0254)), true)
+
+  // The JPEG only specifies an aspect ratio, so we fall back to 72 dpi.
+  let molecular = measure(image("/molecular.jpg"), styles)
+  test(molecular.width, 2819pt)
+  test(molecular.height, 2091pt)
+})
+
+---
+// Error: 2-36 failed to decode image
+#image("/tiger.jpg", format: "png")
+
+---
+// Error: 30-35 expected "png", "jpg", "gif", "svg", or auto
+#image("/tiger.jpg", format: "bmp")
+
+---
+// Error: 2-30 image has only one page
+#image("/tiger.jpg", page: 2)
+
+---
+// Error: 28-29 number must be positive
+#image("/tiger.jpg", page: 0)
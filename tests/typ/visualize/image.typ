@@ -57,6 +57,10 @@ A #box(image("/tiger.jpg", height: 1cm, width: 80%)) B
 // Error: 2-22 unknown image format
 #image("./image.typ")
 
+---
+// Error: 2-30 unknown image format
+#image(bytes("not an image"))
+
 ---
 // Error: 2-19 failed to parse svg: found closing tag 'g' instead of 'style' in line 4
 #image("/bad.svg")
@@ -0,0 +1,55 @@
+// Test tiling patterns.
+// Ref: false
+
+---
+#let dots = pattern(size: (6pt, 6pt), circle(radius: 2pt, fill: blue))
+#test(type(dots), "pattern")
+#test(repr(dots), "pattern(size: (6pt, 6pt), ..)")
+#test(
+  repr(pattern(spacing: (2pt, 1pt), relative: "parent", square(size: 4pt))),
+  "pattern(size: (4pt, 4pt), spacing: (2pt, 1pt), relative: \"parent\", ..)",
+)
+
+---
+// Test patterns as fills and strokes of shapes and text.
+#let hatch = pattern(
+  size: (5pt, 5pt),
+  line(start: (0%, 100%), end: (100%, 0%), stroke: 0.5pt),
+)
+#rect(width: 100%, height: 20pt, fill: hatch)
+#circle(radius: 10pt, stroke: 3pt + hatch)
+#text(16pt, weight: "bold", fill: hatch)[Hatched]
+#rotate(20deg, rect(width: 30pt, height: 20pt, fill: hatch))
+
+---
+// Test that parent-relative tiles line up across shapes.
+#let grid-paper = pattern(
+  size: (4pt, 4pt),
+  relative: "parent",
+  rect(width: 100%, height: 100%, stroke: 0.25pt + gray),
+)
+#box(rect(width: 21pt, height: 10pt, fill: grid-paper))
+#box(rect(width: 13pt, height: 10pt, fill: grid-paper))
+
+---
+// Test a page background with spaced tiles that contain text.
+#set page(fill: pattern(size: (10pt, 10pt), spacing: (5pt, 5pt), text(6pt)[A]))
+Text on a patterned page.
+
+---
+// Test a pattern within a pattern.
+#let inner = pattern(size: (2pt, 2pt), square(size: 1pt, fill: red))
+#let outer = pattern(size: (10pt, 10pt), circle(radius: 4pt, fill: inner))
+#rect(width: 40pt, height: 20pt, fill: outer)
+
+---
+// Error: 2-50 pattern spacing must not be negative
+#pattern(spacing: (-1pt, 0pt), square(size: 2pt))
+
+---
+// Error: 2-11 pattern tile size must be positive
+#pattern[]
+
+---
+// Error: 2-30 pattern tile size must be positive
+#pattern(size: (0pt, 5pt))[A]
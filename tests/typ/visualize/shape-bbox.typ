@@ -0,0 +1,56 @@
+// Test the bounding boxes of paths and polygons and regular polygons.
+// Ref: false
+
+---
+#let approx(a, b) = calc.abs((a - b) / 1pt) < 0.001
+
+// The stroke is part of the bounding box.
+#style(styles => {
+  let size = measure(path(stroke: 2pt, (0pt, 0pt), (20pt, 0pt), (20pt, 10pt)), styles)
+  test(approx(size.width, 22pt), true)
+  test(approx(size.height, 12pt), true)
+})
+
+// Curves are measured by their extrema, not their control points.
+#style(styles => {
+  let size = measure(path(fill: red, stroke: none, (0pt, 0pt), ((20pt, 0pt), (0pt, 20pt))), styles)
+  test(approx(size.width, 20pt), true)
+  test(approx(size.height, 80pt / 9), true)
+})
+
+// The origin stays part of the bounding box.
+#style(styles => {
+  let size = measure(polygon(fill: red, (10pt, 10pt), (20pt, 10pt), (20pt, 20pt)), styles)
+  test(approx(size.width, 20pt), true)
+  test(approx(size.height, 20pt), true)
+})
+
+---
+#let approx(a, b) = calc.abs((a - b) / 1pt) < 0.001
+
+// Test regular polygons.
+#style(styles => {
+  let square = measure(polygon.regular(fill: red, stroke: none, size: 20pt, vertices: 4), styles)
+  test(approx(square.width, 20pt * calc.sqrt(2) / 2), true)
+  test(approx(square.height, 20pt * calc.sqrt(2) / 2), true)
+
+  let triangle = measure(polygon.regular(fill: red, stroke: none, size: 20pt), styles)
+  test(approx(triangle.width, 20pt * calc.sqrt(3) / 2), true)
+  test(approx(triangle.height, 15pt), true)
+
+  let stroked = measure(polygon.regular(size: 20pt, vertices: 4), styles)
+  test(approx(stroked.width, square.width + 1pt), true)
+})
+
+---
+// Test fill rules.
+#test(polygon(fill-rule: "even-odd", (0pt, 0pt)).fill-rule, "even-odd")
+#polygon(fill: blue, fill-rule: "even-odd", (20pt, 0pt), (32pt, 36pt), (1pt, 13pt), (39pt, 13pt), (8pt, 36pt))
+
+---
+// Error: 18-23 expected "non-zero" or "even-odd"
+#path(fill-rule: "odd")
+
+---
+// Error: 28-29 polygon must have at least 3 vertices
+#polygon.regular(vertices: 2)
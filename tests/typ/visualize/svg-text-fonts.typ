@@ -0,0 +1,19 @@
+// Test that text in SVGs is resolved against the document's fonts.
+// Ref: false
+
+---
+#let chart = bytes(
+  "<svg xmlns='http://www.w3.org/2000/svg' width='120' height='80'>
+    <rect x='10' y='30' width='20' height='40' fill='#0074d9'/>
+    <rect x='50' y='10' width='20' height='60' fill='#ff4136'/>
+    <text x='10' y='78' font-family='IBM Plex Sans' font-size='8'>Blue</text>
+    <text x='50' y='78' font-family='Linux Libertine' font-weight='bold' font-size='8'>Red</text>
+    <text x='90' y='40' font-size='8'>Missing</text>
+    <text x='90' y='60' font-family='Stupid' font-size='8'>Unknown</text>
+  </svg>"
+)
+
+// Text without a family or with an unknown family uses the current font.
+#image(chart, format: "svg")
+#set text(font: "DejaVu Sans Mono")
+#image(chart, format: "svg")